@@ -0,0 +1,39 @@
+//! Times the frames of an AS3 game loop.
+//!
+//! The movie is a small shooter written like the update loops of AS3 game
+//! engines: every frame, a world updates, moves and collides a few hundred
+//! entities of several classes, recycles the dead ones through object pools
+//! and refreshes a text HUD. As in those engines, the entities are only
+//! handled through untyped references, so the timings are dominated by
+//! dynamic property lookups, method calls and display object property
+//! accesses rather than by rendering.
+//!
+//! Run with `cargo bench -p ruffle_core --bench avm2_game_loop`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::PlayerBuilder;
+
+/// Frames to run before measuring, so that the entity count settles and the
+/// object pools are filled.
+const WARMUP_FRAMES: u32 = 60;
+
+fn game_loop(c: &mut Criterion) {
+    let movie = SwfMovie::from_data(
+        include_bytes!("avm2_game_loop/test.swf"),
+        "file:///avm2_game_loop/test.swf".to_string(),
+        None,
+    )
+    .expect("Benchmark movie should be valid");
+    let player = PlayerBuilder::new().with_movie(movie).build();
+    let mut player = player.lock().unwrap();
+
+    for _ in 0..WARMUP_FRAMES {
+        player.run_frame();
+    }
+
+    c.bench_function("avm2_game_loop", |b| b.iter(|| player.run_frame()));
+}
+
+criterion_group!(benches, game_loop);
+criterion_main!(benches);
@@ -0,0 +1,201 @@
+package {
+	import flash.display.Sprite;
+	import flash.events.Event;
+	import flash.text.TextField;
+
+	// A small shooter, laid out the way AS3 game engines usually are: a world
+	// owning a flat list of entities that it updates, moves, collides and
+	// recycles every frame. Entities are only ever handled through untyped
+	// references, as engines do with their mixed entity lists, so none of the
+	// property accesses can be resolved ahead of time.
+	public class Test extends Sprite {
+		public static const WIDTH:Number = 800;
+		public static const HEIGHT:Number = 600;
+		private static const CELL_SIZE:Number = 50;
+
+		private var entities:Array = [];
+		private var pools:Object = {};
+		private var classes:Object = {ship: Ship, bullet: Bullet, asteroid: Asteroid};
+		private var hud:TextField = new TextField();
+		private var score:int = 0;
+		private var seed:uint = 12345;
+
+		public function Test() {
+			for (var i:int = 0; i < 20; i++) {
+				spawn("ship", random() * WIDTH, random() * HEIGHT, random() * 2 - 1, random() * 2 - 1);
+			}
+			for (i = 0; i < 600; i++) {
+				spawnAsteroid();
+			}
+			addChild(hud);
+			addEventListener("hit", onHit);
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+		}
+
+		public function random():Number {
+			seed = (seed * 1103515245 + 12345) & 0x7FFFFFFF;
+			return seed / 0x80000000;
+		}
+
+		public function spawn(kind:String, x:Number, y:Number, vx:Number, vy:Number):* {
+			var pool:Array = pools[kind] || (pools[kind] = []);
+			var entity:* = pool.length > 0 ? pool.pop() : new classes[kind]();
+			entity.x = x;
+			entity.y = y;
+			entity.vx = vx;
+			entity.vy = vy;
+			entity.dead = false;
+			entity.reset();
+			addChild(entity);
+			entities.push(entity);
+			return entity;
+		}
+
+		private function spawnAsteroid():void {
+			var angle:Number = random() * Math.PI * 2;
+			spawn("asteroid", random() * WIDTH, random() * HEIGHT, Math.cos(angle), Math.sin(angle));
+		}
+
+		private function onHit(event:Event):void {
+			score++;
+			spawnAsteroid();
+		}
+
+		private function onEnterFrame(event:Event):void {
+			var entity:*;
+			var i:int;
+
+			for (i = 0; i < entities.length; i++) {
+				entity = entities[i];
+				entity.update(this);
+				entity.x += entity.vx;
+				entity.y += entity.vy;
+				if (entity.x < 0 || entity.x > WIDTH) {
+					entity.vx = -entity.vx;
+				}
+				if (entity.y < 0 || entity.y > HEIGHT) {
+					entity.vy = -entity.vy;
+				}
+			}
+
+			var grid:Object = {};
+			for (i = 0; i < entities.length; i++) {
+				entity = entities[i];
+				if (entity.kind == "asteroid") {
+					var key:String = int(entity.x / CELL_SIZE) + "_" + int(entity.y / CELL_SIZE);
+					(grid[key] || (grid[key] = [])).push(entity);
+				}
+			}
+
+			for (i = 0; i < entities.length; i++) {
+				entity = entities[i];
+				if (entity.kind != "bullet" || entity.dead) {
+					continue;
+				}
+				var cell:Array = grid[int(entity.x / CELL_SIZE) + "_" + int(entity.y / CELL_SIZE)];
+				for each (var other:* in cell) {
+					var dx:Number = other.x - entity.x;
+					var dy:Number = other.y - entity.y;
+					var radius:Number = other.radius + entity.radius;
+					if (!other.dead && dx * dx + dy * dy < radius * radius) {
+						other.dead = true;
+						entity.dead = true;
+						dispatchEvent(new Event("hit"));
+						break;
+					}
+				}
+			}
+
+			for (i = entities.length - 1; i >= 0; i--) {
+				entity = entities[i];
+				if (entity.dead) {
+					removeChild(entity);
+					entities[i] = entities[entities.length - 1];
+					entities.pop();
+					pools[entity.kind].push(entity);
+				}
+			}
+
+			hud.text = "Score: " + score + " Entities: " + entities.length;
+		}
+	}
+}
+
+import flash.display.Sprite;
+
+class Entity extends Sprite {
+	public var kind:String;
+	public var vx:Number = 0;
+	public var vy:Number = 0;
+	public var radius:Number;
+	public var dead:Boolean = false;
+
+	public function Entity(kind:String, radius:Number, color:uint) {
+		this.kind = kind;
+		this.radius = radius;
+		graphics.beginFill(color);
+		graphics.drawCircle(0, 0, radius);
+		graphics.endFill();
+	}
+
+	public function reset():void {
+	}
+
+	public function update(world:*):void {
+	}
+}
+
+class Ship extends Entity {
+	private var cooldown:int;
+
+	public function Ship() {
+		super("ship", 8, 0x00FF00);
+	}
+
+	override public function reset():void {
+		cooldown = 0;
+	}
+
+	override public function update(world:*):void {
+		rotation += 3;
+		if (--cooldown <= 0) {
+			var angle:Number = rotation * Math.PI / 180;
+			world.spawn("bullet", x, y, Math.cos(angle) * 8, Math.sin(angle) * 8);
+			cooldown = 5;
+		}
+	}
+}
+
+class Bullet extends Entity {
+	private var life:int;
+
+	public function Bullet() {
+		super("bullet", 2, 0xFFFF00);
+	}
+
+	override public function reset():void {
+		life = 60;
+	}
+
+	override public function update(world:*):void {
+		if (--life <= 0) {
+			dead = true;
+		}
+	}
+}
+
+class Asteroid extends Entity {
+	private var spin:Number;
+
+	public function Asteroid() {
+		super("asteroid", 12, 0x808080);
+	}
+
+	override public function reset():void {
+		spin = 2;
+	}
+
+	override public function update(world:*):void {
+		rotation += spin;
+	}
+}
@@ -0,0 +1,366 @@
+//! Building the accessibility tree from the display list.
+//!
+//! Flash Player only exposes a subset of the display list to assistive technology:
+//! buttons, text fields, and movie clips which were given a name or description via
+//! `accessibilityProperties` (AVM2) or `_accProps` (AVM1). Everything else is flattened
+//! into its closest exposed ancestor.
+
+use crate::avm1::{
+    Activation as Avm1Activation, ActivationIdentifier, TObject as _, Value as Avm1Value,
+};
+use crate::avm2::{Activation as Avm2Activation, TObject as _, Value as Avm2Value};
+use crate::backend::accessibility::{
+    AccessibilityNode, AccessibilityNodeId, AccessibilityRole, AccessibilityState,
+};
+use crate::context::UpdateContext;
+use crate::display_object::{
+    ButtonState, DisplayObject, TDisplayObject, TDisplayObjectContainer, TInteractiveObject,
+};
+use crate::library::Library;
+use ruffle_render::matrix::Matrix;
+
+/// The contents of an `AccessibilityProperties` object (or AVM1 `_accProps`).
+#[derive(Debug, Clone, Default)]
+struct AccessibilityProperties {
+    name: String,
+    description: String,
+    shortcut: String,
+    silent: bool,
+    force_simple: bool,
+    no_auto_labeling: bool,
+}
+
+/// The answers of a custom AVM2 `AccessibilityImplementation` about its object.
+///
+/// These are cached on the object until it is invalidated, so that building the tree
+/// doesn't call into ActionScript for objects that didn't change.
+#[derive(Debug, Clone, Default)]
+pub struct AccessibilityImplementation {
+    name: Option<String>,
+    role: Option<u32>,
+    value: Option<String>,
+    state: Option<u32>,
+}
+
+/// Returns the ID used for the accessibility node of the given display object.
+pub fn node_id(object: DisplayObject<'_>) -> AccessibilityNodeId {
+    object.base().accessibility_id()
+}
+
+/// Builds the accessibility tree of the whole stage.
+pub fn build_tree(context: &mut UpdateContext<'_>) -> AccessibilityNode {
+    let stage = context.stage;
+    let view_matrix = stage.view_matrix();
+
+    let mut children = Vec::new();
+    for child in stage.iter_render_list() {
+        collect_nodes(context, child, view_matrix, &mut children);
+    }
+
+    AccessibilityNode {
+        id: node_id(stage.into()),
+        role: AccessibilityRole::Client,
+        name: String::new(),
+        description: String::new(),
+        shortcut: String::new(),
+        value: None,
+        bounds: view_matrix * stage.view_bounds(),
+        tab_index: None,
+        state: AccessibilityState::empty(),
+        children,
+    }
+}
+
+/// Appends the accessible nodes of `object` (either a node for the object itself,
+/// or the nodes of its children if it is not exposed) to `nodes`.
+fn collect_nodes<'gc>(
+    context: &mut UpdateContext<'gc>,
+    object: DisplayObject<'gc>,
+    view_matrix: Matrix,
+    nodes: &mut Vec<AccessibilityNode>,
+) {
+    if !object.visible() {
+        return;
+    }
+
+    let properties = read_properties(context, object).unwrap_or_default();
+    if properties.silent {
+        return;
+    }
+
+    let mut state = AccessibilityState::empty();
+    let mut tab_index = None;
+    if let Some(interactive) = object.as_interactive() {
+        if interactive.has_focus() {
+            state |= AccessibilityState::FOCUSED;
+        }
+        if interactive.is_tabbable(context) {
+            state |= AccessibilityState::FOCUSABLE;
+        }
+        tab_index = interactive.tab_index();
+    }
+
+    let mut value = None;
+    let mut auto_label = None;
+    let role = if object.as_avm1_button().is_some() || object.as_avm2_button().is_some() {
+        match object.state() {
+            Some(ButtonState::Down) => state |= AccessibilityState::PRESSED,
+            Some(ButtonState::Over) => state |= AccessibilityState::HOT,
+            _ => {}
+        }
+        if !properties.no_auto_labeling {
+            auto_label = Some(descendant_text(context.library, object));
+        }
+        Some(AccessibilityRole::PushButton)
+    } else if let Some(edit_text) = object.as_edit_text() {
+        if edit_text.is_multiline() {
+            state |= AccessibilityState::MULTILINE;
+        }
+        if edit_text.is_selectable() {
+            state |= AccessibilityState::SELECTABLE;
+        }
+        let text = edit_text.text().to_utf8_lossy().into_owned();
+        if edit_text.is_editable() {
+            if edit_text.is_password() {
+                state |= AccessibilityState::PROTECTED;
+            } else {
+                value = Some(text);
+            }
+            Some(AccessibilityRole::EditableText)
+        } else {
+            state |= AccessibilityState::READ_ONLY;
+            if edit_text.is_password() {
+                state |= AccessibilityState::PROTECTED;
+            } else {
+                auto_label = Some(text);
+            }
+            Some(AccessibilityRole::StaticText)
+        }
+    } else if let DisplayObject::Text(text) = object {
+        state |= AccessibilityState::READ_ONLY;
+        auto_label = Some(text.text(context.library).to_utf8_lossy().into_owned());
+        Some(AccessibilityRole::StaticText)
+    } else if !properties.name.is_empty() || !properties.description.is_empty() {
+        Some(AccessibilityRole::Graphic)
+    } else {
+        None
+    };
+
+    // A custom `AccessibilityImplementation` overrides anything we figured out ourselves.
+    let implementation = read_implementation(context, object).unwrap_or_default();
+    let role = implementation
+        .role
+        .map(AccessibilityRole::from_msaa_role)
+        .or(role);
+    if let Some(implementation_state) = implementation.state {
+        state = AccessibilityState::from_msaa_state(implementation_state);
+    }
+    if implementation.value.is_some() {
+        value = implementation.value;
+    }
+    if implementation.name.is_some() {
+        auto_label = implementation.name;
+    }
+
+    let mut children = Vec::new();
+    let collect_children = match role {
+        // Buttons and text fields are always leaves.
+        Some(AccessibilityRole::PushButton)
+        | Some(AccessibilityRole::StaticText)
+        | Some(AccessibilityRole::EditableText) => false,
+        _ => !properties.force_simple,
+    };
+    if collect_children {
+        if let Some(container) = object.as_container() {
+            for child in container.iter_render_list() {
+                collect_nodes(context, child, view_matrix, &mut children);
+            }
+        }
+    }
+
+    match role {
+        Some(role) => {
+            let name = if properties.name.is_empty() {
+                auto_label.unwrap_or_default()
+            } else {
+                properties.name
+            };
+            nodes.push(AccessibilityNode {
+                id: node_id(object),
+                role,
+                name,
+                description: properties.description,
+                shortcut: properties.shortcut,
+                value,
+                bounds: view_matrix * object.world_bounds(),
+                tab_index,
+                state,
+                children,
+            });
+        }
+        None => nodes.extend(children),
+    }
+}
+
+/// Concatenates the text of all text fields and static texts below `object`, used to label buttons.
+fn descendant_text<'gc>(library: &Library<'gc>, object: DisplayObject<'gc>) -> String {
+    let mut text = String::new();
+    if let Some(container) = object.as_container() {
+        for child in container.iter_render_list() {
+            let child_text = if let Some(edit_text) = child.as_edit_text() {
+                if edit_text.is_password() {
+                    continue;
+                }
+                edit_text.text().to_utf8_lossy().into_owned()
+            } else if let DisplayObject::Text(text) = child {
+                text.text(library).to_utf8_lossy().into_owned()
+            } else {
+                descendant_text(library, child)
+            };
+            let child_text = child_text.trim();
+            if !child_text.is_empty() {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(child_text);
+            }
+        }
+    }
+    text
+}
+
+/// Queries the `AccessibilityImplementation` assigned to an AVM2 interactive object,
+/// unless its answers are still cached.
+fn read_implementation<'gc>(
+    context: &mut UpdateContext<'gc>,
+    object: DisplayObject<'gc>,
+) -> Option<AccessibilityImplementation> {
+    let interactive = object.as_interactive()?;
+    let Avm2Value::Object(implementation) = interactive.accessibility_implementation() else {
+        return None;
+    };
+    if let Some(cached) = interactive.accessibility_cache() {
+        return Some(cached);
+    }
+
+    let mut activation = Avm2Activation::from_nothing(context);
+
+    // Child ID 0 refers to the object itself.
+    let mut call = |name: &'static str| -> Option<Avm2Value<'gc>> {
+        match implementation.call_public_property(name, &[0.into()], &mut activation) {
+            Ok(Avm2Value::Null | Avm2Value::Undefined) => None,
+            Ok(value) => Some(value),
+            Err(e) => {
+                tracing::warn!("Error calling AccessibilityImplementation.{name}: {e:?}");
+                None
+            }
+        }
+    };
+    let name = call("get_accName");
+    let role = call("get_accRole");
+    let value = call("get_accValue");
+    let state = call("get_accState");
+
+    let mut string = |value: Option<Avm2Value<'gc>>| -> Option<String> {
+        value?
+            .coerce_to_string(&mut activation)
+            .ok()
+            .map(|value| value.to_utf8_lossy().into_owned())
+    };
+    let name = string(name);
+    let value = string(value);
+
+    let mut number = |value: Option<Avm2Value<'gc>>| -> Option<u32> {
+        value?.coerce_to_u32(&mut activation).ok()
+    };
+    let answers = AccessibilityImplementation {
+        name,
+        role: number(role),
+        value,
+        state: number(state),
+    };
+    interactive.set_accessibility_cache(context.gc(), answers.clone());
+    Some(answers)
+}
+
+/// Reads the accessibility properties assigned to a display object by ActionScript.
+fn read_properties<'gc>(
+    context: &mut UpdateContext<'gc>,
+    object: DisplayObject<'gc>,
+) -> Option<AccessibilityProperties> {
+    if let Avm2Value::Object(avm2_object) = object.object2() {
+        let mut activation = Avm2Activation::from_nothing(context);
+        let Ok(Avm2Value::Object(properties)) =
+            avm2_object.get_public_property("accessibilityProperties", &mut activation)
+        else {
+            return None;
+        };
+
+        let mut string = |name: &'static str| -> String {
+            properties
+                .get_public_property(name, &mut activation)
+                .and_then(|value| value.coerce_to_string(&mut activation))
+                .map(|value| value.to_utf8_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let name = string("name");
+        let description = string("description");
+        let shortcut = string("shortcut");
+
+        let mut boolean = |name: &'static str| -> bool {
+            properties
+                .get_public_property(name, &mut activation)
+                .map(|value| value.coerce_to_boolean())
+                .unwrap_or_default()
+        };
+        Some(AccessibilityProperties {
+            name,
+            description,
+            shortcut,
+            silent: boolean("silent"),
+            force_simple: boolean("forceSimple"),
+            no_auto_labeling: boolean("noAutoLabeling"),
+        })
+    } else if let Avm1Value::Object(avm1_object) = object.object() {
+        let mut activation = Avm1Activation::from_nothing(
+            context,
+            ActivationIdentifier::root("[Accessibility Properties]"),
+            object.avm1_root(),
+        );
+        let Ok(Avm1Value::Object(properties)) = avm1_object.get("_accProps", &mut activation)
+        else {
+            return None;
+        };
+
+        let swf_version = activation.swf_version();
+        let mut string = |name: &'static str| -> String {
+            match properties.get(name, &mut activation) {
+                Ok(Avm1Value::Undefined) | Err(_) => String::new(),
+                Ok(value) => value
+                    .coerce_to_string(&mut activation)
+                    .map(|value| value.to_utf8_lossy().into_owned())
+                    .unwrap_or_default(),
+            }
+        };
+        let name = string("name");
+        let description = string("description");
+        let shortcut = string("shortcut");
+
+        let mut boolean = |name: &'static str| -> bool {
+            properties
+                .get(name, &mut activation)
+                .map(|value| value.as_bool(swf_version))
+                .unwrap_or_default()
+        };
+        Some(AccessibilityProperties {
+            name,
+            description,
+            shortcut,
+            silent: boolean("silent"),
+            force_simple: boolean("forceSimple"),
+            no_auto_labeling: boolean("noAutoLabeling"),
+        })
+    } else {
+        None
+    }
+}
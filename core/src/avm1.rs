@@ -7,6 +7,7 @@ mod function;
 #[macro_use]
 mod property_decl;
 
+mod action_cache;
 mod activation;
 mod callable_value;
 mod clamp;
@@ -26,6 +27,7 @@ mod value;
 #[cfg(test)]
 mod tests;
 
+pub use action_cache::ActionCache;
 pub use activation::{Activation, ActivationIdentifier};
 pub use debug::VariableDumper;
 pub use error::Error;
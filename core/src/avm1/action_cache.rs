@@ -0,0 +1,289 @@
+//! Pre-decoded AVM1 actions, cached per movie.
+//!
+//! Decoding actions from the SWF data is expensive for actions carrying
+//! strings, which have to be decoded and allocated every time they run. The
+//! action sequence of each `SwfSlice` is instead decoded once when it is
+//! first run, and kept in the library of its movie.
+//!
+//! Execution still follows byte offsets into the movie data, as AVM1 jumps
+//! may land anywhere, including in the middle of another action. Actions at
+//! offsets which weren't decoded ahead of time are simply decoded on the fly.
+
+use crate::avm1::{Activation, Error, Value};
+use crate::string::{AvmString, SwfStrExt as _};
+use crate::tag_utils::SwfSlice;
+use fnv::FnvHashMap;
+use gc_arena::{Collect, Gc};
+use swf::avm1::read::Reader;
+use swf::avm1::types::{Action, Value as SwfValue};
+
+/// The decoded action sequences of a single movie.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct ActionCache<'gc> {
+    /// The programs of each code slice, keyed by the bounds of the slice and
+    /// the SWF version they were decoded with.
+    programs: FnvHashMap<(usize, usize, u8), Gc<'gc, ActionProgram<'gc>>>,
+}
+
+impl<'gc> ActionCache<'gc> {
+    pub fn get(&self, code: &SwfSlice, swf_version: u8) -> Option<Gc<'gc, ActionProgram<'gc>>> {
+        self.programs
+            .get(&(code.start, code.end, swf_version))
+            .copied()
+    }
+
+    pub fn insert(
+        &mut self,
+        code: &SwfSlice,
+        swf_version: u8,
+        program: Gc<'gc, ActionProgram<'gc>>,
+    ) {
+        self.programs
+            .insert((code.start, code.end, swf_version), program);
+    }
+}
+
+/// The actions of a code slice, decoded in sequence from its start.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct ActionProgram<'gc> {
+    actions: Vec<CachedAction<'gc>>,
+}
+
+impl<'gc> ActionProgram<'gc> {
+    /// Decodes the actions of a code slice, up to its end or the first
+    /// action that fails to decode.
+    pub fn decode(activation: &mut Activation<'_, 'gc>, code: &SwfSlice) -> Self {
+        let mut actions: Vec<CachedAction<'gc>> = vec![];
+        let mut position = code.start;
+        while position < code.end {
+            match CachedAction::decode(activation, code, position) {
+                Ok(action) => {
+                    position = action.next;
+                    actions.push(action);
+                }
+                // Leave the error to be raised if the action actually runs.
+                Err(_) => break,
+            }
+        }
+
+        // Resolve jumps to the index of their target, so that running them
+        // doesn't need a lookup.
+        let offsets: Vec<usize> = actions.iter().map(|action| action.offset).collect();
+        for action in &mut actions {
+            if let DecodedAction::Jump(target) | DecodedAction::If(target) = &mut action.action {
+                target.index = offsets.binary_search(&target.offset).ok();
+            }
+        }
+
+        Self { actions }
+    }
+
+    pub fn get(&self, index: usize) -> &CachedAction<'gc> {
+        &self.actions[index]
+    }
+
+    /// Finds the action starting at an offset into the movie data.
+    fn index_of(&self, offset: usize) -> Option<usize> {
+        self.actions
+            .binary_search_by_key(&offset, |action| action.offset)
+            .ok()
+    }
+}
+
+/// A single decoded action.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct CachedAction<'gc> {
+    /// The offset of the action into the movie data.
+    pub offset: usize,
+
+    /// The offset of the following action into the movie data.
+    pub next: usize,
+
+    pub action: DecodedAction<'gc>,
+}
+
+impl<'gc> CachedAction<'gc> {
+    /// Decodes the action at an offset into the movie data of a code slice.
+    pub fn decode(
+        activation: &mut Activation<'_, 'gc>,
+        code: &SwfSlice,
+        offset: usize,
+    ) -> Result<Self, Error<'gc>> {
+        let data = code.movie.data();
+        let mut reader = Reader::new(&data[offset..], activation.swf_version());
+        let action = reader.read_action()?;
+        let next = data.len() - reader.get_ref().len();
+
+        let action = match action {
+            Action::ConstantPool(constant_pool) => {
+                let constants = constant_pool
+                    .strings
+                    .iter()
+                    .map(|s| {
+                        activation
+                            .context
+                            .interner
+                            .intern_wstr(
+                                activation.context.gc_context,
+                                s.decode(activation.encoding()),
+                            )
+                            .into()
+                    })
+                    .collect();
+                DecodedAction::ConstantPool(Gc::new(activation.context.gc_context, constants))
+            }
+            Action::Push(push) => DecodedAction::Push(
+                push.values
+                    .into_iter()
+                    .map(|value| match value {
+                        SwfValue::Undefined => PushValue::Value(Value::Undefined),
+                        SwfValue::Null => PushValue::Value(Value::Null),
+                        SwfValue::Bool(v) => PushValue::Value(v.into()),
+                        SwfValue::Int(v) => PushValue::Value(v.into()),
+                        SwfValue::Float(v) => PushValue::Value(v.into()),
+                        SwfValue::Double(v) => PushValue::Value(v.into()),
+                        SwfValue::Str(v) => PushValue::Value(
+                            AvmString::new(
+                                activation.context.gc_context,
+                                v.decode(activation.encoding()),
+                            )
+                            .into(),
+                        ),
+                        SwfValue::Register(v) => PushValue::Register(v),
+                        SwfValue::ConstantPool(i) => PushValue::ConstantPool(i),
+                    })
+                    .collect(),
+            ),
+            Action::Jump(jump) => DecodedAction::Jump(JumpTarget::new(data, next, jump.offset)),
+            Action::If(action) => DecodedAction::If(JumpTarget::new(data, next, action.offset)),
+            _ => DecodedAction::Other,
+        };
+
+        Ok(Self {
+            offset,
+            next,
+            action,
+        })
+    }
+}
+
+/// The parts of an action that are worth keeping decoded.
+#[derive(Collect, Debug)]
+#[collect(no_drop)]
+pub enum DecodedAction<'gc> {
+    /// A constant pool, with its strings already interned.
+    ConstantPool(Gc<'gc, Vec<Value<'gc>>>),
+
+    /// The values pushed by a push action.
+    Push(Vec<PushValue<'gc>>),
+
+    Jump(JumpTarget),
+
+    If(JumpTarget),
+
+    /// Any other action, which is read again from the SWF data when run.
+    Other,
+}
+
+/// A value pushed by a push action.
+#[derive(Collect, Clone, Copy, Debug)]
+#[collect(no_drop)]
+pub enum PushValue<'gc> {
+    Value(Value<'gc>),
+
+    /// The value of a register, read when the action runs.
+    Register(u8),
+
+    /// An index into the constant pool in use when the action runs.
+    ///
+    /// Constant pools are set by actions themselves, so this can't be
+    /// resolved ahead of time.
+    ConstantPool(u16),
+}
+
+/// The resolved target of a jump.
+#[derive(Collect, Clone, Copy, Debug)]
+#[collect(require_static)]
+pub struct JumpTarget {
+    /// The offset of the target into the movie data.
+    pub offset: usize,
+
+    /// The index of the target in the program containing the jump, if it
+    /// starts an action decoded ahead of time.
+    index: Option<usize>,
+}
+
+impl JumpTarget {
+    fn new(data: &[u8], next: usize, jump_offset: i16) -> Self {
+        // Jumping before the start of the movie data ends up at its end, like
+        // `Reader::seek` does.
+        let offset = usize::try_from(next as isize + isize::from(jump_offset))
+            .map_or(data.len(), |offset| offset.min(data.len()));
+        Self {
+            offset,
+            index: None,
+        }
+    }
+}
+
+/// The position of the next action to run within a program.
+pub struct ActionCursor {
+    /// The offset of the next action into the movie data.
+    pub position: usize,
+
+    /// The index of the next action in the program, if it was decoded ahead of time.
+    pub index: Option<usize>,
+}
+
+impl ActionCursor {
+    pub fn new(program: &ActionProgram<'_>, position: usize) -> Self {
+        Self {
+            position,
+            index: program.index_of(position),
+        }
+    }
+
+    /// Moves on to the action following the current one, which ends at `next`.
+    pub fn advance(&mut self, program: &ActionProgram<'_>, next: usize) {
+        self.index = match self.index {
+            Some(index)
+                if program
+                    .actions
+                    .get(index + 1)
+                    .is_some_and(|action| action.offset == next) =>
+            {
+                Some(index + 1)
+            }
+            _ => program.index_of(next),
+        };
+        self.position = next;
+    }
+
+    pub fn jump(&mut self, program: &ActionProgram<'_>, target: JumpTarget) {
+        self.index = target.index.or_else(|| program.index_of(target.offset));
+        self.position = target.offset;
+    }
+
+    /// Moves past the current action without running it.
+    pub fn skip(
+        &mut self,
+        program: &ActionProgram<'_>,
+        code: &SwfSlice,
+        swf_version: u8,
+    ) -> Result<(), swf::error::Error> {
+        let next = match self.index {
+            Some(index) => program.actions[index].next,
+            None => {
+                let data = code.movie.data();
+                let mut reader = Reader::new(&data[self.position..], swf_version);
+                reader.read_action()?;
+                data.len() - reader.get_ref().len()
+            }
+        };
+        self.advance(program, next);
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
+use crate::avm1::action_cache::{
+    ActionCursor, ActionProgram, CachedAction, DecodedAction, JumpTarget, PushValue,
+};
 use crate::avm1::callable_value::CallableValue;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Avm1Function, ExecutionReason, FunctionObject};
 use crate::avm1::object::{Object, TObject};
 use crate::avm1::property::Attribute;
-use crate::avm1::runtime::skip_actions;
 use crate::avm1::scope::{Scope, ScopeClass};
 use crate::avm1::{fscommand, globals, scope, ArrayObject, ScriptObject, Value};
 use crate::backend::navigator::{NavigationMethod, Request};
@@ -425,10 +427,11 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     }
 
     pub fn run_actions(&mut self, code: SwfSlice) -> Result<ReturnType<'gc>, Error<'gc>> {
-        let mut read = Reader::new(&code.movie.data()[code.start..], self.swf_version());
+        let program = self.action_program(&code);
+        let mut cursor = ActionCursor::new(&program, code.start);
 
         loop {
-            let result = self.do_action(&code, &mut read);
+            let result = self.do_action(&code, program, &mut cursor);
             match result {
                 Ok(FrameControl::Return(return_type)) => break Ok(return_type),
                 Ok(FrameControl::Continue) => {}
@@ -437,11 +440,32 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         }
     }
 
-    /// Run a single action from a given action reader.
-    fn do_action<'b>(
+    /// Get the decoded actions of a code slice, decoding them if they haven't been run before.
+    fn action_program(&mut self, code: &SwfSlice) -> Gc<'gc, ActionProgram<'gc>> {
+        let swf_version = self.swf_version();
+        let library = self
+            .context
+            .library
+            .library_for_movie_mut(code.movie.clone());
+        if let Some(program) = library.avm1_action_cache().get(code, swf_version) {
+            return program;
+        }
+
+        let program = Gc::new(self.context.gc_context, ActionProgram::decode(self, code));
+        self.context
+            .library
+            .library_for_movie_mut(code.movie.clone())
+            .avm1_action_cache_mut()
+            .insert(code, swf_version, program);
+        program
+    }
+
+    /// Run a single action at the position of a given cursor.
+    fn do_action(
         &mut self,
-        data: &'b SwfSlice,
-        reader: &mut Reader<'b>,
+        data: &SwfSlice,
+        program: Gc<'gc, ActionProgram<'gc>>,
+        cursor: &mut ActionCursor,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         *self.context.actions_since_timeout_check += 1;
         if *self.context.actions_since_timeout_check >= 2000 {
@@ -451,119 +475,169 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             }
         }
 
-        if reader.get_ref().as_ptr() as usize >= data.as_ref().as_ptr_range().end as usize {
+        if cursor.position >= data.end {
             //Executing beyond the end of a function constitutes an implicit return.
-            Ok(FrameControl::Return(ReturnType::Implicit))
-        } else {
-            let action = reader.read_action()?;
-            avm_debug!(
-                self.context.avm1,
-                "({}) Action: {action:?}",
-                self.id.depth(),
-            );
+            return Ok(FrameControl::Return(ReturnType::Implicit));
+        }
+
+        // Actions that weren't decoded ahead of time (e.g. when jumping into the
+        // middle of another action) are decoded on the fly.
+        let decoded;
+        let cached = match cursor.index {
+            Some(index) => program.get(index),
+            None => {
+                decoded = CachedAction::decode(self, data, cursor.position)?;
+                &decoded
+            }
+        };
+        cursor.advance(&program, cached.next);
+
+        match &cached.action {
+            DecodedAction::ConstantPool(constants) => {
+                avm_debug!(
+                    self.context.avm1,
+                    "({}) Action: ConstantPool({constants:?})",
+                    self.id.depth(),
+                );
+                self.action_constant_pool(*constants)
+            }
+            DecodedAction::Push(values) => {
+                avm_debug!(
+                    self.context.avm1,
+                    "({}) Action: Push({values:?})",
+                    self.id.depth(),
+                );
+                self.action_push(values)
+            }
+            DecodedAction::Jump(target) => self.action_jump(*target, &program, cursor),
+            DecodedAction::If(target) => self.action_if(*target, &program, cursor),
+            DecodedAction::Other => {
+                let mut reader =
+                    Reader::new(&data.movie.data()[cached.offset..], self.swf_version());
+                let action = reader.read_action()?;
+                self.do_swf_action(action, data, &program, cursor)
+            }
+        }
+    }
+
+    /// Run a single action that is read from the SWF data every time it runs.
+    fn do_swf_action(
+        &mut self,
+        action: Action<'_>,
+        data: &SwfSlice,
+        program: &ActionProgram<'gc>,
+        cursor: &mut ActionCursor,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        avm_debug!(
+            self.context.avm1,
+            "({}) Action: {action:?}",
+            self.id.depth(),
+        );
 
-            match action {
-                Action::Add => self.action_add(),
-                Action::Add2 => self.action_add_2(),
-                Action::And => self.action_and(),
-                Action::AsciiToChar => self.action_ascii_to_char(),
-                Action::BitAnd => self.action_bit_and(),
-                Action::BitLShift => self.action_bit_lshift(),
-                Action::BitOr => self.action_bit_or(),
-                Action::BitRShift => self.action_bit_rshift(),
-                Action::BitURShift => self.action_bit_urshift(),
-                Action::BitXor => self.action_bit_xor(),
-                Action::Call => self.action_call(),
-                Action::CallFunction => self.action_call_function(),
-                Action::CallMethod => self.action_call_method(),
-                Action::CastOp => self.action_cast_op(),
-                Action::CharToAscii => self.action_char_to_ascii(),
-                Action::CloneSprite => self.action_clone_sprite(),
-                Action::ConstantPool(action) => self.action_constant_pool(action),
-                Action::Decrement => self.action_decrement(),
-                Action::DefineFunction(action) => self.action_define_function(action.into(), data),
-                Action::DefineFunction2(action) => self.action_define_function(action, data),
-                Action::DefineLocal => self.action_define_local(),
-                Action::DefineLocal2 => self.action_define_local_2(),
-                Action::Delete => self.action_delete(),
-                Action::Delete2 => self.action_delete_2(),
-                Action::Divide => self.action_divide(),
-                Action::End => self.action_end(),
-                Action::EndDrag => self.action_end_drag(),
-                Action::Enumerate => self.action_enumerate(),
-                Action::Enumerate2 => self.action_enumerate_2(),
-                Action::Equals => self.action_equals(),
-                Action::Equals2 => self.action_equals_2(),
-                Action::Extends => self.action_extends(),
-                Action::GetMember => self.action_get_member(),
-                Action::GetProperty => self.action_get_property(),
-                Action::GetTime => self.action_get_time(),
-                Action::GetVariable => self.action_get_variable(),
-                Action::GetUrl(action) => self.action_get_url(action),
-                Action::GetUrl2(action) => self.action_get_url_2(action),
-                Action::GotoFrame(action) => self.action_goto_frame(action),
-                Action::GotoFrame2(action) => self.action_goto_frame_2(action),
-                Action::Greater => self.action_greater(),
-                Action::GotoLabel(action) => self.action_goto_label(action),
-                Action::If(action) => self.action_if(action, reader, data),
-                Action::Increment => self.action_increment(),
-                Action::InitArray => self.action_init_array(),
-                Action::InitObject => self.action_init_object(),
-                Action::ImplementsOp => self.action_implements_op(),
-                Action::InstanceOf => self.action_instance_of(),
-                Action::Jump(action) => self.action_jump(action, reader, data),
-                Action::Less => self.action_less(),
-                Action::Less2 => self.action_less_2(),
-                Action::MBAsciiToChar => self.action_mb_ascii_to_char(),
-                Action::MBCharToAscii => self.action_mb_char_to_ascii(),
-                Action::MBStringLength => self.action_mb_string_length(),
-                Action::MBStringExtract => self.action_mb_string_extract(),
-                Action::Modulo => self.action_modulo(),
-                Action::Multiply => self.action_multiply(),
-                Action::NextFrame => self.action_next_frame(),
-                Action::NewMethod => self.action_new_method(),
-                Action::NewObject => self.action_new_object(),
-                Action::Not => self.action_not(),
-                Action::Or => self.action_or(),
-                Action::Play => self.action_play(),
-                Action::Pop => self.action_pop(),
-                Action::PreviousFrame => self.action_prev_frame(),
-                Action::Push(action) => self.action_push(action),
-                Action::PushDuplicate => self.action_push_duplicate(),
-                Action::RandomNumber => self.action_random_number(),
-                Action::RemoveSprite => self.action_remove_sprite(),
-                Action::Return => self.action_return(),
-                Action::SetMember => self.action_set_member(),
-                Action::SetProperty => self.action_set_property(),
-                Action::SetTarget(action) => self.action_set_target(action),
-                Action::SetTarget2 => self.action_set_target_2(),
-                Action::SetVariable => self.action_set_variable(),
-                Action::StackSwap => self.action_stack_swap(),
-                Action::StartDrag => self.action_start_drag(),
-                Action::Stop => self.action_stop(),
-                Action::StopSounds => self.action_stop_sounds(),
-                Action::StoreRegister(action) => self.action_store_register(action),
-                Action::StrictEquals => self.action_strict_equals(),
-                Action::StringAdd => self.action_string_add(),
-                Action::StringEquals => self.action_string_equals(),
-                Action::StringExtract => self.action_string_extract(),
-                Action::StringGreater => self.action_string_greater(),
-                Action::StringLength => self.action_string_length(),
-                Action::StringLess => self.action_string_less(),
-                Action::Subtract => self.action_subtract(),
-                Action::TargetPath => self.action_target_path(),
-                Action::Throw => self.action_throw(),
-                Action::ToggleQuality => self.action_toggle_quality(),
-                Action::ToInteger => self.action_to_integer(),
-                Action::ToNumber => self.action_to_number(),
-                Action::ToString => self.action_to_string(),
-                Action::Trace => self.action_trace(),
-                Action::Try(action) => self.action_try(&action, data),
-                Action::TypeOf => self.action_type_of(),
-                Action::WaitForFrame(action) => self.action_wait_for_frame(action, reader),
-                Action::WaitForFrame2(action) => self.action_wait_for_frame_2(action, reader),
-                Action::With(action) => self.action_with(action, data),
-                Action::Unknown(action) => self.action_unknown(action),
+        match action {
+            Action::Add => self.action_add(),
+            Action::Add2 => self.action_add_2(),
+            Action::And => self.action_and(),
+            Action::AsciiToChar => self.action_ascii_to_char(),
+            Action::BitAnd => self.action_bit_and(),
+            Action::BitLShift => self.action_bit_lshift(),
+            Action::BitOr => self.action_bit_or(),
+            Action::BitRShift => self.action_bit_rshift(),
+            Action::BitURShift => self.action_bit_urshift(),
+            Action::BitXor => self.action_bit_xor(),
+            Action::Call => self.action_call(),
+            Action::CallFunction => self.action_call_function(),
+            Action::CallMethod => self.action_call_method(),
+            Action::CastOp => self.action_cast_op(),
+            Action::CharToAscii => self.action_char_to_ascii(),
+            Action::CloneSprite => self.action_clone_sprite(),
+            Action::Decrement => self.action_decrement(),
+            Action::DefineFunction(action) => self.action_define_function(action.into(), data),
+            Action::DefineFunction2(action) => self.action_define_function(action, data),
+            Action::DefineLocal => self.action_define_local(),
+            Action::DefineLocal2 => self.action_define_local_2(),
+            Action::Delete => self.action_delete(),
+            Action::Delete2 => self.action_delete_2(),
+            Action::Divide => self.action_divide(),
+            Action::End => self.action_end(),
+            Action::EndDrag => self.action_end_drag(),
+            Action::Enumerate => self.action_enumerate(),
+            Action::Enumerate2 => self.action_enumerate_2(),
+            Action::Equals => self.action_equals(),
+            Action::Equals2 => self.action_equals_2(),
+            Action::Extends => self.action_extends(),
+            Action::GetMember => self.action_get_member(),
+            Action::GetProperty => self.action_get_property(),
+            Action::GetTime => self.action_get_time(),
+            Action::GetVariable => self.action_get_variable(),
+            Action::GetUrl(action) => self.action_get_url(action),
+            Action::GetUrl2(action) => self.action_get_url_2(action),
+            Action::GotoFrame(action) => self.action_goto_frame(action),
+            Action::GotoFrame2(action) => self.action_goto_frame_2(action),
+            Action::Greater => self.action_greater(),
+            Action::GotoLabel(action) => self.action_goto_label(action),
+            Action::Increment => self.action_increment(),
+            Action::InitArray => self.action_init_array(),
+            Action::InitObject => self.action_init_object(),
+            Action::ImplementsOp => self.action_implements_op(),
+            Action::InstanceOf => self.action_instance_of(),
+            Action::Less => self.action_less(),
+            Action::Less2 => self.action_less_2(),
+            Action::MBAsciiToChar => self.action_mb_ascii_to_char(),
+            Action::MBCharToAscii => self.action_mb_char_to_ascii(),
+            Action::MBStringLength => self.action_mb_string_length(),
+            Action::MBStringExtract => self.action_mb_string_extract(),
+            Action::Modulo => self.action_modulo(),
+            Action::Multiply => self.action_multiply(),
+            Action::NextFrame => self.action_next_frame(),
+            Action::NewMethod => self.action_new_method(),
+            Action::NewObject => self.action_new_object(),
+            Action::Not => self.action_not(),
+            Action::Or => self.action_or(),
+            Action::Play => self.action_play(),
+            Action::Pop => self.action_pop(),
+            Action::PreviousFrame => self.action_prev_frame(),
+            Action::PushDuplicate => self.action_push_duplicate(),
+            Action::RandomNumber => self.action_random_number(),
+            Action::RemoveSprite => self.action_remove_sprite(),
+            Action::Return => self.action_return(),
+            Action::SetMember => self.action_set_member(),
+            Action::SetProperty => self.action_set_property(),
+            Action::SetTarget(action) => self.action_set_target(action),
+            Action::SetTarget2 => self.action_set_target_2(),
+            Action::SetVariable => self.action_set_variable(),
+            Action::StackSwap => self.action_stack_swap(),
+            Action::StartDrag => self.action_start_drag(),
+            Action::Stop => self.action_stop(),
+            Action::StopSounds => self.action_stop_sounds(),
+            Action::StoreRegister(action) => self.action_store_register(action),
+            Action::StrictEquals => self.action_strict_equals(),
+            Action::StringAdd => self.action_string_add(),
+            Action::StringEquals => self.action_string_equals(),
+            Action::StringExtract => self.action_string_extract(),
+            Action::StringGreater => self.action_string_greater(),
+            Action::StringLength => self.action_string_length(),
+            Action::StringLess => self.action_string_less(),
+            Action::Subtract => self.action_subtract(),
+            Action::TargetPath => self.action_target_path(),
+            Action::Throw => self.action_throw(),
+            Action::ToggleQuality => self.action_toggle_quality(),
+            Action::ToInteger => self.action_to_integer(),
+            Action::ToNumber => self.action_to_number(),
+            Action::ToString => self.action_to_string(),
+            Action::Trace => self.action_trace(),
+            Action::Try(action) => self.action_try(&action, data),
+            Action::TypeOf => self.action_type_of(),
+            Action::WaitForFrame(action) => {
+                self.action_wait_for_frame(action, data, program, cursor)
+            }
+            Action::WaitForFrame2(action) => {
+                self.action_wait_for_frame_2(action, data, program, cursor)
+            }
+            Action::With(action) => self.action_with(action, data),
+            Action::Unknown(action) => self.action_unknown(action),
+            Action::ConstantPool(_) | Action::If(_) | Action::Jump(_) | Action::Push(_) => {
+                unreachable!("Action should have been decoded ahead of time")
             }
         }
     }
@@ -861,22 +935,9 @@ impl<'a, 'gc> Activation<'a, 'gc> {
 
     fn action_constant_pool(
         &mut self,
-        action: ConstantPool,
+        constants: Gc<'gc, Vec<Value<'gc>>>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let constants = action
-            .strings
-            .iter()
-            .map(|s| {
-                self.context
-                    .interner
-                    .intern_wstr(self.context.gc_context, s.decode(self.encoding()))
-                    .into()
-            })
-            .collect();
-
-        self.context
-            .avm1
-            .set_constant_pool(Gc::new(self.context.gc_context, constants));
+        self.context.avm1.set_constant_pool(constants);
         self.set_constant_pool(self.context.avm1.constant_pool());
 
         Ok(FrameControl::Continue)
@@ -1444,15 +1505,15 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(FrameControl::Continue)
     }
 
-    fn action_if<'b>(
+    fn action_if(
         &mut self,
-        action: If,
-        reader: &mut Reader<'b>,
-        data: &'b SwfSlice,
+        target: JumpTarget,
+        program: &ActionProgram<'gc>,
+        cursor: &mut ActionCursor,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let val = self.context.avm1.pop();
         if val.as_bool(self.swf_version()) {
-            reader.seek(data.movie.data(), action.offset);
+            cursor.jump(program, target);
         }
         Ok(FrameControl::Continue)
     }
@@ -1552,13 +1613,13 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(FrameControl::Continue)
     }
 
-    fn action_jump<'b>(
+    fn action_jump(
         &mut self,
-        action: Jump,
-        reader: &mut Reader<'b>,
-        data: &'b SwfSlice,
+        target: JumpTarget,
+        program: &ActionProgram<'gc>,
+        cursor: &mut ActionCursor,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        reader.seek(data.movie.data(), action.offset);
+        cursor.jump(program, target);
         Ok(FrameControl::Continue)
     }
 
@@ -1812,21 +1873,12 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(FrameControl::Continue)
     }
 
-    fn action_push(&mut self, action: Push) -> Result<FrameControl<'gc>, Error<'gc>> {
-        for value in action.values {
-            use swf::avm1::types::Value as SwfValue;
+    fn action_push(&mut self, values: &[PushValue<'gc>]) -> Result<FrameControl<'gc>, Error<'gc>> {
+        for &value in values {
             let value = match value {
-                SwfValue::Undefined => Value::Undefined,
-                SwfValue::Null => Value::Null,
-                SwfValue::Bool(v) => v.into(),
-                SwfValue::Int(v) => v.into(),
-                SwfValue::Float(v) => v.into(),
-                SwfValue::Double(v) => v.into(),
-                SwfValue::Str(v) => {
-                    AvmString::new(self.context.gc_context, v.decode(self.encoding())).into()
-                }
-                SwfValue::Register(v) => self.current_register(v),
-                SwfValue::ConstantPool(i) => {
+                PushValue::Value(v) => v,
+                PushValue::Register(v) => self.current_register(v),
+                PushValue::ConstantPool(i) => {
                     if let Some(value) = self.constant_pool().get(i as usize) {
                         *value
                     } else {
@@ -2313,7 +2365,9 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn action_wait_for_frame(
         &mut self,
         action: WaitForFrame,
-        r: &mut Reader<'_>,
+        data: &SwfSlice,
+        program: &ActionProgram<'gc>,
+        cursor: &mut ActionCursor,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let frame_num = action.frame;
         let loaded = if frame_num > 16000 {
@@ -2328,8 +2382,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
 
         if !loaded {
             // Note that the offset is given in # of actions, NOT in bytes.
-            // Read the actions and toss them away.
-            skip_actions(r, action.num_actions_to_skip);
+            self.skip_actions(data, program, cursor, action.num_actions_to_skip);
         }
 
         Ok(FrameControl::Continue)
@@ -2338,7 +2391,9 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn action_wait_for_frame_2(
         &mut self,
         action: WaitForFrame2,
-        r: &mut Reader<'_>,
+        data: &SwfSlice,
+        program: &ActionProgram<'gc>,
+        cursor: &mut ActionCursor,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let frame_val = self.context.avm1.pop();
         let frame_num = match frame_val {
@@ -2371,13 +2426,29 @@ impl<'a, 'gc> Activation<'a, 'gc> {
 
         if !loaded {
             // Note that the offset is given in # of actions, NOT in bytes.
-            // Read the actions and toss them away.
-            skip_actions(r, action.num_actions_to_skip);
+            self.skip_actions(data, program, cursor, action.num_actions_to_skip);
         }
 
         Ok(FrameControl::Continue)
     }
 
+    /// Skip over a given number of actions without running them.
+    ///
+    /// Note that this counts actions, NOT bytes.
+    fn skip_actions(
+        &self,
+        data: &SwfSlice,
+        program: &ActionProgram<'gc>,
+        cursor: &mut ActionCursor,
+        num_actions_to_skip: u8,
+    ) {
+        for _ in 0..num_actions_to_skip {
+            if let Err(e) = cursor.skip(program, data, self.swf_version()) {
+                tracing::warn!("Couldn't skip action: {}", e);
+            }
+        }
+    }
+
     fn action_with(
         &mut self,
         action: With,
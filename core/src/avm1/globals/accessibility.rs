@@ -3,8 +3,8 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::property_decl::{define_properties_on, Declaration};
-use crate::avm1::{Object, ScriptObject, Value};
-use crate::avm1_stub;
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::backend::accessibility::AccessibilityEvent;
 use crate::context::GcContext;
 
 const OBJECT_DECLS: &[Declaration] = declare_properties! {
@@ -18,16 +18,38 @@ pub fn is_active<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "Accessibility", "isActive");
-    Ok(Value::Bool(false))
+    Ok(activation.context.accessibility.is_active().into())
 }
 
 pub fn send_event<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "Accessibility", "sendEvent");
+    let node = match args.get(0) {
+        Some(source @ (Value::MovieClip(_) | Value::Object(_))) => source
+            .coerce_to_object(activation)
+            .as_display_object()
+            .map(crate::accessibility::node_id),
+        _ => None,
+    };
+    let child_id = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+    let event_type = args
+        .get(2)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_u32(activation)?;
+
+    activation
+        .context
+        .accessibility
+        .send_event(AccessibilityEvent {
+            node,
+            child_id,
+            event_type,
+        });
     Ok(Value::Undefined)
 }
 
@@ -36,7 +58,9 @@ pub fn update_properties<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "Accessibility", "updateProperties");
+    // Changes to `_accProps` are only picked up once the tree is rebuilt.
+    let stage = activation.context.stage;
+    stage.set_accessibility_invalidated(activation.context.gc_context, true);
     Ok(Value::Undefined)
 }
 
@@ -51,22 +51,93 @@ const OBJECT_DECLS: &[Declaration] = declare_properties! {
     "getRemote" => method(get_remote);
 };
 
+/// Returns the host and path that `getLocal` stores the shared objects of `url` under.
+fn storage_location(url: &str) -> Option<(String, String)> {
+    let url = url::Url::parse(url).ok()?;
+    let mut path = url.path().trim_matches('/');
+    let host = if url.scheme() == "file" {
+        // Remove drive letter on Windows, matching `getLocal`.
+        if let [_, b':', b'/', ..] = path.as_bytes() {
+            path = &path[3..];
+        }
+        "localhost"
+    } else {
+        url.host_str().unwrap_or_default()
+    };
+    Some((host.to_string(), path.to_string()))
+}
+
+/// Converts the URL passed to `deleteAll`/`getDiskUsage` into the storage key prefix
+/// used by `getLocal` (`host/path/to/movie.swf`).
+///
+/// Returns `None` if the URL is not on the domain of the calling movie, as movies can
+/// only access their own shared objects.
+fn storage_prefix(activation: &mut Activation, url: &str) -> Option<String> {
+    let (movie_host, _) = storage_location(activation.base_clip().movie().url())?;
+    let (host, path) = match storage_location(url) {
+        Some(location) => location,
+        None => {
+            let url = url.trim_matches('/');
+            let (host, path) = url.split_once('/').unwrap_or((url, ""));
+            (host.to_string(), path.to_string())
+        }
+    };
+    if host.is_empty() || host != movie_host {
+        tracing::warn!("SharedObject: Tried to access the shared objects of another domain");
+        return None;
+    }
+    // Avoid any paths with `..`, as `getLocal` does.
+    if path.split('/').any(|s| s.starts_with('.')) {
+        return None;
+    }
+
+    if path.is_empty() {
+        Some(host)
+    } else {
+        Some(format!("{host}/{path}"))
+    }
+}
+
 fn delete_all<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "SharedObject", "deleteAll");
-    Ok(Value::Undefined)
+    let url = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let Some(prefix) = storage_prefix(activation, &url.to_utf8_lossy()) else {
+        return Ok(false.into());
+    };
+
+    let storage = &mut activation.context.storage;
+    for key in storage.keys_with_prefix(&prefix) {
+        storage.remove_key(&key);
+    }
+    Ok(true.into())
 }
 
 fn get_disk_usage<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm1_stub!(activation, "SharedObject", "getDiskUsage");
-    Ok(Value::Undefined)
+    let url = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation)?;
+    let Some(prefix) = storage_prefix(activation, &url.to_utf8_lossy()) else {
+        return Ok(0.into());
+    };
+
+    let storage = &activation.context.storage;
+    let usage: usize = storage
+        .keys_with_prefix(&prefix)
+        .iter()
+        .filter_map(|key| storage.get_size(key))
+        .sum();
+    Ok(usage.into())
 }
 
 pub fn serialize<'gc>(activation: &mut Activation<'_, 'gc>, value: Value<'gc>) -> AmfValue {
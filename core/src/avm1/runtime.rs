@@ -14,7 +14,6 @@ use crate::tag_utils::SwfSlice;
 use crate::{avm1, avm_debug};
 use gc_arena::{Collect, Gc, Mutation};
 use std::borrow::Cow;
-use swf::avm1::read::Reader;
 use tracing::instrument;
 
 #[derive(Collect)]
@@ -574,14 +573,6 @@ impl<'gc> Avm1<'gc> {
 
 /// Utility function used by `Avm1::action_wait_for_frame` and
 /// `Avm1::action_wait_for_frame_2`.
-pub fn skip_actions(reader: &mut Reader<'_>, num_actions_to_skip: u8) {
-    for _ in 0..num_actions_to_skip {
-        if let Err(e) = reader.read_action() {
-            tracing::warn!("Couldn't skip action: {}", e);
-        }
-    }
-}
-
 pub fn root_error_handler<'gc>(activation: &mut Activation<'_, 'gc>, error: Error<'gc>) {
     match &error {
         Error::ThrownValue(value) => {
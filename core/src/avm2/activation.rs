@@ -7,6 +7,7 @@ use crate::avm2::e4x::{escape_attribute_value, escape_element_value};
 use crate::avm2::error::{
     make_error_1065, make_error_1127, make_error_1506, make_null_or_undefined_error, type_error,
 };
+use crate::avm2::inline_cache::InlineCache;
 use crate::avm2::method::{BytecodeMethod, Method, ResolvedParamConfig};
 use crate::avm2::object::{
     ArrayObject, ByteArrayObject, ClassObject, FunctionObject, NamespaceObject, ScriptObject,
@@ -14,6 +15,7 @@ use crate::avm2::object::{
 };
 use crate::avm2::object::{Object, TObject};
 use crate::avm2::op::Op;
+use crate::avm2::property::Property;
 use crate::avm2::scope::{search_scope_stack, Scope, ScopeChain};
 use crate::avm2::script::Script;
 use crate::avm2::value::Value;
@@ -23,7 +25,7 @@ use crate::avm2::{Avm2, Error};
 use crate::context::{GcContext, UpdateContext};
 use crate::string::{AvmAtom, AvmString};
 use crate::tag_utils::SwfMovie;
-use gc_arena::Gc;
+use gc_arena::{Gc, GcCell};
 use smallvec::SmallVec;
 use std::cmp::{min, Ordering};
 use std::sync::Arc;
@@ -756,12 +758,14 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         // The method must be verified at this point
 
         let verified_info = method.verified_info.read();
-        let verified_code = verified_info.as_ref().unwrap().parsed_code.as_slice();
+        let verified_info = verified_info.as_ref().unwrap();
+        let verified_code = verified_info.parsed_code.as_slice();
+        let inline_caches = verified_info.inline_caches.as_slice();
 
         self.ip = 0;
 
         let val = loop {
-            let result = self.do_next_opcode(method, verified_code);
+            let result = self.do_next_opcode(method, verified_code, inline_caches);
             match result {
                 Ok(FrameControl::Return(value)) => break Ok(value),
                 Ok(FrameControl::Continue) => {}
@@ -828,6 +832,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
         opcodes: &[Op<'gc>],
+        inline_caches: &[Option<GcCell<'gc, InlineCache<'gc>>>],
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         self.actions_since_timeout_check += 1;
         if self.actions_since_timeout_check >= 64000 {
@@ -872,7 +877,11 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::CallProperty {
                     multiname,
                     num_args,
-                } => self.op_call_property(*multiname, *num_args),
+                } => self.op_call_property(
+                    *multiname,
+                    *num_args,
+                    inline_caches[self.ip as usize - 1],
+                ),
                 Op::CallPropLex {
                     multiname,
                     num_args,
@@ -880,7 +889,11 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::CallPropVoid {
                     multiname,
                     num_args,
-                } => self.op_call_prop_void(*multiname, *num_args),
+                } => self.op_call_prop_void(
+                    *multiname,
+                    *num_args,
+                    inline_caches[self.ip as usize - 1],
+                ),
                 Op::CallStatic { index, num_args } => {
                     self.op_call_static(method, *index, *num_args)
                 }
@@ -895,8 +908,12 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::ReturnValue => self.op_return_value(method),
                 Op::ReturnValueNoCoerce => self.op_return_value_no_coerce(),
                 Op::ReturnVoid => self.op_return_void(),
-                Op::GetProperty { multiname } => self.op_get_property(*multiname),
-                Op::SetProperty { multiname } => self.op_set_property(*multiname),
+                Op::GetProperty { multiname } => {
+                    self.op_get_property(*multiname, inline_caches[self.ip as usize - 1])
+                }
+                Op::SetProperty { multiname } => {
+                    self.op_set_property(*multiname, inline_caches[self.ip as usize - 1])
+                }
                 Op::InitProperty { multiname } => self.op_init_property(*multiname),
                 Op::DeleteProperty { multiname } => self.op_delete_property(*multiname),
                 Op::GetSuper { multiname } => self.op_get_super(*multiname),
@@ -1194,10 +1211,30 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(FrameControl::Continue)
     }
 
+    /// Look up the trait a multiname resolves to on an object, through the
+    /// inline cache of the current op if it has one.
+    fn lookup_trait(
+        &self,
+        inline_cache: Option<GcCell<'gc, InlineCache<'gc>>>,
+        object: Object<'gc>,
+        multiname: &Multiname<'gc>,
+    ) -> Option<Property> {
+        match inline_cache {
+            Some(inline_cache) => InlineCache::get_trait(
+                inline_cache,
+                self.context.gc_context,
+                object.vtable(),
+                multiname,
+            ),
+            None => object.vtable().get_trait(multiname),
+        }
+    }
+
     fn op_call_property(
         &mut self,
         multiname: Gc<'gc, Multiname<'gc>>,
         arg_count: u32,
+        inline_cache: Option<GcCell<'gc, InlineCache<'gc>>>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let args = self.pop_stack_args(arg_count);
         let multiname = multiname.fill_with_runtime_params(self)?;
@@ -1205,7 +1242,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             .pop_stack()
             .coerce_to_object_or_typeerror(self, Some(&multiname))?;
 
-        let value = receiver.call_property(&multiname, &args, self)?;
+        let property = self.lookup_trait(inline_cache, receiver, &multiname);
+        let value = receiver.call_property_with_trait(&multiname, property, &args, self)?;
 
         self.push_stack(value);
 
@@ -1239,6 +1277,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         &mut self,
         multiname: Gc<'gc, Multiname<'gc>>,
         arg_count: u32,
+        inline_cache: Option<GcCell<'gc, InlineCache<'gc>>>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let args = self.pop_stack_args(arg_count);
         let multiname = multiname.fill_with_runtime_params(self)?;
@@ -1246,7 +1285,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             .pop_stack()
             .coerce_to_object_or_typeerror(self, Some(&multiname))?;
 
-        receiver.call_property(&multiname, &args, self)?;
+        let property = self.lookup_trait(inline_cache, receiver, &multiname);
+        receiver.call_property_with_trait(&multiname, property, &args, self)?;
 
         Ok(FrameControl::Continue)
     }
@@ -1337,12 +1377,14 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_get_property(
         &mut self,
         multiname: Gc<'gc, Multiname<'gc>>,
+        inline_cache: Option<GcCell<'gc, InlineCache<'gc>>>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         // default path for static names
         if !multiname.has_lazy_component() {
             let object = self.pop_stack();
             let object = object.coerce_to_object_or_typeerror(self, Some(&multiname))?;
-            let value = object.get_property(&multiname, self)?;
+            let property = self.lookup_trait(inline_cache, object, &multiname);
+            let value = object.get_property_with_trait(&multiname, property, self)?;
             self.push_stack(value);
             return Ok(FrameControl::Continue);
         }
@@ -1397,6 +1439,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     fn op_set_property(
         &mut self,
         multiname: Gc<'gc, Multiname<'gc>>,
+        inline_cache: Option<GcCell<'gc, InlineCache<'gc>>>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.pop_stack();
 
@@ -1404,7 +1447,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         if !multiname.has_lazy_component() {
             let object = self.pop_stack();
             let object = object.coerce_to_object_or_typeerror(self, Some(&multiname))?;
-            object.set_property(&multiname, value, self)?;
+            let property = self.lookup_trait(inline_cache, object, &multiname);
+            object.set_property_with_trait(&multiname, property, value, self)?;
             return Ok(FrameControl::Continue);
         }
 
@@ -308,6 +308,13 @@ impl ByteArrayStorage {
         self.position.set(0)
     }
 
+    /// Removes the bytes before the read position, moving it to the start.
+    pub fn discard_read(&mut self) {
+        let position = self.position().min(self.len());
+        self.bytes.drain(..position);
+        self.position.set(0);
+    }
+
     #[inline]
     pub fn shrink_to_fit(&mut self) {
         self.bytes.shrink_to_fit()
@@ -876,12 +876,12 @@ fn get_gradient_colors<'gc>(
                                     .get(i)
                                     .expect("Length was already checked at this point")
                                     .coerce_to_u32(activation)?;
-                                let alpha = colors_array
+                                let alpha = alphas_array
                                     .get(i)
                                     .expect("Length was already checked at this point")
                                     .coerce_to_number(activation)?
                                     as f32;
-                                let ratio = colors_array
+                                let ratio = ratios_array
                                     .get(i)
                                     .expect("Length was already checked at this point")
                                     .coerce_to_u32(activation)?;
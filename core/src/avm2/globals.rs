@@ -73,6 +73,7 @@ pub struct SystemClasses<'gc> {
     pub framelabel: ClassObject<'gc>,
     pub scene: ClassObject<'gc>,
     pub application_domain: ClassObject<'gc>,
+    pub system: ClassObject<'gc>,
     pub event: ClassObject<'gc>,
     pub fullscreenevent: ClassObject<'gc>,
     pub video: ClassObject<'gc>,
@@ -113,8 +114,11 @@ pub struct SystemClasses<'gc> {
     pub progressevent: ClassObject<'gc>,
     pub httpstatusevent: ClassObject<'gc>,
     pub textevent: ClassObject<'gc>,
+    pub imeevent: ClassObject<'gc>,
     pub errorevent: ClassObject<'gc>,
     pub ioerrorevent: ClassObject<'gc>,
+    pub dataevent: ClassObject<'gc>,
+    pub nativeprocessexitevent: ClassObject<'gc>,
     pub securityerrorevent: ClassObject<'gc>,
     pub transform: ClassObject<'gc>,
     pub colortransform: ClassObject<'gc>,
@@ -144,6 +148,7 @@ pub struct SystemClasses<'gc> {
     pub indexbuffer3d: ClassObject<'gc>,
     pub vertexbuffer3d: ClassObject<'gc>,
     pub program3d: ClassObject<'gc>,
+    pub urlrequest: ClassObject<'gc>,
     pub urlvariables: ClassObject<'gc>,
     pub bevelfilter: ClassObject<'gc>,
     pub bitmapfilter: ClassObject<'gc>,
@@ -205,6 +210,7 @@ impl<'gc> SystemClasses<'gc> {
             framelabel: object,
             scene: object,
             application_domain: object,
+            system: object,
             event: object,
             fullscreenevent: object,
             video: object,
@@ -243,8 +249,11 @@ impl<'gc> SystemClasses<'gc> {
             progressevent: object,
             httpstatusevent: object,
             textevent: object,
+            imeevent: object,
             errorevent: object,
             ioerrorevent: object,
+            dataevent: object,
+            nativeprocessexitevent: object,
             securityerrorevent: object,
             transform: object,
             colortransform: object,
@@ -274,6 +283,7 @@ impl<'gc> SystemClasses<'gc> {
             indexbuffer3d: object,
             vertexbuffer3d: object,
             program3d: object,
+            urlrequest: object,
             urlvariables: object,
             bevelfilter: object,
             bitmapfilter: object,
@@ -786,6 +796,7 @@ fn load_playerglobal<'gc>(
                 graphicstrianglepath
             ),
             ("flash.display", "GraphicsSolidFill", graphicssolidfill),
+            ("flash.display", "GraphicsShaderFill", graphicsshaderfill),
             ("flash.display", "GraphicsStroke", graphicsstroke),
             ("flash.display", "Graphics", graphics),
             ("flash.display", "Loader", loader),
@@ -820,12 +831,19 @@ fn load_playerglobal<'gc>(
             ("flash.events", "Event", event),
             ("flash.events", "EventDispatcher", eventdispatcher),
             ("flash.events", "TextEvent", textevent),
+            ("flash.events", "IMEEvent", imeevent),
             ("flash.events", "ErrorEvent", errorevent),
             ("flash.events", "KeyboardEvent", keyboardevent),
             ("flash.events", "ProgressEvent", progressevent),
             ("flash.events", "HTTPStatusEvent", httpstatusevent),
             ("flash.events", "SecurityErrorEvent", securityerrorevent),
             ("flash.events", "IOErrorEvent", ioerrorevent),
+            ("flash.events", "DataEvent", dataevent),
+            (
+                "flash.events",
+                "NativeProcessExitEvent",
+                nativeprocessexitevent
+            ),
             ("flash.events", "MouseEvent", mouseevent),
             ("flash.events", "FullScreenEvent", fullscreenevent),
             ("flash.events", "UncaughtErrorEvents", uncaughterrorevents),
@@ -843,12 +861,14 @@ fn load_playerglobal<'gc>(
             ("flash.media", "SoundChannel", soundchannel),
             ("flash.media", "SoundTransform", soundtransform),
             ("flash.media", "Video", video),
+            ("flash.net", "URLRequest", urlrequest),
             ("flash.net", "URLVariables", urlvariables),
             ("flash.net", "FileReference", filereference),
             ("flash.net", "FileFilter", filefilter),
             ("flash.utils", "ByteArray", bytearray),
             ("flash.utils", "Dictionary", dictionary),
             ("flash.system", "ApplicationDomain", application_domain),
+            ("flash.system", "System", system),
             ("flash.text", "Font", font),
             ("flash.text", "StaticText", statictext),
             ("flash.text", "TextFormat", textformat),
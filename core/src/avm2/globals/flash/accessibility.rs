@@ -0,0 +1,4 @@
+//! `flash.accessibility` namespace
+#![allow(clippy::module_inception)]
+
+pub mod accessibility;
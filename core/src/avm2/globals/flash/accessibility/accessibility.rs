@@ -0,0 +1,55 @@
+//! `flash.accessibility.Accessibility` builtin
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::backend::accessibility::AccessibilityEvent;
+use crate::display_object::TDisplayObject;
+
+pub fn get_active<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.accessibility.is_active().into())
+}
+
+pub fn send_event<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let source = args
+        .try_get_object(activation, 0)
+        .and_then(|source| source.as_display_object());
+    if let Some(source) = source {
+        // The answers of its `AccessibilityImplementation` may have changed.
+        source.invalidate_accessibility(activation.context.gc_context);
+    }
+    let node = source.map(crate::accessibility::node_id);
+    let child_id = args.get_u32(activation, 1)?;
+    let event_type = args.get_u32(activation, 2)?;
+
+    activation
+        .context
+        .accessibility
+        .send_event(AccessibilityEvent {
+            node,
+            child_id,
+            event_type,
+        });
+    Ok(Value::Undefined)
+}
+
+pub fn update_properties<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Changes to `accessibilityProperties` are only picked up once the tree is rebuilt.
+    let stage = activation.context.stage;
+    stage.set_accessibility_invalidated(activation.context.gc_context, true);
+    Ok(Value::Undefined)
+}
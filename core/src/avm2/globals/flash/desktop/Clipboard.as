@@ -1,54 +1,125 @@
 package flash.desktop {
-    import __ruffle__.stub_getter;
-    import __ruffle__.stub_method;
-    import flash.system.System;
+    import flash.filesystem.File;
 
     public class Clipboard {
-        private static var _generalClipboard = new Clipboard();
+        private static var _generalClipboard:Clipboard = null;
+
+        // Whether this is the system clipboard, whose standard formats are held by the UI backend.
+        private var _isGeneral:Boolean = false;
+
+        // Data set on this clipboard, by format.
+        // For the general clipboard, this only holds custom formats.
+        private var _data:Object = {};
+
+        // Functions rendering deferred data, by format.
+        private var _handlers:Object = {};
 
         public static function get generalClipboard(): Clipboard {
-            return Clipboard._generalClipboard;
+            if (_generalClipboard === null) {
+                _generalClipboard = new Clipboard();
+                _generalClipboard._isGeneral = true;
+            }
+            return _generalClipboard;
         }
 
+        private static native function getGeneralFormats(): Array;
+        private static native function getGeneralData(format: String): *;
+        private static native function setGeneralData(format: String, data: *): Boolean;
+        private static native function clearGeneralData(): void;
+
         function Clipboard() {
             // TODO: This should only be callable in AIR
         }
 
         public function get formats(): Array {
-            stub_getter("flash.desktop.Clipboard", "formats");
-            return new Array();
+            var result: Array = this._isGeneral ? getGeneralFormats() : [];
+            var format: String;
+            for (format in this._data) {
+                if (result.indexOf(format) == -1) {
+                    result.push(format);
+                }
+            }
+            for (format in this._handlers) {
+                if (result.indexOf(format) == -1) {
+                    result.push(format);
+                }
+            }
+            return result;
         }
 
         public function clear(): void {
-            stub_method("flash.desktop.Clipboard", "clear");
+            this._data = {};
+            this._handlers = {};
+            if (this._isGeneral) {
+                clearGeneralData();
+            }
         }
 
         public function clearData(format: String): void {
-            stub_method("flash.desktop.Clipboard", "clearData");
+            delete this._data[format];
+            delete this._handlers[format];
+            if (this._isGeneral) {
+                setGeneralData(format, null);
+            }
         }
 
         public function getData(format: String, transferMode: String = ClipboardTransferMode.ORIGINAL_PREFERRED): Object {
-            stub_method("flash.desktop.Clipboard", "getData");
+            if (this._handlers.hasOwnProperty(format)) {
+                // Deferred data is only rendered once.
+                var handler: Function = this._handlers[format];
+                delete this._handlers[format];
+                this.setData(format, handler());
+            }
+
+            if (this._isGeneral) {
+                var data: * = getGeneralData(format);
+                if (data !== undefined) {
+                    return fromGeneralData(format, data);
+                }
+            }
+
+            if (this._data.hasOwnProperty(format)) {
+                return this._data[format];
+            }
             return null;
         }
 
         public function hasFormat(format: String): Boolean {
-            stub_method("flash.desktop.Clipboard", "hasFormat");
-            return false;
+            return this.formats.indexOf(format) != -1;
         }
 
         public function setData(format: String, data: Object, serializable: Boolean = true): Boolean {
-            stub_method("flash.desktop.Clipboard", "setData");
-            if (format == ClipboardFormats.TEXT_FORMAT) {
-                System.setClipboard(data);
+            delete this._handlers[format];
+            if (this._isGeneral && setGeneralData(format, toGeneralData(format, data))) {
                 return true;
             }
-            return false;
+            this._data[format] = data;
+            return true;
         }
 
         public function setDataHandler(format: String, handler: Function, serializable: Boolean = true): Boolean {
-            stub_method("flash.desktop.Clipboard", "setDataHandler");
-            return false;
+            this.clearData(format);
+            this._handlers[format] = handler;
+            return true;
+        }
+
+        // Converts the data of a standard format to what the UI backend understands.
+        private static function toGeneralData(format: String, data: Object): * {
+            if (format == ClipboardFormats.FILE_LIST_FORMAT && data is Array) {
+                return (data as Array).map(function(file: *, index: int, array: Array): String {
+                    return (file is File) ? (file as File).nativePath : String(file);
+                });
+            }
+            return data;
+        }
+
+        private static function fromGeneralData(format: String, data: *): Object {
+            if (format == ClipboardFormats.FILE_LIST_FORMAT && data is Array) {
+                return (data as Array).map(function(path: String, index: int, array: Array): File {
+                    return new File(path);
+                });
+            }
+            return data;
         }
     }
 }
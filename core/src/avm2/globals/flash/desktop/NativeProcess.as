@@ -1,20 +1,70 @@
 package flash.desktop {
-    import __ruffle__.stub_method;
-
+    import flash.errors.IllegalOperationError;
     import flash.events.EventDispatcher;
+    import flash.utils.ByteArray;
+    import flash.utils.IDataInput;
+    import flash.utils.IDataOutput;
 
+    // The standard streams are plain ByteArrays: data written to `standardInput`
+    // is sent to the process on the next update, and output from the process is
+    // appended to `standardOutput` and `standardError` as it arrives.
     [API("668")]
     public class NativeProcess extends EventDispatcher {
+        private var _standardInput:ByteArray = new ByteArray();
+        private var _standardOutput:ByteArray = new ByteArray();
+        private var _standardError:ByteArray = new ByteArray();
+
         public function NativeProcess() {
             super();
         }
 
-        public static function get isSupported():Boolean {
-            return false;
+        public static native function get isSupported():Boolean;
+
+        public native function get running():Boolean;
+
+        private native function startProcess(executable:String, workingDirectory:String, arguments:Array,
+                                             standardInput:ByteArray, standardOutput:ByteArray, standardError:ByteArray):void;
+
+        public native function closeInput():void;
+
+        public native function exit(force:Boolean = false):void;
+
+        public function get standardInput():IDataOutput {
+            return this._standardInput;
+        }
+
+        public function get standardOutput():IDataInput {
+            return this._standardOutput;
+        }
+
+        public function get standardError():IDataInput {
+            return this._standardError;
         }
 
         public function start(info:NativeProcessStartupInfo):void {
-            stub_method("flash.desktop.NativeProcess", "start");
+            if (!isSupported) {
+                throw new IllegalOperationError("Error #3214: NativeProcess is not supported on this platform.", 3214);
+            }
+            if (info.executable === null) {
+                throw new ArgumentError("Error #2007: Parameter executable must be non-null.", 2007);
+            }
+            if (this.running) {
+                throw new IllegalOperationError("Error #3210: The NativeProcess is already running.", 3210);
+            }
+
+            var args:Array = [];
+            if (info.arguments !== null) {
+                for each (var argument:String in info.arguments) {
+                    args.push(argument);
+                }
+            }
+            var workingDirectory:String = info.workingDirectory !== null ? info.workingDirectory.nativePath : null;
+
+            this._standardInput = new ByteArray();
+            this._standardOutput = new ByteArray();
+            this._standardError = new ByteArray();
+            this.startProcess(info.executable.nativePath, workingDirectory, args,
+                              this._standardInput, this._standardOutput, this._standardError);
         }
     }
 }
@@ -0,0 +1,222 @@
+//! `flash.desktop.Clipboard` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::object::{BitmapDataObject, ByteArrayObject, Object, TObject};
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::{ArrayObject, ArrayStorage, Error};
+use crate::backend::ui::{ClipboardData, ClipboardFormat};
+use crate::bitmap::bitmap_data::{BitmapData, BitmapDataWrapper, Color};
+use crate::string::{AvmString, WStr};
+use gc_arena::GcCell;
+use ruffle_render::bitmap::PixelRegion;
+
+const FORMATS: [(&str, ClipboardFormat); 6] = [
+    ("air:text", ClipboardFormat::Text),
+    ("air:html", ClipboardFormat::Html),
+    ("air:rtf", ClipboardFormat::RichText),
+    ("air:url", ClipboardFormat::Url),
+    ("air:bitmap", ClipboardFormat::Bitmap),
+    ("air:file list", ClipboardFormat::FileList),
+];
+
+fn format_from_name(name: AvmString) -> Option<ClipboardFormat> {
+    FORMATS
+        .iter()
+        .find(|(format_name, _)| &*name == WStr::from_units(format_name.as_bytes()))
+        .map(|(_, format)| *format)
+}
+
+fn format_name(format: ClipboardFormat) -> &'static str {
+    FORMATS
+        .iter()
+        .find(|(_, f)| *f == format)
+        .map(|(name, _)| *name)
+        .expect("Every format has a name")
+}
+
+/// Converts an AS3 value to clipboard data of the given format.
+fn value_to_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    format: ClipboardFormat,
+    value: Value<'gc>,
+) -> Result<Option<ClipboardData>, Error<'gc>> {
+    let data = match format {
+        ClipboardFormat::Text => {
+            ClipboardData::Text(value.coerce_to_string(activation)?.to_string())
+        }
+        ClipboardFormat::Html => {
+            ClipboardData::Html(value.coerce_to_string(activation)?.to_string())
+        }
+        ClipboardFormat::Url => ClipboardData::Url(value.coerce_to_string(activation)?.to_string()),
+        ClipboardFormat::RichText => {
+            let Some(object) = value.as_object() else {
+                return Ok(None);
+            };
+            let Some(bytes) = object.as_bytearray().map(|b| b.bytes().to_vec()) else {
+                return Ok(None);
+            };
+            ClipboardData::RichText(bytes)
+        }
+        ClipboardFormat::Bitmap => {
+            let Some(bitmap_data) = value.as_object().and_then(|o| o.as_bitmap_data()) else {
+                return Ok(None);
+            };
+            if bitmap_data.disposed() {
+                return Ok(None);
+            }
+            let (width, height) = (bitmap_data.width(), bitmap_data.height());
+            let read = bitmap_data.read_area(
+                PixelRegion::for_whole_size(width, height),
+                activation.context.renderer,
+            );
+            let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+            for y in 0..height {
+                for x in 0..width {
+                    let color = read.get_pixel32_raw(x, y).to_un_multiplied_alpha();
+                    rgba.extend([color.red(), color.green(), color.blue(), color.alpha()]);
+                }
+            }
+            ClipboardData::Bitmap {
+                width,
+                height,
+                rgba,
+            }
+        }
+        ClipboardFormat::FileList => {
+            let Some(array) = value.as_object() else {
+                return Ok(None);
+            };
+            let paths: Vec<Value<'gc>> = array
+                .as_array_storage()
+                .map(|storage| storage.iter().flatten().collect())
+                .unwrap_or_default();
+            let mut files = Vec::with_capacity(paths.len());
+            for path in paths {
+                files.push(path.coerce_to_string(activation)?.to_string());
+            }
+            ClipboardData::FileList(files)
+        }
+    };
+    Ok(Some(data))
+}
+
+/// Converts clipboard data to the AS3 value `Clipboard.getData` returns for its format.
+fn data_to_value<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    data: ClipboardData,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(match data {
+        ClipboardData::Text(text) | ClipboardData::Html(text) | ClipboardData::Url(text) => {
+            AvmString::new_utf8(activation.context.gc_context, text).into()
+        }
+        ClipboardData::RichText(bytes) => {
+            let storage = ByteArrayStorage::from_vec(bytes);
+            ByteArrayObject::from_storage(activation, storage)?.into()
+        }
+        ClipboardData::Bitmap {
+            width,
+            height,
+            rgba,
+        } => {
+            let pixels = rgba
+                .chunks_exact(4)
+                .map(|p| Color::argb(p[3], p[0], p[1], p[2]).to_premultiplied_alpha(true))
+                .collect();
+            let bitmap_data = BitmapData::new_with_pixels(width, height, true, pixels);
+            let wrapper =
+                BitmapDataWrapper::new(GcCell::new(activation.context.gc_context, bitmap_data));
+            let class = activation.avm2().classes().bitmapdata;
+            BitmapDataObject::from_bitmap_data_internal(activation, wrapper, class)?.into()
+        }
+        ClipboardData::FileList(files) => {
+            let paths = files
+                .into_iter()
+                .map(|path| Some(AvmString::new_utf8(activation.context.gc_context, path).into()))
+                .collect();
+            ArrayObject::from_storage(activation, ArrayStorage::from_storage(paths))?.into()
+        }
+    })
+}
+
+/// Implements `Clipboard.getGeneralFormats`
+pub fn get_general_formats<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let formats = activation
+        .context
+        .ui
+        .clipboard_formats()
+        .into_iter()
+        .map(|format| Some(AvmString::from(format_name(format)).into()))
+        .collect();
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_storage(formats))?.into())
+}
+
+/// Implements `Clipboard.getGeneralData`
+///
+/// Returns `undefined` for formats the system clipboard can't hold.
+pub fn get_general_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let Some(format) = format_from_name(args.get_string(activation, 0)?) else {
+        return Ok(Value::Undefined);
+    };
+
+    match activation.context.ui.clipboard_data(format) {
+        Some(data) => data_to_value(activation, data),
+        None => Ok(Value::Null),
+    }
+}
+
+/// Implements `Clipboard.setGeneralData`
+///
+/// Replaces the data of the given format while keeping every other format,
+/// or removes it if `data` is `null`. Returns `false` for formats the system
+/// clipboard can't hold.
+pub fn set_general_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let Some(format) = format_from_name(args.get_string(activation, 0)?) else {
+        return Ok(false.into());
+    };
+    let value = args.get_value(1);
+
+    let new_data = if matches!(value, Value::Null | Value::Undefined) {
+        None
+    } else {
+        match value_to_data(activation, format, value)? {
+            Some(data) => Some(data),
+            None => return Ok(false.into()),
+        }
+    };
+
+    let ui = &mut activation.context.ui;
+    let mut data: Vec<ClipboardData> = ui
+        .clipboard_formats()
+        .into_iter()
+        .filter(|f| *f != format)
+        .filter_map(|f| ui.clipboard_data(f))
+        .collect();
+    data.extend(new_data);
+    ui.set_clipboard_data(data);
+
+    Ok(true.into())
+}
+
+/// Implements `Clipboard.clearGeneralData`
+pub fn clear_general_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    activation.context.ui.set_clipboard_data(vec![]);
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,115 @@
+//! `flash.desktop.NativeProcess` native methods
+
+use crate::avm2::error::{error, illegal_operation_error};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::{Activation, Error, Value};
+use crate::context::UpdateContext;
+use crate::native_process::ProcessStartupInfo;
+
+/// Implements `NativeProcess.isSupported`
+pub fn get_is_supported<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.navigator.can_start_process().into())
+}
+
+/// Implements `NativeProcess.running`
+pub fn get_running<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.native_processes.is_running(this).into())
+}
+
+/// Implements `NativeProcess.startProcess`
+///
+/// Takes the native paths of the executable and working directory, the arguments,
+/// and the `ByteArray`s backing the standard streams.
+pub fn start_process<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if activation.context.native_processes.is_running(this) {
+        return Err(Error::AvmError(illegal_operation_error(
+            activation,
+            "Error #3210: The NativeProcess is already running.",
+            3210,
+        )?));
+    }
+
+    let executable = args.get_string(activation, 0)?.to_string();
+    let working_directory = match args.get_value(1) {
+        Value::Null | Value::Undefined => None,
+        value => Some(value.coerce_to_string(activation)?.to_string()),
+    };
+
+    let values: Vec<Value<'gc>> = args
+        .try_get_object(activation, 2)
+        .and_then(|array| {
+            array
+                .as_array_storage()
+                .map(|storage| storage.iter().flatten().collect())
+        })
+        .unwrap_or_default();
+    let mut arguments = Vec::with_capacity(values.len());
+    for value in values {
+        arguments.push(value.coerce_to_string(activation)?.to_string());
+    }
+
+    let standard_input = args.get_object(activation, 3, "standardInput")?;
+    let standard_output = args.get_object(activation, 4, "standardOutput")?;
+    let standard_error = args.get_object(activation, 5, "standardError")?;
+
+    let info = ProcessStartupInfo {
+        executable,
+        arguments,
+        working_directory,
+    };
+
+    let UpdateContext {
+        native_processes,
+        navigator,
+        ..
+    } = activation.context;
+    if let Err(e) = native_processes.start(
+        *navigator,
+        this,
+        info,
+        standard_input,
+        standard_output,
+        standard_error,
+    ) {
+        return Err(Error::AvmError(error(
+            activation,
+            &format!("Error #3219: The NativeProcess could not be started. '{e}'"),
+            3219,
+        )?));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `NativeProcess.closeInput`
+pub fn close_input<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    activation.context.native_processes.close_input(this);
+    Ok(Value::Undefined)
+}
+
+/// Implements `NativeProcess.exit`
+pub fn exit<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    activation.context.native_processes.exit(this);
+    Ok(Value::Undefined)
+}
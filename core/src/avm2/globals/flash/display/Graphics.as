@@ -1,7 +1,6 @@
 package flash.display
 {
     import flash.geom.Matrix;
-    import __ruffle__.stub_method;
 
     // note: no need for an allocator, as it's never constructed from AS
     public final class Graphics
@@ -11,14 +10,15 @@ package flash.display
             throw new Error("You cannot construct Graphics directly.");
         }
 
+        // The `BitmapData` and `Shader` objects used by bitmap and shader fills, indexed by drawing bitmap id.
+        internal var _fills:Array;
+
         public native function beginBitmapFill(bitmap:BitmapData, matrix:Matrix = null, repeat:Boolean = true, smooth:Boolean = false):void;
         public native function beginFill(color:uint, alpha:Number = 1.0):void;
         public native function beginGradientFill(
             type:String, colors:Array, alphas:Array, ratios:Array, matrix:Matrix = null, spreadMethod:String = "pad", interpolationMethod:String = "rgb", focalPointRatio:Number = 0
         ): void;
-        public function beginShaderFill(shader:Shader, matrix:Matrix = null):void {
-            stub_method("flash.display.Graphics", "beginShaderFill");
-        }
+        public native function beginShaderFill(shader:Shader, matrix:Matrix = null):void;
         public native function clear(): void;
         public native function curveTo(controlX:Number, controlY:Number, anchorX:Number, anchorY:Number): void;
         public native function drawCircle(x:Number, y:Number, radius:Number): void;
@@ -31,7 +31,6 @@ package flash.display
         ): void;
         public native function lineTo(x:Number, y:Number): void;
         public native function moveTo(x:Number, y:Number): void;
-        //public native function beginShaderFill(shader:Shader, matrix:Matrix = null):void;
         public native function lineGradientStyle(
             type:String, colors:Array, alphas:Array, ratios:Array, matrix:Matrix = null, spreadMethod:String = "pad", interpolationMethod:String = "rgb", focalPointRatio:Number = 0
         ):void;
@@ -7,7 +7,6 @@ package flash.display {
 
     [Ruffle(NativeInstanceInit)]
     public class InteractiveObject extends DisplayObject {
-        private var _accessibilityImpl:AccessibilityImplementation = null;
         private var _needsSoftKeyboard:Boolean = false;
         private var _softKeyboardInputAreaOfInterest:Rectangle = new Rectangle();
 
@@ -15,13 +14,8 @@ package flash.display {
             throw new Error("You cannot directly construct InteractiveObject.")
         }
 
-        public function get accessibilityImplementation():AccessibilityImplementation {
-            return this._accessibilityImpl;
-        }
-        public function set accessibilityImplementation(value:AccessibilityImplementation):void {
-            stub_setter("flash.display.InteractiveObject", "accessibilityImplementation");
-            this._accessibilityImpl = value;
-        }
+        public native function get accessibilityImplementation():AccessibilityImplementation;
+        public native function set accessibilityImplementation(value:AccessibilityImplementation):void;
 
         public native function get mouseEnabled():Boolean;
         public native function set mouseEnabled(value:Boolean):void;
@@ -5,21 +5,28 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::error::{make_error_2004, make_error_2008, Error2004Type};
-use crate::avm2::globals::flash::geom::transform::object_to_matrix;
-use crate::avm2::object::{Object, TObject, VectorObject};
+use crate::avm2::globals::flash::display::bitmap_data::fill_bitmap_data_from_symbol;
+use crate::avm2::globals::flash::display::shader_job::get_shader_args;
+use crate::avm2::globals::flash::geom::transform::{matrix_to_object, object_to_matrix};
+use crate::avm2::object::{ArrayObject, BitmapDataObject, Object, TObject, VectorObject};
 use crate::avm2::parameters::ParametersExt;
 use crate::avm2::value::Value;
 use crate::avm2::vector::VectorStorage;
-use crate::avm2::{ArrayStorage, Error};
+use crate::avm2::{ArrayStorage, Error, Multiname};
 use crate::avm2_stub_method;
-use crate::display_object::TDisplayObject;
+use crate::character::Character;
+use crate::display_object::{DisplayObject, TDisplayObject, TDisplayObjectContainer};
 use crate::drawing::Drawing;
 use crate::string::{AvmString, WStr};
-use ruffle_render::shape_utils::{DrawCommand, FillRule, GradientType};
+use crate::tag_utils::SwfMovie;
+use ruffle_render::shape_utils::{
+    DistilledShape, DrawCommand, DrawPath, FillRule, GradientType, TexturedVertex,
+};
 use std::f64::consts::FRAC_1_SQRT_2;
+use std::sync::Arc;
 use swf::{
-    Color, FillStyle, Fixed16, Fixed8, Gradient, GradientInterpolation, GradientRecord,
-    GradientSpread, LineCapStyle, LineJoinStyle, LineStyle, Matrix, Point, Twips,
+    Color, FillStyle, Fixed8, Gradient, GradientInterpolation, GradientRecord, GradientSpread,
+    LineCapStyle, LineJoinStyle, LineStyle, Matrix, Point, Twips,
 };
 
 /// Convert an RGB `color` and `alpha` argument pair into a `swf::Color`.
@@ -28,6 +35,47 @@ fn color_from_args(rgb: u32, alpha: f64) -> Color {
     Color::from_rgb(rgb, (alpha * 255.0) as u8)
 }
 
+/// The matrix that bitmap fill matrices are scaled by, as bitmap fills are defined in twips.
+fn bitmap_scale_matrix() -> Matrix {
+    Matrix::scale(
+        (Twips::TWIPS_PER_PIXEL as i16).into(),
+        (Twips::TWIPS_PER_PIXEL as i16).into(),
+    )
+}
+
+/// The drawing bitmap id used by a bitmap (or shader) fill style.
+fn bitmap_fill_id(style: &FillStyle) -> Option<u16> {
+    match style {
+        FillStyle::Bitmap { id, .. } => Some(*id),
+        _ => None,
+    }
+}
+
+/// Remember the `BitmapData` or `Shader` object used by the drawing bitmap `id`,
+/// so that `readGraphicsData` can hand it back out.
+fn store_fill_object<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    graphics: Object<'gc>,
+    id: u16,
+    fill_object: Object<'gc>,
+) -> Result<(), Error<'gc>> {
+    let fills_name = Multiname::new(activation.avm2().flash_display_internal, "_fills");
+    let fills = match graphics.get_property(&fills_name, activation)? {
+        Value::Object(fills) => fills,
+        _ => {
+            let fills = ArrayObject::empty(activation)?;
+            graphics.set_property(&fills_name, fills.into(), activation)?;
+            fills
+        }
+    };
+
+    if let Some(mut fills) = fills.as_array_storage_mut(activation.context.gc_context) {
+        fills.set(id as usize, fill_object.into());
+    }
+
+    Ok(())
+}
+
 /// Implements `Graphics.beginFill`.
 pub fn begin_fill<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -52,9 +100,9 @@ pub fn begin_bitmap_fill<'gc>(
     this: Object<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some(this) = this.as_display_object() {
-        let bitmap = args
-            .get_object(activation, 0, "bitmap")?
+    if let Some(dobj) = this.as_display_object() {
+        let bitmap_object = args.get_object(activation, 0, "bitmap")?;
+        let bitmap = bitmap_object
             .as_bitmap_data()
             .expect("Bitmap argument is ensured to be a BitmapData from actionscript");
         let matrix = if let Some(matrix) = args.try_get_object(activation, 1) {
@@ -74,19 +122,56 @@ pub fn begin_bitmap_fill<'gc>(
             width: bitmap.width() as u16,
             height: bitmap.height() as u16,
         };
-        let scale_matrix = Matrix::scale(
-            (Twips::TWIPS_PER_PIXEL as i16).into(),
-            (Twips::TWIPS_PER_PIXEL as i16).into(),
-        );
 
-        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+        let id = if let Some(mut draw) = dobj.as_drawing(activation.context.gc_context) {
             let id = draw.add_bitmap(bitmap);
             draw.set_fill_style(Some(FillStyle::Bitmap {
                 id,
-                matrix: matrix * scale_matrix,
+                matrix: matrix * bitmap_scale_matrix(),
                 is_smoothed,
                 is_repeating,
             }));
+            Some(id)
+        } else {
+            None
+        };
+
+        if let Some(id) = id {
+            store_fill_object(activation, this, id, bitmap_object)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.beginShaderFill`.
+pub fn begin_shader_fill<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(dobj) = this.as_display_object() {
+        let shader = args.get_object(activation, 0, "shader")?;
+        let matrix = if let Some(matrix) = args.try_get_object(activation, 1) {
+            object_to_matrix(matrix, activation)?
+        } else {
+            // Users can explicitly pass in `null` to mean identity matrix
+            ruffle_render::matrix::Matrix::IDENTITY
+        };
+
+        let (shader_handle, arguments) = get_shader_args(shader, activation)?;
+
+        let id = if let Some(mut draw) = dobj.as_drawing(activation.context.gc_context) {
+            let style = draw.add_shader_fill(shader_handle, arguments, matrix);
+            let id = bitmap_fill_id(&style);
+            draw.set_fill_style(Some(style));
+            id
+        } else {
+            None
+        };
+
+        if let Some(id) = id {
+            store_fill_object(activation, this, id, shader)?;
         }
     }
 
@@ -222,12 +307,18 @@ pub fn clear<'gc>(
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some(this) = this.as_display_object() {
-        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+    if let Some(dobj) = this.as_display_object() {
+        if let Some(mut draw) = dobj.as_drawing(activation.context.gc_context) {
             draw.clear()
         }
     }
 
+    this.set_property(
+        &Multiname::new(activation.avm2().flash_display_internal, "_fills"),
+        Value::Null,
+        activation,
+    )?;
+
     Ok(Value::Undefined)
 }
 
@@ -918,21 +1009,37 @@ pub fn copy_from<'gc>(
     this: Object<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some(this) = this.as_display_object() {
-        let source = args
-            .get_object(activation, 0, "sourceGraphics")?
+    if let Some(dobj) = this.as_display_object() {
+        let source_graphics = args.get_object(activation, 0, "sourceGraphics")?;
+        let source = source_graphics
             .as_display_object()
             .expect("Bad sourceGraphics");
 
-        let source = source
-            .as_drawing(activation.context.gc_context)
-            .expect("Missing drawing for sourceGraphics");
+        {
+            let source = source
+                .as_drawing(activation.context.gc_context)
+                .expect("Missing drawing for sourceGraphics");
+
+            let mut target_drawing = dobj
+                .as_drawing(activation.context.gc_context)
+                .expect("Missing drawing for target");
 
-        let mut target_drawing = this
-            .as_drawing(activation.context.gc_context)
-            .expect("Missing drawing for target");
+            target_drawing.copy_from(&source);
+        }
 
-        target_drawing.copy_from(&source);
+        // The copied drawing refers to the same fill objects as the source.
+        let fills_name = Multiname::new(activation.avm2().flash_display_internal, "_fills");
+        let fills = match source_graphics.get_property(&fills_name, activation)? {
+            Value::Object(fills) => {
+                let storage = fills
+                    .as_array_storage()
+                    .map(|storage| storage.clone())
+                    .unwrap_or_else(|| ArrayStorage::new(0));
+                ArrayObject::from_storage(activation, storage)?.into()
+            }
+            _ => Value::Null,
+        };
+        this.set_property(&fills_name, fills, activation)?;
     }
     Ok(Value::Undefined)
 }
@@ -1051,143 +1158,105 @@ fn draw_triangles_internal<'gc>(
     uvt_data: Option<&Object<'gc>>,
     culling: TriangleCulling,
 ) -> Result<(), Error<'gc>> {
-    // FIXME Triangles should be drawn using non-zero winding rule.
-    //   When fixed, update output.expected.png of avm2/graphics_draw_triangles.
-    avm2_stub_method!(
-        activation,
-        "flash.display.Graphics",
-        "drawTriangles",
-        "winding behavior"
-    );
-
-    if uvt_data.is_some() {
-        avm2_stub_method!(
-            activation,
-            "flash.display.Graphics",
-            "drawTriangles",
-            "with uvt data"
-        );
-    }
-
     let vertices = vertices
         .as_vector_storage()
         .expect("vertices is not a Vector");
 
-    if let Some(indices) = indices {
-        if vertices.length() % 2 != 0 {
-            return Err(make_error_2004(activation, Error2004Type::ArgumentError));
-        }
+    if vertices.length() % 2 != 0 || (indices.is_none() && vertices.length() % 6 != 0) {
+        return Err(make_error_2004(activation, Error2004Type::ArgumentError));
+    }
+
+    let mut points = Vec::with_capacity(vertices.length() / 2);
+    let mut coordinates = vertices.iter();
+    while let (Some(x), Some(y)) = (coordinates.next(), coordinates.next()) {
+        let x = Twips::from_pixels(x.coerce_to_number(activation)?);
+        let y = Twips::from_pixels(y.coerce_to_number(activation)?);
+        points.push(Point::new(x, y));
+    }
 
+    // Without indices, every three vertices form a triangle.
+    let indices = if let Some(indices) = indices {
         let indices = indices
             .as_vector_storage()
             .expect("indices is not a Vector");
-
-        fn read_point<'gc>(
-            vertices: &VectorStorage<'gc>,
-            index: usize,
-            activation: &mut Activation<'_, 'gc>,
-        ) -> Result<Point<Twips>, Error<'gc>> {
-            let x = {
-                let x = vertices
-                    .get(2 * index, activation)?
-                    .coerce_to_number(activation)?;
-                Twips::from_pixels(x)
-            };
-            let y = {
-                let y = vertices
-                    .get(2 * index + 1, activation)?
-                    .coerce_to_number(activation)?;
-                Twips::from_pixels(y)
-            };
-
-            Ok(Point::new(x, y))
-        }
-
-        fn next_triangle<'gc>(
-            vertices: &VectorStorage<'gc>,
-            indices: &mut impl Iterator<Item = Value<'gc>>,
-            activation: &mut Activation<'_, 'gc>,
-        ) -> Option<Triangle> {
-            match (indices.next(), indices.next(), indices.next()) {
-                (Some(i0), Some(i1), Some(i2)) => {
-                    let i0 = i0.coerce_to_u32(activation).ok()? as usize;
-                    let i1 = i1.coerce_to_u32(activation).ok()? as usize;
-                    let i2 = i2.coerce_to_u32(activation).ok()? as usize;
-
-                    let p0 = read_point(vertices, i0, activation).ok()?;
-                    let p1 = read_point(vertices, i1, activation).ok()?;
-                    let p2 = read_point(vertices, i2, activation).ok()?;
-
-                    Some((p0, p1, p2))
-                }
-                _ => None,
-            }
+        let mut result = Vec::with_capacity(indices.length());
+        for index in indices.iter() {
+            result.push(index.coerce_to_u32(activation)? as usize);
         }
+        result
+    } else {
+        (0..points.len()).collect()
+    };
 
-        let indices = &mut indices.iter();
+    // Texture coordinates are either (u, v) or (u, v, t) for every vertex.
+    let uvts = if let Some(uvt_data) = uvt_data {
+        let uvt_data = uvt_data
+            .as_vector_storage()
+            .expect("uvtData is not a Vector");
+        let stride = if uvt_data.length() == points.len() * 2 {
+            2
+        } else if uvt_data.length() == points.len() * 3 {
+            3
+        } else {
+            return Err(make_error_2004(activation, Error2004Type::ArgumentError));
+        };
 
-        while let Some(triangle) = next_triangle(&vertices, indices, activation) {
-            draw_triangle_internal(triangle, drawing, culling);
+        let mut values = Vec::with_capacity(uvt_data.length());
+        for value in uvt_data.iter() {
+            values.push(value.coerce_to_number(activation)? as f32);
         }
+        let uvts: Vec<_> = values
+            .chunks_exact(stride)
+            .map(|uvt| (uvt[0], uvt[1], uvt.get(2).copied().unwrap_or(1.0)))
+            .collect();
+        Some(uvts)
     } else {
-        if vertices.length() % 6 != 0 {
-            return Err(make_error_2004(activation, Error2004Type::ArgumentError));
-        }
+        None
+    };
 
-        let mut vertices = vertices.iter();
-
-        fn read_point<'gc>(
-            vertices: &mut impl Iterator<Item = Value<'gc>>,
-            activation: &mut Activation<'_, 'gc>,
-        ) -> Result<Option<Point<Twips>>, Error<'gc>> {
-            let x = {
-                let x = vertices.next();
-                let x = match x {
-                    Some(x) => x.coerce_to_number(activation)?,
-                    None => return Ok(None),
-                };
-                Twips::from_pixels(x)
-            };
-            let y = {
-                let y = vertices.next();
-                let y = match y {
-                    Some(y) => y.coerce_to_number(activation)?,
-                    None => return Ok(None),
-                };
-                Twips::from_pixels(y)
-            };
+    // Texture coordinates only have a meaning with a bitmap fill.
+    let uvts = uvts.filter(|_| matches!(drawing.fill_style(), Some(FillStyle::Bitmap { .. })));
 
-            Ok(Some(Point::new(x, y)))
+    // Triangles are always filled using the non-zero winding rule.
+    drawing.set_fill_rule(Some(FillRule::NonZero));
+
+    let mut textured_vertices = Vec::new();
+    for triangle in indices.chunks_exact(3) {
+        let (Some(&a), Some(&b), Some(&c)) = (
+            points.get(triangle[0]),
+            points.get(triangle[1]),
+            points.get(triangle[2]),
+        ) else {
+            // Flash stops drawing at the first triangle referencing a missing vertex.
+            break;
+        };
+
+        if culling.cull((a, b, c)) {
+            continue;
         }
 
-        fn next_triangle<'gc>(
-            vertices: &mut impl Iterator<Item = Value<'gc>>,
-            activation: &mut Activation<'_, 'gc>,
-        ) -> Result<Option<Triangle>, Error<'gc>> {
-            match (
-                read_point(vertices, activation)?,
-                read_point(vertices, activation)?,
-                read_point(vertices, activation)?,
-            ) {
-                (Some(p0), Some(p1), Some(p2)) => Ok(Some((p0, p1, p2))),
-                _ => Ok(None),
+        if let Some(uvts) = &uvts {
+            for (&index, position) in triangle.iter().zip([a, b, c]) {
+                let (u, v, t) = uvts[index];
+                textured_vertices.push(TexturedVertex { position, u, v, t });
             }
+        } else {
+            draw_triangle_internal((a, b, c), drawing);
         }
+    }
 
-        while let Some(triangle) = next_triangle(&mut vertices, activation)? {
-            draw_triangle_internal(triangle, drawing, culling);
-        }
+    if !textured_vertices.is_empty() {
+        drawing.draw_textured_triangles(textured_vertices);
     }
 
+    // Reset winding rule after drawing the triangles.
+    drawing.set_fill_rule(None);
+
     Ok(())
 }
 
 #[inline]
-fn draw_triangle_internal((a, b, c): Triangle, drawing: &mut Drawing, culling: TriangleCulling) {
-    if culling.cull((a, b, c)) {
-        return;
-    }
-
+fn draw_triangle_internal((a, b, c): Triangle, drawing: &mut Drawing) {
     drawing.draw_command(DrawCommand::MoveTo(a));
 
     drawing.draw_command(DrawCommand::LineTo(b));
@@ -1207,13 +1276,13 @@ pub fn draw_graphics_data<'gc>(
     {
         //assert_eq!(vector.value_type(), Some(activation.avm2().classes().igraphicsdata));
 
-        let this = this.as_display_object().expect("Bad this");
+        let dobj = this.as_display_object().expect("Bad this");
 
-        if let Some(mut drawing) = this.as_drawing(activation.context.gc_context) {
+        if let Some(mut drawing) = dobj.as_drawing(activation.context.gc_context) {
             for elem in vector.iter() {
                 let obj = elem.coerce_to_object(activation)?;
 
-                handle_igraphics_data(activation, &mut drawing, &obj)?;
+                handle_igraphics_data(activation, this, &mut drawing, &obj)?;
             }
         };
     }
@@ -1227,9 +1296,9 @@ pub fn line_bitmap_style<'gc>(
     this: Object<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some(this) = this.as_display_object() {
-        let bitmap = args
-            .get_object(activation, 0, "bitmap")?
+    if let Some(dobj) = this.as_display_object() {
+        let bitmap_object = args.get_object(activation, 0, "bitmap")?;
+        let bitmap = bitmap_object
             .as_bitmap_data()
             .expect("Bitmap argument is ensured to be a BitmapData from actionscript");
         let matrix = if let Some(matrix) = args.try_get_object(activation, 1) {
@@ -1249,19 +1318,22 @@ pub fn line_bitmap_style<'gc>(
             width: bitmap.width() as u16,
             height: bitmap.height() as u16,
         };
-        let scale_matrix = Matrix::scale(
-            Fixed16::from_f64(bitmap.width as f64),
-            Fixed16::from_f64(bitmap.height as f64),
-        );
 
-        if let Some(mut draw) = this.as_drawing(activation.context.gc_context) {
+        let id = if let Some(mut draw) = dobj.as_drawing(activation.context.gc_context) {
             let id = draw.add_bitmap(bitmap);
             draw.set_line_fill_style(FillStyle::Bitmap {
                 id,
-                matrix: matrix * scale_matrix,
+                matrix: matrix * bitmap_scale_matrix(),
                 is_smoothed,
                 is_repeating,
             });
+            Some(id)
+        } else {
+            None
+        };
+
+        if let Some(id) = id {
+            store_fill_object(activation, this, id, bitmap_object)?;
         }
     }
 
@@ -1271,19 +1343,534 @@ pub fn line_bitmap_style<'gc>(
 /// Implements `Graphics.readGraphicsData`
 pub fn read_graphics_data<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_method!(activation, "flash.display.Graphics", "readGraphicsData");
+    let recurse = args.get_bool(0);
+
+    let mut graphics_data = Vec::new();
+    if let Some(dobj) = this.as_display_object() {
+        read_display_object_graphics(
+            activation,
+            dobj,
+            Some(this),
+            ruffle_render::matrix::Matrix::IDENTITY,
+            recurse,
+            &mut graphics_data,
+        )?;
+    }
+
     let value_type = activation
         .avm2()
         .classes()
         .igraphicsdata
         .inner_class_definition();
-    let new_storage = VectorStorage::new(0, false, Some(value_type), activation);
+    let new_storage = VectorStorage::from_values(graphics_data, false, Some(value_type));
     Ok(VectorObject::from_vector(new_storage, activation)?.into())
 }
 
+/// Where the `BitmapData` and `Shader` objects of bitmap fills are looked up.
+enum FillObjects<'gc> {
+    /// A drawing made through `Graphics`, with the `_fills` array of that `Graphics`.
+    Drawing(Option<Object<'gc>>),
+
+    /// A shape placed from the timeline, whose bitmap fills refer to library characters.
+    Library(Arc<SwfMovie>),
+}
+
+impl<'gc> FillObjects<'gc> {
+    fn get(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        id: u16,
+    ) -> Result<Option<Object<'gc>>, Error<'gc>> {
+        match self {
+            Self::Drawing(fills) => Ok(fills
+                .and_then(|fills| {
+                    fills
+                        .as_array_storage()
+                        .and_then(|fills| fills.get(id as usize))
+                })
+                .and_then(|fill| fill.as_object())),
+            Self::Library(movie) => {
+                let compressed = match activation
+                    .context
+                    .library
+                    .library_for_movie(movie.clone())
+                    .and_then(|library| library.character_by_id(id))
+                {
+                    Some(Character::Bitmap { compressed, .. }) => compressed.clone(),
+                    _ => return Ok(None),
+                };
+
+                let bitmap_data = fill_bitmap_data_from_symbol(activation, &compressed);
+                let class = activation.avm2().classes().bitmapdata;
+                let bitmap_data =
+                    BitmapDataObject::from_bitmap_data_internal(activation, bitmap_data, class)?;
+                Ok(Some(bitmap_data))
+            }
+        }
+    }
+}
+
+/// Append the graphics data of a display object (and, if `recurse` is set, its children)
+/// to `graphics_data`, with every coordinate transformed by `matrix`.
+fn read_display_object_graphics<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    dobj: DisplayObject<'gc>,
+    graphics: Option<Object<'gc>>,
+    matrix: ruffle_render::matrix::Matrix,
+    recurse: bool,
+    graphics_data: &mut Vec<Value<'gc>>,
+) -> Result<(), Error<'gc>> {
+    let static_shape = if let DisplayObject::Graphic(graphic) = dobj {
+        graphic.static_shape()
+    } else {
+        None
+    };
+
+    if let Some(shape) = static_shape {
+        let shape = DistilledShape::from(&shape);
+        let fill_objects = FillObjects::Library(dobj.movie());
+        read_paths(
+            activation,
+            &shape.paths,
+            &fill_objects,
+            matrix,
+            graphics_data,
+        )?;
+    } else if let Some(drawing) = dobj
+        .as_drawing(activation.context.gc_context)
+        .map(|drawing| drawing.clone())
+    {
+        let graphics = match graphics {
+            Some(graphics) => Some(graphics),
+            // Only `Shape` and `Sprite` have drawings, and both keep their `Graphics` here.
+            None => match dobj.object2() {
+                Value::Object(object) => object
+                    .get_property(
+                        &Multiname::new(activation.avm2().flash_display_internal, "_graphics"),
+                        activation,
+                    )?
+                    .as_object(),
+                _ => None,
+            },
+        };
+        let fills = match graphics {
+            Some(graphics) => graphics
+                .get_property(
+                    &Multiname::new(activation.avm2().flash_display_internal, "_fills"),
+                    activation,
+                )?
+                .as_object(),
+            None => None,
+        };
+
+        let fill_objects = FillObjects::Drawing(fills);
+        read_paths(
+            activation,
+            &drawing.paths(),
+            &fill_objects,
+            matrix,
+            graphics_data,
+        )?;
+    }
+
+    if recurse {
+        if let Some(container) = dobj.as_container() {
+            for child in container.iter_render_list() {
+                let child_matrix = matrix * *child.base().matrix();
+                read_display_object_graphics(
+                    activation,
+                    child,
+                    None,
+                    child_matrix,
+                    recurse,
+                    graphics_data,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert drawing paths into `IGraphicsData` objects that draw them again
+/// when passed to `drawGraphicsData`.
+fn read_paths<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    paths: &[DrawPath],
+    fill_objects: &FillObjects<'gc>,
+    matrix: ruffle_render::matrix::Matrix,
+    graphics_data: &mut Vec<Value<'gc>>,
+) -> Result<(), Error<'gc>> {
+    for path in paths {
+        match path {
+            DrawPath::Fill {
+                style,
+                commands,
+                winding_rule,
+            } => {
+                let commands = drawn_commands(commands);
+                if commands.is_empty() {
+                    continue;
+                }
+
+                // Fills whose bitmap is gone can't be drawn again, so they're left out.
+                let Some(fill) = read_fill_style(activation, style, fill_objects, matrix)? else {
+                    continue;
+                };
+
+                graphics_data.push(fill);
+                graphics_data.push(read_commands(activation, &commands, *winding_rule, matrix)?);
+                graphics_data.push(
+                    activation
+                        .avm2()
+                        .classes()
+                        .graphicsendfill
+                        .construct(activation, &[])?
+                        .into(),
+                );
+            }
+            DrawPath::Stroke {
+                style, commands, ..
+            } => {
+                let commands = drawn_commands(commands);
+                if commands.is_empty() {
+                    continue;
+                }
+
+                let Some(stroke) = read_line_style(activation, style, fill_objects, matrix)? else {
+                    continue;
+                };
+
+                graphics_data.push(stroke);
+                graphics_data.push(read_commands(
+                    activation,
+                    &commands,
+                    FillRule::EvenOdd,
+                    matrix,
+                )?);
+                // A stroke with the default `NaN` thickness ends the line style.
+                graphics_data.push(
+                    activation
+                        .avm2()
+                        .classes()
+                        .graphicsstroke
+                        .construct(activation, &[])?
+                        .into(),
+                );
+            }
+            DrawPath::TexturedTriangles { style, vertices } => {
+                let Some(fill) = read_fill_style(activation, style, fill_objects, matrix)? else {
+                    continue;
+                };
+
+                let mut positions = Vec::with_capacity(vertices.len() * 2);
+                let mut uvt_data = Vec::with_capacity(vertices.len() * 3);
+                for vertex in vertices {
+                    let position = matrix * vertex.position;
+                    positions.push(position.x.to_pixels().into());
+                    positions.push(position.y.to_pixels().into());
+                    uvt_data.push(vertex.u.into());
+                    uvt_data.push(vertex.v.into());
+                    uvt_data.push(vertex.t.into());
+                }
+
+                let number = activation.avm2().classes().number.inner_class_definition();
+                let positions = VectorStorage::from_values(positions, false, Some(number));
+                let positions = VectorObject::from_vector(positions, activation)?;
+                let uvt_data = VectorStorage::from_values(uvt_data, false, Some(number));
+                let uvt_data = VectorObject::from_vector(uvt_data, activation)?;
+
+                graphics_data.push(fill);
+                graphics_data.push(
+                    activation
+                        .avm2()
+                        .classes()
+                        .graphicstrianglepath
+                        .construct(
+                            activation,
+                            &[
+                                positions.into(),
+                                Value::Null,
+                                uvt_data.into(),
+                                "none".into(),
+                            ],
+                        )?
+                        .into(),
+                );
+                graphics_data.push(
+                    activation
+                        .avm2()
+                        .classes()
+                        .graphicsendfill
+                        .construct(activation, &[])?
+                        .into(),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Drop the moves that aren't followed by anything being drawn, such as the move
+/// to the pen position that paths of a drawing start with.
+fn drawn_commands(commands: &[DrawCommand]) -> Vec<DrawCommand> {
+    let mut drawn = Vec::with_capacity(commands.len());
+    for command in commands {
+        if let (Some(DrawCommand::MoveTo(_)), DrawCommand::MoveTo(_)) = (drawn.last(), command) {
+            drawn.pop();
+        }
+        drawn.push(command.clone());
+    }
+    if let Some(DrawCommand::MoveTo(_)) = drawn.last() {
+        drawn.pop();
+    }
+    drawn
+}
+
+/// Convert draw commands into a `GraphicsPath`.
+fn read_commands<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    commands: &[DrawCommand],
+    winding_rule: FillRule,
+    matrix: ruffle_render::matrix::Matrix,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut command_values = Vec::with_capacity(commands.len());
+    let mut data = Vec::with_capacity(commands.len() * 2);
+    let mut push_point = |point: Point<Twips>| {
+        let point = matrix * point;
+        data.push(point.x.to_pixels().into());
+        data.push(point.y.to_pixels().into());
+    };
+
+    for command in commands {
+        match *command {
+            DrawCommand::MoveTo(point) => {
+                // MOVE_TO
+                command_values.push(1.into());
+                push_point(point);
+            }
+            DrawCommand::LineTo(point) => {
+                // LINE_TO
+                command_values.push(2.into());
+                push_point(point);
+            }
+            DrawCommand::QuadraticCurveTo { control, anchor } => {
+                // CURVE_TO
+                command_values.push(3.into());
+                push_point(control);
+                push_point(anchor);
+            }
+            DrawCommand::CubicCurveTo {
+                control_a,
+                control_b,
+                anchor,
+            } => {
+                // CUBIC_CURVE_TO
+                command_values.push(6.into());
+                push_point(control_a);
+                push_point(control_b);
+                push_point(anchor);
+            }
+        }
+    }
+
+    let int = activation.avm2().classes().int.inner_class_definition();
+    let command_values = VectorStorage::from_values(command_values, false, Some(int));
+    let command_values = VectorObject::from_vector(command_values, activation)?;
+    let number = activation.avm2().classes().number.inner_class_definition();
+    let data = VectorStorage::from_values(data, false, Some(number));
+    let data = VectorObject::from_vector(data, activation)?;
+    let winding = match winding_rule {
+        FillRule::EvenOdd => "evenOdd",
+        FillRule::NonZero => "nonZero",
+    };
+
+    Ok(activation
+        .avm2()
+        .classes()
+        .graphicspath
+        .construct(
+            activation,
+            &[command_values.into(), data.into(), winding.into()],
+        )?
+        .into())
+}
+
+/// Convert a line style into a `GraphicsStroke`.
+fn read_line_style<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    style: &LineStyle,
+    fill_objects: &FillObjects<'gc>,
+    matrix: ruffle_render::matrix::Matrix,
+) -> Result<Option<Value<'gc>>, Error<'gc>> {
+    let Some(fill) = read_fill_style(activation, style.fill_style(), fill_objects, matrix)? else {
+        return Ok(None);
+    };
+
+    let scale_mode = match (style.allow_scale_x(), style.allow_scale_y()) {
+        (true, true) => "normal",
+        (true, false) => "horizontal",
+        (false, true) => "vertical",
+        (false, false) => "none",
+    };
+    let caps = match style.start_cap() {
+        LineCapStyle::None => "none",
+        LineCapStyle::Round => "round",
+        LineCapStyle::Square => "square",
+    };
+    let (joints, miter_limit) = match style.join_style() {
+        LineJoinStyle::Round => ("round", 3.0),
+        LineJoinStyle::Bevel => ("bevel", 3.0),
+        LineJoinStyle::Miter(miter_limit) => ("miter", miter_limit.to_f64()),
+    };
+
+    let args = [
+        style.width().to_pixels().into(),
+        style.is_pixel_hinted().into(),
+        scale_mode.into(),
+        caps.into(),
+        joints.into(),
+        miter_limit.into(),
+        fill,
+    ];
+    Ok(Some(
+        activation
+            .avm2()
+            .classes()
+            .graphicsstroke
+            .construct(activation, &args)?
+            .into(),
+    ))
+}
+
+/// Convert a fill style into an `IGraphicsFill`, or `None` if its bitmap can't be found.
+fn read_fill_style<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    style: &FillStyle,
+    fill_objects: &FillObjects<'gc>,
+    matrix: ruffle_render::matrix::Matrix,
+) -> Result<Option<Value<'gc>>, Error<'gc>> {
+    let fill = match style {
+        FillStyle::Color(color) => {
+            let args = [color.to_rgb().into(), (color.a as f64 / 255.0).into()];
+            activation
+                .avm2()
+                .classes()
+                .graphicssolidfill
+                .construct(activation, &args)?
+        }
+        FillStyle::LinearGradient(gradient) => {
+            read_gradient(activation, "linear", gradient, 0.0, matrix)?
+        }
+        FillStyle::RadialGradient(gradient) => {
+            read_gradient(activation, "radial", gradient, 0.0, matrix)?
+        }
+        FillStyle::FocalGradient {
+            gradient,
+            focal_point,
+        } => read_gradient(activation, "radial", gradient, focal_point.to_f64(), matrix)?,
+        FillStyle::Bitmap {
+            id,
+            matrix: bitmap_matrix,
+            is_smoothed,
+            is_repeating,
+        } => {
+            let Some(fill_object) = fill_objects.get(activation, *id)? else {
+                return Ok(None);
+            };
+
+            // Undo the scaling to twips that `beginBitmapFill` applies.
+            let bitmap_matrix = matrix
+                * ruffle_render::matrix::Matrix::from(*bitmap_matrix)
+                * ruffle_render::matrix::Matrix::TWIPS_TO_PIXELS;
+            let bitmap_matrix = matrix_to_object(bitmap_matrix, activation)?;
+
+            if fill_object.as_bitmap_data().is_some() {
+                let args = [
+                    fill_object.into(),
+                    bitmap_matrix,
+                    (*is_repeating).into(),
+                    (*is_smoothed).into(),
+                ];
+                activation
+                    .avm2()
+                    .classes()
+                    .graphicsbitmapfill
+                    .construct(activation, &args)?
+            } else {
+                let args = [fill_object.into(), bitmap_matrix];
+                activation
+                    .avm2()
+                    .classes()
+                    .graphicsshaderfill
+                    .construct(activation, &args)?
+            }
+        }
+    };
+
+    Ok(Some(fill.into()))
+}
+
+/// Convert a gradient into a `GraphicsGradientFill`.
+fn read_gradient<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    gradient_type: &'static str,
+    gradient: &Gradient,
+    focal_point: f64,
+    matrix: ruffle_render::matrix::Matrix,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let colors: Vec<Value<'gc>> = gradient
+        .records
+        .iter()
+        .map(|record| record.color.to_rgb().into())
+        .collect();
+    let alphas: Vec<Value<'gc>> = gradient
+        .records
+        .iter()
+        .map(|record| (record.color.a as f64 / 255.0).into())
+        .collect();
+    let ratios: Vec<Value<'gc>> = gradient
+        .records
+        .iter()
+        .map(|record| record.ratio.into())
+        .collect();
+
+    let colors = ArrayObject::from_storage(activation, ArrayStorage::from_args(&colors))?;
+    let alphas = ArrayObject::from_storage(activation, ArrayStorage::from_args(&alphas))?;
+    let ratios = ArrayObject::from_storage(activation, ArrayStorage::from_args(&ratios))?;
+    let gradient_matrix = matrix * ruffle_render::matrix::Matrix::from(gradient.matrix);
+    let gradient_matrix = matrix_to_object(gradient_matrix, activation)?;
+    let spread_method = match gradient.spread {
+        GradientSpread::Pad => "pad",
+        GradientSpread::Reflect => "reflect",
+        GradientSpread::Repeat => "repeat",
+    };
+    let interpolation_method = match gradient.interpolation {
+        GradientInterpolation::Rgb => "rgb",
+        GradientInterpolation::LinearRgb => "linearRGB",
+    };
+
+    let args = [
+        gradient_type.into(),
+        colors.into(),
+        alphas.into(),
+        ratios.into(),
+        gradient_matrix,
+        spread_method.into(),
+        interpolation_method.into(),
+        focal_point.into(),
+    ];
+    activation
+        .avm2()
+        .classes()
+        .graphicsgradientfill
+        .construct(activation, &args)
+}
+
 fn read_point<'gc>(
     activation: &mut Activation<'_, 'gc>,
     data: &VectorStorage<'gc>,
@@ -1417,6 +2004,7 @@ fn process_commands<'gc>(
 
 fn handle_igraphics_data<'gc>(
     activation: &mut Activation<'_, 'gc>,
+    graphics: Object<'gc>,
     drawing: &mut Drawing,
     obj: &Object<'gc>,
 ) -> Result<(), Error<'gc>> {
@@ -1429,7 +2017,7 @@ fn handle_igraphics_data<'gc>(
             .graphicsbitmapfill
             .inner_class_definition()
     {
-        let style = handle_bitmap_fill(activation, drawing, obj)?;
+        let style = handle_bitmap_fill(activation, graphics, drawing, obj)?;
         drawing.set_fill_style(Some(style));
     } else if class
         == activation
@@ -1492,8 +2080,8 @@ fn handle_igraphics_data<'gc>(
             .graphicsshaderfill
             .inner_class_definition()
     {
-        tracing::warn!("Graphics shader fill unimplemented {:?}", class);
-        drawing.set_fill_style(None);
+        let style = handle_shader_fill(activation, graphics, drawing, obj)?;
+        drawing.set_fill_style(style);
     } else if class
         == activation
             .avm2()
@@ -1519,7 +2107,7 @@ fn handle_igraphics_data<'gc>(
                     .get_public_property("fill", activation)?
                     .coerce_to_object(activation)?;
 
-                handle_igraphics_fill(activation, drawing, &fill)?
+                handle_igraphics_fill(activation, graphics, drawing, &fill)?
             };
 
             let joints = obj
@@ -1605,6 +2193,7 @@ fn handle_graphics_triangle_path<'gc>(
 
 fn handle_igraphics_fill<'gc>(
     activation: &mut Activation<'_, 'gc>,
+    graphics: Object<'gc>,
     drawing: &mut Drawing,
     obj: &Object<'gc>,
 ) -> Result<Option<FillStyle>, Error<'gc>> {
@@ -1617,7 +2206,7 @@ fn handle_igraphics_fill<'gc>(
             .graphicsbitmapfill
             .inner_class_definition()
     {
-        let style = handle_bitmap_fill(activation, drawing, obj)?;
+        let style = handle_bitmap_fill(activation, graphics, drawing, obj)?;
         Ok(Some(style))
     } else if class
         == activation
@@ -1652,14 +2241,39 @@ fn handle_igraphics_fill<'gc>(
             .graphicsshaderfill
             .inner_class_definition()
     {
-        tracing::warn!("Graphics shader fill unimplemented {:?}", class);
-        Ok(None)
+        handle_shader_fill(activation, graphics, drawing, obj)
     } else {
         tracing::warn!("Unknown graphics fill class {:?}", class);
         Ok(None)
     }
 }
 
+fn handle_shader_fill<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    graphics: Object<'gc>,
+    drawing: &mut Drawing,
+    obj: &Object<'gc>,
+) -> Result<Option<FillStyle>, Error<'gc>> {
+    let Some(shader) = obj.get_public_property("shader", activation)?.as_object() else {
+        // A fill without a shader doesn't draw anything.
+        return Ok(None);
+    };
+
+    let matrix = match obj.get_public_property("matrix", activation)?.as_object() {
+        Some(matrix) => object_to_matrix(matrix, activation)?,
+        None => ruffle_render::matrix::Matrix::IDENTITY,
+    };
+
+    let (shader_handle, arguments) = get_shader_args(shader, activation)?;
+
+    let style = drawing.add_shader_fill(shader_handle, arguments, matrix);
+    if let Some(id) = bitmap_fill_id(&style) {
+        store_fill_object(activation, graphics, id, shader)?;
+    }
+
+    Ok(Some(style))
+}
+
 fn handle_solid_fill<'gc>(
     activation: &mut Activation<'_, 'gc>,
     obj: &Object<'gc>,
@@ -1768,12 +2382,14 @@ fn handle_gradient_fill<'gc>(
 
 fn handle_bitmap_fill<'gc>(
     activation: &mut Activation<'_, 'gc>,
+    graphics: Object<'gc>,
     drawing: &mut Drawing,
     obj: &Object<'gc>,
 ) -> Result<FillStyle, Error<'gc>> {
-    let bitmap_data = obj
+    let bitmap_object = obj
         .get_public_property("bitmapData", activation)?
-        .coerce_to_object(activation)?
+        .coerce_to_object(activation)?;
+    let bitmap_data = bitmap_object
         .as_bitmap_data()
         .expect("Bitmap argument is ensured to be a BitmapData from actionscript");
 
@@ -1805,16 +2421,12 @@ fn handle_bitmap_fill<'gc>(
         height: bitmap_data.height() as u16,
     };
 
-    let scale_matrix = Matrix::scale(
-        Fixed16::from_f64(bitmap.width as f64),
-        Fixed16::from_f64(bitmap.height as f64),
-    );
-
     let id = drawing.add_bitmap(bitmap);
+    store_fill_object(activation, graphics, id, bitmap_object)?;
 
     let style = FillStyle::Bitmap {
         id,
-        matrix: matrix * scale_matrix,
+        matrix: matrix * bitmap_scale_matrix(),
         is_smoothed,
         is_repeating,
     };
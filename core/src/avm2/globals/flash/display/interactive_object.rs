@@ -118,6 +118,39 @@ pub fn set_context_menu<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `InteractiveObject.accessibilityImplementation`'s getter.
+pub fn get_accessibility_implementation<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(int) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_interactive())
+    {
+        return Ok(int.accessibility_implementation());
+    }
+
+    Ok(Value::Null)
+}
+
+/// Implements `InteractiveObject.accessibilityImplementation`'s setter.
+pub fn set_accessibility_implementation<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(int) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_interactive())
+    {
+        let value = args.get_value(0);
+        int.set_accessibility_implementation(activation.context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn get_tab_enabled<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Object<'gc>,
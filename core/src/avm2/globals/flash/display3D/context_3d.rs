@@ -7,8 +7,8 @@ use crate::avm2::{Error, Object};
 use crate::avm2_stub_method;
 use ruffle_render::backend::Context3DWrapMode;
 use ruffle_render::backend::{
-    BufferUsage, Context3DBlendFactor, Context3DCompareMode, Context3DTextureFormat,
-    Context3DTriangleFace, Context3DVertexBufferFormat, ProgramType,
+    BufferUsage, Context3DBlendFactor, Context3DCompareMode, Context3DStencilAction,
+    Context3DTextureFormat, Context3DTriangleFace, Context3DVertexBufferFormat, ProgramType,
 };
 use ruffle_render::backend::{Context3DProfile, Context3DTextureFilter};
 use swf::{Rectangle, Twips};
@@ -560,6 +560,56 @@ pub fn set_depth_test<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn set_stencil_actions<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.as_context_3d() {
+        let triangle_face = args.get_string(activation, 0)?;
+        let compare_mode = args.get_string(activation, 1)?;
+        let action_on_both_pass = args.get_string(activation, 2)?;
+        let action_on_depth_fail = args.get_string(activation, 3)?;
+        let action_on_depth_pass_stencil_fail = args.get_string(activation, 4)?;
+
+        let face = Context3DTriangleFace::from_wstr(&triangle_face)
+            .ok_or_else(|| make_error_2008(activation, "triangleFace"))?;
+        let compare_mode = Context3DCompareMode::from_wstr(&compare_mode)
+            .ok_or_else(|| make_error_2008(activation, "compareMode"))?;
+        let action_on_both_pass = Context3DStencilAction::from_wstr(&action_on_both_pass)
+            .ok_or_else(|| make_error_2008(activation, "actionOnBothPass"))?;
+        let action_on_depth_fail = Context3DStencilAction::from_wstr(&action_on_depth_fail)
+            .ok_or_else(|| make_error_2008(activation, "actionOnDepthFail"))?;
+        let action_on_depth_pass_stencil_fail =
+            Context3DStencilAction::from_wstr(&action_on_depth_pass_stencil_fail)
+                .ok_or_else(|| make_error_2008(activation, "actionOnDepthPassStencilFail"))?;
+
+        context.set_stencil_actions(
+            face,
+            compare_mode,
+            action_on_both_pass,
+            action_on_depth_fail,
+            action_on_depth_pass_stencil_fail,
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_stencil_reference_value<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(context) = this.as_context_3d() {
+        // The stencil buffer only has 8 bits, so everything above that is ignored.
+        let reference_value = args.get_u32(activation, 0)? & 0xFF;
+        let read_mask = args.get_u32(activation, 1)? & 0xFF;
+        let write_mask = args.get_u32(activation, 2)? & 0xFF;
+        context.set_stencil_reference_value(reference_value, read_mask, write_mask);
+    }
+    Ok(Value::Undefined)
+}
+
 pub fn set_blend_factors<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Object<'gc>,
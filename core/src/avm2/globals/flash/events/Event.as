@@ -89,6 +89,15 @@ package flash.events {
 		[API("682")]
 		public static const WORKER_STATE:String = "workerState";
 
+		[API("668")]
+		public static const STANDARD_ERROR_CLOSE:String = "standardErrorClose";
+
+		[API("668")]
+		public static const STANDARD_INPUT_CLOSE:String = "standardInputClose";
+
+		[API("668")]
+		public static const STANDARD_OUTPUT_CLOSE:String = "standardOutputClose";
+
 		public function Event(type:String, bubbles:Boolean = false, cancelable:Boolean = false) {
 			this.init(type, bubbles, cancelable);
 		}
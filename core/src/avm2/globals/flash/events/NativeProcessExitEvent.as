@@ -0,0 +1,21 @@
+package flash.events {
+    [API("668")]
+    public class NativeProcessExitEvent extends Event {
+        public static const EXIT:String = "exit";
+
+        public var exitCode:Number;
+
+        public function NativeProcessExitEvent(type:String, bubbles:Boolean = false, cancelable:Boolean = false, exitCode:Number = NaN) {
+            super(type, bubbles, cancelable);
+            this.exitCode = exitCode;
+        }
+
+        override public function clone():Event {
+            return new NativeProcessExitEvent(this.type, this.bubbles, this.cancelable, this.exitCode);
+        }
+
+        override public function toString():String {
+            return this.formatToString("NativeProcessExitEvent", "type", "bubbles", "cancelable", "eventPhase", "exitCode");
+        }
+    }
+}
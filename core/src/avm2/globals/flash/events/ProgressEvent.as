@@ -3,6 +3,13 @@ package flash.events {
         public static const PROGRESS:String = "progress";
         public static const SOCKET_DATA:String = "socketData";
 
+        [API("668")]
+        public static const STANDARD_ERROR_DATA:String = "standardErrorData";
+        [API("668")]
+        public static const STANDARD_INPUT_PROGRESS:String = "standardInputProgress";
+        [API("668")]
+        public static const STANDARD_OUTPUT_DATA:String = "standardOutputData";
+
         public var bytesLoaded:Number;
         public var bytesTotal:Number;
 
@@ -1,36 +1,256 @@
 package flash.filesystem {
-    import __ruffle__.stub_constructor;
-    import __ruffle__.stub_getter;
-    import __ruffle__.stub_method;
-
+    import flash.errors.IOError;
     import flash.net.FileReference;
+    import flash.utils.ByteArray;
 
     [API("661")]
     public class File extends FileReference {
         private static var _applicationDirectory:File = null;
+        private static var _applicationStorageDirectory:File = null;
+
+        // The sandboxed URL of this file (`app:/...` or `app-storage:/...`),
+        // or null if it's outside of the sandbox.
+        private var _url:String = null;
+
+        // The path this file was created with, if it's outside of the sandbox.
+        private var _path:String = null;
 
         public function File(path:String = null) {
-            stub_constructor("flash.filesystem.File");
+            if (path !== null) {
+                this.nativePath = path;
+            }
+        }
+
+        private static native function normalizeUrl(path:String):String;
+        private static native function resolveUrl(url:String, path:String):String;
+        private static native function getNativePath(url:String):String;
+        private static native function getMetadata(url:String):Object;
+        private static native function listDirectory(url:String):Array;
+        internal static native function readFile(url:String):ByteArray;
+        internal static native function writeFile(url:String, data:ByteArray):void;
+        private static native function createDirectoryAt(url:String):void;
+        private static native function deleteAt(url:String, recursive:Boolean):void;
+        private static native function moveAt(from:String, to:String):void;
+
+        private static function fromUrl(url:String):File {
+            var file:File = new File();
+            file._url = url;
+            return file;
         }
 
         public static function get applicationDirectory():File {
             if (_applicationDirectory === null) {
-                _applicationDirectory = new File();
+                _applicationDirectory = fromUrl("app:/");
             }
 
             return _applicationDirectory;
         }
 
-        public function resolvePath(path:String):File {
-            stub_method("flash.filesystem.File", "resolvePath");
+        public static function get applicationStorageDirectory():File {
+            if (_applicationStorageDirectory === null) {
+                _applicationStorageDirectory = fromUrl("app-storage:/");
+            }
+
+            return _applicationStorageDirectory;
+        }
+
+        public static function get separator():String {
+            return "/";
+        }
+
+        public static function get lineEnding():String {
+            return "\n";
+        }
+
+        public static function get systemCharset():String {
+            return "utf-8";
+        }
+
+        internal function get sandboxedUrl():String {
+            if (this._url === null) {
+                throw new SecurityError("Error #3001: File or directory access denied.", 3001);
+            }
+            return this._url;
+        }
+
+        public function get url():String {
+            return this._url !== null ? this._url : this._path;
+        }
 
-            return new File();
+        public function set url(value:String):void {
+            this.nativePath = value;
+        }
+
+        public function get nativePath():String {
+            if (this._url === null) {
+                return this._path;
+            }
+            var path:String = getNativePath(this._url);
+            return path !== null ? path : this._url;
+        }
+
+        public function set nativePath(value:String):void {
+            this._url = normalizeUrl(value);
+            this._path = this._url === null ? value : null;
+        }
+
+        override public function get name():String {
+            var path:String = this.nativePath;
+            if (path === null) {
+                return null;
+            }
+            var parts:Array = path.split("\\").join("/").split("/");
+            while (parts.length > 1 && parts[parts.length - 1] == "") {
+                parts.pop();
+            }
+            return parts[parts.length - 1];
+        }
+
+        override public function get type():String {
+            var name:String = this.name;
+            var dot:int = name !== null ? name.lastIndexOf(".") : -1;
+            if (dot <= 0 || this.isDirectory) {
+                return null;
+            }
+            return name.substr(dot);
+        }
+
+        private function get metadata():Object {
+            if (this._url === null) {
+                return null;
+            }
+            return getMetadata(this._url);
+        }
+
+        private function get existingMetadata():Object {
+            var metadata:Object = this.metadata;
+            if (metadata === null) {
+                throw new IOError("Error #3003: File or directory does not exist.", 3003);
+            }
+            return metadata;
         }
 
         public function get exists():Boolean {
-            stub_getter("flash.filesystem.File", "exists");
+            return this.metadata !== null;
+        }
 
+        public function get isDirectory():Boolean {
+            var metadata:Object = this.metadata;
+            return metadata !== null && metadata.isDirectory;
+        }
+
+        public function get isHidden():Boolean {
+            var name:String = this.name;
+            return name !== null && name.charAt(0) == ".";
+        }
+
+        public function get isPackage():Boolean {
+            return false;
+        }
+
+        public function get isSymbolicLink():Boolean {
             return false;
         }
+
+        override public function get size():Number {
+            return this.existingMetadata.size;
+        }
+
+        override public function get creationDate():Date {
+            return this.existingMetadata.creationDate;
+        }
+
+        override public function get modificationDate():Date {
+            return this.existingMetadata.modificationDate;
+        }
+
+        public function get parent():File {
+            if (this._url === null) {
+                return null;
+            }
+            var parentUrl:String = resolveUrl(this._url, "..");
+            return parentUrl !== null ? fromUrl(parentUrl) : null;
+        }
+
+        public function resolvePath(path:String):File {
+            if (this._url === null) {
+                return new File(this._path + "/" + path);
+            }
+            var url:String = resolveUrl(this._url, path);
+            return url !== null ? fromUrl(url) : new File(path);
+        }
+
+        public function clone():File {
+            var file:File = new File();
+            file._url = this._url;
+            file._path = this._path;
+            return file;
+        }
+
+        public function canonicalize():void {
+        }
+
+        public function getDirectoryListing():Array {
+            var url:String = this.sandboxedUrl;
+            return listDirectory(url).map(function(name:String, index:int, array:Array):File {
+                return fromUrl(resolveUrl(url, name));
+            });
+        }
+
+        public function createDirectory():void {
+            createDirectoryAt(this.sandboxedUrl);
+        }
+
+        public function deleteFile():void {
+            if (this.existingMetadata.isDirectory) {
+                throw new IOError("Error #3001: File or directory access denied.", 3001);
+            }
+            deleteAt(this.sandboxedUrl, false);
+        }
+
+        public function deleteDirectory(deleteDirectoryContents:Boolean = false):void {
+            if (!this.existingMetadata.isDirectory) {
+                throw new IOError("Error #3007: Cannot delete file or directory.", 3007);
+            }
+            deleteAt(this.sandboxedUrl, deleteDirectoryContents);
+        }
+
+        public function copyTo(newLocation:FileReference, overwrite:Boolean = false):void {
+            var destination:File = prepareDestination(newLocation, overwrite);
+            copyRecursive(this, destination);
+        }
+
+        public function moveTo(newLocation:FileReference, overwrite:Boolean = false):void {
+            var destination:File = prepareDestination(newLocation, overwrite);
+            moveAt(this.sandboxedUrl, destination.sandboxedUrl);
+        }
+
+        private function prepareDestination(newLocation:FileReference, overwrite:Boolean):File {
+            var destination:File = newLocation as File;
+            if (destination === null) {
+                throw new ArgumentError("Error #2004: One of the parameters is invalid.", 2004);
+            }
+            if (!this.exists) {
+                throw new IOError("Error #3003: File or directory does not exist.", 3003);
+            }
+            if (destination.exists) {
+                if (!overwrite) {
+                    throw new IOError("Error #3002: File or directory exists.", 3002);
+                }
+                deleteAt(destination.sandboxedUrl, true);
+            }
+            return destination;
+        }
+
+        private static function copyRecursive(source:File, destination:File):void {
+            if (source.isDirectory) {
+                destination.createDirectory();
+                for each (var child:File in source.getDirectoryListing()) {
+                    copyRecursive(child, destination.resolvePath(child.name));
+                }
+            } else {
+                writeFile(destination.sandboxedUrl, readFile(source.sandboxedUrl));
+            }
+        }
     }
 }
@@ -0,0 +1,250 @@
+package flash.filesystem {
+    import flash.errors.IOError;
+    import flash.events.Event;
+    import flash.events.EventDispatcher;
+    import flash.events.IOErrorEvent;
+    import flash.events.ProgressEvent;
+    import flash.utils.ByteArray;
+    import flash.utils.Endian;
+    import flash.utils.IDataInput;
+    import flash.utils.IDataOutput;
+    import flash.utils.setTimeout;
+    import __ruffle__.stub_getter;
+    import __ruffle__.stub_setter;
+
+    // The whole file is held in memory while it's open, and written back
+    // to the filesystem after every write, as AIR's synchronous API expects.
+    [API("661")]
+    public class FileStream extends EventDispatcher implements IDataInput, IDataOutput {
+        private var _file:File = null;
+        private var _mode:String = null;
+        private var _buffer:ByteArray = null;
+        private var _endian:String = Endian.BIG_ENDIAN;
+        private var _objectEncoding:uint = 3;
+
+        public function FileStream() {
+        }
+
+        public function open(file:File, fileMode:String):void {
+            this.close();
+
+            var url:String = file.sandboxedUrl;
+            var buffer:ByteArray;
+            if (fileMode == FileMode.WRITE) {
+                buffer = new ByteArray();
+                File.writeFile(url, buffer);
+            } else if (fileMode == FileMode.READ) {
+                buffer = File.readFile(url);
+            } else if (fileMode == FileMode.APPEND || fileMode == FileMode.UPDATE) {
+                buffer = file.exists ? File.readFile(url) : new ByteArray();
+                if (!file.exists) {
+                    File.writeFile(url, buffer);
+                }
+            } else {
+                throw new ArgumentError("Error #2008: Parameter fileMode must be one of the accepted values.", 2008);
+            }
+
+            buffer.endian = this._endian;
+            buffer.objectEncoding = this._objectEncoding;
+            buffer.position = fileMode == FileMode.APPEND ? buffer.length : 0;
+
+            this._file = file.clone();
+            this._mode = fileMode;
+            this._buffer = buffer;
+        }
+
+        public function openAsync(file:File, fileMode:String):void {
+            var self:FileStream = this;
+            try {
+                this.open(file, fileMode);
+            } catch (e:Error) {
+                setTimeout(function():void {
+                    self.dispatchEvent(new IOErrorEvent(IOErrorEvent.IO_ERROR, false, false, e.message, e.errorID));
+                }, 0);
+                return;
+            }
+
+            var buffer:ByteArray = this._buffer;
+            setTimeout(function():void {
+                if (self._buffer !== buffer || fileMode == FileMode.WRITE || fileMode == FileMode.APPEND) {
+                    return;
+                }
+                self.dispatchEvent(new ProgressEvent(ProgressEvent.PROGRESS, false, false, buffer.length, buffer.length));
+                self.dispatchEvent(new Event(Event.COMPLETE));
+            }, 0);
+        }
+
+        public function close():void {
+            this._file = null;
+            this._mode = null;
+            this._buffer = null;
+        }
+
+        private function get readBuffer():ByteArray {
+            if (this._buffer === null || this._mode == FileMode.WRITE || this._mode == FileMode.APPEND) {
+                throw new IOError("Error #2029: This FileStream object does not have a stream opened.", 2029);
+            }
+            return this._buffer;
+        }
+
+        private function get writeBuffer():ByteArray {
+            if (this._buffer === null || this._mode == FileMode.READ) {
+                throw new IOError("Error #2029: This FileStream object does not have a stream opened.", 2029);
+            }
+            if (this._mode == FileMode.APPEND) {
+                this._buffer.position = this._buffer.length;
+            }
+            return this._buffer;
+        }
+
+        private function flush():void {
+            File.writeFile(this._file.sandboxedUrl, this._buffer);
+        }
+
+        public function get bytesAvailable():uint {
+            return this._buffer !== null ? this._buffer.bytesAvailable : 0;
+        }
+
+        public function get position():Number {
+            return this._buffer !== null ? this._buffer.position : 0;
+        }
+
+        public function set position(value:Number):void {
+            if (this._buffer !== null) {
+                this._buffer.position = value;
+            }
+        }
+
+        public function get readAhead():Number {
+            stub_getter("flash.filesystem.FileStream", "readAhead");
+            return Number.POSITIVE_INFINITY;
+        }
+
+        public function set readAhead(value:Number):void {
+            stub_setter("flash.filesystem.FileStream", "readAhead");
+        }
+
+        public function get endian():String {
+            return this._endian;
+        }
+
+        public function set endian(value:String):void {
+            if (value !== Endian.BIG_ENDIAN && value !== Endian.LITTLE_ENDIAN) {
+                throw new ArgumentError("Error #2008: Parameter endian must be one of the accepted values.", 2008);
+            }
+            this._endian = value;
+            if (this._buffer !== null) {
+                this._buffer.endian = value;
+            }
+        }
+
+        public function get objectEncoding():uint {
+            return this._objectEncoding;
+        }
+
+        public function set objectEncoding(value:uint):void {
+            this._objectEncoding = value;
+            if (this._buffer !== null) {
+                this._buffer.objectEncoding = value;
+            }
+        }
+
+        public function truncate():void {
+            var buffer:ByteArray = this.writeBuffer;
+            buffer.length = buffer.position;
+            this.flush();
+        }
+
+        public function readBoolean():Boolean {
+            return this.readBuffer.readBoolean();
+        }
+        public function readByte():int {
+            return this.readBuffer.readByte();
+        }
+        public function readBytes(bytes:ByteArray, offset:uint = 0, length:uint = 0):void {
+            this.readBuffer.readBytes(bytes, offset, length);
+        }
+        public function readDouble():Number {
+            return this.readBuffer.readDouble();
+        }
+        public function readFloat():Number {
+            return this.readBuffer.readFloat();
+        }
+        public function readInt():int {
+            return this.readBuffer.readInt();
+        }
+        public function readMultiByte(length:uint, charSet:String):String {
+            return this.readBuffer.readMultiByte(length, charSet);
+        }
+        public function readObject():* {
+            return this.readBuffer.readObject();
+        }
+        public function readShort():int {
+            return this.readBuffer.readShort();
+        }
+        public function readUnsignedByte():uint {
+            return this.readBuffer.readUnsignedByte();
+        }
+        public function readUnsignedInt():uint {
+            return this.readBuffer.readUnsignedInt();
+        }
+        public function readUnsignedShort():uint {
+            return this.readBuffer.readUnsignedShort();
+        }
+        public function readUTF():String {
+            return this.readBuffer.readUTF();
+        }
+        public function readUTFBytes(length:uint):String {
+            return this.readBuffer.readUTFBytes(length);
+        }
+
+        public function writeBoolean(value:Boolean):void {
+            this.writeBuffer.writeBoolean(value);
+            this.flush();
+        }
+        public function writeByte(value:int):void {
+            this.writeBuffer.writeByte(value);
+            this.flush();
+        }
+        public function writeBytes(bytes:ByteArray, offset:uint = 0, length:uint = 0):void {
+            this.writeBuffer.writeBytes(bytes, offset, length);
+            this.flush();
+        }
+        public function writeDouble(value:Number):void {
+            this.writeBuffer.writeDouble(value);
+            this.flush();
+        }
+        public function writeFloat(value:Number):void {
+            this.writeBuffer.writeFloat(value);
+            this.flush();
+        }
+        public function writeInt(value:int):void {
+            this.writeBuffer.writeInt(value);
+            this.flush();
+        }
+        public function writeMultiByte(value:String, charSet:String):void {
+            this.writeBuffer.writeMultiByte(value, charSet);
+            this.flush();
+        }
+        public function writeObject(object:*):void {
+            this.writeBuffer.writeObject(object);
+            this.flush();
+        }
+        public function writeShort(value:int):void {
+            this.writeBuffer.writeShort(value);
+            this.flush();
+        }
+        public function writeUnsignedInt(value:uint):void {
+            this.writeBuffer.writeUnsignedInt(value);
+            this.flush();
+        }
+        public function writeUTF(value:String):void {
+            this.writeBuffer.writeUTF(value);
+            this.flush();
+        }
+        public function writeUTFBytes(value:String):void {
+            this.writeBuffer.writeUTFBytes(value);
+            this.flush();
+        }
+    }
+}
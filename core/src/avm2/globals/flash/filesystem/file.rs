@@ -0,0 +1,246 @@
+//! `flash.filesystem.File` native methods
+
+use crate::avm2::bytearray::ByteArrayStorage;
+use crate::avm2::error::{io_error, security_error};
+use crate::avm2::object::{ByteArrayObject, DateObject, Object, TObject};
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::{Activation, ArrayObject, ArrayStorage, Error, Value};
+use crate::backend::filesystem::{join_path, FileRoot, FilesystemBackend};
+use crate::string::AvmString;
+use chrono::{DateTime, Utc};
+use std::io::ErrorKind;
+
+/// Parses a sandboxed URL (`app:/...` or `app-storage:/...`), or a path or `file:` URL
+/// on the host system that lies within the sandbox.
+fn parse_url(filesystem: &dyn FilesystemBackend, url: &str) -> Option<(FileRoot, String)> {
+    for root in [FileRoot::Application, FileRoot::ApplicationStorage] {
+        if let Some(path) = url
+            .strip_prefix(root.scheme())
+            .and_then(|rest| rest.strip_prefix(':'))
+        {
+            return Some((root, join_path("", path)?));
+        }
+    }
+    filesystem.sandboxed_path(url)
+}
+
+fn to_url(root: FileRoot, path: &str) -> String {
+    format!("{}:/{path}", root.scheme())
+}
+
+/// Parses the URL passed as the given argument, throwing a `SecurityError` if it's outside of the sandbox.
+fn get_url<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<(FileRoot, String), Error<'gc>> {
+    let url = args.get_string(activation, index)?.to_string();
+    match parse_url(activation.context.filesystem, &url) {
+        Some(file) => Ok(file),
+        None => Err(Error::AvmError(security_error(
+            activation,
+            "Error #3001: File or directory access denied.",
+            3001,
+        )?)),
+    }
+}
+
+/// Converts a filesystem error to the matching `IOError`.
+fn to_io_error<'gc>(activation: &mut Activation<'_, 'gc>, error: std::io::Error) -> Error<'gc> {
+    let (message, code) = match error.kind() {
+        ErrorKind::NotFound => ("Error #3003: File or directory does not exist.", 3003),
+        ErrorKind::PermissionDenied => ("Error #3001: File or directory access denied.", 3001),
+        ErrorKind::AlreadyExists => ("Error #3002: File or directory exists.", 3002),
+        ErrorKind::DirectoryNotEmpty => ("Error #3012: Cannot delete file or directory.", 3012),
+        _ => ("Error #3013: File or directory is in use.", 3013),
+    };
+    match io_error(activation, message, code) {
+        Ok(err) => Error::AvmError(err),
+        Err(err) => err,
+    }
+}
+
+/// Implements `File.normalizeUrl`
+///
+/// Returns the sandboxed URL of the given path or URL, or `null` if it's outside of the sandbox.
+pub fn normalize_url<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = args.get_string(activation, 0)?.to_string();
+    Ok(match parse_url(activation.context.filesystem, &url) {
+        Some((root, path)) => {
+            AvmString::new_utf8(activation.context.gc_context, to_url(root, &path)).into()
+        }
+        None => Value::Null,
+    })
+}
+
+/// Implements `File.resolveUrl`
+pub fn resolve_url<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (root, base) = get_url(activation, args, 0)?;
+    let path = args.get_string(activation, 1)?.to_string();
+    Ok(match join_path(&base, &path) {
+        Some(path) => {
+            AvmString::new_utf8(activation.context.gc_context, to_url(root, &path)).into()
+        }
+        None => Value::Null,
+    })
+}
+
+/// Implements `File.getNativePath`
+pub fn get_native_path<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (root, path) = get_url(activation, args, 0)?;
+    Ok(
+        match activation.context.filesystem.native_path(root, &path) {
+            Some(native_path) => {
+                AvmString::new_utf8(activation.context.gc_context, native_path).into()
+            }
+            None => Value::Null,
+        },
+    )
+}
+
+/// Implements `File.getMetadata`
+pub fn get_metadata<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (root, path) = get_url(activation, args, 0)?;
+    let Some(metadata) = activation.context.filesystem.metadata(root, &path) else {
+        return Ok(Value::Null);
+    };
+
+    let creation_date = match metadata.creation_time {
+        Some(time) => DateObject::from_date_time(activation, DateTime::<Utc>::from(time))?.into(),
+        None => Value::Null,
+    };
+    let modification_date = match metadata.modification_time {
+        Some(time) => DateObject::from_date_time(activation, DateTime::<Utc>::from(time))?.into(),
+        None => Value::Null,
+    };
+    let values: [(&str, Value<'gc>); 4] = [
+        ("isDirectory", metadata.is_directory.into()),
+        ("size", (metadata.size as f64).into()),
+        ("creationDate", creation_date),
+        ("modificationDate", modification_date),
+    ];
+
+    let object = activation
+        .avm2()
+        .classes()
+        .object
+        .construct(activation, &[])?;
+    for (name, value) in values {
+        object.set_public_property(name, value, activation)?;
+    }
+    Ok(object.into())
+}
+
+/// Implements `File.listDirectory`
+pub fn list_directory<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (root, path) = get_url(activation, args, 0)?;
+    let names = match activation.context.filesystem.read_dir(root, &path) {
+        Ok(names) => names,
+        Err(e) => return Err(to_io_error(activation, e)),
+    };
+
+    let names = names
+        .into_iter()
+        .map(|name| Some(AvmString::new_utf8(activation.context.gc_context, name).into()))
+        .collect();
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_storage(names))?.into())
+}
+
+/// Implements `File.readFile`
+pub fn read_file<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (root, path) = get_url(activation, args, 0)?;
+    let data = match activation.context.filesystem.read(root, &path) {
+        Ok(data) => data,
+        Err(e) => return Err(to_io_error(activation, e)),
+    };
+
+    let storage = ByteArrayStorage::from_vec(data);
+    Ok(ByteArrayObject::from_storage(activation, storage)?.into())
+}
+
+/// Implements `File.writeFile`
+pub fn write_file<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (root, path) = get_url(activation, args, 0)?;
+    let data = args
+        .try_get_object(activation, 1)
+        .and_then(|o| o.as_bytearray().map(|b| b.bytes().to_vec()))
+        .unwrap_or_default();
+
+    if let Err(e) = activation.context.filesystem.write(root, &path, &data) {
+        return Err(to_io_error(activation, e));
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `File.createDirectoryAt`
+pub fn create_directory_at<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (root, path) = get_url(activation, args, 0)?;
+    if let Err(e) = activation.context.filesystem.create_dir(root, &path) {
+        return Err(to_io_error(activation, e));
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `File.deleteAt`
+pub fn delete_at<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (root, path) = get_url(activation, args, 0)?;
+    let recursive = args.get_bool(1);
+    if let Err(e) = activation.context.filesystem.remove(root, &path, recursive) {
+        return Err(to_io_error(activation, e));
+    }
+    Ok(Value::Undefined)
+}
+
+/// Implements `File.moveAt`
+pub fn move_at<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (from_root, from) = get_url(activation, args, 0)?;
+    let (to_root, to) = get_url(activation, args, 1)?;
+    if let Err(e) = activation
+        .context
+        .filesystem
+        .rename(from_root, &from, to_root, &to)
+    {
+        return Err(to_io_error(activation, e));
+    }
+    Ok(Value::Undefined)
+}
@@ -8,6 +8,7 @@ use crate::backend::navigator::NavigationMethod;
 use indexmap::IndexMap;
 
 pub mod file_reference;
+pub mod file_reference_list;
 pub mod local_connection;
 pub mod net_connection;
 pub mod net_stream;
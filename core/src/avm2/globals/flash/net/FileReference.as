@@ -37,13 +37,9 @@ package flash.net
 
         public native function browse(typeFilter:Array = null): Boolean;
 
-        public function cancel():void {
-            stub_method("flash.net.FileReference", "cancel");
-        }
+        public native function cancel():void;
 
-        public function download(request:URLRequest, defaultFileName:String = null):void {
-            stub_method("flash.net.FileReference", "download");
-        }
+        public native function download(request:URLRequest, defaultFileName:String = null):void;
 
         public native function load():void;
 
@@ -54,13 +50,9 @@ package flash.net
 
         public native function save(data:*, defaultFileName:String = null):void;
 
-        public function upload(request:URLRequest, uploadDataFieldName:String = "Filedata", testUpload:Boolean = false):void {
-            stub_method("flash.net.FileReference", "upload");
-        }
+        public native function upload(request:URLRequest, uploadDataFieldName:String = "Filedata", testUpload:Boolean = false):void;
 
         [API("681")]
-        public function uploadUnencoded(request:URLRequest):void {
-            stub_method("flash.net.FileReference", "uploadUnencoded");
-        }
+        public native function uploadUnencoded(request:URLRequest):void;
     }
 }
\ No newline at end of file
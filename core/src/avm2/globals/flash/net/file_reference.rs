@@ -1,10 +1,15 @@
 use crate::avm2::bytearray::ByteArrayStorage;
 use crate::avm2::error::{argument_error, error, make_error_2037, make_error_2097};
+use crate::avm2::globals::flash::display::loader::request_from_url_request;
 pub use crate::avm2::object::file_reference_allocator;
-use crate::avm2::object::{ByteArrayObject, DateObject, FileReference};
+use crate::avm2::object::{ByteArrayObject, DateObject, FileReference, FileReferenceObject};
+use crate::avm2::parameters::ParametersExt;
 use crate::avm2::{Activation, Avm2, Error, EventObject, Object, TObject, Value};
+use crate::backend::navigator::{NavigationMethod, Request};
 use crate::backend::ui::FileFilter;
+use crate::loader::encode_file_upload;
 use crate::string::AvmString;
+use url::form_urlencoded;
 
 pub fn get_creation_date<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -119,15 +124,13 @@ pub fn get_type<'gc>(
     Ok(type_)
 }
 
-pub fn browse<'gc>(
+/// Parses the `typeFilter` array of `FileReference.browse` and `FileReferenceList.browse`.
+pub fn file_filters_from_value<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    this: Object<'gc>,
-    args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error<'gc>> {
-    let this = this.as_file_reference().unwrap();
-
+    value: Value<'gc>,
+) -> Result<Vec<FileFilter>, Error<'gc>> {
     let mut filters = Vec::new();
-    if let Value::Object(obj) = args[0] {
+    if let Value::Object(obj) = value {
         if let Some(array_storage) = obj.as_array_storage() {
             for filter in array_storage.iter() {
                 if let Some(Value::Object(obj)) = filter {
@@ -171,6 +174,18 @@ pub fn browse<'gc>(
         }
     }
 
+    Ok(filters)
+}
+
+pub fn browse<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_file_reference().unwrap();
+
+    let filters = file_filters_from_value(activation, args[0])?;
+
     let dialog = activation.context.ui.display_file_open_dialog(filters);
     let result = match dialog {
         Some(dialog) => {
@@ -273,8 +288,160 @@ pub fn save<'gc>(
 
             activation.context.navigator.spawn_future(process);
         }
-        None => return Err(Error::AvmError(error(activation, "Error #2174: Only one download, upload, load or save operation can be active at a time on each FileReference.", 2174)?)),
+        None => return Err(make_error_2174(activation)),
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn make_error_2174<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
+    match error(
+        activation,
+        "Error #2174: Only one download, upload, load or save operation can be active at a time on each FileReference.",
+        2174,
+    ) {
+        Ok(err) => Error::AvmError(err),
+        Err(err) => err,
     }
+}
+
+pub fn cancel<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_file_reference().unwrap();
+
+    // Removing the loader makes the pending upload or download bail out once it resumes.
+    if let Some(handle) = this.active_loader() {
+        activation.context.load_manager.remove_loader(handle);
+        this.set_active_loader(None);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn download<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_file_reference().unwrap();
+
+    if this.active_loader().is_some() {
+        return Err(make_error_2174(activation));
+    }
+
+    let url_request = args.get_object(activation, 0, "request")?;
+    let request = request_from_url_request(activation, url_request)?;
+
+    // Default to the last segment of the URL path, like Flash Player does.
+    let file_name = match args.get_value(1) {
+        Value::String(name) if !name.is_empty() => name.to_string(),
+        _ => {
+            let path = request.url().split(['?', '#']).next().unwrap_or_default();
+            path.rsplit('/').next().unwrap_or_default().to_string()
+        }
+    };
+
+    let dialog = activation.context.ui.display_file_save_dialog(
+        file_name.to_owned(),
+        format!("Select location to download the file {}", file_name),
+    );
+
+    match dialog {
+        Some(dialog) => {
+            let process = activation.context.load_manager.download_file_dialog_avm2(
+                activation.context.player.clone(),
+                this,
+                dialog,
+                request,
+            );
+
+            activation.context.navigator.spawn_future(process);
+        }
+        None => return Err(make_error_2174(activation)),
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Starts an upload of the selected file, checking the same preconditions as Flash Player.
+fn start_upload<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: FileReferenceObject<'gc>,
+    url_request: Object<'gc>,
+    encode: impl FnOnce(&Request, &str, &[u8]) -> (Vec<u8>, String),
+) -> Result<(), Error<'gc>> {
+    if this.active_loader().is_some() {
+        return Err(make_error_2174(activation));
+    }
+
+    let request = request_from_url_request(activation, url_request)?;
+
+    let (body, size) = match *this.file_reference() {
+        FileReference::None => return Err(make_error_2037(activation)),
+        FileReference::FileDialogResult(ref dialog_result) => {
+            let file_name = dialog_result.file_name().unwrap_or_default();
+            let contents = dialog_result.contents();
+            (encode(&request, &file_name, contents), contents.len())
+        }
+    };
+
+    // File uploads are always sent as POST requests.
+    let mut upload_request = Request::post(request.url().to_string(), Some(body));
+    upload_request.set_headers(request.headers().clone());
+
+    let process = activation.context.load_manager.upload_file_avm2(
+        activation.context.player.clone(),
+        this,
+        upload_request,
+        size,
+    );
+    activation.context.navigator.spawn_future(process);
+
+    Ok(())
+}
+
+pub fn upload<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_file_reference().unwrap();
+    let url_request = args.get_object(activation, 0, "request")?;
+    let data_field_name = args.get_string(activation, 1)?.to_string();
+
+    start_upload(
+        activation,
+        this,
+        url_request,
+        |request, file_name, contents| {
+            // Any `URLVariables` of a POST request are sent as additional form fields.
+            let fields: Vec<(String, String)> = match (request.method(), request.body()) {
+                (NavigationMethod::Post, Some((body, _))) => form_urlencoded::parse(body)
+                    .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            encode_file_upload(&fields, &data_field_name, file_name, contents)
+        },
+    )?;
+
+    Ok(Value::Undefined)
+}
+
+pub fn upload_unencoded<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_file_reference().unwrap();
+    let url_request = args.get_object(activation, 0, "request")?;
+
+    start_upload(activation, this, url_request, |_, _, contents| {
+        (contents.to_vec(), "application/octet-stream".to_string())
+    })?;
 
     Ok(Value::Undefined)
 }
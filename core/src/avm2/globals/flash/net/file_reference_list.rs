@@ -0,0 +1,39 @@
+//! `flash.net.FileReferenceList` native methods
+
+use crate::avm2::globals::flash::net::file_reference::file_filters_from_value;
+use crate::avm2::{Activation, ArrayObject, Error, Multiname, Object, TObject, Value};
+
+pub fn browse<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let filters = file_filters_from_value(activation, args[0])?;
+
+    let dialog = activation
+        .context
+        .ui
+        .display_file_open_dialog_multiple(filters);
+    let result = match dialog {
+        Some(dialog) => {
+            // The previous selection is discarded as soon as a new one starts.
+            let file_list = ArrayObject::empty(activation)?;
+            this.set_property(
+                &Multiname::new(activation.avm2().flash_net_internal, "_fileList"),
+                file_list.into(),
+                activation,
+            )?;
+
+            let process = activation
+                .context
+                .load_manager
+                .select_file_list_dialog_avm2(activation.context.player.clone(), this, dialog);
+
+            activation.context.navigator.spawn_future(process);
+            true
+        }
+        None => false,
+    };
+
+    Ok(result.into())
+}
@@ -6,7 +6,6 @@
 package flash.system
 {
     import flash.events.EventDispatcher;
-    import __ruffle__.stub_method;
     import __ruffle__.stub_getter;
     import __ruffle__.stub_setter;
 
@@ -19,31 +18,19 @@ package flash.system
         private static var _enabled: Boolean;
 
         // The isSupported property is set to true if the IME class is available on the current platform, otherwise it is set to false.
-        private static var _isSupported: Boolean;
+        private static var _isSupported: Boolean = true;
 
         // Causes the runtime to abandon any composition that is in progress.
-        public static function compositionAbandoned():void
-        {
-            stub_method("flash.system.IME", "compositionAbandoned");
-        }
+        public static native function compositionAbandoned():void;
 
         // Call this method when the selection within the composition has been updated, either interactively or programmatically.
-        public static function compositionSelectionChanged(start:int, end:int):void
-        {
-            stub_method("flash.system.IME", "compositionSelectionChanged");
-        }
+        public static native function compositionSelectionChanged(start:int, end:int):void;
 
         // Instructs the IME to select the first candidate for the current composition string.
-        public static function doConversion():void
-        {
-            stub_method("flash.system.IME", "doConversion");
-        }
+        public static native function doConversion():void;
 
         // Sets the IME composition string.
-        public static function setCompositionString(composition:String):void
-        {
-            stub_method("flash.system.IME", "setCompositionString");
-        }
+        public static native function setCompositionString(composition:String):void;
 
         public function get isSupported() : Boolean
         {
@@ -2,6 +2,12 @@ package flash.system {
     public final class System {
         import __ruffle__.stub_method;
 
+        private static var _ime: IME = new IME();
+
+        public static function get ime(): IME {
+            return _ime;
+        }
+
         public static function gc(): void {
 
         }
@@ -0,0 +1,65 @@
+//! `flash.system.IME` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::Object;
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::string::WString;
+
+/// Implements `flash.system.IME.compositionAbandoned` method
+pub fn composition_abandoned<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(text) = activation.context.focus_tracker.get_as_edit_text() {
+        text.set_ime_composition(WString::new(), None, activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.IME.compositionSelectionChanged` method
+pub fn composition_selection_changed<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let start = args.get_i32(activation, 0)?.max(0) as usize;
+    let end = args.get_i32(activation, 1)?.max(0) as usize;
+
+    if let Some(text) = activation.context.focus_tracker.get_as_edit_text() {
+        text.set_ime_composition_cursor(start, end, activation.context.gc_context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.IME.doConversion` method
+pub fn do_conversion<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(text) = activation.context.focus_tracker.get_as_edit_text() {
+        text.commit_ime_composition(activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.IME.setCompositionString` method
+pub fn set_composition_string<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let composition = args.get_string(activation, 0)?;
+
+    if let Some(text) = activation.context.focus_tracker.get_as_edit_text() {
+        text.set_ime_composition(composition.as_wstr().into(), None, activation.context);
+    }
+
+    Ok(Value::Undefined)
+}
@@ -1,6 +1,7 @@
 use crate::avm2::error::argument_error;
-use crate::avm2::{Activation, ClassObject, Error, Object, Value};
-use crate::avm2_stub_getter;
+use crate::avm2::{Activation, ClassObject, Error, Object, TObject, Value};
+use crate::display_object::DisplayObject;
+use crate::string::AvmString;
 
 pub fn static_text_allocator<'gc>(
     _class: ClassObject<'gc>,
@@ -16,9 +17,13 @@ pub fn static_text_allocator<'gc>(
 /// Implements `StaticText.text`
 pub fn get_text<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    avm2_stub_getter!(activation, "flash.text.StaticText", "text");
+    if let Some(DisplayObject::Text(text)) = this.as_display_object() {
+        let text = text.text(activation.context.library);
+        return Ok(AvmString::new(activation.context.gc_context, text).into());
+    }
+
     Ok("".into())
 }
@@ -0,0 +1,325 @@
+//! `flash.text.TextSnapshot` native methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::parameters::ParametersExt;
+use crate::avm2::value::Value;
+use crate::avm2::{ArrayObject, ArrayStorage, Error, Multiname};
+use crate::display_object::{
+    DisplayObject, StaticGlyph, TDisplayObject, TDisplayObjectContainer, Text,
+};
+use crate::prelude::{Matrix, Twips};
+use crate::string::{AvmString, WString};
+use std::ops::Range;
+use swf::Point;
+
+/// A character of a snapshot, along with the static text it belongs to.
+struct SnapshotChar<'gc> {
+    text: Text<'gc>,
+    /// The index of this character within its text.
+    index: usize,
+    glyph: StaticGlyph<'gc>,
+}
+
+impl SnapshotChar<'_> {
+    /// The matrix transforming this character's text space into the container's space.
+    fn matrix(&self) -> Matrix {
+        *self.text.base().matrix() * self.text.text_transform()
+    }
+}
+
+/// Lists every character of the static texts placed in the snapshot's container.
+fn get_chars<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+) -> Result<Vec<SnapshotChar<'gc>>, Error<'gc>> {
+    let container = this
+        .get_property(
+            &Multiname::new(activation.avm2().flash_text_internal, "_container"),
+            activation,
+        )?
+        .as_object()
+        .and_then(|o| o.as_display_object())
+        .and_then(|d| d.as_container());
+
+    let mut chars = Vec::new();
+    if let Some(container) = container {
+        for child in container.iter_render_list() {
+            let DisplayObject::Text(text) = child else {
+                continue;
+            };
+            for (index, mut glyph) in text
+                .glyphs(activation.context.library)
+                .into_iter()
+                .enumerate()
+            {
+                // Separate texts are always on their own lines.
+                glyph.new_line |= index == 0 && !chars.is_empty();
+                chars.push(SnapshotChar { text, index, glyph });
+            }
+        }
+    }
+    Ok(chars)
+}
+
+/// Clamps the `[begin_index, end_index)` range to the characters of the snapshot.
+fn char_range(begin_index: i32, end_index: i32, len: usize) -> Range<usize> {
+    let end = (end_index.max(0) as usize).min(len);
+    let begin = (begin_index.max(0) as usize).min(end);
+    begin..end
+}
+
+fn chars_to_string<'gc, 'a>(
+    activation: &mut Activation<'_, 'gc>,
+    chars: impl Iterator<Item = &'a SnapshotChar<'gc>>,
+    include_line_endings: bool,
+) -> Value<'gc>
+where
+    'gc: 'a,
+{
+    let mut text = WString::new();
+    for (i, c) in chars.enumerate() {
+        if include_line_endings && i > 0 && c.glyph.new_line {
+            text.push_char('\n');
+        }
+        text.push_char(c.glyph.character);
+    }
+    AvmString::new(activation.context.gc_context, text).into()
+}
+
+/// Implements `TextSnapshot.charCount`
+pub fn get_char_count<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(get_chars(activation, this)?.len().into())
+}
+
+/// Implements `TextSnapshot.findText`
+pub fn find_text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let begin_index = args.get_i32(activation, 0)?.max(0) as usize;
+    let text_to_find = args.get_string(activation, 1)?;
+    let case_sensitive = args.get_bool(2);
+
+    let normalize = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+    let needle: Vec<char> = text_to_find
+        .chars()
+        .map(|c| normalize(c.unwrap_or(char::REPLACEMENT_CHARACTER)))
+        .collect();
+    let haystack: Vec<char> = get_chars(activation, this)?
+        .iter()
+        .map(|c| normalize(c.glyph.character))
+        .collect();
+
+    if needle.is_empty() || begin_index >= haystack.len() {
+        return Ok((-1).into());
+    }
+
+    let found = haystack[begin_index..]
+        .windows(needle.len())
+        .position(|window| window == needle.as_slice());
+    Ok(found.map_or(-1, |i| (begin_index + i) as i32).into())
+}
+
+/// Implements `TextSnapshot.getSelected`
+pub fn get_selected<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let begin_index = args.get_i32(activation, 0)?;
+    let end_index = args.get_i32(activation, 1)?;
+
+    let chars = get_chars(activation, this)?;
+    let range = char_range(begin_index, end_index, chars.len());
+    Ok(chars[range]
+        .iter()
+        .any(|c| c.text.is_char_selected(c.index))
+        .into())
+}
+
+/// Implements `TextSnapshot.getSelectedText`
+pub fn get_selected_text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let include_line_endings = args.get_bool(0);
+
+    let chars = get_chars(activation, this)?;
+    Ok(chars_to_string(
+        activation,
+        chars.iter().filter(|c| c.text.is_char_selected(c.index)),
+        include_line_endings,
+    ))
+}
+
+/// Implements `TextSnapshot.getText`
+pub fn get_text<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let begin_index = args.get_i32(activation, 0)?;
+    let end_index = args.get_i32(activation, 1)?;
+    let include_line_endings = args.get_bool(2);
+
+    let chars = get_chars(activation, this)?;
+    let range = char_range(begin_index, end_index, chars.len());
+    Ok(chars_to_string(
+        activation,
+        chars[range].iter(),
+        include_line_endings,
+    ))
+}
+
+/// Implements `TextSnapshot.getTextRunInfo`
+pub fn get_text_run_info<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let begin_index = args.get_i32(activation, 0)?;
+    let end_index = args.get_i32(activation, 1)?;
+
+    let chars = get_chars(activation, this)?;
+    let range = char_range(begin_index, end_index, chars.len());
+
+    let mut runs = Vec::with_capacity(range.len());
+    for (index_in_run, c) in chars.iter().enumerate().take(range.end).skip(range.start) {
+        let matrix = c.matrix() * Matrix::translate(c.glyph.x, c.glyph.y);
+        let bounds = c.glyph.bounds();
+        // Lower-left, lower-right, upper-right and upper-left corners.
+        let corners = [
+            Point::new(bounds.x_min, bounds.y_max),
+            Point::new(bounds.x_max, bounds.y_max),
+            Point::new(bounds.x_max, bounds.y_min),
+            Point::new(bounds.x_min, bounds.y_min),
+        ]
+        .map(|corner| c.matrix() * corner);
+
+        let run = activation
+            .avm2()
+            .classes()
+            .object
+            .construct(activation, &[])?;
+        let font_name = AvmString::new_utf8(
+            activation.context.gc_context,
+            c.glyph.font.descriptor().name(),
+        );
+        let color = c.glyph.color.to_rgb();
+        let values: [(&str, Value<'gc>); 19] = [
+            ("indexInRun", index_in_run.into()),
+            ("selected", c.text.is_char_selected(c.index).into()),
+            ("font", font_name.into()),
+            ("color", color.into()),
+            ("height", c.glyph.height.to_pixels().into()),
+            ("matrix_a", matrix.a.into()),
+            ("matrix_b", matrix.b.into()),
+            ("matrix_c", matrix.c.into()),
+            ("matrix_d", matrix.d.into()),
+            ("matrix_tx", matrix.tx.to_pixels().into()),
+            ("matrix_ty", matrix.ty.to_pixels().into()),
+            ("corner0x", corners[0].x.to_pixels().into()),
+            ("corner0y", corners[0].y.to_pixels().into()),
+            ("corner1x", corners[1].x.to_pixels().into()),
+            ("corner1y", corners[1].y.to_pixels().into()),
+            ("corner2x", corners[2].x.to_pixels().into()),
+            ("corner2y", corners[2].y.to_pixels().into()),
+            ("corner3x", corners[3].x.to_pixels().into()),
+            ("corner3y", corners[3].y.to_pixels().into()),
+        ];
+        for (name, value) in values {
+            run.set_public_property(name, value, activation)?;
+        }
+        runs.push(Some(run.into()));
+    }
+
+    Ok(ArrayObject::from_storage(activation, ArrayStorage::from_storage(runs))?.into())
+}
+
+/// Implements `TextSnapshot.hitTestTextNearPos`
+pub fn hit_test_text_near_pos<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let x = args.get_f64(activation, 0)?;
+    let y = args.get_f64(activation, 1)?;
+    let max_distance = args.get_f64(activation, 2)?;
+
+    let point = Point::new(Twips::from_pixels(x), Twips::from_pixels(y));
+    let mut nearest = None;
+    for (i, c) in get_chars(activation, this)?.iter().enumerate() {
+        let bounds = c.matrix() * c.glyph.bounds();
+        let dx = (bounds.x_min - point.x)
+            .max(point.x - bounds.x_max)
+            .max(Twips::ZERO)
+            .to_pixels();
+        let dy = (bounds.y_min - point.y)
+            .max(point.y - bounds.y_max)
+            .max(Twips::ZERO)
+            .to_pixels();
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance <= max_distance && nearest.map_or(true, |(_, d)| distance < d) {
+            nearest = Some((i, distance));
+        }
+    }
+
+    Ok(nearest.map_or(-1.0, |(i, _)| i as f64).into())
+}
+
+/// Implements `TextSnapshot.setSelectColor`
+pub fn set_select_color<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let color = swf::Color::from_rgb(args.get_u32(activation, 0)?, 255);
+
+    let mut texts: Vec<Text<'gc>> = Vec::new();
+    for c in get_chars(activation, this)? {
+        if !texts
+            .iter()
+            .any(|text| DisplayObject::ptr_eq((*text).into(), c.text.into()))
+        {
+            texts.push(c.text);
+        }
+    }
+    for text in texts {
+        text.set_select_color(activation.context.gc_context, color);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `TextSnapshot.setSelected`
+pub fn set_selected<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let begin_index = args.get_i32(activation, 0)?;
+    let end_index = args.get_i32(activation, 1)?;
+    let select = args.get_bool(2);
+
+    let chars = get_chars(activation, this)?;
+    let range = char_range(begin_index, end_index, chars.len());
+    for c in &chars[range] {
+        c.text
+            .set_char_selected(activation.context.gc_context, c.index, select);
+    }
+
+    Ok(Value::Undefined)
+}
@@ -1,7 +1,6 @@
 package flash.ui {
 
     import __ruffle__.stub_getter;
-    import __ruffle__.stub_method;
 
     public final class Mouse {
         public static native function hide(): void;
@@ -11,16 +10,14 @@ package flash.ui {
             return true;
         }
         public static function get supportsNativeCursor():Boolean {
-            stub_getter("flash.ui.Mouse", "supportsNativeCursor");
             return true;
         }
 
-        public static function registerCursor(name:String, cursor:MouseCursorData):void {
-            stub_method("flash.ui.Mouse", "registerCursor");
-        }
+        public static native function get cursor():String;
+        public static native function set cursor(value:String):void;
 
-        public static function unregisterCursor(name:String):void {
-            stub_method("flash.ui.Mouse", "unregisterCursor");
-        }
+        public static native function registerCursor(name:String, cursor:MouseCursorData):void;
+
+        public static native function unregisterCursor(name:String):void;
     }
 }
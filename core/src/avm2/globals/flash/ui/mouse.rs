@@ -1,9 +1,16 @@
 //! `flash.ui.Mouse` builtin
 
 use crate::avm2::activation::Activation;
-use crate::avm2::object::Object;
+use crate::avm2::error::{argument_error, make_error_2007};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::parameters::ParametersExt;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::backend::ui::{CursorImage, CustomMouseCursor};
+use crate::context::UpdateContext;
+use crate::string::AvmString;
+use ruffle_render::bitmap::PixelRegion;
+use std::sync::Arc;
 
 pub fn hide<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -22,3 +29,141 @@ pub fn show<'gc>(
     activation.context.ui.set_mouse_visible(true);
     Ok(Value::Undefined)
 }
+
+pub fn get_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = &activation.context.mouse_data.cursor_name;
+    Ok(AvmString::new_utf8(activation.context.gc_context, name).into())
+}
+
+pub fn set_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args
+        .get_string_non_null(activation, 0, "cursor")?
+        .to_string();
+
+    let is_builtin = matches!(
+        name.as_str(),
+        "auto" | "arrow" | "button" | "hand" | "ibeam"
+    );
+    if !is_builtin
+        && !activation
+            .context
+            .mouse_data
+            .registered_cursors
+            .contains_key(&name)
+    {
+        return Err(Error::AvmError(argument_error(
+            activation,
+            "Error #2008: Parameter cursor must be one of the accepted values.",
+            2008,
+        )?));
+    }
+
+    let UpdateContext { mouse_data, ui, .. } = activation.context;
+    mouse_data.cursor_name = name;
+    mouse_data.update_cursor(*ui);
+
+    Ok(Value::Undefined)
+}
+
+pub fn register_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args.get_string_non_null(activation, 0, "name")?.to_string();
+    let cursor_data = args.get_object(activation, 1, "cursor")?;
+
+    let data = cursor_data.get_public_property("data", activation)?;
+    let bitmaps: Vec<Value<'gc>> = data
+        .as_object()
+        .and_then(|data| data.as_vector_storage().map(|data| data.iter().collect()))
+        .unwrap_or_default();
+
+    let mut frames = Vec::with_capacity(bitmaps.len());
+    for bitmap in bitmaps {
+        let Some(bitmap_data) = bitmap.as_object().and_then(|o| o.as_bitmap_data()) else {
+            continue;
+        };
+        bitmap_data.check_valid(activation)?;
+
+        let (width, height) = (bitmap_data.width(), bitmap_data.height());
+        let read = bitmap_data.read_area(
+            PixelRegion::for_whole_size(width, height),
+            activation.context.renderer,
+        );
+        let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let color = read.get_pixel32_raw(x, y).to_un_multiplied_alpha();
+                rgba.extend_from_slice(&[color.red(), color.green(), color.blue(), color.alpha()]);
+            }
+        }
+
+        frames.push(CursorImage {
+            width,
+            height,
+            rgba,
+        });
+    }
+
+    if frames.is_empty() {
+        return Err(make_error_2007(activation, "data"));
+    }
+
+    let hotspot = cursor_data
+        .get_public_property("hotSpot", activation)?
+        .coerce_to_object(activation)?;
+    let hotspot_x = hotspot
+        .get_public_property("x", activation)?
+        .coerce_to_number(activation)?;
+    let hotspot_y = hotspot
+        .get_public_property("y", activation)?
+        .coerce_to_number(activation)?;
+
+    let frame_rate = cursor_data
+        .get_public_property("frameRate", activation)?
+        .coerce_to_number(activation)?;
+    let frame_rate = if frame_rate.is_finite() && frame_rate > 0.0 {
+        frame_rate
+    } else {
+        0.0
+    };
+
+    let cursor = CustomMouseCursor {
+        frames,
+        // Float to int casts saturate, so negative coordinates become 0.
+        hotspot: (hotspot_x as u32, hotspot_y as u32),
+        frame_rate,
+    };
+
+    let UpdateContext { mouse_data, ui, .. } = activation.context;
+    mouse_data.registered_cursors.insert(name, Arc::new(cursor));
+    // The cursor may be in use already, in which case it's replaced right away.
+    mouse_data.update_cursor(*ui);
+
+    Ok(Value::Undefined)
+}
+
+pub fn unregister_cursor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = args.get_string_non_null(activation, 0, "name")?.to_string();
+
+    let UpdateContext { mouse_data, ui, .. } = activation.context;
+    if mouse_data.registered_cursors.remove(&name).is_some() && mouse_data.cursor_name == name {
+        mouse_data.cursor_name = "auto".to_string();
+    }
+    mouse_data.update_cursor(*ui);
+
+    Ok(Value::Undefined)
+}
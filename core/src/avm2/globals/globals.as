@@ -161,6 +161,7 @@ include "flash/errors/StackOverflowError.as"
 // Event needs to come before its subclasses
 include "flash/events/Event.as"
 include "flash/events/TextEvent.as"
+include "flash/events/IMEEvent.as"
 include "flash/events/ActivityEvent.as"
 include "flash/events/ErrorEvent.as"
 include "flash/events/GestureEvent.as"
@@ -187,6 +188,7 @@ include "flash/events/GesturePhase.as"
 include "flash/events/HTTPStatusEvent.as"
 include "flash/events/IOErrorEvent.as"
 include "flash/events/KeyboardEvent.as"
+include "flash/events/NativeProcessExitEvent.as"
 include "flash/events/NetDataEvent.as"
 include "flash/events/NetFilterEvent.as"
 include "flash/events/NetStatusEvent.as"
@@ -314,6 +316,8 @@ include "flash/net/URLVariables.as"
 include "flash/net/XMLSocket.as"
 
 include "flash/filesystem/File.as" // File extends FileReference
+include "flash/filesystem/FileMode.as"
+include "flash/filesystem/FileStream.as"
 
 include "flash/net/drm/AuthenticationMethod.as"
 include "flash/net/drm/LoadVoucherSetting.as"
@@ -0,0 +1,204 @@
+//! Inline caches of trait lookups for property access ops.
+//!
+//! Ops like `getproperty` on receivers of an unknown type look up their
+//! multiname in the receiver's vtable each time they run. Each such call site
+//! gets an `InlineCache`, remembering the result of that lookup for the last
+//! few vtables it saw.
+//!
+//! Only the trait lookup is cached. A lookup that finds no trait still falls
+//! back to the receiver's dynamic properties and prototype chain every time,
+//! so changes to those are always observed.
+
+use crate::avm2::multiname::Multiname;
+use crate::avm2::property::Property;
+use crate::avm2::vtable::VTable;
+use gc_arena::{Collect, GcCell, Mutation};
+
+/// The number of vtables a call site remembers before it gives up on caching.
+const MAX_ENTRIES: usize = 4;
+
+#[derive(Collect, Clone, Copy)]
+#[collect(no_drop)]
+struct CacheEntry<'gc> {
+    vtable: VTable<'gc>,
+
+    /// The `traits_version` of the vtable when the lookup was made.
+    traits_version: u32,
+
+    property: Option<Property>,
+}
+
+/// The trait lookups made by a single call site.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct InlineCache<'gc> {
+    entries: Vec<CacheEntry<'gc>>,
+
+    /// Whether this call site has seen too many vtables to be worth caching.
+    megamorphic: bool,
+}
+
+impl<'gc> InlineCache<'gc> {
+    /// Look up the trait a call site's multiname resolves to in a vtable.
+    ///
+    /// This returns the same as `VTable::get_trait`.
+    pub fn get_trait(
+        this: GcCell<'gc, Self>,
+        mc: &Mutation<'gc>,
+        vtable: VTable<'gc>,
+        multiname: &Multiname<'gc>,
+    ) -> Option<Property> {
+        let traits_version = vtable.traits_version();
+        {
+            let cache = this.read();
+            if cache.megamorphic {
+                return vtable.get_trait(multiname);
+            }
+
+            if let Some(entry) = cache
+                .entries
+                .iter()
+                .find(|entry| entry.vtable == vtable && entry.traits_version == traits_version)
+            {
+                return entry.property;
+            }
+        }
+
+        let property = vtable.get_trait(multiname);
+
+        let mut cache = this.write(mc);
+        let entry = CacheEntry {
+            vtable,
+            traits_version,
+            property,
+        };
+        if let Some(stale) = cache.entries.iter_mut().find(|e| e.vtable == vtable) {
+            *stale = entry;
+        } else if cache.entries.len() < MAX_ENTRIES {
+            cache.entries.push(entry);
+        } else {
+            cache.entries = Vec::new();
+            cache.megamorphic = true;
+        }
+
+        property
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::object::TObject;
+    use crate::avm2::{Activation, QName, Value};
+    use crate::player::PlayerBuilder;
+    use crate::tag_utils::SwfMovie;
+
+    fn with_activation<F>(test: F)
+    where
+        F: for<'a, 'gc> FnOnce(&mut Activation<'_, 'gc>),
+    {
+        let player = PlayerBuilder::new().with_movie(SwfMovie::empty(10)).build();
+        let mut player = player.lock().unwrap();
+        player.mutate_with_update_context(|context| {
+            test(&mut Activation::from_nothing(context));
+        })
+    }
+
+    fn public_name<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        local_name: &'static str,
+    ) -> QName<'gc> {
+        QName::new(activation.avm2().find_public_namespace(), local_name)
+    }
+
+    #[test]
+    fn lookups_are_cached_per_vtable() {
+        with_activation(|activation| {
+            let mc = activation.gc();
+            let cache = GcCell::new(mc, InlineCache::default());
+            let vtable = VTable::empty(mc);
+            let name = public_name(activation, "cached");
+            let class = activation.avm2().classes().int.inner_class_definition();
+            vtable.install_const_trait_late(mc, name, Value::Integer(1), class);
+
+            for _ in 0..2 {
+                let property = InlineCache::get_trait(cache, mc, vtable, &name.into());
+                assert!(matches!(property, Some(Property::ConstSlot { .. })));
+            }
+            assert_eq!(cache.read().entries.len(), 1);
+            assert!(!cache.read().megamorphic);
+        });
+    }
+
+    #[test]
+    fn installing_a_trait_invalidates_lookups() {
+        with_activation(|activation| {
+            let mc = activation.gc();
+            let cache = GcCell::new(mc, InlineCache::default());
+            let global = activation
+                .avm2()
+                .toplevel_global_object()
+                .expect("Player should have set up the global object");
+            let name = public_name(activation, "installedLate");
+            let class = activation.avm2().classes().int.inner_class_definition();
+
+            let property = InlineCache::get_trait(cache, mc, global.vtable(), &name.into());
+            assert!(property.is_none());
+
+            let traits_version = global.vtable().traits_version();
+            global.install_const_late(mc, name, Value::Integer(1), class);
+            assert_ne!(global.vtable().traits_version(), traits_version);
+
+            let property = InlineCache::get_trait(cache, mc, global.vtable(), &name.into());
+            assert!(matches!(property, Some(Property::ConstSlot { .. })));
+            assert_eq!(cache.read().entries.len(), 1);
+        });
+    }
+
+    #[test]
+    fn too_many_vtables_make_a_call_site_megamorphic() {
+        with_activation(|activation| {
+            let mc = activation.gc();
+            let cache = GcCell::new(mc, InlineCache::default());
+            let name = public_name(activation, "polymorphic");
+            let padding = public_name(activation, "padding");
+            let class = activation.avm2().classes().int.inner_class_definition();
+
+            // Every vtable has the trait in a different slot.
+            let vtables: Vec<_> = (0..=MAX_ENTRIES)
+                .map(|i| {
+                    let vtable = VTable::empty(mc);
+                    for _ in 0..i {
+                        vtable.install_const_trait_late(mc, padding, Value::Integer(0), class);
+                    }
+                    vtable.install_const_trait_late(mc, name, Value::Integer(1), class);
+                    vtable
+                })
+                .collect();
+            let assert_slot = |property, slot| {
+                assert!(
+                    matches!(property, Some(Property::ConstSlot { slot_id }) if slot_id == slot)
+                );
+            };
+
+            for (i, vtable) in vtables.iter().take(MAX_ENTRIES).enumerate() {
+                let property = InlineCache::get_trait(cache, mc, *vtable, &name.into());
+                assert_slot(property, i as u32);
+            }
+            assert_eq!(cache.read().entries.len(), MAX_ENTRIES);
+            assert!(!cache.read().megamorphic);
+
+            let property = InlineCache::get_trait(cache, mc, vtables[MAX_ENTRIES], &name.into());
+            assert_slot(property, MAX_ENTRIES as u32);
+            assert!(cache.read().entries.is_empty());
+            assert!(cache.read().megamorphic);
+
+            // Lookups still resolve once the call site stops caching them.
+            for (i, vtable) in vtables.iter().enumerate() {
+                let property = InlineCache::get_trait(cache, mc, *vtable, &name.into());
+                assert_slot(property, i as u32);
+            }
+            assert!(cache.read().entries.is_empty());
+        });
+    }
+}
@@ -234,14 +234,27 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// This corresponds directly to the AVM2 operation `getproperty`, with the
     /// exception that it does not special-case object lookups on dictionary
     /// structured objects.
-    #[allow(unused_mut)] //Not unused.
     #[no_dynamic]
     fn get_property(
+        self,
+        multiname: &Multiname<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let property = self.vtable().get_trait(multiname);
+        self.get_property_with_trait(multiname, property, activation)
+    }
+
+    /// Same as get_property, but with the result of looking up `multiname` in
+    /// this object's vtable already provided (e.g. by an inline cache).
+    #[allow(unused_mut)] //Not unused.
+    #[no_dynamic]
+    fn get_property_with_trait(
         mut self,
         multiname: &Multiname<'gc>,
+        property: Option<Property>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        match self.vtable().get_trait(multiname) {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 self.base().get_slot(slot_id)
             }
@@ -344,7 +357,21 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<(), Error<'gc>> {
-        match self.vtable().get_trait(multiname) {
+        let property = self.vtable().get_trait(multiname);
+        self.set_property_with_trait(multiname, property, value, activation)
+    }
+
+    /// Same as set_property, but with the result of looking up `multiname` in
+    /// this object's vtable already provided (e.g. by an inline cache).
+    #[no_dynamic]
+    fn set_property_with_trait(
+        &self,
+        multiname: &Multiname<'gc>,
+        property: Option<Property>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match property {
             Some(Property::Slot { slot_id }) => {
                 let value = self
                     .vtable()
@@ -487,15 +514,29 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// This method should not be overridden.
     ///
     /// This corresponds directly to the `callproperty` operation in AVM2.
-    #[allow(unused_mut)]
     #[no_dynamic]
     fn call_property(
+        self,
+        multiname: &Multiname<'gc>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let property = self.vtable().get_trait(multiname);
+        self.call_property_with_trait(multiname, property, arguments, activation)
+    }
+
+    /// Same as call_property, but with the result of looking up `multiname` in
+    /// this object's vtable already provided (e.g. by an inline cache).
+    #[allow(unused_mut)]
+    #[no_dynamic]
+    fn call_property_with_trait(
         mut self,
         multiname: &Multiname<'gc>,
+        property: Option<Property>,
         arguments: &[Value<'gc>],
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        match self.vtable().get_trait(multiname) {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 let obj = self.base().get_slot(slot_id)?.as_callable(
                     activation,
@@ -3,6 +3,7 @@ use crate::avm2::object::{ClassObject, Object, ObjectPtr, TObject};
 use crate::avm2::value::Value;
 use crate::avm2::{Activation, Error};
 use crate::backend::ui::FileDialogResult;
+use crate::loader::LoaderHandle;
 use gc_arena::{Collect, Gc};
 use gc_arena::{GcWeak, Mutation};
 use std::cell::{Cell, Ref, RefCell};
@@ -20,6 +21,7 @@ pub fn file_reference_allocator<'gc>(
             base,
             reference: RefCell::new(FileReference::None),
             loaded: Cell::new(false),
+            active_loader: Cell::new(None),
         },
     ))
     .into())
@@ -73,6 +75,15 @@ impl<'gc> FileReferenceObject<'gc> {
     pub fn loaded(&self) -> bool {
         self.0.loaded.get()
     }
+
+    /// The loader of the upload or download currently in progress, if any.
+    pub fn active_loader(&self) -> Option<LoaderHandle> {
+        self.0.active_loader.get()
+    }
+
+    pub fn set_active_loader(&self, handle: Option<LoaderHandle>) {
+        self.0.active_loader.set(handle)
+    }
 }
 
 pub enum FileReference {
@@ -90,6 +101,9 @@ pub struct FileReferenceObjectData<'gc> {
     reference: RefCell<FileReference>,
 
     loaded: Cell<bool>,
+
+    #[collect(require_static)]
+    active_loader: Cell<Option<LoaderHandle>>,
 }
 
 const _: () = assert!(std::mem::offset_of!(FileReferenceObjectData, base) == 0);
@@ -3,6 +3,7 @@ use crate::avm2::error::{
     make_error_1014, make_error_1021, make_error_1025, make_error_1032, make_error_1054,
     make_error_1107, verify_error,
 };
+use crate::avm2::inline_cache::InlineCache;
 use crate::avm2::method::{BytecodeMethod, ParamConfig, ResolvedParamConfig};
 use crate::avm2::multiname::Multiname;
 use crate::avm2::op::Op;
@@ -10,7 +11,7 @@ use crate::avm2::script::TranslationUnit;
 use crate::avm2::{Activation, Error, QName};
 use crate::string::AvmAtom;
 
-use gc_arena::{Collect, Gc};
+use gc_arena::{Collect, Gc, GcCell};
 use std::collections::{HashMap, HashSet};
 use swf::avm2::read::Reader;
 use swf::avm2::types::{
@@ -23,6 +24,10 @@ use swf::error::Error as AbcReadError;
 pub struct VerifiedMethodInfo<'gc> {
     pub parsed_code: Vec<Op<'gc>>,
 
+    /// The inline cache of each op in `parsed_code`, for the property access
+    /// ops that need one.
+    pub inline_caches: Vec<Option<GcCell<'gc, InlineCache<'gc>>>>,
+
     pub exceptions: Vec<Exception<'gc>>,
 
     pub param_config: Vec<ResolvedParamConfig<'gc>>,
@@ -656,8 +661,29 @@ pub fn verify_method<'gc>(
         );
     }
 
+    // Property accesses with a static name that the optimizer couldn't resolve
+    // ahead of time cache their trait lookups instead.
+    let inline_caches = verified_code
+        .iter()
+        .map(|op| match op {
+            Op::GetProperty { multiname }
+            | Op::SetProperty { multiname }
+            | Op::CallProperty { multiname, .. }
+            | Op::CallPropVoid { multiname, .. }
+                if !multiname.has_lazy_component() =>
+            {
+                Some(GcCell::new(
+                    activation.context.gc_context,
+                    InlineCache::default(),
+                ))
+            }
+            _ => None,
+        })
+        .collect();
+
     Ok(VerifiedMethodInfo {
         parsed_code: verified_code,
+        inline_caches,
         exceptions: new_exceptions,
         param_config: resolved_param_config,
         return_type: resolved_return_type,
@@ -1134,6 +1160,7 @@ fn resolve_op<'gc>(
             is_local_register,
             register_name,
             register,
+            ..
         } => {
             let register_name = pool_string(activation, translation_unit, register_name)?;
 
@@ -27,6 +27,10 @@ pub struct VTableData<'gc> {
 
     resolved_traits: PropertyMap<'gc, Property>,
 
+    /// Incremented whenever `resolved_traits` changes, so that inline caches
+    /// can tell when a trait lookup they remember has gone stale.
+    traits_version: u32,
+
     /// Use hashmaps for the metadata tables because metadata will rarely be present on traits
     slot_metadata_table: HashMap<u32, Box<[Metadata<'gc>]>>,
 
@@ -68,6 +72,7 @@ impl<'gc> VTable<'gc> {
                 scope: None,
                 protected_namespace: None,
                 resolved_traits: PropertyMap::new(),
+                traits_version: 0,
                 slot_metadata_table: HashMap::new(),
                 disp_metadata_table: HashMap::new(),
                 slot_classes: vec![],
@@ -89,6 +94,7 @@ impl<'gc> VTable<'gc> {
                 scope: None,
                 protected_namespace: None,
                 resolved_traits: rt,
+                traits_version: 0,
                 slot_metadata_table: HashMap::new(),
                 disp_metadata_table: HashMap::new(),
                 method_table: vec![],
@@ -137,6 +143,10 @@ impl<'gc> VTable<'gc> {
             .cloned()
     }
 
+    pub fn traits_version(self) -> u32 {
+        self.0.read().traits_version
+    }
+
     pub fn get_trait_with_ns(self, name: &Multiname<'gc>) -> Option<(Namespace<'gc>, Property)> {
         if name.is_attribute() {
             return None;
@@ -275,6 +285,8 @@ impl<'gc> VTable<'gc> {
 
         write.protected_namespace = defining_class_def.protected_namespace();
 
+        write.traits_version = write.traits_version.wrapping_add(1);
+
         if let Some(superclass_vtable) = superclass_vtable {
             write.resolved_traits = superclass_vtable.0.read().resolved_traits.clone();
             write.slot_metadata_table = superclass_vtable.0.read().slot_metadata_table.clone();
@@ -551,6 +563,7 @@ impl<'gc> VTable<'gc> {
         write
             .resolved_traits
             .insert(name, Property::new_const_slot(new_slot_id));
+        write.traits_version = write.traits_version.wrapping_add(1);
         write.slot_classes.push(PropertyClass::Class(class));
 
         new_slot_id
@@ -570,6 +583,7 @@ impl<'gc> VTable<'gc> {
 
         if let Some(prop) = prop {
             write.resolved_traits.insert(interface_name, prop);
+            write.traits_version = write.traits_version.wrapping_add(1);
         }
     }
 
@@ -1,4 +1,6 @@
+pub mod accessibility;
 pub mod audio;
+pub mod filesystem;
 pub mod log;
 pub mod navigator;
 pub mod storage;
@@ -0,0 +1,288 @@
+//! Accessibility backend and the accessibility tree exposed to it.
+
+use bitflags::bitflags;
+use downcast_rs::Downcast;
+use std::fmt::Write;
+use swf::{Rectangle, Twips};
+
+/// A stable identifier for a node of the accessibility tree.
+///
+/// The identifier stays the same for as long as the underlying display object is alive.
+pub type AccessibilityNodeId = u64;
+
+/// The role of an accessible node, mirroring the MSAA roles used by Flash Player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessibilityRole {
+    /// The root of the tree, representing the whole movie.
+    Client,
+
+    /// A movie clip that was given a name or description.
+    Graphic,
+
+    /// A button.
+    PushButton,
+
+    /// Non-editable text, from either static or dynamic text fields.
+    StaticText,
+
+    /// An input text field.
+    EditableText,
+
+    /// Any other MSAA role, as reported by a custom `AccessibilityImplementation`.
+    Other(u32),
+}
+
+impl AccessibilityRole {
+    /// The MSAA role constant for this role, as returned by `get_accRole`.
+    pub fn msaa_role(self) -> u32 {
+        match self {
+            AccessibilityRole::Client => 0x0A,
+            AccessibilityRole::Graphic => 0x28,
+            AccessibilityRole::StaticText => 0x29,
+            AccessibilityRole::EditableText => 0x2A,
+            AccessibilityRole::PushButton => 0x2B,
+            AccessibilityRole::Other(role) => role,
+        }
+    }
+
+    /// The role corresponding to an MSAA role constant.
+    pub fn from_msaa_role(role: u32) -> Self {
+        match role {
+            0x0A => AccessibilityRole::Client,
+            0x28 => AccessibilityRole::Graphic,
+            0x29 => AccessibilityRole::StaticText,
+            0x2A => AccessibilityRole::EditableText,
+            0x2B => AccessibilityRole::PushButton,
+            _ => AccessibilityRole::Other(role),
+        }
+    }
+}
+
+bitflags! {
+    /// State flags of an accessible node.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct AccessibilityState: u16 {
+        /// The node currently has keyboard focus.
+        const FOCUSED = 1 << 0;
+
+        /// The node can receive keyboard focus.
+        const FOCUSABLE = 1 << 1;
+
+        /// The node is a button which is currently held down.
+        const PRESSED = 1 << 2;
+
+        /// The mouse is currently over the node.
+        const HOT = 1 << 3;
+
+        /// The node's value cannot be edited.
+        const READ_ONLY = 1 << 4;
+
+        /// The node's value is hidden, e.g. password fields.
+        const PROTECTED = 1 << 5;
+
+        /// The node is a text field accepting multiple lines.
+        const MULTILINE = 1 << 6;
+
+        /// The node's text can be selected.
+        const SELECTABLE = 1 << 7;
+    }
+}
+
+impl AccessibilityState {
+    /// Converts MSAA state flags, as returned by `get_accState`.
+    pub fn from_msaa_state(state: u32) -> Self {
+        const MAPPING: [(u32, AccessibilityState); 7] = [
+            (0x4, AccessibilityState::FOCUSED),
+            (0x8, AccessibilityState::PRESSED),
+            (0x40, AccessibilityState::READ_ONLY),
+            (0x80, AccessibilityState::HOT),
+            (0x10_0000, AccessibilityState::FOCUSABLE),
+            (0x20_0000, AccessibilityState::SELECTABLE),
+            (0x2000_0000, AccessibilityState::PROTECTED),
+        ];
+        MAPPING
+            .iter()
+            .filter(|(flag, _)| state & flag != 0)
+            .fold(AccessibilityState::empty(), |acc, (_, flag)| acc | *flag)
+    }
+}
+
+/// A node of the accessibility tree, built from the display list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityNode {
+    pub id: AccessibilityNodeId,
+    pub role: AccessibilityRole,
+    pub name: String,
+    pub description: String,
+    pub shortcut: String,
+
+    /// The current contents of editable nodes (text fields).
+    pub value: Option<String>,
+
+    /// The bounds of this node, in viewport coordinates.
+    pub bounds: Rectangle<Twips>,
+
+    /// The custom tab ordering of this node, if one was set.
+    pub tab_index: Option<i32>,
+
+    pub state: AccessibilityState,
+    pub children: Vec<AccessibilityNode>,
+}
+
+impl AccessibilityNode {
+    /// Finds the node with the given ID in this subtree.
+    pub fn find(&self, id: AccessibilityNodeId) -> Option<&AccessibilityNode> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+
+    /// Finds the node that currently has focus in this subtree.
+    pub fn focused(&self) -> Option<&AccessibilityNode> {
+        if self.state.contains(AccessibilityState::FOCUSED) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.focused())
+    }
+
+    /// Renders this subtree as indented text, one node per line.
+    ///
+    /// Node IDs are omitted, so the output is stable between runs and may be used in tests.
+    pub fn dump(&self) -> String {
+        let mut output = String::new();
+        self.dump_into(&mut output, 0);
+        output
+    }
+
+    fn dump_into(&self, output: &mut String, depth: usize) {
+        let _ = write!(
+            output,
+            "{}{:?} {:?}",
+            "  ".repeat(depth),
+            self.role,
+            self.name
+        );
+        if !self.description.is_empty() {
+            let _ = write!(output, " description={:?}", self.description);
+        }
+        if !self.shortcut.is_empty() {
+            let _ = write!(output, " shortcut={:?}", self.shortcut);
+        }
+        if let Some(value) = &self.value {
+            let _ = write!(output, " value={value:?}");
+        }
+        if let Some(tab_index) = self.tab_index {
+            let _ = write!(output, " tab_index={tab_index}");
+        }
+        if !self.state.is_empty() {
+            let flags: Vec<_> = self.state.iter_names().map(|(name, _)| name).collect();
+            let _ = write!(output, " [{}]", flags.join(", "));
+        }
+        let _ = writeln!(
+            output,
+            " ({}, {}, {}, {})",
+            self.bounds.x_min.to_pixels(),
+            self.bounds.y_min.to_pixels(),
+            self.bounds.width().to_pixels(),
+            self.bounds.height().to_pixels()
+        );
+        for child in &self.children {
+            child.dump_into(output, depth + 1);
+        }
+    }
+}
+
+/// An event sent by content through `Accessibility.sendEvent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessibilityEvent {
+    /// The node the event was sent for, if it is part of the tree.
+    pub node: Option<AccessibilityNodeId>,
+
+    /// The MSAA child ID, or 0 for the node itself.
+    pub child_id: u32,
+
+    /// The MSAA event constant, e.g. `EVENT_OBJECT_NAMECHANGE` (0x800C).
+    pub event_type: u32,
+}
+
+pub trait AccessibilityBackend: Downcast {
+    /// Whether assistive technology (such as a screen reader) is currently listening.
+    ///
+    /// The accessibility tree is only built while this returns true.
+    fn is_active(&self) -> bool;
+
+    /// Called with a freshly built tree whenever it differs from the previous one.
+    fn update_tree(&mut self, tree: AccessibilityNode);
+
+    /// Called when content sends an event through `Accessibility.sendEvent`.
+    fn send_event(&mut self, _event: AccessibilityEvent) {}
+}
+impl_downcast!(AccessibilityBackend);
+
+/// Accessibility backend that does nothing.
+#[derive(Default)]
+pub struct NullAccessibilityBackend;
+
+impl NullAccessibilityBackend {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl AccessibilityBackend for NullAccessibilityBackend {
+    fn is_active(&self) -> bool {
+        false
+    }
+
+    fn update_tree(&mut self, _tree: AccessibilityNode) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump_tree() {
+        let tree = AccessibilityNode {
+            id: 1,
+            role: AccessibilityRole::Client,
+            name: String::new(),
+            description: String::new(),
+            shortcut: String::new(),
+            value: None,
+            bounds: Rectangle {
+                x_min: Twips::ZERO,
+                y_min: Twips::ZERO,
+                x_max: Twips::from_pixels(550.0),
+                y_max: Twips::from_pixels(400.0),
+            },
+            tab_index: None,
+            state: AccessibilityState::empty(),
+            children: vec![AccessibilityNode {
+                id: 2,
+                role: AccessibilityRole::PushButton,
+                name: "Play".to_string(),
+                description: String::new(),
+                shortcut: "Ctrl+P".to_string(),
+                value: None,
+                bounds: Rectangle {
+                    x_min: Twips::from_pixels(10.0),
+                    y_min: Twips::from_pixels(20.0),
+                    x_max: Twips::from_pixels(110.0),
+                    y_max: Twips::from_pixels(50.0),
+                },
+                tab_index: Some(1),
+                state: AccessibilityState::FOCUSABLE | AccessibilityState::FOCUSED,
+                children: vec![],
+            }],
+        };
+
+        assert_eq!(
+            tree.dump(),
+            "Client \"\" (0, 0, 550, 400)\n  PushButton \"Play\" shortcut=\"Ctrl+P\" tab_index=1 [FOCUSED, FOCUSABLE] (10, 20, 100, 30)\n"
+        );
+        assert_eq!(tree.focused().map(|node| node.id), Some(2));
+        assert_eq!(tree.find(2).map(|node| node.name.as_str()), Some("Play"));
+    }
+}
@@ -0,0 +1,313 @@
+//! Sandboxed file access for AIR applications.
+
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind};
+use std::time::SystemTime;
+
+/// A directory that AIR applications can access files in.
+///
+/// Paths within a root are relative, `/`-separated and normalized (without `.` or `..`
+/// components); the empty path is the root itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FileRoot {
+    /// The read-only directory the application was installed in, known as `app:`.
+    Application,
+
+    /// The application's private writable directory, known as `app-storage:`.
+    ApplicationStorage,
+}
+
+impl FileRoot {
+    /// The URL scheme AIR uses to refer to this root.
+    pub fn scheme(self) -> &'static str {
+        match self {
+            FileRoot::Application => "app",
+            FileRoot::ApplicationStorage => "app-storage",
+        }
+    }
+}
+
+/// Information about a file or directory.
+#[derive(Debug, Clone, Default)]
+pub struct FileMetadata {
+    pub is_directory: bool,
+    pub size: u64,
+    pub creation_time: Option<SystemTime>,
+    pub modification_time: Option<SystemTime>,
+}
+
+pub trait FilesystemBackend {
+    /// Returns the path of the given file on the host system, if it has one.
+    fn native_path(&self, root: FileRoot, path: &str) -> Option<String>;
+
+    /// Converts a path on the host system to a file within one of the roots,
+    /// returning `None` if it's outside of the sandbox.
+    fn sandboxed_path(&self, native_path: &str) -> Option<(FileRoot, String)>;
+
+    /// Returns information about the given file, or `None` if it doesn't exist.
+    fn metadata(&self, root: FileRoot, path: &str) -> Option<FileMetadata>;
+
+    /// Lists the names of the entries of the given directory.
+    fn read_dir(&self, root: FileRoot, path: &str) -> Result<Vec<String>, Error>;
+
+    /// Reads the whole content of the given file.
+    fn read(&self, root: FileRoot, path: &str) -> Result<Vec<u8>, Error>;
+
+    /// Replaces the content of the given file, creating it and its parent directories if needed.
+    fn write(&mut self, root: FileRoot, path: &str, data: &[u8]) -> Result<(), Error>;
+
+    /// Creates the given directory and its parent directories, if they don't exist yet.
+    fn create_dir(&mut self, root: FileRoot, path: &str) -> Result<(), Error>;
+
+    /// Deletes the given file or directory. Directories must be empty unless `recursive` is set.
+    fn remove(&mut self, root: FileRoot, path: &str, recursive: bool) -> Result<(), Error>;
+
+    /// Moves a file or directory, replacing any file at the destination.
+    fn rename(
+        &mut self,
+        from_root: FileRoot,
+        from: &str,
+        to_root: FileRoot,
+        to: &str,
+    ) -> Result<(), Error>;
+}
+
+/// Joins a path onto a sandboxed path, resolving `.` and `..` components.
+///
+/// Both `/` and `\` are accepted as separators. Returns `None` if the result would escape the root.
+pub fn join_path(base: &str, path: &str) -> Option<String> {
+    let mut components: Vec<&str> = if path.starts_with(['/', '\\']) {
+        vec![]
+    } else {
+        base.split('/').filter(|c| !c.is_empty()).collect()
+    };
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            component => components.push(component),
+        }
+    }
+    Some(components.join("/"))
+}
+
+/// Returns the parent of a sandboxed path, or `None` for the root itself.
+pub fn parent_path(path: &str) -> Option<&str> {
+    if path.is_empty() {
+        return None;
+    }
+    Some(path.rsplit_once('/').map_or("", |(parent, _)| parent))
+}
+
+enum MemoryEntry {
+    File(Vec<u8>),
+    Directory,
+}
+
+/// A filesystem held in memory, with an empty application directory.
+#[derive(Default)]
+pub struct MemoryFilesystemBackend {
+    entries: BTreeMap<(FileRoot, String), MemoryEntry>,
+}
+
+impl MemoryFilesystemBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn is_directory(&self, root: FileRoot, path: &str) -> bool {
+        path.is_empty()
+            || matches!(
+                self.entries.get(&(root, path.to_string())),
+                Some(MemoryEntry::Directory)
+            )
+    }
+
+    fn children(&self, root: FileRoot, path: &str) -> Vec<String> {
+        let prefix = if path.is_empty() {
+            String::new()
+        } else {
+            format!("{path}/")
+        };
+        self.entries
+            .keys()
+            .filter(|(r, _)| *r == root)
+            .filter_map(|(_, p)| p.strip_prefix(&prefix))
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("{prefix}{name}"))
+            .collect()
+    }
+
+    fn check_writable(root: FileRoot) -> Result<(), Error> {
+        if root == FileRoot::Application {
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        Ok(())
+    }
+}
+
+impl FilesystemBackend for MemoryFilesystemBackend {
+    fn native_path(&self, _root: FileRoot, _path: &str) -> Option<String> {
+        None
+    }
+
+    fn sandboxed_path(&self, _native_path: &str) -> Option<(FileRoot, String)> {
+        None
+    }
+
+    fn metadata(&self, root: FileRoot, path: &str) -> Option<FileMetadata> {
+        if path.is_empty() {
+            return Some(FileMetadata {
+                is_directory: true,
+                ..Default::default()
+            });
+        }
+        match self.entries.get(&(root, path.to_string()))? {
+            MemoryEntry::File(data) => Some(FileMetadata {
+                is_directory: false,
+                size: data.len() as u64,
+                ..Default::default()
+            }),
+            MemoryEntry::Directory => Some(FileMetadata {
+                is_directory: true,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn read_dir(&self, root: FileRoot, path: &str) -> Result<Vec<String>, Error> {
+        if !self.is_directory(root, path) {
+            return Err(ErrorKind::NotFound.into());
+        }
+        Ok(self
+            .children(root, path)
+            .into_iter()
+            .filter_map(|child| {
+                let name = child.rsplit('/').next()?;
+                (parent_path(&child) == Some(path)).then(|| name.to_string())
+            })
+            .collect())
+    }
+
+    fn read(&self, root: FileRoot, path: &str) -> Result<Vec<u8>, Error> {
+        match self.entries.get(&(root, path.to_string())) {
+            Some(MemoryEntry::File(data)) => Ok(data.clone()),
+            _ => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn write(&mut self, root: FileRoot, path: &str, data: &[u8]) -> Result<(), Error> {
+        Self::check_writable(root)?;
+        if path.is_empty() || self.is_directory(root, path) {
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        if let Some(parent) = parent_path(path) {
+            self.create_dir(root, parent)?;
+        }
+        self.entries
+            .insert((root, path.to_string()), MemoryEntry::File(data.to_vec()));
+        Ok(())
+    }
+
+    fn create_dir(&mut self, root: FileRoot, path: &str) -> Result<(), Error> {
+        Self::check_writable(root)?;
+        let mut current = Some(path);
+        while let Some(path) = current.filter(|p| !p.is_empty()) {
+            match self.entries.get(&(root, path.to_string())) {
+                Some(MemoryEntry::File(_)) => return Err(ErrorKind::AlreadyExists.into()),
+                Some(MemoryEntry::Directory) => {}
+                None => {
+                    self.entries
+                        .insert((root, path.to_string()), MemoryEntry::Directory);
+                }
+            }
+            current = parent_path(path);
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, root: FileRoot, path: &str, recursive: bool) -> Result<(), Error> {
+        Self::check_writable(root)?;
+        if !self.entries.contains_key(&(root, path.to_string())) {
+            return Err(ErrorKind::NotFound.into());
+        }
+        let children = self.children(root, path);
+        if !children.is_empty() && !recursive {
+            return Err(ErrorKind::DirectoryNotEmpty.into());
+        }
+        for child in children {
+            self.entries.remove(&(root, child));
+        }
+        self.entries.remove(&(root, path.to_string()));
+        Ok(())
+    }
+
+    fn rename(
+        &mut self,
+        from_root: FileRoot,
+        from: &str,
+        to_root: FileRoot,
+        to: &str,
+    ) -> Result<(), Error> {
+        Self::check_writable(from_root)?;
+        Self::check_writable(to_root)?;
+        if !self.entries.contains_key(&(from_root, from.to_string())) {
+            return Err(ErrorKind::NotFound.into());
+        }
+        if self.is_directory(to_root, to) {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+        if let Some(parent) = parent_path(to) {
+            self.create_dir(to_root, parent)?;
+        }
+        let mut moved = self.children(from_root, from);
+        moved.push(from.to_string());
+        for path in moved {
+            if let Some(entry) = self.entries.remove(&(from_root, path.clone())) {
+                let new_path = format!("{to}{}", &path[from.len()..]);
+                self.entries.insert((to_root, new_path), entry);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_path_resolves_components() {
+        assert_eq!(join_path("a/b", "c/./d"), Some("a/b/c/d".to_string()));
+        assert_eq!(join_path("a/b", "../c"), Some("a/c".to_string()));
+        assert_eq!(join_path("a/b", "/c"), Some("c".to_string()));
+        assert_eq!(join_path("a", "..\\b"), Some("b".to_string()));
+        assert_eq!(join_path("a", "../.."), None);
+    }
+
+    #[test]
+    fn memory_backend_files_and_directories() {
+        let mut fs = MemoryFilesystemBackend::new();
+        let storage = FileRoot::ApplicationStorage;
+        fs.write(storage, "saves/slot1.sav", b"hello").unwrap();
+        assert!(fs.metadata(storage, "saves").unwrap().is_directory);
+        assert_eq!(fs.metadata(storage, "saves/slot1.sav").unwrap().size, 5);
+        assert_eq!(fs.read_dir(storage, "").unwrap(), vec!["saves".to_string()]);
+        assert_eq!(
+            fs.read_dir(storage, "saves").unwrap(),
+            vec!["slot1.sav".to_string()]
+        );
+
+        fs.rename(storage, "saves", storage, "backup").unwrap();
+        assert_eq!(fs.read(storage, "backup/slot1.sav").unwrap(), b"hello");
+        assert!(fs.metadata(storage, "saves").is_none());
+
+        assert!(fs.remove(storage, "backup", false).is_err());
+        fs.remove(storage, "backup", true).unwrap();
+        assert!(fs.read_dir(storage, "").unwrap().is_empty());
+
+        assert!(fs.write(FileRoot::Application, "file", b"").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Browser-related platform functions
 
 use crate::loader::Error;
+use crate::native_process::{ProcessAction, ProcessCommand, ProcessHandle, ProcessStartupInfo};
 use crate::socket::{ConnectionState, SocketAction, SocketHandle};
 use crate::string::WStr;
 use async_channel::{Receiver, Sender};
@@ -12,6 +13,7 @@ use std::fmt;
 use std::fmt::Display;
 use std::fs::File;
 use std::future::Future;
+use std::io;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
@@ -319,6 +321,30 @@ pub trait NavigatorBackend: Downcast {
         receiver: Receiver<Vec<u8>>,
         sender: Sender<SocketAction>,
     );
+
+    /// Whether this backend is able to start native processes at all.
+    fn can_start_process(&self) -> bool {
+        false
+    }
+
+    /// Handle a request from an AIR application to start a native process.
+    ///
+    /// Returns an error if the process could not be started, for example because the
+    /// executable isn't one the user allowed.
+    ///
+    /// Use [ProcessAction::Data] and [ProcessAction::Close] to forward the output of the process,
+    /// and [ProcessAction::Exit] once it has exited.
+    ///
+    /// When the Sender of the Receiver is dropped then the process should be terminated.
+    fn start_process(
+        &mut self,
+        _info: ProcessStartupInfo,
+        _handle: ProcessHandle,
+        _receiver: Receiver<ProcessCommand>,
+        _sender: Sender<ProcessAction>,
+    ) -> Result<(), io::Error> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
 }
 impl_downcast!(NavigatorBackend);
 
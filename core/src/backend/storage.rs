@@ -10,6 +10,26 @@ pub trait StorageBackend {
     }
 
     fn remove_key(&mut self, name: &str);
+
+    /// Returns the names of every value currently held by this backend.
+    fn keys(&self) -> Vec<String>;
+
+    /// Returns the names of every value stored under the given path prefix.
+    ///
+    /// A name matches if it is equal to `prefix` or continues it with a `/`.
+    fn keys_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let prefix = prefix.trim_matches('/');
+        if prefix.is_empty() {
+            return self.keys();
+        }
+        self.keys()
+            .into_iter()
+            .filter(|key| {
+                key.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .collect()
+    }
 }
 
 #[derive(Default)]
@@ -36,4 +56,8 @@ impl StorageBackend for MemoryStorageBackend {
     fn remove_key(&mut self, name: &str) {
         self.map.remove(name);
     }
+
+    fn keys(&self) -> Vec<String> {
+        self.map.keys().cloned().collect()
+    }
 }
@@ -7,6 +7,9 @@ use fluent_templates::loader::langid;
 pub use fluent_templates::LanguageIdentifier;
 use std::borrow::Cow;
 use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use swf::{Rectangle, Twips};
 use url::Url;
 
 pub type FullscreenError = Cow<'static, str>;
@@ -62,6 +65,57 @@ impl_downcast!(FileDialogResult);
 /// Future representing a file selection in process
 pub type DialogResultFuture = OwnedFuture<Box<dyn FileDialogResult>, DialogLoaderError>;
 
+/// Future representing a selection of several files in process.
+/// An empty list means the selection was canceled.
+pub type MultipleDialogResultFuture =
+    OwnedFuture<Vec<Box<dyn FileDialogResult>>, DialogLoaderError>;
+
+/// A kind of data that can be held by the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipboardFormat {
+    /// Plain text.
+    Text,
+    /// An HTML fragment.
+    Html,
+    /// A Rich Text Format document.
+    RichText,
+    /// A URL.
+    Url,
+    /// An image.
+    Bitmap,
+    /// A list of paths to files.
+    FileList,
+}
+
+/// A clipboard payload in one of the [`ClipboardFormat`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardData {
+    Text(String),
+    Html(String),
+    RichText(Vec<u8>),
+    Url(String),
+    /// An image, as non-premultiplied RGBA pixels in row-major order.
+    Bitmap {
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    },
+    FileList(Vec<String>),
+}
+
+impl ClipboardData {
+    pub fn format(&self) -> ClipboardFormat {
+        match self {
+            ClipboardData::Text(_) => ClipboardFormat::Text,
+            ClipboardData::Html(_) => ClipboardFormat::Html,
+            ClipboardData::RichText(_) => ClipboardFormat::RichText,
+            ClipboardData::Url(_) => ClipboardFormat::Url,
+            ClipboardData::Bitmap { .. } => ClipboardFormat::Bitmap,
+            ClipboardData::FileList(_) => ClipboardFormat::FileList,
+        }
+    }
+}
+
 pub trait UiBackend: Downcast {
     fn mouse_visible(&self) -> bool;
 
@@ -81,6 +135,41 @@ pub trait UiBackend: Downcast {
     /// Sets the clipboard to the given content.
     fn set_clipboard_content(&mut self, content: String);
 
+    /// Lists the formats the clipboard currently holds data in.
+    ///
+    /// The default implementation only knows about plain text.
+    fn clipboard_formats(&mut self) -> Vec<ClipboardFormat> {
+        if self.clipboard_available() {
+            vec![ClipboardFormat::Text]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Get the clipboard content in the given format, if there is any.
+    fn clipboard_data(&mut self, format: ClipboardFormat) -> Option<ClipboardData> {
+        match format {
+            ClipboardFormat::Text if self.clipboard_available() => {
+                Some(ClipboardData::Text(self.clipboard_content()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Replaces the whole clipboard content with the given payloads, at most one per format.
+    ///
+    /// The default implementation only keeps the plain text payload.
+    fn set_clipboard_data(&mut self, data: Vec<ClipboardData>) {
+        let text = data
+            .into_iter()
+            .find_map(|data| match data {
+                ClipboardData::Text(text) => Some(text),
+                _ => None,
+            })
+            .unwrap_or_default();
+        self.set_clipboard_content(text);
+    }
+
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError>;
 
     /// Displays a message about an error during root movie download.
@@ -95,6 +184,13 @@ pub trait UiBackend: Downcast {
 
     fn close_virtual_keyboard(&self);
 
+    /// Enables or disables the input method editor (IME), used to compose text in some languages.
+    fn set_ime_enabled(&mut self, enabled: bool);
+
+    /// Tells the input method editor where text is being entered, in viewport coordinates,
+    /// so that its candidate window can be placed next to it.
+    fn set_ime_cursor_area(&mut self, area: Rectangle<Twips>);
+
     fn language(&self) -> LanguageIdentifier;
 
     fn display_unsupported_video(&self, url: Url);
@@ -118,6 +214,25 @@ pub trait UiBackend: Downcast {
     /// * `filters` represents a list of filters to the possible file types that can be selected
     fn display_file_open_dialog(&mut self, filters: Vec<FileFilter>) -> Option<DialogResultFuture>;
 
+    /// Displays a file selection dialog allowing several files to be selected at once,
+    /// returning None if the dialog cannot be displayed.
+    ///
+    /// By default this falls back to selecting a single file.
+    fn display_file_open_dialog_multiple(
+        &mut self,
+        filters: Vec<FileFilter>,
+    ) -> Option<MultipleDialogResultFuture> {
+        let dialog = self.display_file_open_dialog(filters)?;
+        Some(Box::pin(async move {
+            let result = dialog.await?;
+            Ok(if result.is_cancelled() {
+                vec![]
+            } else {
+                vec![result]
+            })
+        }))
+    }
+
     /// Display a dialog allowing a user to select a destination to save a file to
     ///
     /// * `file_name` is a suggestion for the file name to save the file as
@@ -135,7 +250,7 @@ impl_downcast!(UiBackend);
 
 /// A mouse cursor icon displayed by the Flash Player.
 /// Communicated from the core to the UI backend via `UiBackend::set_mouse_cursor`.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub enum MouseCursor {
     /// The default arrow icon.
     /// Equivalent to AS3 `MouseCursor.ARROW`.
@@ -152,6 +267,61 @@ pub enum MouseCursor {
     /// The grabby-dragging hand icon.
     /// Equivalent to AS3 `MouseCursor.HAND`.
     Grab,
+
+    /// A bitmap cursor registered through AS3 `Mouse.registerCursor`.
+    Custom(Arc<CustomMouseCursor>),
+}
+
+impl PartialEq for MouseCursor {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // Comparing the pixels of every frame would be too slow for each mouse update,
+            // and registering a cursor always creates a new one.
+            (MouseCursor::Custom(a), MouseCursor::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+/// A cursor made from one or more bitmaps, animated if there are several.
+#[derive(Debug)]
+pub struct CustomMouseCursor {
+    /// The images of the cursor, shown in a loop.
+    pub frames: Vec<CursorImage>,
+
+    /// The point of the images that is placed at the mouse position, in pixels.
+    pub hotspot: (u32, u32),
+
+    /// How many frames are shown per second, or 0 if the cursor isn't animated.
+    pub frame_rate: f64,
+}
+
+impl CustomMouseCursor {
+    /// The index of the frame to show after `elapsed` time since the cursor was first shown.
+    pub fn frame_at(&self, elapsed: Duration) -> usize {
+        if self.frames.len() <= 1 || self.frame_rate <= 0.0 {
+            return 0;
+        }
+        (elapsed.as_secs_f64() * self.frame_rate) as usize % self.frames.len()
+    }
+
+    /// How long each frame is shown for, if the cursor is animated.
+    pub fn frame_duration(&self) -> Option<Duration> {
+        if self.frames.len() <= 1 || self.frame_rate <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(1.0 / self.frame_rate))
+    }
+}
+
+/// A single image of a `CustomMouseCursor`.
+#[derive(Debug)]
+pub struct CursorImage {
+    pub width: u32,
+    pub height: u32,
+
+    /// The pixels of the image as non-premultiplied RGBA.
+    pub rgba: Vec<u8>,
 }
 
 struct ClickEventData {
@@ -363,6 +533,10 @@ impl UiBackend for NullUiBackend {
 
     fn close_virtual_keyboard(&self) {}
 
+    fn set_ime_enabled(&mut self, _enabled: bool) {}
+
+    fn set_ime_cursor_area(&mut self, _area: Rectangle<Twips>) {}
+
     fn language(&self) -> LanguageIdentifier {
         US_ENGLISH.clone()
     }
@@ -14,7 +14,9 @@ use crate::avm2::Activation as Avm2Activation;
 use crate::avm2::TObject as _;
 use crate::avm2::{Avm2, Object as Avm2Object, SoundChannelObject};
 use crate::backend::{
+    accessibility::AccessibilityBackend,
     audio::{AudioBackend, AudioManager, SoundHandle, SoundInstanceHandle},
+    filesystem::FilesystemBackend,
     log::LogBackend,
     navigator::NavigatorBackend,
     storage::StorageBackend,
@@ -28,6 +30,7 @@ use crate::frame_lifecycle::FramePhase;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::local_connection::LocalConnections;
+use crate::native_process::NativeProcesses;
 use crate::net_connection::NetConnections;
 use crate::player::PostFrameCallback;
 use crate::player::{MouseData, Player};
@@ -133,6 +136,12 @@ pub struct UpdateContext<'gc> {
     /// The storage backend, used for storing persistent state
     pub storage: &'gc mut dyn StorageBackend,
 
+    /// The filesystem backend, used by AIR applications to access files.
+    pub filesystem: &'gc mut dyn FilesystemBackend,
+
+    /// The accessibility backend, used to expose content to assistive technology.
+    pub accessibility: &'gc mut dyn AccessibilityBackend,
+
     /// The logging backend, used for trace output capturing.
     ///
     /// **DO NOT** use this field directly, use the `avm_trace` method instead.
@@ -239,6 +248,9 @@ pub struct UpdateContext<'gc> {
 
     pub sockets: &'gc mut Sockets<'gc>,
 
+    /// Processes started by AIR applications.
+    pub native_processes: &'gc mut NativeProcesses<'gc>,
+
     /// List of active NetConnection instances.
     pub net_connections: &'gc mut NetConnections<'gc>,
 
@@ -454,6 +466,7 @@ impl<'gc> UpdateContext<'gc> {
 
         // Clean up the stage before loading another root movie.
         self.sockets.close_all();
+        self.native_processes.close_all();
         self.timers.remove_all();
 
         self.set_root_movie(movie);
@@ -18,9 +18,10 @@ use gc_arena::{Collect, Mutation};
 use ruffle_macros::enum_trait_object;
 use ruffle_render::pixel_bender::PixelBenderShaderHandle;
 use ruffle_render::transform::{Transform, TransformStack};
-use std::cell::{Ref, RefMut};
+use std::cell::{OnceCell, Ref, RefMut};
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use swf::{ColorTransform, Fixed8};
 
@@ -59,7 +60,7 @@ use ruffle_render::blend::ExtendedBlendMode;
 use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
 use ruffle_render::filters::Filter;
 pub use stage::{Stage, StageAlign, StageDisplayState, StageScaleMode, WindowMode};
-pub use text::Text;
+pub use text::{StaticGlyph, Text};
 pub use video::Video;
 
 use self::loader_display::LoaderDisplayWeak;
@@ -265,6 +266,10 @@ pub struct DisplayObjectBase<'gc> {
     /// None means not cached, Some means cached.
     #[collect(require_static)]
     cache: Option<BitmapCache>,
+
+    /// The identifier of this object in the accessibility tree, assigned when first needed.
+    #[collect(require_static)]
+    accessibility_id: OnceCell<u64>,
 }
 
 impl<'gc> Default for DisplayObjectBase<'gc> {
@@ -294,6 +299,7 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             next_scroll_rect: Default::default(),
             scaling_grid: Default::default(),
             cache: None,
+            accessibility_id: OnceCell::new(),
         }
     }
 }
@@ -305,6 +311,16 @@ impl<'gc> DisplayObjectBase<'gc> {
         self.flags = flags_to_keep | DisplayObjectFlags::VISIBLE;
     }
 
+    /// Returns the identifier of this object in the accessibility tree.
+    ///
+    /// Identifiers are never reused, so they stay valid for as long as the object is alive.
+    pub fn accessibility_id(&self) -> u64 {
+        static NEXT_ACCESSIBILITY_ID: AtomicU64 = AtomicU64::new(1);
+        *self
+            .accessibility_id
+            .get_or_init(|| NEXT_ACCESSIBILITY_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
     fn depth(&self) -> Depth {
         self.depth
     }
@@ -1231,6 +1247,7 @@ pub trait TDisplayObject<'gc>:
     /// It is the callers responsibility to do so.
     fn set_matrix(&self, gc_context: &Mutation<'gc>, matrix: Matrix) {
         self.base_mut(gc_context).set_matrix(matrix);
+        self.invalidate_accessibility(gc_context);
     }
 
     /// Sets the color transform of this object.
@@ -1328,6 +1345,7 @@ pub trait TDisplayObject<'gc>:
                 // we only want to inform ancestors to avoid unnecessary invalidations for tx/ty
                 parent.invalidate_cached_bitmap(gc_context);
             }
+            self.invalidate_accessibility(gc_context);
         }
     }
 
@@ -1347,6 +1365,7 @@ pub trait TDisplayObject<'gc>:
                 // we only want to inform ancestors to avoid unnecessary invalidations for tx/ty
                 parent.invalidate_cached_bitmap(gc_context);
             }
+            self.invalidate_accessibility(gc_context);
         }
     }
 
@@ -1369,6 +1388,7 @@ pub trait TDisplayObject<'gc>:
                 // we only want to inform ancestors to avoid unnecessary invalidations for tx/ty
                 parent.invalidate_cached_bitmap(gc_context);
             }
+            self.invalidate_accessibility(gc_context);
         }
     }
 
@@ -1391,6 +1411,7 @@ pub trait TDisplayObject<'gc>:
                 // we only want to inform ancestors to avoid unnecessary invalidations for tx/ty
                 parent.invalidate_cached_bitmap(gc_context);
             }
+            self.invalidate_accessibility(gc_context);
         }
     }
 
@@ -1413,6 +1434,7 @@ pub trait TDisplayObject<'gc>:
                 // we only want to inform ancestors to avoid unnecessary invalidations for tx/ty
                 parent.invalidate_cached_bitmap(gc_context);
             }
+            self.invalidate_accessibility(gc_context);
         }
     }
 
@@ -1756,6 +1778,7 @@ pub trait TDisplayObject<'gc>:
                 // We don't need to invalidate ourselves, we're just toggling if the bitmap is rendered.
                 parent.invalidate_cached_bitmap(context.gc());
             }
+            self.invalidate_accessibility(context.gc());
         }
 
         if !value {
@@ -2494,6 +2517,26 @@ pub trait TDisplayObject<'gc>:
         }
     }
 
+    /// Inform the stage that what this object exposes to assistive technology may have changed,
+    /// so that the accessibility tree is rebuilt.
+    /// This also drops the cached answers of the `AccessibilityImplementation` of this object
+    /// and its ancestors, which may describe their children.
+    fn invalidate_accessibility(&self, mc: &Mutation<'gc>) {
+        let mut object: DisplayObject<'gc> = (*self).into();
+        loop {
+            if let Some(interactive) = object.as_interactive() {
+                interactive.clear_accessibility_cache(mc);
+            }
+            match object.parent() {
+                Some(parent) => object = parent,
+                None => break,
+            }
+        }
+        if let Some(stage) = object.as_stage() {
+            stage.set_accessibility_invalidated(mc, true);
+        }
+    }
+
     /// Retrieve a named property from the AVM1 object.
     ///
     /// This is required as some boolean properties in AVM1 can in fact hold any value.
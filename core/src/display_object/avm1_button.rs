@@ -200,6 +200,7 @@ impl<'gc> Avm1Button<'gc> {
         }
 
         self.invalidate_cached_bitmap(context.gc());
+        self.invalidate_accessibility(context.gc());
     }
 
     pub fn state(&self) -> Option<ButtonState> {
@@ -280,6 +280,7 @@ impl<'gc> Avm2Button<'gc> {
     /// Change the rendered state of the button.
     pub fn set_state(self, context: &mut UpdateContext<'gc>, state: ButtonState) {
         self.invalidate_cached_bitmap(context.gc());
+        self.invalidate_accessibility(context.gc());
         self.0.state.set(state);
 
         for state in self.all_state_children(false) {
@@ -210,6 +210,7 @@ pub trait TDisplayObjectContainer<'gc>:
 
         let this: DisplayObject<'_> = self.into();
         this.invalidate_cached_bitmap(context.gc_context);
+        this.invalidate_accessibility(context.gc_context);
 
         removed_child
     }
@@ -237,6 +238,7 @@ pub trait TDisplayObjectContainer<'gc>:
             .swap_at_depth(context, this, child, depth);
 
         this.invalidate_cached_bitmap(context.gc_context);
+        this.invalidate_accessibility(context.gc_context);
     }
 
     /// Insert a child display object into the container at a specific position
@@ -282,6 +284,7 @@ pub trait TDisplayObjectContainer<'gc>:
         }
 
         this.invalidate_cached_bitmap(context.gc_context);
+        this.invalidate_accessibility(context.gc_context);
     }
 
     /// Swap two children in the render list.
@@ -292,6 +295,7 @@ pub trait TDisplayObjectContainer<'gc>:
             .swap_at_id(index1, index2);
         let this: DisplayObject<'_> = (*self).into();
         this.invalidate_cached_bitmap(context.gc_context);
+        this.invalidate_accessibility(context.gc_context);
     }
 
     /// Remove (and unloads) a child display object from this container's render and depth lists.
@@ -335,6 +339,7 @@ pub trait TDisplayObjectContainer<'gc>:
 
                 drop(raw_container);
                 this.invalidate_cached_bitmap(context.gc_context);
+                this.invalidate_accessibility(context.gc_context);
 
                 return;
             }
@@ -366,6 +371,7 @@ pub trait TDisplayObjectContainer<'gc>:
 
             let this: DisplayObject<'_> = (*self).into();
             this.invalidate_cached_bitmap(context.gc_context);
+            this.invalidate_accessibility(context.gc_context);
         }
     }
 
@@ -384,6 +390,7 @@ pub trait TDisplayObjectContainer<'gc>:
             .insert_child_into_depth_list(depth, child);
 
         this.invalidate_cached_bitmap(context.gc_context);
+        this.invalidate_accessibility(context.gc_context);
     }
 
     /// Removes (without unloading) a child display object from this container's depth list.
@@ -402,6 +409,7 @@ pub trait TDisplayObjectContainer<'gc>:
 
         let this: DisplayObject<'_> = (*self).into();
         this.invalidate_cached_bitmap(context.gc_context);
+        this.invalidate_accessibility(context.gc_context);
     }
 
     /// Remove a set of children identified by their render list indices from
@@ -446,6 +454,7 @@ pub trait TDisplayObjectContainer<'gc>:
         drop(write);
         let this: DisplayObject<'_> = (*self).into();
         this.invalidate_cached_bitmap(context.gc_context);
+        this.invalidate_accessibility(context.gc_context);
     }
 
     /// Determine if the container is empty.
@@ -9,21 +9,24 @@ use crate::avm1::{
 };
 use crate::avm2::Avm2;
 use crate::avm2::{
-    Activation as Avm2Activation, EventObject as Avm2EventObject, Object as Avm2Object,
-    StageObject as Avm2StageObject, TObject as _,
+    Activation as Avm2Activation, Error as Avm2Error, EventObject as Avm2EventObject,
+    Object as Avm2Object, StageObject as Avm2StageObject, TObject as _,
 };
+use crate::backend::navigator::Request;
 use crate::backend::ui::MouseCursor;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::interactive::{
     InteractiveObject, InteractiveObjectBase, TInteractiveObject,
 };
-use crate::display_object::{DisplayObjectBase, DisplayObjectPtr};
+use crate::display_object::{DisplayObjectBase, DisplayObjectPtr, MovieClip};
 use crate::events::{ClipEvent, ClipEventResult, TextControlCode};
 use crate::font::{FontType, Glyph, TextRenderSettings};
 use crate::html;
 use crate::html::{
-    FormatSpans, Layout, LayoutBox, LayoutContent, LayoutLine, LayoutMetrics, Position, TextFormat,
+    FormatSpans, Layout, LayoutBox, LayoutContent, LayoutLine, LayoutMetrics, Position, Size,
+    TextFormat, TextImage,
 };
+use crate::loader::MovieLoaderVMData;
 use crate::prelude::*;
 use crate::string::{utils as string_utils, AvmString, SwfStrExt as _, WStr, WString};
 use crate::tag_utils::SwfMovie;
@@ -172,6 +175,23 @@ pub struct EditTextData<'gc> {
     /// Information related to the last click event inside this text field.
     #[collect(require_static)]
     last_click: Option<ClickEventData>,
+
+    /// The text currently being composed with an input method, if any.
+    #[collect(require_static)]
+    ime_composition: Option<ImeComposition>,
+
+    /// The text spans the layout was built from while composing text, which
+    /// contain the composition in place of the selection.
+    #[collect(require_static)]
+    composed_text_spans: Option<FormatSpans>,
+
+    /// The display objects of the images embedded with `<img>` tags, in the
+    /// order of `text_spans.images()`.
+    images: Vec<Option<DisplayObject<'gc>>>,
+
+    /// The image sizes the current layout was built with.
+    #[collect(require_static)]
+    image_sizes: Vec<Size<Twips>>,
 }
 
 impl<'gc> EditTextData<'gc> {
@@ -188,6 +208,13 @@ impl<'gc> EditTextData<'gc> {
             Twips::ZERO
         }
     }
+
+    /// The text spans the current layout was built from.
+    fn layout_text_spans(&self) -> &FormatSpans {
+        self.composed_text_spans
+            .as_ref()
+            .unwrap_or(&self.text_spans)
+    }
 }
 
 impl<'gc> EditText<'gc> {
@@ -239,6 +266,7 @@ impl<'gc> EditText<'gc> {
             swf_tag.bounds().width() - Twips::from_pixels(Self::INTERNAL_PADDING * 2.0),
             swf_tag.is_word_wrap(),
             font_type,
+            &[],
         );
 
         let mut base = InteractiveObjectBase::default();
@@ -304,6 +332,10 @@ impl<'gc> EditText<'gc> {
                 is_tlf: false,
                 restrict: EditTextRestrict::allow_all(),
                 last_click: None,
+                ime_composition: None,
+                composed_text_spans: None,
+                images: Vec::new(),
+                image_sizes: Vec::new(),
             },
         ));
 
@@ -374,6 +406,7 @@ impl<'gc> EditText<'gc> {
         edit_text.text_spans = FormatSpans::from_text(text.into(), default_format);
         drop(edit_text);
 
+        self.load_images(context);
         self.relayout(context);
     }
 
@@ -399,6 +432,7 @@ impl<'gc> EditText<'gc> {
             );
             drop(write);
 
+            self.load_images(context);
             self.relayout(context);
         } else {
             self.set_text(text, context);
@@ -743,6 +777,7 @@ impl<'gc> EditText<'gc> {
     /// have already been calculated and applied to HTML trees lowered into the
     /// text-span representation.
     fn relayout(self, context: &mut UpdateContext<'gc>) {
+        let image_sizes = self.image_sizes();
         let mut edit_text = self.0.write(context.gc_context);
         let autosize = edit_text.autosize;
         let is_word_wrap = edit_text.flags.contains(EditTextFlag::WORD_WRAP);
@@ -757,6 +792,25 @@ impl<'gc> EditText<'gc> {
             edit_text.text_spans.clear_displayed_text();
         }
 
+        // Text being composed is laid out in place of the selection.
+        let selection = edit_text.selection;
+        let composed_text_spans = match (&edit_text.ime_composition, selection) {
+            (Some(composition), Some(selection)) => {
+                let mut spans = edit_text.text_spans.clone();
+                spans.replace_text(selection.start(), selection.end(), &composition.text, None);
+                if edit_text.flags.contains(EditTextFlag::PASSWORD) {
+                    spans.hide_text();
+                }
+                Some(spans)
+            }
+            _ => None,
+        };
+        if let (Some(composition), Some(selection)) =
+            (edit_text.ime_composition.as_mut(), selection)
+        {
+            composition.start = selection.start();
+        }
+
         // Determine the internal width available for content layout.
         let content_width = if autosize == AutoSizeMode::None || is_word_wrap {
             edit_text.requested_width - padding
@@ -773,15 +827,20 @@ impl<'gc> EditText<'gc> {
         };
 
         let new_layout = html::lower_from_text_spans(
-            &edit_text.text_spans,
+            composed_text_spans
+                .as_ref()
+                .unwrap_or(&edit_text.text_spans),
             context,
             movie,
             content_width,
             is_word_wrap,
             font_type,
+            &image_sizes,
         );
 
         edit_text.layout = new_layout;
+        edit_text.image_sizes = image_sizes;
+        edit_text.composed_text_spans = composed_text_spans;
         // reset scroll
         edit_text.hscroll = 0.0;
         edit_text.scroll = 1;
@@ -815,7 +874,188 @@ impl<'gc> EditText<'gc> {
             edit_text.bounds.set_height(height);
         }
         drop(edit_text);
+        self.position_images(context.gc_context);
         self.invalidate_cached_bitmap(context.gc_context);
+        self.invalidate_accessibility(context.gc_context);
+    }
+
+    /// Creates the display objects of the images embedded in the text,
+    /// replacing the previous ones.
+    fn load_images(self, context: &mut UpdateContext<'gc>) {
+        let images = self.0.read().text_spans.images().to_vec();
+        let objects = images
+            .iter()
+            .map(|image| {
+                let object = if self.movie().is_action_script_3() {
+                    self.load_image_avm2(context, image)
+                } else {
+                    self.load_image_avm1(context, image)
+                };
+                if let Some(object) = object {
+                    object.set_parent(context, Some(self.into()));
+                }
+                object
+            })
+            .collect();
+        self.0.write(context.gc_context).images = objects;
+    }
+
+    /// Instantiates an image under AVM1, either from a library symbol
+    /// exported with the image's name, or by loading it into a new clip.
+    fn load_image_avm1(
+        self,
+        context: &mut UpdateContext<'gc>,
+        image: &TextImage,
+    ) -> Option<DisplayObject<'gc>> {
+        let movie = self.movie();
+        let name = AvmString::new(context.gc_context, image.src.clone());
+        let library = context.library.library_for_movie(movie.clone())?;
+        if library.character_by_export_name(name).is_some() {
+            if let Ok(symbol) = library.instantiate_by_export_name(name, context.gc_context) {
+                symbol.post_instantiation(context, None, Instantiator::Avm1, false);
+                return Some(symbol);
+            }
+        }
+
+        let clip = MovieClip::new(movie, context.gc_context);
+        clip.post_instantiation(context, None, Instantiator::Avm1, false);
+        let future = context.load_manager.load_movie_into_clip(
+            context.player.clone(),
+            clip.into(),
+            Request::get(image.src.to_utf8_lossy().into_owned()),
+            None,
+            MovieLoaderVMData::Avm1 { broadcaster: None },
+        );
+        context.navigator.spawn_future(future);
+        Some(clip.into())
+    }
+
+    /// Instantiates an image under AVM2, either by constructing the display
+    /// object class named by the image, or by loading it with a `Loader`.
+    fn load_image_avm2(
+        self,
+        context: &mut UpdateContext<'gc>,
+        image: &TextImage,
+    ) -> Option<DisplayObject<'gc>> {
+        let domain = context
+            .library
+            .library_for_movie(self.movie())?
+            .avm2_domain();
+        let mut activation = Avm2Activation::from_domain(context, domain);
+        let name = AvmString::new(activation.context.gc_context, image.src.clone());
+
+        if let Ok(Avm2Value::Object(class)) =
+            domain.get_defined_value_handling_vector(&mut activation, name)
+        {
+            if let Some(class) = class.as_class_object() {
+                match class.construct(&mut activation, &[]) {
+                    Ok(object) => return object.as_display_object(),
+                    Err(e) => tracing::warn!("Error when constructing <img> {}: {:?}", name, e),
+                }
+            }
+        }
+
+        let result = (|| -> Result<Avm2Object<'gc>, Avm2Error<'gc>> {
+            let loader = activation
+                .avm2()
+                .classes()
+                .loader
+                .construct(&mut activation, &[])?;
+            let request = activation
+                .avm2()
+                .classes()
+                .urlrequest
+                .construct(&mut activation, &[name.into()])?;
+            loader.call_public_property("load", &[request.into()], &mut activation)?;
+            Ok(loader)
+        })();
+        match result {
+            Ok(loader) => loader.as_display_object(),
+            Err(e) => {
+                tracing::warn!("Error when loading <img> {}: {:?}", name, e);
+                None
+            }
+        }
+    }
+
+    /// The sizes images are laid out with: the requested ones, or the natural
+    /// size of their display object.
+    fn image_sizes(self) -> Vec<Size<Twips>> {
+        let edit_text = self.0.read();
+        edit_text
+            .text_spans
+            .images()
+            .iter()
+            .zip(&edit_text.images)
+            .map(|(image, object)| {
+                let natural = object
+                    .map(|o| o.bounds_with_transform(&Matrix::default()))
+                    .filter(Rectangle::is_valid);
+                let natural_width = natural.as_ref().map_or(Twips::ZERO, |b| b.width());
+                let natural_height = natural.as_ref().map_or(Twips::ZERO, |b| b.height());
+                Size::from((
+                    image.width.map_or(natural_width, Twips::from_pixels),
+                    image.height.map_or(natural_height, Twips::from_pixels),
+                ))
+            })
+            .collect()
+    }
+
+    /// Moves and scales image display objects to where the layout placed them.
+    fn position_images(self, gc_context: &Mutation<'gc>) {
+        let edit_text = self.0.read();
+        for (object, bounds) in edit_text.images.iter().zip(edit_text.layout.image_bounds()) {
+            let Some(object) = object else {
+                continue;
+            };
+
+            let natural = object.bounds_with_transform(&Matrix::default());
+            let (scale_x, scale_y) = if natural.is_valid() {
+                let scale = |size: Twips, natural: Twips| {
+                    if natural > Twips::ZERO {
+                        size.to_pixels() / natural.to_pixels()
+                    } else {
+                        1.0
+                    }
+                };
+                (
+                    scale(bounds.width(), natural.width()),
+                    scale(bounds.height(), natural.height()),
+                )
+            } else {
+                (1.0, 1.0)
+            };
+            let origin = if natural.is_valid() {
+                Position::from((
+                    Twips::from_pixels(natural.x_min.to_pixels() * scale_x),
+                    Twips::from_pixels(natural.y_min.to_pixels() * scale_y),
+                ))
+            } else {
+                Position::default()
+            };
+
+            object.set_matrix(
+                gc_context,
+                Matrix::create_box(
+                    scale_x as f32,
+                    scale_y as f32,
+                    bounds.offset_x() - origin.x(),
+                    bounds.offset_y() - origin.y(),
+                ),
+            );
+        }
+    }
+
+    /// Returns the display object of the image declared with the given `id`.
+    pub fn image_reference(self, id: &WStr) -> Option<DisplayObject<'gc>> {
+        let edit_text = self.0.read();
+        edit_text
+            .text_spans
+            .images()
+            .iter()
+            .zip(&edit_text.images)
+            .find(|(image, _)| image.id[..] == *id)
+            .and_then(|(_, object)| *object)
     }
 
     /// Measure the width and height of the `EditText`'s current text load.
@@ -921,7 +1161,14 @@ impl<'gc> EditText<'gc> {
         });
 
         let focused = self.has_focus();
-        let visible_selection = if focused {
+        let composition = edit_text
+            .ime_composition
+            .as_ref()
+            .filter(|_| focused && edit_text.composed_text_spans.is_some());
+        let visible_selection = if let Some(composition) = composition {
+            // While composing, only the caret within the composition is shown.
+            Some(TextSelection::for_position(composition.caret()))
+        } else if focused {
             edit_text.selection
         } else if self.always_show_selection() {
             // Caret is not shown even if alwaysShowSelection is true
@@ -932,7 +1179,7 @@ impl<'gc> EditText<'gc> {
 
         let caret = if let LayoutContent::Text { start, end, .. } = &lbox.content() {
             if let Some(visible_selection) = visible_selection {
-                let text_len = edit_text.text_spans.text().len();
+                let text_len = edit_text.layout_text_spans().text().len();
                 if visible_selection.is_caret()
                     && !edit_text.flags.contains(EditTextFlag::READ_ONLY)
                     && visible_selection.start() >= *start
@@ -961,7 +1208,7 @@ impl<'gc> EditText<'gc> {
         // Instead, we embed an SWF version of Noto Sans to use as the "device font", and render
         // it the same as any other SWF outline text.
         if let Some((text, _tf, font, params, color)) =
-            lbox.as_renderable_text(edit_text.text_spans.displayed_text())
+            lbox.as_renderable_text(edit_text.layout_text_spans().displayed_text())
         {
             let baseline = font.get_baseline_for_height(params.height());
             let descent = font.get_descent_for_height(params.height());
@@ -995,6 +1242,20 @@ impl<'gc> EditText<'gc> {
                         context.transform_stack.pop();
                     }
 
+                    // Underline the text being composed, with a thicker line for the part being converted.
+                    if let Some(composition) = composition {
+                        if composition.contains(start + pos) {
+                            let thickness = if composition.is_converting(start + pos) {
+                                2.0
+                            } else {
+                                1.0
+                            };
+                            self.render_composition_underline(
+                                context, x, advance, baseline, thickness, color,
+                            );
+                        }
+                    }
+
                     // Update caret position
                     if let Some(caret) = caret {
                         if pos == caret {
@@ -1038,6 +1299,25 @@ impl<'gc> EditText<'gc> {
         context.commands.draw_rect(color, selection_box);
     }
 
+    fn render_composition_underline(
+        self,
+        context: &mut RenderContext<'_, 'gc>,
+        x: Twips,
+        width: Twips,
+        baseline: Twips,
+        thickness: f32,
+        color: Color,
+    ) {
+        let underline = context.transform_stack.transform().matrix
+            * Matrix::create_box(
+                width.to_pixels() as f32,
+                thickness,
+                x,
+                baseline + Twips::ONE,
+            );
+        context.commands.draw_rect(color, underline);
+    }
+
     fn render_caret(
         self,
         context: &mut RenderContext<'_, 'gc>,
@@ -1758,6 +2038,167 @@ impl<'gc> EditText<'gc> {
         self.on_changed(&mut activation);
     }
 
+    /// Sets the text being composed with an input method.
+    ///
+    /// The composition is displayed in place of the selection, but does not become
+    /// part of the text until it is committed. An empty text ends the composition.
+    /// `cursor` is relative to the start of the composition.
+    pub fn set_ime_composition(
+        self,
+        text: WString,
+        cursor: Option<(usize, usize)>,
+        context: &mut UpdateContext<'gc>,
+    ) {
+        if !self.is_editable() {
+            return;
+        }
+
+        let started = self.0.read().ime_composition.is_none();
+        let composition = (!text.is_empty()).then(|| {
+            let cursor = cursor.map(|(from, to)| (from.min(text.len()), to.min(text.len())));
+            ImeComposition {
+                text,
+                cursor,
+                start: 0,
+            }
+        });
+        let is_composing = composition.is_some();
+        if started && !is_composing {
+            return;
+        }
+
+        self.0.write(context.gc_context).ime_composition = composition;
+        self.relayout(context);
+
+        if started {
+            if let Avm2Value::Object(target) = self.object2() {
+                let mut activation = Avm2Activation::from_nothing(context);
+                let ime_evt = Avm2EventObject::ime_event(
+                    &mut activation,
+                    "imeStartComposition",
+                    AvmString::default(),
+                    true,
+                    false,
+                );
+                Avm2::dispatch_event(activation.context, ime_evt, target);
+            }
+        }
+
+        if is_composing {
+            self.update_ime_cursor_area(context);
+        }
+    }
+
+    /// Changes which part of the current composition the input method is working on.
+    pub fn set_ime_composition_cursor(self, from: usize, to: usize, gc_context: &Mutation<'gc>) {
+        let mut edit_text = self.0.write(gc_context);
+        if let Some(composition) = edit_text.ime_composition.as_mut() {
+            let len = composition.text.len();
+            composition.cursor = Some((from.min(len), to.min(len)));
+        }
+        drop(edit_text);
+        self.invalidate_cached_bitmap(gc_context);
+    }
+
+    /// Inserts the text currently being composed, as if it was typed in.
+    pub fn commit_ime_composition(self, context: &mut UpdateContext<'gc>) {
+        let Some(composition) = self.0.read().ime_composition.clone() else {
+            return;
+        };
+        self.commit_ime_text(&composition.text, context);
+    }
+
+    /// Ends the current composition (if any), and inserts the text the input method produced.
+    pub fn commit_ime_text(self, text: &WStr, context: &mut UpdateContext<'gc>) {
+        if self.0.read().ime_composition.is_some() {
+            self.0.write(context.gc_context).ime_composition = None;
+            self.relayout(context);
+        }
+
+        if text.is_empty() || !self.is_editable() {
+            return;
+        }
+
+        // `IMEEvent.IME_COMPOSITION` is dispatched to `System.ime`.
+        if self.movie().is_action_script_3() {
+            let mut activation = Avm2Activation::from_nothing(context);
+            let system = activation.avm2().classes().system;
+            if let Ok(Avm2Value::Object(ime)) = system.get_public_property("ime", &mut activation) {
+                let text = AvmString::new(activation.context.gc_context, text);
+                let ime_evt = Avm2EventObject::ime_event(
+                    &mut activation,
+                    "imeComposition",
+                    text,
+                    false,
+                    false,
+                );
+                Avm2::dispatch_event(activation.context, ime_evt, ime);
+            }
+        }
+
+        for character in text.chars() {
+            self.text_input(character.unwrap_or(char::REPLACEMENT_CHARACTER), context);
+        }
+    }
+
+    /// Tells the UI where text is being entered, so that the candidate window
+    /// of the input method can be placed next to it.
+    pub fn update_ime_cursor_area(self, context: &mut UpdateContext<'gc>) {
+        if let Some(bounds) = self.caret_bounds() {
+            let bounds = context.stage.view_matrix() * bounds;
+            context.ui.set_ime_cursor_area(bounds);
+        }
+    }
+
+    /// The bounds of the caret (or the start of the composition, while composing),
+    /// in stage coordinates.
+    fn caret_bounds(self) -> Option<Rectangle<Twips>> {
+        let edit_text = self.0.read();
+        let position = match &edit_text.ime_composition {
+            Some(composition) => composition.start,
+            None => edit_text.selection?.start(),
+        };
+
+        let layout_box = edit_text.layout.boxes_iter().find(|layout_box| {
+            layout_box.is_text_box()
+                && layout_box.start() <= position
+                && position <= layout_box.end()
+        })?;
+        let (text, _tf, font, params, color) =
+            layout_box.as_renderable_text(edit_text.layout_text_spans().displayed_text())?;
+
+        let offset = position - layout_box.start();
+        let mut caret_x = Twips::ZERO;
+        font.evaluate(
+            text,
+            self.text_transform(color, Twips::ZERO),
+            params,
+            |pos, _transform, _glyph: &Glyph, advance, x| {
+                if pos < offset {
+                    caret_x = x + advance;
+                }
+            },
+        );
+        let height = font.get_baseline_for_height(params.height())
+            + font.get_descent_for_height(params.height());
+
+        let origin = layout_box.bounds().origin();
+        let padding = Twips::from_pixels(Self::INTERNAL_PADDING);
+        let x = edit_text.bounds.x_min + padding - Twips::from_pixels(edit_text.hscroll)
+            + origin.x()
+            + caret_x;
+        let y = edit_text.bounds.y_min + padding - edit_text.vertical_scroll_offset() + origin.y();
+        let bounds = Rectangle {
+            x_min: x,
+            x_max: x + Twips::ONE,
+            y_min: y,
+            y_max: y + height,
+        };
+        drop(edit_text);
+
+        Some(self.local_to_global_matrix() * bounds)
+    }
+
     fn initialize_as_broadcaster(&self, activation: &mut Avm1Activation<'_, 'gc>) {
         if let Avm1Value::Object(object) = self.object() {
             activation.context.avm1.broadcaster_functions().initialize(
@@ -2115,6 +2556,18 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         self.0.read().static_data.swf.clone()
     }
 
+    fn enter_frame(&self, context: &mut UpdateContext<'gc>) {
+        let images = self.0.read().images.clone();
+        for image in images.iter().flatten() {
+            image.enter_frame(context);
+        }
+
+        // Images take their natural size once loaded, and text flows around them.
+        if self.image_sizes() != self.0.read().image_sizes {
+            self.relayout(context);
+        }
+    }
+
     /// Construct objects placed on this frame.
     fn construct_frame(&self, context: &mut UpdateContext<'gc>) {
         if self.movie().is_action_script_3() && matches!(self.object2(), Avm2Value::Null) {
@@ -2150,6 +2603,11 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
                 .add_to_exec_list(context.gc_context, (*self).into());
             self.construct_as_avm1_object(context, run_frame);
         }
+
+        if !self.0.read().text_spans.images().is_empty() {
+            self.load_images(context);
+            self.relayout(context);
+        }
     }
 
     fn object(&self) -> Avm1Value<'gc> {
@@ -2191,6 +2649,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         edit_text.base.base.set_x(x - offset);
         drop(edit_text);
         self.invalidate_cached_bitmap(gc_context);
+        self.invalidate_accessibility(gc_context);
     }
 
     fn y(&self) -> Twips {
@@ -2205,6 +2664,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         edit_text.base.base.set_y(y - offset);
         drop(edit_text);
         self.invalidate_cached_bitmap(gc_context);
+        self.invalidate_accessibility(gc_context);
     }
 
     fn width(&self) -> f64 {
@@ -2240,6 +2700,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     fn set_matrix(&self, gc_context: &Mutation<'gc>, matrix: Matrix) {
         self.0.write(gc_context).base.base.set_matrix(matrix);
         self.invalidate_cached_bitmap(gc_context);
+        self.invalidate_accessibility(gc_context);
     }
 
     fn render_self(&self, context: &mut RenderContext<'_, 'gc>) {
@@ -2332,6 +2793,10 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
                 }
                 self.render_layout_box(context, layout_box);
             }
+
+            for image in edit_text.images.iter().flatten() {
+                image.render(context);
+            }
         }
 
         context.transform_stack.pop();
@@ -2676,12 +3141,32 @@ impl<'gc> TInteractiveObject<'gc> for EditText<'gc> {
         &self,
         context: &mut UpdateContext<'gc>,
         focused: bool,
-        _other: Option<InteractiveObject<'gc>>,
+        other: Option<InteractiveObject<'gc>>,
     ) {
         let is_avm1 = !self.movie().is_action_script_3();
         if !focused && is_avm1 {
             self.set_selection(None, context.gc_context);
         }
+
+        if focused {
+            context.ui.set_ime_enabled(self.is_editable());
+            if self.is_editable() {
+                self.update_ime_cursor_area(context);
+            }
+        } else {
+            // Any composition in progress is abandoned when losing focus.
+            if self.0.read().ime_composition.is_some() {
+                self.0.write(context.gc_context).ime_composition = None;
+                self.relayout(context);
+            }
+
+            let other_is_editable = other
+                .and_then(|other| other.as_displayobject().as_edit_text())
+                .is_some_and(|text| text.is_editable());
+            if !other_is_editable {
+                context.ui.set_ime_enabled(false);
+            }
+        }
     }
 
     fn is_focusable_by_mouse(&self, _context: &mut UpdateContext<'gc>) -> bool {
@@ -2783,6 +3268,41 @@ impl TextSelectionMode {
     }
 }
 
+/// Text being composed with an input method, displayed in place of the selection
+/// until it is committed.
+#[derive(Clone, Debug)]
+struct ImeComposition {
+    /// The composed text.
+    text: WString,
+
+    /// The part of the composition the input method is working on, relative to its start.
+    /// When it is empty, it is only the position of the caret.
+    cursor: Option<(usize, usize)>,
+
+    /// The position of the composition in the laid out text.
+    start: usize,
+}
+
+impl ImeComposition {
+    fn contains(&self, position: usize) -> bool {
+        position >= self.start && position < self.start + self.text.len()
+    }
+
+    fn is_converting(&self, position: usize) -> bool {
+        match self.cursor {
+            Some((from, to)) if from != to => {
+                position >= self.start + from && position < self.start + to
+            }
+            _ => false,
+        }
+    }
+
+    /// The position of the caret in the laid out text.
+    fn caret(&self) -> usize {
+        self.start + self.cursor.map_or(self.text.len(), |(_, to)| to)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct TextSelection {
     from: usize,
@@ -107,6 +107,16 @@ impl<'gc> Graphic<'gc> {
             w.drawing.get_or_insert_with(Drawing::new)
         })
     }
+
+    /// The shape this graphic was defined with, if it hasn't been drawn into since.
+    pub fn static_shape(&self) -> Option<swf::Shape> {
+        let read = self.0.read();
+        if read.drawing.is_none() {
+            Some(read.static_data.shape.clone())
+        } else {
+            None
+        }
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
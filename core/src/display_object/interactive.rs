@@ -1,5 +1,6 @@
 //! Interactive object enumtrait
 
+use crate::accessibility::AccessibilityImplementation;
 use crate::avm1::Avm1;
 use crate::avm1::Value as Avm1Value;
 use crate::avm2::activation::Activation as Avm2Activation;
@@ -94,6 +95,13 @@ pub struct InteractiveObjectBase<'gc> {
 
     /// Specifies whether this object displays a yellow rectangle when focused.
     focus_rect: Option<bool>,
+
+    /// The AVM2 `AccessibilityImplementation` of this object.
+    accessibility_implementation: Avm2Value<'gc>,
+
+    /// The answers of the `AccessibilityImplementation`, kept until this object is invalidated.
+    #[collect(require_static)]
+    accessibility_cache: Option<AccessibilityImplementation>,
 }
 
 impl<'gc> Default for InteractiveObjectBase<'gc> {
@@ -105,6 +113,8 @@ impl<'gc> Default for InteractiveObjectBase<'gc> {
             tab_enabled: None,
             tab_index: None,
             focus_rect: None,
+            accessibility_implementation: Avm2Value::Null,
+            accessibility_cache: None,
         }
     }
 }
@@ -178,6 +188,30 @@ pub trait TInteractiveObject<'gc>:
         self.raw_interactive_mut(mc).context_menu = value;
     }
 
+    fn accessibility_implementation(self) -> Avm2Value<'gc> {
+        self.raw_interactive().accessibility_implementation
+    }
+
+    fn set_accessibility_implementation(self, mc: &Mutation<'gc>, value: Avm2Value<'gc>) {
+        self.raw_interactive_mut(mc).accessibility_implementation = value;
+        self.as_displayobject().invalidate_accessibility(mc);
+    }
+
+    /// The cached answers of the `AccessibilityImplementation` of this object, if any.
+    fn accessibility_cache(self) -> Option<AccessibilityImplementation> {
+        self.raw_interactive().accessibility_cache.clone()
+    }
+
+    fn set_accessibility_cache(self, mc: &Mutation<'gc>, value: AccessibilityImplementation) {
+        self.raw_interactive_mut(mc).accessibility_cache = Some(value);
+    }
+
+    fn clear_accessibility_cache(self, mc: &Mutation<'gc>) {
+        if self.raw_interactive().accessibility_cache.is_some() {
+            self.raw_interactive_mut(mc).accessibility_cache = None;
+        }
+    }
+
     /// Get the boolean flag which determines whether objects display a glowing border
     /// when they have focus.
     fn focus_rect(self) -> Option<bool> {
@@ -106,6 +106,10 @@ pub struct StageData<'gc> {
     /// Whether or not a RENDER event should be dispatched on the next render
     invalidated: bool,
 
+    /// Whether the accessibility tree has to be rebuilt, because anything below the stage
+    /// changed since it was last built.
+    accessibility_invalidated: bool,
+
     /// Whether to use high quality downsampling for bitmaps.
     ///
     /// This is usually implied by `quality` being `Best` or higher, but the AVM1
@@ -175,6 +179,7 @@ impl<'gc> Stage<'gc> {
                     StageDisplayState::Normal
                 },
                 invalidated: false,
+                accessibility_invalidated: false,
                 align: Default::default(),
                 forced_align: false,
                 allow_fullscreen: true,
@@ -211,7 +216,6 @@ impl<'gc> Stage<'gc> {
             .unwrap_or(Matrix::ZERO)
     }
 
-    #[allow(dead_code)]
     pub fn view_matrix(self) -> Matrix {
         self.0.read().viewport_matrix
     }
@@ -255,6 +259,20 @@ impl<'gc> Stage<'gc> {
         self.0.write(gc_context).invalidated = value;
     }
 
+    /// Whether the accessibility tree has to be rebuilt.
+    pub fn accessibility_invalidated(self) -> bool {
+        self.0.read().accessibility_invalidated
+    }
+
+    /// Marks the accessibility tree as outdated (or up-to-date).
+    ///
+    /// Changes of display objects on the stage go through
+    /// [`TDisplayObject::invalidate_accessibility`] instead, this is only needed
+    /// for changes which don't belong to a single object, such as focus changes.
+    pub fn set_accessibility_invalidated(self, gc_context: &Mutation<'gc>, value: bool) {
+        self.0.write(gc_context).accessibility_invalidated = value;
+    }
+
     /// Returns the quality setting of the stage.
     ///
     /// In the Flash Player, the quality setting affects anti-aliasing and smoothing of bitmaps.
@@ -3,8 +3,10 @@ use crate::avm2::{
 };
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr};
-use crate::font::TextRenderSettings;
+use crate::font::{Font, TextRenderSettings};
+use crate::library::Library;
 use crate::prelude::*;
+use crate::string::WString;
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::Instantiator;
 use core::fmt;
@@ -34,6 +36,14 @@ pub struct TextData<'gc> {
     #[collect(require_static)]
     render_settings: TextRenderSettings,
     avm2_object: Option<Avm2Object<'gc>>,
+
+    /// Which characters have been selected through `TextSnapshot.setSelected`.
+    #[collect(require_static)]
+    selection: Vec<bool>,
+
+    /// The color used to highlight selected characters.
+    #[collect(require_static)]
+    select_color: swf::Color,
 }
 
 impl<'gc> Text<'gc> {
@@ -58,6 +68,8 @@ impl<'gc> Text<'gc> {
                 ),
                 render_settings: Default::default(),
                 avm2_object: None,
+                selection: Vec::new(),
+                select_color: swf::Color::from_rgb(0xFFFF00, 255),
             },
         ))
     }
@@ -66,6 +78,139 @@ impl<'gc> Text<'gc> {
         self.0.write(gc_context).render_settings = settings;
         self.invalidate_cached_bitmap(gc_context);
     }
+
+    /// The matrix applied to all text records of this text.
+    pub fn text_transform(self) -> Matrix {
+        self.0.read().static_data.text_transform
+    }
+
+    /// Lists every glyph of this text, in the order of its text records.
+    ///
+    /// Glyphs whose font is missing are skipped, as they can't be rendered either.
+    pub fn glyphs(self, library: &Library<'gc>) -> Vec<StaticGlyph<'gc>> {
+        let tf = self.0.read();
+        let Some(library) = library.library_for_movie(tf.static_data.swf.clone()) else {
+            return Vec::new();
+        };
+
+        let mut glyphs = Vec::new();
+        let mut color = swf::Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        };
+        let mut font_id = 0;
+        let mut height = Twips::ZERO;
+        let mut x = Twips::ZERO;
+        let mut y = Twips::ZERO;
+        let mut new_line = false;
+        for block in &tf.static_data.text_blocks {
+            if let Some(x_offset) = block.x_offset {
+                x = x_offset;
+            }
+            if let Some(y_offset) = block.y_offset {
+                new_line |= !glyphs.is_empty() && y_offset != y;
+                y = y_offset;
+            }
+            color = block.color.unwrap_or(color);
+            font_id = block.font_id.unwrap_or(font_id);
+            height = block.height.unwrap_or(height);
+            if let Some(font) = library.get_font(font_id) {
+                for c in &block.glyphs {
+                    if let Some(glyph) = font.get_glyph(c.index as usize) {
+                        glyphs.push(StaticGlyph {
+                            index: c.index as usize,
+                            character: glyph.character(),
+                            font,
+                            color,
+                            height,
+                            x,
+                            y,
+                            advance: Twips::new(c.advance),
+                            new_line: std::mem::take(&mut new_line),
+                        });
+                        x += Twips::new(c.advance);
+                    }
+                }
+            }
+        }
+        glyphs
+    }
+
+    /// The characters of this text, as mapped back from its glyphs.
+    pub fn text(self, library: &Library<'gc>) -> WString {
+        let mut text = WString::new();
+        for glyph in self.glyphs(library) {
+            text.push_char(glyph.character);
+        }
+        text
+    }
+
+    pub fn is_char_selected(self, index: usize) -> bool {
+        self.0.read().selection.get(index).copied().unwrap_or(false)
+    }
+
+    pub fn set_char_selected(self, gc_context: &Mutation<'gc>, index: usize, selected: bool) {
+        let mut write = self.0.write(gc_context);
+        if write.selection.len() <= index {
+            if !selected {
+                return;
+            }
+            write.selection.resize(index + 1, false);
+        }
+        write.selection[index] = selected;
+        drop(write);
+        self.invalidate_cached_bitmap(gc_context);
+    }
+
+    pub fn set_select_color(self, gc_context: &Mutation<'gc>, color: swf::Color) {
+        self.0.write(gc_context).select_color = color;
+        self.invalidate_cached_bitmap(gc_context);
+    }
+}
+
+/// A single glyph of a static text, along with the style of the record it belongs to.
+#[derive(Clone)]
+pub struct StaticGlyph<'gc> {
+    /// The index of this glyph in its font.
+    pub index: usize,
+    pub character: char,
+    pub font: Font<'gc>,
+    pub color: swf::Color,
+    pub height: Twips,
+    /// The position of this glyph's origin on the baseline, before the text transform.
+    pub x: Twips,
+    pub y: Twips,
+    pub advance: Twips,
+    /// Whether this glyph begins a new line of text.
+    pub new_line: bool,
+}
+
+impl StaticGlyph<'_> {
+    /// The matrix transforming this glyph's shape into text space.
+    pub fn matrix(&self) -> Matrix {
+        let scale = (self.height.get() as f32) / self.font.scale();
+        Matrix::create_box(scale, scale, self.x, self.y)
+    }
+
+    /// The box this glyph occupies, from ascent to descent, before the text transform.
+    pub fn bounds(&self) -> Rectangle<Twips> {
+        let mut ascent = self.font.get_baseline_for_height(self.height);
+        let mut descent = self.font.get_descent_for_height(self.height);
+        if ascent == Twips::ZERO && descent == Twips::ZERO {
+            // Fonts used by static text usually come without layout information,
+            // so fall back to the EM square sitting on the baseline.
+            ascent = self.height;
+            descent = Twips::ZERO;
+        }
+        Rectangle {
+            x_min: self.x,
+            x_max: self.x + self.advance,
+            y_min: self.y - ascent,
+            y_max: self.y + descent,
+        }
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Text<'gc> {
@@ -104,62 +249,46 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
             tracing::warn!("PlaceObject: expected text at character ID {}", id);
         }
         self.invalidate_cached_bitmap(context.gc_context);
+        self.invalidate_accessibility(context.gc_context);
     }
 
     fn run_frame_avm1(&self, _context: &mut UpdateContext) {
         // Noop
     }
 
-    fn render_self(&self, context: &mut RenderContext) {
-        let tf = self.0.read();
+    fn render_self(&self, context: &mut RenderContext<'_, 'gc>) {
         context.transform_stack.push(&Transform {
-            matrix: tf.static_data.text_transform,
+            matrix: self.text_transform(),
             ..Default::default()
         });
 
-        let mut color = swf::Color {
-            r: 0,
-            g: 0,
-            b: 0,
-            a: 0,
-        };
-        let mut font_id = 0;
-        let mut height = Twips::ZERO;
+        let select_color = self.0.read().select_color;
         let mut transform: Transform = Default::default();
-        for block in &tf.static_data.text_blocks {
-            if let Some(x) = block.x_offset {
-                transform.matrix.tx = x;
+        for (i, glyph) in self.glyphs(context.library).iter().enumerate() {
+            if self.is_char_selected(i) {
+                let bounds = glyph.bounds();
+                let selection_box = context.transform_stack.transform().matrix
+                    * Matrix::create_box(
+                        bounds.width().to_pixels() as f32,
+                        bounds.height().to_pixels() as f32,
+                        bounds.x_min,
+                        bounds.y_min,
+                    );
+                context.commands.draw_rect(select_color, selection_box);
             }
-            if let Some(y) = block.y_offset {
-                transform.matrix.ty = y;
-            }
-            color = block.color.unwrap_or(color);
-            font_id = block.font_id.unwrap_or(font_id);
-            height = block.height.unwrap_or(height);
-            if let Some(font) = context
-                .library
-                .library_for_movie(self.movie())
-                .unwrap()
-                .get_font(font_id)
-            {
-                let scale = (height.get() as f32) / font.scale();
-                transform.matrix.a = scale;
-                transform.matrix.d = scale;
-                transform.color_transform.set_mult_color(&color);
-                for c in &block.glyphs {
-                    if let Some(glyph) = font.get_glyph(c.index as usize) {
-                        if let Some(glyph_shape_handle) = glyph.shape_handle(context.renderer) {
-                            context.transform_stack.push(&transform);
-                            context.commands.render_shape(
-                                glyph_shape_handle,
-                                context.transform_stack.transform(),
-                            );
-                            context.transform_stack.pop();
-                        }
 
-                        transform.matrix.tx += Twips::new(c.advance);
-                    }
-                }
+            transform.matrix = glyph.matrix();
+            transform.color_transform.set_mult_color(&glyph.color);
+            if let Some(glyph_shape_handle) = glyph
+                .font
+                .get_glyph(glyph.index)
+                .and_then(|g| g.shape_handle(context.renderer))
+            {
+                context.transform_stack.push(&transform);
+                context
+                    .commands
+                    .render_shape(glyph_shape_handle, context.transform_stack.transform());
+                context.transform_stack.pop();
             }
         }
         context.transform_stack.pop();
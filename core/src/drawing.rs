@@ -1,9 +1,14 @@
 use crate::context::RenderContext;
-use ruffle_render::backend::{RenderBackend, ShapeHandle};
-use ruffle_render::bitmap::{BitmapHandle, BitmapInfo, BitmapSize, BitmapSource};
+use ruffle_render::backend::{PixelBenderOutput, PixelBenderTarget, RenderBackend, ShapeHandle};
+use ruffle_render::bitmap::{
+    Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, BitmapSize, BitmapSource,
+};
 use ruffle_render::commands::CommandHandler;
+use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender::{PixelBenderShaderArgument, PixelBenderShaderHandle};
 use ruffle_render::shape_utils::{
     cubic_curve_bounds, quadratic_curve_bounds, DistilledShape, DrawCommand, DrawPath, FillRule,
+    TexturedVertex,
 };
 use std::cell::{Cell, RefCell};
 use swf::{FillStyle, LineStyle, Point, Rectangle, Twips};
@@ -15,7 +20,7 @@ pub struct Drawing {
     edge_bounds: Rectangle<Twips>,
     dirty: Cell<bool>,
     paths: Vec<DrawingPath>,
-    bitmaps: Vec<BitmapInfo>,
+    bitmaps: Vec<DrawingBitmap>,
     current_fill: Option<DrawingFill>,
     current_line: Option<DrawingLine>,
     pending_lines: Vec<DrawingLine>,
@@ -97,6 +102,11 @@ impl Drawing {
 
                     this.set_fill_style(None);
                 }
+                DrawPath::TexturedTriangles { style, vertices } => {
+                    this.new_fill(Some(style.clone()), None);
+                    this.draw_textured_triangles(vertices);
+                    this.set_fill_style(None);
+                }
             }
         }
 
@@ -235,82 +245,308 @@ impl Drawing {
                 stretch_bounds(&self.shape_bounds, &command, stroke_width, self.cursor);
             self.edge_bounds =
                 stretch_bounds(&self.edge_bounds, &command, Twips::ZERO, self.cursor);
+            self.update_shader_fills();
         }
 
         self.cursor = command.end_point();
         self.dirty.set(true);
     }
 
+    /// Returns the style of the current fill, if any.
+    pub fn fill_style(&self) -> Option<&FillStyle> {
+        self.current_fill.as_ref().map(|fill| &fill.style)
+    }
+
+    /// Draws triangles textured with the current fill, which should be a bitmap fill.
+    ///
+    /// The outlines of the triangles are still stroked by the current line style.
+    pub fn draw_textured_triangles(&mut self, vertices: Vec<TexturedVertex>) {
+        let Some(fill) = self.current_fill.as_ref() else {
+            return;
+        };
+        let style = fill.style.clone();
+        let rule = fill.rule;
+
+        // The triangles are their own path, so end the current fill around them.
+        self.new_fill(None, None);
+        let commands = TexturedVertex::triangle_commands(&vertices);
+        self.paths
+            .push(DrawingPath::TexturedTriangles(DrawingTriangles {
+                style: style.clone(),
+                vertices,
+            }));
+        for command in commands {
+            self.draw_command(command);
+        }
+        self.new_fill(Some(style), Some(rule));
+    }
+
     pub fn add_bitmap(&mut self, bitmap: BitmapInfo) -> u16 {
         let id = self.bitmaps.len() as u16;
-        self.bitmaps.push(bitmap);
+        self.bitmaps.push(DrawingBitmap::Bitmap(bitmap));
         id
     }
 
-    pub fn register_or_replace(&self, renderer: &mut dyn RenderBackend) -> ShapeHandle {
-        if self.dirty.get() || self.render_handle.borrow().is_none() {
-            self.dirty.set(false);
-            let mut paths = Vec::with_capacity(self.paths.len());
-
-            for path in &self.paths {
-                match path {
-                    DrawingPath::Fill(fill) => {
-                        paths.push(DrawPath::Fill {
-                            style: &fill.style,
-                            commands: fill.commands.to_owned(),
-                            winding_rule: fill.rule,
-                        });
-                    }
-                    DrawingPath::Line(line) => {
-                        paths.push(DrawPath::Stroke {
-                            style: &line.style,
-                            commands: line.commands.to_owned(),
-                            is_closed: line.is_closed,
-                        });
+    /// Creates a fill style that evaluates a Pixel Bender shader for every pixel.
+    ///
+    /// `matrix` maps shader coordinates to the coordinate space of the drawing.
+    /// The shader is evaluated into a bitmap once the drawing is rendered, covering
+    /// the whole drawing, wherever it lies in shader coordinates.
+    pub fn add_shader_fill(
+        &mut self,
+        shader: PixelBenderShaderHandle,
+        arguments: Vec<PixelBenderShaderArgument<'static>>,
+        matrix: Matrix,
+    ) -> FillStyle {
+        let id = self.bitmaps.len() as u16;
+        let (origin, _) = self.shader_fill_area(&matrix);
+        self.bitmaps.push(DrawingBitmap::Shader(ShaderFill {
+            shader,
+            arguments,
+            matrix,
+            origin,
+            output: RefCell::new(None),
+        }));
+        FillStyle::Bitmap {
+            id,
+            matrix: shader_fill_style_matrix(matrix, origin),
+            is_smoothed: true,
+            is_repeating: false,
+        }
+    }
+
+    /// The area a shader fill with the given matrix is evaluated over.
+    ///
+    /// This is the origin and size, in shader coordinates, of the smallest area
+    /// of whole pixels that covers the drawing.
+    fn shader_fill_area(&self, matrix: &Matrix) -> ((i32, i32), BitmapSize) {
+        let bounds = match matrix.inverse() {
+            Some(inverse) if self.edge_bounds.is_valid() => inverse * self.edge_bounds.clone(),
+            _ => Default::default(),
+        };
+        if !bounds.is_valid() {
+            let size = BitmapSize {
+                width: 1,
+                height: 1,
+            };
+            return ((0, 0), size);
+        }
+        let axis = |min: Twips, max: Twips| {
+            let origin = min.to_pixels().floor();
+            let size = (max.to_pixels().ceil() - origin).clamp(1.0, MAX_SHADER_FILL_SIZE);
+            (origin as i32, size as u16)
+        };
+        let (x, width) = axis(bounds.x_min, bounds.x_max);
+        let (y, height) = axis(bounds.y_min, bounds.y_max);
+        ((x, y), BitmapSize { width, height })
+    }
+
+    /// Moves the areas shader fills are evaluated over along with the bounds of the drawing,
+    /// and offsets the matrices of the fill styles using them to match.
+    fn update_shader_fills(&mut self) {
+        for id in 0..self.bitmaps.len() {
+            let DrawingBitmap::Shader(fill) = &self.bitmaps[id] else {
+                continue;
+            };
+            let (origin, _) = self.shader_fill_area(&fill.matrix);
+            if origin == fill.origin {
+                continue;
+            }
+            let style_matrix = shader_fill_style_matrix(fill.matrix, origin);
+            if let DrawingBitmap::Shader(fill) = &mut self.bitmaps[id] {
+                fill.origin = origin;
+                fill.output.replace(None);
+            }
+
+            let styles = self
+                .paths
+                .iter_mut()
+                .filter_map(|path| match path {
+                    DrawingPath::Fill(fill) => Some(&mut fill.style),
+                    DrawingPath::TexturedTriangles(triangles) => Some(&mut triangles.style),
+                    DrawingPath::Line(_) => None,
+                })
+                .chain(self.current_fill.as_mut().map(|fill| &mut fill.style));
+            for style in styles {
+                if let FillStyle::Bitmap {
+                    id: style_id,
+                    matrix,
+                    ..
+                } = style
+                {
+                    if *style_id as usize == id {
+                        *matrix = style_matrix;
                     }
                 }
             }
+        }
+    }
 
-            if let Some(fill) = &self.current_fill {
-                paths.push(DrawPath::Fill {
-                    style: &fill.style,
-                    commands: fill.commands.to_owned(),
-                    winding_rule: fill.rule,
-                })
+    /// Evaluates a shader fill, on the GPU if the backend can render into a bitmap,
+    /// and otherwise by reading back the shader output.
+    fn evaluate_shader_fill(
+        &self,
+        fill: &ShaderFill,
+        backend: &mut dyn RenderBackend,
+    ) -> Option<BitmapInfo> {
+        let (_, size) = self.shader_fill_area(&fill.matrix);
+        if let Some(output) = &*fill.output.borrow() {
+            if output.width == size.width && output.height == size.height {
+                return Some(output.clone());
             }
+        }
 
-            for line in &self.pending_lines {
-                let mut commands = line.commands.to_owned();
-                let is_closed = if self.current_fill.is_some() {
-                    commands.push(DrawCommand::LineTo(self.fill_start));
-                    true
-                } else {
-                    self.cursor == self.fill_start
-                };
-                paths.push(DrawPath::Stroke {
-                    style: &line.style,
-                    commands,
-                    is_closed,
-                })
+        let shader = if fill.origin == (0, 0) {
+            fill.shader.clone()
+        } else {
+            let (x, y) = fill.origin;
+            let shader = fill
+                .shader
+                .0
+                .parsed_shader()
+                .with_out_coord_offset((x as f32, y as f32));
+            match backend.compile_pixelbender_shader(shader) {
+                Ok(shader) => shader,
+                Err(e) => {
+                    tracing::warn!("Failed to compile shader fill: {e}");
+                    return None;
+                }
             }
+        };
 
-            if let Some(line) = &self.current_line {
-                let mut commands = line.commands.to_owned();
-                let is_closed = if self.current_fill.is_some() {
-                    commands.push(DrawCommand::LineTo(self.fill_start));
-                    true
-                } else {
-                    self.cursor == self.fill_start
+        let (width, height) = (size.width as u32, size.height as u32);
+        let handle = backend
+            .create_empty_texture(width, height)
+            .ok()
+            .filter(|handle| {
+                backend
+                    .run_pixelbender_shader(
+                        shader.clone(),
+                        &fill.arguments,
+                        &PixelBenderTarget::Bitmap(handle.clone()),
+                    )
+                    .is_ok()
+            });
+        let handle = match handle {
+            Some(handle) => handle,
+            None => {
+                let output = backend.run_pixelbender_shader(
+                    shader,
+                    &fill.arguments,
+                    &PixelBenderTarget::Bytes { width, height },
+                );
+                let pixels = match output {
+                    Ok(PixelBenderOutput::Bytes(pixels)) => pixels,
+                    Ok(PixelBenderOutput::Bitmap(_)) => return None,
+                    Err(e) => {
+                        tracing::warn!("Failed to evaluate shader fill: {e}");
+                        return None;
+                    }
                 };
-                paths.push(DrawPath::Stroke {
-                    style: &line.style,
-                    commands,
-                    is_closed,
-                })
+                let channels = pixels.len() / (width as usize * height as usize * 4);
+                let rgba: Vec<u8> = bytemuck::cast_slice::<u8, f32>(&pixels)
+                    .chunks_exact(channels.max(1))
+                    .flat_map(|pixel| {
+                        let alpha = pixel.get(3).copied().unwrap_or(1.0);
+                        [pixel[0], pixel[1], pixel[2], alpha]
+                            .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8)
+                    })
+                    .collect();
+                let bitmap = Bitmap::new(width, height, BitmapFormat::Rgba, rgba);
+                match backend.register_bitmap(bitmap) {
+                    Ok(handle) => handle,
+                    Err(e) => {
+                        tracing::warn!("Failed to register shader fill: {e}");
+                        return None;
+                    }
+                }
+            }
+        };
+
+        let output = BitmapInfo {
+            handle,
+            width: size.width,
+            height: size.height,
+        };
+        fill.output.replace(Some(output.clone()));
+        Some(output)
+    }
+
+    /// The paths of this drawing, in the order they are rendered.
+    pub fn paths(&self) -> Vec<DrawPath<'_>> {
+        let mut paths = Vec::with_capacity(self.paths.len());
+
+        for path in &self.paths {
+            match path {
+                DrawingPath::Fill(fill) => {
+                    paths.push(DrawPath::Fill {
+                        style: &fill.style,
+                        commands: fill.commands.to_owned(),
+                        winding_rule: fill.rule,
+                    });
+                }
+                DrawingPath::Line(line) => {
+                    paths.push(DrawPath::Stroke {
+                        style: &line.style,
+                        commands: line.commands.to_owned(),
+                        is_closed: line.is_closed,
+                    });
+                }
+                DrawingPath::TexturedTriangles(triangles) => {
+                    paths.push(DrawPath::TexturedTriangles {
+                        style: &triangles.style,
+                        vertices: triangles.vertices.to_owned(),
+                    });
+                }
             }
+        }
 
+        if let Some(fill) = &self.current_fill {
+            paths.push(DrawPath::Fill {
+                style: &fill.style,
+                commands: fill.commands.to_owned(),
+                winding_rule: fill.rule,
+            })
+        }
+
+        for line in &self.pending_lines {
+            let mut commands = line.commands.to_owned();
+            let is_closed = if self.current_fill.is_some() {
+                commands.push(DrawCommand::LineTo(self.fill_start));
+                true
+            } else {
+                self.cursor == self.fill_start
+            };
+            paths.push(DrawPath::Stroke {
+                style: &line.style,
+                commands,
+                is_closed,
+            })
+        }
+
+        if let Some(line) = &self.current_line {
+            let mut commands = line.commands.to_owned();
+            let is_closed = if self.current_fill.is_some() {
+                commands.push(DrawCommand::LineTo(self.fill_start));
+                true
+            } else {
+                self.cursor == self.fill_start
+            };
+            paths.push(DrawPath::Stroke {
+                style: &line.style,
+                commands,
+                is_closed,
+            })
+        }
+
+        paths
+    }
+
+    pub fn register_or_replace(&self, renderer: &mut dyn RenderBackend) -> ShapeHandle {
+        if self.dirty.get() || self.render_handle.borrow().is_none() {
+            self.dirty.set(false);
             let shape = DistilledShape {
-                paths,
+                paths: self.paths(),
                 shape_bounds: self.shape_bounds.clone(),
                 edge_bounds: self.edge_bounds.clone(),
                 id: 0,
@@ -360,6 +596,16 @@ impl Drawing {
                         return true;
                     }
                 }
+                DrawingPath::TexturedTriangles(triangles) => {
+                    // Test each triangle by itself, as they're filled with the non-zero rule.
+                    let commands = TexturedVertex::triangle_commands(&triangles.vertices);
+                    if commands
+                        .chunks_exact(4)
+                        .any(|triangle| shape_utils::draw_command_fill_hit_test(triangle, point))
+                    {
+                        return true;
+                    }
+                }
             }
         }
 
@@ -427,16 +673,53 @@ impl Drawing {
 
 impl BitmapSource for Drawing {
     fn bitmap_size(&self, id: u16) -> Option<BitmapSize> {
-        self.bitmaps.get(id as usize).map(|bm| BitmapSize {
-            width: bm.width,
-            height: bm.height,
-        })
+        match self.bitmaps.get(id as usize)? {
+            DrawingBitmap::Bitmap(bm) => Some(BitmapSize {
+                width: bm.width,
+                height: bm.height,
+            }),
+            DrawingBitmap::Shader(fill) => Some(self.shader_fill_area(&fill.matrix).1),
+        }
     }
-    fn bitmap_handle(&self, id: u16, _backend: &mut dyn RenderBackend) -> Option<BitmapHandle> {
-        self.bitmaps.get(id as usize).map(|bm| bm.handle.clone())
+    fn bitmap_handle(&self, id: u16, backend: &mut dyn RenderBackend) -> Option<BitmapHandle> {
+        match self.bitmaps.get(id as usize)? {
+            DrawingBitmap::Bitmap(bm) => Some(bm.handle.clone()),
+            DrawingBitmap::Shader(fill) => self
+                .evaluate_shader_fill(fill, backend)
+                .map(|output| output.handle),
+        }
     }
 }
 
+/// The largest width or height, in pixels, that a shader fill is evaluated over.
+const MAX_SHADER_FILL_SIZE: f64 = 4096.0;
+
+/// The matrix of a shader fill style, mapping the pixels of the evaluated area to the drawing.
+fn shader_fill_style_matrix(matrix: Matrix, origin: (i32, i32)) -> swf::Matrix {
+    let origin = Matrix::translate(
+        Twips::from_pixels(origin.0.into()),
+        Twips::from_pixels(origin.1.into()),
+    );
+    (matrix * origin * Matrix::PIXELS_TO_TWIPS).into()
+}
+
+#[derive(Debug, Clone)]
+enum DrawingBitmap {
+    Bitmap(BitmapInfo),
+    Shader(ShaderFill),
+}
+
+#[derive(Debug, Clone)]
+struct ShaderFill {
+    shader: PixelBenderShaderHandle,
+    arguments: Vec<PixelBenderShaderArgument<'static>>,
+    matrix: Matrix,
+    /// The shader coordinates of the top left pixel of the evaluated area.
+    origin: (i32, i32),
+    /// The last evaluated output, which is reused for as long as the area of the fill stays the same.
+    output: RefCell<Option<BitmapInfo>>,
+}
+
 #[derive(Debug, Clone)]
 struct DrawingFill {
     style: FillStyle,
@@ -451,10 +734,17 @@ struct DrawingLine {
     is_closed: bool,
 }
 
+#[derive(Debug, Clone)]
+struct DrawingTriangles {
+    style: FillStyle,
+    vertices: Vec<TexturedVertex>,
+}
+
 #[derive(Debug, Clone)]
 enum DrawingPath {
     Fill(DrawingFill),
     Line(DrawingLine),
+    TexturedTriangles(DrawingTriangles),
 }
 
 fn stretch_bounds(
@@ -1,7 +1,7 @@
 use crate::display_object::InteractiveObject;
 use swf::ClipEventFlag;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum PlayerEvent {
     KeyDown {
         key_code: KeyCode,
@@ -42,10 +42,24 @@ pub enum PlayerEvent {
     TextControl {
         code: TextControlCode,
     },
+    Ime(ImeEvent),
     FocusGained,
     FocusLost,
 }
 
+/// An event sent by an input method editor (IME) while the user composes text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// The text being composed has changed.
+    ///
+    /// The optional range is the cursor (or selected part) of the composition,
+    /// as UTF-8 byte offsets into the text. An empty text ends the composition.
+    Preedit(String, Option<(usize, usize)>),
+
+    /// The composition is done, and the given text should be inserted.
+    Commit(String),
+}
+
 /// The distance scrolled by the mouse wheel.
 #[derive(Debug, Clone, Copy)]
 pub enum MouseWheelDelta {
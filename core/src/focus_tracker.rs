@@ -160,6 +160,9 @@ impl<'gc> FocusTracker<'gc> {
         if !InteractiveObject::option_ptr_eq(old, new) {
             let focus = unlock!(Gc::write(context.gc(), self.0), FocusTrackerData, focus);
             focus.set(new);
+            context
+                .stage
+                .set_accessibility_invalidated(context.gc(), true);
 
             // The highlight always follows the focus.
             self.update_highlight(context);
@@ -209,6 +209,7 @@ impl FontFace {
                             shape_handle: Default::default(),
                             shape: GlyphShape::Drawing(drawing),
                             advance,
+                            character,
                         })
                     } else {
                         let advance = Twips::new(face.glyph_hor_advance(glyph_id)? as i32);
@@ -217,6 +218,7 @@ impl FontFace {
                             shape_handle: Default::default(),
                             shape: GlyphShape::None,
                             advance,
+                            character,
                         })
                     }
                 })
@@ -416,6 +418,7 @@ impl<'gc> Font<'gc> {
                     shape_handle: None.into(),
                     advance: Twips::new(swf_glyph.advance.into()),
                     shape: GlyphShape::Swf(RefCell::new(SwfGlyphOrShape::Glyph(swf_glyph))),
+                    character: char::from_u32(code.into()).unwrap_or(char::REPLACEMENT_CHARACTER),
                 };
 
                 // Eager-load ASCII characters.
@@ -808,6 +811,9 @@ pub struct Glyph {
 
     shape: GlyphShape,
     advance: Twips,
+
+    // The character this glyph was defined for.
+    character: char,
 }
 
 impl Glyph {
@@ -825,6 +831,10 @@ impl Glyph {
     pub fn advance(&self) -> Twips {
         self.advance
     }
+
+    pub fn character(&self) -> char {
+        self.character
+    }
 }
 
 /// Structure which identifies a particular font by name and properties.
@@ -4,7 +4,7 @@ use crate::context::UpdateContext;
 use crate::drawing::Drawing;
 use crate::font::{EvalParameters, Font, FontType};
 use crate::html::dimensions::{BoxBounds, Position, Size};
-use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
+use crate::html::text_format::{FormatSpans, TextFormat, TextImage, TextImageAlign, TextSpan};
 use crate::string::{utils as string_utils, WStr};
 use crate::tag_utils::SwfMovie;
 use crate::DefaultFont;
@@ -110,10 +110,21 @@ pub struct LayoutContext<'a, 'gc> {
 
     /// The total width of the text field being laid out.
     max_bounds: Twips,
+
+    /// The images of the text being laid out, along with their sizes.
+    images: Vec<(&'a TextImage, Size<Twips>)>,
+
+    /// The area taken by each image placed so far, including its spacing.
+    image_areas: Vec<(TextImageAlign, BoxBounds<Twips>)>,
 }
 
 impl<'a, 'gc> LayoutContext<'a, 'gc> {
-    fn new(movie: Arc<SwfMovie>, max_bounds: Twips, text: &'a WStr) -> Self {
+    fn new(
+        movie: Arc<SwfMovie>,
+        max_bounds: Twips,
+        text: &'a WStr,
+        images: Vec<(&'a TextImage, Size<Twips>)>,
+    ) -> Self {
         Self {
             movie,
             cursor: Default::default(),
@@ -129,7 +140,50 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             has_line_break: false,
             current_line_span: Default::default(),
             max_bounds,
+            images,
+            image_areas: Vec::new(),
+        }
+    }
+
+    /// Place all images declared up to `position` at the given height.
+    ///
+    /// Images stack up horizontally on their side of the text field.
+    fn place_images(&mut self, position: usize, y: Twips) {
+        while let Some(&(image, size)) = self.images.get(self.image_areas.len()) {
+            if image.position > position {
+                break;
+            }
+
+            let area_size = Size::from((
+                size.width() + Twips::from_pixels(image.hspace) * 2,
+                size.height() + Twips::from_pixels(image.vspace) * 2,
+            ));
+            let (left, right) = self.image_offsets(y);
+            let x = match image.align {
+                TextImageAlign::Left => left,
+                TextImageAlign::Right => self.max_bounds - right - area_size.width(),
+            };
+            self.image_areas.push((
+                image.align,
+                BoxBounds::from_position_and_size(Position::from((x, y)), area_size),
+            ));
+        }
+    }
+
+    /// Calculate how much space images take on the left and right of a line
+    /// starting at the given height.
+    fn image_offsets(&self, y: Twips) -> (Twips, Twips) {
+        let mut left = Twips::ZERO;
+        let mut right = Twips::ZERO;
+        for (align, area) in &self.image_areas {
+            if area.offset_y() <= y && y < area.extent_y() {
+                match align {
+                    TextImageAlign::Left => left = max(left, area.extent_x()),
+                    TextImageAlign::Right => right = max(right, self.max_bounds - area.offset_x()),
+                }
+            }
         }
+        (left, right)
     }
 
     /// Calculate the font-provided leading present on this line.
@@ -289,9 +343,11 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
 
         let mut line_bounds = line_bounds.unwrap_or_default();
 
+        let (image_left, image_right) = self.image_offsets(self.cursor.y());
         let left_adjustment =
-            Self::left_alignment_offset(&self.current_line_span, self.is_first_line);
-        let right_adjustment = Twips::from_pixels(self.current_line_span.right_margin);
+            Self::left_alignment_offset(&self.current_line_span, self.is_first_line) + image_left;
+        let right_adjustment =
+            Twips::from_pixels(self.current_line_span.right_margin) + image_right;
 
         let misalignment =
             self.max_bounds - left_adjustment - right_adjustment - line_bounds.width();
@@ -419,6 +475,7 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         self.is_first_line = true;
         self.has_line_break = true;
         self.max_font_size = Twips::from_pixels(self.current_line_span.font.size);
+        self.place_images(end + 1, self.cursor.y());
     }
 
     /// Adjust the text layout cursor down to the next line.
@@ -448,6 +505,7 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
         self.is_first_line = false;
         self.has_line_break = true;
         self.max_font_size = Twips::from_pixels(self.current_line_span.font.size);
+        self.place_images(end, self.cursor.y());
     }
 
     /// Adjust the text layout cursor in response to a tab.
@@ -703,8 +761,10 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     ///
     /// Offsets returned by this function should not be considered final;
     fn wrap_dimensions(&self, current_span: &TextSpan) -> (Twips, Twips) {
-        let width = self.max_bounds - Twips::from_pixels(self.current_line_span.right_margin);
-        let offset = Self::left_alignment_offset(current_span, self.is_first_line);
+        let (image_left, image_right) = self.image_offsets(self.cursor.y());
+        let width =
+            self.max_bounds - Twips::from_pixels(self.current_line_span.right_margin) - image_right;
+        let offset = Self::left_alignment_offset(current_span, self.is_first_line) + image_left;
 
         (width, offset + self.cursor.x())
     }
@@ -726,10 +786,35 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
             font_type,
         );
 
+        // Images declared after the last line are placed below it.
+        let next_line_y = self.cursor.y() + self.max_font_size + self.line_leading_adjustment();
+        self.place_images(usize::MAX, next_line_y);
+
+        let mut image_bounds = Vec::with_capacity(self.image_areas.len());
+        for ((image, size), (_, area)) in self.images.iter().zip(&self.image_areas) {
+            let spacing = Position::from((
+                Twips::from_pixels(image.hspace),
+                Twips::from_pixels(image.vspace),
+            ));
+            image_bounds.push(BoxBounds::from_position_and_size(
+                area.origin() + spacing,
+                *size,
+            ));
+
+            // Images extend the text like lines do.
+            for bounds in [&mut self.bounds, &mut self.exterior_bounds] {
+                match bounds {
+                    Some(bounds) => *bounds += *area,
+                    None => *bounds = Some(*area),
+                }
+            }
+        }
+
         Layout {
             bounds: self.bounds.unwrap_or_default(),
             exterior_bounds: self.exterior_bounds.unwrap_or_default(),
             lines: self.lines,
+            image_bounds,
         }
     }
 
@@ -750,6 +835,10 @@ pub struct Layout<'gc> {
     exterior_bounds: BoxBounds<Twips>,
 
     lines: Vec<LayoutLine<'gc>>,
+
+    /// The bounds of each image of the laid-out text, excluding spacing.
+    #[collect(require_static)]
+    image_bounds: Vec<BoxBounds<Twips>>,
 }
 
 impl<'gc> Layout<'gc> {
@@ -770,6 +859,11 @@ impl<'gc> Layout<'gc> {
         &self.lines
     }
 
+    /// Bounds of the images declared in the laid-out text, in order of appearance.
+    pub fn image_bounds(&self) -> &[BoxBounds<Twips>] {
+        &self.image_bounds
+    }
+
     pub fn boxes_iter(&self) -> LayoutBoxIter<'_, 'gc> {
         LayoutBoxIter {
             lines_iter: self.lines.iter(),
@@ -1008,6 +1102,8 @@ impl<'gc> LayoutBox<'gc> {
 }
 
 /// Construct a new layout from text spans.
+///
+/// `image_sizes` holds the size of each of the images of `fs`.
 pub fn lower_from_text_spans<'gc>(
     fs: &FormatSpans,
     context: &mut UpdateContext<'gc>,
@@ -1015,8 +1111,20 @@ pub fn lower_from_text_spans<'gc>(
     bounds: Twips,
     is_word_wrap: bool,
     font_type: FontType,
+    image_sizes: &[Size<Twips>],
 ) -> Layout<'gc> {
-    let mut layout_context = LayoutContext::new(movie, bounds, fs.displayed_text());
+    let images = fs
+        .images()
+        .iter()
+        .zip(
+            image_sizes
+                .iter()
+                .copied()
+                .chain(std::iter::repeat(Size::default())),
+        )
+        .collect();
+    let mut layout_context = LayoutContext::new(movie, bounds, fs.displayed_text(), images);
+    layout_context.place_images(0, Twips::ZERO);
 
     for (span_start, _end, span_text, span) in fs.iter_spans() {
         if let Some(font) = layout_context.resolve_font(context, span, font_type) {
@@ -1,7 +1,7 @@
 //! Tests for HTML module
 
 use crate::html::dimensions::{BoxBounds, Position, Size};
-use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
+use crate::html::text_format::{FormatSpans, TextFormat, TextImageAlign, TextSpan};
 use crate::string::{WStr, WString};
 use swf::{Rectangle, Twips};
 
@@ -868,3 +868,48 @@ fn formatspans_replace_text_degenerate() {
     assert_eq!((0, 1), fs.get_span_boundaries(0, 5));
     assert_eq!((1, 2), fs.get_span_boundaries(5, 9));
 }
+
+#[test]
+fn formatspans_from_html_img() {
+    let fs = FormatSpans::from_html(
+        WStr::from_units(
+            b"ab<img src=\"smiley.png\" id=\"smile\" width=\"20\" align=\"right\" hspace=\"2\">cd",
+        ),
+        TextFormat::default(),
+        true,
+        false,
+        10,
+    );
+
+    assert_eq!(WStr::from_units(b"abcd"), fs.text());
+    assert_eq!(1, fs.images().len());
+
+    let image = &fs.images()[0];
+    assert_eq!(2, image.position);
+    assert_eq!(WStr::from_units(b"smiley.png"), &image.src[..]);
+    assert_eq!(WStr::from_units(b"smile"), &image.id[..]);
+    assert_eq!(Some(20.0), image.width);
+    assert_eq!(None, image.height);
+    assert_eq!(2.0, image.hspace);
+    assert_eq!(8.0, image.vspace);
+    assert_eq!(TextImageAlign::Right, image.align);
+}
+
+#[test]
+fn formatspans_replace_text_moves_images() {
+    let mut fs = FormatSpans::from_html(
+        WStr::from_units(b"abc<img src=\"a\">def<img src=\"b\">ghi"),
+        TextFormat::default(),
+        true,
+        false,
+        10,
+    );
+
+    fs.replace_text(1, 2, WStr::from_units(b"1234"), None);
+    assert_eq!(6, fs.images()[0].position);
+    assert_eq!(9, fs.images()[1].position);
+
+    fs.replace_text(5, 8, WStr::empty(), None);
+    assert_eq!(5, fs.images()[0].position);
+    assert_eq!(6, fs.images()[1].position);
+}
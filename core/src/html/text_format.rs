@@ -571,6 +571,49 @@ impl TextSpan {
     }
 }
 
+/// The side of the text field an `<img>` is placed on.
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TextImageAlign {
+    #[default]
+    Left,
+    Right,
+}
+
+/// An image embedded into HTML text with an `<img>` tag.
+///
+/// Images float on the side of the text field given by `align`, starting at
+/// the line following their position, and text flows around them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextImage {
+    /// The position in text the image was declared at.
+    pub position: usize,
+
+    /// The URL or library symbol to display.
+    pub src: WString,
+
+    /// The name of the image, used by `getImageReference`.
+    pub id: WString,
+
+    /// The requested width of the image in pixels, or `None` to use its own width.
+    pub width: Option<f64>,
+
+    /// The requested height of the image in pixels, or `None` to use its own height.
+    pub height: Option<f64>,
+
+    /// The horizontal space around the image in pixels, where no text appears.
+    pub hspace: f64,
+
+    /// The vertical space around the image in pixels, where no text appears.
+    pub vspace: f64,
+
+    pub align: TextImageAlign,
+}
+
+impl TextImage {
+    /// The default spacing around images, in pixels.
+    const DEFAULT_SPACE: f64 = 8.0;
+}
+
 /// Struct which contains text formatted by `TextSpan`s.
 #[derive(Clone, Debug)]
 pub struct FormatSpans {
@@ -578,6 +621,7 @@ pub struct FormatSpans {
     displayed_text: WString,
     spans: Vec<TextSpan>,
     default_format: TextFormat,
+    images: Vec<TextImage>,
 }
 
 impl Default for FormatSpans {
@@ -593,6 +637,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: vec![TextSpan::default()],
             default_format: TextFormat::default(),
+            images: Vec::new(),
         }
     }
 
@@ -604,6 +649,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: spans.to_vec(),
             default_format: Default::default(),
+            images: Vec::new(),
         }
     }
 
@@ -614,6 +660,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans: vec![TextSpan::with_length_and_format(len, &format)],
             default_format: format,
+            images: Vec::new(),
         }
     }
 
@@ -637,6 +684,7 @@ impl FormatSpans {
         let mut format_stack = vec![default_format.clone()];
         let mut text = WString::new();
         let mut spans: Vec<TextSpan> = Vec::new();
+        let mut images: Vec<TextImage> = Vec::new();
 
         // quick_xml::Reader requires a [u8] slice, but doesn't actually care about Unicode;
         // this means we can pass the raw buffer in the Latin1 case.
@@ -719,6 +767,34 @@ impl FormatSpans {
                             // Skip push to `format_stack`.
                             continue;
                         }
+                        b"img" => {
+                            if let Some(src) = attribute(b"src") {
+                                let space = |value: Option<WString>| {
+                                    value
+                                        .and_then(|v| v.parse().ok())
+                                        .unwrap_or(TextImage::DEFAULT_SPACE)
+                                };
+                                let align = match attribute(b"align") {
+                                    Some(align) if align == WStr::from_units(b"right") => {
+                                        TextImageAlign::Right
+                                    }
+                                    _ => TextImageAlign::Left,
+                                };
+                                images.push(TextImage {
+                                    position: text.len(),
+                                    src,
+                                    id: attribute(b"id").unwrap_or_default(),
+                                    width: attribute(b"width").and_then(|v| v.parse().ok()),
+                                    height: attribute(b"height").and_then(|v| v.parse().ok()),
+                                    hspace: space(attribute(b"hspace")),
+                                    vspace: space(attribute(b"vspace")),
+                                    align,
+                                });
+                            }
+
+                            // Skip push to `format_stack`.
+                            continue;
+                        }
                         b"p" => {
                             p_open = true;
                             if let Some(align) = attribute(b"align") {
@@ -909,7 +985,7 @@ impl FormatSpans {
                     }
 
                     match tag_name {
-                        b"br" | b"sbr" => {
+                        b"br" | b"sbr" | b"img" => {
                             // Skip pop from `format_stack`.
                             continue;
                         }
@@ -969,6 +1045,7 @@ impl FormatSpans {
             displayed_text: WString::new(),
             spans,
             default_format,
+            images,
         };
         if condense_white && swf_version >= 8 {
             ret.condense_white_swf8();
@@ -1015,6 +1092,11 @@ impl FormatSpans {
     }
 
     /// Retrieve the text backing the format spans.
+    /// The images declared with `<img>` tags, in order of appearance.
+    pub fn images(&self) -> &[TextImage] {
+        &self.images
+    }
+
     pub fn text(&self) -> &WStr {
         &self.text
     }
@@ -1311,6 +1393,8 @@ impl FormatSpans {
             return;
         }
 
+        let removed = to.min(self.text.len()).saturating_sub(from);
+
         if from < self.text.len() {
             self.ensure_span_break_at(from);
             self.ensure_span_break_at(to);
@@ -1350,6 +1434,16 @@ impl FormatSpans {
 
         self.text = new_string;
 
+        // Images stay anchored to the text around them; those in the
+        // replaced range move to its start.
+        for image in &mut self.images {
+            if image.position >= to {
+                image.position = image.position - removed + with.len();
+            } else if image.position > from {
+                image.position = from;
+            }
+        }
+
         self.normalize();
     }
 
@@ -1368,7 +1462,7 @@ impl FormatSpans {
     }
 
     pub fn to_html(&self) -> WString {
-        if self.text.is_empty() {
+        if self.text.is_empty() && self.images.is_empty() {
             return WString::new();
         }
 
@@ -1380,10 +1474,21 @@ impl FormatSpans {
         };
 
         let spans = self.iter_spans();
+        let mut images = self.images.iter().peekable();
 
-        for (_start, _end, text, span) in spans {
+        for (start, end, text, span) in spans {
             state.set_span(span);
-            state.push_text(text);
+            let mut pushed = start;
+            while let Some(image) = images.next_if(|image| image.position < end) {
+                let position = image.position.max(pushed);
+                state.push_text(&text[pushed - start..position - start]);
+                state.push_image(image);
+                pushed = position;
+            }
+            state.push_text(&text[pushed - start..]);
+        }
+        for image in images {
+            state.push_image(image);
         }
 
         state.close_all_tags();
@@ -1701,6 +1806,34 @@ impl<'a> FormatState<'a> {
         }
     }
 
+    fn push_image(&mut self, image: &TextImage) {
+        self.result.push_str(WStr::from_units(b"<IMG"));
+        let mut attribute = |name: &str, value: &WStr| {
+            let value = value.to_utf8_lossy();
+            let _ = write!(self.result, " {}=\"{}\"", name, escape(&value));
+        };
+        attribute("SRC", &image.src);
+        if !image.id.is_empty() {
+            attribute("ID", &image.id);
+        }
+        if let Some(width) = image.width {
+            attribute("WIDTH", &WString::from_utf8(&width.to_string()));
+        }
+        if let Some(height) = image.height {
+            attribute("HEIGHT", &WString::from_utf8(&height.to_string()));
+        }
+        if image.align == TextImageAlign::Right {
+            attribute("ALIGN", WStr::from_units(b"right"));
+        }
+        if image.hspace != TextImage::DEFAULT_SPACE {
+            attribute("HSPACE", &WString::from_utf8(&image.hspace.to_string()));
+        }
+        if image.vspace != TextImage::DEFAULT_SPACE {
+            attribute("VSPACE", &WString::from_utf8(&image.vspace.to_string()));
+        }
+        self.result.push(b'>' as u16);
+    }
+
     fn push_line(&mut self, line: &WStr) {
         if line.is_empty() {
             return;
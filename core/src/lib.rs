@@ -18,6 +18,7 @@ extern crate downcast_rs;
 #[macro_use]
 extern crate num_derive;
 
+mod accessibility;
 #[macro_use]
 mod avm1;
 mod avm2;
@@ -39,6 +40,7 @@ pub mod limits;
 pub mod loader;
 mod local_connection;
 mod locale;
+pub mod native_process;
 mod net_connection;
 pub mod pixel_bender;
 mod player;
@@ -1,4 +1,4 @@
-use crate::avm1::{PropertyMap as Avm1PropertyMap, PropertyMap};
+use crate::avm1::{ActionCache as Avm1ActionCache, PropertyMap as Avm1PropertyMap, PropertyMap};
 use crate::avm2::{Class as Avm2Class, Domain as Avm2Domain};
 use crate::backend::audio::SoundHandle;
 use crate::character::Character;
@@ -126,6 +126,7 @@ pub struct MovieLibrary<'gc> {
     jpeg_tables: Option<Vec<u8>>,
     fonts: FontMap<'gc>,
     avm2_domain: Option<Avm2Domain<'gc>>,
+    avm1_action_cache: Avm1ActionCache<'gc>,
 }
 
 impl<'gc> MovieLibrary<'gc> {
@@ -138,6 +139,7 @@ impl<'gc> MovieLibrary<'gc> {
             jpeg_tables: None,
             fonts: Default::default(),
             avm2_domain: None,
+            avm1_action_cache: Default::default(),
         }
     }
 
@@ -348,6 +350,15 @@ impl<'gc> MovieLibrary<'gc> {
     pub fn try_avm2_domain(&self) -> Option<Avm2Domain<'gc>> {
         self.avm2_domain
     }
+
+    /// Get the decoded AVM1 actions of this movie that have been run so far.
+    pub fn avm1_action_cache(&self) -> &Avm1ActionCache<'gc> {
+        &self.avm1_action_cache
+    }
+
+    pub fn avm1_action_cache_mut(&mut self) -> &mut Avm1ActionCache<'gc> {
+        &mut self.avm1_action_cache
+    }
 }
 
 pub struct MovieLibrarySource<'a, 'gc> {
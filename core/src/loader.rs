@@ -10,11 +10,12 @@ use crate::avm2::object::{
     TObject as _,
 };
 use crate::avm2::{
-    Activation as Avm2Activation, Avm2, BitmapDataObject, Domain as Avm2Domain,
-    Object as Avm2Object, Value as Avm2Value,
+    Activation as Avm2Activation, ArrayObject as Avm2ArrayObject, ArrayStorage, Avm2,
+    BitmapDataObject, Domain as Avm2Domain, Multiname as Avm2Multiname, Object as Avm2Object,
+    Value as Avm2Value,
 };
 use crate::backend::navigator::{ErrorResponse, OwnedFuture, Request, SuccessResponse};
-use crate::backend::ui::DialogResultFuture;
+use crate::backend::ui::{DialogResultFuture, MultipleDialogResultFuture};
 use crate::bitmap::bitmap_data::Color;
 use crate::bitmap::bitmap_data::{BitmapData, BitmapDataWrapper};
 use crate::context::{ActionQueue, ActionType, UpdateContext};
@@ -275,9 +276,12 @@ impl<'gc> LoadManager<'gc> {
             | Loader::NetStream { self_handle, .. }
             | Loader::FileDialog { self_handle, .. }
             | Loader::FileDialogAvm2 { self_handle, .. }
+            | Loader::FileListDialogAvm2 { self_handle, .. }
             | Loader::SaveFileDialog { self_handle, .. }
             | Loader::DownloadFileDialog { self_handle, .. }
+            | Loader::DownloadFileDialogAvm2 { self_handle, .. }
             | Loader::UploadFile { self_handle, .. }
+            | Loader::UploadFileAvm2 { self_handle, .. }
             | Loader::StyleSheet { self_handle, .. }
             | Loader::MovieUnloader { self_handle, .. } => *self_handle = Some(handle),
         }
@@ -634,6 +638,25 @@ impl<'gc> LoadManager<'gc> {
         loader.file_dialog_loader(player, dialog)
     }
 
+    /// Display a dialog allowing a user to select several files
+    ///
+    /// Returns a future that will be resolved when the files are selected
+    #[must_use]
+    pub fn select_file_list_dialog_avm2(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Avm2Object<'gc>,
+        dialog: MultipleDialogResultFuture,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::FileListDialogAvm2 {
+            self_handle: None,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.file_list_dialog_loader(player, dialog)
+    }
+
     /// Display a dialog allowing a user to save a file
     #[must_use]
     pub fn save_file_dialog(
@@ -672,6 +695,49 @@ impl<'gc> LoadManager<'gc> {
         loader.file_download_dialog_loader(player, dialog, url)
     }
 
+    /// Display a dialog allowing a user to download a file from an AVM2 scope
+    ///
+    /// Returns a future that will be resolved when a file is selected and the download has completed
+    #[must_use]
+    pub fn download_file_dialog_avm2(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: FileReferenceObject<'gc>,
+        dialog: DialogResultFuture,
+        request: Request,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::DownloadFileDialogAvm2 {
+            self_handle: None,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+        target_object.set_active_loader(Some(handle));
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.file_download_dialog_loader_avm2(player, dialog, request)
+    }
+
+    /// Upload a file from an AVM2 scope
+    ///
+    /// The request should already contain the file in its body.
+    /// Returns a future that will be resolved when the file upload has completed
+    #[must_use]
+    pub fn upload_file_avm2(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: FileReferenceObject<'gc>,
+        request: Request,
+        size: usize,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::UploadFileAvm2 {
+            self_handle: None,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+        target_object.set_active_loader(Some(handle));
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.file_upload_loader_avm2(player, request, size)
+    }
+
     /// Upload a file
     ///
     /// Returns a future that will be resolved when the file upload has completed
@@ -866,6 +932,16 @@ pub enum Loader<'gc> {
         target_object: FileReferenceObject<'gc>,
     },
 
+    /// Loader that is choosing several files from an AVM2 scope.
+    FileListDialogAvm2 {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<LoaderHandle>,
+
+        /// The target AVM2 `FileReferenceList` to set the selected files on.
+        target_object: Avm2Object<'gc>,
+    },
+
     /// Loader that is saving a file to disk from an AVM2 scope.
     SaveFileDialog {
         /// The handle to refer to this loader instance.
@@ -896,6 +972,26 @@ pub enum Loader<'gc> {
         target_object: Object<'gc>,
     },
 
+    /// Loader that is downloading a file from an AVM2 scope.
+    DownloadFileDialogAvm2 {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<LoaderHandle>,
+
+        /// The target AVM2 object to save the downloaded file for.
+        target_object: FileReferenceObject<'gc>,
+    },
+
+    /// Loader that is uploading a file from an AVM2 scope.
+    UploadFileAvm2 {
+        /// The handle to refer to this loader instance.
+        #[collect(require_static)]
+        self_handle: Option<LoaderHandle>,
+
+        /// The target AVM2 object whose file is uploaded.
+        target_object: FileReferenceObject<'gc>,
+    },
+
     /// Loader that is downloading a stylesheet
     StyleSheet {
         /// The handle to refer to this loader instance.
@@ -3208,41 +3304,10 @@ impl<'gc> Loader<'gc> {
         Box::pin(async move {
             let total_size_bytes = data.len();
 
-            //FIXME: The code below won't work if the payload contains the boundary separator
-            if file_name.contains("------------BOUNDARY")
-                || data.windows(20).any(|b| b == b"------------BOUNDARY")
-            {
-                tracing::error!(
-                    "File upload data contains boundary separator, request cannot be sent"
-                );
-                return Err(Error::Cancelled);
-            }
-
-            // Format the data into multipart/form-data
-            let mut out_data = Vec::new();
-            out_data.extend_from_slice(b"------------BOUNDARY\n");
-            out_data.extend_from_slice(b"Content-Disposition: form-data; name=\"Filename\"\n\n");
-            out_data.extend_from_slice(file_name.as_bytes());
-            out_data.extend_from_slice(b"\n------------BOUNDARY\n");
-            out_data.extend_from_slice(
-                b"Content-Disposition: form-data; name=\"Filedata\"; filename=\"",
-            );
-            out_data.extend_from_slice(file_name.as_bytes());
-            out_data.extend_from_slice(b"\"\n");
-            out_data.extend_from_slice(b"Content-Type: application/octet-stream\n\n");
-            out_data.extend_from_slice(&data);
-            out_data.extend_from_slice(b"\n------------BOUNDARY\n");
-            out_data.extend_from_slice(b"Content-Disposition: form-data; name=\"Upload\"\n\n");
-            out_data.extend_from_slice(b"Submit Query");
-            out_data.extend_from_slice(b"\n------------BOUNDARY\n");
-
             // Upload the data
             let req = Request::post(
                 url,
-                Some((
-                    out_data,
-                    "multipart/form-data; boundary=------------BOUNDARY".to_string(),
-                )),
+                Some(encode_file_upload(&[], "Filedata", &file_name, &data)),
             );
             // Doing this in two steps to prevent holding the player lock during fetch
             let future = player.lock().unwrap().navigator().fetch(req);
@@ -3350,4 +3415,505 @@ impl<'gc> Loader<'gc> {
             })
         })
     }
+
+    /// Loader to process callbacks for a dialog selecting several files
+    pub fn file_list_dialog_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        dialog: MultipleDialogResultFuture,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::FileListDialogAvm2 { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotFileDialogLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let dialog_result = dialog.await;
+
+            // Dialog is done, allow opening new dialogs
+            player.lock().unwrap().ui_mut().close_file_dialog();
+
+            player.lock().unwrap().update(|uc| -> Result<(), Error> {
+                let target_object = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::FileListDialogAvm2 { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotFileDialogLoader),
+                };
+                uc.load_manager.remove_loader(handle);
+
+                let results = match dialog_result {
+                    Ok(results) => results,
+                    Err(err) => {
+                        tracing::warn!("Error on file dialog: {:?}", err);
+                        return Ok(());
+                    }
+                };
+
+                let mut activation = Avm2Activation::from_nothing(uc);
+
+                if results.is_empty() {
+                    let cancel_event =
+                        Avm2EventObject::bare_default_event(activation.context, "cancel");
+                    Avm2::dispatch_event(activation.context, cancel_event, target_object);
+                    return Ok(());
+                }
+
+                let mut files = Vec::with_capacity(results.len());
+                for result in results {
+                    let file = activation
+                        .avm2()
+                        .classes()
+                        .filereference
+                        .construct(&mut activation, &[])
+                        .map_err(|e| Error::Avm2Error(e.to_string()))?;
+                    file.as_file_reference()
+                        .expect("FileReference should be a FileReferenceObject")
+                        .init_from_dialog_result(result);
+                    files.push(Some(file.into()));
+                }
+
+                let file_list = Avm2ArrayObject::from_storage(
+                    &mut activation,
+                    ArrayStorage::from_storage(files),
+                )
+                .map_err(|e| Error::Avm2Error(e.to_string()))?;
+                target_object
+                    .set_property(
+                        &Avm2Multiname::new(activation.avm2().flash_net_internal, "_fileList"),
+                        file_list.into(),
+                        &mut activation,
+                    )
+                    .map_err(|e| Error::Avm2Error(e.to_string()))?;
+
+                let select_event =
+                    Avm2EventObject::bare_default_event(activation.context, "select");
+                Avm2::dispatch_event(activation.context, select_event, target_object);
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Loader to handle a file download dialog from an AVM2 scope
+    ///
+    /// Fetches the data of `request`, saves the data to the selected destination and dispatches events
+    pub fn file_download_dialog_loader_avm2(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        dialog: DialogResultFuture,
+        request: Request,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::DownloadFileDialogAvm2 { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotFileDownloadDialogLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let dialog_result = dialog.await;
+
+            // Dialog is done, allow opening new dialogs
+            player.lock().unwrap().ui_mut().close_file_dialog();
+
+            let selected = player.lock().unwrap().update(|uc| -> Result<bool, Error> {
+                let target_object = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::DownloadFileDialogAvm2 { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotFileDownloadDialogLoader),
+                };
+
+                let selected = match &dialog_result {
+                    Ok(dialog_result) => !dialog_result.is_cancelled(),
+                    Err(err) => {
+                        tracing::warn!("Download dialog had an error {:?}", err);
+                        false
+                    }
+                };
+
+                let event_type = if selected {
+                    "select"
+                } else {
+                    uc.load_manager.remove_loader(handle);
+                    target_object.set_active_loader(None);
+                    "cancel"
+                };
+                let event = Avm2EventObject::bare_default_event(uc, event_type);
+                Avm2::dispatch_event(uc, event, target_object.into());
+
+                Ok(selected)
+            })?;
+
+            let mut dialog_result = match dialog_result {
+                Ok(dialog_result) if selected => dialog_result,
+                _ => return Ok(()),
+            };
+
+            // Doing this in two steps to prevent holding the player lock during fetch
+            let future = player.lock().unwrap().navigator().fetch(request);
+            let download_res = Self::wait_for_full_response(future).await;
+
+            player.lock().unwrap().update(|uc| -> Result<(), Error> {
+                let target_object = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::DownloadFileDialogAvm2 { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotFileDownloadDialogLoader),
+                };
+                uc.load_manager.remove_loader(handle);
+                target_object.set_active_loader(None);
+
+                let mut activation = Avm2Activation::from_nothing(uc);
+
+                match download_res {
+                    Ok((body, _, _, _)) => {
+                        let open_event =
+                            Avm2EventObject::bare_default_event(activation.context, "open");
+                        Avm2::dispatch_event(activation.context, open_event, target_object.into());
+
+                        dialog_result.write_and_refresh(&body);
+                        target_object.init_from_dialog_result(dialog_result);
+
+                        let size = body.len() as u64;
+                        let progress_evt = Avm2EventObject::progress_event(
+                            &mut activation,
+                            "progress",
+                            size,
+                            size,
+                            false,
+                            false,
+                        );
+                        Avm2::dispatch_event(
+                            activation.context,
+                            progress_evt,
+                            target_object.into(),
+                        );
+
+                        let complete_event =
+                            Avm2EventObject::bare_default_event(activation.context, "complete");
+                        Avm2::dispatch_event(
+                            activation.context,
+                            complete_event,
+                            target_object.into(),
+                        );
+                    }
+                    Err(response) => {
+                        tracing::error!(
+                            "Error during FileReference download of {:?}: {:?}",
+                            response.url,
+                            response.error
+                        );
+                        Self::dispatch_file_io_error(
+                            &mut activation,
+                            target_object.into(),
+                            &response.error,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Loader to handle a file upload from an AVM2 scope
+    ///
+    /// Sends `request`, which holds the encoded file, and dispatches events.
+    /// `size` is the size of the file being uploaded.
+    pub fn file_upload_loader_avm2(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        request: Request,
+        size: usize,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::UploadFileAvm2 { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotFileUploadLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            player.lock().unwrap().update(|uc| -> Result<(), Error> {
+                let target_object = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::UploadFileAvm2 { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotFileUploadLoader),
+                };
+
+                let open_event = Avm2EventObject::bare_default_event(uc, "open");
+                Avm2::dispatch_event(uc, open_event, target_object.into());
+
+                Ok(())
+            })?;
+
+            // Doing this in two steps to prevent holding the player lock during fetch
+            let future = player.lock().unwrap().navigator().fetch(request);
+            let result = Self::wait_for_full_response(future).await;
+
+            player.lock().unwrap().update(|uc| -> Result<(), Error> {
+                let target_object = match uc.load_manager.get_loader(handle) {
+                    Some(&Loader::UploadFileAvm2 { target_object, .. }) => target_object,
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotFileUploadLoader),
+                };
+                uc.load_manager.remove_loader(handle);
+                target_object.set_active_loader(None);
+
+                let mut activation = Avm2Activation::from_nothing(uc);
+
+                match result {
+                    Ok((body, _, _, _)) => {
+                        let size = size as u64;
+                        let progress_evt = Avm2EventObject::progress_event(
+                            &mut activation,
+                            "progress",
+                            size,
+                            size,
+                            false,
+                            false,
+                        );
+                        Avm2::dispatch_event(
+                            activation.context,
+                            progress_evt,
+                            target_object.into(),
+                        );
+
+                        let complete_event =
+                            Avm2EventObject::bare_default_event(activation.context, "complete");
+                        Avm2::dispatch_event(
+                            activation.context,
+                            complete_event,
+                            target_object.into(),
+                        );
+
+                        // This is only dispatched if the server responded with some data.
+                        if !body.is_empty() {
+                            let data = AvmString::new_utf8_bytes(activation.gc(), &body);
+                            let data_evt = activation
+                                .avm2()
+                                .classes()
+                                .dataevent
+                                .construct(
+                                    &mut activation,
+                                    &[
+                                        "uploadCompleteData".into(),
+                                        false.into(),
+                                        false.into(),
+                                        data.into(),
+                                    ],
+                                )
+                                .map_err(|e| Error::Avm2Error(e.to_string()))?;
+                            Avm2::dispatch_event(
+                                activation.context,
+                                data_evt,
+                                target_object.into(),
+                            );
+                        }
+                    }
+                    Err(response) => {
+                        tracing::error!(
+                            "Error during FileReference upload to {:?}: {:?}",
+                            response.url,
+                            response.error
+                        );
+                        Self::dispatch_file_io_error(
+                            &mut activation,
+                            target_object.into(),
+                            &response.error,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    /// Dispatches the events for a failed AVM2 `FileReference` upload or download:
+    /// `httpStatus` if the server responded with an error, followed by `ioError`.
+    fn dispatch_file_io_error<'a>(
+        activation: &mut Avm2Activation<'_, 'a>,
+        target: Avm2Object<'a>,
+        error: &Error,
+    ) -> Result<(), Error> {
+        if let Error::HttpNotOk(_, status_code, redirected, _) = *error {
+            let http_status_evt = activation
+                .avm2()
+                .classes()
+                .httpstatusevent
+                .construct(
+                    activation,
+                    &[
+                        "httpStatus".into(),
+                        false.into(),
+                        false.into(),
+                        status_code.into(),
+                        redirected.into(),
+                    ],
+                )
+                .map_err(|e| Error::Avm2Error(e.to_string()))?;
+
+            Avm2::dispatch_event(activation.context, http_status_evt, target);
+        }
+
+        let io_error_evt = activation
+            .avm2()
+            .classes()
+            .ioerrorevent
+            .construct(
+                activation,
+                &[
+                    "ioError".into(),
+                    false.into(),
+                    false.into(),
+                    "Error #2038: File I/O Error.".into(),
+                    2038.into(),
+                ],
+            )
+            .map_err(|e| Error::Avm2Error(e.to_string()))?;
+
+        Avm2::dispatch_event(activation.context, io_error_evt, target);
+
+        Ok(())
+    }
+}
+
+/// Encodes a file upload as `multipart/form-data`, laid out the same way as Flash Player does.
+///
+/// `fields` are sent after the file name and before the file itself.
+/// Returns the body along with its content type.
+pub fn encode_file_upload(
+    fields: &[(String, String)],
+    data_field_name: &str,
+    file_name: &str,
+    data: &[u8],
+) -> (Vec<u8>, String) {
+    let contains = |haystack: &[u8], needle: &str| {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle.as_bytes())
+    };
+
+    // Pick a boundary that doesn't occur anywhere in the content.
+    let mut boundary = "----------RuffleFileUpload".to_string();
+    let mut attempt = 0;
+    while contains(data, &boundary)
+        || file_name.contains(&boundary)
+        || fields
+            .iter()
+            .any(|(name, value)| name.contains(&boundary) || value.contains(&boundary))
+    {
+        attempt += 1;
+        boundary = format!("----------RuffleFileUpload{attempt}");
+    }
+
+    let mut body = Vec::new();
+    let mut add_field = |name: &str, value: &[u8]| {
+        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        body.extend_from_slice(
+            format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n").as_bytes(),
+        );
+        body.extend_from_slice(value);
+        body.extend_from_slice(b"\r\n");
+    };
+
+    add_field("Filename", file_name.as_bytes());
+    for (name, value) in fields {
+        add_field(name, value.as_bytes());
+    }
+
+    body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+    body.extend_from_slice(
+        format!(
+            "Content-Disposition: form-data; name=\"{data_field_name}\"; filename=\"{file_name}\"\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\r\n");
+
+    body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+    body.extend_from_slice(b"Content-Disposition: form-data; name=\"Upload\"\r\n\r\n");
+    body.extend_from_slice(b"Submit Query\r\n");
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+    (body, format!("multipart/form-data; boundary={boundary}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::loader::encode_file_upload;
+
+    #[test]
+    fn test_encode_file_upload_layout() {
+        let fields = [("user".to_string(), "ruffle".to_string())];
+        let (body, content_type) = encode_file_upload(&fields, "Filedata", "a.txt", b"hello");
+
+        assert_eq!(
+            content_type,
+            "multipart/form-data; boundary=----------RuffleFileUpload"
+        );
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "------------RuffleFileUpload\r\n\
+             Content-Disposition: form-data; name=\"Filename\"\r\n\r\n\
+             a.txt\r\n\
+             ------------RuffleFileUpload\r\n\
+             Content-Disposition: form-data; name=\"user\"\r\n\r\n\
+             ruffle\r\n\
+             ------------RuffleFileUpload\r\n\
+             Content-Disposition: form-data; name=\"Filedata\"; filename=\"a.txt\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n\
+             hello\r\n\
+             ------------RuffleFileUpload\r\n\
+             Content-Disposition: form-data; name=\"Upload\"\r\n\r\n\
+             Submit Query\r\n\
+             ------------RuffleFileUpload--\r\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_file_upload_boundary_in_content() {
+        let (body, content_type) = encode_file_upload(
+            &[],
+            "Filedata",
+            "a.txt",
+            b"--------RuffleFileUpload and ----------RuffleFileUpload1",
+        );
+
+        assert_eq!(
+            content_type,
+            "multipart/form-data; boundary=----------RuffleFileUpload2"
+        );
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.starts_with("------------RuffleFileUpload2\r\n"));
+        assert!(body.ends_with("\r\n------------RuffleFileUpload2--\r\n"));
+        assert_eq!(body.matches("------------RuffleFileUpload2\r\n").count(), 3);
+
+        let (_, content_type) = encode_file_upload(
+            &[("x".to_string(), "----------RuffleFileUpload".to_string())],
+            "Filedata",
+            "a.txt",
+            b"",
+        );
+        assert_eq!(
+            content_type,
+            "multipart/form-data; boundary=----------RuffleFileUpload1"
+        );
+    }
 }
@@ -0,0 +1,364 @@
+use crate::{
+    avm2::{
+        object::TObject as Avm2TObject, Activation as Avm2Activation, Avm2, EventObject,
+        Object as Avm2Object,
+    },
+    backend::navigator::NavigatorBackend,
+    context::UpdateContext,
+    string::AvmString,
+};
+use async_channel::{unbounded, Receiver, Sender};
+use gc_arena::Collect;
+use slotmap::{new_key_type, SlotMap};
+use std::io;
+
+new_key_type! {
+    pub struct ProcessHandle;
+}
+
+/// The most output of a single stream that's buffered before the movie reads it.
+///
+/// Anything a process writes beyond this is dropped.
+const MAX_BUFFERED_OUTPUT: usize = 16 * 1024 * 1024;
+
+/// What an AIR application asked to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessStartupInfo {
+    /// The path of the executable on the host system.
+    pub executable: String,
+
+    pub arguments: Vec<String>,
+
+    /// The path of the directory to start the process in, if not the current one.
+    pub working_directory: Option<String>,
+}
+
+/// A request sent from the player to a running process.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProcessCommand {
+    /// Write data to the standard input of the process.
+    Input(Vec<u8>),
+
+    /// Close the standard input of the process.
+    CloseInput,
+
+    /// Terminate the process.
+    Exit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessStream {
+    Output,
+    Error,
+}
+
+/// A notification sent from a running process to the player.
+#[derive(Debug, PartialEq)]
+pub enum ProcessAction {
+    /// Data was read from the standard output or error of the process.
+    Data(ProcessHandle, ProcessStream, Vec<u8>),
+
+    /// The standard output or error of the process was closed.
+    Close(ProcessHandle, ProcessStream),
+
+    /// The standard input of the process was closed.
+    InputClose(ProcessHandle),
+
+    /// Writing to the standard input of the process failed.
+    InputError(ProcessHandle, String),
+
+    /// The process exited, with its exit code if it has one.
+    Exit(ProcessHandle, Option<i32>),
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct NativeProcess<'gc> {
+    target: Avm2Object<'gc>,
+    standard_input: Avm2Object<'gc>,
+    standard_output: Avm2Object<'gc>,
+    standard_error: Avm2Object<'gc>,
+    #[collect(require_static)]
+    sender: Sender<ProcessCommand>,
+}
+
+/// Manages the native processes started by `flash.desktop.NativeProcess`.
+pub struct NativeProcesses<'gc> {
+    processes: SlotMap<ProcessHandle, NativeProcess<'gc>>,
+
+    receiver: Receiver<ProcessAction>,
+    sender: Sender<ProcessAction>,
+}
+
+unsafe impl<'gc> Collect for NativeProcesses<'gc> {
+    fn trace(&self, cc: &gc_arena::Collection) {
+        for (_, process) in self.processes.iter() {
+            process.trace(cc)
+        }
+    }
+}
+
+impl<'gc> NativeProcesses<'gc> {
+    pub fn empty() -> Self {
+        let (sender, receiver) = unbounded();
+
+        Self {
+            processes: SlotMap::with_key(),
+            receiver,
+            sender,
+        }
+    }
+
+    /// Starts a process on behalf of the given `NativeProcess` object.
+    ///
+    /// Data written to `standard_input` is sent to the process on every update,
+    /// and its output is appended to `standard_output` and `standard_error`.
+    pub fn start(
+        &mut self,
+        backend: &mut dyn NavigatorBackend,
+        target: Avm2Object<'gc>,
+        info: ProcessStartupInfo,
+        standard_input: Avm2Object<'gc>,
+        standard_output: Avm2Object<'gc>,
+        standard_error: Avm2Object<'gc>,
+    ) -> Result<(), io::Error> {
+        let (sender, receiver) = unbounded();
+
+        let handle = self.processes.insert(NativeProcess {
+            target,
+            standard_input,
+            standard_output,
+            standard_error,
+            sender,
+        });
+
+        if let Err(e) = backend.start_process(info, handle, receiver, self.sender.clone()) {
+            self.processes.remove(handle);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn find(&self, target: Avm2Object<'gc>) -> Option<ProcessHandle> {
+        self.processes
+            .iter()
+            .find(|(_, process)| Avm2Object::ptr_eq(process.target, target))
+            .map(|(handle, _)| handle)
+    }
+
+    pub fn is_running(&self, target: Avm2Object<'gc>) -> bool {
+        self.find(target).is_some()
+    }
+
+    fn send(&self, handle: ProcessHandle, command: ProcessCommand) {
+        if let Some(process) = self.processes.get(handle) {
+            // The channel is only closed once the process has exited,
+            // in which case there's nothing left to do anyway.
+            let _ = process.sender.try_send(command);
+        }
+    }
+
+    /// Sends any data written to the standard input of the process since the last update,
+    /// returning how many bytes were sent.
+    fn flush_input(&self, handle: ProcessHandle) -> usize {
+        let Some(process) = self.processes.get(handle) else {
+            return 0;
+        };
+        let Some(mut input) = process.standard_input.as_bytearray_mut() else {
+            return 0;
+        };
+        if input.len() == 0 {
+            return 0;
+        }
+
+        let data = input.bytes().to_vec();
+        input.clear();
+        drop(input);
+
+        let len = data.len();
+        self.send(handle, ProcessCommand::Input(data));
+        len
+    }
+
+    pub fn close_input(&self, target: Avm2Object<'gc>) {
+        if let Some(handle) = self.find(target) {
+            self.flush_input(handle);
+            self.send(handle, ProcessCommand::CloseInput);
+        }
+    }
+
+    pub fn exit(&self, target: Avm2Object<'gc>) {
+        if let Some(handle) = self.find(target) {
+            self.send(handle, ProcessCommand::Exit);
+        }
+    }
+
+    pub fn close_all(&mut self) {
+        // Dropping the senders tells the backend to terminate the processes.
+        self.processes.clear();
+    }
+
+    fn dispatch_progress_event(
+        context: &mut UpdateContext<'gc>,
+        target: Avm2Object<'gc>,
+        event_type: &'static str,
+        bytes: usize,
+    ) {
+        let mut activation = Avm2Activation::from_nothing(context);
+
+        let progress_evt = activation
+            .avm2()
+            .classes()
+            .progressevent
+            .construct(
+                &mut activation,
+                &[
+                    event_type.into(),
+                    false.into(),
+                    false.into(),
+                    bytes.into(),
+                    0.into(),
+                ],
+            )
+            .expect("ProgressEvent should be constructed");
+
+        Avm2::dispatch_event(activation.context, progress_evt, target);
+    }
+
+    pub fn update_processes(context: &mut UpdateContext<'gc>) {
+        let pending_input: Vec<_> = context
+            .native_processes
+            .processes
+            .iter()
+            .map(|(handle, process)| (handle, process.target))
+            .collect();
+
+        for (handle, target) in pending_input {
+            let written = context.native_processes.flush_input(handle);
+            if written > 0 {
+                Self::dispatch_progress_event(context, target, "standardInputProgress", written);
+            }
+        }
+
+        let mut actions = vec![];
+
+        while let Ok(action) = context.native_processes.receiver.try_recv() {
+            actions.push(action)
+        }
+
+        for action in actions {
+            match action {
+                ProcessAction::Data(handle, stream, data) => {
+                    let (target, buffer) = match context.native_processes.processes.get(handle) {
+                        Some(process) if stream == ProcessStream::Output => {
+                            (process.target, process.standard_output)
+                        }
+                        Some(process) => (process.target, process.standard_error),
+                        // Process must have been closed before we could send event.
+                        None => continue,
+                    };
+
+                    let mut buffered = 0;
+                    if let Some(mut buffer) = buffer.as_bytearray_mut() {
+                        // What has been read can't be reached again through `IDataInput`.
+                        buffer.discard_read();
+
+                        let len = buffer.len();
+                        buffered = data.len().min(MAX_BUFFERED_OUTPUT.saturating_sub(len));
+                        if buffered < data.len() {
+                            tracing::warn!(
+                                "Process output isn't being read, dropping {} bytes",
+                                data.len() - buffered
+                            );
+                        }
+
+                        // Append without moving the read position.
+                        if let Err(e) = buffer.write_at(&data[..buffered], len) {
+                            tracing::error!("Failed to buffer process output: {:?}", e);
+                        }
+                    }
+                    if buffered == 0 {
+                        continue;
+                    }
+
+                    let event_type = match stream {
+                        ProcessStream::Output => "standardOutputData",
+                        ProcessStream::Error => "standardErrorData",
+                    };
+                    Self::dispatch_progress_event(context, target, event_type, buffered);
+                }
+                ProcessAction::Close(handle, stream) => {
+                    let target = match context.native_processes.processes.get(handle) {
+                        Some(process) => process.target,
+                        None => continue,
+                    };
+
+                    let event_type = match stream {
+                        ProcessStream::Output => "standardOutputClose",
+                        ProcessStream::Error => "standardErrorClose",
+                    };
+                    let close_evt = EventObject::bare_default_event(context, event_type);
+                    Avm2::dispatch_event(context, close_evt, target);
+                }
+                ProcessAction::InputClose(handle) => {
+                    let target = match context.native_processes.processes.get(handle) {
+                        Some(process) => process.target,
+                        None => continue,
+                    };
+
+                    let close_evt = EventObject::bare_default_event(context, "standardInputClose");
+                    Avm2::dispatch_event(context, close_evt, target);
+                }
+                ProcessAction::InputError(handle, message) => {
+                    let target = match context.native_processes.processes.get(handle) {
+                        Some(process) => process.target,
+                        None => continue,
+                    };
+
+                    let mut activation = Avm2Activation::from_nothing(context);
+                    let message = AvmString::new_utf8(activation.gc(), message);
+
+                    let io_error_evt = activation
+                        .avm2()
+                        .classes()
+                        .ioerrorevent
+                        .construct(
+                            &mut activation,
+                            &[
+                                "standardInputIoError".into(),
+                                false.into(),
+                                false.into(),
+                                message.into(),
+                                0.into(),
+                            ],
+                        )
+                        .expect("IOErrorEvent should be constructed");
+
+                    Avm2::dispatch_event(activation.context, io_error_evt, target);
+                }
+                ProcessAction::Exit(handle, exit_code) => {
+                    let target = match context.native_processes.processes.remove(handle) {
+                        Some(process) => process.target,
+                        None => continue,
+                    };
+
+                    let mut activation = Avm2Activation::from_nothing(context);
+
+                    // Processes killed by a signal have no exit code.
+                    let exit_code = exit_code.map_or(f64::NAN, f64::from);
+                    let exit_evt = activation
+                        .avm2()
+                        .classes()
+                        .nativeprocessexitevent
+                        .construct(
+                            &mut activation,
+                            &["exit".into(), false.into(), false.into(), exit_code.into()],
+                        )
+                        .expect("NativeProcessExitEvent should be constructed");
+
+                    Avm2::dispatch_event(activation.context, exit_evt, target);
+                }
+            }
+        }
+    }
+}
@@ -11,11 +11,13 @@ use crate::avm2::{
 };
 use crate::backend::ui::FontDefinition;
 use crate::backend::{
+    accessibility::{AccessibilityBackend, AccessibilityNode},
     audio::{AudioBackend, AudioManager},
+    filesystem::FilesystemBackend,
     log::LogBackend,
     navigator::{NavigatorBackend, Request},
     storage::StorageBackend,
-    ui::{InputManager, MouseCursor, UiBackend},
+    ui::{CustomMouseCursor, InputManager, MouseCursor, UiBackend},
 };
 use crate::compatibility_rules::CompatibilityRules;
 use crate::config::Letterbox;
@@ -30,7 +32,9 @@ use crate::display_object::{
     TInteractiveObject, WindowMode,
 };
 use crate::events::GamepadButton;
-use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, MouseButton, PlayerEvent};
+use crate::events::{
+    ButtonKeyCode, ClipEvent, ClipEventResult, ImeEvent, KeyCode, MouseButton, PlayerEvent,
+};
 use crate::external::{ExternalInterface, ExternalInterfaceProvider, NullFsCommandProvider};
 use crate::external::{FsCommandProvider, Value as ExternalValue};
 use crate::focus_tracker::NavigationDirection;
@@ -40,11 +44,12 @@ use crate::limits::ExecutionLimit;
 use crate::loader::{LoadBehavior, LoadManager};
 use crate::local_connection::LocalConnections;
 use crate::locale::get_current_date_time;
+use crate::native_process::NativeProcesses;
 use crate::net_connection::NetConnections;
 use crate::prelude::*;
 use crate::socket::Sockets;
 use crate::streams::StreamManager;
-use crate::string::{AvmString, AvmStringInterner};
+use crate::string::{AvmString, AvmStringInterner, WString};
 use crate::stub::StubCollection;
 use crate::tag_utils::SwfMovie;
 use crate::timer::Timers;
@@ -112,6 +117,22 @@ pub struct MouseData<'gc> {
     pub pressed: Option<InteractiveObject<'gc>>,
     pub right_pressed: Option<InteractiveObject<'gc>>,
     pub middle_pressed: Option<InteractiveObject<'gc>>,
+
+    /// The cursor picked for the object under the mouse, used when `Mouse.cursor` is `AUTO`.
+    #[collect(require_static)]
+    pub auto_cursor: MouseCursor,
+
+    /// The value of AS3 `Mouse.cursor`.
+    #[collect(require_static)]
+    pub cursor_name: String,
+
+    /// The cursors registered through AS3 `Mouse.registerCursor`, by name.
+    #[collect(require_static)]
+    pub registered_cursors: HashMap<String, Arc<CustomMouseCursor>>,
+
+    /// The cursor that was last sent to the UI backend.
+    #[collect(require_static)]
+    displayed_cursor: MouseCursor,
 }
 
 impl<'gc> MouseData<'gc> {
@@ -132,6 +153,29 @@ impl<'gc> MouseData<'gc> {
             MouseButton::Middle => self.middle_pressed = value,
         }
     }
+
+    /// The cursor that should be shown, taking `Mouse.cursor` into account.
+    fn cursor(&self) -> MouseCursor {
+        match self.cursor_name.as_str() {
+            "arrow" => MouseCursor::Arrow,
+            "button" => MouseCursor::Hand,
+            "hand" => MouseCursor::Grab,
+            "ibeam" => MouseCursor::IBeam,
+            name => match self.registered_cursors.get(name) {
+                Some(cursor) => MouseCursor::Custom(cursor.clone()),
+                None => self.auto_cursor.clone(),
+            },
+        }
+    }
+
+    /// Tells the UI backend about the cursor to show, if it has changed.
+    pub fn update_cursor(&mut self, ui: &mut dyn UiBackend) {
+        let cursor = self.cursor();
+        if cursor != self.displayed_cursor {
+            self.displayed_cursor = cursor.clone();
+            ui.set_mouse_cursor(cursor);
+        }
+    }
 }
 
 #[derive(Collect)]
@@ -186,6 +230,9 @@ struct GcRootData<'gc> {
 
     sockets: Sockets<'gc>,
 
+    /// Processes started by AIR applications.
+    native_processes: NativeProcesses<'gc>,
+
     /// List of active NetConnection objects.
     net_connections: NetConnections<'gc>,
 
@@ -230,6 +277,7 @@ impl<'gc> GcRootData<'gc> {
         &mut AudioManager<'gc>,
         &mut StreamManager<'gc>,
         &mut Sockets<'gc>,
+        &mut NativeProcesses<'gc>,
         &mut NetConnections<'gc>,
         &mut LocalConnections<'gc>,
         &mut Vec<PostFrameCallback<'gc>>,
@@ -254,6 +302,7 @@ impl<'gc> GcRootData<'gc> {
             &mut self.audio_manager,
             &mut self.stream_manager,
             &mut self.sockets,
+            &mut self.native_processes,
             &mut self.net_connections,
             &mut self.local_connections,
             &mut self.post_frame_callbacks,
@@ -265,7 +314,9 @@ impl<'gc> GcRootData<'gc> {
 
 type GcArena = gc_arena::Arena<Rootable![GcRoot<'_>]>;
 
+type Accessibility = Box<dyn AccessibilityBackend>;
 type Audio = Box<dyn AudioBackend>;
+type Filesystem = Box<dyn FilesystemBackend>;
 type Navigator = Box<dyn NavigatorBackend>;
 type Renderer = Box<dyn RenderBackend>;
 type Storage = Box<dyn StorageBackend>;
@@ -300,9 +351,14 @@ pub struct Player {
     audio: Audio,
     navigator: Navigator,
     storage: Storage,
+    filesystem: Filesystem,
     log: Log,
     ui: Ui,
     video: Video,
+    accessibility: Accessibility,
+
+    /// The accessibility tree last sent to the accessibility backend.
+    accessibility_tree: Option<AccessibilityNode>,
 
     transform_stack: TransformStack,
 
@@ -333,8 +389,6 @@ pub struct Player {
     mouse_in_stage: bool,
     mouse_position: Point<Twips>,
 
-    /// The current mouse cursor icon.
-    mouse_cursor: MouseCursor,
     mouse_cursor_needs_check: bool,
 
     system: SystemProperties,
@@ -544,6 +598,7 @@ impl Player {
             });
 
             self.update_sockets();
+            self.update_native_processes();
             self.update_net_connections();
             self.update_timers(dt);
             self.update(|context| {
@@ -959,6 +1014,7 @@ impl Player {
     pub fn handle_event(&mut self, event: PlayerEvent) -> bool {
         match event {
             PlayerEvent::FocusGained | PlayerEvent::FocusLost => self.handle_focus_event(event),
+            PlayerEvent::Ime(ime_event) => self.handle_ime_event(ime_event),
             PlayerEvent::KeyDown { .. }
             | PlayerEvent::KeyUp { .. }
             | PlayerEvent::MouseMove { .. }
@@ -984,6 +1040,39 @@ impl Player {
         true
     }
 
+    /// Handles an event of the input method editor, which is forwarded to the focused text field.
+    fn handle_ime_event(&mut self, event: ImeEvent) -> bool {
+        let handled = self.mutate_with_update_context(|context| {
+            let Some(text) = context.focus_tracker.get_as_edit_text() else {
+                return false;
+            };
+
+            match event {
+                ImeEvent::Preedit(composition, cursor) => {
+                    // The cursor is given in UTF-8 bytes, while the text field works with WStr units.
+                    let to_units = |offset: usize| {
+                        composition
+                            .get(..offset)
+                            .map_or(0, |prefix| WString::from_utf8(prefix).len())
+                    };
+                    let cursor = cursor.map(|(from, to)| (to_units(from), to_units(to)));
+                    text.set_ime_composition(WString::from_utf8(&composition), cursor, context);
+                }
+                ImeEvent::Commit(composition) => {
+                    text.commit_ime_text(&WString::from_utf8(&composition), context);
+                }
+            }
+
+            Self::run_actions(context);
+            true
+        });
+
+        if handled {
+            self.needs_render = true;
+        }
+        handled
+    }
+
     /// Input event handling is a complicated affair, involving several different
     /// concerns that need to resolve with specific priority.
     ///
@@ -1387,7 +1476,7 @@ impl Player {
             }
         }
 
-        if self.should_reset_highlight(event) {
+        if self.should_reset_highlight(&event) {
             self.mutate_with_update_context(|context| {
                 context.focus_tracker.reset_highlight();
             });
@@ -1396,7 +1485,7 @@ impl Player {
         player_event_handled
     }
 
-    fn should_reset_highlight(&self, event: PlayerEvent) -> bool {
+    fn should_reset_highlight(&self, event: &PlayerEvent) -> bool {
         if matches!(
             event,
             PlayerEvent::MouseDown {
@@ -1488,13 +1577,14 @@ impl Player {
         is_mouse_moved: bool,
         player_event_handled: &mut bool,
     ) -> bool {
-        let mut new_cursor = self.mouse_cursor;
         let mut mouse_cursor_needs_check = self.mouse_cursor_needs_check;
         let mouse_in_stage = self.mouse_in_stage();
 
         // Determine the display object the mouse is hovering over.
         // Search through levels from top-to-bottom, returning the first display object that is under the mouse.
         let needs_render = self.mutate_with_update_context(|context| {
+            let mut new_cursor = context.mouse_data.auto_cursor.clone();
+
             // Objects may be hovered using Tab,
             // skip mouse hover when it's not necessary.
             let mut skip_mouse_hover = !is_mouse_moved
@@ -1789,14 +1879,14 @@ impl Player {
                 refresh
             };
             Self::run_actions(context);
+
+            // Update mouse cursor if it has changed.
+            context.mouse_data.auto_cursor = new_cursor;
+            context.mouse_data.update_cursor(context.ui);
+
             needs_render
         });
 
-        // Update mouse cursor if it has changed.
-        if new_cursor != self.mouse_cursor {
-            self.mouse_cursor = new_cursor;
-            self.ui.set_mouse_cursor(new_cursor)
-        }
         self.mouse_cursor_needs_check = mouse_cursor_needs_check;
 
         needs_render
@@ -1950,11 +2040,51 @@ impl Player {
             }
         });
 
+        self.update_accessibility();
         self.needs_render = true;
     }
 
+    /// Builds the accessibility tree of the current display list.
+    pub fn accessibility_tree(&mut self) -> AccessibilityNode {
+        self.mutate_with_update_context(crate::accessibility::build_tree)
+    }
+
+    /// Sends an up-to-date accessibility tree to the accessibility backend,
+    /// if assistive technology is active and anything changed since the last update.
+    ///
+    /// The tree is only rebuilt once the stage was invalidated, which happens on changes of the
+    /// display list, of text and of the properties of display objects, on focus changes,
+    /// and on `Accessibility.updateProperties`.
+    pub fn update_accessibility(&mut self) {
+        if !self.accessibility.is_active() {
+            self.accessibility_tree = None;
+            return;
+        }
+
+        let invalidated =
+            self.enter_arena(|_, gc_root, _| gc_root.stage.accessibility_invalidated());
+        if self.accessibility_tree.is_some() && !invalidated {
+            return;
+        }
+
+        let tree = self.mutate_with_update_context(|context| {
+            context
+                .stage
+                .set_accessibility_invalidated(context.gc(), false);
+            crate::accessibility::build_tree(context)
+        });
+        if self.accessibility_tree.as_ref() != Some(&tree) {
+            self.accessibility_tree = Some(tree.clone());
+            self.accessibility.update_tree(tree);
+        }
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub fn render(&mut self) {
+        // Rendering resets the invalidation of the display list, so pick up any changes
+        // made since the last frame (e.g. by input events) before that happens.
+        self.update_accessibility();
+
         let invalidated = self.enter_arena(|_, gc_root, _| gc_root.stage.invalidated());
 
         if invalidated {
@@ -2051,6 +2181,22 @@ impl Player {
         &mut self.storage
     }
 
+    pub fn filesystem(&self) -> &Filesystem {
+        &self.filesystem
+    }
+
+    pub fn filesystem_mut(&mut self) -> &mut Filesystem {
+        &mut self.filesystem
+    }
+
+    pub fn accessibility(&self) -> &Accessibility {
+        &self.accessibility
+    }
+
+    pub fn accessibility_mut(&mut self) -> &mut Accessibility {
+        &mut self.accessibility
+    }
+
     pub fn destroy(self) -> Renderer {
         self.renderer
     }
@@ -2184,6 +2330,7 @@ impl Player {
                 audio_manager,
                 stream_manager,
                 sockets,
+                native_processes,
                 net_connections,
                 local_connections,
                 post_frame_callbacks,
@@ -2214,6 +2361,8 @@ impl Player {
                 page_url: &mut this.page_url,
                 instance_counter: &mut this.instance_counter,
                 storage: this.storage.deref_mut(),
+                filesystem: this.filesystem.deref_mut(),
+                accessibility: this.accessibility.deref_mut(),
                 log: this.log.deref_mut(),
                 video: this.video.deref_mut(),
                 avm1_shared_objects,
@@ -2239,6 +2388,7 @@ impl Player {
                 stub_tracker: &mut this.stub_tracker,
                 stream_manager,
                 sockets,
+                native_processes,
                 net_connections,
                 local_connections,
                 dynamic_root,
@@ -2356,6 +2506,13 @@ impl Player {
         })
     }
 
+    /// Update running native processes.
+    pub fn update_native_processes(&mut self) {
+        self.mutate_with_update_context(|context| {
+            NativeProcesses::update_processes(context);
+        })
+    }
+
     /// Update connected NetConnections.
     pub fn update_net_connections(&mut self) {
         self.mutate_with_update_context(|context| {
@@ -2438,7 +2595,9 @@ pub struct PlayerBuilder {
     movie: Option<SwfMovie>,
 
     // Backends
+    accessibility: Option<Accessibility>,
     audio: Option<Audio>,
+    filesystem: Option<Filesystem>,
     log: Option<Log>,
     navigator: Option<Navigator>,
     renderer: Option<Renderer>,
@@ -2486,7 +2645,9 @@ impl PlayerBuilder {
         Self {
             movie: None,
 
+            accessibility: None,
             audio: None,
+            filesystem: None,
             log: None,
             navigator: None,
             renderer: None,
@@ -2536,6 +2697,16 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets the accessibility backend of the player.
+    #[inline]
+    pub fn with_accessibility(
+        mut self,
+        accessibility: impl 'static + AccessibilityBackend,
+    ) -> Self {
+        self.accessibility = Some(Box::new(accessibility));
+        self
+    }
+
     /// Sets the audio backend of the player.
     #[inline]
     pub fn with_audio(mut self, audio: impl 'static + AudioBackend) -> Self {
@@ -2550,6 +2721,13 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets the filesystem backend of the player, used by AIR applications.
+    #[inline]
+    pub fn with_filesystem(mut self, filesystem: impl 'static + FilesystemBackend) -> Self {
+        self.filesystem = Some(Box::new(filesystem));
+        self
+    }
+
     /// Sets the logging backend of the player.
     #[inline]
     pub fn with_log(mut self, log: impl 'static + LogBackend) -> Self {
@@ -2774,6 +2952,10 @@ impl PlayerBuilder {
                 pressed: None,
                 right_pressed: None,
                 middle_pressed: None,
+                auto_cursor: MouseCursor::Arrow,
+                cursor_name: "auto".to_string(),
+                registered_cursors: HashMap::new(),
+                displayed_cursor: MouseCursor::Arrow,
             },
             avm1_shared_objects: HashMap::new(),
             avm2_shared_objects: HashMap::new(),
@@ -2782,6 +2964,7 @@ impl PlayerBuilder {
             unbound_text_fields: Vec::new(),
             stream_manager: StreamManager::new(),
             sockets: Sockets::empty(),
+            native_processes: NativeProcesses::empty(),
             net_connections: NetConnections::default(),
             local_connections: LocalConnections::empty(),
             dynamic_root: DynamicRootSet::new(gc_context),
@@ -2798,9 +2981,15 @@ impl PlayerBuilder {
     pub fn build(self) -> Arc<Mutex<Player>> {
         use crate::backend::*;
         use ruffle_video::null;
+        let accessibility = self
+            .accessibility
+            .unwrap_or_else(|| Box::new(accessibility::NullAccessibilityBackend::new()));
         let audio = self
             .audio
             .unwrap_or_else(|| Box::new(audio::NullAudioBackend::new()));
+        let filesystem = self
+            .filesystem
+            .unwrap_or_else(|| Box::new(filesystem::MemoryFilesystemBackend::new()));
         let log = self
             .log
             .unwrap_or_else(|| Box::new(log::NullLogBackend::new()));
@@ -2833,7 +3022,10 @@ impl PlayerBuilder {
         let player = Arc::new_cyclic(|self_ref| {
             Mutex::new(Player {
                 // Backends
+                accessibility,
+                accessibility_tree: None,
                 audio,
+                filesystem,
                 log,
                 navigator,
                 renderer,
@@ -2861,7 +3053,6 @@ impl PlayerBuilder {
                 input: Default::default(),
                 mouse_in_stage: true,
                 mouse_position: Point::ZERO,
-                mouse_cursor: MouseCursor::Arrow,
                 mouse_cursor_needs_check: false,
 
                 // Misc. state
@@ -8,6 +8,7 @@ use crate::util::{
 };
 use anyhow::{Context, Error};
 use gilrs::{Event, EventType, Gilrs};
+use ruffle_core::events::ImeEvent;
 use ruffle_core::PlayerEvent;
 use ruffle_render::backend::ViewportDimensions;
 use std::cell::RefCell;
@@ -16,7 +17,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use url::Url;
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Size};
-use winit::event::{ElementState, KeyEvent, Modifiers, WindowEvent};
+use winit::event::{ElementState, Ime, KeyEvent, Modifiers, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop, EventLoopBuilder};
 use winit::keyboard::{Key, NamedKey};
 use winit::window::{Fullscreen, Icon, Window, WindowBuilder};
@@ -372,6 +373,15 @@ impl App {
                             };
                             check_redraw = true;
                         }
+                        WindowEvent::Ime(ime) => {
+                            let event = match ime {
+                                Ime::Preedit(text, cursor) => ImeEvent::Preedit(text, cursor),
+                                Ime::Commit(text) => ImeEvent::Commit(text),
+                                Ime::Enabled | Ime::Disabled => return,
+                            };
+                            self.player.handle_event(PlayerEvent::Ime(event));
+                            check_redraw = true;
+                        }
                         _ => (),
                     }
                 }
@@ -480,6 +490,10 @@ impl App {
                     }
                 }
 
+                winit::event::Event::UserEvent(RuffleEvent::AccessKitActionRequest(request)) => {
+                    self.gui.borrow_mut().on_accesskit_action_request(request);
+                }
+
                 winit::event::Event::UserEvent(RuffleEvent::BrowseAndOpen(options)) => {
                     let event_loop = event_loop_proxy.clone();
                     let picker = self.gui.borrow().file_picker();
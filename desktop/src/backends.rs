@@ -1,9 +1,11 @@
+mod accessibility;
 mod audio;
 mod external_interface;
 mod fscommand;
 mod navigator;
 mod ui;
 
+pub use accessibility::DesktopAccessibilityBackend;
 pub use audio::CpalAudioBackend;
 pub use external_interface::DesktopExternalInterfaceProvider;
 pub use fscommand::DesktopFSCommandProvider;
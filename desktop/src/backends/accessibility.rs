@@ -0,0 +1,110 @@
+use egui::accesskit::{NodeBuilder, Rect, Role};
+use egui::{Context, Id};
+use ruffle_core::backend::accessibility::{
+    AccessibilityBackend, AccessibilityNode, AccessibilityRole, AccessibilityState,
+};
+
+/// Keeps the latest accessibility tree of the movie, so that the GUI can
+/// publish it to the platform's accessibility layer (through AccessKit).
+#[derive(Default)]
+pub struct DesktopAccessibilityBackend {
+    /// Whether a screen reader asked for the accessibility tree.
+    active: bool,
+    tree: Option<AccessibilityNode>,
+}
+
+impl DesktopAccessibilityBackend {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds the movie's nodes to the AccessKit tree egui is building for this frame.
+    ///
+    /// `menu_height_offset` is the height of the menu bar in physical pixels.
+    pub fn show(&mut self, ctx: &Context, menu_height_offset: f64) {
+        let root_id = Id::new("ruffle_accessibility");
+
+        // egui only hands out node builders while AccessKit is enabled,
+        // which happens once assistive technology requests the tree.
+        self.active = ctx
+            .accesskit_node_builder(root_id, |builder| {
+                builder.set_role(Role::Window);
+            })
+            .is_some();
+        if !self.active {
+            return;
+        }
+
+        if let Some(tree) = &self.tree {
+            let pixels_per_point = ctx.pixels_per_point() as f64;
+            ctx.with_accessibility_parent(root_id, || {
+                add_node(ctx, tree, pixels_per_point, menu_height_offset);
+            });
+        }
+    }
+}
+
+impl AccessibilityBackend for DesktopAccessibilityBackend {
+    fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn update_tree(&mut self, tree: AccessibilityNode) {
+        self.tree = Some(tree);
+    }
+}
+
+fn add_node(ctx: &Context, node: &AccessibilityNode, pixels_per_point: f64, y_offset: f64) {
+    let id = Id::new(("ruffle_accessibility", node.id));
+    ctx.accesskit_node_builder(id, |builder| {
+        fill_node(builder, node, pixels_per_point, y_offset);
+    });
+    ctx.with_accessibility_parent(id, || {
+        for child in &node.children {
+            add_node(ctx, child, pixels_per_point, y_offset);
+        }
+    });
+}
+
+fn fill_node(
+    builder: &mut NodeBuilder,
+    node: &AccessibilityNode,
+    pixels_per_point: f64,
+    y_offset: f64,
+) {
+    builder.set_role(match node.role {
+        AccessibilityRole::Client => Role::Pane,
+        AccessibilityRole::Graphic => Role::Image,
+        AccessibilityRole::PushButton => Role::Button,
+        AccessibilityRole::StaticText => Role::Label,
+        AccessibilityRole::EditableText if node.state.contains(AccessibilityState::PROTECTED) => {
+            Role::PasswordInput
+        }
+        AccessibilityRole::EditableText if node.state.contains(AccessibilityState::MULTILINE) => {
+            Role::MultilineTextInput
+        }
+        AccessibilityRole::EditableText => Role::TextInput,
+        AccessibilityRole::Other(_) => Role::GenericContainer,
+    });
+    if !node.name.is_empty() {
+        builder.set_name(node.name.clone());
+    }
+    if !node.description.is_empty() {
+        builder.set_description(node.description.clone());
+    }
+    if !node.shortcut.is_empty() {
+        builder.set_keyboard_shortcut(node.shortcut.clone());
+    }
+    if let Some(value) = &node.value {
+        builder.set_value(value.clone());
+    }
+    if node.state.contains(AccessibilityState::READ_ONLY) {
+        builder.set_read_only();
+    }
+    builder.set_bounds(Rect {
+        x0: node.bounds.x_min.to_pixels() / pixels_per_point,
+        y0: (node.bounds.y_min.to_pixels() + y_offset) / pixels_per_point,
+        x1: node.bounds.x_max.to_pixels() / pixels_per_point,
+        y1: (node.bounds.y_max.to_pixels() + y_offset) / pixels_per_point,
+    });
+}
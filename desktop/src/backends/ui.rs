@@ -8,11 +8,14 @@ use rfd::{
 };
 use ruffle_core::backend::navigator::OpenURLMode;
 use ruffle_core::backend::ui::{
-    DialogLoaderError, DialogResultFuture, FileDialogResult, FileFilter, FontDefinition,
-    FullscreenError, LanguageIdentifier, MouseCursor, UiBackend,
+    ClipboardData, ClipboardFormat, CustomMouseCursor, DialogLoaderError, DialogResultFuture,
+    FileDialogResult, FileFilter, FontDefinition, FullscreenError, LanguageIdentifier, MouseCursor,
+    MultipleDialogResultFuture, UiBackend,
 };
+use ruffle_core::swf::{Rectangle, Twips};
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Instant;
 use tracing::error;
 use url::Url;
 use winit::raw_window_handle::HasDisplayHandle;
@@ -112,16 +115,59 @@ impl FileDialogResult for DesktopFileDialogResult {
     }
 }
 
+/// A custom cursor of the movie, uploaded to egui.
+struct CustomCursorTextures {
+    cursor: Arc<CustomMouseCursor>,
+    frames: Vec<egui::TextureHandle>,
+    shown_since: Instant,
+}
+
+impl CustomCursorTextures {
+    fn new(egui_ctx: &egui::Context, cursor: Arc<CustomMouseCursor>) -> Self {
+        let frames = cursor
+            .frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let image = egui::ColorImage::from_rgba_unmultiplied(
+                    [frame.width as usize, frame.height as usize],
+                    &frame.rgba,
+                );
+                egui_ctx.load_texture(
+                    format!("custom_cursor_{i}"),
+                    image,
+                    egui::TextureOptions::NEAREST,
+                )
+            })
+            .collect();
+
+        Self {
+            cursor,
+            frames,
+            shown_since: Instant::now(),
+        }
+    }
+}
+
 pub struct DesktopUiBackend {
     window: Arc<Window>,
     cursor_visible: bool,
     clipboard: Clipboard,
+    /// Non-text clipboard payloads set by the movie, along with the text copied with them.
+    /// The system clipboard only holds text, so these are dropped once it changes.
+    clipboard_data: Option<(String, Vec<ClipboardData>)>,
     preferences: GlobalPreferences,
     preferred_cursor: MouseCursor,
     open_url_mode: OpenURLMode,
     font_database: Rc<fontdb::Database>,
     /// Is a dialog currently open
     dialog_open: bool,
+    /// Where the movie is entering text, waiting to be passed to the IME
+    ime_cursor_area: Option<Rectangle<Twips>>,
+    /// The textures of the custom cursor currently in use
+    custom_cursor: Option<CustomCursorTextures>,
+    /// Whether the custom cursor was painted in the last frame, in place of the OS cursor
+    custom_cursor_shown: bool,
 }
 
 impl DesktopUiBackend {
@@ -144,11 +190,15 @@ impl DesktopUiBackend {
             window,
             cursor_visible: true,
             clipboard,
+            clipboard_data: None,
             preferences,
             preferred_cursor: MouseCursor::Arrow,
             open_url_mode,
             dialog_open: false,
             font_database,
+            ime_cursor_area: None,
+            custom_cursor: None,
+            custom_cursor_shown: false,
         })
     }
 
@@ -159,15 +209,117 @@ impl DesktopUiBackend {
                 MouseCursor::Hand => egui::CursorIcon::PointingHand,
                 MouseCursor::IBeam => egui::CursorIcon::Text,
                 MouseCursor::Grab => egui::CursorIcon::Grab,
+                // Painted by `show_custom_cursor` instead, as long as it can be.
+                MouseCursor::Custom(_) if self.custom_cursor_shown => egui::CursorIcon::None,
+                MouseCursor::Custom(_) => egui::CursorIcon::Default,
             }
         } else {
             egui::CursorIcon::None
         }
     }
+
+    /// Whether the custom cursor was painted in the last frame.
+    ///
+    /// The OS cursor has to be hidden whenever it was, even over egui's UI, and shown otherwise.
+    pub fn is_custom_cursor_shown(&self) -> bool {
+        self.custom_cursor_shown
+    }
+
+    /// Paints the custom cursor of the movie at the mouse position, if one is in use.
+    ///
+    /// Custom cursors aren't OS cursors: winit 0.29 can't create cursors from images, so egui
+    /// paints the cursor on top of the window while the OS cursor is hidden. This means the
+    /// cursor is only shown inside the window, lags behind the mouse by up to a frame, and is
+    /// replaced by the OS cursor over egui's own UI.
+    pub fn show_custom_cursor(&mut self, egui_ctx: &egui::Context) {
+        self.custom_cursor_shown = false;
+        let MouseCursor::Custom(cursor) = &self.preferred_cursor else {
+            self.custom_cursor = None;
+            return;
+        };
+        if !self.cursor_visible || egui_ctx.wants_pointer_input() {
+            return;
+        }
+        let Some(position) = egui_ctx.pointer_hover_pos() else {
+            return;
+        };
+
+        if !matches!(&self.custom_cursor, Some(textures) if Arc::ptr_eq(&textures.cursor, cursor)) {
+            self.custom_cursor = Some(CustomCursorTextures::new(egui_ctx, cursor.clone()));
+        }
+        let Some(textures) = &self.custom_cursor else {
+            return;
+        };
+
+        let frame = &textures.frames[cursor.frame_at(textures.shown_since.elapsed())];
+        let pixels_per_point = egui_ctx.pixels_per_point();
+        let hotspot = egui::vec2(cursor.hotspot.0 as f32, cursor.hotspot.1 as f32);
+        let rect = egui::Rect::from_min_size(
+            position - hotspot / pixels_per_point,
+            frame.size_vec2() / pixels_per_point,
+        );
+
+        egui_ctx
+            .layer_painter(egui::LayerId::new(
+                egui::Order::Tooltip,
+                egui::Id::new("custom_cursor"),
+            ))
+            .image(
+                frame.id(),
+                rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+
+        self.custom_cursor_shown = true;
+
+        if let Some(frame_duration) = cursor.frame_duration() {
+            egui_ctx.request_repaint_after(frame_duration);
+        }
+    }
+
+    /// Returns the non-text clipboard payloads, if the system clipboard still holds what they were copied with.
+    fn clipboard_extra_data(&mut self) -> &[ClipboardData] {
+        let text = self.clipboard.get().unwrap_or_default();
+        if matches!(&self.clipboard_data, Some((copied, _)) if *copied != text) {
+            self.clipboard_data = None;
+        }
+        self.clipboard_data
+            .as_ref()
+            .map_or(&[][..], |(_, data)| data.as_slice())
+    }
+
+    /// Takes the area (in viewport coordinates) the IME candidate window should be placed next to,
+    /// if it changed since last time.
+    pub fn take_ime_cursor_area(&mut self) -> Option<Rectangle<Twips>> {
+        self.ime_cursor_area.take()
+    }
 }
 
 const DOWNLOAD_FAILED_MESSAGE: &str = "Ruffle failed to open or download this file.";
 
+/// Creates a dialog for opening files of the types matching the given filters.
+fn open_file_dialog(filters: Vec<FileFilter>) -> AsyncFileDialog {
+    let mut dialog = AsyncFileDialog::new();
+
+    for filter in filters {
+        if cfg!(target_os = "macos") && filter.mac_type.is_some() {
+            let mac_type = filter.mac_type.expect("Checked above");
+            let extensions: Vec<&str> = mac_type.split(';').collect();
+            dialog = dialog.add_filter(&filter.description, &extensions);
+        } else {
+            let extensions: Vec<&str> = filter
+                .extensions
+                .split(';')
+                .map(|x| x.trim_start_matches("*."))
+                .collect();
+            dialog = dialog.add_filter(&filter.description, &extensions);
+        }
+    }
+
+    dialog
+}
+
 impl UiBackend for DesktopUiBackend {
     fn mouse_visible(&self) -> bool {
         self.cursor_visible
@@ -186,9 +338,49 @@ impl UiBackend for DesktopUiBackend {
     }
 
     fn set_clipboard_content(&mut self, content: String) {
+        self.clipboard_data = None;
         self.clipboard.set(content);
     }
 
+    fn clipboard_formats(&mut self) -> Vec<ClipboardFormat> {
+        let mut formats = Vec::new();
+        if self.clipboard_available() {
+            formats.push(ClipboardFormat::Text);
+        }
+        formats.extend(
+            self.clipboard_extra_data()
+                .iter()
+                .map(ClipboardData::format),
+        );
+        formats
+    }
+
+    fn clipboard_data(&mut self, format: ClipboardFormat) -> Option<ClipboardData> {
+        if format == ClipboardFormat::Text {
+            return self
+                .clipboard
+                .get()
+                .filter(|text| !text.is_empty())
+                .map(ClipboardData::Text);
+        }
+        self.clipboard_extra_data()
+            .iter()
+            .find(|data| data.format() == format)
+            .cloned()
+    }
+
+    fn set_clipboard_data(&mut self, data: Vec<ClipboardData>) {
+        let (text, other): (Vec<_>, Vec<_>) = data
+            .into_iter()
+            .partition(|data| data.format() == ClipboardFormat::Text);
+        let text = match text.into_iter().next() {
+            Some(ClipboardData::Text(text)) => text,
+            _ => String::new(),
+        };
+        self.clipboard.set(text.clone());
+        self.clipboard_data = Some((text, other));
+    }
+
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
         self.window.set_fullscreen(if is_full {
             Some(Fullscreen::Borderless(None))
@@ -313,6 +505,14 @@ impl UiBackend for DesktopUiBackend {
 
     fn close_virtual_keyboard(&self) {}
 
+    fn set_ime_enabled(&mut self, enabled: bool) {
+        self.window.set_ime_allowed(enabled);
+    }
+
+    fn set_ime_cursor_area(&mut self, area: Rectangle<Twips>) {
+        self.ime_cursor_area = Some(area);
+    }
+
     fn language(&self) -> LanguageIdentifier {
         self.preferences.language().clone()
     }
@@ -326,22 +526,7 @@ impl UiBackend for DesktopUiBackend {
 
         // Create the dialog future
         Some(Box::pin(async move {
-            let mut dialog = AsyncFileDialog::new();
-
-            for filter in filters {
-                if cfg!(target_os = "macos") && filter.mac_type.is_some() {
-                    let mac_type = filter.mac_type.expect("Checked above");
-                    let extensions: Vec<&str> = mac_type.split(';').collect();
-                    dialog = dialog.add_filter(&filter.description, &extensions);
-                } else {
-                    let extensions: Vec<&str> = filter
-                        .extensions
-                        .split(';')
-                        .map(|x| x.trim_start_matches("*."))
-                        .collect();
-                    dialog = dialog.add_filter(&filter.description, &extensions);
-                }
-            }
+            let dialog = open_file_dialog(filters);
 
             let result: Result<Box<dyn FileDialogResult>, DialogLoaderError> = Ok(Box::new(
                 DesktopFileDialogResult::new(dialog.pick_file().await),
@@ -350,6 +535,31 @@ impl UiBackend for DesktopUiBackend {
         }))
     }
 
+    fn display_file_open_dialog_multiple(
+        &mut self,
+        filters: Vec<FileFilter>,
+    ) -> Option<MultipleDialogResultFuture> {
+        // Prevent opening multiple dialogs at the same time
+        if self.dialog_open {
+            return None;
+        }
+        self.dialog_open = true;
+
+        // Create the dialog future
+        Some(Box::pin(async move {
+            let dialog = open_file_dialog(filters);
+
+            let handles = dialog.pick_files().await.unwrap_or_default();
+            Ok(handles
+                .into_iter()
+                .map(|handle| {
+                    Box::new(DesktopFileDialogResult::new(Some(handle)))
+                        as Box<dyn FileDialogResult>
+                })
+                .collect())
+        }))
+    }
+
     fn display_file_save_dialog(
         &mut self,
         file_name: String,
@@ -121,6 +121,10 @@ pub struct Opt {
     #[clap(long = "socket-allow", number_of_values = 1, action = clap::ArgAction::Append)]
     pub socket_allow: Vec<String>,
 
+    /// Add an executable that AIR applications may start with `NativeProcess`.
+    #[clap(long = "process-allow", number_of_values = 1, action = clap::ArgAction::Append)]
+    pub process_allow: Vec<std::path::PathBuf>,
+
     /// Define how to deal with TCP Socket connections.
     #[clap(long = "tcp-connections")]
     pub tcp_connections: Option<SocketMode>,
@@ -213,6 +217,27 @@ pub struct Opt {
     /// (like inlining constant pool entries) can't be disabled.
     #[clap(long)]
     pub no_avm2_optimizer: bool,
+
+    /// Import Flash Player shared objects (`.sol` files) into the save directory, then exit.
+    ///
+    /// This may either be Flash Player's `#SharedObjects` directory, or one of the profile
+    /// directories inside of it. Existing saves are kept unless `--overwrite-shared-objects` is given.
+    #[clap(
+        long,
+        value_name = "DIRECTORY",
+        conflicts_with = "export_shared_objects"
+    )]
+    pub import_shared_objects: Option<std::path::PathBuf>,
+
+    /// Export all shared objects from the save directory as `.sol` files, then exit.
+    ///
+    /// The resulting directory can be placed inside of Flash Player's `#SharedObjects` directory.
+    #[clap(long, value_name = "DIRECTORY")]
+    pub export_shared_objects: Option<std::path::PathBuf>,
+
+    /// Replace existing saves when using `--import-shared-objects`.
+    #[clap(long, action, requires = "import_shared_objects")]
+    pub overwrite_shared_objects: bool,
 }
 
 fn parse_movie_file_or_url(path: &str) -> Result<Url, Error> {
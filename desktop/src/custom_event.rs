@@ -30,4 +30,13 @@ pub enum RuffleEvent {
 
     /// The user selected an item in the right-click context menu.
     ContextMenuItemClicked(usize),
+
+    /// Assistive technology requested an action on a GUI or movie element.
+    AccessKitActionRequest(egui_winit::accesskit_winit::ActionRequestEvent),
+}
+
+impl From<egui_winit::accesskit_winit::ActionRequestEvent> for RuffleEvent {
+    fn from(event: egui_winit::accesskit_winit::ActionRequestEvent) -> Self {
+        RuffleEvent::AccessKitActionRequest(event)
+    }
 }
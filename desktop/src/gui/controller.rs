@@ -1,4 +1,4 @@
-use crate::backends::DesktopUiBackend;
+use crate::backends::{DesktopAccessibilityBackend, DesktopUiBackend};
 use crate::custom_event::RuffleEvent;
 use crate::gui::movie::{MovieView, MovieViewRenderer};
 use crate::gui::theme::ThemeController;
@@ -17,7 +17,7 @@ use std::time::{Duration, Instant};
 use unic_langid::LanguageIdentifier;
 use url::Url;
 use wgpu::SurfaceError;
-use winit::dpi::PhysicalSize;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::WindowEvent;
 use winit::event_loop::EventLoop;
 use winit::keyboard::{Key, NamedKey};
@@ -99,9 +99,20 @@ impl GuiController {
 
         let theme_controller =
             ThemeController::new(window.clone(), preferences.clone(), egui_ctx.clone()).await;
-        let mut egui_winit =
-            egui_winit::State::new(egui_ctx, ViewportId::ROOT, window.as_ref(), None, None);
+        let mut egui_winit = egui_winit::State::new(
+            egui_ctx.clone(),
+            ViewportId::ROOT,
+            window.as_ref(),
+            None,
+            None,
+        );
         egui_winit.set_max_texture_side(descriptors.limits.max_texture_dimension_2d as usize);
+        egui_winit.init_accesskit(window.as_ref(), event_loop.clone(), move || {
+            // A screen reader asked for the tree, start building it from now on.
+            egui_ctx.enable_accesskit();
+            egui_ctx.request_repaint();
+            egui_ctx.accesskit_placeholder_tree_update()
+        });
 
         let movie_view_renderer = Arc::new(MovieViewRenderer::new(
             &descriptors.device,
@@ -215,6 +226,14 @@ impl GuiController {
         response.consumed
     }
 
+    pub fn on_accesskit_action_request(
+        &mut self,
+        request: egui_winit::accesskit_winit::ActionRequestEvent,
+    ) {
+        self.egui_winit.on_accesskit_action_request(request.request);
+        self.window.request_redraw();
+    }
+
     pub fn create_movie(
         &mut self,
         player: &mut PlayerController,
@@ -268,17 +287,32 @@ impl GuiController {
 
         let raw_input = self.egui_winit.take_egui_input(&self.window);
         let show_menu = self.window.fullscreen().is_none() && !self.no_gui;
+        let menu_height_offset = if show_menu {
+            MENU_HEIGHT as f64 * self.window.scale_factor()
+        } else {
+            0.0
+        };
         let mut full_output = self.egui_winit.egui_ctx().run(raw_input, |context| {
             self.gui.update(
                 context,
                 show_menu,
                 player.as_deref_mut(),
-                if show_menu {
-                    MENU_HEIGHT as f64 * self.window.scale_factor()
-                } else {
-                    0.0
-                },
+                menu_height_offset,
             );
+            if let Some(player) = player.as_deref_mut() {
+                player
+                    .accessibility_mut()
+                    .downcast_mut::<DesktopAccessibilityBackend>()
+                    .unwrap_or_else(|| {
+                        panic!("Accessibility Backend should be DesktopAccessibilityBackend")
+                    })
+                    .show(context, menu_height_offset);
+                player
+                    .ui_mut()
+                    .downcast_mut::<DesktopUiBackend>()
+                    .unwrap_or_else(|| panic!("UI Backend should be DesktopUiBackend"))
+                    .show_custom_cursor(context);
+            }
         });
         self.repaint_after = full_output
             .viewport_output
@@ -286,19 +320,38 @@ impl GuiController {
             .expect("Root viewport must exist")
             .repaint_delay;
 
-        // If we're not in a UI, tell egui which cursor we prefer to use instead
-        if !self.egui_winit.egui_ctx().wants_pointer_input() {
-            if let Some(player) = player.as_deref() {
-                full_output.platform_output.cursor_icon = player
-                    .ui()
-                    .downcast_ref::<DesktopUiBackend>()
-                    .unwrap_or_else(|| panic!("UI Backend should be DesktopUiBackend"))
-                    .cursor();
+        // If we're not in a UI, tell egui which cursor we prefer to use instead.
+        // A painted custom cursor always replaces the OS cursor, so that they are never both shown.
+        if let Some(player) = player.as_deref() {
+            let ui = player
+                .ui()
+                .downcast_ref::<DesktopUiBackend>()
+                .unwrap_or_else(|| panic!("UI Backend should be DesktopUiBackend"));
+            if !self.egui_winit.egui_ctx().wants_pointer_input() || ui.is_custom_cursor_shown() {
+                full_output.platform_output.cursor_icon = ui.cursor();
             }
         }
         self.egui_winit
             .handle_platform_output(&self.window, full_output.platform_output);
 
+        // Place the IME candidate window next to the text being entered in the movie.
+        if let Some(player) = player.as_deref_mut() {
+            if let Some(area) = player
+                .ui_mut()
+                .downcast_mut::<DesktopUiBackend>()
+                .unwrap_or_else(|| panic!("UI Backend should be DesktopUiBackend"))
+                .take_ime_cursor_area()
+            {
+                self.window.set_ime_cursor_area(
+                    PhysicalPosition::new(
+                        area.x_min.to_pixels(),
+                        area.y_min.to_pixels() + menu_height_offset,
+                    ),
+                    PhysicalSize::new(area.width().to_pixels(), area.height().to_pixels()),
+                );
+            }
+        }
+
         let clipped_primitives = self
             .egui_winit
             .egui_ctx()
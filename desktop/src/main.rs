@@ -24,6 +24,10 @@ use clap::Parser;
 use cli::Opt;
 use rfd::MessageDialogResult;
 use ruffle_core::StaticCallstack;
+use ruffle_frontend_utils::backends::storage::DiskStorageBackend;
+use ruffle_frontend_utils::shared_objects::{
+    export_shared_objects, import_flash_shared_objects, SharedObjectTransfer,
+};
 use std::cell::RefCell;
 use std::env;
 use std::fs::File;
@@ -147,6 +151,17 @@ fn shutdown() {
     }
 }
 
+fn report_shared_object_transfer(action: &str, result: &SharedObjectTransfer) {
+    for (name, reason) in &result.skipped {
+        tracing::warn!("Skipped shared object {name}: {reason:?}");
+    }
+    tracing::info!(
+        "{action} {} shared objects ({} skipped)",
+        result.copied.len(),
+        result.skipped.len()
+    );
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     init();
@@ -181,6 +196,24 @@ async fn main() -> Result<(), Error> {
 
     subscriber.init();
 
+    if let Some(source) = &preferences.cli.import_shared_objects {
+        let mut storage = DiskStorageBackend::new(preferences.cli.save_directory.clone());
+        let result = import_flash_shared_objects(
+            source,
+            &mut storage,
+            preferences.cli.overwrite_shared_objects,
+        )?;
+        report_shared_object_transfer("Imported", &result);
+        return Ok(());
+    }
+
+    if let Some(destination) = &preferences.cli.export_shared_objects {
+        let storage = DiskStorageBackend::new(preferences.cli.save_directory.clone());
+        let result = export_shared_objects(&storage, destination)?;
+        report_shared_object_transfer("Exported", &result);
+        return Ok(());
+    }
+
     let result = App::new(preferences).await.and_then(|app| app.run());
 
     #[cfg(windows)]
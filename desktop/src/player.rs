@@ -1,6 +1,6 @@
 use crate::backends::{
-    CpalAudioBackend, DesktopExternalInterfaceProvider, DesktopFSCommandProvider, DesktopUiBackend,
-    RfdNavigatorInterface,
+    CpalAudioBackend, DesktopAccessibilityBackend, DesktopExternalInterfaceProvider,
+    DesktopFSCommandProvider, DesktopUiBackend, RfdNavigatorInterface,
 };
 use crate::custom_event::RuffleEvent;
 use crate::gui::MovieView;
@@ -12,6 +12,7 @@ use ruffle_core::config::Letterbox;
 use ruffle_core::events::{GamepadButton, KeyCode};
 use ruffle_core::{DefaultFont, LoadBehavior, Player, PlayerBuilder, PlayerEvent};
 use ruffle_frontend_utils::backends::executor::{AsyncExecutor, PollRequester};
+use ruffle_frontend_utils::backends::filesystem::DiskFilesystemBackend;
 use ruffle_frontend_utils::backends::navigator::ExternalNavigatorBackend;
 use ruffle_frontend_utils::bundle::source::BundleSourceError;
 use ruffle_frontend_utils::bundle::{Bundle, BundleError};
@@ -41,6 +42,7 @@ pub struct LaunchOptions {
     pub proxy: Option<Url>,
     pub socket_allowed: HashSet<String>,
     pub tcp_connections: Option<SocketMode>,
+    pub process_allowed: HashSet<PathBuf>,
     pub fullscreen: bool,
     pub save_directory: PathBuf,
     pub open_url_mode: OpenURLMode,
@@ -93,6 +95,7 @@ impl From<&GlobalPreferences> for LaunchOptions {
             open_url_mode: value.cli.open_url_mode,
             socket_allowed: HashSet::from_iter(value.cli.socket_allow.iter().cloned()),
             tcp_connections: value.cli.tcp_connections,
+            process_allowed: HashSet::from_iter(value.cli.process_allow.iter().cloned()),
             gamepad_button_mapping: HashMap::from_iter(value.cli.gamepad_button.iter().cloned()),
             avm2_optimizer_enabled: !value.cli.no_avm2_optimizer,
         }
@@ -191,6 +194,7 @@ impl ActivePlayer {
                     proxy: opt.proxy.clone(),
                     socket_allowed: opt.socket_allowed.clone(),
                     tcp_connections: opt.tcp_connections,
+                    process_allowed: opt.process_allowed.clone(),
                     fullscreen: opt.fullscreen,
                     save_directory: opt.save_directory.clone(),
                     open_url_mode: opt.open_url_mode,
@@ -203,6 +207,14 @@ impl ActivePlayer {
         let (executor, future_spawner) = AsyncExecutor::new(WinitWaker(event_loop.clone()));
         let movie_url = content.initial_swf_url().clone();
         let readable_name = content.name();
+        let storage_name = readable_name.replace(
+            |c: char| !c.is_alphanumeric() && !matches!(c, '-' | '_' | '.' | ' '),
+            "_",
+        );
+        let filesystem = DiskFilesystemBackend::new(
+            content.application_directory(),
+            opt.save_directory.join("app-storage").join(storage_name),
+        );
         let navigator = ExternalNavigatorBackend::new(
             opt.player
                 .base
@@ -216,6 +228,7 @@ impl ActivePlayer {
             opt.open_url_mode,
             opt.socket_allowed.clone(),
             opt.tcp_connections.unwrap_or(SocketMode::Ask),
+            opt.process_allowed.clone(),
             Rc::new(content),
             RfdNavigatorInterface,
         );
@@ -262,6 +275,8 @@ impl ActivePlayer {
             .with_navigator(navigator)
             .with_renderer(renderer)
             .with_storage(preferences.storage_backend().create_backend(&opt))
+            .with_filesystem(filesystem)
+            .with_accessibility(DesktopAccessibilityBackend::new())
             .with_fs_commands(Box::new(DesktopFSCommandProvider {
                 event_loop: event_loop.clone(),
             }))
@@ -18,6 +18,8 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use walkdir::{DirEntry, WalkDir};
 
+mod svg;
+
 #[derive(Parser, Debug, Copy, Clone)]
 struct SizeOpt {
     /// The amount to scale the page size with
@@ -76,6 +78,15 @@ struct Opt {
     /// Skip unsupported movie types (currently AVM 2)
     #[clap(long, action)]
     skip_unsupported: bool,
+
+    /// Export the shapes and morph shapes of each file as SVG images, instead of capturing frames.
+    /// The images of each file are stored in a directory, named after the file by default.
+    #[clap(long, action)]
+    svg: bool,
+
+    /// The ratio to export morph shapes at with `--svg`, from 0 (the start shape) to 65535 (the end shape)
+    #[clap(long = "morph-ratio", default_value = "0")]
+    morph_ratio: u16,
 }
 
 /// Captures a screenshot. The resulting image uses straight alpha
@@ -377,12 +388,66 @@ fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()>
     Ok(())
 }
 
+fn export_svgs(opt: &Opt) -> Result<()> {
+    if opt.swf.is_file() {
+        let output = opt.output_path.clone().unwrap_or_else(|| {
+            let mut result = PathBuf::new();
+            result.set_file_name(opt.swf.file_stem().unwrap());
+            result
+        });
+        let count = svg::export_svgs(&opt.swf, &output, opt.morph_ratio)?;
+        if !opt.silent {
+            println!(
+                "Saved {count} shapes of {} to {}",
+                opt.swf.to_string_lossy(),
+                output.to_string_lossy()
+            );
+        }
+    } else if !opt.swf.is_dir() {
+        return Err(anyhow!("Given path is not a file or directory."));
+    } else if let Some(output) = &opt.output_path {
+        let files = find_files(&opt.swf, !opt.silent);
+        let count: usize = files
+            .par_iter()
+            .map(|file| {
+                let mut destination = output.clone();
+                let mut relative_path = file
+                    .path()
+                    .strip_prefix(&opt.swf)
+                    .unwrap_or_else(|_| file.path())
+                    .to_path_buf();
+                relative_path.set_extension("");
+                destination.push(relative_path);
+                svg::export_svgs(file.path(), &destination, opt.morph_ratio).unwrap_or(0)
+            })
+            .sum();
+        if !opt.silent {
+            println!(
+                "Saved {count} shapes of {} files to {}",
+                files.len(),
+                output.to_string_lossy()
+            );
+        }
+    } else {
+        return Err(anyhow!(
+            "Output directory is required when exporting multiple files."
+        ));
+    }
+
+    Ok(())
+}
+
 fn trace_path(_opt: &Opt) -> Option<&Path> {
     None
 }
 
 fn main() -> Result<()> {
     let opt: Opt = Opt::parse();
+    if opt.svg {
+        // Shapes are exported without rendering them, so no graphics device is needed.
+        return export_svgs(&opt);
+    }
+
     let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
         backends: opt.graphics.into(),
         ..Default::default()
@@ -0,0 +1,86 @@
+use anyhow::{anyhow, Result};
+use image::RgbaImage;
+use ruffle_core::swf::{self, CharacterId, Tag};
+use ruffle_render::bitmap::{Bitmap, BitmapSize};
+use ruffle_render::shape_utils::DistilledShape;
+use ruffle_render::svg::{morph_shape_to_svg, shape_to_svg, SvgBitmap};
+use ruffle_render::utils::{
+    decode_define_bits_jpeg, decode_define_bits_lossless, glue_tables_to_jpeg,
+    unmultiply_alpha_rgba,
+};
+use std::collections::HashMap;
+use std::fs::create_dir_all;
+use std::path::Path;
+
+/// Writes every shape and morph shape of a SWF file as an SVG image in `output`, along with the
+/// bitmaps used by their fills. Returns the number of images written.
+pub fn export_svgs(swf_path: &Path, output: &Path, morph_ratio: u16) -> Result<usize> {
+    let data = std::fs::read(swf_path)?;
+    let swf_buf = swf::decompress_swf(&data[..])?;
+    let swf = swf::parse_swf(&swf_buf)?;
+    create_dir_all(output)?;
+
+    let jpeg_tables = swf.tags.iter().find_map(|tag| match tag {
+        Tag::JpegTables(jpeg_tables) => Some(*jpeg_tables),
+        _ => None,
+    });
+    let mut bitmaps = HashMap::new();
+    for tag in &swf.tags {
+        let (id, bitmap) = match tag {
+            Tag::DefineBits { id, jpeg_data } => {
+                let jpeg_data = glue_tables_to_jpeg(jpeg_data, jpeg_tables);
+                (*id, decode_define_bits_jpeg(&jpeg_data, None))
+            }
+            Tag::DefineBitsJpeg2 { id, jpeg_data } => {
+                (*id, decode_define_bits_jpeg(jpeg_data, None))
+            }
+            Tag::DefineBitsJpeg3(jpeg) => (
+                jpeg.id,
+                decode_define_bits_jpeg(jpeg.data, Some(jpeg.alpha_data)),
+            ),
+            Tag::DefineBitsLossless(bitmap) => (bitmap.id, decode_define_bits_lossless(bitmap)),
+            _ => continue,
+        };
+        // A bitmap that can't be decoded leaves the fills using it empty.
+        if let Ok(bitmap) = bitmap {
+            bitmaps.insert(id, write_bitmap(id, bitmap, output)?);
+        }
+    }
+
+    let mut count = 0;
+    for tag in &swf.tags {
+        let (file_name, svg) = match tag {
+            Tag::DefineShape(shape) => (
+                format!("shape_{}.svg", shape.id),
+                shape_to_svg(&DistilledShape::from(shape), &bitmaps),
+            ),
+            Tag::DefineMorphShape(morph_shape) => (
+                format!("morph_shape_{}.svg", morph_shape.id),
+                morph_shape_to_svg(morph_shape, morph_ratio, &bitmaps),
+            ),
+            _ => continue,
+        };
+        std::fs::write(output.join(file_name), svg)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Writes a bitmap as a PNG file next to the images that use it.
+fn write_bitmap(id: CharacterId, bitmap: Bitmap, output: &Path) -> Result<SvgBitmap> {
+    let mut bitmap = bitmap.to_rgba();
+    let (width, height) = (bitmap.width(), bitmap.height());
+    unmultiply_alpha_rgba(bitmap.data_mut());
+    let image = RgbaImage::from_raw(width, height, bitmap.data().to_vec())
+        .ok_or_else(|| anyhow!("Invalid size {width}x{height} of bitmap {id}"))?;
+
+    let file_name = format!("bitmap_{id}.png");
+    image.save(output.join(&file_name))?;
+    Ok(SvgBitmap {
+        href: file_name,
+        size: BitmapSize {
+            width: width as u16,
+            height: height as u16,
+        },
+    })
+}
@@ -1,3 +1,4 @@
 pub mod executor;
+pub mod filesystem;
 pub mod navigator;
 pub mod storage;
@@ -0,0 +1,247 @@
+use crate::bundle::source::BundleSource;
+use ruffle_core::backend::filesystem::{FileMetadata, FileRoot, FilesystemBackend};
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+use url::Url;
+
+/// Where the files of an AIR application's `app:` directory come from.
+pub enum ApplicationDirectory {
+    /// The application has no files of its own.
+    None,
+
+    /// A directory on disk, such as the one containing the movie.
+    Directory(PathBuf),
+
+    /// The content of a bundle.
+    Bundle(BundleSource),
+}
+
+impl ApplicationDirectory {
+    /// Uses the content directory of a bundle, reading it straight from disk when possible.
+    pub fn from_bundle(source: BundleSource) -> Self {
+        match source {
+            BundleSource::Directory(directory) => Self::Directory(directory.join("content")),
+            source => Self::Bundle(source),
+        }
+    }
+}
+
+/// A filesystem exposing a read-only application directory and a writable storage directory on disk.
+pub struct DiskFilesystemBackend {
+    application_directory: ApplicationDirectory,
+    storage_directory: PathBuf,
+}
+
+impl DiskFilesystemBackend {
+    pub fn new(application_directory: ApplicationDirectory, storage_directory: PathBuf) -> Self {
+        Self {
+            application_directory,
+            storage_directory,
+        }
+    }
+
+    /// Returns the directory on disk backing the given root, if there is one.
+    fn root_directory(&self, root: FileRoot) -> Option<&Path> {
+        match (root, &self.application_directory) {
+            (FileRoot::Application, ApplicationDirectory::Directory(directory)) => Some(directory),
+            (FileRoot::Application, _) => None,
+            (FileRoot::ApplicationStorage, _) => Some(&self.storage_directory),
+        }
+    }
+
+    /// Returns the path on disk of the given file, if its root is backed by a directory on disk.
+    fn disk_path(&self, root: FileRoot, path: &str) -> Option<PathBuf> {
+        let directory = self.root_directory(root)?;
+        Some(
+            path.split('/')
+                .fold(directory.to_owned(), |acc, c| acc.join(c)),
+        )
+    }
+
+    /// Returns the path on disk of the given file, failing if it can't be written to.
+    fn writable_path(&self, root: FileRoot, path: &str) -> Result<PathBuf, Error> {
+        if root == FileRoot::Application {
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        self.disk_path(root, path)
+            .ok_or_else(|| ErrorKind::PermissionDenied.into())
+    }
+
+    fn bundle(&self, root: FileRoot) -> Option<&BundleSource> {
+        match (root, &self.application_directory) {
+            (FileRoot::Application, ApplicationDirectory::Bundle(source)) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a path below `directory` to a `/`-separated relative path.
+fn relative_path(directory: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(directory).ok()?;
+    let mut components = vec![];
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(components.join("/"))
+}
+
+impl FilesystemBackend for DiskFilesystemBackend {
+    fn native_path(&self, root: FileRoot, path: &str) -> Option<String> {
+        self.disk_path(root, path)
+            .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    fn sandboxed_path(&self, native_path: &str) -> Option<(FileRoot, String)> {
+        let path = match Url::parse(native_path) {
+            Ok(url) if url.scheme() == "file" => url.to_file_path().ok()?,
+            _ => PathBuf::from(native_path),
+        };
+        [FileRoot::ApplicationStorage, FileRoot::Application]
+            .into_iter()
+            .find_map(|root| {
+                let directory = self.root_directory(root)?;
+                Some((root, relative_path(directory, &path)?))
+            })
+    }
+
+    fn metadata(&self, root: FileRoot, path: &str) -> Option<FileMetadata> {
+        if let Some(source) = self.bundle(root) {
+            if let Ok(data) = source.read_content(path) {
+                return Some(FileMetadata {
+                    is_directory: false,
+                    size: data.len() as u64,
+                    ..Default::default()
+                });
+            }
+            return source.list_content(path).ok().map(|_| FileMetadata {
+                is_directory: true,
+                ..Default::default()
+            });
+        }
+
+        let Ok(metadata) = fs::metadata(self.disk_path(root, path)?) else {
+            // The storage directory is only created once something is written to it.
+            return (root == FileRoot::ApplicationStorage && path.is_empty()).then(|| {
+                FileMetadata {
+                    is_directory: true,
+                    ..Default::default()
+                }
+            });
+        };
+        Some(FileMetadata {
+            is_directory: metadata.is_dir(),
+            size: metadata.len(),
+            creation_time: metadata.created().ok(),
+            modification_time: metadata.modified().ok(),
+        })
+    }
+
+    fn read_dir(&self, root: FileRoot, path: &str) -> Result<Vec<String>, Error> {
+        if let Some(source) = self.bundle(root) {
+            return source.list_content(path);
+        }
+
+        let directory = self
+            .disk_path(root, path)
+            .ok_or_else(|| Error::from(ErrorKind::NotFound))?;
+        if root == FileRoot::ApplicationStorage && path.is_empty() && !directory.exists() {
+            return Ok(vec![]);
+        }
+        let mut names = vec![];
+        for entry in fs::read_dir(directory)? {
+            if let Some(name) = entry?.file_name().to_str() {
+                names.push(name.to_owned());
+            }
+        }
+        Ok(names)
+    }
+
+    fn read(&self, root: FileRoot, path: &str) -> Result<Vec<u8>, Error> {
+        if let Some(source) = self.bundle(root) {
+            return source.read_content(path);
+        }
+
+        let path = self
+            .disk_path(root, path)
+            .ok_or_else(|| Error::from(ErrorKind::NotFound))?;
+        fs::read(path)
+    }
+
+    fn write(&mut self, root: FileRoot, path: &str, data: &[u8]) -> Result<(), Error> {
+        let path = self.writable_path(root, path)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, data)
+    }
+
+    fn create_dir(&mut self, root: FileRoot, path: &str) -> Result<(), Error> {
+        let path = self.writable_path(root, path)?;
+        fs::create_dir_all(path)
+    }
+
+    fn remove(&mut self, root: FileRoot, path: &str, recursive: bool) -> Result<(), Error> {
+        if path.is_empty() {
+            return Err(ErrorKind::PermissionDenied.into());
+        }
+        let path = self.writable_path(root, path)?;
+        if !path.is_dir() {
+            fs::remove_file(path)
+        } else if recursive {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_dir(path)
+        }
+    }
+
+    fn rename(
+        &mut self,
+        from_root: FileRoot,
+        from: &str,
+        to_root: FileRoot,
+        to: &str,
+    ) -> Result<(), Error> {
+        let from = self.writable_path(from_root, from)?;
+        let to = self.writable_path(to_root, to)?;
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn storage_round_trip() {
+        let tmp_dir = tempdir().unwrap();
+        let storage_directory = tmp_dir.path().join("storage");
+        let mut backend =
+            DiskFilesystemBackend::new(ApplicationDirectory::None, storage_directory.clone());
+        let storage = FileRoot::ApplicationStorage;
+
+        let written = backend.write(storage, "saves/slot.sav", b"data");
+        let listing = backend.read_dir(storage, "saves");
+        let read = backend.read(storage, "saves/slot.sav");
+        let native_path = backend.native_path(storage, "saves/slot.sav");
+        let sandboxed = native_path
+            .as_deref()
+            .and_then(|path| backend.sandboxed_path(path));
+        let app_write = backend.write(FileRoot::Application, "file", b"");
+        drop(tmp_dir);
+
+        assert!(written.is_ok());
+        assert_eq!(listing.ok(), Some(vec!["slot.sav".to_owned()]));
+        assert_eq!(read.ok().as_deref(), Some(&b"data"[..]));
+        assert_eq!(sandboxed, Some((storage, "saves/slot.sav".to_owned())));
+        assert!(app_write.is_err());
+    }
+}
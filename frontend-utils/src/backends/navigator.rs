@@ -1,4 +1,5 @@
 mod fetch;
+mod process;
 
 use crate::backends::executor::{spawn_tokio, FutureSpawner};
 use crate::backends::navigator::fetch::{Response, ResponseBody};
@@ -13,12 +14,15 @@ use ruffle_core::backend::navigator::{
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
+use ruffle_core::native_process::{
+    ProcessAction, ProcessCommand, ProcessHandle, ProcessStartupInfo,
+};
 use ruffle_core::socket::{ConnectionState, SocketAction, SocketHandle};
 use std::collections::HashSet;
 use std::fs::File;
 use std::io;
 use std::io::ErrorKind;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -55,6 +59,9 @@ pub struct ExternalNavigatorBackend<F: FutureSpawner, I: NavigatorInterface> {
 
     socket_mode: SocketMode,
 
+    /// Executables that AIR applications are allowed to start.
+    process_allowed: HashSet<PathBuf>,
+
     upgrade_to_https: bool,
 
     open_url_mode: OpenURLMode,
@@ -77,6 +84,7 @@ impl<F: FutureSpawner, I: NavigatorInterface> ExternalNavigatorBackend<F, I> {
         open_url_mode: OpenURLMode,
         socket_allowed: HashSet<String>,
         socket_mode: SocketMode,
+        process_allowed: HashSet<PathBuf>,
         content: Rc<PlayingContent>,
         interface: I,
     ) -> Self {
@@ -128,6 +136,10 @@ impl<F: FutureSpawner, I: NavigatorInterface> ExternalNavigatorBackend<F, I> {
             open_url_mode,
             socket_allowed,
             socket_mode,
+            process_allowed: process_allowed
+                .iter()
+                .map(|path| process::canonical_executable(path))
+                .collect(),
             content,
             interface,
         }
@@ -485,6 +497,36 @@ impl<F: FutureSpawner + 'static, I: NavigatorInterface> NavigatorBackend
 
         tokio::spawn(future);
     }
+
+    fn can_start_process(&self) -> bool {
+        !self.process_allowed.is_empty()
+    }
+
+    fn start_process(
+        &mut self,
+        info: ProcessStartupInfo,
+        handle: ProcessHandle,
+        receiver: Receiver<ProcessCommand>,
+        sender: Sender<ProcessAction>,
+    ) -> Result<(), io::Error> {
+        let executable = process::canonical_executable(Path::new(&info.executable));
+        if !self.process_allowed.contains(&executable) {
+            tracing::warn!(
+                "SWF tried to start {}, but it isn't an allowed executable",
+                info.executable
+            );
+            return Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                "executable is not allowed",
+            ));
+        }
+
+        let info = ProcessStartupInfo {
+            executable: executable.to_string_lossy().into_owned(),
+            ..info
+        };
+        process::spawn_process(info, handle, receiver, sender)
+    }
 }
 
 #[cfg(test)]
@@ -562,6 +604,13 @@ mod tests {
     }
 
     fn new_test_backend(socket_allow: bool) -> ExternalNavigatorBackend<TestFutureSpawner, ()> {
+        new_test_backend_with_processes(socket_allow, Default::default())
+    }
+
+    fn new_test_backend_with_processes(
+        socket_allow: bool,
+        process_allowed: HashSet<PathBuf>,
+    ) -> ExternalNavigatorBackend<TestFutureSpawner, ()> {
         let url = Url::parse("https://example.com/path/").unwrap();
         ExternalNavigatorBackend::new(
             url.clone(),
@@ -577,6 +626,7 @@ mod tests {
             } else {
                 SocketMode::Deny
             },
+            process_allowed,
             Rc::new(PlayingContent::DirectFile(url)),
             (),
         )
@@ -781,4 +831,102 @@ mod tests {
 
         assert_eq!(read_server(&mut server_socket).await, "Sending some data");
     }
+
+    #[test]
+    fn test_process_deny() {
+        let mut backend = new_test_backend(true);
+        let (_command_sender, command_receiver) = async_channel::unbounded();
+        let (action_sender, action_receiver) = async_channel::unbounded();
+
+        let result = backend.start_process(
+            ProcessStartupInfo {
+                executable: "ruffle-test-executable".to_string(),
+                arguments: vec![],
+                working_directory: None,
+            },
+            ProcessHandle::default(),
+            command_receiver,
+            action_sender,
+        );
+
+        assert!(!backend.can_start_process());
+        assert_eq!(
+            result.map_err(|e| e.kind()),
+            Err(ErrorKind::PermissionDenied)
+        );
+        assert!(action_receiver.is_empty());
+    }
+
+    /// Starts `sh` with the given script, returning the actions it sends.
+    #[cfg(unix)]
+    fn run_test_script(script: &str) -> Vec<ProcessAction> {
+        let mut backend =
+            new_test_backend_with_processes(false, HashSet::from([PathBuf::from("/bin/sh")]));
+        let (_command_sender, command_receiver) = async_channel::unbounded();
+        let (action_sender, action_receiver) = async_channel::unbounded();
+
+        assert!(backend.can_start_process());
+        backend
+            .start_process(
+                ProcessStartupInfo {
+                    executable: "/bin/sh".to_string(),
+                    arguments: vec!["-c".to_string(), script.to_string()],
+                    working_directory: None,
+                },
+                ProcessHandle::default(),
+                command_receiver,
+                action_sender,
+            )
+            .expect("allowed process should start");
+
+        let deadline = std::time::Instant::now() + TIMEOUT;
+        let mut actions = vec![];
+        while std::time::Instant::now() < deadline {
+            match action_receiver.try_recv() {
+                Ok(action @ ProcessAction::Exit(..)) => {
+                    actions.push(action);
+                    return actions;
+                }
+                Ok(action) => actions.push(action),
+                Err(_) => std::thread::sleep(Duration::from_millis(1)),
+            }
+        }
+        panic!("Process didn't exit in time, received {actions:?}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_output_and_exit() {
+        use ruffle_core::native_process::ProcessStream;
+
+        let actions = run_test_script("printf out; printf err >&2; exit 3");
+        let handle = ProcessHandle::default();
+
+        let output = |stream| {
+            actions
+                .iter()
+                .filter_map(|action| match action {
+                    ProcessAction::Data(_, s, data) if *s == stream => Some(data.clone()),
+                    _ => None,
+                })
+                .flatten()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(output(ProcessStream::Output), b"out");
+        assert_eq!(output(ProcessStream::Error), b"err");
+        assert!(actions.contains(&ProcessAction::Close(handle, ProcessStream::Output)));
+        assert!(actions.contains(&ProcessAction::Close(handle, ProcessStream::Error)));
+        assert_eq!(actions.last(), Some(&ProcessAction::Exit(handle, Some(3))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_exit_with_inherited_pipes() {
+        // The background process keeps the pipes open long after `sh` exits.
+        let actions = run_test_script("sleep 10 & exit 0");
+        assert_eq!(
+            actions.last(),
+            Some(&ProcessAction::Exit(ProcessHandle::default(), Some(0)))
+        );
+    }
 }
@@ -0,0 +1,160 @@
+use async_channel::{Receiver, Sender};
+use ruffle_core::native_process::{
+    ProcessAction, ProcessCommand, ProcessHandle, ProcessStartupInfo, ProcessStream,
+};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the output of a process that has exited to be forwarded.
+///
+/// The pipes may be held open by a child of the process, so the exit is
+/// reported after this even if they haven't been closed yet.
+const OUTPUT_GRACE_PERIOD: Duration = Duration::from_millis(100);
+
+/// Resolves a path to the form it's compared against the allowlist in.
+pub fn canonical_executable(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_owned())
+}
+
+/// Starts a process, forwarding its standard streams over the given channels.
+///
+/// Each stream is serviced by its own thread, as std offers no way of polling pipes.
+pub fn spawn_process(
+    info: ProcessStartupInfo,
+    handle: ProcessHandle,
+    receiver: Receiver<ProcessCommand>,
+    sender: Sender<ProcessAction>,
+) -> Result<(), io::Error> {
+    let mut command = Command::new(&info.executable);
+    command
+        .args(&info.arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(working_directory) = &info.working_directory {
+        command.current_dir(working_directory);
+    }
+
+    let mut child = command.spawn()?;
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let child = Arc::new(Mutex::new(child));
+
+    let readers = [
+        (
+            stdout.map(|s| Box::new(s) as Box<dyn Read + Send>),
+            ProcessStream::Output,
+        ),
+        (
+            stderr.map(|s| Box::new(s) as Box<dyn Read + Send>),
+            ProcessStream::Error,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(stream, kind)| {
+        let stream = stream?;
+        let sender = sender.clone();
+        Some(thread::spawn(move || {
+            read_stream(stream, kind, handle, sender)
+        }))
+    })
+    .collect::<Vec<_>>();
+
+    let writer_child = child.clone();
+    let writer_sender = sender.clone();
+    thread::spawn(move || write_input(stdin, writer_child, handle, receiver, writer_sender));
+
+    thread::spawn(move || {
+        let exit_code = wait(&child);
+        let deadline = Instant::now() + OUTPUT_GRACE_PERIOD;
+        while readers.iter().any(|reader| !reader.is_finished()) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let _ = sender.try_send(ProcessAction::Exit(handle, exit_code));
+    });
+
+    Ok(())
+}
+
+fn read_stream(
+    mut stream: Box<dyn Read + Send>,
+    kind: ProcessStream,
+    handle: ProcessHandle,
+    sender: Sender<ProcessAction>,
+) {
+    let mut buffer = [0; 4096];
+    loop {
+        match stream.read(&mut buffer) {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(_) | Ok(0) => break,
+            Ok(read) => {
+                let data = buffer[..read].to_vec();
+                if sender
+                    .try_send(ProcessAction::Data(handle, kind, data))
+                    .is_err()
+                {
+                    // The player is gone, nobody is listening anymore.
+                    return;
+                }
+            }
+        }
+    }
+    let _ = sender.try_send(ProcessAction::Close(handle, kind));
+}
+
+fn write_input(
+    mut stdin: Option<impl Write>,
+    child: Arc<Mutex<Child>>,
+    handle: ProcessHandle,
+    receiver: Receiver<ProcessCommand>,
+    sender: Sender<ProcessAction>,
+) {
+    loop {
+        match receiver.recv_blocking() {
+            Ok(ProcessCommand::Input(data)) => {
+                let Some(input) = &mut stdin else {
+                    continue;
+                };
+                if let Err(e) = input.write_all(&data).and_then(|_| input.flush()) {
+                    let _ = sender.try_send(ProcessAction::InputError(handle, e.to_string()));
+                    stdin = None;
+                }
+            }
+            Ok(ProcessCommand::CloseInput) => {
+                if stdin.take().is_some() {
+                    let _ = sender.try_send(ProcessAction::InputClose(handle));
+                }
+            }
+            // std can only terminate processes forcefully, so a graceful exit
+            // isn't distinguished from a forced one. The same goes for the
+            // player going away (the sender being dropped).
+            Ok(ProcessCommand::Exit) | Err(_) => {
+                drop(stdin);
+                if let Ok(mut child) = child.lock() {
+                    let _ = child.kill();
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Waits for the process to exit, without holding the lock so that it can still be killed.
+fn wait(child: &Mutex<Child>) -> Option<i32> {
+    loop {
+        match child.lock() {
+            Ok(mut child) => match child.try_wait() {
+                Ok(Some(status)) => return status.code(),
+                Ok(None) => {}
+                Err(_) => return None,
+            },
+            Err(_) => return None,
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -31,6 +31,25 @@ impl DiskStorageBackend {
     fn get_shared_object_path(&self, name: &str) -> PathBuf {
         self.shared_objects_path.join(format!("{name}.sol"))
     }
+
+    /// Recursively collects the names of all `.sol` files below `dir`.
+    fn collect_keys(dir: &Path, prefix: &str, keys: &mut Vec<String>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name();
+            let Some(file_name) = file_name.to_str() else {
+                continue;
+            };
+            if path.is_dir() {
+                Self::collect_keys(&path, &format!("{prefix}{file_name}/"), keys);
+            } else if let Some(name) = file_name.strip_suffix(".sol") {
+                keys.push(format!("{prefix}{name}"));
+            }
+        }
+    }
 }
 
 impl StorageBackend for DiskStorageBackend {
@@ -85,4 +104,10 @@ impl StorageBackend for DiskStorageBackend {
         }
         let _ = fs::remove_file(path);
     }
+
+    fn keys(&self) -> Vec<String> {
+        let mut keys = Vec::new();
+        Self::collect_keys(&self.shared_objects_path, "", &mut keys);
+        keys
+    }
 }
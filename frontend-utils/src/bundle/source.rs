@@ -16,6 +16,9 @@ trait BundleSourceImpl {
 
     /// Reads a file specifically from the content directory of the bundle.
     fn read_content(&self, path: &str) -> Result<Self::Read, Error>;
+
+    /// Lists the names of the entries of a directory within the content directory of the bundle.
+    fn list_content(&self, path: &str) -> Result<Vec<String>, Error>;
 }
 
 pub enum BundleSource {
@@ -90,4 +93,12 @@ impl BundleSource {
             BundleSource::ZipFile(zip) => zip.read_content(path).map(|cursor| cursor.into_inner()),
         }
     }
+
+    /// Lists the names of the entries of a directory within the content directory of the bundle.
+    pub fn list_content(&self, path: &str) -> Result<Vec<String>, Error> {
+        match self {
+            BundleSource::Directory(directory) => directory.list_content(path),
+            BundleSource::ZipFile(zip) => zip.list_content(path),
+        }
+    }
 }
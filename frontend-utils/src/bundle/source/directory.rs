@@ -26,6 +26,23 @@ impl BundleSourceImpl for Path {
         }
         File::open(potential_path)
     }
+
+    fn list_content(&self, path: &str) -> Result<Vec<String>, Error> {
+        let root = self.join("content").canonicalize()?;
+        let potential_path = root
+            .join(path.strip_prefix('/').unwrap_or(path))
+            .canonicalize()?;
+        if !potential_path.starts_with(root) {
+            return Err(Error::from(ErrorKind::NotFound));
+        }
+        let mut names = vec![];
+        for entry in std::fs::read_dir(potential_path)? {
+            if let Some(name) = entry?.file_name().to_str() {
+                names.push(name.to_owned());
+            }
+        }
+        Ok(names)
+    }
 }
 
 #[cfg(test)]
@@ -187,4 +204,17 @@ mod tests {
 
         assert!(success)
     }
+
+    #[test]
+    fn list_content_works() {
+        let tmp_dir = tempdir().unwrap();
+        let _ = std::fs::create_dir_all(tmp_dir.path().join("content/saves"));
+        let _ = std::fs::write(tmp_dir.path().join("content/saves/slot.sav"), "Fancy!");
+        let result = tmp_dir.path().list_content("saves");
+        let outside = tmp_dir.path().list_content("..");
+        drop(tmp_dir);
+
+        assert_eq!(result.ok(), Some(vec!["slot.sav".to_owned()]));
+        assert!(matches!(outside, Err(e) if e.kind() == ErrorKind::NotFound));
+    }
 }
@@ -36,6 +36,32 @@ impl<R: Read + Seek> BundleSourceImpl for ZipSource<R> {
         let path = path.strip_prefix('/').unwrap_or(path);
         self.read_file(&format!("content/{path}"))
     }
+
+    fn list_content(&self, path: &str) -> Result<Vec<String>, Error> {
+        let path = path.trim_matches('/');
+        let prefix = if path.is_empty() {
+            "content/".to_owned()
+        } else {
+            format!("content/{path}/")
+        };
+        let archive = self.0.borrow();
+        let mut found = false;
+        let mut names: Vec<String> = vec![];
+        for file_name in archive.file_names() {
+            let Some(rest) = file_name.strip_prefix(&prefix) else {
+                continue;
+            };
+            found = true;
+            let name = rest.split('/').next().unwrap_or_default();
+            if !name.is_empty() && !names.iter().any(|n| n == name) {
+                names.push(name.to_owned());
+            }
+        }
+        if !found {
+            return Err(Error::from(ErrorKind::NotFound));
+        }
+        Ok(names)
+    }
 }
 
 #[cfg(test)]
@@ -104,4 +130,18 @@ mod tests {
         file.read_to_string(&mut string).unwrap();
         assert_eq!("Hello world!\n", string);
     }
+
+    #[test]
+    fn list_content_valid() {
+        let not_a_zip = include_bytes!("./test-assets/bundle-and-content.xip");
+        let source = ZipSource::open(Cursor::new(not_a_zip)).unwrap();
+        assert_eq!(
+            source.list_content("/").unwrap(),
+            vec!["foo.txt".to_owned()]
+        );
+        assert!(matches!(
+            source.list_content("missing"),
+            Err(e) if e.kind() == ErrorKind::NotFound
+        ));
+    }
 }
@@ -1,3 +1,5 @@
+use crate::backends::filesystem::ApplicationDirectory;
+use crate::bundle::source::BundleSource;
 use crate::bundle::Bundle;
 use std::fmt::{Debug, Formatter};
 use std::fs::File;
@@ -75,4 +77,24 @@ impl PlayingContent {
             }
         }
     }
+
+    /// Returns where the files of the `app:` directory of AIR applications come from:
+    /// the content of a bundle, or the directory containing a local movie.
+    pub fn application_directory(&self) -> ApplicationDirectory {
+        match self {
+            PlayingContent::DirectFile(url) => url
+                .to_file_path()
+                .ok()
+                .and_then(|path| path.parent().map(Path::to_owned))
+                .map_or(ApplicationDirectory::None, ApplicationDirectory::Directory),
+            PlayingContent::Bundle(url, _) => url
+                .to_file_path()
+                .ok()
+                .and_then(|path| BundleSource::from_path(path).ok())
+                .map_or(
+                    ApplicationDirectory::None,
+                    ApplicationDirectory::from_bundle,
+                ),
+        }
+    }
 }
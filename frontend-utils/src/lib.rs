@@ -2,6 +2,7 @@ pub mod bookmarks;
 pub mod bundle;
 pub mod parse;
 pub mod recents;
+pub mod shared_objects;
 pub mod write;
 
 pub mod backends;
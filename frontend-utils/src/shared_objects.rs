@@ -0,0 +1,314 @@
+//! Import and export of Flash Player local shared objects (`.sol` files).
+//!
+//! Flash Player keeps shared objects in a `#SharedObjects/<profile>/` directory,
+//! where `<profile>` is a randomly named folder. Below it, files are laid out as
+//! `<host>/<path to swf>/<name>.sol`, which is the same layout Ruffle uses for its
+//! storage keys (see `SharedObject.getLocal`), so files can be moved between the two
+//! without renaming anything.
+
+use ruffle_core::backend::storage::StorageBackend;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the directory Flash Player stores its shared objects in.
+pub const FLASH_SHARED_OBJECTS_DIRECTORY: &str = "#SharedObjects";
+
+/// Flash Player stores shared objects of `local-with-networking` SWFs under this host,
+/// whereas Ruffle always uses `localhost` for local files.
+const LOCAL_WITH_NET_HOST: &str = "#localWithNet";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SharedObjectError {
+    #[error("Directory {0} does not exist")]
+    DirectoryDoesntExist(PathBuf),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The outcome of an import or export operation.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SharedObjectTransfer {
+    /// Storage keys that were copied successfully.
+    pub copied: Vec<String>,
+
+    /// Storage keys that were not copied, along with the reason why.
+    pub skipped: Vec<(String, SkipReason)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The data is not a valid AMF0 or AMF3 `.sol` file.
+    InvalidData,
+
+    /// A shared object with this name already exists and overwriting was not requested.
+    AlreadyExists,
+
+    /// The name cannot be safely used as a path.
+    InvalidName,
+
+    /// The backend refused to store the data, or the file could not be written.
+    WriteFailed,
+}
+
+/// Returns true if `data` is a well-formed `.sol` file, in either AMF0 or AMF3 encoding.
+pub fn is_valid_sol(data: &[u8]) -> bool {
+    flash_lso::read::Reader::default().parse(data).is_ok()
+}
+
+/// Copies every `.sol` file found in a Flash Player shared objects directory into `storage`.
+///
+/// `source` may either be the `#SharedObjects` directory itself (in which case every
+/// profile inside of it is imported), or a single profile directory.
+pub fn import_flash_shared_objects(
+    source: &Path,
+    storage: &mut dyn StorageBackend,
+    overwrite: bool,
+) -> Result<SharedObjectTransfer, SharedObjectError> {
+    if !source.is_dir() {
+        return Err(SharedObjectError::DirectoryDoesntExist(source.to_owned()));
+    }
+
+    let profiles = if source
+        .file_name()
+        .is_some_and(|name| name == FLASH_SHARED_OBJECTS_DIRECTORY)
+    {
+        let mut profiles = Vec::new();
+        for entry in fs::read_dir(source)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                profiles.push(path);
+            }
+        }
+        profiles.sort();
+        profiles
+    } else {
+        vec![source.to_owned()]
+    };
+
+    let mut files = Vec::new();
+    for profile in &profiles {
+        collect_sol_files(profile, &mut Vec::new(), &mut files)?;
+    }
+
+    let mut result = SharedObjectTransfer::default();
+    for (path, mut components) in files {
+        if components.first().map(String::as_str) == Some(LOCAL_WITH_NET_HOST) {
+            components[0] = "localhost".to_string();
+        }
+        let key = components.join("/");
+
+        let data = fs::read(&path)?;
+        if !is_valid_sol(&data) {
+            tracing::warn!("Skipping invalid shared object {}", path.display());
+            result.skipped.push((key, SkipReason::InvalidData));
+        } else if !overwrite && storage.get(&key).is_some() {
+            result.skipped.push((key, SkipReason::AlreadyExists));
+        } else if storage.put(&key, &data) {
+            result.copied.push(key);
+        } else {
+            result.skipped.push((key, SkipReason::WriteFailed));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Writes every shared object held by `storage` to `destination` as a `.sol` file.
+///
+/// The resulting directory can be used as a Flash Player profile directory
+/// (i.e. placed inside of `#SharedObjects`).
+pub fn export_shared_objects(
+    storage: &dyn StorageBackend,
+    destination: &Path,
+) -> Result<SharedObjectTransfer, SharedObjectError> {
+    let mut keys = storage.keys();
+    keys.sort();
+
+    let mut result = SharedObjectTransfer::default();
+    for key in keys {
+        let relative = Path::new(&key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            result.skipped.push((key, SkipReason::InvalidName));
+            continue;
+        }
+
+        let Some(data) = storage.get(&key) else {
+            continue;
+        };
+        if !is_valid_sol(&data) {
+            result.skipped.push((key, SkipReason::InvalidData));
+            continue;
+        }
+
+        let path = destination.join(format!("{key}.sol"));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match fs::write(&path, &data) {
+            Ok(()) => result.copied.push(key),
+            Err(e) => {
+                tracing::warn!("Unable to write shared object {}: {e}", path.display());
+                result.skipped.push((key, SkipReason::WriteFailed));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Recursively finds all `.sol` files below `dir`, along with their path components
+/// (without the extension) relative to the directory the search started at.
+fn collect_sol_files(
+    dir: &Path,
+    components: &mut Vec<String>,
+    files: &mut Vec<(PathBuf, Vec<String>)>,
+) -> Result<(), SharedObjectError> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for path in entries {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            components.push(name.to_string());
+            collect_sol_files(&path, components, files)?;
+            components.pop();
+        } else if let Some(name) = name.strip_suffix(".sol") {
+            let mut key = components.clone();
+            key.push(name.to_string());
+            files.push((path.clone(), key));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flash_lso::types::{AMFVersion, Lso};
+    use ruffle_core::backend::storage::MemoryStorageBackend;
+    use tempfile::tempdir;
+
+    fn sol(name: &str, version: AMFVersion) -> Vec<u8> {
+        let mut lso = Lso::new(Vec::new(), name.to_string(), version);
+        flash_lso::write::write_to_bytes(&mut lso).expect("valid lso")
+    }
+
+    #[test]
+    fn import_profile_directory() {
+        let dir = tempdir().unwrap();
+        let profile = dir
+            .path()
+            .join(FLASH_SHARED_OBJECTS_DIRECTORY)
+            .join("ABCD1234");
+        fs::create_dir_all(profile.join("localhost/games/game.swf")).unwrap();
+        fs::create_dir_all(profile.join("#localWithNet/other.swf")).unwrap();
+        fs::write(
+            profile.join("localhost/games/game.swf/save.sol"),
+            sol("save", AMFVersion::AMF0),
+        )
+        .unwrap();
+        fs::write(
+            profile.join("#localWithNet/other.swf/data.sol"),
+            sol("data", AMFVersion::AMF3),
+        )
+        .unwrap();
+        fs::write(profile.join("localhost/games/broken.sol"), b"not a sol").unwrap();
+
+        let mut storage = MemoryStorageBackend::new();
+        let result = import_flash_shared_objects(
+            &dir.path().join(FLASH_SHARED_OBJECTS_DIRECTORY),
+            &mut storage,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.copied,
+            vec![
+                "localhost/other.swf/data".to_string(),
+                "localhost/games/game.swf/save".to_string(),
+            ]
+        );
+        assert_eq!(
+            result.skipped,
+            vec![(
+                "localhost/games/broken".to_string(),
+                SkipReason::InvalidData
+            )]
+        );
+        assert_eq!(
+            storage.get("localhost/games/game.swf/save"),
+            Some(sol("save", AMFVersion::AMF0))
+        );
+    }
+
+    #[test]
+    fn import_does_not_overwrite() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("localhost")).unwrap();
+        fs::write(
+            dir.path().join("localhost/save.sol"),
+            sol("save", AMFVersion::AMF0),
+        )
+        .unwrap();
+
+        let mut storage = MemoryStorageBackend::new();
+        storage.put("localhost/save", b"existing");
+
+        let result = import_flash_shared_objects(dir.path(), &mut storage, false).unwrap();
+        assert!(result.copied.is_empty());
+        assert_eq!(storage.get("localhost/save"), Some(b"existing".to_vec()));
+
+        let result = import_flash_shared_objects(dir.path(), &mut storage, true).unwrap();
+        assert_eq!(result.copied, vec!["localhost/save".to_string()]);
+        assert_eq!(
+            storage.get("localhost/save"),
+            Some(sol("save", AMFVersion::AMF0))
+        );
+    }
+
+    #[test]
+    fn export_round_trip() {
+        let dir = tempdir().unwrap();
+        let mut storage = MemoryStorageBackend::new();
+        storage.put("localhost/game.swf/save", &sol("save", AMFVersion::AMF3));
+        storage.put(
+            "localhost/game.swf/#nested/save",
+            &sol("save", AMFVersion::AMF0),
+        );
+        storage.put("localhost/../escape", &sol("escape", AMFVersion::AMF0));
+
+        let result = export_shared_objects(&storage, dir.path()).unwrap();
+        assert_eq!(result.copied.len(), 2);
+        assert_eq!(
+            result.skipped,
+            vec![("localhost/../escape".to_string(), SkipReason::InvalidName)]
+        );
+        assert_eq!(
+            fs::read(dir.path().join("localhost/game.swf/save.sol")).unwrap(),
+            sol("save", AMFVersion::AMF3)
+        );
+
+        let mut imported = MemoryStorageBackend::new();
+        import_flash_shared_objects(dir.path(), &mut imported, false).unwrap();
+        let mut keys = imported.keys();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "localhost/game.swf/#nested/save".to_string(),
+                "localhost/game.swf/save".to_string(),
+            ]
+        );
+    }
+}
@@ -13,6 +13,7 @@ use ruffle_render::bitmap::{
 use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
 use ruffle_render::error::Error;
 use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender::interpreter::{self, InterpretedShader};
 use ruffle_render::quality::StageQuality;
 use ruffle_render::shape_utils::{DistilledShape, DrawCommand, LineScaleMode, LineScales};
 use ruffle_render::transform::Transform;
@@ -572,18 +573,19 @@ impl RenderBackend for WebCanvasRenderBackend {
 
     fn compile_pixelbender_shader(
         &mut self,
-        _shader: ruffle_render::pixel_bender::PixelBenderShader,
+        shader: ruffle_render::pixel_bender::PixelBenderShader,
     ) -> Result<ruffle_render::pixel_bender::PixelBenderShaderHandle, Error> {
-        Err(Error::Unimplemented("compile_pixelbender_shader".into()))
+        // Shaders are run on the CPU.
+        Ok(InterpretedShader::new_handle(shader))
     }
 
     fn run_pixelbender_shader(
         &mut self,
-        _handle: ruffle_render::pixel_bender::PixelBenderShaderHandle,
-        _arguments: &[ruffle_render::pixel_bender::PixelBenderShaderArgument],
-        _target: &PixelBenderTarget,
+        handle: ruffle_render::pixel_bender::PixelBenderShaderHandle,
+        arguments: &[ruffle_render::pixel_bender::PixelBenderShaderArgument],
+        target: &PixelBenderTarget,
     ) -> Result<PixelBenderOutput, Error> {
-        Err(Error::Unimplemented("run_pixelbender_shader".into()))
+        interpreter::run_pixelbender_shader(&handle, arguments, target)
     }
 
     fn resolve_sync_handle(
@@ -935,7 +937,7 @@ fn swf_shape_to_canvas_commands(
     bitmap_source: &dyn BitmapSource,
     backend: &mut WebCanvasRenderBackend,
 ) -> Vec<CanvasDrawCommand> {
-    use ruffle_render::shape_utils::DrawPath;
+    use ruffle_render::shape_utils::{DrawPath, TexturedVertex};
     use swf::{FillStyle, LineCapStyle, LineJoinStyle};
 
     // Some browsers will vomit if you try to load/draw an image with 0 width/height.
@@ -1008,6 +1010,57 @@ fn swf_shape_to_canvas_commands(
                     fill_style,
                 });
             }
+            DrawPath::TexturedTriangles { style, vertices } => {
+                // Textured triangles are only ever created with bitmap fills.
+                let FillStyle::Bitmap {
+                    id,
+                    is_smoothed,
+                    is_repeating,
+                    ..
+                } = style
+                else {
+                    continue;
+                };
+                let Some(size) = bitmap_source.bitmap_size(*id) else {
+                    continue;
+                };
+
+                // Canvas patterns can only be transformed affinely, so each triangle gets
+                // its own pattern, and perspective is ignored.
+                for triangle in vertices.chunks_exact(3) {
+                    let triangle = [triangle[0], triangle[1], triangle[2]];
+                    let Some(matrix) = TexturedVertex::bitmap_matrix(
+                        &triangle,
+                        size.width.into(),
+                        size.height.into(),
+                    ) else {
+                        continue;
+                    };
+                    let Some(bitmap) = create_bitmap_pattern(
+                        *id,
+                        matrix.into(),
+                        *is_smoothed,
+                        *is_repeating,
+                        bitmap_source,
+                        backend,
+                    ) else {
+                        continue;
+                    };
+
+                    let canvas_path = Path2d::new().expect("Path2d constructor must succeed");
+                    canvas_path.add_path_with_transformation(
+                        &draw_commands_to_path2d(
+                            &TexturedVertex::triangle_commands(&triangle),
+                            false,
+                        ),
+                        bounds_viewbox_matrix.unchecked_ref(),
+                    );
+                    canvas_data.push(CanvasDrawCommand::Fill {
+                        path: canvas_path,
+                        fill_style: CanvasFillStyle::Bitmap(bitmap),
+                    });
+                }
+            }
             DrawPath::Stroke {
                 commands,
                 style,
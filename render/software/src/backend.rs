@@ -0,0 +1,271 @@
+use crate::bitmap::{as_bitmap, interpreter_arguments, SoftwareBitmap, SoftwareSyncHandle};
+use crate::buffer::PixelBuffer;
+use crate::commands::SoftwareCommandHandler;
+use crate::filters::apply_filter;
+use crate::mesh::Mesh;
+use crate::raster::RenderTarget;
+use ruffle_render::backend::{
+    BitmapCacheEntry, Context3D, Context3DProfile, PixelBenderOutput, PixelBenderTarget,
+    RenderBackend, ShapeHandle, ViewportDimensions,
+};
+use ruffle_render::bitmap::{
+    Bitmap, BitmapHandle, BitmapSource, PixelRegion, RgbaBufRead, SyncHandle,
+};
+use ruffle_render::commands::CommandList;
+use ruffle_render::error::Error;
+use ruffle_render::filters::Filter;
+use ruffle_render::pixel_bender::interpreter::{self, InterpretedShader};
+use ruffle_render::pixel_bender::{
+    PixelBenderShader, PixelBenderShaderArgument, PixelBenderShaderHandle,
+};
+use ruffle_render::quality::StageQuality;
+use ruffle_render::shape_utils::DistilledShape;
+use ruffle_render::tessellator::ShapeTessellator;
+use std::borrow::Cow;
+use std::sync::Arc;
+use swf::Color;
+
+/// A render backend that rasterizes everything on the CPU.
+///
+/// The last submitted frame can be read back with `capture_frame`.
+pub struct SoftwareRenderBackend {
+    dimensions: ViewportDimensions,
+    quality: StageQuality,
+    shape_tessellator: ShapeTessellator,
+    frame: PixelBuffer,
+}
+
+impl SoftwareRenderBackend {
+    pub fn new(dimensions: ViewportDimensions) -> Self {
+        Self {
+            dimensions,
+            quality: StageQuality::High,
+            shape_tessellator: ShapeTessellator::new(),
+            frame: PixelBuffer::new(dimensions.width, dimensions.height),
+        }
+    }
+
+    /// Returns the last submitted frame, with straight alpha.
+    pub fn capture_frame(&self) -> image::RgbaImage {
+        let mut bytes = self.frame.to_rgba8();
+        ruffle_render::utils::unmultiply_alpha_rgba(&mut bytes);
+        image::RgbaImage::from_raw(self.frame.width(), self.frame.height(), bytes)
+            .expect("Frame must have the correct size")
+    }
+
+    fn execute(commands: CommandList, target: RenderTarget, is_layer: bool) -> PixelBuffer {
+        let mut handler = SoftwareCommandHandler::new(target, is_layer);
+        commands.execute(&mut handler);
+        handler.finish().resolve()
+    }
+}
+
+/// Converts a clear color into the components of a render target, as the GPU backends do.
+fn clear_color(color: Color) -> [f32; 4] {
+    [color.r, color.g, color.b, color.a].map(|c| f32::from(c) / 255.0)
+}
+
+impl RenderBackend for SoftwareRenderBackend {
+    fn viewport_dimensions(&self) -> ViewportDimensions {
+        self.dimensions
+    }
+
+    fn set_viewport_dimensions(&mut self, dimensions: ViewportDimensions) {
+        self.dimensions = dimensions;
+        self.frame = PixelBuffer::new(dimensions.width, dimensions.height);
+    }
+
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        let mesh = self
+            .shape_tessellator
+            .tessellate_shape(shape, bitmap_source);
+        ShapeHandle(Arc::new(Mesh::new(mesh, bitmap_source, self)))
+    }
+
+    fn render_offscreen(
+        &mut self,
+        handle: BitmapHandle,
+        commands: CommandList,
+        quality: StageQuality,
+        bounds: PixelRegion,
+    ) -> Option<Box<dyn SyncHandle>> {
+        let bitmap = as_bitmap(&handle);
+        let target = RenderTarget::from_buffer(&bitmap.pixels(), quality.sample_count());
+        let pixels = Self::execute(commands, target, true);
+        *bitmap.pixels_mut() = pixels;
+        Some(Box::new(SoftwareSyncHandle {
+            bitmap: handle,
+            region: bounds,
+        }))
+    }
+
+    fn apply_filter(
+        &mut self,
+        source: BitmapHandle,
+        source_point: (u32, u32),
+        source_size: (u32, u32),
+        destination: BitmapHandle,
+        dest_point: (u32, u32),
+        filter: Filter,
+    ) -> Option<Box<dyn SyncHandle>> {
+        // Cropping first makes it safe for the source to be the destination.
+        let source = as_bitmap(&source).pixels().crop(source_point, source_size);
+        let filtered = apply_filter(&source, &filter);
+
+        let dest = as_bitmap(&destination);
+        let mut dest_pixels = dest.pixels_mut();
+        dest_pixels.copy_from(&filtered, (0, 0), source_size, dest_point);
+        let region = PixelRegion::for_whole_size(dest_pixels.width(), dest_pixels.height());
+        drop(dest_pixels);
+        Some(Box::new(SoftwareSyncHandle {
+            bitmap: destination,
+            region,
+        }))
+    }
+
+    fn is_filter_supported(&self, _filter: &Filter) -> bool {
+        true
+    }
+
+    fn is_offscreen_supported(&self) -> bool {
+        true
+    }
+
+    fn submit_frame(
+        &mut self,
+        clear: Color,
+        commands: CommandList,
+        cache_entries: Vec<BitmapCacheEntry>,
+    ) {
+        for entry in cache_entries {
+            let bitmap = as_bitmap(&entry.handle);
+            let (width, height) = {
+                let pixels = bitmap.pixels();
+                (pixels.width(), pixels.height())
+            };
+            let target = RenderTarget::new(
+                width,
+                height,
+                self.quality.sample_count(),
+                clear_color(entry.clear),
+            );
+            let mut pixels = Self::execute(entry.commands, target, false);
+            for filter in &entry.filters {
+                pixels = apply_filter(&pixels, filter);
+            }
+            *bitmap.pixels_mut() = pixels;
+        }
+
+        let target = RenderTarget::new(
+            self.dimensions.width,
+            self.dimensions.height,
+            self.quality.sample_count(),
+            clear_color(clear),
+        );
+        self.frame = Self::execute(commands, target, false);
+    }
+
+    fn create_empty_texture(&mut self, width: u32, height: u32) -> Result<BitmapHandle, Error> {
+        Ok(SoftwareBitmap::new_handle(PixelBuffer::new(width, height)))
+    }
+
+    fn register_bitmap(&mut self, bitmap: Bitmap) -> Result<BitmapHandle, Error> {
+        let bitmap = bitmap.to_rgba();
+        Ok(SoftwareBitmap::new_handle(PixelBuffer::from_rgba8(
+            bitmap.width(),
+            bitmap.height(),
+            bitmap.data(),
+        )))
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: &BitmapHandle,
+        bitmap: Bitmap,
+        mut region: PixelRegion,
+    ) -> Result<(), Error> {
+        let bitmap = bitmap.to_rgba();
+        let source = PixelBuffer::from_rgba8(bitmap.width(), bitmap.height(), bitmap.data());
+        let mut pixels = as_bitmap(handle).pixels_mut();
+        region.clamp(pixels.width(), pixels.height());
+        pixels.copy_from(
+            &source,
+            (region.x_min, region.y_min),
+            (region.width(), region.height()),
+            (region.x_min, region.y_min),
+        );
+        Ok(())
+    }
+
+    fn create_context3d(
+        &mut self,
+        _profile: Context3DProfile,
+    ) -> Result<Box<dyn Context3D>, Error> {
+        Err(Error::Unimplemented("createContext3D".into()))
+    }
+
+    fn context3d_present(&mut self, _context: &mut dyn Context3D) -> Result<(), Error> {
+        Err(Error::Unimplemented("Context3D.present".into()))
+    }
+
+    fn debug_info(&self) -> Cow<'static, str> {
+        Cow::Borrowed("Renderer: Software")
+    }
+
+    fn name(&self) -> &'static str {
+        "software"
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.quality = quality;
+    }
+
+    fn compile_pixelbender_shader(
+        &mut self,
+        shader: PixelBenderShader,
+    ) -> Result<PixelBenderShaderHandle, Error> {
+        Ok(InterpretedShader::new_handle(shader))
+    }
+
+    fn run_pixelbender_shader(
+        &mut self,
+        handle: PixelBenderShaderHandle,
+        arguments: &[PixelBenderShaderArgument],
+        target: &PixelBenderTarget,
+    ) -> Result<PixelBenderOutput, Error> {
+        let arguments = interpreter_arguments(arguments);
+        let PixelBenderTarget::Bitmap(bitmap) = target else {
+            return interpreter::run_pixelbender_shader(&handle, &arguments, target);
+        };
+
+        let pixels = as_bitmap(bitmap);
+        let (width, height) = {
+            let pixels = pixels.pixels();
+            (pixels.width(), pixels.height())
+        };
+        let output = interpreter::run_shader(handle.0.parsed_shader(), &arguments, width, height)?;
+        *pixels.pixels_mut() = PixelBuffer::from_shader_output(width, height, output);
+        Ok(PixelBenderOutput::Bitmap(Box::new(SoftwareSyncHandle {
+            bitmap: bitmap.clone(),
+            region: PixelRegion::for_whole_size(width, height),
+        })))
+    }
+
+    fn resolve_sync_handle(
+        &mut self,
+        handle: Box<dyn SyncHandle>,
+        with_rgba: RgbaBufRead,
+    ) -> Result<(), Error> {
+        let handle = handle.downcast::<SoftwareSyncHandle>().unwrap();
+        let pixels = as_bitmap(&handle.bitmap).pixels();
+        let mut region = handle.region;
+        region.clamp(pixels.width(), pixels.height());
+        let bytes = pixels.region_to_rgba8(region);
+        with_rgba(&bytes, region.width() * 4);
+        Ok(())
+    }
+}
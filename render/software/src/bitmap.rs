@@ -0,0 +1,91 @@
+use crate::buffer::PixelBuffer;
+use ruffle_render::bitmap::{BitmapHandle, BitmapHandleImpl, PixelRegion, SyncHandle};
+use ruffle_render::pixel_bender::{ImageInputTexture, PixelBenderShaderArgument};
+use std::cell::{Ref, RefCell, RefMut};
+use std::sync::Arc;
+
+/// A texture, stored as premultiplied pixels.
+#[derive(Debug)]
+pub struct SoftwareBitmap {
+    pixels: RefCell<PixelBuffer>,
+}
+
+impl BitmapHandleImpl for SoftwareBitmap {}
+
+impl SoftwareBitmap {
+    pub fn new_handle(pixels: PixelBuffer) -> BitmapHandle {
+        BitmapHandle(Arc::new(Self {
+            pixels: RefCell::new(pixels),
+        }))
+    }
+
+    pub fn pixels(&self) -> Ref<'_, PixelBuffer> {
+        self.pixels.borrow()
+    }
+
+    pub fn pixels_mut(&self) -> RefMut<'_, PixelBuffer> {
+        self.pixels.borrow_mut()
+    }
+}
+
+pub fn as_bitmap(handle: &BitmapHandle) -> &SoftwareBitmap {
+    handle
+        .0
+        .downcast_ref::<SoftwareBitmap>()
+        .expect("Bitmap handle must be a software bitmap")
+}
+
+/// Replaces any bitmap inputs of a Pixel Bender shader with their pixels,
+/// as the interpreter doesn't know about our bitmap handles.
+pub fn interpreter_arguments(
+    arguments: &[PixelBenderShaderArgument],
+) -> Vec<PixelBenderShaderArgument<'static>> {
+    arguments
+        .iter()
+        .map(|argument| match argument {
+            PixelBenderShaderArgument::ImageInput {
+                index,
+                channels,
+                name,
+                texture,
+            } => PixelBenderShaderArgument::ImageInput {
+                index: *index,
+                channels: *channels,
+                name: name.clone(),
+                texture: match texture {
+                    Some(ImageInputTexture::Bitmap(handle)) => {
+                        Some(as_bitmap(handle).pixels().to_shader_input())
+                    }
+                    Some(ImageInputTexture::Bytes {
+                        width,
+                        height,
+                        channels,
+                        bytes,
+                    }) => Some(ImageInputTexture::Bytes {
+                        width: *width,
+                        height: *height,
+                        channels: *channels,
+                        bytes: bytes.clone(),
+                    }),
+                    // Only the GPU backends have textures that aren't bitmaps.
+                    Some(ImageInputTexture::TextureRef(_)) | None => None,
+                },
+            },
+            PixelBenderShaderArgument::ValueInput { index, value } => {
+                PixelBenderShaderArgument::ValueInput {
+                    index: *index,
+                    value: value.clone(),
+                }
+            }
+        })
+        .collect()
+}
+
+/// Everything is drawn immediately, so a sync handle only needs to remember what to read back.
+#[derive(Debug)]
+pub struct SoftwareSyncHandle {
+    pub bitmap: BitmapHandle,
+    pub region: PixelRegion,
+}
+
+impl SyncHandle for SoftwareSyncHandle {}
@@ -0,0 +1,199 @@
+use crate::buffer::{unmultiply, Rgba};
+use swf::BlendMode;
+
+/// Combines a source color with the destination color, or returns None to leave it untouched.
+pub type BlendFunction = fn(Rgba, Rgba) -> Option<Rgba>;
+
+/// Whether the blend mode applies to the nearest `Layer` rather than the target directly below it.
+pub fn targets_layer(mode: BlendMode) -> bool {
+    matches!(mode, BlendMode::Alpha | BlendMode::Erase)
+}
+
+pub fn blend_function(mode: BlendMode) -> BlendFunction {
+    match mode {
+        BlendMode::Normal | BlendMode::Layer => |src, dst| Some(over(src, dst)),
+        BlendMode::Add => |src, dst| Some(trivial(src, dst, |s, d| s + d)),
+        BlendMode::Subtract => |src, dst| Some(trivial(src, dst, |s, d| d - s)),
+        BlendMode::Screen => |src, dst| Some(trivial(src, dst, |s, d| s + d * (1.0 - s))),
+        // Flash special-cases multiply: a fully transparent destination is replaced entirely.
+        BlendMode::Multiply => |src, dst| {
+            if dst[3] > 0.0 {
+                complex(src, dst, |s, d| s * d)
+            } else if src[3] > 0.0 {
+                Some(src)
+            } else {
+                None
+            }
+        },
+        BlendMode::Lighten => |src, dst| complex(src, dst, f32::max),
+        BlendMode::Darken => |src, dst| complex(src, dst, f32::min),
+        BlendMode::Difference => |src, dst| complex(src, dst, |s, d| (d - s).abs()),
+        BlendMode::Invert => |src, dst| complex(src, dst, |_s, d| 1.0 - d),
+        BlendMode::Overlay => |src, dst| complex(src, dst, |s, d| hard_light(d, s)),
+        BlendMode::HardLight => |src, dst| complex(src, dst, hard_light),
+        BlendMode::Alpha => |src, dst| {
+            (src[3] > 0.0).then(|| {
+                [
+                    dst[0] * src[3],
+                    dst[1] * src[3],
+                    dst[2] * src[3],
+                    src[3] * dst[3],
+                ]
+            })
+        },
+        BlendMode::Erase => |src, dst| {
+            (src[3] > 0.0).then(|| {
+                let keep = 1.0 - src[3];
+                [dst[0] * keep, dst[1] * keep, dst[2] * keep, dst[3] * keep]
+            })
+        },
+    }
+}
+
+/// Premultiplied "source over destination" blending.
+pub fn over(src: Rgba, dst: Rgba) -> Rgba {
+    let keep = 1.0 - src[3];
+    [
+        src[0] + dst[0] * keep,
+        src[1] + dst[1] * keep,
+        src[2] + dst[2] * keep,
+        src[3] + dst[3] * keep,
+    ]
+}
+
+/// A blend that the GPU backends express with fixed function blending, with alpha always composited "over".
+fn trivial(src: Rgba, dst: Rgba, color: impl Fn(f32, f32) -> f32) -> Rgba {
+    [
+        color(src[0], dst[0]),
+        color(src[1], dst[1]),
+        color(src[2], dst[2]),
+        src[3] + dst[3] * (1.0 - src[3]),
+    ]
+}
+
+/// A separable blend mode, with `func` operating on straight alpha colors.
+/// Nothing is written where the source is fully transparent.
+fn complex(src: Rgba, dst: Rgba, func: impl Fn(f32, f32) -> f32) -> Option<Rgba> {
+    if src[3] <= 0.0 {
+        return None;
+    }
+    let (straight_src, straight_dst) = (unmultiply(src), unmultiply(dst));
+    let channel = |i: usize| {
+        src[i] * (1.0 - dst[3])
+            + dst[i] * (1.0 - src[3])
+            + src[3] * dst[3] * func(straight_src[i], straight_dst[i])
+    };
+    Some([
+        channel(0),
+        channel(1),
+        channel(2),
+        src[3] + dst[3] * (1.0 - src[3]),
+    ])
+}
+
+fn hard_light(src: f32, dst: f32) -> f32 {
+    if src <= 0.5 {
+        2.0 * src * dst
+    } else {
+        1.0 - 2.0 * (1.0 - dst) * (1.0 - src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blend_function, over, targets_layer};
+    use swf::BlendMode;
+
+    const TRANSPARENT: [f32; 4] = [0.0; 4];
+    const HALF_RED: [f32; 4] = [0.5, 0.0, 0.0, 0.5];
+    const GRAY: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+
+    fn blend(mode: BlendMode, src: [f32; 4], dst: [f32; 4]) -> Option<[f32; 4]> {
+        blend_function(mode)(src, dst)
+    }
+
+    #[test]
+    fn over_is_premultiplied() {
+        assert_eq!(over(HALF_RED, GRAY), [0.75, 0.25, 0.25, 1.0]);
+        assert_eq!(over(TRANSPARENT, GRAY), GRAY);
+        assert_eq!(over(GRAY, HALF_RED), GRAY);
+    }
+
+    #[test]
+    fn trivial_modes_composite_alpha_over() {
+        assert_eq!(
+            blend(BlendMode::Add, HALF_RED, GRAY),
+            Some([1.0, 0.5, 0.5, 1.0])
+        );
+        assert_eq!(
+            blend(BlendMode::Subtract, HALF_RED, GRAY),
+            Some([0.0, 0.5, 0.5, 1.0])
+        );
+        assert_eq!(
+            blend(BlendMode::Screen, HALF_RED, TRANSPARENT),
+            Some(HALF_RED)
+        );
+    }
+
+    #[test]
+    fn complex_modes_skip_transparent_sources() {
+        for mode in [
+            BlendMode::Lighten,
+            BlendMode::Darken,
+            BlendMode::Difference,
+            BlendMode::Invert,
+            BlendMode::Overlay,
+            BlendMode::HardLight,
+            BlendMode::Multiply,
+        ] {
+            assert_eq!(blend(mode, TRANSPARENT, GRAY), None, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn complex_modes_use_straight_colors() {
+        let white = [1.0; 4];
+        assert_eq!(blend(BlendMode::Multiply, white, GRAY), Some(GRAY));
+        assert_eq!(
+            blend(BlendMode::Difference, white, GRAY),
+            Some([0.5, 0.5, 0.5, 1.0])
+        );
+        assert_eq!(blend(BlendMode::Darken, white, GRAY), Some(GRAY));
+        assert_eq!(blend(BlendMode::Lighten, white, GRAY), Some(white));
+        assert_eq!(
+            blend(BlendMode::Invert, HALF_RED, [0.25, 0.75, 1.0, 1.0]),
+            Some([0.5, 0.5, 0.5, 1.0])
+        );
+        assert_eq!(
+            blend(BlendMode::HardLight, [0.25, 0.75, 0.5, 1.0], GRAY),
+            Some([0.25, 0.75, 0.5, 1.0])
+        );
+    }
+
+    #[test]
+    fn multiply_replaces_transparent_destination() {
+        assert_eq!(
+            blend(BlendMode::Multiply, HALF_RED, TRANSPARENT),
+            Some(HALF_RED)
+        );
+        assert_eq!(blend(BlendMode::Multiply, TRANSPARENT, TRANSPARENT), None);
+    }
+
+    #[test]
+    fn alpha_and_erase_use_source_alpha() {
+        assert!(targets_layer(BlendMode::Alpha));
+        assert!(targets_layer(BlendMode::Erase));
+        assert!(!targets_layer(BlendMode::Normal));
+
+        assert_eq!(
+            blend(BlendMode::Alpha, HALF_RED, GRAY),
+            Some([0.25, 0.25, 0.25, 0.5])
+        );
+        assert_eq!(
+            blend(BlendMode::Erase, HALF_RED, GRAY),
+            Some([0.25, 0.25, 0.25, 0.5])
+        );
+        assert_eq!(blend(BlendMode::Alpha, TRANSPARENT, GRAY), None);
+        assert_eq!(blend(BlendMode::Erase, TRANSPARENT, GRAY), None);
+    }
+}
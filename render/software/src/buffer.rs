@@ -0,0 +1,245 @@
+use ruffle_render::bitmap::PixelRegion;
+use ruffle_render::pixel_bender::ImageInputTexture;
+
+/// An RGBA color with premultiplied alpha, each component being in the range 0.0 to 1.0.
+pub type Rgba = [f32; 4];
+
+/// An image of premultiplied colors, with one color per pixel.
+///
+/// This is what textures are stored as, and what render targets are resolved to.
+#[derive(Clone, Debug)]
+pub struct PixelBuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<Rgba>,
+}
+
+impl PixelBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::filled(width, height, [0.0; 4])
+    }
+
+    pub fn filled(width: u32, height: u32, color: Rgba) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width as usize * height as usize],
+        }
+    }
+
+    /// Creates a buffer from tightly packed RGBA bytes, which must already be premultiplied.
+    pub fn from_rgba8(width: u32, height: u32, data: &[u8]) -> Self {
+        let pixels = data
+            .chunks_exact(4)
+            .take(width as usize * height as usize)
+            .map(|c| {
+                [
+                    f32::from(c[0]) / 255.0,
+                    f32::from(c[1]) / 255.0,
+                    f32::from(c[2]) / 255.0,
+                    f32::from(c[3]) / 255.0,
+                ]
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Returns the tightly packed (premultiplied) RGBA bytes of the given region.
+    pub fn region_to_rgba8(&self, region: PixelRegion) -> Vec<u8> {
+        let mut region = region;
+        region.clamp(self.width, self.height);
+        let mut bytes = Vec::with_capacity(region.width() as usize * region.height() as usize * 4);
+        for y in region.y_min..region.y_max {
+            for x in region.x_min..region.x_max {
+                bytes.extend(self.get(x, y).map(to_u8));
+            }
+        }
+        bytes
+    }
+
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.region_to_rgba8(PixelRegion::for_whole_size(self.width, self.height))
+    }
+
+    /// Creates a buffer from the output of the Pixel Bender interpreter.
+    pub fn from_shader_output(width: u32, height: u32, pixels: Vec<Rgba>) -> Self {
+        Self {
+            width,
+            height,
+            pixels: pixels.into_iter().map(|c| c.map(quantize)).collect(),
+        }
+    }
+
+    /// Converts this buffer into an image input for the Pixel Bender interpreter.
+    pub fn to_shader_input(&self) -> ImageInputTexture<'static> {
+        ImageInputTexture::Bytes {
+            width: self.width,
+            height: self.height,
+            channels: 4,
+            bytes: self
+                .pixels
+                .iter()
+                .flatten()
+                .flat_map(|c| c.to_le_bytes())
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Rgba {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, color: Rgba) {
+        let index = (y * self.width + x) as usize;
+        self.pixels[index] = color;
+    }
+
+    /// Gets a pixel, or None if the coordinates lie outside of this buffer.
+    pub fn get_checked(&self, x: i32, y: i32) -> Option<Rgba> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            None
+        } else {
+            Some(self.get(x as u32, y as u32))
+        }
+    }
+
+    /// Gets a pixel, with coordinates clamped to the edges of this buffer.
+    pub fn get_clamped(&self, x: i32, y: i32) -> Rgba {
+        self.texel(x, y, false)
+    }
+
+    fn texel(&self, x: i32, y: i32, repeating: bool) -> Rgba {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+        let (width, height) = (self.width as i32, self.height as i32);
+        let (x, y) = if repeating {
+            (x.rem_euclid(width), y.rem_euclid(height))
+        } else {
+            (x.clamp(0, width - 1), y.clamp(0, height - 1))
+        };
+        self.get(x as u32, y as u32)
+    }
+
+    /// Samples this buffer at normalized texture coordinates, like a GPU sampler would.
+    pub fn sample(&self, u: f32, v: f32, smoothed: bool, repeating: bool) -> Rgba {
+        let x = u * self.width as f32;
+        let y = v * self.height as f32;
+        if !smoothed {
+            return self.texel(x.floor() as i32, y.floor() as i32, repeating);
+        }
+
+        // Texel centers are at half coordinates.
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let top = mix(
+            self.texel(x0, y0, repeating),
+            self.texel(x0.saturating_add(1), y0, repeating),
+            fx,
+        );
+        let bottom = mix(
+            self.texel(x0, y0.saturating_add(1), repeating),
+            self.texel(x0.saturating_add(1), y0.saturating_add(1), repeating),
+            fx,
+        );
+        mix(top, bottom, fy)
+    }
+
+    /// Returns a copy of the `size` pixels starting at `point`.
+    /// Any part of the area outside of this buffer is transparent.
+    pub fn crop(&self, point: (u32, u32), size: (u32, u32)) -> PixelBuffer {
+        let mut result = PixelBuffer::new(size.0, size.1);
+        result.copy_from(self, point, size, (0, 0));
+        result
+    }
+
+    /// Copies an area of `source` into this buffer, clipping it to both buffers.
+    pub fn copy_from(
+        &mut self,
+        source: &PixelBuffer,
+        source_point: (u32, u32),
+        size: (u32, u32),
+        dest_point: (u32, u32),
+    ) {
+        let width = size
+            .0
+            .min(source.width.saturating_sub(source_point.0))
+            .min(self.width.saturating_sub(dest_point.0));
+        let height = size
+            .1
+            .min(source.height.saturating_sub(source_point.1))
+            .min(self.height.saturating_sub(dest_point.1));
+        for y in 0..height {
+            for x in 0..width {
+                self.set(
+                    dest_point.0 + x,
+                    dest_point.1 + y,
+                    source.get(source_point.0 + x, source_point.1 + y),
+                );
+            }
+        }
+    }
+}
+
+/// Rounds a component to the nearest value an 8-bit texture can hold.
+pub fn quantize(value: f32) -> f32 {
+    f32::from(to_u8(value)) / 255.0
+}
+
+pub fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+pub fn mix(a: Rgba, b: Rgba, t: f32) -> Rgba {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+pub fn saturate(color: Rgba) -> Rgba {
+    color.map(|c| c.clamp(0.0, 1.0))
+}
+
+pub fn premultiply(color: Rgba) -> Rgba {
+    [
+        color[0] * color[3],
+        color[1] * color[3],
+        color[2] * color[3],
+        color[3],
+    ]
+}
+
+pub fn unmultiply(color: Rgba) -> Rgba {
+    if color[3] > 0.0 {
+        [
+            color[0] / color[3],
+            color[1] / color[3],
+            color[2] / color[3],
+            color[3],
+        ]
+    } else {
+        [0.0; 4]
+    }
+}
+
+/// Converts an 8-bit straight alpha color into a premultiplied one.
+pub fn color_to_rgba(color: swf::Color) -> Rgba {
+    premultiply([
+        f32::from(color.r) / 255.0,
+        f32::from(color.g) / 255.0,
+        f32::from(color.b) / 255.0,
+        f32::from(color.a) / 255.0,
+    ])
+}
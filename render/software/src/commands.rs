@@ -0,0 +1,355 @@
+use crate::bitmap::as_bitmap;
+use crate::blend::{blend_function, targets_layer, BlendFunction};
+use crate::buffer::{color_to_rgba, premultiply, saturate, unmultiply, Rgba};
+use crate::mesh::{as_mesh, transform_point, DrawType};
+use crate::raster::RenderTarget;
+use ruffle_render::backend::ShapeHandle;
+use ruffle_render::bitmap::{BitmapHandle, PixelSnapping};
+use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
+use ruffle_render::lines::{emulate_line, emulate_line_rect};
+use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender::interpreter::run_shader;
+use ruffle_render::pixel_bender::{PixelBenderShaderArgument, PixelBenderShaderHandle};
+use ruffle_render::transform::Transform;
+use swf::{BlendMode, Color, ColorTransform};
+
+/// A unit square, used for rectangles and bitmaps. Its positions double as texture coordinates.
+const QUAD: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+const QUAD_INDICES: [u32; 6] = [0, 1, 2, 0, 2, 3];
+
+struct Layer {
+    target: RenderTarget,
+
+    /// Whether this target was created for `BlendMode::Layer`,
+    /// making it the one that `Alpha` and `Erase` blends apply to.
+    is_layer: bool,
+}
+
+/// Draws a `CommandList` into a `RenderTarget`.
+///
+/// Blend modes are drawn into a fresh target of their own, which is then blended onto its parent.
+pub struct SoftwareCommandHandler {
+    layers: Vec<Layer>,
+}
+
+impl SoftwareCommandHandler {
+    pub fn new(target: RenderTarget, is_layer: bool) -> Self {
+        Self {
+            layers: vec![Layer { target, is_layer }],
+        }
+    }
+
+    pub fn finish(mut self) -> RenderTarget {
+        self.layers
+            .pop()
+            .expect("Command handler must have a target")
+            .target
+    }
+
+    fn target(&mut self) -> &mut RenderTarget {
+        &mut self
+            .layers
+            .last_mut()
+            .expect("Command handler must have a target")
+            .target
+    }
+
+    /// Transforms the vertices by `matrix` (with translation in pixels) and fills the triangles.
+    ///
+    /// `shade` receives the barycentric coordinates of a pixel and the vertex indices of its triangle.
+    fn draw_triangles(
+        &mut self,
+        matrix: &Matrix,
+        positions: &[[f32; 2]],
+        indices: &[u32],
+        blend: BlendFunction,
+        shade: &mut dyn FnMut([f32; 3], [usize; 3]) -> Rgba,
+    ) {
+        let tx = matrix.tx.to_pixels() as f32;
+        let ty = matrix.ty.to_pixels() as f32;
+        let screen: Vec<[f32; 2]> = positions
+            .iter()
+            .map(|&[x, y]| {
+                [
+                    matrix.a * x + matrix.c * y + tx,
+                    matrix.b * x + matrix.d * y + ty,
+                ]
+            })
+            .collect();
+
+        let target = self.target();
+        for triangle in indices.chunks_exact(3) {
+            let vertices = [
+                triangle[0] as usize,
+                triangle[1] as usize,
+                triangle[2] as usize,
+            ];
+            if vertices.iter().any(|&i| i >= screen.len()) {
+                continue;
+            }
+            target.fill_triangle(vertices.map(|i| screen[i]), blend, &mut |weights| {
+                shade(weights, vertices)
+            });
+        }
+    }
+
+    fn draw_bitmap(
+        &mut self,
+        bitmap: &BitmapHandle,
+        mut matrix: Matrix,
+        color_transform: &ColorTransform,
+        smoothing: bool,
+        is_stage3d: bool,
+    ) {
+        let pixels = as_bitmap(bitmap).pixels();
+        matrix *= Matrix::scale(pixels.width() as f32, pixels.height() as f32);
+        let mult = color_transform.mult_rgba_normalized();
+        let add = color_transform.add_rgba_normalized();
+        let blend: BlendFunction = if is_stage3d {
+            // Stage3D output replaces the color, but keeps the alpha of what's below it.
+            |src, dst| Some([src[0], src[1], src[2], dst[3]])
+        } else {
+            blend_function(BlendMode::Normal)
+        };
+        self.draw_triangles(&matrix, &QUAD, &QUAD_INDICES, blend, &mut |weights, i| {
+            let [u, v] = interpolate(weights, i.map(|i| QUAD[i]));
+            transform_texel(pixels.sample(u, v, smoothing, false), mult, add, is_stage3d)
+        });
+    }
+
+    fn run_shader_blend(&mut self, shader: &PixelBenderShaderHandle, layer: &RenderTarget) {
+        let target = self.target();
+        let background = target.resolve();
+        let foreground = layer.resolve();
+        let (width, height) = (target.width(), target.height());
+        let arguments = [
+            PixelBenderShaderArgument::ImageInput {
+                index: 0,
+                channels: 0xFF,
+                name: "background".to_string(),
+                texture: Some(background.to_shader_input()),
+            },
+            PixelBenderShaderArgument::ImageInput {
+                index: 1,
+                channels: 0xFF,
+                name: "foreground".to_string(),
+                texture: Some(foreground.to_shader_input()),
+            },
+        ];
+        match run_shader(shader.0.parsed_shader(), &arguments, width, height) {
+            Ok(pixels) => {
+                for (i, color) in pixels.into_iter().enumerate() {
+                    let i = i as u32;
+                    target.set_pixel(i % width, i / width, color);
+                }
+            }
+            Err(e) => tracing::error!("Failed to run Pixel Bender blend mode: {e}"),
+        }
+    }
+}
+
+impl CommandHandler for SoftwareCommandHandler {
+    fn render_bitmap(
+        &mut self,
+        bitmap: BitmapHandle,
+        transform: Transform,
+        smoothing: bool,
+        pixel_snapping: PixelSnapping,
+    ) {
+        let mut matrix = transform.matrix;
+        pixel_snapping.apply(&mut matrix);
+        self.draw_bitmap(
+            &bitmap,
+            matrix,
+            &transform.color_transform,
+            smoothing,
+            false,
+        );
+    }
+
+    fn render_stage3d(&mut self, bitmap: BitmapHandle, transform: Transform) {
+        self.draw_bitmap(
+            &bitmap,
+            transform.matrix,
+            &transform.color_transform,
+            false,
+            true,
+        );
+    }
+
+    fn render_shape(&mut self, shape: ShapeHandle, transform: Transform) {
+        let mesh = as_mesh(&shape);
+        let matrix = transform.matrix;
+        let mult = transform.color_transform.mult_rgba_normalized();
+        let add = transform.color_transform.add_rgba_normalized();
+        let normal = blend_function(BlendMode::Normal);
+        let writes_color = self.target().writes_color();
+
+        for draw in &mesh.draws {
+            let indices = if writes_color {
+                &draw.indices[..]
+            } else {
+                // Strokes are not part of a mask.
+                &draw.indices[..(draw.mask_index_count as usize).min(draw.indices.len())]
+            };
+            let positions: Vec<[f32; 2]> = draw.vertices.iter().map(|v| [v.x, v.y]).collect();
+
+            match &draw.draw_type {
+                DrawType::Color => {
+                    let colors: Vec<Rgba> = draw
+                        .vertices
+                        .iter()
+                        .map(|v| {
+                            let color = [v.color.r, v.color.g, v.color.b, v.color.a]
+                                .map(|c| f32::from(c) / 255.0);
+                            premultiply(saturate(apply_color_transform(color, mult, add)))
+                        })
+                        .collect();
+                    self.draw_triangles(&matrix, &positions, indices, normal, &mut |weights, i| {
+                        interpolate(weights, i.map(|i| colors[i]))
+                    });
+                }
+                DrawType::Gradient {
+                    matrix: gradient_matrix,
+                    gradient,
+                } => {
+                    let gradient = &mesh.gradients[*gradient];
+                    self.draw_triangles(&matrix, &positions, indices, normal, &mut |weights, i| {
+                        let [x, y] = interpolate(weights, i.map(|i| positions[i]));
+                        let (u, v) = transform_point(gradient_matrix, x, y);
+                        let color = gradient.sample(u, v);
+                        premultiply(saturate(apply_color_transform(color, mult, add)))
+                    });
+                }
+                DrawType::Bitmap {
+                    matrix: bitmap_matrix,
+                    bitmap,
+                    is_smoothed,
+                    is_repeating,
+                } => {
+                    let pixels = as_bitmap(bitmap).pixels();
+                    self.draw_triangles(&matrix, &positions, indices, normal, &mut |weights, i| {
+                        let [x, y] = interpolate(weights, i.map(|i| positions[i]));
+                        let (u, v) = transform_point(bitmap_matrix, x, y);
+                        let texel = pixels.sample(u, v, *is_smoothed, *is_repeating);
+                        transform_texel(texel, mult, add, false)
+                    });
+                }
+                DrawType::TexturedTriangles {
+                    bitmap,
+                    is_smoothed,
+                    is_repeating,
+                    uvts,
+                } => {
+                    let pixels = as_bitmap(bitmap).pixels();
+                    // The coordinates are premultiplied by T, so that they can be interpolated linearly.
+                    let uvts: Vec<[f32; 3]> =
+                        uvts.iter().map(|&[u, v, t]| [u * t, v * t, t]).collect();
+                    self.draw_triangles(&matrix, &positions, indices, normal, &mut |weights, i| {
+                        let uvt = interpolate(
+                            weights,
+                            i.map(|i| uvts.get(i).copied().unwrap_or([0.0, 0.0, 1.0])),
+                        );
+                        // Dividing the linearly interpolated values by T gives perspective-correct coordinates.
+                        let (u, v) = (uvt[0] / uvt[2], uvt[1] / uvt[2]);
+                        let texel = pixels.sample(u, v, *is_smoothed, *is_repeating);
+                        transform_texel(texel, mult, add, false)
+                    });
+                }
+            }
+        }
+    }
+
+    fn draw_rect(&mut self, color: Color, matrix: Matrix) {
+        let color = color_to_rgba(color);
+        self.draw_triangles(
+            &matrix,
+            &QUAD,
+            &QUAD_INDICES,
+            blend_function(BlendMode::Normal),
+            &mut |_, _| color,
+        );
+    }
+
+    fn draw_line(&mut self, color: Color, matrix: Matrix) {
+        emulate_line(self, color, matrix);
+    }
+
+    fn draw_line_rect(&mut self, color: Color, matrix: Matrix) {
+        emulate_line_rect(self, color, matrix);
+    }
+
+    fn push_mask(&mut self) {
+        self.target().push_mask();
+    }
+
+    fn activate_mask(&mut self) {
+        self.target().activate_mask();
+    }
+
+    fn deactivate_mask(&mut self) {
+        self.target().deactivate_mask();
+    }
+
+    fn pop_mask(&mut self) {
+        self.target().pop_mask();
+    }
+
+    fn blend(&mut self, commands: CommandList, blend_mode: RenderBlendMode) {
+        let target = self.target();
+        let layer = RenderTarget::new(
+            target.width(),
+            target.height(),
+            target.sample_count(),
+            [0.0; 4],
+        );
+        self.layers.push(Layer {
+            target: layer,
+            is_layer: matches!(blend_mode, RenderBlendMode::Builtin(BlendMode::Layer)),
+        });
+        commands.execute(self);
+        let layer = self
+            .layers
+            .pop()
+            .expect("Blend layer must not have been removed")
+            .target;
+
+        match blend_mode {
+            RenderBlendMode::Builtin(mode) if targets_layer(mode) => {
+                // Without a layer to apply to, these blend modes do nothing.
+                if let Some(parent) = self.layers.iter_mut().rev().find(|layer| layer.is_layer) {
+                    parent.target.composite(&layer, blend_function(mode));
+                }
+            }
+            RenderBlendMode::Builtin(mode) => {
+                self.target().composite(&layer, blend_function(mode));
+            }
+            RenderBlendMode::Shader(shader) => self.run_shader_blend(&shader, &layer),
+        }
+    }
+}
+
+fn interpolate<const N: usize>(weights: [f32; 3], values: [[f32; N]; 3]) -> [f32; N] {
+    std::array::from_fn(|i| {
+        values[0][i] * weights[0] + values[1][i] * weights[1] + values[2][i] * weights[2]
+    })
+}
+
+fn apply_color_transform(color: Rgba, mult: [f32; 4], add: [f32; 4]) -> Rgba {
+    std::array::from_fn(|i| color[i] * mult[i] + add[i])
+}
+
+/// Applies a color transform to a premultiplied texel, as the bitmap shaders of the GPU backends do.
+///
+/// Stage3D output saturates after premultiplying, so that in-range colors are never altered.
+fn transform_texel(texel: Rgba, mult: [f32; 4], add: [f32; 4], late_saturate: bool) -> Rgba {
+    if texel[3] <= 0.0 {
+        return texel;
+    }
+    let color = apply_color_transform(unmultiply(texel), mult, add);
+    if late_saturate {
+        saturate(premultiply(color))
+    } else {
+        premultiply(saturate(color))
+    }
+}
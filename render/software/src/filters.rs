@@ -0,0 +1,393 @@
+use crate::bitmap::{as_bitmap, interpreter_arguments};
+use crate::buffer::{color_to_rgba, premultiply, saturate, unmultiply, PixelBuffer, Rgba};
+use ruffle_render::filters::{
+    gradient_filter_colors, DisplacementMapFilter, DisplacementMapFilterMode, Filter, ShaderFilter,
+};
+use ruffle_render::pixel_bender::interpreter::run_shader;
+use ruffle_render::pixel_bender::PixelBenderShaderArgument;
+use swf::{
+    BevelFilter, BlurFilter, ColorMatrixFilter, ConvolutionFilter, GlowFilter, GradientFilter,
+};
+
+/// The largest convolution matrix that the GPU backends support.
+const MAX_CONVOLUTION_SIZE: usize = 15;
+
+/// Applies a filter to `source`, producing an image of the same size.
+///
+/// Every filter mirrors the shader that the wgpu backend uses for it.
+pub fn apply_filter(source: &PixelBuffer, filter: &Filter) -> PixelBuffer {
+    match filter {
+        Filter::BlurFilter(filter) => blur(source, filter),
+        Filter::GlowFilter(filter) => glow(source, filter, (0.0, 0.0)),
+        Filter::DropShadowFilter(filter) => {
+            let distance = filter.distance.to_f32();
+            let angle = filter.angle.to_f32();
+            glow(
+                source,
+                &filter.inner_glow_filter(),
+                (-angle.cos() * distance, -angle.sin() * distance),
+            )
+        }
+        Filter::BevelFilter(filter) => bevel(source, filter),
+        Filter::GradientGlowFilter(filter) => gradient(source, filter, GradientFilterType::Glow),
+        Filter::GradientBevelFilter(filter) => gradient(source, filter, GradientFilterType::Bevel),
+        Filter::ColorMatrixFilter(filter) => color_matrix(source, filter),
+        Filter::ConvolutionFilter(filter) => convolution(source, filter),
+        Filter::DisplacementMapFilter(filter) => displacement_map(source, filter),
+        Filter::ShaderFilter(filter) => shader(source, filter),
+    }
+}
+
+fn map_pixels(source: &PixelBuffer, mut func: impl FnMut(u32, u32) -> Rgba) -> PixelBuffer {
+    let mut result = PixelBuffer::new(source.width(), source.height());
+    for y in 0..source.height() {
+        for x in 0..source.width() {
+            result.set(x, y, func(x, y));
+        }
+    }
+    result
+}
+
+fn blur(source: &PixelBuffer, filter: &BlurFilter) -> PixelBuffer {
+    let mut result = source.clone();
+    for _ in 0..filter.num_passes() {
+        result = blur_pass(&result, filter.blur_x.to_f32(), true);
+        result = blur_pass(&result, filter.blur_y.to_f32(), false);
+    }
+    result
+}
+
+/// Blurs in one direction with a box kernel of `strength` pixels,
+/// the outermost pixels of which are only partially weighted.
+fn blur_pass(source: &PixelBuffer, strength: f32, horizontal: bool) -> PixelBuffer {
+    // Full width of the kernel (left edge to right edge)
+    let full_size = strength.min(255.0);
+    if full_size <= 1.0 {
+        // A width of 1 or less is a noop (it'd just sample itself and nothing else)
+        return source.clone();
+    }
+    let radius = (full_size - 1.0) / 2.0;
+    let m = radius.ceil() - 1.0;
+    let last_weight = ((radius - m) * 255.0).floor() / 255.0;
+    let (m, reach) = (m as i32, m as i32 + 1);
+
+    map_pixels(source, |x, y| {
+        let mut total = [0.0; 4];
+        for k in -reach..=reach {
+            let weight = if k.abs() <= m { 1.0 } else { last_weight };
+            let color = if horizontal {
+                source.get_clamped(x as i32 + k, y as i32)
+            } else {
+                source.get_clamped(x as i32, y as i32 + k)
+            };
+            for (total, component) in total.iter_mut().zip(color) {
+                *total += component * weight;
+            }
+        }
+        total.map(|c| (c / full_size * 255.0).floor() / 255.0)
+    })
+}
+
+/// Samples the alpha of a blurred image at a pixel of the source offset by `offset`,
+/// treating everything outside of the image as transparent.
+fn blurred_alpha(blurred: &PixelBuffer, x: u32, y: u32, offset: (f32, f32)) -> f32 {
+    let u = (x as f32 + 0.5 + offset.0) / blurred.width() as f32;
+    let v = (y as f32 + 0.5 + offset.1) / blurred.height() as f32;
+    if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+        return 0.0;
+    }
+    blurred.sample(u, v, false, false)[3]
+}
+
+fn glow(source: &PixelBuffer, filter: &GlowFilter, offset: (f32, f32)) -> PixelBuffer {
+    let blurred = blur(source, &filter.inner_blur_filter());
+    let strength = filter.strength.to_f32();
+    // Start with 1 alpha because we'll be multiplying the whole thing
+    let rgb = [
+        f32::from(filter.color.r) / 255.0,
+        f32::from(filter.color.g) / 255.0,
+        f32::from(filter.color.b) / 255.0,
+        1.0,
+    ];
+    let color_alpha = f32::from(filter.color.a) / 255.0;
+    let scale = |c: Rgba, s: f32| c.map(|c| c * s);
+    let add = |a: Rgba, b: Rgba| std::array::from_fn(|i| a[i] + b[i]);
+
+    map_pixels(source, |x, y| {
+        let blur = blurred_alpha(&blurred, x, y, offset);
+        let dest = source.get(x, y);
+        if filter.is_inner() {
+            let alpha = color_alpha * ((1.0 - blur) * strength).clamp(0.0, 1.0);
+            if filter.composite_source() && !filter.is_knockout() {
+                add(scale(rgb, alpha * dest[3]), scale(dest, 1.0 - alpha))
+            } else {
+                // It's intentional that the !composite_source is different for inner/outer. Just Flash things.
+                scale(rgb, alpha * dest[3])
+            }
+        } else {
+            let alpha = color_alpha * (blur * strength).clamp(0.0, 1.0);
+            if filter.is_knockout() {
+                scale(rgb, alpha * (1.0 - dest[3]))
+            } else if filter.composite_source() {
+                add(scale(rgb, alpha * (1.0 - dest[3])), dest)
+            } else {
+                scale(rgb, alpha)
+            }
+        }
+    })
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum BevelType {
+    Outer,
+    Inner,
+    Full,
+}
+
+impl BevelType {
+    fn new(on_top: bool, inner: bool) -> Self {
+        if on_top {
+            BevelType::Full
+        } else if inner {
+            BevelType::Inner
+        } else {
+            BevelType::Outer
+        }
+    }
+}
+
+/// Combines the glow of a bevel with the source, as both bevel shaders do.
+fn composite_bevel(
+    glow: Rgba,
+    dest: Rgba,
+    bevel_type: BevelType,
+    knockout: bool,
+    composite_source: bool,
+) -> Rgba {
+    let glow_alpha = glow[3];
+    let channel = |i: usize| match bevel_type {
+        BevelType::Full if knockout => glow[i],
+        BevelType::Full => dest[i] - dest[i] * glow_alpha + glow[i],
+        BevelType::Inner if knockout || !composite_source => glow[i] * dest[3],
+        BevelType::Inner => glow[i] * dest[3] + dest[i] * (1.0 - glow_alpha),
+        BevelType::Outer if knockout => glow[i] - glow[i] * dest[3],
+        BevelType::Outer if composite_source => dest[i] + glow[i] - glow[i] * dest[3],
+        BevelType::Outer => glow[i],
+    };
+    std::array::from_fn(channel)
+}
+
+fn bevel(source: &PixelBuffer, filter: &BevelFilter) -> PixelBuffer {
+    let blurred = blur(source, &filter.inner_blur_filter());
+    let strength = filter.strength.to_f32();
+    let distance = filter.distance.to_f32();
+    let angle = filter.angle.to_f32();
+    let offset = (angle.cos() * distance, angle.sin() * distance);
+    let highlight = color_to_rgba(filter.highlight_color);
+    let shadow = color_to_rgba(filter.shadow_color);
+    let bevel_type = BevelType::new(filter.is_on_top(), filter.is_inner());
+
+    map_pixels(source, |x, y| {
+        let left = blurred_alpha(&blurred, x, y, offset);
+        let right = blurred_alpha(&blurred, x, y, (-offset.0, -offset.1));
+        let highlight_alpha = ((left - right) * strength).clamp(0.0, 1.0);
+        let shadow_alpha = ((right - left) * strength).clamp(0.0, 1.0);
+        let glow =
+            std::array::from_fn(|i| highlight[i] * highlight_alpha + shadow[i] * shadow_alpha);
+        // Regular bevels always behave as if the source is composited.
+        composite_bevel(
+            glow,
+            source.get(x, y),
+            bevel_type,
+            filter.is_knockout(),
+            true,
+        )
+    })
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum GradientFilterType {
+    Glow,
+    Bevel,
+}
+
+fn gradient(
+    source: &PixelBuffer,
+    filter: &GradientFilter,
+    gradient_type: GradientFilterType,
+) -> PixelBuffer {
+    let blurred = blur(source, &filter.inner_blur_filter());
+    let colors = gradient_filter_colors(&filter.colors);
+    let strength = filter.strength.to_f32();
+    let distance = filter.distance.to_f32();
+    let angle = filter.angle.to_f32();
+    let offset = match gradient_type {
+        // A gradient glow is offset like a drop shadow.
+        GradientFilterType::Glow => (-angle.cos() * distance, -angle.sin() * distance),
+        GradientFilterType::Bevel => (angle.cos() * distance, angle.sin() * distance),
+    };
+    let bevel_type = BevelType::new(filter.is_on_top(), filter.is_inner());
+    let gradient_color = |ratio: f32| colors[(ratio.clamp(0.0, 1.0) * 255.0).round() as usize];
+
+    map_pixels(source, |x, y| {
+        let left = blurred_alpha(&blurred, x, y, offset);
+        let glow = match gradient_type {
+            GradientFilterType::Glow if bevel_type == BevelType::Inner => {
+                gradient_color((1.0 - left) * strength)
+            }
+            GradientFilterType::Glow => gradient_color(left * strength),
+            GradientFilterType::Bevel => {
+                // Ratio 0 is the shadow side of the bevel, ratio 255 the highlight side.
+                let right = blurred_alpha(&blurred, x, y, (-offset.0, -offset.1));
+                let difference = ((left - right) * strength).clamp(-1.0, 1.0);
+                gradient_color(0.5 + difference * 0.5)
+            }
+        };
+        composite_bevel(
+            glow,
+            source.get(x, y),
+            bevel_type,
+            filter.is_knockout(),
+            filter.composite_source(),
+        )
+    })
+}
+
+fn color_matrix(source: &PixelBuffer, filter: &ColorMatrixFilter) -> PixelBuffer {
+    let m = &filter.matrix;
+    map_pixels(source, |x, y| {
+        let [r, g, b, a] = unmultiply(source.get(x, y));
+        let color = std::array::from_fn(|i| {
+            let row = &m[i * 5..i * 5 + 5];
+            (row[0] * r + row[1] * g + row[2] * b + row[3] * a + row[4] / 255.0).clamp(0.0, 1.0)
+        });
+        premultiply(color)
+    })
+}
+
+fn convolution(source: &PixelBuffer, filter: &ConvolutionFilter) -> PixelBuffer {
+    let matrix_x = usize::from(filter.num_matrix_cols).min(MAX_CONVOLUTION_SIZE);
+    let matrix_y = usize::from(filter.num_matrix_rows).min(MAX_CONVOLUTION_SIZE);
+    let divisor = if filter.divisor == 0.0 {
+        1.0
+    } else {
+        filter.divisor
+    };
+    let default_color = [
+        f32::from(filter.default_color.r) / 255.0,
+        f32::from(filter.default_color.g) / 255.0,
+        f32::from(filter.default_color.b) / 255.0,
+        f32::from(filter.default_color.a) / 255.0,
+    ];
+    let (width, height) = (source.width() as i32, source.height() as i32);
+
+    // Loads a pixel of the source with its alpha unmultiplied.
+    let load = |x: i32, y: i32| {
+        if let Some(color) = source.get_checked(x, y) {
+            unmultiply(color)
+        } else if filter.is_clamped() {
+            unmultiply(source.get_clamped(x.clamp(0, width - 1), y.clamp(0, height - 1)))
+        } else {
+            default_color
+        }
+    };
+
+    let center = ((matrix_x / 2) as i32, (matrix_y / 2) as i32);
+    map_pixels(source, |x, y| {
+        let (x, y) = (x as i32, y as i32);
+        let mut sum = [0.0; 4];
+        for my in 0..matrix_y {
+            for mx in 0..matrix_x {
+                let weight = filter
+                    .matrix
+                    .get(my * matrix_x + mx)
+                    .copied()
+                    .unwrap_or_default();
+                let color = load(x + mx as i32 - center.0, y + my as i32 - center.1);
+                for (sum, component) in sum.iter_mut().zip(color) {
+                    *sum += weight * component;
+                }
+            }
+        }
+
+        let mut color = saturate(sum.map(|c| c / divisor + filter.bias / 255.0));
+        if filter.is_preserve_alpha() {
+            color[3] = load(x, y)[3];
+        }
+        premultiply(color)
+    })
+}
+
+fn displacement_map(source: &PixelBuffer, filter: &DisplacementMapFilter) -> PixelBuffer {
+    let Some(map) = &filter.map_bitmap else {
+        return source.clone();
+    };
+    let map = as_bitmap(map).pixels();
+    let (width, height) = (source.width() as f32, source.height() as f32);
+    let (map_width, map_height) = (map.width() as f32, map.height() as f32);
+    let color = color_to_rgba(filter.color);
+    let component = |map: Rgba, component: u8| match component {
+        1 => map[0] * 255.0,
+        2 => map[1] * 255.0,
+        4 => map[2] * 255.0,
+        8 => map[3] * 255.0,
+        // This means zero displacement.
+        _ => 128.0,
+    };
+    let scale_x = filter.viewscale_x * filter.scale_x;
+    let scale_y = filter.viewscale_y * filter.scale_y;
+
+    map_pixels(source, |x, y| {
+        let (source_x, source_y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let map_u = (source_x - filter.map_point.0 as f32) / filter.viewscale_x / map_width;
+        let map_v = (source_y - filter.map_point.1 as f32) / filter.viewscale_y / map_height;
+        let map_color = if (0.0..=1.0).contains(&map_u) && (0.0..=1.0).contains(&map_v) {
+            map.sample(map_u, map_v, false, false)
+        } else {
+            [0.5; 4]
+        };
+
+        let displaced_x =
+            source_x + (component(map_color, filter.component_x) - 128.0) * scale_x / 256.0;
+        let displaced_y =
+            source_y + (component(map_color, filter.component_y) - 128.0) * scale_y / 256.0;
+        let (mut u, mut v) = (displaced_x / width, displaced_y / height);
+        let out_of_bounds = !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v);
+
+        match filter.mode {
+            // Wrapping is taken care of by sampling with repetition.
+            DisplacementMapFilterMode::Wrap => {}
+            DisplacementMapFilterMode::Clamp => {
+                u = u.clamp(0.0, 1.0);
+                v = v.clamp(0.0, 1.0);
+            }
+            DisplacementMapFilterMode::Ignore if out_of_bounds => {
+                u = source_x / width;
+                v = source_y / height;
+            }
+            DisplacementMapFilterMode::Color if out_of_bounds => return color,
+            DisplacementMapFilterMode::Ignore | DisplacementMapFilterMode::Color => {}
+        }
+        source.sample(u, v, true, true)
+    })
+}
+
+fn shader(source: &PixelBuffer, filter: &ShaderFilter) -> PixelBuffer {
+    let mut arguments = interpreter_arguments(&filter.shader_args);
+    for argument in &mut arguments {
+        if let PixelBenderShaderArgument::ImageInput { texture, .. } = argument {
+            *texture = Some(source.to_shader_input());
+            // Only bind the first input from the source texture
+            break;
+        }
+    }
+
+    let (width, height) = (source.width(), source.height());
+    match run_shader(filter.shader.0.parsed_shader(), &arguments, width, height) {
+        Ok(pixels) => PixelBuffer::from_shader_output(width, height, pixels),
+        Err(e) => {
+            tracing::error!("Failed to run Pixel Bender shader filter: {e}");
+            PixelBuffer::new(width, height)
+        }
+    }
+}
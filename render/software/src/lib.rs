@@ -0,0 +1,18 @@
+//! A render backend that draws everything on the CPU.
+//!
+//! It is much slower than the GPU backends, but it doesn't need any graphics device and
+//! produces the same output on every machine, which makes it suitable for headless testing.
+
+// Bitmaps and meshes are only ever used from the thread that owns the renderer.
+#![allow(clippy::arc_with_non_send_sync)]
+
+mod backend;
+mod bitmap;
+mod blend;
+mod buffer;
+mod commands;
+mod filters;
+mod mesh;
+mod raster;
+
+pub use backend::SoftwareRenderBackend;
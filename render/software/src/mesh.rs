@@ -0,0 +1,265 @@
+use crate::buffer::{mix, Rgba};
+use ruffle_render::backend::{RenderBackend, ShapeHandle, ShapeHandleImpl};
+use ruffle_render::bitmap::{BitmapHandle, BitmapSource};
+use ruffle_render::shape_utils::GradientType;
+use ruffle_render::tessellator::{
+    DrawType as TessDrawType, Gradient as TessGradient, Mesh as TessMesh, Vertex,
+};
+use swf::{GradientInterpolation, GradientSpread};
+
+/// The number of colors that a gradient is sampled into, matching the GPU backends.
+const GRADIENT_SIZE: usize = 256;
+
+#[derive(Debug)]
+pub struct Mesh {
+    pub draws: Vec<Draw>,
+    pub gradients: Vec<Gradient>,
+}
+
+impl ShapeHandleImpl for Mesh {}
+
+pub fn as_mesh(handle: &ShapeHandle) -> &Mesh {
+    handle
+        .0
+        .downcast_ref::<Mesh>()
+        .expect("Shape handle must be a software mesh")
+}
+
+impl Mesh {
+    pub fn new(
+        mesh: TessMesh,
+        bitmap_source: &dyn BitmapSource,
+        backend: &mut dyn RenderBackend,
+    ) -> Self {
+        let mut draws = Vec::with_capacity(mesh.draws.len());
+        for draw in mesh.draws {
+            let draw_type = match draw.draw_type {
+                TessDrawType::Color => DrawType::Color,
+                TessDrawType::Gradient { matrix, gradient } => {
+                    DrawType::Gradient { matrix, gradient }
+                }
+                TessDrawType::Bitmap(bitmap) => {
+                    let Some(handle) = bitmap_source.bitmap_handle(bitmap.bitmap_id, backend)
+                    else {
+                        tracing::warn!("Couldn't find bitmap {} for shape", bitmap.bitmap_id);
+                        continue;
+                    };
+                    DrawType::Bitmap {
+                        matrix: bitmap.matrix,
+                        bitmap: handle,
+                        is_smoothed: bitmap.is_smoothed,
+                        is_repeating: bitmap.is_repeating,
+                    }
+                }
+                TessDrawType::TexturedTriangles(triangles) => {
+                    let Some(handle) = bitmap_source.bitmap_handle(triangles.bitmap_id, backend)
+                    else {
+                        tracing::warn!("Couldn't find bitmap {} for shape", triangles.bitmap_id);
+                        continue;
+                    };
+                    DrawType::TexturedTriangles {
+                        bitmap: handle,
+                        is_smoothed: triangles.is_smoothed,
+                        is_repeating: triangles.is_repeating,
+                        uvts: triangles.uvts,
+                    }
+                }
+            };
+            draws.push(Draw {
+                draw_type,
+                vertices: draw.vertices,
+                indices: draw.indices,
+                mask_index_count: draw.mask_index_count,
+            });
+        }
+
+        Self {
+            draws,
+            gradients: mesh.gradients.into_iter().map(Gradient::new).collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Draw {
+    pub draw_type: DrawType,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub mask_index_count: u32,
+}
+
+#[derive(Debug)]
+pub enum DrawType {
+    Color,
+    Gradient {
+        /// Maps object space (in pixels) to the gradient square, as a column-major 3x3 matrix.
+        matrix: [[f32; 3]; 3],
+        gradient: usize,
+    },
+    Bitmap {
+        /// Maps object space (in pixels) to texture coordinates, as a column-major 3x3 matrix.
+        matrix: [[f32; 3]; 3],
+        bitmap: BitmapHandle,
+        is_smoothed: bool,
+        is_repeating: bool,
+    },
+    TexturedTriangles {
+        bitmap: BitmapHandle,
+        is_smoothed: bool,
+        is_repeating: bool,
+        uvts: Vec<[f32; 3]>,
+    },
+}
+
+/// Applies a column-major 3x3 texture matrix to a point.
+pub fn transform_point(matrix: &[[f32; 3]; 3], x: f32, y: f32) -> (f32, f32) {
+    (
+        matrix[0][0] * x + matrix[1][0] * y + matrix[2][0],
+        matrix[0][1] * x + matrix[1][1] * y + matrix[2][1],
+    )
+}
+
+#[derive(Debug)]
+pub struct Gradient {
+    /// The straight alpha colors of the gradient, in the same 8-bit precision as a GPU texture.
+    colors: Vec<Rgba>,
+    gradient_type: GradientType,
+    repeat_mode: GradientSpread,
+    focal_point: f32,
+    linear_rgb: bool,
+}
+
+impl Gradient {
+    fn new(gradient: TessGradient) -> Self {
+        let linear_rgb = gradient.interpolation == GradientInterpolation::LinearRgb;
+        let mut colors = vec![[0.0; 4]; GRADIENT_SIZE];
+        if !gradient.records.is_empty() {
+            let convert = |c: u8| {
+                if linear_rgb {
+                    srgb_to_linear(f32::from(c) / 255.0) * 255.0
+                } else {
+                    f32::from(c)
+                }
+            };
+            let lerp = |a: f32, b: f32, t: f32| (a + (b - a) * t) as u8;
+
+            for (t, color) in colors.iter_mut().enumerate() {
+                let mut last = 0;
+                let mut next = 0;
+                for (i, record) in gradient.records.iter().enumerate().rev() {
+                    if (record.ratio as usize) < t {
+                        last = i;
+                        next = (i + 1).min(gradient.records.len() - 1);
+                        break;
+                    }
+                }
+
+                let last_record = &gradient.records[last];
+                let next_record = &gradient.records[next];
+                let a = if next == last {
+                    // This happens before the first gradient record, or after the last one.
+                    0.0
+                } else {
+                    (t as f32 - f32::from(last_record.ratio))
+                        / (f32::from(next_record.ratio) - f32::from(last_record.ratio))
+                };
+
+                let (from, to) = (last_record.color, next_record.color);
+                *color = [
+                    lerp(convert(from.r), convert(to.r), a),
+                    lerp(convert(from.g), convert(to.g), a),
+                    lerp(convert(from.b), convert(to.b), a),
+                    lerp(f32::from(from.a), f32::from(to.a), a),
+                ]
+                .map(|c| f32::from(c) / 255.0);
+            }
+        }
+
+        Self {
+            colors,
+            gradient_type: gradient.gradient_type,
+            repeat_mode: gradient.repeat_mode,
+            focal_point: gradient.focal_point.to_f32().clamp(-0.98, 0.98),
+            linear_rgb,
+        }
+    }
+
+    /// Gets the straight alpha color of the gradient at the given gradient square coordinates.
+    pub fn sample(&self, u: f32, v: f32) -> Rgba {
+        let mut t = self.find_t(u, v);
+        match self.repeat_mode {
+            GradientSpread::Pad => t = t.clamp(0.0, 1.0),
+            GradientSpread::Reflect => {
+                t = t.abs();
+                if (t as i32) & 1 == 0 {
+                    t = fract(t);
+                } else {
+                    t = 1.0 - fract(t);
+                }
+            }
+            GradientSpread::Repeat => t = fract(t),
+        }
+
+        // Linearly filtered and clamped, like the 1D texture of the GPU backends.
+        let x = t * GRADIENT_SIZE as f32 - 0.5;
+        let x0 = x.floor();
+        let index = |i: f32| (i as i32).clamp(0, GRADIENT_SIZE as i32 - 1) as usize;
+        let color = mix(self.colors[index(x0)], self.colors[index(x0 + 1.0)], x - x0);
+
+        if self.linear_rgb {
+            linear_to_srgb(color)
+        } else {
+            color
+        }
+    }
+
+    fn find_t(&self, u: f32, v: f32) -> f32 {
+        match self.gradient_type {
+            GradientType::Linear => u,
+            GradientType::Radial => {
+                let (x, y) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+                (x * x + y * y).sqrt()
+            }
+            GradientType::Focal => {
+                let focal_point = self.focal_point;
+                let (x, y) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+                let (dx, dy) = (focal_point - x, -y);
+                let l = (dx * dx + dy * dy).sqrt();
+                let (dx, dy) = (dx / l, dy / l);
+                l / ((1.0 - focal_point * focal_point * dy * dy).sqrt() + focal_point * dx)
+            }
+        }
+    }
+}
+
+fn fract(value: f32) -> f32 {
+    value - value.floor()
+}
+
+fn srgb_to_linear(color: f32) -> f32 {
+    if color <= 0.04045 {
+        color / 12.92
+    } else {
+        f32::powf((color + 0.055) / 1.055, 2.4)
+    }
+}
+
+/// Converts the color channels of a gradient color back to sRGB, as the GPU backends do.
+fn linear_to_srgb(color: Rgba) -> Rgba {
+    let alpha = color[3];
+    let convert = |c: f32| {
+        let c = if alpha > 0.0 { c / alpha } else { c };
+        let c = if c >= 0.0031308 {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        } else {
+            12.92 * c
+        };
+        c * alpha
+    };
+    [
+        convert(color[0]),
+        convert(color[1]),
+        convert(color[2]),
+        alpha,
+    ]
+}
@@ -0,0 +1,413 @@
+use crate::blend::BlendFunction;
+use crate::buffer::{quantize, PixelBuffer, Rgba};
+
+/// The standard multisample positions, in 1/16ths of a pixel from the pixel center.
+fn sample_positions(sample_count: u32) -> &'static [(i8, i8)] {
+    match sample_count {
+        0 | 1 => &[(0, 0)],
+        2 => &[(4, 4), (-4, -4)],
+        3 | 4 => &[(-2, -6), (6, -2), (-6, 2), (2, 6)],
+        5..=8 => &[
+            (1, -3),
+            (-1, 3),
+            (5, 1),
+            (-3, -5),
+            (-5, 5),
+            (-7, -1),
+            (3, 7),
+            (7, -7),
+        ],
+        _ => &[
+            (1, 1),
+            (-1, -3),
+            (-3, 2),
+            (4, -1),
+            (-5, -2),
+            (2, 5),
+            (5, 3),
+            (3, -5),
+            (-2, 6),
+            (0, -7),
+            (-4, -6),
+            (-6, 4),
+            (-8, 0),
+            (7, -4),
+            (6, 7),
+            (-7, -8),
+        ],
+    }
+}
+
+/// What drawing currently does to the stencil and color of a target.
+/// This mirrors the stencil states that the GPU backends use to implement masks.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum MaskState {
+    NoMask,
+    DrawMaskStencil,
+    DrawMaskedContent,
+    ClearMaskStencil,
+}
+
+/// A multisampled color and stencil buffer that primitives are rasterized into.
+///
+/// Every sample is stored with the precision of an 8-bit texture,
+/// and samples are averaged together when the target is resolved.
+pub struct RenderTarget {
+    width: u32,
+    height: u32,
+    positions: &'static [(i8, i8)],
+    colors: Vec<Rgba>,
+    stencil: Vec<u8>,
+    mask_state: MaskState,
+    num_masks: u32,
+}
+
+impl RenderTarget {
+    pub fn new(width: u32, height: u32, sample_count: u32, clear: Rgba) -> Self {
+        let positions = sample_positions(sample_count);
+        let len = width as usize * height as usize * positions.len();
+        Self {
+            width,
+            height,
+            positions,
+            colors: vec![clear.map(quantize); len],
+            stencil: vec![0; len],
+            mask_state: MaskState::NoMask,
+            num_masks: 0,
+        }
+    }
+
+    /// Creates a target that starts out with the contents of `buffer`.
+    pub fn from_buffer(buffer: &PixelBuffer, sample_count: u32) -> Self {
+        let mut target = Self::new(buffer.width(), buffer.height(), sample_count, [0.0; 4]);
+        for y in 0..buffer.height() {
+            for x in 0..buffer.width() {
+                target.set_pixel(x, y, buffer.get(x, y));
+            }
+        }
+        target
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.positions.len() as u32
+    }
+
+    /// Averages the samples of every pixel.
+    pub fn resolve(&self) -> PixelBuffer {
+        let samples = self.positions.len();
+        let mut buffer = PixelBuffer::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let start = self.sample_index(x, y, 0);
+                let mut sum = [0.0; 4];
+                for color in &self.colors[start..start + samples] {
+                    for (sum, component) in sum.iter_mut().zip(color) {
+                        *sum += component;
+                    }
+                }
+                buffer.set(x, y, sum.map(|c| quantize(c / samples as f32)));
+            }
+        }
+        buffer
+    }
+
+    /// Overwrites every sample of a pixel, ignoring any masks.
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Rgba) {
+        let start = self.sample_index(x, y, 0);
+        let samples = self.positions.len();
+        self.colors[start..start + samples].fill(color.map(quantize));
+    }
+
+    pub fn push_mask(&mut self) {
+        self.num_masks += 1;
+        self.mask_state = MaskState::DrawMaskStencil;
+    }
+
+    pub fn activate_mask(&mut self) {
+        self.mask_state = MaskState::DrawMaskedContent;
+    }
+
+    pub fn deactivate_mask(&mut self) {
+        self.mask_state = MaskState::ClearMaskStencil;
+    }
+
+    pub fn pop_mask(&mut self) {
+        self.num_masks = self.num_masks.saturating_sub(1);
+        self.mask_state = if self.num_masks == 0 {
+            MaskState::NoMask
+        } else {
+            MaskState::DrawMaskedContent
+        };
+    }
+
+    /// Whether drawing currently writes to the color buffer, as opposed to only the stencil buffer.
+    pub fn writes_color(&self) -> bool {
+        matches!(
+            self.mask_state,
+            MaskState::NoMask | MaskState::DrawMaskedContent
+        )
+    }
+
+    fn sample_index(&self, x: u32, y: u32, sample: usize) -> usize {
+        (y as usize * self.width as usize + x as usize) * self.positions.len() + sample
+    }
+
+    /// Applies the stencil operation of the current mask state to a sample,
+    /// returning whether its color may be written.
+    fn stencil_test(&mut self, index: usize) -> bool {
+        let stencil = &mut self.stencil[index];
+        match self.mask_state {
+            MaskState::NoMask => true,
+            MaskState::DrawMaskStencil => {
+                if u32::from(*stencil) + 1 == self.num_masks {
+                    *stencil = stencil.saturating_add(1);
+                }
+                false
+            }
+            MaskState::DrawMaskedContent => u32::from(*stencil) == self.num_masks,
+            MaskState::ClearMaskStencil => {
+                if u32::from(*stencil) == self.num_masks {
+                    *stencil = stencil.saturating_sub(1);
+                }
+                false
+            }
+        }
+    }
+
+    fn write_sample(&mut self, index: usize, src: Rgba, blend: BlendFunction) {
+        if self.stencil_test(index) {
+            if let Some(color) = blend(src, self.colors[index]) {
+                self.colors[index] = color.map(quantize);
+            }
+        }
+    }
+
+    /// Rasterizes a triangle.
+    ///
+    /// `shade` is called once for every pixel that has any sample covered, with the barycentric
+    /// coordinates of the pixel center, and returns the premultiplied color of that pixel.
+    pub fn fill_triangle(
+        &mut self,
+        points: [[f32; 2]; 3],
+        blend: BlendFunction,
+        shade: &mut dyn FnMut([f32; 3]) -> Rgba,
+    ) {
+        if points.iter().flatten().any(|c| !c.is_finite()) {
+            return;
+        }
+        let [a, b, c] = points;
+        let area = edge(a, b, c);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        // Orient every triangle the same way, so that the interior is always on the positive side.
+        let sign = area.signum();
+        let area = area.abs();
+        let edges = [(b, c), (c, a), (a, b)];
+        // Samples exactly on an edge belong to only one of the two triangles sharing that edge.
+        let inclusive = edges.map(|(from, to)| {
+            let dx = (to[0] - from[0]) * sign;
+            let dy = (to[1] - from[1]) * sign;
+            dy < 0.0 || (dy == 0.0 && dx > 0.0)
+        });
+
+        let min_x = a[0].min(b[0]).min(c[0]).floor().max(0.0);
+        let min_y = a[1].min(b[1]).min(c[1]).floor().max(0.0);
+        let max_x = (a[0].max(b[0]).max(c[0]).ceil() + 1.0).min(self.width as f32);
+        let max_y = (a[1].max(b[1]).max(c[1]).ceil() + 1.0).min(self.height as f32);
+        if min_x >= max_x || min_y >= max_y {
+            return;
+        }
+
+        let writes_color = self.writes_color();
+        for y in min_y as u32..max_y as u32 {
+            for x in min_x as u32..max_x as u32 {
+                let mut covered = 0u32;
+                for (sample, (ox, oy)) in self.positions.iter().enumerate() {
+                    let p = [
+                        x as f32 + 0.5 + f32::from(*ox) / 16.0,
+                        y as f32 + 0.5 + f32::from(*oy) / 16.0,
+                    ];
+                    let inside = edges.iter().zip(inclusive).all(|(&(from, to), inclusive)| {
+                        let e = edge(from, to, p) * sign;
+                        e > 0.0 || (e == 0.0 && inclusive)
+                    });
+                    if inside {
+                        covered |= 1 << sample;
+                    }
+                }
+                if covered == 0 {
+                    continue;
+                }
+
+                let color = if writes_color {
+                    let p = [x as f32 + 0.5, y as f32 + 0.5];
+                    shade(edges.map(|(from, to)| edge(from, to, p) * sign / area))
+                } else {
+                    [0.0; 4]
+                };
+                for sample in 0..self.positions.len() {
+                    if covered & (1 << sample) != 0 {
+                        let index = self.sample_index(x, y, sample);
+                        self.write_sample(index, color, blend);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Blends every sample of `layer` (which must be the same size as this target) onto this target.
+    pub fn composite(&mut self, layer: &RenderTarget, blend: BlendFunction) {
+        debug_assert_eq!(self.colors.len(), layer.colors.len());
+        let writes_color = self.writes_color();
+        for (index, &src) in layer.colors.iter().enumerate() {
+            // When drawing a mask, the visible parts of the layer are what gets added to it.
+            if writes_color || src[3] > 0.0 {
+                self.write_sample(index, src, blend);
+            }
+        }
+    }
+}
+
+/// The signed area of the parallelogram spanned by `from -> to` and `from -> p`.
+fn edge(from: [f32; 2], to: [f32; 2], p: [f32; 2]) -> f32 {
+    (to[0] - from[0]) * (p[1] - from[1]) - (to[1] - from[1]) * (p[0] - from[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenderTarget;
+    use crate::blend::blend_function;
+    use crate::buffer::quantize;
+    use swf::BlendMode;
+
+    const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+    fn fill_rect(target: &mut RenderTarget, rect: [f32; 4], mode: BlendMode, color: [f32; 4]) {
+        let [x0, y0, x1, y1] = rect;
+        for points in [
+            [[x0, y0], [x1, y0], [x1, y1]],
+            [[x0, y0], [x1, y1], [x0, y1]],
+        ] {
+            target.fill_triangle(points, blend_function(mode), &mut |_| color);
+        }
+    }
+
+    #[test]
+    fn shared_edges_are_filled_once() {
+        let mut target = RenderTarget::new(6, 6, 1, [0.0; 4]);
+        fill_rect(
+            &mut target,
+            [1.0, 1.0, 5.0, 5.0],
+            BlendMode::Add,
+            [0.2, 0.0, 0.0, 0.2],
+        );
+
+        let buffer = target.resolve();
+        for y in 0..6 {
+            for x in 0..6 {
+                let expected = if (1..5).contains(&x) && (1..5).contains(&y) {
+                    quantize(0.2)
+                } else {
+                    0.0
+                };
+                assert_eq!(buffer.get(x, y)[0], expected, "pixel {x}, {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn pixel_centers_decide_coverage() {
+        let mut target = RenderTarget::new(4, 1, 1, [0.0; 4]);
+        fill_rect(&mut target, [0.4, 0.0, 2.5, 1.0], BlendMode::Normal, RED);
+
+        let buffer = target.resolve();
+        assert_eq!(buffer.get(0, 0), RED);
+        assert_eq!(buffer.get(1, 0), RED);
+        // The center of the third pixel is exactly on the right edge, which isn't inclusive.
+        assert_eq!(buffer.get(2, 0), [0.0; 4]);
+        assert_eq!(buffer.get(3, 0), [0.0; 4]);
+    }
+
+    #[test]
+    fn partial_coverage_is_resolved() {
+        let mut target = RenderTarget::new(1, 1, 4, [0.0; 4]);
+        fill_rect(&mut target, [0.0, 0.0, 0.5, 1.0], BlendMode::Normal, RED);
+
+        assert_eq!(target.sample_count(), 4);
+        // Two of the four samples are left of the pixel center.
+        let half = quantize(0.5);
+        assert_eq!(target.resolve().get(0, 0), [half, 0.0, 0.0, half]);
+    }
+
+    #[test]
+    fn degenerate_triangles_are_skipped() {
+        let mut target = RenderTarget::new(2, 2, 1, [0.0; 4]);
+        let mut shaded = false;
+        for points in [
+            [[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]],
+            [[0.0, 0.0], [f32::NAN, 1.0], [2.0, 0.0]],
+        ] {
+            target.fill_triangle(points, blend_function(BlendMode::Normal), &mut |_| {
+                shaded = true;
+                RED
+            });
+        }
+        assert!(!shaded);
+    }
+
+    #[test]
+    fn masks_clip_content() {
+        let mut target = RenderTarget::new(4, 1, 1, [0.0; 4]);
+        target.push_mask();
+        assert!(!target.writes_color());
+        fill_rect(&mut target, [1.0, 0.0, 3.0, 1.0], BlendMode::Normal, RED);
+        target.activate_mask();
+        fill_rect(
+            &mut target,
+            [0.0, 0.0, 4.0, 1.0],
+            BlendMode::Normal,
+            [0.0, 0.0, 1.0, 1.0],
+        );
+        target.deactivate_mask();
+        fill_rect(&mut target, [1.0, 0.0, 3.0, 1.0], BlendMode::Normal, RED);
+        target.pop_mask();
+        assert!(target.writes_color());
+
+        let buffer = target.resolve();
+        assert_eq!(buffer.get(0, 0), [0.0; 4]);
+        assert_eq!(buffer.get(1, 0), [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(buffer.get(2, 0), [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(buffer.get(3, 0), [0.0; 4]);
+
+        // Once the mask is popped, nothing is clipped anymore.
+        fill_rect(&mut target, [0.0, 0.0, 4.0, 1.0], BlendMode::Normal, RED);
+        assert_eq!(target.resolve().get(0, 0), RED);
+    }
+
+    #[test]
+    fn nested_masks_intersect() {
+        let mut target = RenderTarget::new(4, 1, 1, [0.0; 4]);
+        target.push_mask();
+        fill_rect(&mut target, [0.0, 0.0, 3.0, 1.0], BlendMode::Normal, RED);
+        target.activate_mask();
+        target.push_mask();
+        fill_rect(&mut target, [1.0, 0.0, 4.0, 1.0], BlendMode::Normal, RED);
+        target.activate_mask();
+        fill_rect(&mut target, [0.0, 0.0, 4.0, 1.0], BlendMode::Normal, RED);
+
+        let buffer = target.resolve();
+        assert_eq!(buffer.get(0, 0), [0.0; 4]);
+        assert_eq!(buffer.get(1, 0), RED);
+        assert_eq!(buffer.get(2, 0), RED);
+        assert_eq!(buffer.get(3, 0), [0.0; 4]);
+    }
+}
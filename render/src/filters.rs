@@ -80,6 +80,8 @@ impl Filter {
             Filter::GlowFilter(filter) => filter.calculate_dest_rect(source_rect),
             Filter::DropShadowFilter(filter) => filter.calculate_dest_rect(source_rect),
             Filter::BevelFilter(filter) => filter.calculate_dest_rect(source_rect),
+            Filter::GradientGlowFilter(filter) => filter.calculate_dest_rect(source_rect),
+            Filter::GradientBevelFilter(filter) => filter.calculate_dest_rect(source_rect),
             Filter::DisplacementMapFilter(filter) => filter.calculate_dest_rect(source_rect),
             _ => source_rect,
         }
@@ -201,3 +203,83 @@ impl Default for DisplacementMapFilter {
         }
     }
 }
+
+/// Samples the gradient of a `GradientGlowFilter` or `GradientBevelFilter` at every ratio,
+/// as premultiplied RGBA colors with components in the range 0.0 to 1.0.
+pub fn gradient_filter_colors(records: &[swf::GradientRecord]) -> [[f32; 4]; 256] {
+    let mut colors = [[0.0; 4]; 256];
+    let (Some(first), Some(last)) = (records.first(), records.last()) else {
+        return colors;
+    };
+
+    let to_rgba = |color: Color| {
+        [
+            f32::from(color.r) / 255.0,
+            f32::from(color.g) / 255.0,
+            f32::from(color.b) / 255.0,
+            f32::from(color.a) / 255.0,
+        ]
+    };
+
+    for (ratio, output) in colors.iter_mut().enumerate() {
+        let ratio = ratio as u8;
+        let [r, g, b, a] = if ratio <= first.ratio {
+            to_rgba(first.color)
+        } else if ratio >= last.ratio {
+            to_rgba(last.color)
+        } else {
+            let index = records
+                .windows(2)
+                .position(|pair| ratio <= pair[1].ratio)
+                .unwrap_or_default();
+            let (start, end) = (&records[index], &records[index + 1]);
+            let t = if end.ratio > start.ratio {
+                f32::from(ratio - start.ratio) / f32::from(end.ratio - start.ratio)
+            } else {
+                1.0
+            };
+            let start = to_rgba(start.color);
+            let end = to_rgba(end.color);
+            std::array::from_fn(|i| start[i] + (end[i] - start[i]) * t)
+        };
+        *output = [r * a, g * a, b * a, a];
+    }
+
+    colors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::gradient_filter_colors;
+    use swf::{Color, GradientRecord};
+
+    #[test]
+    fn gradient_filter_colors_interpolate() {
+        let colors = gradient_filter_colors(&[
+            GradientRecord {
+                ratio: 0,
+                color: Color::from_rgba(0x00000000),
+            },
+            GradientRecord {
+                ratio: 128,
+                color: Color::from_rgba(0xFF0000FF),
+            },
+            GradientRecord {
+                ratio: 255,
+                color: Color::from_rgba(0xFF00FF00),
+            },
+        ]);
+
+        assert_eq!(colors[0], [0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(colors[64], [0.0, 0.0, 0.25, 0.5]);
+        assert_eq!(colors[128], [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(colors[255], [0.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn gradient_filter_colors_without_records() {
+        assert!(gradient_filter_colors(&[])
+            .iter()
+            .all(|color| *color == [0.0; 4]));
+    }
+}
@@ -14,6 +14,7 @@ pub mod pixel_bender;
 pub mod renderdoc;
 pub mod shader_source;
 pub mod shape_utils;
+pub mod svg;
 pub mod transform;
 pub mod utils;
 
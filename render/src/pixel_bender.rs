@@ -1,6 +1,7 @@
 //! Pixel bender bytecode parsing code.
 //! This is heavily based on https://github.com/jamesward/pbjas and https://github.com/HaxeFoundation/format/tree/master/format/pbj
 
+pub mod interpreter;
 #[cfg(test)]
 mod tests;
 
@@ -258,6 +259,26 @@ pub enum Operation {
     },
 }
 
+impl Operation {
+    /// The registers this operation reads or writes.
+    fn registers(&self) -> Vec<&PixelBenderReg> {
+        match self {
+            Operation::Normal { dst, src, .. }
+            | Operation::SampleNearest { dst, src, .. }
+            | Operation::SampleLinear { dst, src, .. } => vec![dst, src],
+            Operation::LoadInt { dst, .. } | Operation::LoadFloat { dst, .. } => vec![dst],
+            Operation::If { src } => vec![src],
+            Operation::Select {
+                src1,
+                src2,
+                condition,
+                dst,
+            } => vec![src1, src2, condition, dst],
+            Operation::Nop | Operation::Else | Operation::EndIf => vec![],
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PixelBenderShaderArgument<'a> {
     ImageInput {
@@ -321,6 +342,66 @@ pub struct PixelBenderShader {
     pub operations: Vec<Operation>,
 }
 
+impl PixelBenderShader {
+    /// Returns a copy of this shader whose `outCoord()` is moved by `offset`,
+    /// so that it can be evaluated over an area that doesn't start at the origin.
+    pub fn with_out_coord_offset(&self, offset: (f32, f32)) -> Self {
+        let mut shader = self.clone();
+        let out_coord = self.params.iter().find_map(|param| match param {
+            PixelBenderParam::Normal {
+                qualifier: PixelBenderParamQualifier::Input,
+                reg,
+                name,
+                ..
+            } if name == OUT_COORD_NAME => Some(reg),
+            _ => None,
+        });
+        let Some(out_coord) = out_coord else {
+            return shader;
+        };
+        let [x, y, ..] = out_coord.channels[..] else {
+            return shader;
+        };
+
+        // Matrices span several registers, so leave room after every register in use.
+        let param_regs = self.params.iter().filter_map(|param| match param {
+            PixelBenderParam::Normal { reg, .. } => Some(reg),
+            PixelBenderParam::Texture { .. } => None,
+        });
+        let index = param_regs
+            .chain(self.operations.iter().flat_map(Operation::registers))
+            .filter(|reg| reg.kind == PixelBenderRegKind::Float)
+            .map(|reg| reg.index + 4)
+            .max()
+            .unwrap_or_default();
+        let offset_reg = |channels: &[PixelBenderRegChannel]| PixelBenderReg {
+            index,
+            channels: channels.to_vec(),
+            kind: PixelBenderRegKind::Float,
+        };
+
+        shader.operations.splice(
+            0..0,
+            [
+                Operation::LoadFloat {
+                    dst: offset_reg(&[x]),
+                    val: offset.0,
+                },
+                Operation::LoadFloat {
+                    dst: offset_reg(&[y]),
+                    val: offset.1,
+                },
+                Operation::Normal {
+                    opcode: Opcode::Add,
+                    dst: out_coord.clone(),
+                    src: offset_reg(&out_coord.channels),
+                },
+            ],
+        );
+        shader
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum PixelBenderParam {
     Normal {
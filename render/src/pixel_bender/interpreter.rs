@@ -0,0 +1,609 @@
+//! A CPU interpreter for Pixel Bender shaders, for backends that can't compile them to GPU shaders.
+//!
+//! This mirrors the semantics of `naga-pixelbender`: every register is a 4-component vector,
+//! sources are swizzled, and the destination mask selects the channels that get written.
+
+use std::sync::Arc;
+
+use crate::backend::{PixelBenderOutput, PixelBenderTarget};
+use crate::error::Error;
+use crate::pixel_bender::{
+    ImageInputTexture, Opcode, Operation, PixelBenderParam, PixelBenderParamQualifier,
+    PixelBenderReg, PixelBenderRegChannel, PixelBenderRegKind, PixelBenderShader,
+    PixelBenderShaderArgument, PixelBenderShaderHandle, PixelBenderShaderImpl, PixelBenderType,
+    OUT_COORD_NAME,
+};
+
+/// A shader that is run by this interpreter rather than compiled for a GPU.
+#[derive(Debug)]
+pub struct InterpretedShader(PixelBenderShader);
+
+impl PixelBenderShaderImpl for InterpretedShader {
+    fn parsed_shader(&self) -> &PixelBenderShader {
+        &self.0
+    }
+}
+
+impl InterpretedShader {
+    pub fn new_handle(shader: PixelBenderShader) -> PixelBenderShaderHandle {
+        PixelBenderShaderHandle(Arc::new(Self(shader)))
+    }
+}
+
+/// Runs a shader for `RenderBackend::run_pixelbender_shader`.
+///
+/// Only `PixelBenderTarget::Bytes` is supported, as writing to a bitmap requires the backend.
+pub fn run_pixelbender_shader(
+    shader: &PixelBenderShaderHandle,
+    arguments: &[PixelBenderShaderArgument],
+    target: &PixelBenderTarget,
+) -> Result<PixelBenderOutput, Error> {
+    let PixelBenderTarget::Bytes { width, height } = *target else {
+        return Err(Error::Unimplemented(
+            "Pixel Bender shader with a bitmap target in software".into(),
+        ));
+    };
+
+    let shader = shader.0.parsed_shader();
+    let channels = output_channels(shader)?;
+    let pixels = run_shader(shader, arguments, width, height)?;
+
+    let mut bytes = Vec::with_capacity(pixels.len() * channels * std::mem::size_of::<f32>());
+    for pixel in pixels {
+        for value in &pixel[..channels] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    Ok(PixelBenderOutput::Bytes(bytes))
+}
+
+/// Evaluates a shader over a `width` x `height` area, returning the RGBA output of every pixel.
+///
+/// Shaders with a `float3` output get an alpha of 1.
+pub fn run_shader(
+    shader: &PixelBenderShader,
+    arguments: &[PixelBenderShaderArgument],
+    width: u32,
+    height: u32,
+) -> Result<Vec<[f32; 4]>, Error> {
+    let output = output_reg(shader)?;
+    let out_coord = shader.params.iter().find_map(|param| match param {
+        PixelBenderParam::Normal {
+            qualifier: PixelBenderParamQualifier::Input,
+            reg,
+            name,
+            ..
+        } if name == OUT_COORD_NAME => Some(reg),
+        _ => None,
+    });
+
+    let mut initial = Registers::default();
+    let mut textures = Vec::new();
+    for argument in arguments {
+        match argument {
+            PixelBenderShaderArgument::ValueInput { index, value } => {
+                if let Some(PixelBenderParam::Normal { reg, name, .. }) =
+                    shader.params.get(*index as usize)
+                {
+                    if name != OUT_COORD_NAME {
+                        initial.store_value(reg, value);
+                    }
+                }
+            }
+            PixelBenderShaderArgument::ImageInput { index, texture, .. } => {
+                let index = *index as usize;
+                if index >= textures.len() {
+                    textures.resize_with(index + 1, || None);
+                }
+                textures[index] = texture.as_ref().map(Texture::new).transpose()?;
+            }
+        }
+    }
+
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let mut registers = initial.clone();
+            if let Some(reg) = out_coord {
+                // Pixel Bender coordinates refer to the center of the pixel.
+                registers.store(reg, [x as f32 + 0.5, y as f32 + 0.5, 0.0, 0.0]);
+            }
+            registers.execute(&shader.operations, &textures)?;
+
+            let mut pixel = registers.load(output);
+            if output.channels.len() == 3 {
+                pixel[3] = 1.0;
+            }
+            pixels.push(pixel);
+        }
+    }
+    Ok(pixels)
+}
+
+fn output_reg(shader: &PixelBenderShader) -> Result<&PixelBenderReg, Error> {
+    shader
+        .params
+        .iter()
+        .find_map(|param| match param {
+            PixelBenderParam::Normal {
+                qualifier: PixelBenderParamQualifier::Output,
+                reg,
+                ..
+            } => Some(reg),
+            _ => None,
+        })
+        .ok_or_else(|| Error::Unimplemented("Pixel Bender shader without an output".into()))
+}
+
+fn output_channels(shader: &PixelBenderShader) -> Result<usize, Error> {
+    match output_reg(shader)?.channels.len() {
+        channels @ (3 | 4) => Ok(channels),
+        _ => Err(Error::Unimplemented(
+            "Pixel Bender output that isn't a float3 or float4".into(),
+        )),
+    }
+}
+
+/// An image input, converted to 4 floats per pixel.
+struct Texture {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Texture {
+    fn new(texture: &ImageInputTexture) -> Result<Self, Error> {
+        let ImageInputTexture::Bytes {
+            width,
+            height,
+            channels,
+            bytes,
+        } = texture
+        else {
+            return Err(Error::Unimplemented(
+                "Pixel Bender bitmap inputs in software".into(),
+            ));
+        };
+
+        let channels = *channels as usize;
+        let pixels = bytes
+            .chunks_exact(channels * std::mem::size_of::<f32>())
+            .map(|pixel| {
+                // Missing channels are filled in the same way as a GPU texture would be.
+                let mut value = [0.0, 0.0, 0.0, 1.0];
+                if channels == 3 {
+                    value[3] = 0.0;
+                }
+                for (value, bytes) in value.iter_mut().zip(pixel.chunks_exact(4)) {
+                    *value = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                }
+                value
+            })
+            .collect();
+
+        Ok(Self {
+            width: *width,
+            height: *height,
+            pixels,
+        })
+    }
+
+    fn texel(&self, x: i64, y: i64) -> [f32; 4] {
+        let x = x.clamp(0, self.width as i64 - 1) as usize;
+        let y = y.clamp(0, self.height as i64 - 1) as usize;
+        self.pixels
+            .get(y * self.width as usize + x)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Samples the texture, clamping out-of-range coordinates to the edge (as a `ShaderJob` does).
+    fn sample(&self, x: f32, y: f32, linear: bool) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+        if !linear {
+            return self.texel(x.floor() as i64, y.floor() as i64);
+        }
+
+        let x = x - 0.5;
+        let y = y - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let top = lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), fx);
+        let bottom = lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), fx);
+        lerp(top, bottom, fy)
+    }
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+/// A square matrix of up to 4x4, stored as columns.
+#[derive(Clone, Copy)]
+struct Matrix {
+    size: usize,
+    columns: [[f32; 4]; 4],
+}
+
+impl Matrix {
+    fn mul_vec(&self, vec: [f32; 4]) -> [f32; 4] {
+        let mut result = [0.0; 4];
+        for (column, value) in self.columns[..self.size].iter().zip(vec) {
+            for (result, component) in result[..self.size].iter_mut().zip(column) {
+                *result += component * value;
+            }
+        }
+        result
+    }
+
+    fn vec_mul(&self, vec: [f32; 4]) -> [f32; 4] {
+        let mut result = [0.0; 4];
+        for (result, column) in result[..self.size].iter_mut().zip(&self.columns) {
+            *result = dot(&vec[..self.size], &column[..self.size]);
+        }
+        result
+    }
+
+    fn mul(&self, other: &Matrix) -> Matrix {
+        let mut columns = [[0.0; 4]; 4];
+        for (column, other) in columns[..self.size].iter_mut().zip(&other.columns) {
+            *column = self.mul_vec(*other);
+        }
+        Matrix {
+            size: self.size,
+            columns,
+        }
+    }
+
+    fn zip(&self, other: &Matrix, f: impl Fn(f32, f32) -> f32) -> Matrix {
+        Matrix {
+            size: self.size,
+            columns: std::array::from_fn(|i| {
+                std::array::from_fn(|j| f(self.columns[i][j], other.columns[i][j]))
+            }),
+        }
+    }
+}
+
+fn matrix_size(reg: &PixelBenderReg) -> Option<usize> {
+    match reg.channels.as_slice() {
+        [PixelBenderRegChannel::M2x2] => Some(2),
+        [PixelBenderRegChannel::M3x3] => Some(3),
+        [PixelBenderRegChannel::M4x4] => Some(4),
+        _ => None,
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn bool_to_float(value: bool) -> f32 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn map(value: [f32; 4], f: impl Fn(f32) -> f32) -> [f32; 4] {
+    value.map(f)
+}
+
+fn zip(a: [f32; 4], b: [f32; 4], f: impl Fn(f32, f32) -> f32) -> [f32; 4] {
+    std::array::from_fn(|i| f(a[i], b[i]))
+}
+
+/// The float and int register banks of a single shader invocation.
+///
+/// Int registers hold their values as floats, which is exact for every value a shader can load.
+#[derive(Clone, Default)]
+struct Registers {
+    floats: Vec<[f32; 4]>,
+    ints: Vec<[f32; 4]>,
+}
+
+impl Registers {
+    fn register(&mut self, kind: PixelBenderRegKind, index: u32) -> &mut [f32; 4] {
+        let bank = match kind {
+            PixelBenderRegKind::Float => &mut self.floats,
+            PixelBenderRegKind::Int => &mut self.ints,
+        };
+        let index = index as usize;
+        if index >= bank.len() {
+            bank.resize(index + 1, [0.0; 4]);
+        }
+        &mut bank[index]
+    }
+
+    /// Loads the swizzled channels of a register. Unused components are meaningless.
+    fn load(&mut self, reg: &PixelBenderReg) -> [f32; 4] {
+        let value = *self.register(reg.kind, reg.index);
+        let mut result = [0.0; 4];
+        for (result, channel) in result.iter_mut().zip(&reg.channels) {
+            *result = value[(*channel as usize).min(3)];
+        }
+        result
+    }
+
+    /// Stores the components of `value` into the channels selected by the destination mask.
+    fn store(&mut self, reg: &PixelBenderReg, value: [f32; 4]) {
+        let is_int = reg.kind == PixelBenderRegKind::Int;
+        let register = self.register(reg.kind, reg.index);
+        for (channel, value) in reg.channels.iter().zip(value) {
+            // Stores into int registers convert the value, truncating towards zero.
+            register[(*channel as usize).min(3)] = if is_int { value.trunc() } else { value };
+        }
+    }
+
+    fn load_matrix(&mut self, reg: &PixelBenderReg, size: usize) -> Matrix {
+        let mut columns = [[0.0; 4]; 4];
+        if size == 2 {
+            // A 2x2 matrix is packed into a single register.
+            let value = *self.register(PixelBenderRegKind::Float, reg.index);
+            columns[0] = [value[0], value[1], 0.0, 0.0];
+            columns[1] = [value[2], value[3], 0.0, 0.0];
+        } else {
+            for (i, column) in columns[..size].iter_mut().enumerate() {
+                *column = *self.register(PixelBenderRegKind::Float, reg.index + i as u32);
+            }
+        }
+        Matrix { size, columns }
+    }
+
+    fn store_matrix(&mut self, reg: &PixelBenderReg, matrix: &Matrix) {
+        if matrix.size == 2 {
+            let [a, b, c, d] = [
+                matrix.columns[0][0],
+                matrix.columns[0][1],
+                matrix.columns[1][0],
+                matrix.columns[1][1],
+            ];
+            *self.register(PixelBenderRegKind::Float, reg.index) = [a, b, c, d];
+        } else {
+            for (i, column) in matrix.columns[..matrix.size].iter().enumerate() {
+                let register = self.register(PixelBenderRegKind::Float, reg.index + i as u32);
+                register[..matrix.size].copy_from_slice(&column[..matrix.size]);
+            }
+        }
+    }
+
+    /// Loads a parameter value into its register(s).
+    fn store_value(&mut self, reg: &PixelBenderReg, value: &PixelBenderType) {
+        let vec = |values: &[f32]| -> [f32; 4] {
+            std::array::from_fn(|i| values.get(i).copied().unwrap_or(0.0))
+        };
+        match value {
+            PixelBenderType::TFloat(x) => self.store(reg, vec(&[*x])),
+            PixelBenderType::TFloat2(x, y) => self.store(reg, vec(&[*x, *y])),
+            PixelBenderType::TFloat3(x, y, z) => self.store(reg, vec(&[*x, *y, *z])),
+            PixelBenderType::TFloat4(x, y, z, w) => self.store(reg, [*x, *y, *z, *w]),
+            PixelBenderType::TInt(x) => self.store(reg, vec(&[*x as f32])),
+            PixelBenderType::TInt2(x, y) => self.store(reg, vec(&[*x as f32, *y as f32])),
+            PixelBenderType::TInt3(x, y, z) => {
+                self.store(reg, vec(&[*x as f32, *y as f32, *z as f32]))
+            }
+            PixelBenderType::TInt4(x, y, z, w) => {
+                self.store(reg, [*x as f32, *y as f32, *z as f32, *w as f32])
+            }
+            // Matrix values are given in column-major order, like the registers.
+            PixelBenderType::TFloat2x2(values) => self.store_matrix(
+                reg,
+                &Matrix {
+                    size: 2,
+                    columns: [vec(&values[0..2]), vec(&values[2..4]), [0.0; 4], [0.0; 4]],
+                },
+            ),
+            PixelBenderType::TFloat3x3(values) => self.store_matrix(
+                reg,
+                &Matrix {
+                    size: 3,
+                    columns: [
+                        vec(&values[0..3]),
+                        vec(&values[3..6]),
+                        vec(&values[6..9]),
+                        [0.0; 4],
+                    ],
+                },
+            ),
+            PixelBenderType::TFloat4x4(values) => self.store_matrix(
+                reg,
+                &Matrix {
+                    size: 4,
+                    columns: std::array::from_fn(|i| vec(&values[i * 4..i * 4 + 4])),
+                },
+            ),
+            PixelBenderType::TString(_) => {}
+        }
+    }
+
+    fn execute(
+        &mut self,
+        operations: &[Operation],
+        textures: &[Option<Texture>],
+    ) -> Result<(), Error> {
+        // For every `if` we're inside of: whether the enclosing block runs, and the condition.
+        let mut conditions: Vec<(bool, bool)> = Vec::new();
+        let mut active = true;
+
+        for operation in operations {
+            match operation {
+                Operation::If { src } => {
+                    let condition = self.load(src)[0] != 0.0;
+                    conditions.push((active, condition));
+                    active = active && condition;
+                }
+                Operation::Else => {
+                    if let Some((parent, condition)) = conditions.last() {
+                        active = *parent && !condition;
+                    }
+                }
+                Operation::EndIf => {
+                    if let Some((parent, _)) = conditions.pop() {
+                        active = parent;
+                    }
+                }
+                _ if !active => {}
+                Operation::Nop => {}
+                Operation::LoadFloat { dst, val } => self.store(dst, [*val; 4]),
+                Operation::LoadInt { dst, val } => self.store(dst, [*val as f32; 4]),
+                Operation::SampleNearest { dst, src, tf }
+                | Operation::SampleLinear { dst, src, tf } => {
+                    let coord = self.load(src);
+                    let linear = matches!(operation, Operation::SampleLinear { .. });
+                    let value = match textures.get(*tf as usize) {
+                        Some(Some(texture)) => texture.sample(coord[0], coord[1], linear),
+                        _ => [0.0; 4],
+                    };
+                    self.store(dst, value);
+                }
+                Operation::Select {
+                    src1,
+                    src2,
+                    condition,
+                    dst,
+                } => {
+                    let value = if self.load(condition)[0] != 0.0 {
+                        self.load(src1)
+                    } else {
+                        self.load(src2)
+                    };
+                    self.store(dst, value);
+                }
+                Operation::Normal { opcode, dst, src } => self.execute_normal(*opcode, dst, src)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn execute_normal(
+        &mut self,
+        opcode: Opcode,
+        dst: &PixelBenderReg,
+        src: &PixelBenderReg,
+    ) -> Result<(), Error> {
+        if let Some(size) = matrix_size(src) {
+            let matrix = self.load_matrix(src, size);
+            match (opcode, matrix_size(dst)) {
+                (Opcode::MatVecMul, None) => {
+                    let vec = self.load(dst);
+                    self.store(dst, matrix.mul_vec(vec));
+                }
+                (Opcode::VecMatMul, None) => {
+                    let vec = self.load(dst);
+                    self.store(dst, matrix.vec_mul(vec));
+                }
+                (Opcode::Mov, Some(_)) => self.store_matrix(dst, &matrix),
+                (Opcode::Mul | Opcode::MatMatMul, Some(dst_size)) => {
+                    let left = self.load_matrix(dst, dst_size);
+                    self.store_matrix(dst, &left.mul(&matrix));
+                }
+                (Opcode::Add | Opcode::Sub, Some(dst_size)) => {
+                    let left = self.load_matrix(dst, dst_size);
+                    let result = if opcode == Opcode::Add {
+                        left.zip(&matrix, |a, b| a + b)
+                    } else {
+                        left.zip(&matrix, |a, b| a - b)
+                    };
+                    self.store_matrix(dst, &result);
+                }
+                _ => {
+                    return Err(Error::Unimplemented(
+                        format!("Pixel Bender matrix opcode {opcode:?}").into(),
+                    ))
+                }
+            }
+            return Ok(());
+        }
+
+        let size = src.channels.len();
+        let value = self.load(src);
+        let left = self.load(dst);
+
+        let result = match opcode {
+            Opcode::Nop => return Ok(()),
+            Opcode::Mov => value,
+            Opcode::Add => zip(left, value, |a, b| a + b),
+            Opcode::Sub => zip(left, value, |a, b| a - b),
+            Opcode::Mul => zip(left, value, |a, b| a * b),
+            Opcode::Div => zip(left, value, |a, b| a / b),
+            Opcode::Rcp => map(value, |a| 1.0 / a),
+            Opcode::Atan2 => zip(left, value, f32::atan2),
+            Opcode::Pow => zip(left, value, f32::powf),
+            Opcode::Mod => zip(left, value, |a, b| a % b),
+            Opcode::Min => zip(left, value, f32::min),
+            Opcode::Max => zip(left, value, f32::max),
+            Opcode::Step => zip(left, value, |edge, x| bool_to_float(x >= edge)),
+            Opcode::Sin => map(value, f32::sin),
+            Opcode::Cos => map(value, f32::cos),
+            Opcode::Tan => map(value, f32::tan),
+            Opcode::Asin => map(value, f32::asin),
+            Opcode::Acos => map(value, f32::acos),
+            Opcode::Atan => map(value, f32::atan),
+            Opcode::Exp => map(value, f32::exp),
+            Opcode::Exp2 => map(value, f32::exp2),
+            Opcode::Log => map(value, f32::ln),
+            Opcode::Log2 => map(value, f32::log2),
+            Opcode::Sqrt => map(value, f32::sqrt),
+            Opcode::RSqrt => map(value, |a| 1.0 / a.sqrt()),
+            Opcode::Abs => map(value, f32::abs),
+            Opcode::Sign => map(value, |a| if a == 0.0 { 0.0 } else { a.signum() }),
+            Opcode::Floor => map(value, f32::floor),
+            Opcode::Ceil => map(value, f32::ceil),
+            Opcode::Fract => map(value, |a| a - a.floor()),
+            Opcode::FloatToInt | Opcode::IntToFloat => value,
+            Opcode::FloatToBool | Opcode::IntToBool => map(value, |a| bool_to_float(a != 0.0)),
+            Opcode::BoolToFloat | Opcode::BoolToInt => value,
+            Opcode::Normalize => {
+                let length = dot(&value[..size], &value[..size]).sqrt();
+                map(value, |a| a / length)
+            }
+            Opcode::Length => [dot(&value[..size], &value[..size]).sqrt(); 4],
+            Opcode::Distance => {
+                let delta = zip(left, value, |a, b| a - b);
+                [dot(&delta[..size], &delta[..size]).sqrt(); 4]
+            }
+            Opcode::DotProduct => [dot(&left[..size], &value[..size]); 4],
+            Opcode::CrossProduct => [
+                left[1] * value[2] - left[2] * value[1],
+                left[2] * value[0] - left[0] * value[2],
+                left[0] * value[1] - left[1] * value[0],
+                0.0,
+            ],
+            Opcode::LogicalNot => map(value, |a| bool_to_float(a == 0.0)),
+            Opcode::LogicalAnd => zip(left, value, |a, b| bool_to_float(a != 0.0 && b != 0.0)),
+            Opcode::LogicalOr => zip(left, value, |a, b| bool_to_float(a != 0.0 || b != 0.0)),
+            Opcode::LogicalXor => zip(left, value, |a, b| bool_to_float((a != 0.0) != (b != 0.0))),
+            Opcode::Equal
+            | Opcode::NotEqual
+            | Opcode::LessThan
+            | Opcode::LessThanEqual
+            | Opcode::VectorEqual
+            | Opcode::VectorNotEqual => {
+                let result = match opcode {
+                    Opcode::Equal => left[0] == value[0],
+                    Opcode::NotEqual => left[0] != value[0],
+                    Opcode::LessThan => left[0] < value[0],
+                    Opcode::LessThanEqual => left[0] <= value[0],
+                    Opcode::VectorEqual => left[..size] == value[..size],
+                    _ => left[..size] != value[..size],
+                };
+                // Comparisons write their result to the first component of int register 0.
+                self.register(PixelBenderRegKind::Int, 0)[0] = bool_to_float(result);
+                return Ok(());
+            }
+            Opcode::BoolAny => [bool_to_float(value[..size].iter().any(|a| *a != 0.0)); 4],
+            Opcode::BoolAll => [bool_to_float(value[..size].iter().all(|a| *a != 0.0)); 4],
+            _ => {
+                return Err(Error::Unimplemented(
+                    format!("Pixel Bender opcode {opcode:?}").into(),
+                ))
+            }
+        };
+        self.store(dst, result);
+        Ok(())
+    }
+}
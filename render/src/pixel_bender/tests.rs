@@ -1,10 +1,10 @@
 use crate::pixel_bender::{
     Opcode, Operation, PixelBenderMetadata, PixelBenderParam, PixelBenderParamQualifier,
-    PixelBenderReg, PixelBenderRegChannel, PixelBenderRegKind, PixelBenderShader, PixelBenderType,
-    PixelBenderTypeOpcode,
+    PixelBenderReg, PixelBenderRegChannel, PixelBenderRegKind, PixelBenderShader,
+    PixelBenderShaderArgument, PixelBenderType, PixelBenderTypeOpcode, OUT_COORD_NAME,
 };
 
-use super::parse_shader;
+use super::{interpreter, parse_shader};
 
 #[test]
 fn simple_shader() {
@@ -343,3 +343,114 @@ fn simple_shader() {
     let shader = parse_shader(shader).expect("Failed to parse shader");
     assert_eq!(shader, expected, "Shader parsed incorrectly!");
 }
+
+/// Outputs the coordinate in RG, and whether x < `threshold` in B.
+fn out_coord_shader() -> PixelBenderShader {
+    let float_reg = |index, channels: &[PixelBenderRegChannel]| PixelBenderReg {
+        index,
+        channels: channels.to_vec(),
+        kind: PixelBenderRegKind::Float,
+    };
+    let int_reg = |index, channels: &[PixelBenderRegChannel]| PixelBenderReg {
+        index,
+        channels: channels.to_vec(),
+        kind: PixelBenderRegKind::Int,
+    };
+    let rg = [PixelBenderRegChannel::R, PixelBenderRegChannel::G];
+    let r = [PixelBenderRegChannel::R];
+    let b = [PixelBenderRegChannel::B];
+
+    PixelBenderShader {
+        name: "OutCoord".to_string(),
+        version: 1,
+        params: vec![
+            PixelBenderParam::Normal {
+                qualifier: PixelBenderParamQualifier::Input,
+                param_type: PixelBenderTypeOpcode::TFloat2,
+                reg: float_reg(0, &rg),
+                name: OUT_COORD_NAME.to_string(),
+                metadata: vec![],
+            },
+            PixelBenderParam::Normal {
+                qualifier: PixelBenderParamQualifier::Output,
+                param_type: PixelBenderTypeOpcode::TFloat4,
+                reg: float_reg(1, &PixelBenderRegChannel::RGBA),
+                name: "dst".to_string(),
+                metadata: vec![],
+            },
+            PixelBenderParam::Normal {
+                qualifier: PixelBenderParamQualifier::Input,
+                param_type: PixelBenderTypeOpcode::TFloat,
+                reg: float_reg(2, &r),
+                name: "threshold".to_string(),
+                metadata: vec![],
+            },
+        ],
+        metadata: vec![],
+        operations: vec![
+            Operation::Normal {
+                opcode: Opcode::Mov,
+                dst: float_reg(1, &rg),
+                src: float_reg(0, &rg),
+            },
+            Operation::LoadFloat {
+                dst: float_reg(1, &PixelBenderRegChannel::RGBA[2..]),
+                val: 0.0,
+            },
+            Operation::Normal {
+                opcode: Opcode::Mov,
+                dst: float_reg(3, &r),
+                src: float_reg(0, &r),
+            },
+            Operation::Normal {
+                opcode: Opcode::LessThan,
+                dst: float_reg(3, &r),
+                src: float_reg(2, &r),
+            },
+            Operation::If {
+                src: int_reg(0, &r),
+            },
+            Operation::LoadFloat {
+                dst: float_reg(1, &b),
+                val: 1.0,
+            },
+            Operation::EndIf,
+        ],
+    }
+}
+
+fn out_coord_arguments() -> [PixelBenderShaderArgument<'static>; 2] {
+    [
+        PixelBenderShaderArgument::ValueInput {
+            index: 0,
+            value: PixelBenderType::TFloat2(f32::NAN, f32::NAN),
+        },
+        PixelBenderShaderArgument::ValueInput {
+            index: 2,
+            value: PixelBenderType::TFloat(1.0),
+        },
+    ]
+}
+
+#[test]
+fn interpreter_out_coord() {
+    let pixels = interpreter::run_shader(&out_coord_shader(), &out_coord_arguments(), 2, 1)
+        .expect("Shader should run");
+    assert_eq!(
+        pixels,
+        vec![[0.5, 0.5, 1.0, 0.0], [1.5, 0.5, 0.0, 0.0]],
+        "Shader evaluated incorrectly!"
+    );
+}
+
+#[test]
+fn interpreter_out_coord_offset() {
+    let shader = out_coord_shader().with_out_coord_offset((-2.0, 3.0));
+    let pixels =
+        interpreter::run_shader(&shader, &out_coord_arguments(), 2, 1).expect("Shader should run");
+    assert_eq!(
+        pixels,
+        vec![[-1.5, 3.5, 1.0, 0.0], [-0.5, 3.5, 1.0, 0.0]],
+        "Shader evaluated incorrectly!"
+    );
+}
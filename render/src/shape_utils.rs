@@ -1,7 +1,9 @@
 use crate::matrix::Matrix;
 use enum_map::Enum;
 use smallvec::SmallVec;
-use swf::{CharacterId, FillStyle, LineStyle, Rectangle, Shape, ShapeRecord, Twips};
+use swf::{
+    CharacterId, FillStyle, Fixed16, Fixed8, LineStyle, Rectangle, Shape, ShapeRecord, Twips,
+};
 
 /// Controls the accuracy of the approximated quadratic curve, when splitting up a cubic curve
 const CUBIC_CURVE_TOLERANCE: f64 = 0.01;
@@ -93,6 +95,84 @@ pub enum DrawPath<'a> {
         commands: Vec<DrawCommand>,
         winding_rule: FillRule,
     },
+    /// Triangles with a bitmap fill mapped onto each vertex, as drawn by
+    /// AS3 `Graphics.drawTriangles` with `uvtData`.
+    /// Every three vertices form a triangle. The matrix of the fill style is ignored.
+    TexturedTriangles {
+        style: &'a FillStyle,
+        vertices: Vec<TexturedVertex>,
+    },
+}
+
+/// A vertex of a `DrawPath::TexturedTriangles`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TexturedVertex {
+    pub position: swf::Point<Twips>,
+
+    /// The texture coordinates, as a fraction of the bitmap size.
+    pub u: f32,
+    pub v: f32,
+
+    /// The perspective factor, with 1 meaning no perspective.
+    ///
+    /// Texture coordinates are interpolated perspective-correctly by interpolating
+    /// `(u * t, v * t, t)` linearly across the triangle and dividing by the interpolated `t`.
+    pub t: f32,
+}
+
+impl TexturedVertex {
+    /// Traces the outlines of the triangles formed by `vertices`.
+    pub fn triangle_commands(vertices: &[TexturedVertex]) -> Vec<DrawCommand> {
+        vertices
+            .chunks_exact(3)
+            .flat_map(|triangle| {
+                [
+                    DrawCommand::MoveTo(triangle[0].position),
+                    DrawCommand::LineTo(triangle[1].position),
+                    DrawCommand::LineTo(triangle[2].position),
+                    DrawCommand::LineTo(triangle[0].position),
+                ]
+            })
+            .collect()
+    }
+
+    /// Returns the affine matrix mapping a bitmap of the given size onto a triangle,
+    /// in the same form as the matrix of a bitmap fill style.
+    ///
+    /// The perspective factor is ignored. Returns `None` for degenerate texture coordinates.
+    pub fn bitmap_matrix(
+        triangle: &[TexturedVertex; 3],
+        width: f32,
+        height: f32,
+    ) -> Option<Matrix> {
+        let [p0, p1, p2] = triangle.map(|vertex| {
+            (
+                vertex.position.x.get() as f32,
+                vertex.position.y.get() as f32,
+            )
+        });
+        let [q0, q1, q2] = triangle.map(|vertex| (vertex.u * width, vertex.v * height));
+
+        let (dq1, dq2) = ((q1.0 - q0.0, q1.1 - q0.1), (q2.0 - q0.0, q2.1 - q0.1));
+        let (dp1, dp2) = ((p1.0 - p0.0, p1.1 - p0.1), (p2.0 - p0.0, p2.1 - p0.1));
+        let det = dq1.0 * dq2.1 - dq2.0 * dq1.1;
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let a = (dp1.0 * dq2.1 - dp2.0 * dq1.1) / det;
+        let b = (dp1.1 * dq2.1 - dp2.1 * dq1.1) / det;
+        let c = (dp2.0 * dq1.0 - dp1.0 * dq2.0) / det;
+        let d = (dp2.1 * dq1.0 - dp1.1 * dq2.0) / det;
+        Some(Matrix {
+            a,
+            b,
+            c,
+            d,
+            tx: Twips::new((p0.0 - (a * q0.0 + c * q0.1)) as i32),
+            ty: Twips::new((p0.1 - (b * q0.0 + d * q0.1)) as i32),
+        })
+    }
 }
 
 /// `DistilledShape` represents a ready-to-be-consumed collection of paths (both fills and strokes)
@@ -1201,6 +1281,385 @@ pub fn swf_glyph_to_shape(glyph: &swf::Glyph) -> swf::Shape {
     }
 }
 
+/// Interpolates between the start and end shapes of a morph shape.
+/// A ratio of 0 returns the start shape, and 65535 the end shape.
+pub fn lerp_morph_shape(
+    start_shape: &swf::MorphShape,
+    end_shape: &swf::MorphShape,
+    ratio: u16,
+) -> Shape {
+    use swf::{Point, ShapeStyles};
+    let b = f32::from(ratio) / 65535.0;
+    let a = 1.0 - b;
+    let fill_styles: Vec<FillStyle> = start_shape
+        .fill_styles
+        .iter()
+        .zip(end_shape.fill_styles.iter())
+        .map(|(start, end)| lerp_fill(start, end, a, b))
+        .collect();
+    let line_styles: Vec<LineStyle> = start_shape
+        .line_styles
+        .iter()
+        .zip(end_shape.line_styles.iter())
+        .map(|(start, end)| {
+            start
+                .clone()
+                .with_width(lerp_twips(start.width(), end.width(), a, b))
+                .with_fill_style(lerp_fill(start.fill_style(), end.fill_style(), a, b))
+        })
+        .collect();
+
+    let mut shape = Vec::with_capacity(start_shape.shape.len());
+    let mut start_iter = start_shape.shape.iter();
+    let mut end_iter = end_shape.shape.iter();
+    let mut start = start_iter.next();
+    let mut end = end_iter.next();
+    let mut start_x = Twips::ZERO;
+    let mut start_y = Twips::ZERO;
+    let mut end_x = Twips::ZERO;
+    let mut end_y = Twips::ZERO;
+    // TODO: Feels like this could be cleaned up a bit.
+    // We step through both the start records and end records, interpolating edges pairwise.
+    // Fill style/line style changes should only appear in the start records.
+    // However, StyleChangeRecord move_to can appear it both start and end records,
+    // and not necessarily in matching pairs; therefore, we have to keep track of the pen position
+    // in case one side is missing a move_to; it will implicitly use the last pen position.
+    while let (Some(s), Some(e)) = (start, end) {
+        match (s, e) {
+            (ShapeRecord::StyleChange(start_change), ShapeRecord::StyleChange(end_change)) => {
+                let mut style_change = start_change.clone();
+                if start_change.move_to.is_some() || end_change.move_to.is_some() {
+                    if let Some(move_to) = &start_change.move_to {
+                        start_x = move_to.x;
+                        start_y = move_to.y;
+                    }
+                    if let Some(move_to) = &end_change.move_to {
+                        end_x = move_to.x;
+                        end_y = move_to.y;
+                    }
+                    style_change.move_to = Some(Point::new(
+                        lerp_twips(start_x, end_x, a, b),
+                        lerp_twips(start_y, end_y, a, b),
+                    ));
+                }
+                shape.push(ShapeRecord::StyleChange(style_change));
+                start = start_iter.next();
+                end = end_iter.next();
+            }
+            (ShapeRecord::StyleChange(start_change), _) => {
+                let mut style_change = start_change.clone();
+                if let Some(move_to) = &start_change.move_to {
+                    start_x = move_to.x;
+                    start_y = move_to.y;
+                    style_change.move_to = Some(Point::new(
+                        lerp_twips(start_x, end_x, a, b),
+                        lerp_twips(start_y, end_y, a, b),
+                    ));
+                }
+                shape.push(ShapeRecord::StyleChange(style_change));
+                update_pos(&mut start_x, &mut start_y, s);
+                start = start_iter.next();
+            }
+            (_, ShapeRecord::StyleChange(end_change)) => {
+                let mut style_change = end_change.clone();
+                if let Some(move_to) = &end_change.move_to {
+                    end_x = move_to.x;
+                    end_y = move_to.y;
+                    style_change.move_to = Some(Point::new(
+                        lerp_twips(start_x, end_x, a, b),
+                        lerp_twips(start_y, end_y, a, b),
+                    ));
+                }
+                shape.push(ShapeRecord::StyleChange(style_change));
+                update_pos(&mut end_x, &mut end_y, s);
+                end = end_iter.next();
+                continue;
+            }
+            _ => {
+                shape.push(lerp_edges(
+                    Point::new(start_x, start_y),
+                    Point::new(end_x, end_y),
+                    s,
+                    e,
+                    a,
+                    b,
+                ));
+                update_pos(&mut start_x, &mut start_y, s);
+                update_pos(&mut end_x, &mut end_y, e);
+                start = start_iter.next();
+                end = end_iter.next();
+            }
+        }
+    }
+
+    let styles = ShapeStyles {
+        fill_styles,
+        line_styles,
+    };
+
+    let bounds = calculate_shape_bounds(&shape);
+    Shape {
+        version: 4,
+        id: 0,
+        shape_bounds: bounds.clone(),
+        edge_bounds: bounds,
+        flags: swf::ShapeFlag::HAS_SCALING_STROKES,
+        styles,
+        shape,
+    }
+}
+
+fn update_pos(x: &mut Twips, y: &mut Twips, record: &swf::ShapeRecord) {
+    match record {
+        ShapeRecord::StraightEdge { delta } => {
+            *x += delta.dx;
+            *y += delta.dy;
+        }
+        ShapeRecord::CurvedEdge {
+            control_delta,
+            anchor_delta,
+        } => {
+            *x += control_delta.dx + anchor_delta.dx;
+            *y += control_delta.dy + anchor_delta.dy;
+        }
+        ShapeRecord::StyleChange(ref style_change) => {
+            if let Some(move_to) = &style_change.move_to {
+                *x = move_to.x;
+                *y = move_to.y;
+            }
+        }
+    }
+}
+
+// Interpolation functions
+// These interpolate between two SWF shape structures.
+// a + b should = 1.0
+
+fn lerp_color(start: &swf::Color, end: &swf::Color, a: f32, b: f32) -> swf::Color {
+    // f32 -> u8 cast is defined to saturate for out of bounds values,
+    // so we don't have to worry about clamping.
+    swf::Color {
+        r: (a * f32::from(start.r) + b * f32::from(end.r)) as u8,
+        g: (a * f32::from(start.g) + b * f32::from(end.g)) as u8,
+        b: (a * f32::from(start.b) + b * f32::from(end.b)) as u8,
+        a: (a * f32::from(start.a) + b * f32::from(end.a)) as u8,
+    }
+}
+
+fn lerp_twips(start: Twips, end: Twips, a: f32, b: f32) -> Twips {
+    Twips::new((start.get() as f32 * a + end.get() as f32 * b).round() as i32)
+}
+
+fn lerp_point_twips(
+    start: swf::Point<Twips>,
+    end: swf::Point<Twips>,
+    a: f32,
+    b: f32,
+) -> swf::Point<Twips> {
+    swf::Point::new(
+        lerp_twips(start.x, end.x, a, b),
+        lerp_twips(start.y, end.y, a, b),
+    )
+}
+
+fn lerp_fill(start: &swf::FillStyle, end: &swf::FillStyle, a: f32, b: f32) -> swf::FillStyle {
+    use swf::FillStyle;
+    match (start, end) {
+        // Color-to-color
+        (FillStyle::Color(start), FillStyle::Color(end)) => {
+            FillStyle::Color(lerp_color(start, end, a, b))
+        }
+
+        // Bitmap-to-bitmap
+        // ID should be the same.
+        (
+            FillStyle::Bitmap {
+                id: start_id,
+                matrix: start,
+                is_smoothed,
+                is_repeating,
+            },
+            FillStyle::Bitmap { matrix: end, .. },
+        ) => FillStyle::Bitmap {
+            id: *start_id,
+            matrix: lerp_matrix(start, end, a, b),
+            is_smoothed: *is_smoothed,
+            is_repeating: *is_repeating,
+        },
+
+        // Linear-to-linear
+        (FillStyle::LinearGradient(start), FillStyle::LinearGradient(end)) => {
+            FillStyle::LinearGradient(lerp_gradient(start, end, a, b))
+        }
+
+        // Radial-to-radial
+        (FillStyle::RadialGradient(start), FillStyle::RadialGradient(end)) => {
+            FillStyle::RadialGradient(lerp_gradient(start, end, a, b))
+        }
+
+        // Focal gradients also interpolate focal point.
+        (
+            FillStyle::FocalGradient {
+                gradient: start,
+                focal_point: start_focal,
+            },
+            FillStyle::FocalGradient {
+                gradient: end,
+                focal_point: end_focal,
+            },
+        ) => FillStyle::FocalGradient {
+            gradient: lerp_gradient(start, end, a, b),
+            focal_point: *start_focal * Fixed8::from_f32(a) + *end_focal * Fixed8::from_f32(b),
+        },
+
+        // All other combinations should not occur, because SWF stores the start/end fill as the same type, always.
+        // If you happened to make, say, a solid color-to-radial gradient tween in the IDE, this would get baked down into
+        // a radial-to-radial gradient on export.
+        _ => {
+            tracing::warn!(
+                "Unexpected morph shape fill style combination: {:#?}, {:#?}",
+                start,
+                end
+            );
+            start.clone()
+        }
+    }
+}
+
+fn lerp_edges(
+    start_pen: swf::Point<Twips>,
+    end_pen: swf::Point<Twips>,
+    start: &swf::ShapeRecord,
+    end: &swf::ShapeRecord,
+    a: f32,
+    b: f32,
+) -> swf::ShapeRecord {
+    use swf::ShapeRecord;
+    let pen = lerp_point_twips(start_pen, end_pen, a, b);
+    match (start, end) {
+        (
+            ShapeRecord::StraightEdge { delta: start_delta },
+            ShapeRecord::StraightEdge { delta: end_delta },
+        ) => {
+            let start_anchor = start_pen + *start_delta;
+            let end_anchor = end_pen + *end_delta;
+
+            let anchor = lerp_point_twips(start_anchor, end_anchor, a, b);
+
+            ShapeRecord::StraightEdge {
+                delta: anchor - pen,
+            }
+        }
+
+        (
+            ShapeRecord::CurvedEdge {
+                control_delta: start_control_delta,
+                anchor_delta: start_anchor_delta,
+            },
+            ShapeRecord::CurvedEdge {
+                control_delta: end_control_delta,
+                anchor_delta: end_anchor_delta,
+            },
+        ) => {
+            let start_control = start_pen + *start_control_delta;
+            let start_anchor = start_control + *start_anchor_delta;
+
+            let end_control = end_pen + *end_control_delta;
+            let end_anchor = end_control + *end_anchor_delta;
+
+            let control = lerp_point_twips(start_control, end_control, a, b);
+            let anchor = lerp_point_twips(start_anchor, end_anchor, a, b);
+
+            ShapeRecord::CurvedEdge {
+                control_delta: control - pen,
+                anchor_delta: anchor - control,
+            }
+        }
+
+        (
+            ShapeRecord::StraightEdge { delta: start_delta },
+            ShapeRecord::CurvedEdge {
+                control_delta: end_control_delta,
+                anchor_delta: end_anchor_delta,
+            },
+        ) => {
+            let start_control = start_pen + *start_delta / 2;
+            let start_anchor = start_pen + *start_delta;
+
+            let end_control = end_pen + *end_control_delta;
+            let end_anchor = end_control + *end_anchor_delta;
+
+            let control = lerp_point_twips(start_control, end_control, a, b);
+            let anchor = lerp_point_twips(start_anchor, end_anchor, a, b);
+
+            ShapeRecord::CurvedEdge {
+                control_delta: control - pen,
+                anchor_delta: anchor - control,
+            }
+        }
+
+        (
+            ShapeRecord::CurvedEdge {
+                control_delta: start_control_delta,
+                anchor_delta: start_anchor_delta,
+            },
+            ShapeRecord::StraightEdge { delta: end_delta },
+        ) => {
+            let start_control = start_pen + *start_control_delta;
+            let start_anchor = start_control + *start_anchor_delta;
+
+            let end_control = end_pen + *end_delta / 2;
+            let end_anchor = end_pen + *end_delta;
+
+            let control = lerp_point_twips(start_control, end_control, a, b);
+            let anchor = lerp_point_twips(start_anchor, end_anchor, a, b);
+
+            ShapeRecord::CurvedEdge {
+                control_delta: control - pen,
+                anchor_delta: anchor - control,
+            }
+        }
+        _ => unreachable!("{:?} {:?}", start, end),
+    }
+}
+
+fn lerp_matrix(start: &swf::Matrix, end: &swf::Matrix, a: f32, b: f32) -> swf::Matrix {
+    // TODO: Lerping a matrix element-wise is geometrically wrong,
+    // but I doubt Flash is decomposing the matrix into scale-rotate-translate?
+    let af = Fixed16::from_f32(a);
+    let bf = Fixed16::from_f32(b);
+    swf::Matrix {
+        a: start.a * af + end.a * bf,
+        b: start.b * af + end.b * bf,
+        c: start.c * af + end.c * bf,
+        d: start.d * af + end.d * bf,
+        tx: lerp_twips(start.tx, end.tx, a, b),
+        ty: lerp_twips(start.ty, end.ty, a, b),
+    }
+}
+
+fn lerp_gradient(start: &swf::Gradient, end: &swf::Gradient, a: f32, b: f32) -> swf::Gradient {
+    use swf::{Gradient, GradientRecord};
+    // Morph gradients are guaranteed to have the same number of records in the start/end gradient.
+    debug_assert_eq!(start.records.len(), end.records.len());
+    let records: Vec<GradientRecord> = start
+        .records
+        .iter()
+        .zip(end.records.iter())
+        .map(|(start, end)| swf::GradientRecord {
+            ratio: (f32::from(start.ratio) * a + f32::from(end.ratio) * b) as u8,
+            color: lerp_color(&start.color, &end.color, a, b),
+        })
+        .collect();
+
+    Gradient {
+        matrix: lerp_matrix(&start.matrix, &end.matrix, a, b),
+        spread: start.spread,
+        interpolation: start.interpolation,
+        records,
+    }
+}
+
 /// Scale mode used by strokes in a shape.
 ///
 /// Determines how the line thickness is affected by the shape's transform.
@@ -1462,4 +1921,17 @@ mod tests {
             1,
         );
     }
+
+    // Regression test for #14074
+    #[test]
+    fn test_lerp_rounding() {
+        let ratio: u16 = 17246;
+        let b = f32::from(ratio) / 65535.0;
+        let a = 1.0 - b;
+
+        assert_eq!(
+            lerp_twips(Twips::new(-7), Twips::new(-7), a, b),
+            Twips::new(-7)
+        );
+    }
 }
@@ -0,0 +1,581 @@
+//! Exports shapes as SVG images.
+//!
+//! Coordinates are written in pixels, so that the document has the size of the shape.
+
+use crate::bitmap::BitmapSize;
+use crate::shape_utils::{lerp_morph_shape, DistilledShape, DrawCommand, DrawPath, FillRule};
+use std::collections::HashMap;
+use std::fmt::Write;
+use swf::{
+    CharacterId, Color, FillStyle, Gradient, GradientInterpolation, GradientSpread, LineCapStyle,
+    LineJoinStyle, LineStyle, Matrix, Rectangle, Twips,
+};
+
+/// Half the size of the square that gradients are defined in, before their matrix is
+/// applied, in pixels.
+const GRADIENT_SIZE: f64 = 16384.0 / 20.0;
+
+/// A bitmap used by a bitmap fill.
+#[derive(Clone, Debug)]
+pub struct SvgBitmap {
+    /// The URL of the image, such as a relative path or a `data:` URL.
+    pub href: String,
+    pub size: BitmapSize,
+}
+
+/// An object that returns the bitmap used by a bitmap fill, given its character ID.
+pub trait SvgBitmapSource {
+    fn svg_bitmap(&self, id: CharacterId) -> Option<&SvgBitmap>;
+}
+
+impl SvgBitmapSource for HashMap<CharacterId, SvgBitmap> {
+    fn svg_bitmap(&self, id: CharacterId) -> Option<&SvgBitmap> {
+        self.get(&id)
+    }
+}
+
+/// Writes a shape as an SVG document.
+///
+/// Bitmap fills whose bitmap isn't returned by `bitmaps` are left unfilled.
+pub fn shape_to_svg(shape: &DistilledShape, bitmaps: &dyn SvgBitmapSource) -> String {
+    let bounds = view_bounds(shape);
+    let mut writer = SvgWriter {
+        bitmaps,
+        bounds: bounds.clone(),
+        defs: String::new(),
+        body: String::new(),
+        num_defs: 0,
+    };
+    for path in &shape.paths {
+        match path {
+            DrawPath::Fill {
+                style,
+                commands,
+                winding_rule,
+            } => writer.fill(style, commands, *winding_rule),
+            DrawPath::Stroke {
+                style,
+                is_closed,
+                commands,
+            } => writer.stroke(style, *is_closed, commands),
+            // These are only created by `Graphics.drawTriangles`, never by SWF shapes.
+            DrawPath::TexturedTriangles { .. } => (),
+        }
+    }
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        bounds.width().to_pixels(),
+        bounds.height().to_pixels(),
+        bounds.x_min,
+        bounds.y_min,
+        bounds.width(),
+        bounds.height(),
+    );
+    if !writer.defs.is_empty() {
+        svg.push_str("<defs>\n");
+        svg.push_str(&writer.defs);
+        svg.push_str("</defs>\n");
+    }
+    svg.push_str(&writer.body);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes a morph shape at the given ratio as an SVG document.
+/// A ratio of 0 writes the start shape, and 65535 the end shape.
+pub fn morph_shape_to_svg(
+    morph_shape: &swf::DefineMorphShape,
+    ratio: u16,
+    bitmaps: &dyn SvgBitmapSource,
+) -> String {
+    let shape = lerp_morph_shape(&morph_shape.start, &morph_shape.end, ratio);
+    let mut distilled = DistilledShape::from(&shape);
+    distilled.id = morph_shape.id;
+    shape_to_svg(&distilled, bitmaps)
+}
+
+/// Returns the area covered by a shape, including its strokes.
+fn view_bounds(shape: &DistilledShape) -> Rectangle<Twips> {
+    let max_width = shape
+        .paths
+        .iter()
+        .filter_map(|path| match path {
+            DrawPath::Stroke { style, .. } => Some(stroke_width(style)),
+            _ => None,
+        })
+        .max()
+        .unwrap_or_default();
+    let edge_bounds = shape.edge_bounds.clone().grow(max_width / 2);
+    if shape.shape_bounds.is_valid() {
+        shape.shape_bounds.clone().union(&edge_bounds)
+    } else {
+        edge_bounds
+    }
+}
+
+/// Returns the width that a stroke is drawn with.
+/// Strokes are never thinner than a pixel.
+fn stroke_width(style: &LineStyle) -> Twips {
+    style.width().max(Twips::ONE)
+}
+
+struct SvgWriter<'a> {
+    bitmaps: &'a dyn SvgBitmapSource,
+
+    /// The area covered by the shape, which non-repeating bitmaps are stretched over.
+    bounds: Rectangle<Twips>,
+
+    defs: String,
+    body: String,
+    num_defs: usize,
+}
+
+impl SvgWriter<'_> {
+    fn fill(&mut self, style: &FillStyle, commands: &[DrawCommand], winding_rule: FillRule) {
+        let paint = self.paint(style);
+        let fill_rule = match winding_rule {
+            FillRule::EvenOdd => "evenodd",
+            FillRule::NonZero => "nonzero",
+        };
+        let _ = write!(
+            self.body,
+            r#"<path d="{}" fill="{}""#,
+            path_data(commands, false),
+            paint.url
+        );
+        if let Some(opacity) = paint.opacity {
+            let _ = write!(self.body, r#" fill-opacity="{opacity}""#);
+        }
+        let _ = writeln!(self.body, r#" fill-rule="{fill_rule}"/>"#);
+    }
+
+    fn stroke(&mut self, style: &LineStyle, is_closed: bool, commands: &[DrawCommand]) {
+        let paint = self.paint(style.fill_style());
+        let _ = write!(
+            self.body,
+            r#"<path d="{}" fill="none" stroke="{}""#,
+            path_data(commands, is_closed && style.allow_close()),
+            paint.url
+        );
+        if let Some(opacity) = paint.opacity {
+            let _ = write!(self.body, r#" stroke-opacity="{opacity}""#);
+        }
+
+        // SVG has a single cap style for both ends of a stroke.
+        let cap = match style.start_cap() {
+            LineCapStyle::Round => "round",
+            LineCapStyle::None => "butt",
+            LineCapStyle::Square => "square",
+        };
+        let _ = write!(self.body, r#" stroke-linecap="{cap}""#);
+        match style.join_style() {
+            LineJoinStyle::Round => {
+                let _ = write!(self.body, r#" stroke-linejoin="round""#);
+            }
+            LineJoinStyle::Bevel => {
+                let _ = write!(self.body, r#" stroke-linejoin="bevel""#);
+            }
+            LineJoinStyle::Miter(limit) => {
+                let _ = write!(
+                    self.body,
+                    r#" stroke-linejoin="miter" stroke-miterlimit="{}""#,
+                    number(limit.to_f64().max(1.0))
+                );
+            }
+        }
+
+        let _ = write!(self.body, r#" stroke-width="{}""#, stroke_width(style));
+        // Strokes that only scale in one direction can't be expressed in SVG,
+        // so they're treated as scaling in both.
+        if !style.allow_scale_x() && !style.allow_scale_y() {
+            let _ = write!(self.body, r#" vector-effect="non-scaling-stroke""#);
+        }
+        let _ = writeln!(self.body, "/>");
+    }
+
+    /// Returns the paint of a fill style, adding any gradient or pattern it needs to the defs.
+    fn paint(&mut self, style: &FillStyle) -> Paint {
+        match style {
+            FillStyle::Color(color) => Paint {
+                url: hex_color(color),
+                opacity: opacity(color.a),
+            },
+            FillStyle::LinearGradient(gradient) => {
+                let id = self.next_id("gradient");
+                let _ = write!(
+                    self.defs,
+                    r#"<linearGradient id="{id}" x1="{}" y1="0" x2="{GRADIENT_SIZE}" y2="0""#,
+                    -GRADIENT_SIZE
+                );
+                self.gradient(gradient, "linearGradient");
+                Paint::url(&id)
+            }
+            FillStyle::RadialGradient(gradient) => {
+                let id = self.next_id("gradient");
+                let _ = write!(
+                    self.defs,
+                    r#"<radialGradient id="{id}" cx="0" cy="0" r="{GRADIENT_SIZE}""#
+                );
+                self.gradient(gradient, "radialGradient");
+                Paint::url(&id)
+            }
+            FillStyle::FocalGradient {
+                gradient,
+                focal_point,
+            } => {
+                let id = self.next_id("gradient");
+                let focal_point = focal_point.to_f64().clamp(-1.0, 1.0);
+                let _ = write!(
+                    self.defs,
+                    r#"<radialGradient id="{id}" cx="0" cy="0" r="{GRADIENT_SIZE}" fx="{}" fy="0""#,
+                    number(focal_point * GRADIENT_SIZE)
+                );
+                self.gradient(gradient, "radialGradient");
+                Paint::url(&id)
+            }
+            FillStyle::Bitmap {
+                id,
+                matrix,
+                is_smoothed,
+                is_repeating,
+            } => {
+                let Some(bitmap) = self.bitmaps.svg_bitmap(*id) else {
+                    return Paint {
+                        url: "none".to_string(),
+                        opacity: None,
+                    };
+                };
+                let (width, height) = (bitmap.size.width, bitmap.size.height);
+                // Patterns always repeat, so a non-repeating bitmap is given a tile large
+                // enough to cover the whole shape. Flash extends the edge pixels of the bitmap
+                // instead, which isn't possible here.
+                let tile = if *is_repeating {
+                    None
+                } else {
+                    self.covering_tile(matrix, width, height)
+                };
+                let (x, y, tile_width, tile_height) =
+                    tile.unwrap_or((0.0, 0.0, width.into(), height.into()));
+                // The matrix maps bitmap pixels to twips, and the tile starts at (x, y).
+                let [a, b, c, d, tx, ty] = components(matrix);
+                let (a, b, c, d) = (a / 20.0, b / 20.0, c / 20.0, d / 20.0);
+                let (tx, ty) = (tx + a * x + c * y, ty + b * x + d * y);
+                let pattern_id = self.next_id("bitmap");
+                let _ = write!(
+                    self.defs,
+                    r#"<pattern id="{pattern_id}" patternUnits="userSpaceOnUse" patternTransform="{}" width="{tile_width}" height="{tile_height}">"#,
+                    transform([a, b, c, d, tx, ty])
+                );
+                let _ = write!(
+                    self.defs,
+                    r#"<image x="{}" y="{}" width="{width}" height="{height}" preserveAspectRatio="none" xlink:href="{}""#,
+                    number(-x),
+                    number(-y),
+                    escape(&bitmap.href)
+                );
+                if !is_smoothed {
+                    let _ = write!(self.defs, r#" style="image-rendering:pixelated""#);
+                }
+                let _ = writeln!(self.defs, "/></pattern>");
+                Paint::url(&pattern_id)
+            }
+        }
+    }
+
+    /// Writes the attributes and stops of a gradient, after its element name and position.
+    fn gradient(&mut self, gradient: &Gradient, element: &str) {
+        let spread = match gradient.spread {
+            GradientSpread::Pad => "pad",
+            GradientSpread::Reflect => "reflect",
+            GradientSpread::Repeat => "repeat",
+        };
+        let _ = write!(
+            self.defs,
+            r#" gradientUnits="userSpaceOnUse" gradientTransform="{}" spreadMethod="{spread}""#,
+            transform(components(&gradient.matrix))
+        );
+        if gradient.interpolation == GradientInterpolation::LinearRgb {
+            let _ = write!(self.defs, r#" color-interpolation="linearRGB""#);
+        }
+        let _ = writeln!(self.defs, ">");
+        for record in &gradient.records {
+            let _ = write!(
+                self.defs,
+                r#"<stop offset="{}" stop-color="{}""#,
+                number(f64::from(record.ratio) / 255.0),
+                hex_color(&record.color)
+            );
+            if let Some(opacity) = opacity(record.color.a) {
+                let _ = write!(self.defs, r#" stop-opacity="{opacity}""#);
+            }
+            let _ = writeln!(self.defs, "/>");
+        }
+        let _ = writeln!(self.defs, "</{element}>");
+    }
+
+    /// Returns a tile of a bitmap fill, in bitmap pixels, that covers both the bitmap and the
+    /// whole shape. Returns `None` if the matrix can't be inverted.
+    fn covering_tile(
+        &self,
+        matrix: &Matrix,
+        width: u16,
+        height: u16,
+    ) -> Option<(f64, f64, f64, f64)> {
+        let [a, b, c, d, tx, ty] = components(matrix);
+        let det = a * d - b * c;
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+        let (mut x_min, mut y_min) = (0.0f64, 0.0f64);
+        let (mut x_max, mut y_max) = (f64::from(width), f64::from(height));
+        for (x, y) in [
+            (self.bounds.x_min, self.bounds.y_min),
+            (self.bounds.x_max, self.bounds.y_min),
+            (self.bounds.x_min, self.bounds.y_max),
+            (self.bounds.x_max, self.bounds.y_max),
+        ] {
+            let (x, y) = (x.to_pixels() - tx, y.to_pixels() - ty);
+            // The matrix is in twips, while the bounds are in pixels.
+            let u = (d * x - c * y) / det * 20.0;
+            let v = (a * y - b * x) / det * 20.0;
+            x_min = x_min.min(u.floor());
+            y_min = y_min.min(v.floor());
+            x_max = x_max.max(u.ceil());
+            y_max = y_max.max(v.ceil());
+        }
+        Some((x_min, y_min, x_max - x_min, y_max - y_min))
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        let id = format!("{prefix}{}", self.num_defs);
+        self.num_defs += 1;
+        id
+    }
+}
+
+/// The value of a `fill` or `stroke` attribute.
+struct Paint {
+    url: String,
+    opacity: Option<String>,
+}
+
+impl Paint {
+    fn url(id: &str) -> Self {
+        Self {
+            url: format!("url(#{id})"),
+            opacity: None,
+        }
+    }
+}
+
+/// Returns the `d` attribute of a path.
+fn path_data(commands: &[DrawCommand], close: bool) -> String {
+    let mut data = String::new();
+    for command in commands {
+        if !data.is_empty() {
+            data.push(' ');
+        }
+        let _ = match command {
+            DrawCommand::MoveTo(point) => write!(data, "M{} {}", point.x, point.y),
+            DrawCommand::LineTo(point) => write!(data, "L{} {}", point.x, point.y),
+            DrawCommand::QuadraticCurveTo { control, anchor } => write!(
+                data,
+                "Q{} {} {} {}",
+                control.x, control.y, anchor.x, anchor.y
+            ),
+            DrawCommand::CubicCurveTo {
+                control_a,
+                control_b,
+                anchor,
+            } => write!(
+                data,
+                "C{} {} {} {} {} {}",
+                control_a.x, control_a.y, control_b.x, control_b.y, anchor.x, anchor.y
+            ),
+        };
+    }
+    if close {
+        data.push_str(" Z");
+    }
+    data
+}
+
+/// Returns the components of a matrix, with its translation in pixels.
+fn components(matrix: &Matrix) -> [f64; 6] {
+    [
+        matrix.a.to_f64(),
+        matrix.b.to_f64(),
+        matrix.c.to_f64(),
+        matrix.d.to_f64(),
+        matrix.tx.to_pixels(),
+        matrix.ty.to_pixels(),
+    ]
+}
+
+/// Returns the value of a `transform` attribute.
+fn transform(components: [f64; 6]) -> String {
+    let [a, b, c, d, tx, ty] = components.map(number);
+    format!("matrix({a} {b} {c} {d} {tx} {ty})")
+}
+
+fn hex_color(color: &Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// Returns the opacity of an alpha value, or `None` if it's opaque.
+fn opacity(alpha: u8) -> Option<String> {
+    (alpha != 255).then(|| number(f64::from(alpha) / 255.0))
+}
+
+/// Formats a number with no more precision than is useful.
+fn number(value: f64) -> String {
+    let value = format!("{value:.5}");
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+    if value == "-0" {
+        "0".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::{Point, PointDelta, ShapeFlag, ShapeRecord, ShapeStyles, StyleChangeData};
+
+    fn shape(fill_style: FillStyle, line_style: LineStyle) -> swf::Shape {
+        let edge = |dx, dy| ShapeRecord::StraightEdge {
+            delta: PointDelta::from_pixels(dx, dy),
+        };
+        swf::Shape {
+            version: 4,
+            id: 1,
+            shape_bounds: Rectangle {
+                x_min: Twips::from_pixels(-5.0),
+                y_min: Twips::from_pixels(-5.0),
+                x_max: Twips::from_pixels(105.0),
+                y_max: Twips::from_pixels(55.0),
+            },
+            edge_bounds: Rectangle {
+                x_min: Twips::ZERO,
+                y_min: Twips::ZERO,
+                x_max: Twips::from_pixels(100.0),
+                y_max: Twips::from_pixels(50.0),
+            },
+            flags: ShapeFlag::HAS_SCALING_STROKES,
+            styles: ShapeStyles {
+                fill_styles: vec![fill_style],
+                line_styles: vec![line_style],
+            },
+            shape: vec![
+                ShapeRecord::StyleChange(Box::new(StyleChangeData {
+                    move_to: Some(Point::ZERO),
+                    fill_style_0: None,
+                    fill_style_1: Some(1),
+                    line_style: Some(1),
+                    new_styles: None,
+                })),
+                edge(100.0, 0.0),
+                edge(0.0, 50.0),
+                edge(-100.0, 0.0),
+                edge(0.0, -50.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn solid_fill_and_stroke() {
+        let shape = shape(
+            FillStyle::Color(Color::from_rgba(0x80FF0000)),
+            LineStyle::new()
+                .with_width(Twips::from_pixels(10.0))
+                .with_color(Color::from_rgb(0x0000FF, 255))
+                .with_start_cap(LineCapStyle::Square)
+                .with_join_style(LineJoinStyle::Miter(swf::Fixed8::from_f32(3.0))),
+        );
+        let svg = shape_to_svg(&DistilledShape::from(&shape), &HashMap::new());
+        assert_eq!(
+            svg,
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="110" height="60" viewBox="-5 -5 110 60">"#,
+                "\n",
+                r##"<path d="M0 0 L100 0 L100 50 L0 50 L0 0" fill="#ff0000" fill-opacity="0.50196" fill-rule="evenodd"/>"##,
+                "\n",
+                r##"<path d="M0 0 L100 0 L100 50 L0 50 L0 0 Z" fill="none" stroke="#0000ff" stroke-linecap="square" stroke-linejoin="miter" stroke-miterlimit="3" stroke-width="10"/>"##,
+                "\n",
+                "</svg>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn gradient_and_bitmap() {
+        let gradient = Gradient {
+            matrix: Matrix::scale(swf::Fixed16::from_f32(0.5), swf::Fixed16::ONE),
+            spread: GradientSpread::Reflect,
+            interpolation: GradientInterpolation::LinearRgb,
+            records: vec![
+                swf::GradientRecord {
+                    ratio: 0,
+                    color: Color::from_rgb(0xFF0000, 255),
+                },
+                swf::GradientRecord {
+                    ratio: 255,
+                    color: Color::from_rgb(0x00FF00, 0),
+                },
+            ],
+        };
+        let bitmap = FillStyle::Bitmap {
+            id: 2,
+            matrix: Matrix::scale(swf::Fixed16::from_f32(20.0), swf::Fixed16::from_f32(20.0)),
+            is_smoothed: false,
+            is_repeating: true,
+        };
+        let shape = shape(
+            FillStyle::FocalGradient {
+                gradient,
+                focal_point: swf::Fixed8::from_f32(-0.5),
+            },
+            LineStyle::new()
+                .with_fill_style(bitmap)
+                .with_allow_scale_x(false)
+                .with_allow_scale_y(false),
+        );
+        let bitmaps = HashMap::from([(
+            2,
+            SvgBitmap {
+                href: "bitmap_2.png".to_string(),
+                size: BitmapSize {
+                    width: 8,
+                    height: 4,
+                },
+            },
+        )]);
+        let svg = shape_to_svg(&DistilledShape::from(&shape), &bitmaps);
+        assert!(svg.contains(concat!(
+            r#"<radialGradient id="gradient0" cx="0" cy="0" r="819.2" fx="-409.6" fy="0" gradientUnits="userSpaceOnUse" gradientTransform="matrix(0.5 0 0 1 0 0)" spreadMethod="reflect" color-interpolation="linearRGB">"#,
+            "\n",
+            r##"<stop offset="0" stop-color="#ff0000"/>"##,
+            "\n",
+            r##"<stop offset="1" stop-color="#00ff00" stop-opacity="0"/>"##,
+            "\n",
+            "</radialGradient>\n",
+        )));
+        assert!(svg.contains(
+            r#"<pattern id="bitmap1" patternUnits="userSpaceOnUse" patternTransform="matrix(1 0 0 1 0 0)" width="8" height="4"><image x="0" y="0" width="8" height="4" preserveAspectRatio="none" xlink:href="bitmap_2.png" style="image-rendering:pixelated"/></pattern>"#
+        ));
+        assert!(svg.contains(r#"fill="url(#gradient0)""#));
+        assert!(svg.contains(
+            r#"stroke="url(#bitmap1)" stroke-linecap="round" stroke-linejoin="round" stroke-width="1" vector-effect="non-scaling-stroke"/>"#
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::bitmap::BitmapSource;
-use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath, GradientType};
+use crate::shape_utils::{
+    DistilledShape, DrawCommand, DrawPath, FillRule, GradientType, TexturedVertex,
+};
 use indexmap::IndexSet;
 use lyon::path::Path;
 use lyon::tessellation::{
@@ -45,6 +47,29 @@ impl ShapeTessellator {
         self.lyon_mesh = VertexBuffers::new();
 
         for path in shape.paths {
+            if let DrawPath::TexturedTriangles { style, vertices } = &path {
+                if let swf::FillStyle::Bitmap {
+                    id,
+                    is_smoothed,
+                    is_repeating,
+                    ..
+                } = style
+                {
+                    if bitmap_source.bitmap_size(*id).is_some() {
+                        self.tessellate_textured_triangles(
+                            TexturedTriangles {
+                                bitmap_id: *id,
+                                is_smoothed: *is_smoothed,
+                                is_repeating: *is_repeating,
+                                uvts: Vec::new(),
+                            },
+                            vertices,
+                        );
+                    }
+                    continue;
+                }
+            }
+
             let (fill_style, lyon_path, next_is_stroke) = match &path {
                 DrawPath::Fill {
                     style,
@@ -60,6 +85,12 @@ impl ShapeTessellator {
                     ruffle_path_to_lyon_path(commands, *is_closed),
                     true,
                 ),
+                // Triangles without a bitmap are filled like any other path.
+                DrawPath::TexturedTriangles { style, vertices } => (
+                    *style,
+                    ruffle_path_to_lyon_path(&TexturedVertex::triangle_commands(vertices), true),
+                    false,
+                ),
             };
 
             let (draw, color, needs_flush) = match fill_style {
@@ -157,6 +188,11 @@ impl ShapeTessellator {
                     &FillOptions::default().with_fill_rule(winding_rule.into()),
                     &mut buffers_builder,
                 ),
+                DrawPath::TexturedTriangles { .. } => self.fill_tess.tessellate_path(
+                    &lyon_path,
+                    &FillOptions::default().with_fill_rule(FillRule::NonZero.into()),
+                    &mut buffers_builder,
+                ),
                 DrawPath::Stroke { style, .. } => {
                     // TODO(Herschel): 0 width indicates "hairline".
                     let width = (style.width().to_pixels() as f32).max(1.0);
@@ -219,6 +255,29 @@ impl ShapeTessellator {
         }
     }
 
+    /// Adds the triangles as a draw of their own, without going through lyon.
+    fn tessellate_textured_triangles(
+        &mut self,
+        mut triangles: TexturedTriangles,
+        vertices: &[TexturedVertex],
+    ) {
+        self.flush_draw(DrawType::Color);
+        self.is_stroke = false;
+
+        let vertices = &vertices[..vertices.len() - vertices.len() % 3];
+        for vertex in vertices {
+            self.lyon_mesh.vertices.push(Vertex {
+                x: vertex.position.x.to_pixels() as f32,
+                y: vertex.position.y.to_pixels() as f32,
+                color: swf::Color::WHITE,
+            });
+            triangles.uvts.push([vertex.u, vertex.v, vertex.t]);
+        }
+        self.lyon_mesh.indices.extend(0..vertices.len() as u32);
+
+        self.flush_draw(DrawType::TexturedTriangles(triangles));
+    }
+
     fn flush_draw(&mut self, draw: DrawType) {
         if self.lyon_mesh.vertices.is_empty() || self.lyon_mesh.indices.len() < 3 {
             // Ignore degenerate fills
@@ -264,6 +323,7 @@ pub enum DrawType {
         gradient: usize,
     },
     Bitmap(Bitmap),
+    TexturedTriangles(TexturedTriangles),
 }
 
 impl DrawType {
@@ -272,6 +332,7 @@ impl DrawType {
             Self::Color => "Color",
             Self::Gradient { .. } => "Gradient",
             Self::Bitmap { .. } => "Bitmap",
+            Self::TexturedTriangles { .. } => "TexturedTriangles",
         }
     }
 }
@@ -300,6 +361,17 @@ pub struct Bitmap {
     pub is_repeating: bool,
 }
 
+/// A bitmap mapped onto triangles using per-vertex texture coordinates.
+#[derive(Clone, Debug)]
+pub struct TexturedTriangles {
+    pub bitmap_id: u16,
+    pub is_smoothed: bool,
+    pub is_repeating: bool,
+
+    /// The `(u, v, t)` texture coordinates of every vertex of the draw, in the same order.
+    pub uvts: Vec<[f32; 3]>,
+}
+
 #[allow(clippy::many_single_char_names)]
 fn swf_to_gl_matrix(m: crate::matrix::Matrix) -> [[f32; 3]; 3] {
     let tx = m.tx.get() as f32;
@@ -14,6 +14,7 @@ use ruffle_render::bitmap::{
 use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
 use ruffle_render::error::Error as BitmapError;
 use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender::interpreter::{self, InterpretedShader};
 use ruffle_render::quality::StageQuality;
 use ruffle_render::shape_utils::{DistilledShape, GradientType};
 use ruffle_render::tessellator::{
@@ -79,7 +80,9 @@ const COLOR_FRAGMENT_GLSL: &str = include_str!("../shaders/color.frag");
 const TEXTURE_VERTEX_GLSL: &str = include_str!("../shaders/texture.vert");
 const GRADIENT_FRAGMENT_GLSL: &str = include_str!("../shaders/gradient.frag");
 const BITMAP_FRAGMENT_GLSL: &str = include_str!("../shaders/bitmap.frag");
-const NUM_VERTEX_ATTRIBUTES: u32 = 2;
+const TEXTURE_UVT_VERTEX_GLSL: &str = include_str!("../shaders/texture_uvt.vert");
+const BITMAP_UVT_FRAGMENT_GLSL: &str = include_str!("../shaders/bitmap_uvt.frag");
+const NUM_VERTEX_ATTRIBUTES: u32 = 3;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MaskState {
@@ -110,6 +113,23 @@ impl From<TessVertex> for Vertex {
     }
 }
 
+/// A vertex of textured triangles, with its texture coordinates stored as `(u * t, v * t, t)`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct UvtVertex {
+    position: [f32; 2],
+    uvt: [f32; 3],
+}
+
+impl UvtVertex {
+    fn new(vertex: TessVertex, [u, v, t]: [f32; 3]) -> Self {
+        Self {
+            position: [vertex.x, vertex.y],
+            uvt: [u * t, v * t, t],
+        }
+    }
+}
+
 pub struct WebGlRenderBackend {
     /// WebGL1 context
     gl: Gl,
@@ -126,6 +146,7 @@ pub struct WebGlRenderBackend {
 
     color_program: ShaderProgram,
     bitmap_program: ShaderProgram,
+    bitmap_uvt_program: ShaderProgram,
     gradient_program: ShaderProgram,
 
     shape_tessellator: ShapeTessellator,
@@ -275,10 +296,16 @@ impl WebGlRenderBackend {
         let bitmap_fragment = Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, BITMAP_FRAGMENT_GLSL)?;
         let gradient_fragment =
             Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, GRADIENT_FRAGMENT_GLSL)?;
+        let texture_uvt_vertex =
+            Self::compile_shader(&gl, Gl::VERTEX_SHADER, TEXTURE_UVT_VERTEX_GLSL)?;
+        let bitmap_uvt_fragment =
+            Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, BITMAP_UVT_FRAGMENT_GLSL)?;
 
         let color_program = ShaderProgram::new(&gl, &color_vertex, &color_fragment)?;
         let bitmap_program = ShaderProgram::new(&gl, &texture_vertex, &bitmap_fragment)?;
         let gradient_program = ShaderProgram::new(&gl, &texture_vertex, &gradient_fragment)?;
+        let bitmap_uvt_program =
+            ShaderProgram::new(&gl, &texture_uvt_vertex, &bitmap_uvt_fragment)?;
 
         gl.enable(Gl::BLEND);
 
@@ -296,6 +323,7 @@ impl WebGlRenderBackend {
             color_program,
             gradient_program,
             bitmap_program,
+            bitmap_uvt_program,
 
             shape_tessellator: ShapeTessellator::new(),
 
@@ -582,12 +610,29 @@ impl WebGlRenderBackend {
             let vertex_buffer = self.gl.create_buffer().ok_or(Error::UnableToCreateBuffer)?;
             self.gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&vertex_buffer));
 
-            let vertices: Vec<_> = draw.vertices.into_iter().map(Vertex::from).collect();
-            self.gl.buffer_data_with_u8_array(
-                Gl::ARRAY_BUFFER,
-                bytemuck::cast_slice(&vertices),
-                Gl::STATIC_DRAW,
-            );
+            // Textured triangles carry their texture coordinates instead of a color.
+            let vertex_size = if let TessDrawType::TexturedTriangles(triangles) = &draw.draw_type {
+                let vertices: Vec<_> = draw
+                    .vertices
+                    .into_iter()
+                    .zip(triangles.uvts.iter())
+                    .map(|(vertex, uvt)| UvtVertex::new(vertex, *uvt))
+                    .collect();
+                self.gl.buffer_data_with_u8_array(
+                    Gl::ARRAY_BUFFER,
+                    bytemuck::cast_slice(&vertices),
+                    Gl::STATIC_DRAW,
+                );
+                std::mem::size_of::<UvtVertex>() as i32
+            } else {
+                let vertices: Vec<_> = draw.vertices.into_iter().map(Vertex::from).collect();
+                self.gl.buffer_data_with_u8_array(
+                    Gl::ARRAY_BUFFER,
+                    bytemuck::cast_slice(&vertices),
+                    Gl::STATIC_DRAW,
+                );
+                std::mem::size_of::<Vertex>() as i32
+            };
 
             let index_buffer = self.gl.create_buffer().ok_or(Error::UnableToCreateBuffer)?;
             self.gl
@@ -602,6 +647,7 @@ impl WebGlRenderBackend {
                 TessDrawType::Color => &self.color_program,
                 TessDrawType::Gradient { .. } => &self.gradient_program,
                 TessDrawType::Bitmap(_) => &self.bitmap_program,
+                TessDrawType::TexturedTriangles(_) => &self.bitmap_uvt_program,
             };
 
             // Unfortunately it doesn't seem to be possible to ensure that vertex attributes will be in
@@ -614,7 +660,7 @@ impl WebGlRenderBackend {
                     2,
                     Gl::FLOAT,
                     false,
-                    12,
+                    vertex_size,
                     0,
                 );
                 self.gl
@@ -627,13 +673,26 @@ impl WebGlRenderBackend {
                     4,
                     Gl::UNSIGNED_BYTE,
                     true,
-                    12,
+                    vertex_size,
                     8,
                 );
                 self.gl
                     .enable_vertex_attrib_array(program.vertex_color_location);
             }
 
+            if program.vertex_uvt_location != 0xffff_ffff {
+                self.gl.vertex_attrib_pointer_with_i32(
+                    program.vertex_uvt_location,
+                    3,
+                    Gl::FLOAT,
+                    false,
+                    vertex_size,
+                    8,
+                );
+                self.gl
+                    .enable_vertex_attrib_array(program.vertex_uvt_location);
+            }
+
             let num_vertex_attributes = program.num_vertex_attributes;
 
             draws.push(match draw.draw_type {
@@ -687,6 +746,26 @@ impl WebGlRenderBackend {
                     num_indices,
                     num_mask_indices,
                 },
+                TessDrawType::TexturedTriangles(triangles) => Draw {
+                    draw_type: DrawType::TexturedTriangles(BitmapDraw {
+                        // Unused, the texture coordinates come from the vertices.
+                        matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+                        handle: bitmap_source.bitmap_handle(triangles.bitmap_id, self),
+                        is_smoothed: triangles.is_smoothed,
+                        is_repeating: triangles.is_repeating,
+                    }),
+                    vao,
+                    vertex_buffer: Buffer {
+                        gl: self.gl.clone(),
+                        buffer: vertex_buffer,
+                    },
+                    index_buffer: Buffer {
+                        gl: self.gl.clone(),
+                        buffer: index_buffer,
+                    },
+                    num_indices,
+                    num_mask_indices,
+                },
             });
 
             self.bind_vertex_array(None);
@@ -1197,11 +1276,10 @@ impl RenderBackend for WebGlRenderBackend {
 
     fn compile_pixelbender_shader(
         &mut self,
-        _shader: ruffle_render::pixel_bender::PixelBenderShader,
+        shader: ruffle_render::pixel_bender::PixelBenderShader,
     ) -> Result<ruffle_render::pixel_bender::PixelBenderShaderHandle, BitmapError> {
-        Err(BitmapError::Unimplemented(
-            "compile_pixelbender_shader".into(),
-        ))
+        // Shaders are run on the CPU.
+        Ok(InterpretedShader::new_handle(shader))
     }
 
     fn resolve_sync_handle(
@@ -1216,11 +1294,11 @@ impl RenderBackend for WebGlRenderBackend {
 
     fn run_pixelbender_shader(
         &mut self,
-        _handle: ruffle_render::pixel_bender::PixelBenderShaderHandle,
-        _arguments: &[ruffle_render::pixel_bender::PixelBenderShaderArgument],
-        _target: &PixelBenderTarget,
+        handle: ruffle_render::pixel_bender::PixelBenderShaderHandle,
+        arguments: &[ruffle_render::pixel_bender::PixelBenderShaderArgument],
+        target: &PixelBenderTarget,
     ) -> Result<PixelBenderOutput, BitmapError> {
-        Err(BitmapError::Unimplemented("run_pixelbender_shader".into()))
+        interpreter::run_pixelbender_shader(&handle, arguments, target)
     }
 
     fn create_empty_texture(
@@ -1386,6 +1464,7 @@ impl CommandHandler for WebGlRenderBackend {
                 DrawType::Color => &self.color_program,
                 DrawType::Gradient(_) => &self.gradient_program,
                 DrawType::Bitmap { .. } => &self.bitmap_program,
+                DrawType::TexturedTriangles { .. } => &self.bitmap_uvt_program,
             };
 
             // Set common render state, while minimizing unnecessary state changes.
@@ -1447,7 +1526,7 @@ impl CommandHandler for WebGlRenderBackend {
                         (gradient.interpolation == swf::GradientInterpolation::LinearRgb) as i32,
                     );
                 }
-                DrawType::Bitmap(bitmap) => {
+                DrawType::Bitmap(bitmap) | DrawType::TexturedTriangles(bitmap) => {
                     let texture = match &bitmap.handle {
                         Some(handle) => &as_registry_data(handle).texture,
                         None => {
@@ -1678,6 +1757,7 @@ enum DrawType {
     Color,
     Gradient(Box<Gradient>),
     Bitmap(BitmapDraw),
+    TexturedTriangles(BitmapDraw),
 }
 
 struct MsaaBuffers {
@@ -1696,6 +1776,7 @@ struct ShaderProgram {
     uniforms: [Option<WebGlUniformLocation>; NUM_UNIFORMS],
     vertex_position_location: u32,
     vertex_color_location: u32,
+    vertex_uvt_location: u32,
     num_vertex_attributes: u32,
 }
 
@@ -1763,6 +1844,7 @@ impl ShaderProgram {
 
         let vertex_position_location = gl.get_attrib_location(&program, "position") as u32;
         let vertex_color_location = gl.get_attrib_location(&program, "color") as u32;
+        let vertex_uvt_location = gl.get_attrib_location(&program, "uvt") as u32;
         let num_vertex_attributes = if vertex_position_location != 0xffff_ffff {
             1
         } else {
@@ -1771,6 +1853,10 @@ impl ShaderProgram {
             1
         } else {
             0
+        } + if vertex_uvt_location != 0xffff_ffff {
+            1
+        } else {
+            0
         };
 
         Ok(ShaderProgram {
@@ -1778,6 +1864,7 @@ impl ShaderProgram {
             uniforms,
             vertex_position_location,
             vertex_color_location,
+            vertex_uvt_location,
             num_vertex_attributes,
         })
     }
@@ -0,0 +1,42 @@
+/// Shader used for drawing triangles textured with per-vertex UVT coordinates,
+/// as drawn by `Graphics.drawTriangles`.
+/// NOTE: The `common.wgsl` source is prepended to this before compilation.
+
+struct VertexInput {
+    /// The position of the vertex in object space.
+    @location(0) position: vec2<f32>,
+
+    /// The texture coordinates of the vertex, premultiplied by its T value: `(u * t, v * t, t)`.
+    @location(1) uvt: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uvt: vec3<f32>,
+};
+
+@group(1) @binding(0) var<uniform> transforms: common__Transforms;
+// The texture matrix at binding 0 is unused, as the coordinates come from the vertices.
+@group(2) @binding(1) var texture: texture_2d<f32>;
+@group(2) @binding(2) var texture_sampler: sampler;
+
+@vertex
+fn main_vertex(in: VertexInput) -> VertexOutput {
+    let pos = common__globals.view_matrix * transforms.world_matrix * vec4<f32>(in.position.x, in.position.y, 0.0, 1.0);
+    return VertexOutput(pos, in.uvt);
+}
+
+@fragment
+fn main_fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Dividing the linearly interpolated values by T gives perspective-correct coordinates.
+    let uv = in.uvt.xy / in.uvt.z;
+    var color: vec4<f32> = textureSample(texture, texture_sampler, uv);
+    // Texture is premultiplied by alpha.
+    // Unmultiply alpha, apply color transform, remultiply alpha.
+    if( color.a > 0.0 ) {
+        color = vec4<f32>(color.rgb / color.a, color.a);
+        color = saturate(color * transforms.mult_color + transforms.add_color);
+        color = vec4<f32>(color.rgb * color.a, color.a);
+    }
+    return color;
+}
@@ -0,0 +1,64 @@
+// NOTE: The `shader_filter_common.wgsl` source is prepended to this before compilation.
+
+struct Filter {
+    /// The matrix, four values per element, row by row.
+    matrix: array<vec4<f32>, 64>,
+    default_color: vec4<f32>,
+    source_point: vec2<i32>,
+    source_size: vec2<i32>,
+    matrix_x: i32,
+    matrix_y: i32,
+    divisor: f32,
+    bias: f32,
+    clamp: u32,
+    preserve_alpha: u32,
+}
+
+@group(0) @binding(0) var texture: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> filter_args: Filter;
+
+@vertex
+fn main_vertex(in: filter__VertexInput) -> filter__VertexOutput {
+    return filter__main_vertex(in);
+}
+
+fn matrix_value(index: i32) -> f32 {
+    return filter_args.matrix[index / 4][index % 4];
+}
+
+/// Loads a pixel of the source with its alpha unmultiplied.
+fn load(position: vec2<i32>) -> vec4<f32> {
+    var color: vec4<f32>;
+    if (all(position >= vec2<i32>(0)) && all(position < filter_args.source_size)) {
+        color = textureLoad(texture, filter_args.source_point + position, 0);
+    } else if (filter_args.clamp > 0u) {
+        let clamped = clamp(position, vec2<i32>(0), filter_args.source_size - 1);
+        color = textureLoad(texture, filter_args.source_point + clamped, 0);
+    } else {
+        return filter_args.default_color;
+    }
+    if (color.a > 0.0) {
+        return vec4<f32>(color.rgb / color.a, color.a);
+    }
+    return vec4<f32>(0.0);
+}
+
+@fragment
+fn main_fragment(in: filter__VertexOutput) -> @location(0) vec4<f32> {
+    let position = vec2<i32>(floor(in.position.xy));
+    let center = vec2<i32>(filter_args.matrix_x / 2, filter_args.matrix_y / 2);
+
+    var sum = vec4<f32>(0.0);
+    for (var y = 0; y < filter_args.matrix_y; y++) {
+        for (var x = 0; x < filter_args.matrix_x; x++) {
+            let weight = matrix_value(y * filter_args.matrix_x + x);
+            sum += weight * load(position + vec2<i32>(x, y) - center);
+        }
+    }
+
+    var color = saturate(sum / filter_args.divisor + filter_args.bias / 255.0);
+    if (filter_args.preserve_alpha > 0u) {
+        color.a = load(position).a;
+    }
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
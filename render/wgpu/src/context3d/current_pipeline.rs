@@ -64,6 +64,11 @@ pub struct CurrentPipeline {
     depth_mask: bool,
     pass_compare_mode: wgpu::CompareFunction,
 
+    stencil_front: StencilFaceState,
+    stencil_back: StencilFaceState,
+    stencil_read_mask: u32,
+    stencil_write_mask: u32,
+
     color_component: wgpu::BlendComponent,
     alpha_component: wgpu::BlendComponent,
 
@@ -122,6 +127,10 @@ impl CurrentPipeline {
 
             depth_mask: true,
             pass_compare_mode: wgpu::CompareFunction::LessEqual,
+            stencil_front: StencilFaceState::IGNORE,
+            stencil_back: StencilFaceState::IGNORE,
+            stencil_read_mask: 0xFF,
+            stencil_write_mask: 0xFF,
             color_component: wgpu::BlendComponent::REPLACE,
             alpha_component: wgpu::BlendComponent::REPLACE,
             sample_count: 1,
@@ -184,6 +193,26 @@ impl CurrentPipeline {
         self.pass_compare_mode = pass_compare_mode;
     }
 
+    pub fn update_stencil_faces(
+        &mut self,
+        stencil_front: StencilFaceState,
+        stencil_back: StencilFaceState,
+    ) {
+        if self.stencil_front != stencil_front || self.stencil_back != stencil_back {
+            self.dirty.set(true);
+        }
+        self.stencil_front = stencil_front;
+        self.stencil_back = stencil_back;
+    }
+
+    pub fn update_stencil_masks(&mut self, read_mask: u32, write_mask: u32) {
+        if self.stencil_read_mask != read_mask || self.stencil_write_mask != write_mask {
+            self.dirty.set(true);
+        }
+        self.stencil_read_mask = read_mask;
+        self.stencil_write_mask = write_mask;
+    }
+
     pub fn update_has_depth_texture(&mut self, has_depth_texture: bool) {
         if self.has_depth_texture != has_depth_texture {
             self.dirty.set(true);
@@ -446,12 +475,11 @@ impl CurrentPipeline {
                 format: TextureFormat::Depth24PlusStencil8,
                 depth_write_enabled: self.depth_mask,
                 depth_compare: self.pass_compare_mode,
-                // FIXME - implement this
                 stencil: wgpu::StencilState {
-                    front: StencilFaceState::IGNORE,
-                    back: StencilFaceState::IGNORE,
-                    read_mask: !0,
-                    write_mask: !0,
+                    front: self.stencil_front,
+                    back: self.stencil_back,
+                    read_mask: self.stencil_read_mask,
+                    write_mask: self.stencil_write_mask,
                 },
                 bias: Default::default(),
             })
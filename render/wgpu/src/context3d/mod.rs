@@ -1,6 +1,7 @@
 use ruffle_render::backend::{
     Context3D, Context3DBlendFactor, Context3DCommand, Context3DCompareMode, Context3DProfile,
-    Context3DTextureFormat, Context3DVertexBufferFormat, IndexBuffer, ProgramType, VertexBuffer,
+    Context3DStencilAction, Context3DTextureFormat, Context3DTriangleFace,
+    Context3DVertexBufferFormat, IndexBuffer, ProgramType, VertexBuffer,
 };
 use ruffle_render::bitmap::BitmapHandle;
 use ruffle_render::error::Error;
@@ -96,6 +97,7 @@ pub struct WgpuContext3D {
     seen_clear_command: bool,
 
     scissor_rectangle: Option<Rectangle<Twips>>,
+    stencil_reference: u32,
 }
 
 impl WgpuContext3D {
@@ -170,6 +172,7 @@ impl WgpuContext3D {
             clear_color: None,
             seen_clear_command: false,
             scissor_rectangle: None,
+            stencil_reference: 0,
         }
     }
 
@@ -297,6 +300,7 @@ impl WgpuContext3D {
                 .as_ref()
                 .expect("Missing compiled pipeline"),
         );
+        pass.set_stencil_reference(self.stencil_reference);
         if let Some(rect) = &self.scissor_rectangle {
             let current_size = self.current_texture_size.unwrap();
             if rect.x_min.to_pixels() < 0.0
@@ -1124,18 +1128,43 @@ impl Context3D for WgpuContext3D {
                 depth_mask,
                 pass_compare_mode,
             } => {
-                let function = match pass_compare_mode {
-                    Context3DCompareMode::Always => wgpu::CompareFunction::Always,
-                    Context3DCompareMode::Equal => wgpu::CompareFunction::Equal,
-                    Context3DCompareMode::Greater => wgpu::CompareFunction::Greater,
-                    Context3DCompareMode::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
-                    Context3DCompareMode::Less => wgpu::CompareFunction::Less,
-                    Context3DCompareMode::LessEqual => wgpu::CompareFunction::LessEqual,
-                    Context3DCompareMode::Never => wgpu::CompareFunction::Never,
-                    Context3DCompareMode::NotEqual => wgpu::CompareFunction::NotEqual,
-                };
+                let function = convert_compare_mode(pass_compare_mode);
                 self.current_pipeline.update_depth(depth_mask, function);
             }
+            Context3DCommand::SetStencilActions {
+                face,
+                compare_mode,
+                action_on_both_pass,
+                action_on_depth_fail,
+                action_on_depth_pass_stencil_fail,
+            } => {
+                let face_state = wgpu::StencilFaceState {
+                    compare: convert_compare_mode(compare_mode),
+                    fail_op: convert_stencil_action(action_on_depth_pass_stencil_fail),
+                    depth_fail_op: convert_stencil_action(action_on_depth_fail),
+                    pass_op: convert_stencil_action(action_on_both_pass),
+                };
+                // Faces that weren't selected don't take part in the stencil test at all.
+                let (front, back) = match face {
+                    Context3DTriangleFace::None => (
+                        wgpu::StencilFaceState::IGNORE,
+                        wgpu::StencilFaceState::IGNORE,
+                    ),
+                    Context3DTriangleFace::Front => (face_state, wgpu::StencilFaceState::IGNORE),
+                    Context3DTriangleFace::Back => (wgpu::StencilFaceState::IGNORE, face_state),
+                    Context3DTriangleFace::FrontAndBack => (face_state, face_state),
+                };
+                self.current_pipeline.update_stencil_faces(front, back);
+            }
+            Context3DCommand::SetStencilReferenceValue {
+                reference_value,
+                read_mask,
+                write_mask,
+            } => {
+                self.stencil_reference = reference_value;
+                self.current_pipeline
+                    .update_stencil_masks(read_mask, write_mask);
+            }
             Context3DCommand::SetBlendFactors {
                 source_factor,
                 destination_factor,
@@ -1222,6 +1251,32 @@ pub struct ClearColor {
     mask: u32,
 }
 
+fn convert_compare_mode(input: Context3DCompareMode) -> wgpu::CompareFunction {
+    match input {
+        Context3DCompareMode::Always => wgpu::CompareFunction::Always,
+        Context3DCompareMode::Equal => wgpu::CompareFunction::Equal,
+        Context3DCompareMode::Greater => wgpu::CompareFunction::Greater,
+        Context3DCompareMode::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
+        Context3DCompareMode::Less => wgpu::CompareFunction::Less,
+        Context3DCompareMode::LessEqual => wgpu::CompareFunction::LessEqual,
+        Context3DCompareMode::Never => wgpu::CompareFunction::Never,
+        Context3DCompareMode::NotEqual => wgpu::CompareFunction::NotEqual,
+    }
+}
+
+fn convert_stencil_action(input: Context3DStencilAction) -> wgpu::StencilOperation {
+    match input {
+        Context3DStencilAction::DecrementSaturate => wgpu::StencilOperation::DecrementClamp,
+        Context3DStencilAction::DecrementWrap => wgpu::StencilOperation::DecrementWrap,
+        Context3DStencilAction::IncrementSaturate => wgpu::StencilOperation::IncrementClamp,
+        Context3DStencilAction::IncrementWrap => wgpu::StencilOperation::IncrementWrap,
+        Context3DStencilAction::Invert => wgpu::StencilOperation::Invert,
+        Context3DStencilAction::Keep => wgpu::StencilOperation::Keep,
+        Context3DStencilAction::Set => wgpu::StencilOperation::Replace,
+        Context3DStencilAction::Zero => wgpu::StencilOperation::Zero,
+    }
+}
+
 fn convert_texture_format(input: Context3DTextureFormat) -> Result<wgpu::TextureFormat, Error> {
     match input {
         // Some of these formats are unsupported by wgpu to various degrees:
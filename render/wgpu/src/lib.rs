@@ -119,6 +119,23 @@ impl From<TessVertex> for PosColorVertex {
     }
 }
 
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct PosUvtVertex {
+    position: [f32; 2],
+    /// Stored as `(u * t, v * t, t)`, to be interpolated linearly.
+    uvt: [f32; 3],
+}
+
+impl PosUvtVertex {
+    fn new(vertex: TessVertex, [u, v, t]: [f32; 3]) -> Self {
+        Self {
+            position: [vertex.x, vertex.y],
+            uvt: [u * t, v * t, t],
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 struct GradientUniforms {
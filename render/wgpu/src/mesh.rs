@@ -1,7 +1,8 @@
 use crate::backend::WgpuRenderBackend;
 use crate::target::RenderTarget;
 use crate::{
-    as_texture, Descriptors, GradientUniforms, PosColorVertex, PosVertex, TextureTransforms,
+    as_texture, Descriptors, GradientUniforms, PosColorVertex, PosUvtVertex, PosVertex,
+    TextureTransforms,
 };
 use std::ops::Range;
 use wgpu::util::DeviceExt;
@@ -9,7 +10,9 @@ use wgpu::util::DeviceExt;
 use crate::buffer_builder::BufferBuilder;
 use ruffle_render::backend::{RenderBackend, ShapeHandle, ShapeHandleImpl};
 use ruffle_render::bitmap::BitmapSource;
-use ruffle_render::tessellator::{Bitmap, Draw as LyonDraw, DrawType as TessDrawType, Gradient};
+use ruffle_render::tessellator::{
+    Bitmap, Draw as LyonDraw, DrawType as TessDrawType, Gradient, TexturedTriangles,
+};
 use swf::{CharacterId, GradientInterpolation};
 
 /// How big to make gradient textures. Larger will keep more detail, but be slower and use more memory.
@@ -86,6 +89,16 @@ impl PendingDraw {
             vertex_buffer
                 .add(&vertices)
                 .expect("Mesh vertex buffer was too large!")
+        } else if let TessDrawType::TexturedTriangles(triangles) = &draw.draw_type {
+            let vertices: Vec<_> = draw
+                .vertices
+                .into_iter()
+                .zip(triangles.uvts.iter())
+                .map(|(vertex, uvt)| PosUvtVertex::new(vertex, *uvt))
+                .collect();
+            vertex_buffer
+                .add(&vertices)
+                .expect("Mesh vertex buffer was too large!")
         } else {
             let vertices: Vec<_> = draw.vertices.into_iter().map(PosVertex::from).collect();
             vertex_buffer
@@ -106,6 +119,14 @@ impl PendingDraw {
             TessDrawType::Bitmap(bitmap) => {
                 PendingDrawType::bitmap(bitmap, shape_id, draw_id, source, backend, uniform_buffer)?
             }
+            TessDrawType::TexturedTriangles(triangles) => PendingDrawType::textured_triangles(
+                triangles,
+                shape_id,
+                draw_id,
+                source,
+                backend,
+                uniform_buffer,
+            )?,
         };
         Some(PendingDraw {
             draw_type,
@@ -133,6 +154,13 @@ pub enum PendingDrawType {
         is_smoothed: bool,
         bind_group_label: Option<String>,
     },
+    TexturedTriangles {
+        texture_transforms_index: wgpu::BufferAddress,
+        texture_view: wgpu::TextureView,
+        is_repeating: bool,
+        is_smoothed: bool,
+        bind_group_label: Option<String>,
+    },
 }
 
 /// Converts an RGBA color from sRGB space to linear color space.
@@ -195,6 +223,36 @@ impl PendingDrawType {
         })
     }
 
+    pub fn textured_triangles(
+        triangles: TexturedTriangles,
+        shape_id: CharacterId,
+        draw_id: usize,
+        source: &dyn BitmapSource,
+        backend: &mut dyn RenderBackend,
+        uniform_buffers: &mut BufferBuilder,
+    ) -> Option<Self> {
+        let handle = source.bitmap_handle(triangles.bitmap_id, backend)?;
+        let texture = as_texture(&handle);
+        let texture_view = texture.texture.create_view(&Default::default());
+        // The texture coordinates come from the vertices, so the bitmap bind group
+        // only needs a placeholder matrix.
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let texture_transforms_index = create_texture_transforms(&identity, uniform_buffers);
+        let bind_group_label = create_debug_label!(
+            "Shape {} (textured triangles) draw {} bindgroup",
+            shape_id,
+            draw_id
+        );
+
+        Some(PendingDrawType::TexturedTriangles {
+            texture_transforms_index,
+            texture_view,
+            is_repeating: triangles.is_repeating,
+            is_smoothed: triangles.is_smoothed,
+            bind_group_label,
+        })
+    }
+
     pub fn finish(
         self,
         descriptors: &Descriptors,
@@ -270,6 +328,27 @@ impl PendingDrawType {
 
                 DrawType::Bitmap { binds }
             }
+            PendingDrawType::TexturedTriangles {
+                texture_transforms_index,
+                texture_view,
+                is_repeating,
+                is_smoothed,
+                bind_group_label,
+            } => {
+                let binds = BitmapBinds::new(
+                    &descriptors.device,
+                    &descriptors.bind_layouts.bitmap,
+                    descriptors
+                        .bitmap_samplers
+                        .get_sampler(is_repeating, is_smoothed),
+                    uniform_buffer,
+                    texture_transforms_index,
+                    texture_view,
+                    bind_group_label,
+                );
+
+                DrawType::TexturedTriangles { binds }
+            }
         }
     }
 }
@@ -280,6 +359,7 @@ pub enum DrawType {
     Color,
     Gradient { bind_group: wgpu::BindGroup },
     Bitmap { binds: BitmapBinds },
+    TexturedTriangles { binds: BitmapBinds },
 }
 
 #[derive(Debug)]
@@ -1,7 +1,7 @@
 use crate::blend::{ComplexBlend, TrivialBlend};
 use crate::layouts::BindLayouts;
 use crate::shaders::Shaders;
-use crate::{MaskState, PosColorVertex, PosVertex};
+use crate::{MaskState, PosColorVertex, PosUvtVertex, PosVertex};
 use enum_map::{enum_map, Enum, EnumMap};
 use std::collections::HashMap;
 use wgpu::{vertex_attr_array, BlendState, PrimitiveTopology};
@@ -25,6 +25,16 @@ pub const VERTEX_BUFFERS_DESCRIPTION_COLOR: [wgpu::VertexBufferLayout; 1] =
         ],
     }];
 
+pub const VERTEX_BUFFERS_DESCRIPTION_UVT: [wgpu::VertexBufferLayout; 1] =
+    [wgpu::VertexBufferLayout {
+        array_stride: std::mem::size_of::<PosUvtVertex>() as u64,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &vertex_attr_array![
+            0 => Float32x2,
+            1 => Float32x3,
+        ],
+    }];
+
 #[derive(Debug)]
 pub struct ShapePipeline {
     pub pipelines: EnumMap<MaskState, wgpu::RenderPipeline>,
@@ -45,6 +55,8 @@ pub struct Pipelines {
     /// or use it in any way.
     pub bitmap_opaque_dummy_stencil: wgpu::RenderPipeline,
     pub bitmap: EnumMap<TrivialBlend, ShapePipeline>,
+    /// Renders triangles with per-vertex texture coordinates.
+    pub bitmap_uvt: ShapePipeline,
     pub gradients: ShapePipeline,
     pub complex_blends: EnumMap<ComplexBlend, ShapePipeline>,
 }
@@ -183,6 +195,19 @@ impl Pipelines {
             .try_into()
             .unwrap();
 
+        let bitmap_uvt_pipeline = create_shape_pipeline(
+            "Bitmap UVT",
+            device,
+            format,
+            &shaders.bitmap_uvt_shader,
+            msaa_sample_count,
+            &VERTEX_BUFFERS_DESCRIPTION_UVT,
+            &bitmap_blend_bindings,
+            BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            &[],
+            PrimitiveTopology::TriangleList,
+        );
+
         let bitmap_opaque_pipeline_layout_label =
             create_debug_label!("Opaque bitmap pipeline layout");
         let bitmap_opaque_pipeline_layout =
@@ -241,6 +266,7 @@ impl Pipelines {
             color: color_pipelines,
             lines: lines_pipelines,
             bitmap: EnumMap::from_array(bitmap_pipelines),
+            bitmap_uvt: bitmap_uvt_pipeline,
             bitmap_opaque,
             bitmap_opaque_dummy_stencil: bitmap_opaque_dummy_depth,
             gradients: gradient_pipeline,
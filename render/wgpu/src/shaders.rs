@@ -12,6 +12,7 @@ pub struct Shaders {
     /// avoid changing initially-in-range rgb values (regadless of whether
     /// dividing by the alpha value would produce an out-of-range value).
     pub bitmap_shader: wgpu::ShaderModule,
+    pub bitmap_uvt_shader: wgpu::ShaderModule,
     pub gradient_shader: wgpu::ShaderModule,
     pub copy_srgb_shader: wgpu::ShaderModule,
     pub copy_shader: wgpu::ShaderModule,
@@ -21,6 +22,8 @@ pub struct Shaders {
     pub glow_filter: wgpu::ShaderModule,
     pub bevel_filter: wgpu::ShaderModule,
     pub displacement_map_filter: wgpu::ShaderModule,
+    pub gradient_filter: wgpu::ShaderModule,
+    pub convolution_filter: wgpu::ShaderModule,
 }
 
 impl Shaders {
@@ -31,6 +34,11 @@ impl Shaders {
             "bitmap.wgsl",
             include_str!("../shaders/bitmap.wgsl"),
         );
+        let bitmap_uvt_shader = make_shader(
+            device,
+            "bitmap_uvt.wgsl",
+            include_str!("../shaders/bitmap_uvt.wgsl"),
+        );
         let copy_srgb_shader = make_shader(
             device,
             "copy_srgb.wgsl",
@@ -62,6 +70,16 @@ impl Shaders {
             "filter/displacement_map.wgsl",
             include_str!("../shaders/filter/displacement_map.wgsl"),
         );
+        let gradient_filter = make_filter_shader(
+            device,
+            "filter/gradient.wgsl",
+            include_str!("../shaders/filter/gradient.wgsl"),
+        );
+        let convolution_filter = make_filter_shader(
+            device,
+            "filter/convolution.wgsl",
+            include_str!("../shaders/filter/convolution.wgsl"),
+        );
         let gradient_shader = make_shader(
             device,
             "gradient.wgsl",
@@ -83,6 +101,7 @@ impl Shaders {
         Self {
             color_shader,
             bitmap_shader,
+            bitmap_uvt_shader,
             gradient_shader,
             copy_srgb_shader,
             copy_shader,
@@ -92,6 +111,8 @@ impl Shaders {
             glow_filter,
             bevel_filter,
             displacement_map_filter,
+            gradient_filter,
+            convolution_filter,
         }
     }
 }
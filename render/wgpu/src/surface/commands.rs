@@ -140,6 +140,18 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
         self.render_pass.set_bind_group(2, bind_group, &[]);
     }
 
+    pub fn prep_bitmap_uvt(&mut self, bind_group: &'pass wgpu::BindGroup) {
+        if self.needs_stencil {
+            self.render_pass
+                .set_pipeline(self.pipelines.bitmap_uvt.pipeline_for(self.mask_state));
+        } else {
+            self.render_pass
+                .set_pipeline(self.pipelines.bitmap_uvt.stencilless_pipeline());
+        }
+
+        self.render_pass.set_bind_group(2, bind_group, &[]);
+    }
+
     pub fn prep_bitmap(
         &mut self,
         bind_group: &'pass wgpu::BindGroup,
@@ -280,6 +292,9 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
                 DrawType::Bitmap { binds, .. } => {
                     self.prep_bitmap(&binds.bind_group, TrivialBlend::Normal, false);
                 }
+                DrawType::TexturedTriangles { binds } => {
+                    self.prep_bitmap_uvt(&binds.bind_group);
+                }
             }
             self.render_pass.set_bind_group(
                 1,
@@ -0,0 +1,198 @@
+//! Decoding of the bitmaps defined in SWF files.
+
+use anyhow::{anyhow, Context, Result};
+use image::{ImageFormat, RgbaImage};
+use std::borrow::Cow;
+use std::io::Read;
+use swf::{BitmapFormat, DefineBitsLossless};
+
+/// Glues the encoding tables of a `JpegTables` tag to the JPEG data of a
+/// `DefineBits` tag.
+pub fn glue_tables_to_jpeg<'a>(jpeg_data: &'a [u8], jpeg_tables: Option<&[u8]>) -> Cow<'a, [u8]> {
+    match jpeg_tables {
+        // Both parts have their own SOI and EOI markers, which are dropped.
+        Some(jpeg_tables) if jpeg_tables.len() >= 2 && jpeg_data.len() >= 2 => {
+            [&jpeg_tables[..jpeg_tables.len() - 2], &jpeg_data[2..]]
+                .concat()
+                .into()
+        }
+        _ => jpeg_data.into(),
+    }
+}
+
+/// Decodes the image of a `DefineBits`, `DefineBitsJpeg2` or `DefineBitsJpeg3`
+/// tag, which may hold JPEG, PNG or GIF data. `alpha_data` is the
+/// zlib-compressed alpha channel of a JPEG in a `DefineBitsJpeg3`.
+pub fn decode_jpeg_tag(data: &[u8], alpha_data: Option<&[u8]>) -> Result<RgbaImage> {
+    match data {
+        [0xFF, 0xD8, ..] | [0xFF, 0xD9, 0xFF, 0xD8, ..] => decode_jpeg(data, alpha_data),
+        [0x89, b'P', b'N', b'G', ..] => {
+            Ok(image::load_from_memory_with_format(data, ImageFormat::Png)?.into_rgba8())
+        }
+        [b'G', b'I', b'F', b'8', ..] => {
+            Ok(image::load_from_memory_with_format(data, ImageFormat::Gif)?.into_rgba8())
+        }
+        _ => Err(anyhow!("Unknown image format")),
+    }
+}
+
+fn decode_jpeg(data: &[u8], alpha_data: Option<&[u8]>) -> Result<RgbaImage> {
+    let data = fix_jpeg_markers(data);
+    let mut decoder = jpeg_decoder::Decoder::new(&data[..]);
+    let pixels = decoder.decode()?;
+    let info = decoder
+        .info()
+        .ok_or_else(|| anyhow!("Missing JPEG header"))?;
+    let mut rgba: Vec<u8> = match info.pixel_format {
+        jpeg_decoder::PixelFormat::RGB24 => pixels
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX])
+            .collect(),
+        jpeg_decoder::PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l, u8::MAX]).collect(),
+        jpeg_decoder::PixelFormat::CMYK32 => pixels
+            .chunks_exact(4)
+            .flat_map(|cmyk| {
+                let k = 255 - u16::from(cmyk[3]);
+                let channel = |value: u8| ((255 - u16::from(value)) * k / 255) as u8;
+                [
+                    channel(cmyk[0]),
+                    channel(cmyk[1]),
+                    channel(cmyk[2]),
+                    u8::MAX,
+                ]
+            })
+            .collect(),
+        format => return Err(anyhow!("Unsupported JPEG pixel format {format:?}")),
+    };
+
+    if let Some(alpha_data) = alpha_data {
+        let alpha = decompress_zlib(alpha_data).context("Couldn't decompress the alpha data")?;
+        if alpha.len() != rgba.len() / 4 {
+            return Err(anyhow!("The alpha data doesn't match the size of the JPEG"));
+        }
+        for (pixel, a) in rgba.chunks_exact_mut(4).zip(alpha) {
+            // The colors are premultiplied, and Flash Player clamps them to
+            // the alpha in files where they aren't.
+            for channel in &mut pixel[..3] {
+                *channel = (*channel).min(a);
+            }
+            pixel[3] = a;
+            unmultiply(pixel);
+        }
+    }
+
+    image_from_rgba(info.width.into(), info.height.into(), rgba)
+}
+
+/// Fixes the JPEG markers that Flash Player accepts but standard JPEG decoders
+/// don't: an EOI and SOI marker pair before the SOF marker, which old SWF files
+/// may have, and a missing EOI marker at the end.
+fn fix_jpeg_markers(data: &[u8]) -> Cow<'_, [u8]> {
+    const SOF0: u8 = 0xC0;
+    const RST0: u8 = 0xD0;
+    const RST7: u8 = 0xD7;
+    const SOI: u8 = 0xD8;
+    const EOI: u8 = 0xD9;
+
+    let mut data = Cow::from(data);
+    let mut pos = 0;
+    while let Some(marker) = data.get(pos..pos + 4) {
+        let len = match marker {
+            [0xFF, EOI, 0xFF, SOI] => {
+                data.to_mut().drain(pos..pos + 4);
+                break;
+            }
+            [0xFF, SOF0, _, _] => break,
+            [0xFF, EOI | SOI | RST0..=RST7, _, _] => 0,
+            [0xFF, _, a, b] => u16::from_be_bytes([*a, *b]).into(),
+            _ => break,
+        };
+        pos += len + 2;
+    }
+    if !data.ends_with(&[0xFF, EOI]) {
+        data.to_mut().extend([0xFF, EOI]);
+    }
+    data
+}
+
+/// Decodes the image of a `DefineBitsLossless` or `DefineBitsLossless2` tag.
+pub fn decode_lossless(bitmap: &DefineBitsLossless) -> Result<RgbaImage> {
+    let data = decompress_zlib(&bitmap.data).context("Couldn't decompress the bitmap data")?;
+    let has_alpha = bitmap.version == 2;
+    let (width, height) = (usize::from(bitmap.width), usize::from(bitmap.height));
+    let truncated = || anyhow!("The bitmap data is truncated");
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    match bitmap.format {
+        BitmapFormat::Rgb15 => {
+            // Rows are padded to 32 bits.
+            let row_len = (width * 2 + 3) & !3;
+            for row in 0..height {
+                let row = data
+                    .get(row * row_len..row * row_len + width * 2)
+                    .ok_or_else(truncated)?;
+                for pixel in row.chunks_exact(2) {
+                    let pixel = u16::from_be_bytes([pixel[0], pixel[1]]);
+                    let channel = |shift: u16| (((pixel >> shift) & 0x1F) * 255 / 31) as u8;
+                    rgba.extend([channel(10), channel(5), channel(0), u8::MAX]);
+                }
+            }
+        }
+        BitmapFormat::Rgb32 => {
+            let pixels = data.get(..width * height * 4).ok_or_else(truncated)?;
+            for pixel in pixels.chunks_exact(4) {
+                let a = if has_alpha { pixel[0] } else { u8::MAX };
+                rgba.extend([pixel[1], pixel[2], pixel[3], a]);
+            }
+        }
+        BitmapFormat::ColorMap8 { num_colors } => {
+            let entry_len = if has_alpha { 4 } else { 3 };
+            let palette_len = (usize::from(num_colors) + 1) * entry_len;
+            let palette = data.get(..palette_len).ok_or_else(truncated)?;
+            // Rows are padded to 32 bits.
+            let row_len = (width + 3) & !3;
+            for row in 0..height {
+                let start = palette_len + row * row_len;
+                let row = data.get(start..start + width).ok_or_else(truncated)?;
+                for &index in row {
+                    // Indices past the end of the palette are transparent, or black
+                    // without alpha.
+                    let color = palette.chunks_exact(entry_len).nth(index.into());
+                    rgba.extend(match (color, has_alpha) {
+                        (Some(color), true) => [color[0], color[1], color[2], color[3]],
+                        (Some(color), false) => [color[0], color[1], color[2], u8::MAX],
+                        (None, true) => [0; 4],
+                        (None, false) => [0, 0, 0, u8::MAX],
+                    });
+                }
+            }
+        }
+    }
+    if has_alpha {
+        // The colors of `DefineBitsLossless2` are premultiplied.
+        rgba.chunks_exact_mut(4).for_each(unmultiply);
+    }
+
+    image_from_rgba(bitmap.width.into(), bitmap.height.into(), rgba)
+}
+
+fn image_from_rgba(width: u32, height: u32, rgba: Vec<u8>) -> Result<RgbaImage> {
+    RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| anyhow!("Invalid bitmap size {width}x{height}"))
+}
+
+/// Converts a premultiplied RGBA pixel to straight alpha.
+fn unmultiply(pixel: &mut [u8]) {
+    let a = u16::from(pixel[3]);
+    for channel in &mut pixel[..3] {
+        if let Some(value) = (u16::from(*channel) * 255).checked_div(a) {
+            *channel = value.min(255) as u8;
+        }
+    }
+}
+
+fn decompress_zlib(data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = vec![];
+    flate2::read::ZlibDecoder::new(data).read_to_end(&mut out)?;
+    Ok(out)
+}
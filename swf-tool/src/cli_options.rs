@@ -0,0 +1,127 @@
+//! CLI Options
+
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[clap(version, about, author)]
+pub struct Opt {
+    #[clap(subcommand)]
+    pub mode: Mode,
+}
+
+#[derive(Parser, Debug)]
+pub enum Mode {
+    /// Show the header of a SWF file
+    Header(HeaderOpt),
+
+    /// List the tags of a SWF file along with their offsets
+    Tags(TagsOpt),
+
+    /// Extract images, sounds, fonts, binary data and ABC from a SWF file
+    Extract(ExtractOpt),
+
+    /// Replace the contents of a tag and write the result to a new SWF file
+    Replace(ReplaceOpt),
+
+    /// Write a SWF file with a different compression
+    Recompress(RecompressOpt),
+}
+
+#[derive(Parser, Debug)]
+pub struct HeaderOpt {
+    /// The SWF file to inspect
+    #[clap(name = "swf")]
+    pub input_path: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+pub struct TagsOpt {
+    /// The SWF file to inspect
+    #[clap(name = "swf")]
+    pub input_path: PathBuf,
+
+    /// Don't list the tags inside of sprites
+    #[clap(long = "no-sprites", action)]
+    pub no_sprites: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ExtractOpt {
+    /// The SWF file to extract from
+    #[clap(name = "swf")]
+    pub input_path: PathBuf,
+
+    /// The directory to write the extracted files to
+    #[clap(name = "output", default_value = ".")]
+    pub output_path: PathBuf,
+
+    /// Only extract these kinds of assets
+    #[clap(long = "only", value_enum, value_delimiter = ',')]
+    pub only: Vec<AssetKind>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ReplaceOpt {
+    /// The SWF file to modify
+    #[clap(name = "swf")]
+    pub input_path: PathBuf,
+
+    /// The tag to replace, as its index in `tags`, such as `12` or `12.3`
+    #[clap(name = "tag")]
+    pub tag: String,
+
+    /// The file holding the new contents of the tag
+    #[clap(name = "file")]
+    pub replacement_path: PathBuf,
+
+    /// The SWF file to write
+    #[clap(name = "output")]
+    pub output_path: PathBuf,
+
+    /// Use the file as the raw body of the tag, instead of the asset it
+    /// holds, such as the data of a `DefineBinaryData`
+    #[clap(long = "raw", action)]
+    pub raw: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct RecompressOpt {
+    /// The SWF file to recompress
+    #[clap(name = "swf")]
+    pub input_path: PathBuf,
+
+    /// The SWF file to write
+    #[clap(name = "output")]
+    pub output_path: PathBuf,
+
+    /// The compression of the written SWF
+    #[clap(short = 'c', long = "compression", value_enum)]
+    pub compression: Compression,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AssetKind {
+    Images,
+    Sounds,
+    Fonts,
+    BinaryData,
+    Abc,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    None,
+    Zlib,
+    Lzma,
+}
+
+impl From<Compression> for swf::Compression {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::None => swf::Compression::None,
+            Compression::Zlib => swf::Compression::Zlib,
+            Compression::Lzma => swf::Compression::Lzma,
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use crate::bitmap::{decode_jpeg_tag, decode_lossless, glue_tables_to_jpeg};
+use crate::cli_options::{AssetKind, ExtractOpt};
+use crate::font::{truetype_font, truetype_font_v1};
+use crate::load_swf;
+use crate::raw::{read_raw_tags, RawTag};
+use crate::sound::convert_sound;
+use anyhow::{anyhow, Context, Result};
+use image::{ImageFormat, RgbaImage};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use swf::{CharacterId, FontInfo, SoundFormat, SwfStr, Tag, TagCode};
+
+pub fn extract_main(opt: ExtractOpt) -> Result<()> {
+    let (_, swf_buf) = load_swf(&opt.input_path)?;
+    let version = swf_buf.header.version();
+    let tags = read_raw_tags(&swf_buf.data, 0, version);
+
+    let mut extractor = Extractor {
+        output_path: opt.output_path,
+        only: opt.only,
+        swf_version: version,
+        names: HashMap::new(),
+        jpeg_tables: None,
+        font_infos: HashMap::new(),
+        num_abc: 0,
+        num_files: 0,
+    };
+    extractor.collect(&tags);
+    extractor.extract_timeline(&tags, None);
+    println!("Extracted {} files", extractor.num_files);
+    Ok(())
+}
+
+struct Extractor<'a> {
+    output_path: PathBuf,
+    only: Vec<AssetKind>,
+    swf_version: u8,
+
+    /// The class or export names of characters, used to name their files.
+    names: HashMap<CharacterId, &'a SwfStr>,
+
+    jpeg_tables: Option<&'a [u8]>,
+    font_infos: HashMap<CharacterId, FontInfo<'a>>,
+    num_abc: usize,
+    num_files: usize,
+}
+
+/// A sound stream being collected from the blocks of a timeline.
+struct SoundStream<'a> {
+    format: SoundFormat,
+    blocks: Vec<&'a [u8]>,
+}
+
+impl<'a> Extractor<'a> {
+    fn parse(&self, tag: &RawTag<'a>) -> Option<Tag<'a>> {
+        match tag.parse(self.swf_version) {
+            Ok(tag) => Some(tag),
+            Err(e) => {
+                eprintln!("Skipping {} at {:#x}: {e}", tag.name(), tag.offset);
+                None
+            }
+        }
+    }
+
+    /// Collects the tags needed to extract others: names, JPEG tables and
+    /// font info.
+    fn collect(&mut self, tags: &[RawTag<'a>]) {
+        for tag in tags {
+            let code = TagCode::from_u16(tag.code);
+            if code == Some(TagCode::DefineSprite) {
+                self.collect(&tag.children);
+                continue;
+            }
+            if !matches!(
+                code,
+                Some(
+                    TagCode::SymbolClass
+                        | TagCode::ExportAssets
+                        | TagCode::JpegTables
+                        | TagCode::DefineFontInfo
+                        | TagCode::DefineFontInfo2
+                )
+            ) {
+                continue;
+            }
+            match self.parse(tag) {
+                Some(Tag::SymbolClass(links)) => {
+                    for link in links {
+                        self.names.entry(link.id).or_insert(link.class_name);
+                    }
+                }
+                Some(Tag::ExportAssets(assets)) => {
+                    for asset in assets {
+                        self.names.entry(asset.id).or_insert(asset.name);
+                    }
+                }
+                Some(Tag::JpegTables(jpeg_tables)) => self.jpeg_tables = Some(jpeg_tables),
+                Some(Tag::DefineFontInfo(font_info)) => {
+                    self.font_infos.insert(font_info.id, *font_info);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Extracts the assets defined in a timeline, and the sound streams
+    /// played in it. `sprite_id` is `None` for the main timeline.
+    fn extract_timeline(&mut self, tags: &[RawTag<'a>], sprite_id: Option<CharacterId>) {
+        let mut streams = vec![];
+        for tag in tags {
+            let code = TagCode::from_u16(tag.code);
+            if code == Some(TagCode::DefineSprite) {
+                self.extract_timeline(&tag.children, tag.character_id());
+                continue;
+            }
+            if !matches!(
+                code,
+                Some(
+                    TagCode::DefineBits
+                        | TagCode::DefineBitsJpeg2
+                        | TagCode::DefineBitsJpeg3
+                        | TagCode::DefineBitsJpeg4
+                        | TagCode::DefineBitsLossless
+                        | TagCode::DefineBitsLossless2
+                        | TagCode::DefineSound
+                        | TagCode::SoundStreamHead
+                        | TagCode::SoundStreamHead2
+                        | TagCode::SoundStreamBlock
+                        | TagCode::DefineFont
+                        | TagCode::DefineFont2
+                        | TagCode::DefineFont3
+                        | TagCode::DefineFont4
+                        | TagCode::DefineBinaryData
+                        | TagCode::DoAbc
+                        | TagCode::DoAbc2
+                )
+            ) {
+                continue;
+            }
+            let Some(parsed) = self.parse(tag) else {
+                continue;
+            };
+            let result = match parsed {
+                Tag::SoundStreamHead(head) | Tag::SoundStreamHead2(head) => {
+                    streams.push(SoundStream {
+                        format: head.stream_format,
+                        blocks: vec![],
+                    });
+                    Ok(())
+                }
+                Tag::SoundStreamBlock(block) => {
+                    if let Some(stream) = streams.last_mut() {
+                        stream.blocks.push(block);
+                    }
+                    Ok(())
+                }
+                parsed => self.extract_tag(parsed),
+            };
+            if let Err(e) = result {
+                eprintln!(
+                    "Couldn't extract {} at {:#x}: {e:#}",
+                    tag.name(),
+                    tag.offset
+                );
+            }
+        }
+
+        if !self.wants(AssetKind::Sounds) {
+            return;
+        }
+        let timeline = match sprite_id {
+            Some(id) => format!("sprite_{id}"),
+            None => "root".to_string(),
+        };
+        for (index, stream) in streams.iter().enumerate() {
+            if stream.blocks.is_empty() {
+                continue;
+            }
+            let name = if streams.len() > 1 {
+                format!("stream_{timeline}_{index}")
+            } else {
+                format!("stream_{timeline}")
+            };
+            // Each MP3 block starts with its sample count and seek samples.
+            let result = match convert_sound(&stream.format, &stream.blocks, 4) {
+                Some(file) => self.write(
+                    &Path::new("sounds").join(format!("{name}.{}", file.extension())),
+                    file.data(),
+                ),
+                None => Err(unsupported_sound(&stream.format)),
+            };
+            if let Err(e) = result {
+                eprintln!("Couldn't extract the sound stream of {timeline}: {e:#}");
+            }
+        }
+    }
+
+    fn extract_tag(&mut self, tag: Tag<'a>) -> Result<()> {
+        match tag {
+            Tag::DefineBits { id, jpeg_data } if self.wants(AssetKind::Images) => {
+                let jpeg_data = glue_tables_to_jpeg(jpeg_data, self.jpeg_tables);
+                self.write_image(id, decode_jpeg_tag(&jpeg_data, None)?)
+            }
+            Tag::DefineBitsJpeg2 { id, jpeg_data } if self.wants(AssetKind::Images) => {
+                self.write_image(id, decode_jpeg_tag(jpeg_data, None)?)
+            }
+            Tag::DefineBitsJpeg3(jpeg) if self.wants(AssetKind::Images) => {
+                let image = decode_jpeg_tag(jpeg.data, Some(jpeg.alpha_data))?;
+                self.write_image(jpeg.id, image)
+            }
+            Tag::DefineBitsLossless(bitmap) if self.wants(AssetKind::Images) => {
+                self.write_image(bitmap.id, decode_lossless(&bitmap)?)
+            }
+            Tag::DefineSound(sound) if self.wants(AssetKind::Sounds) => {
+                // MP3 data starts with its seek samples.
+                let file = convert_sound(&sound.format, &[sound.data], 2)
+                    .ok_or_else(|| unsupported_sound(&sound.format))?;
+                let path = self.asset_path("sounds", sound.id, file.extension());
+                self.write(&path, file.data())
+            }
+            Tag::DefineFont(font) if self.wants(AssetKind::Fonts) => {
+                let data = truetype_font_v1(&font, self.font_infos.get(&font.id), self.swf_version);
+                let path = self.asset_path("fonts", font.id, "ttf");
+                self.write(&path, &data)
+            }
+            Tag::DefineFont2(font) if self.wants(AssetKind::Fonts) => {
+                if font.glyphs.is_empty() {
+                    // A device font, which has nothing to extract.
+                    return Ok(());
+                }
+                let data = truetype_font(&font, self.swf_version);
+                let path = self.asset_path("fonts", font.id, "ttf");
+                self.write(&path, &data)
+            }
+            Tag::DefineFont4(font) if self.wants(AssetKind::Fonts) => {
+                let Some(data) = font.data else {
+                    return Ok(());
+                };
+                let path = self.asset_path("fonts", font.id, "otf");
+                self.write(&path, data)
+            }
+            Tag::DefineBinaryData(binary_data) if self.wants(AssetKind::BinaryData) => {
+                let path = self.asset_path("binary_data", binary_data.id, "bin");
+                self.write(&path, binary_data.data)
+            }
+            Tag::DoAbc(data) if self.wants(AssetKind::Abc) => self.write_abc(None, data),
+            Tag::DoAbc2(do_abc) if self.wants(AssetKind::Abc) => {
+                self.write_abc(Some(do_abc.name), do_abc.data)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn wants(&self, kind: AssetKind) -> bool {
+        self.only.is_empty() || self.only.contains(&kind)
+    }
+
+    /// Returns the path of the file of a character, relative to the output
+    /// directory.
+    fn asset_path(&self, directory: &str, id: CharacterId, extension: &str) -> PathBuf {
+        let file_name = match self.names.get(&id) {
+            Some(name) => format!("{id}_{}.{extension}", self.sanitize(name)),
+            None => format!("{id}.{extension}"),
+        };
+        Path::new(directory).join(file_name)
+    }
+
+    fn sanitize(&self, name: &SwfStr) -> String {
+        name.to_str_lossy(SwfStr::encoding_for_version(self.swf_version))
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || "-_.".contains(c) {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    fn write_image(&mut self, id: CharacterId, image: RgbaImage) -> Result<()> {
+        let mut data = vec![];
+        image.write_to(&mut std::io::Cursor::new(&mut data), ImageFormat::Png)?;
+        let path = self.asset_path("images", id, "png");
+        self.write(&path, &data)
+    }
+
+    fn write_abc(&mut self, name: Option<&SwfStr>, data: &[u8]) -> Result<()> {
+        let index = self.num_abc;
+        self.num_abc += 1;
+        let file_name = match name {
+            Some(name) if !name.is_empty() => format!("{index}_{}.abc", self.sanitize(name)),
+            _ => format!("{index}.abc"),
+        };
+        self.write(&Path::new("abc").join(file_name), data)
+    }
+
+    /// Writes a file, with a path relative to the output directory.
+    fn write(&mut self, path: &Path, data: &[u8]) -> Result<()> {
+        let path = self.output_path.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Couldn't create {}", parent.display()))?;
+        }
+        std::fs::write(&path, data)
+            .with_context(|| format!("Couldn't write {}", path.display()))?;
+        println!("{}", path.display());
+        self.num_files += 1;
+        Ok(())
+    }
+}
+
+fn unsupported_sound(format: &SoundFormat) -> anyhow::Error {
+    anyhow!("{:?} sounds aren't supported", format.compression)
+}
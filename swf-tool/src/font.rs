@@ -0,0 +1,550 @@
+//! Conversion of the glyph outlines of `DefineFont` tags into TrueType fonts.
+//!
+//! Glyph shapes use an EM square of 1024 units, or 20480 for `DefineFont3`,
+//! and are made of quadratic curves like TrueType outlines. The SWF y axis
+//! points down, so each outline is flipped, which also restores the contour
+//! direction expected by TrueType for fonts converted from it.
+
+use swf::{
+    CharacterId, Font, FontFlag, FontInfo, FontInfoFlag, FontV1, ShapeRecord, SwfStr, Twips,
+};
+
+const UNITS_PER_EM: u16 = 1024;
+
+/// A glyph outline as a list of contours, each made of points that are
+/// either on or off the curve.
+struct Outline {
+    code: Option<u16>,
+    advance: Option<u16>,
+    contours: Vec<Vec<(i16, i16, bool)>>,
+}
+
+impl Outline {
+    fn new(code: Option<u16>, advance: Option<u16>, records: &[ShapeRecord], scale: f64) -> Self {
+        let mut contours: Vec<Vec<(i16, i16, bool)>> = vec![];
+        let mut contour = vec![];
+        let (mut x, mut y) = (Twips::ZERO, Twips::ZERO);
+        let point = |x: Twips, y: Twips, on_curve| {
+            let x = (f64::from(x.get()) * scale).round() as i16;
+            let y = (f64::from(-y.get()) * scale).round() as i16;
+            (x, y, on_curve)
+        };
+        for record in records {
+            match record {
+                ShapeRecord::StyleChange(style_change) => {
+                    if let Some(move_to) = &style_change.move_to {
+                        contours.push(std::mem::take(&mut contour));
+                        (x, y) = (move_to.x, move_to.y);
+                    }
+                }
+                ShapeRecord::StraightEdge { delta } => {
+                    if contour.is_empty() {
+                        contour.push(point(x, y, true));
+                    }
+                    (x, y) = (x + delta.dx, y + delta.dy);
+                    contour.push(point(x, y, true));
+                }
+                ShapeRecord::CurvedEdge {
+                    control_delta,
+                    anchor_delta,
+                } => {
+                    if contour.is_empty() {
+                        contour.push(point(x, y, true));
+                    }
+                    (x, y) = (x + control_delta.dx, y + control_delta.dy);
+                    contour.push(point(x, y, false));
+                    (x, y) = (x + anchor_delta.dx, y + anchor_delta.dy);
+                    contour.push(point(x, y, true));
+                }
+            }
+        }
+        contours.push(contour);
+
+        for contour in &mut contours {
+            // Contours are implicitly closed.
+            if contour.len() > 1 && contour.first() == contour.last() {
+                contour.pop();
+            }
+        }
+        contours.retain(|contour| contour.len() > 1);
+
+        // SWF glyphs are filled regardless of their direction, but TrueType
+        // expects outer contours to be clockwise.
+        let outer_area = contours
+            .iter()
+            .map(|contour| signed_area(contour))
+            .max_by(|a, b| a.abs().total_cmp(&b.abs()));
+        if outer_area.is_some_and(|area| area > 0.0) {
+            contours.iter_mut().for_each(|contour| contour.reverse());
+        }
+        Self {
+            code,
+            advance,
+            contours,
+        }
+    }
+
+    fn points(&self) -> impl Iterator<Item = &(i16, i16, bool)> {
+        self.contours.iter().flatten()
+    }
+
+    /// Returns the bounds of the outline as `(x_min, y_min, x_max, y_max)`.
+    fn bounds(&self) -> Option<(i16, i16, i16, i16)> {
+        self.points().fold(None, |bounds, &(x, y, _)| {
+            let (x_min, y_min, x_max, y_max) = bounds.unwrap_or((x, y, x, y));
+            Some((x_min.min(x), y_min.min(y), x_max.max(x), y_max.max(y)))
+        })
+    }
+
+    fn advance(&self) -> u16 {
+        self.advance
+            .or_else(|| {
+                let (_, _, x_max, _) = self.bounds()?;
+                Some(x_max.max(0) as u16)
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// Returns the area of a contour, which is positive when it's
+/// counterclockwise.
+fn signed_area(contour: &[(i16, i16, bool)]) -> f64 {
+    let next = contour.iter().cycle().skip(1);
+    contour
+        .iter()
+        .zip(next)
+        .map(|(&(x0, y0, _), &(x1, y1, _))| {
+            f64::from(x0) * f64::from(y1) - f64::from(x1) * f64::from(y0)
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+struct OutlineFont {
+    name: String,
+    is_bold: bool,
+    is_italic: bool,
+    ascent: Option<i16>,
+    descent: Option<i16>,
+    glyphs: Vec<Outline>,
+}
+
+/// Converts a `DefineFont2` or `DefineFont3` into a TrueType font.
+pub fn truetype_font(font: &Font, swf_version: u8) -> Vec<u8> {
+    let scale = if font.version >= 3 { 1.0 / 20.0 } else { 1.0 };
+    let scale_metric = |value: i32| (f64::from(value) * scale).round() as i16;
+    let has_layout = font.layout.is_some();
+    OutlineFont {
+        name: font_name(font.name, font.id, swf_version),
+        is_bold: font.flags.contains(FontFlag::IS_BOLD),
+        is_italic: font.flags.contains(FontFlag::IS_ITALIC),
+        ascent: font
+            .layout
+            .as_ref()
+            .map(|layout| scale_metric(layout.ascent.into())),
+        descent: font
+            .layout
+            .as_ref()
+            .map(|layout| scale_metric(layout.descent.into())),
+        glyphs: font
+            .glyphs
+            .iter()
+            .map(|glyph| {
+                let advance = has_layout.then(|| scale_metric(glyph.advance.into()).max(0) as u16);
+                Outline::new(Some(glyph.code), advance, &glyph.shape_records, scale)
+            })
+            .collect(),
+    }
+    .write()
+}
+
+/// Converts a `DefineFont` into a TrueType font, using the character codes
+/// and name of its `DefineFontInfo` if there is one.
+pub fn truetype_font_v1(font: &FontV1, info: Option<&FontInfo>, swf_version: u8) -> Vec<u8> {
+    let name = info.map_or(SwfStr::from_bytes(b""), |info| info.name);
+    let flags = info.map_or(FontInfoFlag::empty(), |info| info.flags);
+    OutlineFont {
+        name: font_name(name, font.id, swf_version),
+        is_bold: flags.contains(FontInfoFlag::IS_BOLD),
+        is_italic: flags.contains(FontInfoFlag::IS_ITALIC),
+        ascent: None,
+        descent: None,
+        glyphs: font
+            .glyphs
+            .iter()
+            .enumerate()
+            .map(|(index, records)| {
+                let code = info.and_then(|info| info.code_table.get(index).copied());
+                Outline::new(code, None, records, 1.0)
+            })
+            .collect(),
+    }
+    .write()
+}
+
+fn font_name(name: &SwfStr, id: CharacterId, swf_version: u8) -> String {
+    let name = name.to_str_lossy(SwfStr::encoding_for_version(swf_version));
+    let name = name.trim_end_matches('\0').trim();
+    if name.is_empty() {
+        format!("Font {id}")
+    } else {
+        name.to_string()
+    }
+}
+
+impl OutlineFont {
+    fn write(&self) -> Vec<u8> {
+        // Glyph 0 is the empty `.notdef` glyph.
+        let notdef = Outline {
+            code: None,
+            advance: Some(UNITS_PER_EM / 2),
+            contours: vec![],
+        };
+        let glyphs: Vec<&Outline> = std::iter::once(&notdef).chain(&self.glyphs).collect();
+        let num_glyphs = glyphs.len() as u16;
+
+        let bounds = glyphs
+            .iter()
+            .filter_map(|glyph| glyph.bounds())
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+            .unwrap_or_default();
+        let ascent = self
+            .ascent
+            .unwrap_or(if bounds.3 > 0 { bounds.3 } else { 800 });
+        let descent = self
+            .descent
+            .unwrap_or(if bounds.1 < 0 { -bounds.1 } else { 200 });
+        let advances: Vec<u16> = glyphs.iter().map(|glyph| glyph.advance()).collect();
+        let max_advance = advances.iter().copied().max().unwrap_or(0);
+
+        let mut glyf = vec![];
+        let mut loca = vec![];
+        let mut hmtx = vec![];
+        let mut max_points = 0;
+        let mut max_contours = 0;
+        for (glyph, &advance) in glyphs.iter().zip(&advances) {
+            loca.extend((glyf.len() as u32).to_be_bytes());
+            let (x_min, y_min, x_max, y_max) = glyph.bounds().unwrap_or_default();
+            hmtx.extend(advance.to_be_bytes());
+            hmtx.extend(x_min.to_be_bytes());
+            if glyph.contours.is_empty() {
+                continue;
+            }
+
+            max_points = max_points.max(glyph.points().count() as u16);
+            max_contours = max_contours.max(glyph.contours.len() as u16);
+            glyf.extend((glyph.contours.len() as i16).to_be_bytes());
+            for value in [x_min, y_min, x_max, y_max] {
+                glyf.extend(value.to_be_bytes());
+            }
+            let mut end_point = 0;
+            for contour in &glyph.contours {
+                end_point += contour.len() as u16;
+                glyf.extend((end_point - 1).to_be_bytes());
+            }
+            glyf.extend(0u16.to_be_bytes()); // No instructions.
+            for &(_, _, on_curve) in glyph.points() {
+                glyf.push(on_curve.into());
+            }
+            // Coordinates are relative to the previous point.
+            let mut previous = (0i16, 0i16);
+            let mut ys = vec![];
+            for &(x, y, _) in glyph.points() {
+                glyf.extend(x.wrapping_sub(previous.0).to_be_bytes());
+                ys.extend(y.wrapping_sub(previous.1).to_be_bytes());
+                previous = (x, y);
+            }
+            glyf.extend(ys);
+            while glyf.len() % 4 != 0 {
+                glyf.push(0);
+            }
+        }
+        loca.extend((glyf.len() as u32).to_be_bytes());
+
+        let mut head = vec![];
+        head.extend(0x0001_0000u32.to_be_bytes()); // Version
+        head.extend(0x0001_0000u32.to_be_bytes()); // Font revision
+        head.extend(0u32.to_be_bytes()); // Checksum adjustment, set below.
+        head.extend(0x5F0F_3CF5u32.to_be_bytes()); // Magic number
+        head.extend(0b1001u16.to_be_bytes()); // Baseline at y=0, integer scaling.
+        head.extend(UNITS_PER_EM.to_be_bytes());
+        head.extend(0i64.to_be_bytes()); // Created
+        head.extend(0i64.to_be_bytes()); // Modified
+        for value in [bounds.0, bounds.1, bounds.2, bounds.3] {
+            head.extend(value.to_be_bytes());
+        }
+        head.extend(self.mac_style().to_be_bytes());
+        head.extend(8u16.to_be_bytes()); // Smallest readable size
+        head.extend(2i16.to_be_bytes()); // Font direction hint
+        head.extend(1i16.to_be_bytes()); // Long `loca` offsets
+        head.extend(0i16.to_be_bytes()); // Glyph data format
+
+        let mut hhea = vec![];
+        hhea.extend(0x0001_0000u32.to_be_bytes());
+        hhea.extend(ascent.to_be_bytes());
+        hhea.extend((-descent).to_be_bytes());
+        hhea.extend(0i16.to_be_bytes()); // Line gap
+        hhea.extend(max_advance.to_be_bytes());
+        hhea.extend(bounds.0.to_be_bytes()); // Minimum left side bearing
+        hhea.extend(0i16.to_be_bytes()); // Minimum right side bearing
+        hhea.extend(bounds.2.to_be_bytes()); // Maximum extent
+        hhea.extend(1i16.to_be_bytes()); // Caret slope rise
+        hhea.extend([0; 12]); // Caret slope run, caret offset, reserved
+        hhea.extend(0i16.to_be_bytes()); // Metric data format
+        hhea.extend(num_glyphs.to_be_bytes());
+
+        let mut maxp = vec![];
+        maxp.extend(0x0001_0000u32.to_be_bytes());
+        maxp.extend(num_glyphs.to_be_bytes());
+        maxp.extend(max_points.to_be_bytes());
+        maxp.extend(max_contours.to_be_bytes());
+        maxp.extend([0; 4]); // No composite glyphs.
+        maxp.extend(2u16.to_be_bytes()); // Zones
+        maxp.extend([0; 18]); // No instructions.
+
+        let mut post = vec![];
+        post.extend(0x0003_0000u32.to_be_bytes()); // No glyph names.
+        post.extend(0u32.to_be_bytes()); // Italic angle
+        post.extend((-(UNITS_PER_EM as i16) / 10).to_be_bytes()); // Underline position
+        post.extend((UNITS_PER_EM / 20).to_be_bytes()); // Underline thickness
+        post.extend([0; 20]);
+
+        // Tables are sorted by their tag.
+        let tables = [
+            (*b"OS/2", self.os2(&glyphs, &advances, ascent, descent)),
+            (*b"cmap", cmap(&glyphs)),
+            (*b"glyf", glyf),
+            (*b"head", head),
+            (*b"hhea", hhea),
+            (*b"hmtx", hmtx),
+            (*b"loca", loca),
+            (*b"maxp", maxp),
+            (*b"name", self.name_table()),
+            (*b"post", post),
+        ];
+
+        let mut font = write_sfnt(&tables);
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+        let head_offset = font[12..12 + 16 * tables.len()]
+            .chunks(16)
+            .find(|record| record.starts_with(b"head"))
+            .map(|record| u32::from_be_bytes(record[8..12].try_into().unwrap()) as usize)
+            .expect("head table is always written");
+        font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+        font
+    }
+
+    fn mac_style(&self) -> u16 {
+        u16::from(self.is_bold) | u16::from(self.is_italic) << 1
+    }
+
+    fn subfamily(&self) -> &'static str {
+        match (self.is_bold, self.is_italic) {
+            (false, false) => "Regular",
+            (true, false) => "Bold",
+            (false, true) => "Italic",
+            (true, true) => "Bold Italic",
+        }
+    }
+
+    fn os2(&self, glyphs: &[&Outline], advances: &[u16], ascent: i16, descent: i16) -> Vec<u8> {
+        let codes: Vec<u16> = glyphs.iter().filter_map(|glyph| glyph.code).collect();
+        let average_advance = if advances.is_empty() {
+            0
+        } else {
+            advances
+                .iter()
+                .map(|&advance| u32::from(advance))
+                .sum::<u32>()
+                / advances.len() as u32
+        };
+        let em = UNITS_PER_EM as i16;
+
+        let mut os2 = vec![];
+        os2.extend(4u16.to_be_bytes()); // Version
+        os2.extend((average_advance as i16).to_be_bytes());
+        os2.extend(if self.is_bold { 700u16 } else { 400 }.to_be_bytes());
+        os2.extend(5u16.to_be_bytes()); // Normal width
+        os2.extend(0u16.to_be_bytes()); // Installable embedding
+                                        // Subscript and superscript sizes and offsets.
+        for value in [
+            em * 2 / 3,
+            em * 2 / 3,
+            0,
+            em / 7,
+            em * 2 / 3,
+            em * 2 / 3,
+            0,
+            em / 3,
+        ] {
+            os2.extend(value.to_be_bytes());
+        }
+        os2.extend((em / 20).to_be_bytes()); // Strikeout size
+        os2.extend((em / 4).to_be_bytes()); // Strikeout position
+        os2.extend(0i16.to_be_bytes()); // Family class
+        os2.extend([0; 10]); // PANOSE
+        os2.extend([0; 16]); // Unicode ranges
+        os2.extend(b"    "); // Vendor ID
+        let mut selection = 0u16;
+        if self.is_italic {
+            selection |= 1;
+        }
+        if self.is_bold {
+            selection |= 1 << 5;
+        }
+        if selection == 0 {
+            selection = 1 << 6;
+        }
+        os2.extend(selection.to_be_bytes());
+        os2.extend(codes.iter().min().copied().unwrap_or(0).to_be_bytes());
+        os2.extend(codes.iter().max().copied().unwrap_or(0).to_be_bytes());
+        os2.extend(ascent.to_be_bytes());
+        os2.extend((-descent).to_be_bytes());
+        os2.extend(0i16.to_be_bytes()); // Line gap
+        os2.extend((ascent.max(0) as u16).to_be_bytes());
+        os2.extend((descent.max(0) as u16).to_be_bytes());
+        os2.extend(1u32.to_be_bytes()); // Latin 1 code page
+        os2.extend(0u32.to_be_bytes());
+        os2.extend(0i16.to_be_bytes()); // x-height
+        os2.extend(0i16.to_be_bytes()); // Cap height
+        os2.extend(0u16.to_be_bytes()); // Default character
+        os2.extend(0x20u16.to_be_bytes()); // Break character
+        os2.extend(1u16.to_be_bytes()); // Maximum context
+        os2
+    }
+
+    fn name_table(&self) -> Vec<u8> {
+        let full_name = if self.subfamily() == "Regular" {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.name, self.subfamily())
+        };
+        let postscript_name: String = full_name
+            .chars()
+            .filter(|c| c.is_ascii_graphic() && !"[](){}<>/%".contains(*c))
+            .take(63)
+            .collect();
+        let names = [
+            (1u16, self.name.as_str()),
+            (2, self.subfamily()),
+            (4, &full_name),
+            (6, &postscript_name),
+        ];
+
+        let mut records = vec![];
+        let mut strings = vec![];
+        for (name_id, name) in names {
+            let encoded: Vec<u8> = name.encode_utf16().flat_map(u16::to_be_bytes).collect();
+            // Windows platform, Unicode BMP encoding, US English.
+            for value in [3u16, 1, 0x409, name_id, encoded.len() as u16] {
+                records.extend(value.to_be_bytes());
+            }
+            records.extend((strings.len() as u16).to_be_bytes());
+            strings.extend(encoded);
+        }
+
+        let mut name = vec![];
+        name.extend(0u16.to_be_bytes()); // Format
+        name.extend((names.len() as u16).to_be_bytes());
+        name.extend((6 + records.len() as u16).to_be_bytes());
+        name.extend(records);
+        name.extend(strings);
+        name
+    }
+}
+
+/// Writes a `cmap` table with a format 4 subtable, mapping each character
+/// code to the first glyph that has it.
+fn cmap(glyphs: &[&Outline]) -> Vec<u8> {
+    let mut mapping: Vec<(u16, u16)> = glyphs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, glyph)| Some((glyph.code?, index as u16)))
+        .filter(|&(code, _)| code != 0xFFFF)
+        .collect();
+    mapping.sort_by_key(|&(code, _)| code);
+    mapping.dedup_by_key(|(code, _)| *code);
+
+    // Each segment is a range of codes mapped to consecutive glyphs.
+    let mut segments: Vec<(u16, u16, u16)> = vec![];
+    for (code, glyph) in mapping {
+        match segments.last_mut() {
+            Some((start, end, first_glyph))
+                if code == *end + 1
+                    && glyph.wrapping_sub(code) == first_glyph.wrapping_sub(*start) =>
+            {
+                *end = code;
+            }
+            _ => segments.push((code, code, glyph)),
+        }
+    }
+    // The last segment must end at 0xFFFF.
+    segments.push((0xFFFF, 0xFFFF, 0));
+
+    let segment_count = segments.len() as u16;
+    let search_range: u16 = 2 << segment_count.ilog2();
+    let mut subtable = vec![];
+    subtable.extend(4u16.to_be_bytes()); // Format
+    subtable.extend((16 + 8 * segment_count).to_be_bytes());
+    subtable.extend(0u16.to_be_bytes()); // Language
+    subtable.extend((segment_count * 2).to_be_bytes());
+    subtable.extend(search_range.to_be_bytes());
+    subtable.extend(((search_range / 2).ilog2() as u16).to_be_bytes());
+    subtable.extend((segment_count * 2 - search_range).to_be_bytes());
+    for &(_, end, _) in &segments {
+        subtable.extend(end.to_be_bytes());
+    }
+    subtable.extend(0u16.to_be_bytes()); // Reserved
+    for &(start, _, _) in &segments {
+        subtable.extend(start.to_be_bytes());
+    }
+    for &(start, _, glyph) in &segments {
+        subtable.extend(glyph.wrapping_sub(start).to_be_bytes());
+    }
+    for _ in &segments {
+        subtable.extend(0u16.to_be_bytes()); // No glyph ID arrays.
+    }
+
+    let mut cmap = vec![];
+    cmap.extend(0u16.to_be_bytes()); // Version
+    cmap.extend(1u16.to_be_bytes()); // Number of subtables
+    cmap.extend(3u16.to_be_bytes()); // Windows platform
+    cmap.extend(1u16.to_be_bytes()); // Unicode BMP encoding
+    cmap.extend(12u32.to_be_bytes()); // Subtable offset
+    cmap.extend(subtable);
+    cmap
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Writes the table directory and tables of a font.
+fn write_sfnt(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let search_range: u16 = 16 << num_tables.ilog2();
+    let mut font = vec![];
+    font.extend(0x0001_0000u32.to_be_bytes());
+    font.extend(num_tables.to_be_bytes());
+    font.extend(search_range.to_be_bytes());
+    font.extend((num_tables.ilog2() as u16).to_be_bytes());
+    font.extend((num_tables * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in tables {
+        font.extend(tag);
+        font.extend(checksum(data).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in tables {
+        font.extend(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    font
+}
@@ -0,0 +1,110 @@
+use crate::cli_options::{HeaderOpt, TagsOpt};
+use crate::load_swf;
+use crate::raw::{read_raw_tags, RawTag};
+use anyhow::Result;
+use swf::Compression;
+
+pub fn header_main(opt: HeaderOpt) -> Result<()> {
+    let (file_len, swf_buf) = load_swf(&opt.input_path)?;
+    let header = &swf_buf.header;
+
+    let signature = match header.compression() {
+        Compression::None => "FWS (uncompressed)",
+        Compression::Zlib => "CWS (zlib)",
+        Compression::Lzma => "ZWS (LZMA)",
+    };
+    let stage_size = header.stage_size();
+    let mut attributes = vec![];
+    if header.use_gpu() {
+        attributes.push("UseGpu");
+    }
+    if header.use_direct_blit() {
+        attributes.push("UseDirectBlit");
+    }
+    if header.use_network_sandbox() {
+        attributes.push("UseNetwork");
+    }
+    if header.has_metadata() {
+        attributes.push("HasMetadata");
+    }
+
+    println!("Signature:      {signature}");
+    println!("Version:        {}", header.version());
+    println!(
+        "File length:    {file_len} bytes ({} uncompressed)",
+        header.uncompressed_len()
+    );
+    println!(
+        "Stage size:     {} x {} pixels",
+        stage_size.width().to_pixels(),
+        stage_size.height().to_pixels()
+    );
+    println!("Frame rate:     {}", header.frame_rate());
+    println!("Frames:         {}", header.num_frames());
+    if let Some(color) = header.background_color() {
+        println!(
+            "Background:     #{:02X}{:02X}{:02X}",
+            color.r, color.g, color.b
+        );
+    }
+    println!(
+        "ActionScript:   {}",
+        if header.is_action_script_3() { 3 } else { 2 }
+    );
+    if !attributes.is_empty() {
+        println!("Attributes:     {}", attributes.join(", "));
+    }
+    Ok(())
+}
+
+pub fn tags_main(opt: TagsOpt) -> Result<()> {
+    let (_, swf_buf) = load_swf(&opt.input_path)?;
+    let tags = read_raw_tags(
+        &swf_buf.data,
+        tags_offset(&swf_buf),
+        swf_buf.header.version(),
+    );
+
+    println!("{:<10} {:>10} {:>10}  Tag", "Index", "Offset", "Length");
+    print_tags(&tags, "", !opt.no_sprites);
+    Ok(())
+}
+
+fn print_tags(tags: &[RawTag], prefix: &str, recurse: bool) {
+    for (index, tag) in tags.iter().enumerate() {
+        let path = format!("{prefix}{index}");
+        let mut name = tag.name();
+        if let Some(id) = tag.character_id() {
+            name = format!("{name} #{id}");
+        }
+        println!(
+            "{path:<10} {:>#10x} {:>10}  {name}",
+            tag.offset,
+            tag.data().len()
+        );
+        if recurse {
+            print_tags(&tag.children, &format!("{path}."), recurse);
+        }
+    }
+}
+
+/// Returns the offset of the tag stream within the uncompressed SWF.
+fn tags_offset(swf_buf: &swf::SwfBuf) -> usize {
+    usize::try_from(swf_buf.header.uncompressed_len())
+        .ok()
+        .and_then(|len| len.checked_sub(swf_buf.data.len()))
+        .unwrap_or_else(|| {
+            // The length in the header is wrong, so write the header again to
+            // find its size.
+            let mut header = vec![];
+            let _ = swf::write::write_swf_raw_tags(
+                &swf::Header {
+                    compression: Compression::None,
+                    ..swf_buf.header.swf_header().clone()
+                },
+                &[],
+                &mut header,
+            );
+            header.len()
+        })
+}
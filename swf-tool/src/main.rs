@@ -0,0 +1,38 @@
+use crate::cli_options::{Mode, Opt};
+use crate::extract::extract_main;
+use crate::inspect::{header_main, tags_main};
+use crate::rebuild::{recompress_main, replace_main};
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::path::Path;
+use swf::SwfBuf;
+
+mod bitmap;
+mod cli_options;
+mod extract;
+mod font;
+mod inspect;
+mod raw;
+mod rebuild;
+mod sound;
+
+/// Reads and decompresses a SWF file, returning its length on disk along
+/// with its contents.
+fn load_swf(path: &Path) -> Result<(usize, SwfBuf)> {
+    let data = std::fs::read(path).with_context(|| format!("Couldn't read {}", path.display()))?;
+    let swf_buf = swf::decompress_swf(&data[..])
+        .with_context(|| format!("Couldn't decompress {}", path.display()))?;
+    Ok((data.len(), swf_buf))
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::parse();
+
+    match opt.mode {
+        Mode::Header(header_opt) => header_main(header_opt),
+        Mode::Tags(tags_opt) => tags_main(tags_opt),
+        Mode::Extract(extract_opt) => extract_main(extract_opt),
+        Mode::Replace(replace_opt) => replace_main(replace_opt),
+        Mode::Recompress(recompress_opt) => recompress_main(recompress_opt),
+    }
+}
@@ -0,0 +1,146 @@
+//! Reading tags without parsing their contents, so that they can be listed
+//! and written back exactly as they were.
+
+use swf::read::Reader;
+use swf::{Tag, TagCode};
+
+/// A tag as it appears in a SWF, along with its position.
+pub struct RawTag<'a> {
+    /// The offset of the tag within the uncompressed SWF.
+    pub offset: usize,
+
+    pub code: u16,
+
+    /// The whole tag, including its header.
+    pub bytes: &'a [u8],
+
+    pub header_len: usize,
+
+    /// The tags in the timeline of a `DefineSprite`.
+    pub children: Vec<RawTag<'a>>,
+}
+
+impl<'a> RawTag<'a> {
+    /// The contents of the tag, after its header.
+    pub fn data(&self) -> &'a [u8] {
+        &self.bytes[self.header_len..]
+    }
+
+    pub fn name(&self) -> String {
+        TagCode::format(self.code)
+    }
+
+    /// The ID of the character defined or used by this tag, which comes first
+    /// in its contents.
+    pub fn character_id(&self) -> Option<u16> {
+        let has_id = match TagCode::from_u16(self.code)? {
+            TagCode::DefineSceneAndFrameLabelData => false,
+            TagCode::DoInitAction | TagCode::CsmTextSettings => true,
+            code => format!("{code:?}").starts_with("Define"),
+        };
+        match self.data() {
+            [a, b, ..] if has_id => Some(u16::from_le_bytes([*a, *b])),
+            _ => None,
+        }
+    }
+
+    pub fn parse(&self, swf_version: u8) -> swf::error::Result<Tag<'a>> {
+        Reader::new(self.bytes, swf_version).read_tag()
+    }
+
+    /// Returns this tag with its contents replaced, keeping the form of its
+    /// header, as some tags are expected to use the long form.
+    pub fn with_data(&self, data: &[u8]) -> Vec<u8> {
+        let mut bytes = tag_header(self.code, data.len(), self.header_len > 2);
+        bytes.extend(data);
+        bytes
+    }
+}
+
+/// Reads a list of tags up to its `End` tag, or the end of the data.
+///
+/// `offset` is the offset of `data` within the uncompressed SWF.
+pub fn read_raw_tags(data: &[u8], offset: usize, swf_version: u8) -> Vec<RawTag<'_>> {
+    let mut tags = vec![];
+    let mut reader = Reader::new(data, swf_version);
+    loop {
+        let start = data.len() - reader.get_ref().len();
+        let Ok((code, length)) = reader.read_tag_code_and_length() else {
+            break;
+        };
+        if code == TagCode::End as u16 {
+            break;
+        }
+        let header_len = data.len() - reader.get_ref().len() - start;
+
+        // A truncated tag keeps whatever data is left.
+        let end = (start + header_len).saturating_add(length).min(data.len());
+        let bytes = &data[start..end];
+        *reader.get_mut() = &data[end..];
+
+        // A sprite's ID and frame count come before its tags.
+        let children = match bytes.get(header_len + 4..) {
+            Some(sprite_tags) if code == TagCode::DefineSprite as u16 => {
+                read_raw_tags(sprite_tags, offset + start + header_len + 4, swf_version)
+            }
+            _ => vec![],
+        };
+
+        tags.push(RawTag {
+            offset: offset + start,
+            code,
+            bytes,
+            header_len,
+            children,
+        });
+    }
+    tags
+}
+
+/// Parses a tag path as shown by `swf-tool tags`, such as `12` for the
+/// 12th tag of the main timeline, or `12.3` for the 3rd tag in its sprite.
+pub fn parse_tag_path(path: &str) -> Option<Vec<usize>> {
+    path.split('.').map(|index| index.parse().ok()).collect()
+}
+
+pub fn find_tag<'a, 'b>(tags: &'b [RawTag<'a>], path: &[usize]) -> Option<&'b RawTag<'a>> {
+    let (&index, rest) = path.split_first()?;
+    let tag = tags.get(index)?;
+    if rest.is_empty() {
+        Some(tag)
+    } else {
+        find_tag(&tag.children, rest)
+    }
+}
+
+/// Returns the tag list with the tag at `path` replaced by `replacement`,
+/// which includes its header. Every other tag is written back as is.
+pub fn replace_tag(tags: &[RawTag], path: &[usize], replacement: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    for (index, tag) in tags.iter().enumerate() {
+        match path.split_first() {
+            Some((&target, [])) if target == index => out.extend(replacement),
+            Some((&target, rest)) if target == index => {
+                // The sprite is rebuilt around its replaced timeline.
+                let mut data = tag.data()[..4].to_vec();
+                data.extend(replace_tag(&tag.children, rest, replacement));
+                out.extend(tag.with_data(&data));
+            }
+            _ => out.extend(tag.bytes),
+        }
+    }
+    out.extend(tag_header(TagCode::End as u16, 0, false));
+    out
+}
+
+/// Writes a tag header, in its long form if `is_long` is set or the length
+/// requires it.
+pub fn tag_header(code: u16, length: usize, is_long: bool) -> Vec<u8> {
+    if is_long || length >= 0b111111 {
+        let mut header = (code << 6 | 0b111111).to_le_bytes().to_vec();
+        header.extend((length as u32).to_le_bytes());
+        header
+    } else {
+        (code << 6 | length as u16).to_le_bytes().to_vec()
+    }
+}
@@ -0,0 +1,106 @@
+use crate::cli_options::{RecompressOpt, ReplaceOpt};
+use crate::load_swf;
+use crate::raw::{find_tag, parse_tag_path, read_raw_tags, replace_tag, RawTag};
+use anyhow::{anyhow, Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use swf::{DefineBinaryData, DoAbc2, Header, Tag};
+
+pub fn replace_main(opt: ReplaceOpt) -> Result<()> {
+    let (_, swf_buf) = load_swf(&opt.input_path)?;
+    let version = swf_buf.header.version();
+    let tags = read_raw_tags(&swf_buf.data, 0, version);
+
+    let path = parse_tag_path(&opt.tag).ok_or_else(|| anyhow!("Invalid tag {:?}", opt.tag))?;
+    let tag = find_tag(&tags, &path).ok_or_else(|| anyhow!("No tag {}", opt.tag))?;
+    let replacement = std::fs::read(&opt.replacement_path)
+        .with_context(|| format!("Couldn't read {}", opt.replacement_path.display()))?;
+
+    let data = if opt.raw {
+        replacement
+    } else {
+        replace_contents(tag, &replacement, version)?
+    };
+    let tag_list = replace_tag(&tags, &path, &tag.with_data(&data));
+    write_swf(&opt.output_path, swf_buf.header.swf_header(), &tag_list)
+}
+
+/// Returns the contents of a tag with the asset it holds replaced.
+fn replace_contents(tag: &RawTag, replacement: &[u8], version: u8) -> Result<Vec<u8>> {
+    let parsed = tag
+        .parse(version)
+        .with_context(|| format!("Couldn't parse {}", tag.name()))?;
+    let new_tag = match parsed {
+        Tag::DefineBinaryData(DefineBinaryData { id, .. }) => {
+            Tag::DefineBinaryData(DefineBinaryData {
+                id,
+                data: replacement,
+            })
+        }
+        // JPEG, PNG and GIF images can all be stored in a `DefineBitsJpeg2`.
+        Tag::DefineBitsJpeg2 { id, .. } => Tag::DefineBitsJpeg2 {
+            id,
+            jpeg_data: replacement,
+        },
+        Tag::DoAbc(_) => Tag::DoAbc(replacement),
+        Tag::DoAbc2(DoAbc2 { flags, name, .. }) => Tag::DoAbc2(DoAbc2 {
+            flags,
+            name,
+            data: replacement,
+        }),
+        Tag::DoAction(_) => Tag::DoAction(replacement),
+        Tag::DoInitAction { id, .. } => Tag::DoInitAction {
+            id,
+            action_data: replacement,
+        },
+        _ => {
+            return Err(anyhow!(
+                "Can't replace the contents of {}; use --raw to replace the whole tag body",
+                tag.name()
+            ))
+        }
+    };
+    encode_tag(&new_tag, version)
+}
+
+/// Returns the contents of a tag as written by the `swf` crate.
+fn encode_tag(tag: &Tag, version: u8) -> Result<Vec<u8>> {
+    let mut swf_data = vec![];
+    swf::write_swf(
+        &Header::default_with_swf_version(version),
+        std::slice::from_ref(tag),
+        &mut swf_data,
+    )?;
+    let swf_buf = swf::decompress_swf(&swf_data[..])?;
+    let tags = read_raw_tags(&swf_buf.data, 0, version);
+    let tag = tags
+        .first()
+        .ok_or_else(|| anyhow!("Couldn't write {:?}", tag))?;
+    Ok(tag.data().to_vec())
+}
+
+pub fn recompress_main(opt: RecompressOpt) -> Result<()> {
+    let (file_len, swf_buf) = load_swf(&opt.input_path)?;
+    let header = Header {
+        compression: opt.compression.into(),
+        ..swf_buf.header.swf_header().clone()
+    };
+    write_swf(&opt.output_path, &header, &swf_buf.data)?;
+
+    let new_len = std::fs::metadata(&opt.output_path)?.len();
+    println!(
+        "Wrote {} ({file_len} -> {new_len} bytes)",
+        opt.output_path.display()
+    );
+    Ok(())
+}
+
+fn write_swf(path: &Path, header: &Header, tag_list: &[u8]) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("Couldn't create {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    swf::write::write_swf_raw_tags(header, tag_list, &mut writer)
+        .with_context(|| format!("Couldn't write {}", path.display()))?;
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! Conversion of SWF sounds into WAV and MP3 files.
+
+use bitstream_io::{BigEndian, BitRead, BitReader};
+use swf::{AudioCompression, SoundFormat};
+
+pub enum SoundFile {
+    Wav(Vec<u8>),
+    Mp3(Vec<u8>),
+}
+
+impl SoundFile {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SoundFile::Wav(_) => "wav",
+            SoundFile::Mp3(_) => "mp3",
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        match self {
+            SoundFile::Wav(data) | SoundFile::Mp3(data) => data,
+        }
+    }
+}
+
+/// Converts the blocks of a sound into a file, or returns `None` if its
+/// compression isn't supported.
+///
+/// MP3 data is preceded by a header of `mp3_header_len` bytes in each block:
+/// 2 bytes for a `DefineSound`, and 4 bytes for a `SoundStreamBlock`.
+pub fn convert_sound(
+    format: &SoundFormat,
+    blocks: &[&[u8]],
+    mp3_header_len: usize,
+) -> Option<SoundFile> {
+    let num_channels = if format.is_stereo { 2 } else { 1 };
+    let sample_rate = format.sample_rate.into();
+    Some(match format.compression {
+        AudioCompression::Uncompressed | AudioCompression::UncompressedUnknownEndian => {
+            // 8-bit samples are unsigned in both SWF and WAV.
+            let bits_per_sample = if format.is_16_bit { 16 } else { 8 };
+            SoundFile::Wav(wav_file(
+                num_channels,
+                sample_rate,
+                bits_per_sample,
+                &blocks.concat(),
+            ))
+        }
+        AudioCompression::Adpcm => {
+            let samples: Vec<u8> = blocks
+                .iter()
+                .flat_map(|block| decode_adpcm(block, format.is_stereo))
+                .flat_map(i16::to_le_bytes)
+                .collect();
+            SoundFile::Wav(wav_file(num_channels, sample_rate, 16, &samples))
+        }
+        AudioCompression::Mp3 => SoundFile::Mp3(
+            blocks
+                .iter()
+                .filter_map(|block| block.get(mp3_header_len..))
+                .flatten()
+                .copied()
+                .collect(),
+        ),
+        AudioCompression::Nellymoser16Khz
+        | AudioCompression::Nellymoser8Khz
+        | AudioCompression::Nellymoser
+        | AudioCompression::Speex => return None,
+    })
+}
+
+fn wav_file(num_channels: u16, sample_rate: u32, bits_per_sample: u16, samples: &[u8]) -> Vec<u8> {
+    let block_align = num_channels * bits_per_sample / 8;
+    let mut out = Vec::with_capacity(44 + samples.len());
+    out.extend(b"RIFF");
+    out.extend((36 + samples.len() as u32).to_le_bytes());
+    out.extend(b"WAVE");
+    out.extend(b"fmt ");
+    out.extend(16u32.to_le_bytes());
+    out.extend(1u16.to_le_bytes()); // PCM
+    out.extend(num_channels.to_le_bytes());
+    out.extend(sample_rate.to_le_bytes());
+    out.extend((sample_rate * u32::from(block_align)).to_le_bytes());
+    out.extend(block_align.to_le_bytes());
+    out.extend(bits_per_sample.to_le_bytes());
+    out.extend(b"data");
+    out.extend((samples.len() as u32).to_le_bytes());
+    out.extend(samples);
+    out
+}
+
+const ADPCM_INDEX_TABLE: [&[i16]; 4] = [
+    &[-1, 2],
+    &[-1, -1, 2, 4],
+    &[-1, -1, -1, -1, 2, 4, 6, 8],
+    &[-1, -1, -1, -1, -1, -1, -1, -1, 1, 2, 4, 6, 8, 10, 13, 16],
+];
+
+const ADPCM_STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449,
+    494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272,
+    2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493,
+    10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+/// Decodes ADPCM data into interleaved 16-bit samples.
+///
+/// The data is made of packets of 4096 samples per channel, each starting
+/// with an uncompressed sample. [SWF19 pp.190-191]
+fn decode_adpcm(data: &[u8], is_stereo: bool) -> Vec<i16> {
+    let mut reader = BitReader::endian(data, BigEndian);
+    let Ok(bits_per_sample) = reader.read::<u32>(2).map(|bits| bits + 2) else {
+        return vec![];
+    };
+    let index_table = ADPCM_INDEX_TABLE[bits_per_sample as usize - 2];
+    let sign_mask = 1 << (bits_per_sample - 1);
+
+    let num_channels = if is_stereo { 2 } else { 1 };
+    let mut channels = [(0i32, 0i16); 2];
+    let mut samples = vec![];
+    'packets: loop {
+        for (sample, step_index) in &mut channels[..num_channels] {
+            let (Ok(initial_sample), Ok(initial_index)) =
+                (reader.read_signed::<i16>(16), reader.read::<u16>(6))
+            else {
+                break 'packets;
+            };
+            *sample = initial_sample.into();
+            *step_index = initial_index as i16;
+            samples.push(initial_sample);
+        }
+
+        for _ in 1..4096 {
+            for (sample, step_index) in &mut channels[..num_channels] {
+                let Ok(code) = reader.read::<u32>(bits_per_sample) else {
+                    break 'packets;
+                };
+                let magnitude = code & !sign_mask;
+
+                // delta = (magnitude + 0.5) * step / 2^(bits_per_sample - 2)
+                let step = ADPCM_STEP_TABLE[*step_index as usize];
+                let mut delta = step >> (bits_per_sample - 1);
+                for bit in 0..bits_per_sample - 1 {
+                    if magnitude & (1 << bit) != 0 {
+                        delta += step >> (bits_per_sample - 2 - bit);
+                    }
+                }
+                if code & sign_mask != 0 {
+                    delta = -delta;
+                }
+                *sample = (*sample + delta).clamp(i16::MIN.into(), i16::MAX.into());
+
+                *step_index = (*step_index + index_table[magnitude as usize])
+                    .clamp(0, ADPCM_STEP_TABLE.len() as i16 - 1);
+                samples.push(*sample as i16);
+            }
+        }
+    }
+    samples
+}
@@ -0,0 +1,220 @@
+//! Tests of the `swf-tool` commands, run against `tests/swfs/assets.swf`.
+//!
+//! The SWF holds one asset of each kind that can be extracted: a
+//! `DefineBitsLossless2` (#1), a `DefineBitsJpeg3` (#2), a `DefineSound` (#3),
+//! a `DefineFont2` (#4), a `DoAbc2` named "dummy", and a `DefineBinaryData`
+//! exported as "Greeting" (#5), along with another `DefineBinaryData` (#7)
+//! inside a sprite (#6).
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use swf::{Compression, Tag};
+use tempfile::tempdir;
+
+fn assets_swf() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/swfs/assets.swf")
+}
+
+/// Runs `swf-tool` and returns its standard output, or its standard error if
+/// it failed.
+fn run(args: &[&dyn AsRef<std::ffi::OsStr>]) -> Result<String, String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_swf-tool"))
+        .args(args.iter().map(|arg| arg.as_ref()))
+        .output()
+        .expect("swf-tool should run");
+    if output.status.success() {
+        Ok(String::from_utf8(output.stdout).unwrap())
+    } else {
+        Err(String::from_utf8(output.stderr).unwrap())
+    }
+}
+
+fn read_swf(path: &Path) -> swf::SwfBuf {
+    let data = std::fs::read(path).unwrap();
+    swf::decompress_swf(&data[..]).unwrap()
+}
+
+fn binary_data(tags: &[Tag], id: swf::CharacterId) -> Option<Vec<u8>> {
+    tags.iter().find_map(|tag| match tag {
+        Tag::DefineBinaryData(binary_data) if binary_data.id == id => {
+            Some(binary_data.data.to_vec())
+        }
+        Tag::DefineSprite(sprite) => binary_data(&sprite.tags, id),
+        _ => None,
+    })
+}
+
+#[test]
+fn header() {
+    let output = run(&[&"header", &assets_swf()]).unwrap();
+    assert_eq!(
+        output,
+        "Signature:      CWS (zlib)\n\
+         Version:        10\n\
+         File length:    404 bytes (649 uncompressed)\n\
+         Stage size:     320 x 240 pixels\n\
+         Frame rate:     30\n\
+         Frames:         1\n\
+         Background:     #336699\n\
+         ActionScript:   3\n"
+    );
+}
+
+#[test]
+fn tags() {
+    let output = run(&[&"tags", &assets_swf()]).unwrap();
+    assert_eq!(
+        output,
+        "Index          Offset     Length  Tag\n\
+         0                0x14          4  FileAttributes\n\
+         1                0x1a          3  SetBackgroundColor\n\
+         2                0x1f         22  DefineBitsLossless2 #1\n\
+         3                0x37        306  DefineBitsJpeg3 #2\n\
+         4               0x16f         27  DefineSound #3\n\
+         5               0x18c         60  DefineFont2 #4\n\
+         6               0x1ca        114  DoAbc2\n\
+         7               0x242         26  DefineBinaryData #5\n\
+         8               0x25e         22  DefineSprite #6\n\
+         8.0             0x264         12  DefineBinaryData #7\n\
+         8.1             0x272          0  ShowFrame\n\
+         9               0x276         13  SymbolClass\n\
+         10              0x285          0  ShowFrame\n"
+    );
+
+    let output = run(&[&"tags", &"--no-sprites", &assets_swf()]).unwrap();
+    assert!(output.contains("DefineSprite #6"));
+    assert!(!output.contains("8.0"));
+}
+
+#[test]
+fn extract() {
+    let dir = tempdir().unwrap();
+    let output = run(&[&"extract", &assets_swf(), &dir.path()]).unwrap();
+    assert!(output.ends_with("Extracted 7 files\n"), "{output}");
+
+    // Both images are blue at 50% alpha, stored premultiplied in the SWF.
+    for path in ["images/1.png", "images/2.png"] {
+        let image = image::open(dir.path().join(path)).unwrap().into_rgba8();
+        assert_eq!(image.dimensions(), (8, 8));
+        assert!(image.pixels().all(|pixel| pixel.0 == [0, 0, 251, 128]));
+    }
+
+    let wav = std::fs::read(dir.path().join("sounds/3.wav")).unwrap();
+    assert_eq!(&wav[..4], b"RIFF");
+    assert_eq!(&wav[8..16], b"WAVEfmt ");
+    // 44.1 kHz, mono, 16-bit samples.
+    assert_eq!(&wav[22..24], &1u16.to_le_bytes());
+    assert_eq!(&wav[24..28], &44100u32.to_le_bytes());
+    assert_eq!(&wav[34..36], &16u16.to_le_bytes());
+    assert_eq!(&wav[36..44], b"data\x14\0\0\0");
+
+    let ttf = std::fs::read(dir.path().join("fonts/4.ttf")).unwrap();
+    assert_eq!(&ttf[..4], &[0, 1, 0, 0]);
+
+    let swf_buf = read_swf(&assets_swf());
+    let swf = swf::parse_swf(&swf_buf).unwrap();
+    let abc = swf
+        .tags
+        .iter()
+        .find_map(|tag| match tag {
+            Tag::DoAbc2(do_abc) => Some(do_abc.data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        std::fs::read(dir.path().join("abc/0_dummy.abc")).unwrap(),
+        abc
+    );
+
+    assert_eq!(
+        std::fs::read(dir.path().join("binary_data/5_Greeting.bin")).unwrap(),
+        b"Hello from swf-tool!"
+    );
+    assert_eq!(
+        std::fs::read(dir.path().join("binary_data/7.bin")).unwrap(),
+        b"Nested"
+    );
+}
+
+#[test]
+fn extract_only() {
+    let dir = tempdir().unwrap();
+    let output = run(&[
+        &"extract",
+        &assets_swf(),
+        &dir.path(),
+        &"--only",
+        &"binary-data,abc",
+    ])
+    .unwrap();
+    assert!(output.ends_with("Extracted 3 files\n"), "{output}");
+    assert!(dir.path().join("binary_data/7.bin").exists());
+    assert!(dir.path().join("abc/0_dummy.abc").exists());
+    assert!(!dir.path().join("images").exists());
+}
+
+#[test]
+fn replace() {
+    let dir = tempdir().unwrap();
+    let replacement = dir.path().join("replacement.bin");
+    std::fs::write(&replacement, b"Replaced").unwrap();
+    let output_swf = dir.path().join("output.swf");
+
+    // Tags inside of sprites are replaced through their path.
+    run(&[&"replace", &assets_swf(), &"8.0", &replacement, &output_swf]).unwrap();
+    let swf_buf = read_swf(&output_swf);
+    let swf = swf::parse_swf(&swf_buf).unwrap();
+    assert_eq!(swf.header.compression(), Compression::Zlib);
+    assert_eq!(binary_data(&swf.tags, 5).unwrap(), b"Hello from swf-tool!");
+    assert_eq!(binary_data(&swf.tags, 7).unwrap(), b"Replaced");
+
+    // A raw replacement is the whole body of the tag, including its ID.
+    std::fs::write(&replacement, b"\x05\x00\0\0\0\0Raw").unwrap();
+    run(&[
+        &"replace",
+        &assets_swf(),
+        &"7",
+        &replacement,
+        &output_swf,
+        &"--raw",
+    ])
+    .unwrap();
+    let swf_buf = read_swf(&output_swf);
+    let swf = swf::parse_swf(&swf_buf).unwrap();
+    assert_eq!(binary_data(&swf.tags, 5).unwrap(), b"Raw");
+    assert_eq!(binary_data(&swf.tags, 7).unwrap(), b"Nested");
+}
+
+#[test]
+fn replace_errors() {
+    let dir = tempdir().unwrap();
+    let output_swf = dir.path().join("output.swf");
+    let replacement = dir.path().join("replacement.bin");
+    std::fs::write(&replacement, b"Replaced").unwrap();
+
+    let error = run(&[&"replace", &assets_swf(), &"3", &replacement, &output_swf]).unwrap_err();
+    assert!(error.contains("Can't replace the contents of DefineBitsJpeg3"));
+    let error = run(&[&"replace", &assets_swf(), &"8.5", &replacement, &output_swf]).unwrap_err();
+    assert!(error.contains("No tag 8.5"));
+    assert!(!output_swf.exists());
+}
+
+#[test]
+fn recompress() {
+    let dir = tempdir().unwrap();
+    let original = read_swf(&assets_swf());
+    for (name, compression) in [
+        ("none", Compression::None),
+        ("zlib", Compression::Zlib),
+        ("lzma", Compression::Lzma),
+    ] {
+        let output_swf = dir.path().join(format!("{name}.swf"));
+        let output = run(&[&"recompress", &assets_swf(), &output_swf, &"-c", &name]).unwrap();
+        assert!(output.starts_with("Wrote "), "{output}");
+
+        let swf_buf = read_swf(&output_swf);
+        assert_eq!(swf_buf.header.compression(), compression);
+        assert_eq!(swf_buf.header.version(), 10);
+        assert_eq!(swf_buf.data, original.data);
+    }
+}
@@ -0,0 +1,369 @@
+//! Helpers shared by the textual forms of AVM1 and AVM2 bytecode.
+//!
+//! Both forms are line-based: each line holds a single statement made of
+//! words, string literals and punctuation, and `;` starts a comment.
+
+use crate::error::{AssembleError, AssembleResult};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Writes a byte string as a quoted string literal.
+///
+/// Bytes which aren't valid UTF-8 are escaped, so that the literal always reads
+/// back as the exact same bytes.
+pub(crate) fn write_string_literal(out: &mut String, s: &[u8]) {
+    out.push('"');
+    for chunk in s.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => {
+                    for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                        let _ = write!(out, "\\x{byte:02x}");
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            let _ = write!(out, "\\x{byte:02x}");
+        }
+    }
+    out.push('"');
+}
+
+/// Returns a byte string as a quoted string literal.
+pub(crate) fn string_literal(s: &[u8]) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    write_string_literal(&mut out, s);
+    out
+}
+
+/// Formats bytes as a `bytes` statement.
+pub(crate) fn hex_bytes(bytes: &[u8]) -> String {
+    let bytes: Vec<_> = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("bytes {}", bytes.join(" "))
+}
+
+/// Formats a double so that it parses back to the same bits.
+pub(crate) fn format_double(value: f64) -> String {
+    // Only the usual NaN survives being formatted as a float.
+    if value.is_nan() && value.to_bits() != f64::NAN.to_bits() {
+        format!("0x{:016x}", value.to_bits())
+    } else {
+        format!("{value:?}")
+    }
+}
+
+/// Formats a float so that it parses back to the same bits.
+pub(crate) fn format_float(value: f32) -> String {
+    if value.is_nan() && value.to_bits() != f32::NAN.to_bits() {
+        format!("0x{:08x}", value.to_bits())
+    } else {
+        format!("{value:?}")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Token<'a> {
+    /// A keyword, number, label or any other bare word.
+    Word(&'a str),
+
+    /// A string literal, with its escapes already resolved.
+    Str(Vec<u8>),
+
+    Punct(char),
+}
+
+fn is_punct(c: char) -> bool {
+    matches!(c, '(' | ')' | '[' | ']' | '<' | '>' | ',' | ':')
+}
+
+fn tokenize(line: &str, line_number: usize) -> AssembleResult<Vec<Token<'_>>> {
+    let mut tokens = vec![];
+    let mut chars = line.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c == ';' {
+            break;
+        } else if c.is_whitespace() {
+            chars.next();
+        } else if is_punct(c) {
+            chars.next();
+            tokens.push(Token::Punct(c));
+        } else if c == '"' {
+            chars.next();
+            let mut s = vec![];
+            loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, '"')) => s.push(b'"'),
+                        Some((_, '\\')) => s.push(b'\\'),
+                        Some((_, 'n')) => s.push(b'\n'),
+                        Some((_, 'r')) => s.push(b'\r'),
+                        Some((_, 't')) => s.push(b'\t'),
+                        Some((i, 'x')) => {
+                            let byte = line
+                                .get(i + 1..i + 3)
+                                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                                .ok_or_else(|| {
+                                    AssembleError::new(line_number, "Invalid \\x escape")
+                                })?;
+                            chars.next();
+                            chars.next();
+                            s.push(byte);
+                        }
+                        _ => {
+                            return Err(AssembleError::new(line_number, "Invalid escape"));
+                        }
+                    },
+                    Some((_, c)) => s.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                    None => {
+                        return Err(AssembleError::new(
+                            line_number,
+                            "Unterminated string literal",
+                        ))
+                    }
+                }
+            }
+            tokens.push(Token::Str(s));
+        } else {
+            let mut end = line.len();
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_whitespace() || is_punct(c) || c == '"' || c == ';' {
+                    end = i;
+                    break;
+                }
+                chars.next();
+            }
+            tokens.push(Token::Word(&line[start..end]));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A single line of source text, split into tokens.
+pub(crate) struct Statement<'a> {
+    /// The 1-based line number of this statement.
+    pub line: usize,
+
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Statement<'a> {
+    /// Splits source text into statements, leaving out empty lines.
+    pub fn parse_all(source: &'a str) -> AssembleResult<Vec<Self>> {
+        let mut statements = vec![];
+        for (i, line) in source.lines().enumerate() {
+            let tokens = tokenize(line, i + 1)?;
+            if !tokens.is_empty() {
+                statements.push(Self {
+                    line: i + 1,
+                    tokens,
+                    pos: 0,
+                });
+            }
+        }
+        Ok(statements)
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> AssembleError {
+        AssembleError::new(self.line, message)
+    }
+
+    pub fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    pub fn peek_word(&self) -> Option<&'a str> {
+        match self.peek() {
+            Some(Token::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    pub fn peek_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    /// Checks that the whole statement was consumed.
+    pub fn finish(&self) -> AssembleResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(self.error(format!("Unexpected {}", describe(token)))),
+        }
+    }
+
+    pub fn word(&mut self) -> AssembleResult<&'a str> {
+        match self.peek() {
+            Some(&Token::Word(word)) => {
+                self.pos += 1;
+                Ok(word)
+            }
+            token => Err(self.unexpected(token, "a word")),
+        }
+    }
+
+    /// Consumes the given word, if it is next.
+    pub fn eat_word(&mut self, word: &str) -> bool {
+        if self.peek_word() == Some(word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expect_word(&mut self, word: &str) -> AssembleResult<()> {
+        if self.eat_word(word) {
+            Ok(())
+        } else {
+            Err(self.unexpected(self.peek(), &format!("`{word}`")))
+        }
+    }
+
+    pub fn string(&mut self) -> AssembleResult<Vec<u8>> {
+        match self.peek() {
+            Some(Token::Str(s)) => {
+                let s = s.clone();
+                self.pos += 1;
+                Ok(s)
+            }
+            token => Err(self.unexpected(token, "a string")),
+        }
+    }
+
+    /// Consumes the given punctuation, if it is next.
+    pub fn eat_punct(&mut self, c: char) -> bool {
+        if self.peek_punct(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn punct(&mut self, c: char) -> AssembleResult<()> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(self.peek(), &format!("`{c}`")))
+        }
+    }
+
+    /// Parses a word as a number.
+    pub fn number<T: FromStr>(&mut self) -> AssembleResult<T> {
+        let word = self.word()?;
+        word.parse()
+            .map_err(|_| self.error(format!("Invalid number `{word}`")))
+    }
+
+    /// Parses a double written by [`format_double`].
+    pub fn double(&mut self) -> AssembleResult<f64> {
+        let word = self.word()?;
+        let value = match word.strip_prefix("0x") {
+            Some(bits) => u64::from_str_radix(bits, 16).ok().map(f64::from_bits),
+            None => word.parse().ok(),
+        };
+        value.ok_or_else(|| self.error(format!("Invalid number `{word}`")))
+    }
+
+    /// Parses a float written by [`format_float`].
+    pub fn float(&mut self) -> AssembleResult<f32> {
+        let word = self.word()?;
+        let value = match word.strip_prefix("0x") {
+            Some(bits) => u32::from_str_radix(bits, 16).ok().map(f32::from_bits),
+            None => word.parse().ok(),
+        };
+        value.ok_or_else(|| self.error(format!("Invalid number `{word}`")))
+    }
+
+    /// Parses the rest of a statement written by [`hex_bytes`], after its
+    /// `bytes` keyword.
+    pub fn hex_bytes(&mut self) -> AssembleResult<Vec<u8>> {
+        let mut bytes = vec![];
+        while !self.is_finished() {
+            let byte = self.word()?;
+            bytes.push(
+                u8::from_str_radix(byte, 16)
+                    .map_err(|_| self.error(format!("Invalid byte `{byte}`")))?,
+            );
+        }
+        Ok(bytes)
+    }
+
+    fn unexpected(&self, token: Option<&Token>, expected: &str) -> AssembleError {
+        match token {
+            Some(token) => self.error(format!("Expected {expected}, found {}", describe(token))),
+            None => self.error(format!("Expected {expected}")),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("`{word}`"),
+        Token::Str(s) => string_literal(s),
+        Token::Punct(c) => format!("`{c}`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_literal_round_trip() {
+        let strings: &[&[u8]] = &[
+            b"",
+            b"hello world",
+            b"\"quoted\" \\ back\\slash",
+            b"line\nbreak\ttab\r",
+            b"\x00\x01\x7f",
+            "caf\u{e9} \u{85}".as_bytes(),
+            b"invalid \xff\xfe utf-8 \xc3",
+        ];
+        for &s in strings {
+            let literal = string_literal(s);
+            let statements = Statement::parse_all(&literal).unwrap();
+            assert_eq!(statements[0].tokens, [Token::Str(s.to_vec())], "{literal}");
+        }
+    }
+
+    #[test]
+    fn tokenize_line() {
+        let tokens = tokenize(
+            "L1: callproperty QName(PackageNamespace(\"\"), \"a;b\"), 1 ; comment",
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            tokens,
+            [
+                Token::Word("L1"),
+                Token::Punct(':'),
+                Token::Word("callproperty"),
+                Token::Word("QName"),
+                Token::Punct('('),
+                Token::Word("PackageNamespace"),
+                Token::Punct('('),
+                Token::Str(vec![]),
+                Token::Punct(')'),
+                Token::Punct(','),
+                Token::Str(b"a;b".to_vec()),
+                Token::Punct(')'),
+                Token::Punct(','),
+                Token::Word("1"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,622 @@
+//! Assembly of AVM1 actions from the p-code written by [`disassemble`].
+//!
+//! A pushed `constant` written as a string is looked up in the last
+//! `constantpool` before it.
+//!
+//! [`disassemble`]: crate::avm1::disassemble::disassemble
+
+use crate::assembly::{string_literal, Statement};
+use crate::avm1::opcode::OpCode;
+use crate::avm1::types::*;
+use crate::avm1::write::Writer;
+use crate::error::{AssembleError, AssembleResult};
+use crate::string::SwfStr;
+use std::collections::HashMap;
+use std::num::NonZeroU8;
+
+/// Assembles a block of actions from p-code.
+pub fn assemble(source: &str, swf_version: u8) -> AssembleResult<Vec<u8>> {
+    let statements = Statement::parse_all(source)?;
+    let mut assembler = Assembler {
+        swf_version,
+        last_line: statements.last().map_or(0, |statement| statement.line),
+        statements: statements.into_iter(),
+        opcodes: (0..=u8::MAX)
+            .filter_map(OpCode::from_u8)
+            .map(|opcode| (format!("{opcode:?}").to_lowercase(), opcode))
+            .collect(),
+        constant_pool: vec![],
+    };
+    let (actions, _) = assembler.block(0, &[])?;
+    Ok(actions)
+}
+
+/// A branch target.
+enum Offset {
+    Label(String),
+    Offset(i16),
+}
+
+/// A line of p-code within a block.
+enum BlockItem {
+    Label(String),
+    Bytes(Vec<u8>),
+    Branch(OpCode, Offset),
+}
+
+/// A value pushed by a `push` action.
+enum PushValue {
+    Value(Value<'static>),
+    Str(Vec<u8>),
+}
+
+/// The statement ending a nested block, along with its first word.
+type Terminator<'a> = (&'a str, Statement<'a>);
+
+/// The size of a branch action: its opcode, length and offset.
+const BRANCH_SIZE: usize = 5;
+
+struct Assembler<'a> {
+    swf_version: u8,
+    statements: std::vec::IntoIter<Statement<'a>>,
+    last_line: usize,
+
+    /// The opcode named by each mnemonic.
+    opcodes: HashMap<String, OpCode>,
+
+    /// The strings of the last `constantpool` assembled.
+    constant_pool: Vec<Vec<u8>>,
+}
+
+impl<'a> Assembler<'a> {
+    /// Assembles a block of actions, up to a statement starting with one of
+    /// the `terminators`.
+    ///
+    /// The terminator is returned along with the rest of its statement. The
+    /// top-level block has no terminators, and ends with the source.
+    fn block(
+        &mut self,
+        line: usize,
+        terminators: &[&str],
+    ) -> AssembleResult<(Vec<u8>, Option<Terminator<'a>>)> {
+        let mut items = vec![];
+        let terminator = loop {
+            let Some(mut statement) = self.statements.next() else {
+                if let Some(terminator) = terminators.last() {
+                    return Err(AssembleError::new(
+                        self.last_line,
+                        format!("Missing `{terminator}` for the block on line {line}"),
+                    ));
+                }
+                break None;
+            };
+            let word = statement.word()?;
+            if terminators.contains(&word) {
+                break Some((word, statement));
+            }
+            let item = if statement.eat_punct(':') {
+                BlockItem::Label(word.to_string())
+            } else if word == "bytes" {
+                BlockItem::Bytes(statement.hex_bytes()?)
+            } else {
+                self.action(word, &mut statement)?
+            };
+            statement.finish()?;
+            items.push((statement.line, item));
+        };
+
+        // Find the offset of each label.
+        let mut labels = HashMap::new();
+        let mut offset = 0;
+        for (line, item) in &items {
+            match item {
+                BlockItem::Label(label) => {
+                    if labels.insert(label.clone(), offset).is_some() {
+                        return Err(AssembleError::new(
+                            *line,
+                            format!("Duplicate label `{label}`"),
+                        ));
+                    }
+                }
+                BlockItem::Bytes(bytes) => offset += bytes.len(),
+                BlockItem::Branch(..) => offset += BRANCH_SIZE,
+            }
+        }
+
+        let mut actions = vec![];
+        for (line, item) in items {
+            match item {
+                BlockItem::Label(_) => (),
+                BlockItem::Bytes(bytes) => actions.extend(bytes),
+                BlockItem::Branch(opcode, target) => {
+                    let offset = match target {
+                        Offset::Label(label) => {
+                            let target = *labels.get(&label).ok_or_else(|| {
+                                AssembleError::new(line, format!("Unknown label `{label}`"))
+                            })?;
+                            let offset = target as i64 - (actions.len() + BRANCH_SIZE) as i64;
+                            i16::try_from(offset)
+                                .map_err(|_| AssembleError::new(line, "Branch is too far"))?
+                        }
+                        Offset::Offset(offset) => offset,
+                    };
+                    let action = match opcode {
+                        OpCode::If => Action::If(If { offset }),
+                        _ => Action::Jump(Jump { offset }),
+                    };
+                    actions.extend(self.encode(&action, line)?);
+                }
+            }
+        }
+        Ok((actions, terminator))
+    }
+
+    /// Parses an action, along with any blocks nested in it.
+    fn action(&mut self, mnemonic: &str, statement: &mut Statement) -> AssembleResult<BlockItem> {
+        let Some(&opcode) = self.opcodes.get(mnemonic) else {
+            return Err(statement.error(format!("Unknown action `{mnemonic}`")));
+        };
+        let line = statement.line;
+        let bytes = match opcode {
+            OpCode::If | OpCode::Jump => {
+                let word = statement.word()?;
+                let target = match word.parse() {
+                    Ok(offset) => Offset::Offset(offset),
+                    Err(_) => Offset::Label(word.to_string()),
+                };
+                return Ok(BlockItem::Branch(opcode, target));
+            }
+            OpCode::ConstantPool => {
+                let strings = comma_list(statement, Statement::string)?;
+                let action = Action::ConstantPool(ConstantPool {
+                    strings: strings.iter().map(|s| SwfStr::from_bytes(s)).collect(),
+                });
+                let bytes = self.encode(&action, line)?;
+                self.constant_pool = strings;
+                bytes
+            }
+            OpCode::DefineFunction => {
+                let name = statement.string()?;
+                statement.punct(',')?;
+                let params = bracket_list(statement, Statement::string)?;
+                statement.finish()?;
+                let actions = self.nested_block(line, "endfunction")?;
+                self.encode(
+                    &Action::DefineFunction(DefineFunction {
+                        name: SwfStr::from_bytes(&name),
+                        params: params.iter().map(|s| SwfStr::from_bytes(s)).collect(),
+                        actions: &actions,
+                    }),
+                    line,
+                )?
+            }
+            OpCode::DefineFunction2 => {
+                let name = statement.string()?;
+                statement.punct(',')?;
+                let register_count = statement.number()?;
+                statement.punct(',')?;
+                let flags = bracket_list(statement, |statement| {
+                    let name = statement.word()?;
+                    FunctionFlags::from_name(name)
+                        .ok_or_else(|| statement.error(format!("Unknown flag `{name}`")))
+                })?;
+                statement.punct(',')?;
+                let params = bracket_list(statement, |statement| {
+                    let register_index = if statement.eat_word("register") {
+                        Some(statement.number::<NonZeroU8>()?)
+                    } else {
+                        None
+                    };
+                    Ok((register_index, statement.string()?))
+                })?;
+                statement.finish()?;
+                let actions = self.nested_block(line, "endfunction")?;
+                self.encode(
+                    &Action::DefineFunction2(DefineFunction2 {
+                        name: SwfStr::from_bytes(&name),
+                        register_count,
+                        params: params
+                            .iter()
+                            .map(|(register_index, name)| FunctionParam {
+                                name: SwfStr::from_bytes(name),
+                                register_index: *register_index,
+                            })
+                            .collect(),
+                        flags: flags.into_iter().collect(),
+                        actions: &actions,
+                    }),
+                    line,
+                )?
+            }
+            OpCode::GetUrl => {
+                let url = statement.string()?;
+                statement.punct(',')?;
+                let target = statement.string()?;
+                self.encode(
+                    &Action::GetUrl(GetUrl {
+                        url: SwfStr::from_bytes(&url),
+                        target: SwfStr::from_bytes(&target),
+                    }),
+                    line,
+                )?
+            }
+            OpCode::GetUrl2 => {
+                let method = match statement.word()? {
+                    "none" => SendVarsMethod::None,
+                    "get" => SendVarsMethod::Get,
+                    "post" => SendVarsMethod::Post,
+                    method => return Err(statement.error(format!("Unknown method `{method}`"))),
+                };
+                statement.punct(',')?;
+                let flags = bracket_list(statement, |statement| match statement.word()? {
+                    "LOAD_TARGET" => Ok(GetUrlFlags::LOAD_TARGET),
+                    "LOAD_VARIABLES" => Ok(GetUrlFlags::LOAD_VARIABLES),
+                    name => Err(statement.error(format!("Unknown flag `{name}`"))),
+                })?;
+                let mut get_url = GetUrl2(flags.into_iter().collect());
+                get_url.set_send_vars_method(method);
+                self.encode(&Action::GetUrl2(get_url), line)?
+            }
+            OpCode::GotoFrame => {
+                let frame = statement.number()?;
+                self.encode(&Action::GotoFrame(GotoFrame { frame }), line)?
+            }
+            OpCode::GotoFrame2 => {
+                let set_playing = match statement.word()? {
+                    "play" => true,
+                    "stop" => false,
+                    word => {
+                        return Err(
+                            statement.error(format!("Expected `play` or `stop`, found `{word}`"))
+                        )
+                    }
+                };
+                let scene_offset = if statement.eat_punct(',') {
+                    statement.number()?
+                } else {
+                    0
+                };
+                self.encode(
+                    &Action::GotoFrame2(GotoFrame2 {
+                        set_playing,
+                        scene_offset,
+                    }),
+                    line,
+                )?
+            }
+            OpCode::GotoLabel => {
+                let label = statement.string()?;
+                self.encode(
+                    &Action::GotoLabel(GotoLabel {
+                        label: SwfStr::from_bytes(&label),
+                    }),
+                    line,
+                )?
+            }
+            OpCode::Push => {
+                let values = comma_list(statement, |statement| self.push_value(statement))?;
+                let values = values
+                    .iter()
+                    .map(|value| match value {
+                        PushValue::Value(value) => value.clone(),
+                        PushValue::Str(s) => Value::Str(SwfStr::from_bytes(s)),
+                    })
+                    .collect();
+                self.encode(&Action::Push(Push { values }), line)?
+            }
+            OpCode::SetTarget => {
+                let target = statement.string()?;
+                self.encode(
+                    &Action::SetTarget(SetTarget {
+                        target: SwfStr::from_bytes(&target),
+                    }),
+                    line,
+                )?
+            }
+            OpCode::StoreRegister => {
+                let register = statement.number()?;
+                self.encode(&Action::StoreRegister(StoreRegister { register }), line)?
+            }
+            OpCode::Try => {
+                statement.finish()?;
+                let (try_body, mut terminator) =
+                    self.block(line, &["catch", "finally", "endtry"])?;
+                let mut catch = None;
+                if let Some(("catch", mut statement)) = terminator {
+                    let catch_var = if statement.eat_word("register") {
+                        Err(statement.number()?)
+                    } else {
+                        Ok(statement.string()?)
+                    };
+                    statement.finish()?;
+                    let (catch_body, next) = self.block(statement.line, &["finally", "endtry"])?;
+                    catch = Some((catch_var, catch_body));
+                    terminator = next;
+                }
+                let mut finally_body = None;
+                if let Some(("finally", statement)) = terminator {
+                    statement.finish()?;
+                    let (body, next) = self.block(statement.line, &["endtry"])?;
+                    finally_body = Some(body);
+                    terminator = next;
+                }
+                if let Some((_, statement)) = terminator {
+                    statement.finish()?;
+                }
+                let catch_body = catch.as_ref().map(|(catch_var, catch_body)| {
+                    let catch_var = match catch_var {
+                        Ok(name) => CatchVar::Var(SwfStr::from_bytes(name)),
+                        Err(register) => CatchVar::Register(*register),
+                    };
+                    (catch_var, &catch_body[..])
+                });
+                self.encode(
+                    &Action::Try(Try {
+                        try_body: &try_body,
+                        catch_body,
+                        finally_body: finally_body.as_deref(),
+                    }),
+                    line,
+                )?
+            }
+            OpCode::WaitForFrame => {
+                let frame = statement.number()?;
+                statement.punct(',')?;
+                let num_actions_to_skip = statement.number()?;
+                self.encode(
+                    &Action::WaitForFrame(WaitForFrame {
+                        frame,
+                        num_actions_to_skip,
+                    }),
+                    line,
+                )?
+            }
+            OpCode::WaitForFrame2 => {
+                let num_actions_to_skip = statement.number()?;
+                self.encode(
+                    &Action::WaitForFrame2(WaitForFrame2 {
+                        num_actions_to_skip,
+                    }),
+                    line,
+                )?
+            }
+            OpCode::With => {
+                statement.finish()?;
+                let actions = self.nested_block(line, "endwith")?;
+                self.encode(&Action::With(With { actions: &actions }), line)?
+            }
+            // Every other action has no data.
+            _ => {
+                let mut bytes = vec![];
+                Writer::new(&mut bytes, self.swf_version)
+                    .write_action_header(opcode, 0)
+                    .map_err(|e| AssembleError::new(line, e.to_string()))?;
+                bytes
+            }
+        };
+        Ok(BlockItem::Bytes(bytes))
+    }
+
+    /// Assembles a block nested in an action, up to its `terminator`.
+    fn nested_block(&mut self, line: usize, terminator: &str) -> AssembleResult<Vec<u8>> {
+        let (actions, terminator) = self.block(line, &[terminator])?;
+        if let Some((_, statement)) = terminator {
+            statement.finish()?;
+        }
+        Ok(actions)
+    }
+
+    fn push_value(&self, statement: &mut Statement) -> AssembleResult<PushValue> {
+        if statement.peek_word().is_none() {
+            return Ok(PushValue::Str(statement.string()?));
+        }
+        let value = match statement.word()? {
+            "undefined" => Value::Undefined,
+            "null" => Value::Null,
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "int" => Value::Int(statement.number()?),
+            "float" => Value::Float(statement.float()?),
+            "double" => Value::Double(statement.double()?),
+            "register" => Value::Register(statement.number()?),
+            "constant" => Value::ConstantPool(self.constant(statement)?),
+            word => return Err(statement.error(format!("Unknown value `{word}`"))),
+        };
+        Ok(PushValue::Value(value))
+    }
+
+    /// Parses a reference to the constant pool, written as either a string
+    /// or a raw `#index`.
+    fn constant(&self, statement: &mut Statement) -> AssembleResult<u16> {
+        if let Some(word) = statement.peek_word() {
+            let index = word.strip_prefix('#').and_then(|index| index.parse().ok());
+            statement.word()?;
+            return index.ok_or_else(|| statement.error(format!("Invalid index `{word}`")));
+        }
+        let string = statement.string()?;
+        self.constant_pool
+            .iter()
+            .position(|s| *s == string)
+            .and_then(|index| u16::try_from(index).ok())
+            .ok_or_else(|| {
+                statement.error(format!(
+                    "{} isn't in the constant pool",
+                    string_literal(&string)
+                ))
+            })
+    }
+
+    fn encode(&self, action: &Action, line: usize) -> AssembleResult<Vec<u8>> {
+        let blocks = match action {
+            Action::DefineFunction(function) => vec![function.actions],
+            Action::DefineFunction2(function) => vec![function.actions],
+            Action::Try(try_block) => [
+                Some(try_block.try_body),
+                try_block
+                    .catch_body
+                    .as_ref()
+                    .map(|(_, catch_body)| *catch_body),
+                try_block.finally_body,
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+            Action::With(with) => vec![with.actions],
+            _ => vec![],
+        };
+        if blocks.iter().any(|block| block.len() > u16::MAX.into()) {
+            return Err(AssembleError::new(line, "Block is too long"));
+        }
+
+        let mut bytes = vec![];
+        Writer::new(&mut bytes, self.swf_version)
+            .write_action(action)
+            .map_err(|e| AssembleError::new(line, e.to_string()))?;
+        let blocks_len: usize = blocks.iter().map(|block| block.len()).sum();
+        if bytes.len() - blocks_len > 3 + usize::from(u16::MAX) {
+            return Err(AssembleError::new(line, "Action is too long"));
+        }
+        Ok(bytes)
+    }
+}
+
+/// Parses a list of items separated by commas, up to the end of a statement.
+fn comma_list<'a, T>(
+    statement: &mut Statement<'a>,
+    mut parse: impl FnMut(&mut Statement<'a>) -> AssembleResult<T>,
+) -> AssembleResult<Vec<T>> {
+    let mut items = vec![];
+    if !statement.is_finished() {
+        loop {
+            items.push(parse(statement)?);
+            if !statement.eat_punct(',') {
+                break;
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Parses a list of items separated by commas, within brackets.
+fn bracket_list<'a, T>(
+    statement: &mut Statement<'a>,
+    mut parse: impl FnMut(&mut Statement<'a>) -> AssembleResult<T>,
+) -> AssembleResult<Vec<T>> {
+    statement.punct('[')?;
+    let mut items = vec![];
+    if !statement.eat_punct(']') {
+        loop {
+            items.push(parse(statement)?);
+            if statement.eat_punct(']') {
+                break;
+            }
+            statement.punct(',')?;
+        }
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::disassemble::disassemble;
+    use crate::test_data;
+    use crate::types::Tag;
+
+    /// Collects the action blocks of some tags, including those in sprites.
+    fn action_blocks<'a>(tags: &[Tag<'a>], blocks: &mut Vec<&'a [u8]>) {
+        for tag in tags {
+            match tag {
+                Tag::DoAction(action_data) | Tag::DoInitAction { action_data, .. } => {
+                    blocks.push(action_data)
+                }
+                Tag::PlaceObject(place_object) => {
+                    for clip_action in place_object.clip_actions.iter().flatten() {
+                        blocks.push(clip_action.action_data);
+                    }
+                }
+                Tag::DefineSprite(sprite) => action_blocks(&sprite.tags, blocks),
+                _ => (),
+            }
+        }
+    }
+
+    /// Disassembles and reassembles every action block in the test suite.
+    #[test]
+    fn round_trip_test_suite() {
+        let mut num_blocks = 0;
+        test_data::for_each_test_suite_swf(|path, swf| {
+            let version = swf.header.version();
+            let mut blocks = vec![];
+            action_blocks(&swf.tags, &mut blocks);
+            for actions in blocks {
+                num_blocks += 1;
+                let text = disassemble(actions, version);
+                let assembled = assemble(&text, version).unwrap_or_else(|e| {
+                    panic!("Failed to assemble actions from {}: {e}", path.display())
+                });
+                assert!(
+                    assembled == actions,
+                    "Actions from {} changed after round trip",
+                    path.display()
+                );
+            }
+        });
+        assert!(num_blocks > 0, "No actions found in the test suite");
+    }
+
+    #[test]
+    fn assemble_constants_and_labels() {
+        let actions = assemble(
+            r#"
+              constantpool "a", "b"
+              push constant "b", constant #0, int 1
+            L0:
+              definefunction "f", ["x"]
+                push register 1
+                jump L1
+              L1:
+              endfunction
+              if L0
+              end
+            "#,
+            8,
+        )
+        .unwrap();
+        assert_eq!(
+            actions,
+            [
+                0x88, 0x06, 0x00, 0x02, 0x00, b'a', 0x00, b'b', 0x00, // constantpool
+                0x96, 0x09, 0x00, 0x08, 0x01, 0x08, 0x00, 0x07, 0x01, 0x00, 0x00,
+                0x00, // push
+                0x9b, 0x08, 0x00, b'f', 0x00, 0x01, 0x00, b'x', 0x00, 0x0a,
+                0x00, // definefunction
+                0x96, 0x02, 0x00, 0x04, 0x01, // push register 1
+                0x99, 0x02, 0x00, 0x00, 0x00, // jump +0
+                0x9d, 0x02, 0x00, 0xe6, 0xff, // if -26
+                0x00, // end
+            ]
+        );
+
+        let text = disassemble(&actions, 8);
+        assert!(text.contains("push constant \"b\", constant \"a\", int 1"));
+        assert_eq!(assemble(&text, 8).unwrap(), actions);
+    }
+
+    #[test]
+    fn assemble_errors() {
+        let error = assemble("push constant \"a\"", 8).unwrap_err();
+        assert_eq!(error.message, "\"a\" isn't in the constant pool");
+
+        let error = assemble("jump L5\n", 8).unwrap_err();
+        assert_eq!(error.line, 1);
+        assert_eq!(error.message, "Unknown label `L5`");
+
+        let error = assemble("with\n  stop\n", 8).unwrap_err();
+        assert_eq!(error.message, "Missing `endwith` for the block on line 1");
+
+        let error = assemble("\nfrobnicate\n", 8).unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "Unknown action `frobnicate`");
+    }
+}
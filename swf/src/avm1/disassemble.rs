@@ -0,0 +1,341 @@
+//! Disassembly of AVM1 action blocks into labelled p-code.
+//!
+//! Each action is written on its own line, and can be assembled back into the
+//! same bytes with [`assemble`]:
+//!
+//! ```text
+//!   constantpool "_root", "onEnterFrame"
+//!   push constant "_root"
+//!   getvariable
+//!   push constant "onEnterFrame"
+//!   definefunction2 "", 2, [PRELOAD_THIS, SUPPRESS_ARGUMENTS], [register 1 "x"]
+//!     push register 1, int 0
+//!     greater
+//!     if L0
+//!     push "negative"
+//!     trace
+//!   L0:
+//!   endfunction
+//!   setmember
+//!   try
+//!     push "x"
+//!     throw
+//!   catch register 2
+//!     stop
+//!   endtry
+//!   end
+//! ```
+//!
+//! The bodies of `definefunction`, `definefunction2`, `with` and `try` are
+//! nested in blocks closed by `endfunction`, `endwith` and `endtry`, as `end`
+//! is the action ending the whole block. Branch targets are written as labels,
+//! or as raw offsets when they fall outside the block of the branch or into
+//! the middle of an action.
+//!
+//! A pushed `constant` is written as the string it refers to in the last
+//! `constantpool` before it. When it's out of range, or its string appears
+//! more than once in the pool, it's written as a raw `#index` instead.
+//!
+//! Actions which wouldn't be written back as the same bytes, such as those
+//! with a length that doesn't match their data, are written as `bytes`.
+//!
+//! [`assemble`]: crate::avm1::assemble::assemble
+
+use crate::assembly::{format_double, format_float, hex_bytes, string_literal};
+use crate::avm1::opcode::OpCode;
+use crate::avm1::read::Reader;
+use crate::avm1::types::*;
+use crate::avm1::write::Writer;
+use crate::string::SwfStr;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Disassembles a block of actions, such as the contents of a `DoAction` tag.
+pub fn disassemble(actions: &[u8], swf_version: u8) -> String {
+    let mut disassembler = Disassembler {
+        swf_version,
+        constant_pool: vec![],
+        next_label: 0,
+        output: String::new(),
+    };
+    disassembler.write_block(actions, 1);
+    disassembler.output
+}
+
+/// An action decoded from a block.
+struct DecodedAction<'a> {
+    start: usize,
+    end: usize,
+
+    /// The action, or `None` if it can't be written back as the same bytes.
+    action: Option<Action<'a>>,
+}
+
+fn decode_block(actions: &[u8], swf_version: u8) -> Vec<DecodedAction<'_>> {
+    let mut decoded = vec![];
+    let mut reader = Reader::new(actions, swf_version);
+    while !reader.get_ref().is_empty() {
+        let start = actions.len() - reader.get_ref().len();
+        let Ok(action) = reader.read_action() else {
+            // Nothing after an invalid action can be trusted.
+            decoded.push(DecodedAction {
+                start,
+                end: actions.len(),
+                action: None,
+            });
+            break;
+        };
+        let end = actions.len() - reader.get_ref().len();
+
+        let mut encoded = vec![];
+        let action = match Writer::new(&mut encoded, swf_version).write_action(&action) {
+            Ok(()) if encoded == actions[start..end] && !matches!(action, Action::Unknown(_)) => {
+                Some(action)
+            }
+            _ => None,
+        };
+        decoded.push(DecodedAction { start, end, action });
+    }
+    decoded
+}
+
+/// Returns the offset a branch goes to, relative to the start of its block.
+fn branch_target(decoded: &DecodedAction) -> Option<isize> {
+    match decoded.action {
+        Some(Action::If(If { offset })) | Some(Action::Jump(Jump { offset })) => {
+            Some(decoded.end as isize + offset as isize)
+        }
+        _ => None,
+    }
+}
+
+struct Disassembler<'a> {
+    swf_version: u8,
+
+    /// The strings of the last `ConstantPool` action written.
+    constant_pool: Vec<&'a SwfStr>,
+
+    next_label: usize,
+    output: String,
+}
+
+impl<'a> Disassembler<'a> {
+    fn line(&mut self, indent: usize, text: &str) {
+        for _ in 0..indent {
+            self.output.push_str("  ");
+        }
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    fn write_block(&mut self, actions: &'a [u8], indent: usize) {
+        let decoded = decode_block(actions, self.swf_version);
+
+        let boundaries: BTreeSet<isize> = decoded
+            .iter()
+            .map(|decoded| decoded.start as isize)
+            .chain(std::iter::once(actions.len() as isize))
+            .collect();
+        let targets: BTreeSet<isize> = decoded
+            .iter()
+            .filter_map(branch_target)
+            .filter(|target| boundaries.contains(target))
+            .collect();
+        let labels: BTreeMap<isize, usize> = targets
+            .into_iter()
+            .map(|target| {
+                let label = self.next_label;
+                self.next_label += 1;
+                (target, label)
+            })
+            .collect();
+
+        for decoded in decoded {
+            if let Some(label) = labels.get(&(decoded.start as isize)) {
+                self.line(indent - 1, &format!("L{label}:"));
+            }
+            let bytes = &actions[decoded.start..decoded.end];
+            let target = branch_target(&decoded).and_then(|target| labels.get(&target));
+            let Some(action) = decoded.action else {
+                let line = match Reader::new(bytes, self.swf_version).read_action() {
+                    // Show what the bytes decode to, even though they can't
+                    // be written as such.
+                    Ok(action) if !matches!(action, Action::Unknown(_)) => {
+                        format!(
+                            "{} ; {}",
+                            hex_bytes(bytes),
+                            self.action(&action, bytes[0], None)
+                        )
+                    }
+                    _ => hex_bytes(bytes),
+                };
+                self.line(indent, &line);
+                continue;
+            };
+
+            let line = self.action(&action, bytes[0], target.copied());
+            self.line(indent, &line);
+
+            match action {
+                Action::ConstantPool(constant_pool) => self.constant_pool = constant_pool.strings,
+                Action::DefineFunction(function) => {
+                    self.write_block(function.actions, indent + 1);
+                    self.line(indent, "endfunction");
+                }
+                Action::DefineFunction2(function) => {
+                    self.write_block(function.actions, indent + 1);
+                    self.line(indent, "endfunction");
+                }
+                Action::With(with) => {
+                    self.write_block(with.actions, indent + 1);
+                    self.line(indent, "endwith");
+                }
+                Action::Try(try_block) => {
+                    self.write_block(try_block.try_body, indent + 1);
+                    if let Some((catch_var, catch_body)) = try_block.catch_body {
+                        let line = match catch_var {
+                            CatchVar::Var(name) => format!("catch {}", self.str(name)),
+                            CatchVar::Register(register) => format!("catch register {register}"),
+                        };
+                        self.line(indent, &line);
+                        self.write_block(catch_body, indent + 1);
+                    }
+                    if let Some(finally_body) = try_block.finally_body {
+                        self.line(indent, "finally");
+                        self.write_block(finally_body, indent + 1);
+                    }
+                    self.line(indent, "endtry");
+                }
+                _ => (),
+            }
+        }
+        if let Some(label) = labels.get(&(actions.len() as isize)) {
+            self.line(indent - 1, &format!("L{label}:"));
+        }
+    }
+
+    /// Formats the line of an action, without any nested blocks.
+    ///
+    /// A branch is written with its label if it has one, or its raw offset.
+    fn action(&self, action: &Action, opcode: u8, label: Option<usize>) -> String {
+        let branch = |offset: i16| match label {
+            Some(label) => format!("L{label}"),
+            None => offset.to_string(),
+        };
+        let operands = match action {
+            Action::ConstantPool(constant_pool) => self.str_list(&constant_pool.strings),
+            Action::DefineFunction(function) => format!(
+                "{}, [{}]",
+                self.str(function.name),
+                self.str_list(&function.params)
+            ),
+            Action::DefineFunction2(function) => {
+                let flags: Vec<_> = function.flags.iter_names().map(|(name, _)| name).collect();
+                let params: Vec<_> = function
+                    .params
+                    .iter()
+                    .map(|param| match param.register_index {
+                        Some(register) => format!("register {register} {}", self.str(param.name)),
+                        None => self.str(param.name),
+                    })
+                    .collect();
+                format!(
+                    "{}, {}, [{}], [{}]",
+                    self.str(function.name),
+                    function.register_count,
+                    flags.join(", "),
+                    params.join(", ")
+                )
+            }
+            Action::GetUrl(get_url) => {
+                format!("{}, {}", self.str(get_url.url), self.str(get_url.target))
+            }
+            Action::GetUrl2(get_url) => {
+                let method = match get_url.send_vars_method() {
+                    SendVarsMethod::None => "none",
+                    SendVarsMethod::Get => "get",
+                    SendVarsMethod::Post => "post",
+                };
+                let mut flags = vec![];
+                if get_url.is_target_sprite() {
+                    flags.push("LOAD_TARGET");
+                }
+                if get_url.is_load_vars() {
+                    flags.push("LOAD_VARIABLES");
+                }
+                format!("{method}, [{}]", flags.join(", "))
+            }
+            Action::GotoFrame(goto_frame) => goto_frame.frame.to_string(),
+            Action::GotoFrame2(goto_frame) => {
+                let play = if goto_frame.set_playing {
+                    "play"
+                } else {
+                    "stop"
+                };
+                if goto_frame.scene_offset != 0 {
+                    format!("{play}, {}", goto_frame.scene_offset)
+                } else {
+                    play.to_string()
+                }
+            }
+            Action::GotoLabel(goto_label) => self.str(goto_label.label),
+            Action::If(If { offset }) | Action::Jump(Jump { offset }) => branch(*offset),
+            Action::Push(push) => {
+                let values: Vec<_> = push.values.iter().map(|value| self.value(value)).collect();
+                values.join(", ")
+            }
+            Action::SetTarget(set_target) => self.str(set_target.target),
+            Action::StoreRegister(store_register) => store_register.register.to_string(),
+            Action::WaitForFrame(wait_for_frame) => format!(
+                "{}, {}",
+                wait_for_frame.frame, wait_for_frame.num_actions_to_skip
+            ),
+            Action::WaitForFrame2(wait_for_frame) => wait_for_frame.num_actions_to_skip.to_string(),
+            _ => String::new(),
+        };
+        let mnemonic = mnemonic(opcode);
+        if operands.is_empty() {
+            mnemonic
+        } else {
+            format!("{mnemonic} {operands}")
+        }
+    }
+
+    fn value(&self, value: &Value) -> String {
+        match *value {
+            Value::Undefined => "undefined".to_string(),
+            Value::Null => "null".to_string(),
+            Value::Bool(value) => value.to_string(),
+            Value::Int(value) => format!("int {value}"),
+            Value::Float(value) => format!("float {}", format_float(value)),
+            Value::Double(value) => format!("double {}", format_double(value)),
+            Value::Str(value) => self.str(value),
+            Value::Register(register) => format!("register {register}"),
+            Value::ConstantPool(index) => match self.constant_pool.get(index as usize) {
+                Some(&string)
+                    if self.constant_pool.iter().position(|&s| s == string)
+                        == Some(index as usize) =>
+                {
+                    format!("constant {}", self.str(string))
+                }
+                _ => format!("constant #{index}"),
+            },
+        }
+    }
+
+    fn str(&self, s: &SwfStr) -> String {
+        string_literal(s.as_bytes())
+    }
+
+    fn str_list(&self, strings: &[&SwfStr]) -> String {
+        let strings: Vec<_> = strings.iter().map(|s| self.str(s)).collect();
+        strings.join(", ")
+    }
+}
+
+/// Returns the name of an opcode in p-code.
+fn mnemonic(opcode: u8) -> String {
+    match OpCode::from_u8(opcode) {
+        Some(opcode) => format!("{opcode:?}").to_lowercase(),
+        None => format!("unknown 0x{opcode:02x}"),
+    }
+}
@@ -1,3 +1,5 @@
+pub mod assemble;
+pub mod disassemble;
 pub mod read;
 pub mod types;
 pub mod write;
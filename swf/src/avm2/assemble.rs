@@ -0,0 +1,1468 @@
+//! Assembly of ABC files from the text form written by [`disassemble`].
+//!
+//! Constant pool references written by value are looked up in the constant
+//! pool, and values not already in it are added to its end. This allows
+//! disassembled code to be edited without declaring every new name.
+//!
+//! [`disassemble`]: crate::avm2::disassemble::disassemble
+
+use crate::assembly::Statement;
+use crate::avm2::types::*;
+use crate::avm2::write::Writer;
+use crate::error::{AssembleError, AssembleResult};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Assembles an ABC file from its text form.
+pub fn assemble(source: &str) -> AssembleResult<AbcFile> {
+    let statements = Statement::parse_all(source)?;
+    let mut assembler = Assembler {
+        last_line: statements.last().map_or(0, |statement| statement.line),
+        statements: statements.into_iter(),
+        abc: AbcFile {
+            major_version: 46,
+            minor_version: 16,
+            constant_pool: ConstantPool {
+                ints: vec![],
+                uints: vec![],
+                doubles: vec![],
+                strings: vec![],
+                namespaces: vec![],
+                namespace_sets: vec![],
+                multinames: vec![],
+            },
+            methods: vec![],
+            metadata: vec![],
+            instances: vec![],
+            classes: vec![],
+            scripts: vec![],
+            method_bodies: vec![],
+        },
+        constants: ConstantIndices::default(),
+    };
+    assembler.assemble()?;
+    Ok(assembler.abc)
+}
+
+/// The indices at which each value appears in a table of the constant pool.
+pub(super) struct Indices<K>(HashMap<K, Vec<u32>>);
+
+impl<K> Default for Indices<K> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<K: Eq + Hash> Indices<K> {
+    fn insert(&mut self, value: K, index: u32) {
+        self.0.entry(value).or_default().push(index);
+    }
+
+    /// Returns the index of the first appearance of a value.
+    pub fn first(&self, value: &K) -> Option<u32> {
+        self.get(value, 0)
+    }
+
+    /// Returns the index of the `ordinal`th appearance of a value.
+    pub fn get(&self, value: &K, ordinal: usize) -> Option<u32> {
+        self.0.get(value)?.get(ordinal).copied()
+    }
+
+    /// Returns which appearance of its value the value at `index` is.
+    pub fn ordinal(&self, value: &K, index: u32) -> Option<usize> {
+        self.0.get(value)?.iter().position(|&i| i == index)
+    }
+}
+
+/// The indices of the values in a constant pool.
+#[derive(Default)]
+pub(super) struct ConstantIndices {
+    pub ints: Indices<i32>,
+    pub uints: Indices<u32>,
+    /// Doubles are keyed by their bits, to tell apart NaNs and signed zeros.
+    pub doubles: Indices<u64>,
+    pub strings: Indices<Vec<u8>>,
+    pub namespaces: Indices<Namespace>,
+    pub namespace_sets: Indices<NamespaceSet>,
+    pub multinames: Indices<Multiname>,
+}
+
+impl ConstantIndices {
+    pub fn new(pool: &ConstantPool) -> Self {
+        fn index_all<T: Clone, K: Eq + Hash>(values: &[T], key: impl Fn(&T) -> K) -> Indices<K> {
+            let mut indices = Indices::default();
+            for (i, value) in values.iter().enumerate() {
+                indices.insert(key(value), i as u32 + 1);
+            }
+            indices
+        }
+
+        Self {
+            ints: index_all(&pool.ints, |&value| value),
+            uints: index_all(&pool.uints, |&value| value),
+            doubles: index_all(&pool.doubles, |value| value.to_bits()),
+            strings: index_all(&pool.strings, Clone::clone),
+            namespaces: index_all(&pool.namespaces, Clone::clone),
+            namespace_sets: index_all(&pool.namespace_sets, Clone::clone),
+            multinames: index_all(&pool.multinames, Clone::clone),
+        }
+    }
+}
+
+/// Adds a value to the end of a table of the constant pool, returning its index.
+fn add_constant<T, K: Eq + Hash>(
+    table: &mut Vec<T>,
+    indices: &mut Indices<K>,
+    value: T,
+    key: K,
+) -> u32 {
+    table.push(value);
+    let index = table.len() as u32;
+    indices.insert(key, index);
+    index
+}
+
+/// Returns the index of a value in a table of the constant pool, adding it to
+/// the table if it isn't there yet.
+fn intern_constant<T, K: Eq + Hash>(
+    table: &mut Vec<T>,
+    indices: &mut Indices<K>,
+    value: T,
+    key: K,
+) -> u32 {
+    match indices.first(&key) {
+        Some(index) => index,
+        None => add_constant(table, indices, value, key),
+    }
+}
+
+/// Parses a raw `#index` reference.
+fn parse_index<T>(statement: &mut Statement) -> AssembleResult<Index<T>> {
+    let word = statement.word()?;
+    word.strip_prefix('#')
+        .and_then(|index| index.parse().ok())
+        .map(Index::new)
+        .ok_or_else(|| statement.error(format!("Expected an index, found `{word}`")))
+}
+
+/// Parses a raw `#index` reference, if it is next.
+fn parse_raw_index<T>(statement: &mut Statement) -> AssembleResult<Option<Index<T>>> {
+    match statement.peek_word() {
+        Some(word) if word.starts_with('#') => parse_index(statement).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn namespace_from_kind(kind: &str, name: Index<String>) -> Option<Namespace> {
+    Some(match kind {
+        "Namespace" => Namespace::Namespace(name),
+        "PackageNamespace" => Namespace::Package(name),
+        "PackageInternalNs" => Namespace::PackageInternal(name),
+        "ProtectedNamespace" => Namespace::Protected(name),
+        "ExplicitNamespace" => Namespace::Explicit(name),
+        "StaticProtectedNs" => Namespace::StaticProtected(name),
+        "PrivateNamespace" => Namespace::Private(name),
+        _ => return None,
+    })
+}
+
+fn default_value_from_kind(kind: &str, namespace: Index<Namespace>) -> Option<DefaultValue> {
+    Some(match kind {
+        "Namespace" => DefaultValue::Namespace(namespace),
+        "PackageNamespace" => DefaultValue::Package(namespace),
+        "PackageInternalNs" => DefaultValue::PackageInternal(namespace),
+        "ProtectedNamespace" => DefaultValue::Protected(namespace),
+        "ExplicitNamespace" => DefaultValue::Explicit(namespace),
+        "StaticProtectedNs" => DefaultValue::StaticProtected(namespace),
+        "PrivateNamespace" => DefaultValue::Private(namespace),
+        _ => return None,
+    })
+}
+
+/// A branch target or exception offset.
+enum Offset {
+    Label(String),
+    Offset(u32),
+}
+
+/// A line of code within a method body.
+enum CodeItem {
+    Label(String),
+    /// An op, along with the labels it branches to.
+    Op(Op, Vec<String>),
+    Bytes(Vec<u8>),
+}
+
+struct PendingException {
+    line: usize,
+    from: Offset,
+    to: Offset,
+    target: Offset,
+    type_name: Index<Multiname>,
+    variable_name: Index<Multiname>,
+}
+
+struct Assembler<'a> {
+    statements: std::vec::IntoIter<Statement<'a>>,
+    last_line: usize,
+    abc: AbcFile,
+    constants: ConstantIndices,
+}
+
+impl<'a> Assembler<'a> {
+    /// Returns the next statement within a block that started on `line`.
+    fn next_in_block(&mut self, line: usize) -> AssembleResult<Statement<'a>> {
+        self.statements.next().ok_or_else(|| {
+            AssembleError::new(
+                self.last_line,
+                format!("Missing `end` for the block on line {line}"),
+            )
+        })
+    }
+
+    fn assemble(&mut self) -> AssembleResult<()> {
+        while let Some(mut statement) = self.statements.next() {
+            match statement.word()? {
+                "majorversion" => self.abc.major_version = statement.number()?,
+                "minorversion" => self.abc.minor_version = statement.number()?,
+                "int" => {
+                    let value = statement.number()?;
+                    let pool = &mut self.abc.constant_pool;
+                    add_constant(&mut pool.ints, &mut self.constants.ints, value, value);
+                }
+                "uint" => {
+                    let value = statement.number()?;
+                    let pool = &mut self.abc.constant_pool;
+                    add_constant(&mut pool.uints, &mut self.constants.uints, value, value);
+                }
+                "double" => {
+                    let value = statement.double()?;
+                    let pool = &mut self.abc.constant_pool;
+                    let indices = &mut self.constants.doubles;
+                    add_constant(&mut pool.doubles, indices, value, value.to_bits());
+                }
+                "string" => {
+                    let value = statement.string()?;
+                    let pool = &mut self.abc.constant_pool;
+                    let indices = &mut self.constants.strings;
+                    add_constant(&mut pool.strings, indices, value.clone(), value);
+                }
+                "namespace" => {
+                    let (value, ordinal) = self.namespace_value(&mut statement)?;
+                    if ordinal.is_some() {
+                        return Err(statement.error("Declared namespaces can't have an ordinal"));
+                    }
+                    let pool = &mut self.abc.constant_pool;
+                    let indices = &mut self.constants.namespaces;
+                    add_constant(&mut pool.namespaces, indices, value.clone(), value);
+                }
+                "nsset" => {
+                    let value = self.namespace_set_value(&mut statement)?;
+                    let pool = &mut self.abc.constant_pool;
+                    let indices = &mut self.constants.namespace_sets;
+                    add_constant(&mut pool.namespace_sets, indices, value.clone(), value);
+                }
+                "multiname" => {
+                    let value = self.multiname_value(&mut statement)?;
+                    let pool = &mut self.abc.constant_pool;
+                    let indices = &mut self.constants.multinames;
+                    add_constant(&mut pool.multinames, indices, value.clone(), value);
+                }
+                "method" => {
+                    statement.finish()?;
+                    let method = self.method(statement.line)?;
+                    self.abc.methods.push(method);
+                    continue;
+                }
+                "metadata" => {
+                    statement.finish()?;
+                    let metadata = self.metadata(statement.line)?;
+                    self.abc.metadata.push(metadata);
+                    continue;
+                }
+                "class" => {
+                    statement.finish()?;
+                    let (instance, class) = self.class(statement.line)?;
+                    self.abc.instances.push(instance);
+                    self.abc.classes.push(class);
+                    continue;
+                }
+                "script" => {
+                    statement.finish()?;
+                    let script = self.script(statement.line)?;
+                    self.abc.scripts.push(script);
+                    continue;
+                }
+                "body" => {
+                    statement.finish()?;
+                    let body = self.method_body(statement.line)?;
+                    self.abc.method_bodies.push(body);
+                    continue;
+                }
+                word => return Err(statement.error(format!("Unknown statement `{word}`"))),
+            }
+            statement.finish()?;
+        }
+        Ok(())
+    }
+
+    fn method(&mut self, line: usize) -> AssembleResult<Method> {
+        let mut method = Method {
+            name: Index::new(0),
+            params: vec![],
+            return_type: Index::new(0),
+            flags: MethodFlags::empty(),
+        };
+        loop {
+            let mut statement = self.next_in_block(line)?;
+            match statement.word()? {
+                "end" => {
+                    statement.finish()?;
+                    return Ok(method);
+                }
+                "name" => method.name = self.string(&mut statement)?,
+                "param" => {
+                    let mut param = MethodParam {
+                        name: None,
+                        kind: self.multiname(&mut statement)?,
+                        default_value: None,
+                    };
+                    if statement.eat_word("name") {
+                        param.name = Some(self.string(&mut statement)?);
+                    }
+                    if statement.eat_word("default") {
+                        param.default_value = Some(self.default_value(&mut statement)?);
+                    }
+                    method.params.push(param);
+                }
+                "returns" => method.return_type = self.multiname(&mut statement)?,
+                "flags" => {
+                    while !statement.is_finished() {
+                        let name = statement.word()?;
+                        let flag = MethodFlags::from_name(name).ok_or_else(|| {
+                            statement.error(format!("Unknown method flag `{name}`"))
+                        })?;
+                        method.flags |= flag;
+                    }
+                }
+                word => return Err(statement.error(format!("Unknown method property `{word}`"))),
+            }
+            statement.finish()?;
+        }
+    }
+
+    fn metadata(&mut self, line: usize) -> AssembleResult<Metadata> {
+        let mut metadata = Metadata {
+            name: Index::new(0),
+            items: vec![],
+        };
+        loop {
+            let mut statement = self.next_in_block(line)?;
+            match statement.word()? {
+                "end" => {
+                    statement.finish()?;
+                    return Ok(metadata);
+                }
+                "name" => metadata.name = self.string(&mut statement)?,
+                "item" => {
+                    let key = self.string(&mut statement)?;
+                    let value = self.string(&mut statement)?;
+                    metadata.items.push(MetadataItem { key, value });
+                }
+                word => return Err(statement.error(format!("Unknown metadata property `{word}`"))),
+            }
+            statement.finish()?;
+        }
+    }
+
+    fn class(&mut self, line: usize) -> AssembleResult<(Instance, Class)> {
+        let mut instance = None;
+        let mut class = Class {
+            init_method: Index::new(0),
+            traits: vec![],
+        };
+        loop {
+            let mut statement = self.next_in_block(line)?;
+            match statement.word()? {
+                "end" => {
+                    statement.finish()?;
+                    let instance =
+                        instance.ok_or_else(|| statement.error("Missing `instance` for class"))?;
+                    return Ok((instance, class));
+                }
+                "instance" => {
+                    let name = self.multiname(&mut statement)?;
+                    statement.finish()?;
+                    instance = Some(self.instance(statement.line, name)?);
+                    continue;
+                }
+                "cinit" => class.init_method = parse_index(&mut statement)?,
+                "trait" => class.traits.push(self.trait_(&mut statement)?),
+                word => return Err(statement.error(format!("Unknown class property `{word}`"))),
+            }
+            statement.finish()?;
+        }
+    }
+
+    fn instance(&mut self, line: usize, name: Index<Multiname>) -> AssembleResult<Instance> {
+        let mut instance = Instance {
+            name,
+            super_name: Index::new(0),
+            is_sealed: false,
+            is_final: false,
+            is_interface: false,
+            protected_namespace: None,
+            interfaces: vec![],
+            init_method: Index::new(0),
+            traits: vec![],
+        };
+        loop {
+            let mut statement = self.next_in_block(line)?;
+            match statement.word()? {
+                "end" => {
+                    statement.finish()?;
+                    return Ok(instance);
+                }
+                "extends" => instance.super_name = self.multiname(&mut statement)?,
+                "implements" => {
+                    let interface = self.multiname(&mut statement)?;
+                    instance.interfaces.push(interface);
+                }
+                "flags" => {
+                    while !statement.is_finished() {
+                        match statement.word()? {
+                            "SEALED" => instance.is_sealed = true,
+                            "FINAL" => instance.is_final = true,
+                            "INTERFACE" => instance.is_interface = true,
+                            flag => {
+                                return Err(
+                                    statement.error(format!("Unknown instance flag `{flag}`"))
+                                )
+                            }
+                        }
+                    }
+                }
+                "protectedns" => {
+                    instance.protected_namespace = Some(self.namespace(&mut statement)?)
+                }
+                "iinit" => instance.init_method = parse_index(&mut statement)?,
+                "trait" => instance.traits.push(self.trait_(&mut statement)?),
+                word => return Err(statement.error(format!("Unknown instance property `{word}`"))),
+            }
+            statement.finish()?;
+        }
+    }
+
+    fn script(&mut self, line: usize) -> AssembleResult<Script> {
+        let mut script = Script {
+            init_method: Index::new(0),
+            traits: vec![],
+        };
+        loop {
+            let mut statement = self.next_in_block(line)?;
+            match statement.word()? {
+                "end" => {
+                    statement.finish()?;
+                    return Ok(script);
+                }
+                "sinit" => script.init_method = parse_index(&mut statement)?,
+                "trait" => script.traits.push(self.trait_(&mut statement)?),
+                word => return Err(statement.error(format!("Unknown script property `{word}`"))),
+            }
+            statement.finish()?;
+        }
+    }
+
+    fn trait_(&mut self, statement: &mut Statement) -> AssembleResult<Trait> {
+        let kind = statement.word()?;
+        let name = self.multiname(statement)?;
+
+        let mut id = 0;
+        let mut type_name = Index::new(0);
+        let mut value = None;
+        let mut method = None;
+        let mut class = None;
+        let mut t = Trait {
+            name,
+            kind: TraitKind::Slot {
+                slot_id: 0,
+                type_name: Index::new(0),
+                value: None,
+            },
+            metadata: vec![],
+            is_final: false,
+            is_override: false,
+        };
+        while !statement.is_finished() {
+            match statement.word()? {
+                "slotid" | "dispid" => id = statement.number()?,
+                "type" => type_name = self.multiname(statement)?,
+                "value" => value = Some(self.default_value(statement)?),
+                "method" => method = Some(parse_index(statement)?),
+                "class" => class = Some(parse_index(statement)?),
+                "final" => t.is_final = true,
+                "override" => t.is_override = true,
+                "metadata" => t.metadata.push(parse_index(statement)?),
+                word => return Err(statement.error(format!("Unknown trait property `{word}`"))),
+            }
+        }
+
+        let missing_method = || statement.error("Missing `method` for trait");
+        t.kind = match kind {
+            "slot" => TraitKind::Slot {
+                slot_id: id,
+                type_name,
+                value,
+            },
+            "const" => TraitKind::Const {
+                slot_id: id,
+                type_name,
+                value,
+            },
+            "method" => TraitKind::Method {
+                disp_id: id,
+                method: method.ok_or_else(missing_method)?,
+            },
+            "getter" => TraitKind::Getter {
+                disp_id: id,
+                method: method.ok_or_else(missing_method)?,
+            },
+            "setter" => TraitKind::Setter {
+                disp_id: id,
+                method: method.ok_or_else(missing_method)?,
+            },
+            "function" => TraitKind::Function {
+                slot_id: id,
+                function: method.ok_or_else(missing_method)?,
+            },
+            "class" => TraitKind::Class {
+                slot_id: id,
+                class: class.ok_or_else(|| statement.error("Missing `class` for trait"))?,
+            },
+            kind => return Err(statement.error(format!("Unknown trait kind `{kind}`"))),
+        };
+        Ok(t)
+    }
+
+    fn method_body(&mut self, line: usize) -> AssembleResult<MethodBody> {
+        let mut body = MethodBody {
+            method: Index::new(0),
+            max_stack: 0,
+            num_locals: 0,
+            init_scope_depth: 0,
+            max_scope_depth: 0,
+            code: vec![],
+            exceptions: vec![],
+            traits: vec![],
+        };
+        let mut labels = HashMap::new();
+        let mut exceptions = vec![];
+        loop {
+            let mut statement = self.next_in_block(line)?;
+            match statement.word()? {
+                "end" => {
+                    statement.finish()?;
+                    break;
+                }
+                "method" => body.method = parse_index(&mut statement)?,
+                "maxstack" => body.max_stack = statement.number()?,
+                "localcount" => body.num_locals = statement.number()?,
+                "initscopedepth" => body.init_scope_depth = statement.number()?,
+                "maxscopedepth" => body.max_scope_depth = statement.number()?,
+                "code" => {
+                    statement.finish()?;
+                    (body.code, labels) = self.code(statement.line)?;
+                    continue;
+                }
+                "try" => {
+                    statement.expect_word("from")?;
+                    let from = parse_offset(&mut statement)?;
+                    statement.expect_word("to")?;
+                    let to = parse_offset(&mut statement)?;
+                    statement.expect_word("target")?;
+                    let target = parse_offset(&mut statement)?;
+                    statement.expect_word("type")?;
+                    let type_name = self.multiname(&mut statement)?;
+                    statement.expect_word("name")?;
+                    let variable_name = self.multiname(&mut statement)?;
+                    exceptions.push(PendingException {
+                        line: statement.line,
+                        from,
+                        to,
+                        target,
+                        type_name,
+                        variable_name,
+                    });
+                }
+                "trait" => body.traits.push(self.trait_(&mut statement)?),
+                word => return Err(statement.error(format!("Unknown body property `{word}`"))),
+            }
+            statement.finish()?;
+        }
+
+        // Exceptions may refer to labels in the code, which may come after them.
+        for exception in exceptions {
+            let resolve = |offset: Offset| match offset {
+                Offset::Offset(offset) => Ok(offset),
+                Offset::Label(label) => labels.get(&label).map(|&o| o as u32).ok_or_else(|| {
+                    AssembleError::new(exception.line, format!("Unknown label `{label}`"))
+                }),
+            };
+            body.exceptions.push(Exception {
+                from_offset: resolve(exception.from)?,
+                to_offset: resolve(exception.to)?,
+                target_offset: resolve(exception.target)?,
+                variable_name: exception.variable_name,
+                type_name: exception.type_name,
+            });
+        }
+
+        Ok(body)
+    }
+
+    /// Assembles the code of a method body, returning it along with the
+    /// offsets of its labels.
+    fn code(&mut self, line: usize) -> AssembleResult<(Vec<u8>, HashMap<String, usize>)> {
+        let mut items = vec![];
+        loop {
+            let mut statement = self.next_in_block(line)?;
+            let word = statement.word()?;
+            if word == "end" {
+                statement.finish()?;
+                break;
+            }
+            let item = if statement.eat_punct(':') {
+                CodeItem::Label(word.to_string())
+            } else if word == "bytes" {
+                CodeItem::Bytes(statement.hex_bytes()?)
+            } else {
+                let mut targets = vec![];
+                let op = self.op(word, &mut statement, &mut targets)?;
+                CodeItem::Op(op, targets)
+            };
+            statement.finish()?;
+            items.push((statement.line, item));
+        }
+
+        // Find the offset of each label. Branch offsets don't change the size
+        // of the ops they're in.
+        let mut labels = HashMap::new();
+        let mut offset = 0;
+        for (line, item) in &items {
+            match item {
+                CodeItem::Label(label) => {
+                    if labels.insert(label.clone(), offset).is_some() {
+                        return Err(AssembleError::new(
+                            *line,
+                            format!("Duplicate label `{label}`"),
+                        ));
+                    }
+                }
+                CodeItem::Op(op, _) => offset += encode_op(op, *line)?.len(),
+                CodeItem::Bytes(bytes) => offset += bytes.len(),
+            }
+        }
+
+        let mut code = vec![];
+        for (line, item) in items {
+            match item {
+                CodeItem::Label(_) => (),
+                CodeItem::Op(mut op, targets) => {
+                    let start = code.len();
+                    let end = start + encode_op(&op, line)?.len();
+                    let mut targets = targets.iter().map(|label| {
+                        let target = *labels.get(label).ok_or_else(|| {
+                            AssembleError::new(line, format!("Unknown label `{label}`"))
+                        })? as i64;
+                        Ok(target)
+                    });
+                    let mut next_offset = |base: usize| -> AssembleResult<i32> {
+                        let target = targets.next().expect("Op should have a target")?;
+                        let offset = target - base as i64;
+                        if !(-(1 << 23)..1 << 23).contains(&offset) {
+                            return Err(AssembleError::new(line, "Branch is too far"));
+                        }
+                        Ok(offset as i32)
+                    };
+                    match &mut op {
+                        Op::IfEq { offset }
+                        | Op::IfFalse { offset }
+                        | Op::IfGe { offset }
+                        | Op::IfGt { offset }
+                        | Op::IfLe { offset }
+                        | Op::IfLt { offset }
+                        | Op::IfNe { offset }
+                        | Op::IfNge { offset }
+                        | Op::IfNgt { offset }
+                        | Op::IfNle { offset }
+                        | Op::IfNlt { offset }
+                        | Op::IfStrictEq { offset }
+                        | Op::IfStrictNe { offset }
+                        | Op::IfTrue { offset }
+                        | Op::Jump { offset } => *offset = next_offset(end)?,
+                        Op::LookupSwitch(lookup_switch) => {
+                            lookup_switch.default_offset = next_offset(start)?;
+                            for offset in lookup_switch.case_offsets.iter_mut() {
+                                *offset = next_offset(start)?;
+                            }
+                        }
+                        _ => (),
+                    }
+                    code.extend(encode_op(&op, line)?);
+                }
+                CodeItem::Bytes(bytes) => code.extend(bytes),
+            }
+        }
+
+        Ok((code, labels))
+    }
+
+    /// Parses the operands of an op.
+    ///
+    /// The labels branched to are added to `targets`, in the order their
+    /// offsets appear in the op.
+    fn op(
+        &mut self,
+        mnemonic: &str,
+        statement: &mut Statement,
+        targets: &mut Vec<String>,
+    ) -> AssembleResult<Op> {
+        let mut label = |statement: &mut Statement| -> AssembleResult<i32> {
+            targets.push(statement.word()?.to_string());
+            Ok(0)
+        };
+        Ok(match mnemonic {
+            "add" => Op::Add,
+            "add_i" => Op::AddI,
+            "applytype" => Op::ApplyType {
+                num_types: statement.number()?,
+            },
+            "astype" => Op::AsType {
+                type_name: self.multiname(statement)?,
+            },
+            "astypelate" => Op::AsTypeLate,
+            "bitand" => Op::BitAnd,
+            "bitnot" => Op::BitNot,
+            "bitor" => Op::BitOr,
+            "bitxor" => Op::BitXor,
+            "bkpt" => Op::Bkpt,
+            "bkptline" => Op::BkptLine {
+                line_num: statement.number()?,
+            },
+            "call" => Op::Call {
+                num_args: statement.number()?,
+            },
+            "callmethod" => Op::CallMethod {
+                index: statement.number()?,
+                num_args: comma_number(statement)?,
+            },
+            "callproperty" => Op::CallProperty {
+                index: self.multiname(statement)?,
+                num_args: comma_number(statement)?,
+            },
+            "callproplex" => Op::CallPropLex {
+                index: self.multiname(statement)?,
+                num_args: comma_number(statement)?,
+            },
+            "callpropvoid" => Op::CallPropVoid {
+                index: self.multiname(statement)?,
+                num_args: comma_number(statement)?,
+            },
+            "callstatic" => Op::CallStatic {
+                index: parse_index(statement)?,
+                num_args: comma_number(statement)?,
+            },
+            "callsuper" => Op::CallSuper {
+                index: self.multiname(statement)?,
+                num_args: comma_number(statement)?,
+            },
+            "callsupervoid" => Op::CallSuperVoid {
+                index: self.multiname(statement)?,
+                num_args: comma_number(statement)?,
+            },
+            "checkfilter" => Op::CheckFilter,
+            "coerce" => Op::Coerce {
+                index: self.multiname(statement)?,
+            },
+            "coerce_a" => Op::CoerceA,
+            "coerce_b" => Op::CoerceB,
+            "coerce_d" => Op::CoerceD,
+            "coerce_i" => Op::CoerceI,
+            "coerce_o" => Op::CoerceO,
+            "coerce_s" => Op::CoerceS,
+            "coerce_u" => Op::CoerceU,
+            "construct" => Op::Construct {
+                num_args: statement.number()?,
+            },
+            "constructprop" => Op::ConstructProp {
+                index: self.multiname(statement)?,
+                num_args: comma_number(statement)?,
+            },
+            "constructsuper" => Op::ConstructSuper {
+                num_args: statement.number()?,
+            },
+            "convert_b" => Op::ConvertB,
+            "convert_d" => Op::ConvertD,
+            "convert_i" => Op::ConvertI,
+            "convert_o" => Op::ConvertO,
+            "convert_s" => Op::ConvertS,
+            "convert_u" => Op::ConvertU,
+            "debug" => Op::Debug {
+                is_local_register: statement.number::<u8>()? != 0,
+                register_name: {
+                    statement.punct(',')?;
+                    self.string(statement)?
+                },
+                register: comma_number(statement)?,
+                extra: comma_number(statement)?,
+            },
+            "debugfile" => Op::DebugFile {
+                file_name: self.string(statement)?,
+            },
+            "debugline" => Op::DebugLine {
+                line_num: statement.number()?,
+            },
+            "declocal" => Op::DecLocal {
+                index: statement.number()?,
+            },
+            "declocal_i" => Op::DecLocalI {
+                index: statement.number()?,
+            },
+            "decrement" => Op::Decrement,
+            "decrement_i" => Op::DecrementI,
+            "deleteproperty" => Op::DeleteProperty {
+                index: self.multiname(statement)?,
+            },
+            "divide" => Op::Divide,
+            "dup" => Op::Dup,
+            "dxns" => Op::Dxns {
+                index: self.string(statement)?,
+            },
+            "dxnslate" => Op::DxnsLate,
+            "equals" => Op::Equals,
+            "esc_xattr" => Op::EscXAttr,
+            "esc_xelem" => Op::EscXElem,
+            "finddef" => Op::FindDef {
+                index: self.multiname(statement)?,
+            },
+            "findproperty" => Op::FindProperty {
+                index: self.multiname(statement)?,
+            },
+            "findpropstrict" => Op::FindPropStrict {
+                index: self.multiname(statement)?,
+            },
+            "getdescendants" => Op::GetDescendants {
+                index: self.multiname(statement)?,
+            },
+            "getglobalscope" => Op::GetGlobalScope,
+            "getglobalslot" => Op::GetGlobalSlot {
+                index: statement.number()?,
+            },
+            "getlex" => Op::GetLex {
+                index: self.multiname(statement)?,
+            },
+            "getlocal" => Op::GetLocal {
+                index: statement.number()?,
+            },
+            "getlocal0" => Op::GetLocal { index: 0 },
+            "getlocal1" => Op::GetLocal { index: 1 },
+            "getlocal2" => Op::GetLocal { index: 2 },
+            "getlocal3" => Op::GetLocal { index: 3 },
+            "getouterscope" => Op::GetOuterScope {
+                index: statement.number()?,
+            },
+            "getproperty" => Op::GetProperty {
+                index: self.multiname(statement)?,
+            },
+            "getscopeobject" => Op::GetScopeObject {
+                index: statement.number()?,
+            },
+            "getslot" => Op::GetSlot {
+                index: statement.number()?,
+            },
+            "getsuper" => Op::GetSuper {
+                index: self.multiname(statement)?,
+            },
+            "greaterequals" => Op::GreaterEquals,
+            "greaterthan" => Op::GreaterThan,
+            "hasnext" => Op::HasNext,
+            "hasnext2" => Op::HasNext2 {
+                object_register: statement.number()?,
+                index_register: comma_number(statement)?,
+            },
+            "ifeq" => Op::IfEq {
+                offset: label(statement)?,
+            },
+            "iffalse" => Op::IfFalse {
+                offset: label(statement)?,
+            },
+            "ifge" => Op::IfGe {
+                offset: label(statement)?,
+            },
+            "ifgt" => Op::IfGt {
+                offset: label(statement)?,
+            },
+            "ifle" => Op::IfLe {
+                offset: label(statement)?,
+            },
+            "iflt" => Op::IfLt {
+                offset: label(statement)?,
+            },
+            "ifne" => Op::IfNe {
+                offset: label(statement)?,
+            },
+            "ifnge" => Op::IfNge {
+                offset: label(statement)?,
+            },
+            "ifngt" => Op::IfNgt {
+                offset: label(statement)?,
+            },
+            "ifnle" => Op::IfNle {
+                offset: label(statement)?,
+            },
+            "ifnlt" => Op::IfNlt {
+                offset: label(statement)?,
+            },
+            "ifstricteq" => Op::IfStrictEq {
+                offset: label(statement)?,
+            },
+            "ifstrictne" => Op::IfStrictNe {
+                offset: label(statement)?,
+            },
+            "iftrue" => Op::IfTrue {
+                offset: label(statement)?,
+            },
+            "in" => Op::In,
+            "inclocal" => Op::IncLocal {
+                index: statement.number()?,
+            },
+            "inclocal_i" => Op::IncLocalI {
+                index: statement.number()?,
+            },
+            "increment" => Op::Increment,
+            "increment_i" => Op::IncrementI,
+            "initproperty" => Op::InitProperty {
+                index: self.multiname(statement)?,
+            },
+            "instanceof" => Op::InstanceOf,
+            "istype" => Op::IsType {
+                index: self.multiname(statement)?,
+            },
+            "istypelate" => Op::IsTypeLate,
+            "jump" => Op::Jump {
+                offset: label(statement)?,
+            },
+            "kill" => Op::Kill {
+                index: statement.number()?,
+            },
+            "label" => Op::Label,
+            "lessequals" => Op::LessEquals,
+            "lessthan" => Op::LessThan,
+            "lf32" => Op::Lf32,
+            "lf64" => Op::Lf64,
+            "li16" => Op::Li16,
+            "li32" => Op::Li32,
+            "li8" => Op::Li8,
+            "lookupswitch" => {
+                let default_offset = label(statement)?;
+                statement.punct(',')?;
+                statement.punct('[')?;
+                let mut case_offsets = vec![];
+                while !statement.eat_punct(']') {
+                    if !case_offsets.is_empty() {
+                        statement.punct(',')?;
+                    }
+                    case_offsets.push(label(statement)?);
+                }
+                if case_offsets.is_empty() {
+                    return Err(statement.error("`lookupswitch` needs at least one case"));
+                }
+                Op::LookupSwitch(Box::new(LookupSwitch {
+                    default_offset,
+                    case_offsets: case_offsets.into(),
+                }))
+            }
+            "lshift" => Op::LShift,
+            "modulo" => Op::Modulo,
+            "multiply" => Op::Multiply,
+            "multiply_i" => Op::MultiplyI,
+            "negate" => Op::Negate,
+            "negate_i" => Op::NegateI,
+            "newactivation" => Op::NewActivation,
+            "newarray" => Op::NewArray {
+                num_args: statement.number()?,
+            },
+            "newcatch" => Op::NewCatch {
+                index: parse_index(statement)?,
+            },
+            "newclass" => Op::NewClass {
+                index: parse_index(statement)?,
+            },
+            "newfunction" => Op::NewFunction {
+                index: parse_index(statement)?,
+            },
+            "newobject" => Op::NewObject {
+                num_args: statement.number()?,
+            },
+            "nextname" => Op::NextName,
+            "nextvalue" => Op::NextValue,
+            "nop" => Op::Nop,
+            "not" => Op::Not,
+            "pop" => Op::Pop,
+            "popscope" => Op::PopScope,
+            "pushbyte" => Op::PushByte {
+                value: statement.number()?,
+            },
+            "pushdouble" => Op::PushDouble {
+                value: self.double(statement)?,
+            },
+            "pushfalse" => Op::PushFalse,
+            "pushint" => Op::PushInt {
+                value: self.int(statement)?,
+            },
+            "pushnamespace" => Op::PushNamespace {
+                value: self.namespace(statement)?,
+            },
+            "pushnan" => Op::PushNaN,
+            "pushnull" => Op::PushNull,
+            "pushscope" => Op::PushScope,
+            "pushshort" => Op::PushShort {
+                value: statement.number()?,
+            },
+            "pushstring" => Op::PushString {
+                value: self.string(statement)?,
+            },
+            "pushtrue" => Op::PushTrue,
+            "pushuint" => Op::PushUint {
+                value: self.uint(statement)?,
+            },
+            "pushundefined" => Op::PushUndefined,
+            "pushwith" => Op::PushWith,
+            "returnvalue" => Op::ReturnValue,
+            "returnvoid" => Op::ReturnVoid,
+            "rshift" => Op::RShift,
+            "setglobalslot" => Op::SetGlobalSlot {
+                index: statement.number()?,
+            },
+            "setlocal" => Op::SetLocal {
+                index: statement.number()?,
+            },
+            "setlocal0" => Op::SetLocal { index: 0 },
+            "setlocal1" => Op::SetLocal { index: 1 },
+            "setlocal2" => Op::SetLocal { index: 2 },
+            "setlocal3" => Op::SetLocal { index: 3 },
+            "setproperty" => Op::SetProperty {
+                index: self.multiname(statement)?,
+            },
+            "setslot" => Op::SetSlot {
+                index: statement.number()?,
+            },
+            "setsuper" => Op::SetSuper {
+                index: self.multiname(statement)?,
+            },
+            "sf32" => Op::Sf32,
+            "sf64" => Op::Sf64,
+            "si16" => Op::Si16,
+            "si32" => Op::Si32,
+            "si8" => Op::Si8,
+            "strictequals" => Op::StrictEquals,
+            "subtract" => Op::Subtract,
+            "subtract_i" => Op::SubtractI,
+            "swap" => Op::Swap,
+            "sxi1" => Op::Sxi1,
+            "sxi16" => Op::Sxi16,
+            "sxi8" => Op::Sxi8,
+            "throw" => Op::Throw,
+            "typeof" => Op::TypeOf,
+            "timestamp" => Op::Timestamp,
+            "urshift" => Op::URShift,
+            _ => return Err(statement.error(format!("Unknown instruction `{mnemonic}`"))),
+        })
+    }
+
+    fn int(&mut self, statement: &mut Statement) -> AssembleResult<Index<i32>> {
+        if let Some(index) = parse_raw_index(statement)? {
+            return Ok(index);
+        }
+        let value = statement.number()?;
+        let pool = &mut self.abc.constant_pool;
+        let indices = &mut self.constants.ints;
+        let index = intern_constant(&mut pool.ints, indices, value, value);
+        Ok(Index::new(index))
+    }
+
+    fn uint(&mut self, statement: &mut Statement) -> AssembleResult<Index<u32>> {
+        if let Some(index) = parse_raw_index(statement)? {
+            return Ok(index);
+        }
+        let value = statement.number()?;
+        let pool = &mut self.abc.constant_pool;
+        let indices = &mut self.constants.uints;
+        let index = intern_constant(&mut pool.uints, indices, value, value);
+        Ok(Index::new(index))
+    }
+
+    fn double(&mut self, statement: &mut Statement) -> AssembleResult<Index<f64>> {
+        if let Some(index) = parse_raw_index(statement)? {
+            return Ok(index);
+        }
+        let value = statement.double()?;
+        let pool = &mut self.abc.constant_pool;
+        let indices = &mut self.constants.doubles;
+        let index = intern_constant(&mut pool.doubles, indices, value, value.to_bits());
+        Ok(Index::new(index))
+    }
+
+    fn string(&mut self, statement: &mut Statement) -> AssembleResult<Index<String>> {
+        if let Some(index) = parse_raw_index(statement)? {
+            return Ok(index);
+        }
+        if statement.eat_word("null") {
+            return Ok(Index::new(0));
+        }
+        let value = statement.string()?;
+        let pool = &mut self.abc.constant_pool;
+        let indices = &mut self.constants.strings;
+        let index = intern_constant(&mut pool.strings, indices, value.clone(), value);
+        Ok(Index::new(index))
+    }
+
+    /// Parses the value of a namespace, along with its ordinal if it has one.
+    fn namespace_value(
+        &mut self,
+        statement: &mut Statement,
+    ) -> AssembleResult<(Namespace, Option<usize>)> {
+        let kind = statement.word()?;
+        statement.punct('(')?;
+        let name = self.string(statement)?;
+        let ordinal = if statement.eat_punct(',') {
+            Some(statement.number()?)
+        } else {
+            None
+        };
+        statement.punct(')')?;
+        let namespace = namespace_from_kind(kind, name)
+            .ok_or_else(|| statement.error(format!("Unknown namespace kind `{kind}`")))?;
+        Ok((namespace, ordinal))
+    }
+
+    fn namespace(&mut self, statement: &mut Statement) -> AssembleResult<Index<Namespace>> {
+        if let Some(index) = parse_raw_index(statement)? {
+            return Ok(index);
+        }
+        if statement.eat_word("null") {
+            return Ok(Index::new(0));
+        }
+        let (value, ordinal) = self.namespace_value(statement)?;
+        if let Some(ordinal) = ordinal {
+            return self
+                .constants
+                .namespaces
+                .get(&value, ordinal)
+                .map(Index::new)
+                .ok_or_else(|| statement.error(format!("No namespace with ordinal {ordinal}")));
+        }
+        let pool = &mut self.abc.constant_pool;
+        let indices = &mut self.constants.namespaces;
+        let index = intern_constant(&mut pool.namespaces, indices, value.clone(), value);
+        Ok(Index::new(index))
+    }
+
+    fn namespace_set_value(&mut self, statement: &mut Statement) -> AssembleResult<NamespaceSet> {
+        statement.punct('[')?;
+        let mut namespace_set = vec![];
+        while !statement.eat_punct(']') {
+            if !namespace_set.is_empty() {
+                statement.punct(',')?;
+            }
+            namespace_set.push(self.namespace(statement)?);
+        }
+        Ok(namespace_set)
+    }
+
+    fn namespace_set(&mut self, statement: &mut Statement) -> AssembleResult<Index<NamespaceSet>> {
+        if let Some(index) = parse_raw_index(statement)? {
+            return Ok(index);
+        }
+        if statement.eat_word("null") {
+            return Ok(Index::new(0));
+        }
+        let value = self.namespace_set_value(statement)?;
+        let pool = &mut self.abc.constant_pool;
+        let indices = &mut self.constants.namespace_sets;
+        let index = intern_constant(&mut pool.namespace_sets, indices, value.clone(), value);
+        Ok(Index::new(index))
+    }
+
+    fn multiname_value(&mut self, statement: &mut Statement) -> AssembleResult<Multiname> {
+        let kind = statement.word()?;
+        if kind == "RTQNameL" {
+            return Ok(Multiname::RTQNameL);
+        } else if kind == "RTQNameLA" {
+            return Ok(Multiname::RTQNameLA);
+        }
+
+        statement.punct('(')?;
+        let multiname = match kind {
+            "QName" | "QNameA" => {
+                let namespace = self.namespace(statement)?;
+                statement.punct(',')?;
+                let name = self.string(statement)?;
+                if kind == "QName" {
+                    Multiname::QName { namespace, name }
+                } else {
+                    Multiname::QNameA { namespace, name }
+                }
+            }
+            "RTQName" => Multiname::RTQName {
+                name: self.string(statement)?,
+            },
+            "RTQNameA" => Multiname::RTQNameA {
+                name: self.string(statement)?,
+            },
+            "Multiname" | "MultinameA" => {
+                let name = self.string(statement)?;
+                statement.punct(',')?;
+                let namespace_set = self.namespace_set(statement)?;
+                if kind == "Multiname" {
+                    Multiname::Multiname {
+                        namespace_set,
+                        name,
+                    }
+                } else {
+                    Multiname::MultinameA {
+                        namespace_set,
+                        name,
+                    }
+                }
+            }
+            "MultinameL" => Multiname::MultinameL {
+                namespace_set: self.namespace_set(statement)?,
+            },
+            "MultinameLA" => Multiname::MultinameLA {
+                namespace_set: self.namespace_set(statement)?,
+            },
+            "TypeName" => {
+                let base_type = self.multiname(statement)?;
+                statement.punct('<')?;
+                let mut parameters = vec![];
+                while !statement.eat_punct('>') {
+                    if !parameters.is_empty() {
+                        statement.punct(',')?;
+                    }
+                    parameters.push(self.multiname(statement)?);
+                }
+                Multiname::TypeName {
+                    base_type,
+                    parameters,
+                }
+            }
+            _ => return Err(statement.error(format!("Unknown multiname kind `{kind}`"))),
+        };
+        statement.punct(')')?;
+        Ok(multiname)
+    }
+
+    fn multiname(&mut self, statement: &mut Statement) -> AssembleResult<Index<Multiname>> {
+        if let Some(index) = parse_raw_index(statement)? {
+            return Ok(index);
+        }
+        if statement.eat_word("null") {
+            return Ok(Index::new(0));
+        }
+        let value = self.multiname_value(statement)?;
+        let pool = &mut self.abc.constant_pool;
+        let indices = &mut self.constants.multinames;
+        let index = intern_constant(&mut pool.multinames, indices, value.clone(), value);
+        Ok(Index::new(index))
+    }
+
+    fn default_value(&mut self, statement: &mut Statement) -> AssembleResult<DefaultValue> {
+        let kind = statement
+            .peek_word()
+            .ok_or_else(|| statement.error("Expected a value"))?;
+        if namespace_from_kind(kind, Index::new(0)).is_some() {
+            // Namespace values are usually written as the namespace itself.
+            let namespace = self.namespace(statement)?;
+            return Ok(default_value_from_kind(kind, namespace).expect("Kind should be valid"));
+        }
+
+        statement.word()?;
+        let value = match kind {
+            "True" => return Ok(DefaultValue::True),
+            "False" => return Ok(DefaultValue::False),
+            "Null" => return Ok(DefaultValue::Null),
+            "Undefined" => return Ok(DefaultValue::Undefined),
+            "Integer" => {
+                statement.punct('(')?;
+                DefaultValue::Int(self.int(statement)?)
+            }
+            "UInteger" => {
+                statement.punct('(')?;
+                DefaultValue::Uint(self.uint(statement)?)
+            }
+            "Double" => {
+                statement.punct('(')?;
+                DefaultValue::Double(self.double(statement)?)
+            }
+            "Utf8" => {
+                statement.punct('(')?;
+                DefaultValue::String(self.string(statement)?)
+            }
+            "NamespaceValue" => {
+                statement.punct('(')?;
+                let kind = statement.word()?;
+                statement.punct(',')?;
+                let namespace = self.namespace(statement)?;
+                default_value_from_kind(kind, namespace)
+                    .ok_or_else(|| statement.error(format!("Unknown namespace kind `{kind}`")))?
+            }
+            _ => return Err(statement.error(format!("Unknown value kind `{kind}`"))),
+        };
+        statement.punct(')')?;
+        Ok(value)
+    }
+}
+
+fn parse_offset(statement: &mut Statement) -> AssembleResult<Offset> {
+    let word = statement.word()?;
+    Ok(match word.parse() {
+        Ok(offset) => Offset::Offset(offset),
+        Err(_) => Offset::Label(word.to_string()),
+    })
+}
+
+/// Parses a number preceded by a comma.
+fn comma_number<T: std::str::FromStr>(statement: &mut Statement) -> AssembleResult<T> {
+    statement.punct(',')?;
+    statement.number()
+}
+
+fn encode_op(op: &Op, line: usize) -> AssembleResult<Vec<u8>> {
+    let mut out = vec![];
+    Writer::new(&mut out)
+        .write_op(op)
+        .map_err(|e| AssembleError::new(line, e.to_string()))?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::disassemble::disassemble;
+    use crate::avm2::read::Reader;
+    use crate::test_data;
+    use crate::types::Tag;
+
+    fn write_abc(abc: AbcFile) -> Vec<u8> {
+        let mut out = vec![];
+        Writer::new(&mut out).write(abc).unwrap();
+        out
+    }
+
+    /// Disassembles and reassembles every ABC file in the test suite.
+    #[test]
+    fn round_trip_test_suite() {
+        let mut num_abcs = 0;
+        test_data::for_each_test_suite_swf(|path, swf| {
+            for tag in &swf.tags {
+                let data = match tag {
+                    Tag::DoAbc(data) => data,
+                    Tag::DoAbc2(do_abc) => do_abc.data,
+                    _ => continue,
+                };
+                let abc = Reader::new(data)
+                    .read()
+                    .unwrap_or_else(|e| panic!("Failed to read ABC from {}: {e}", path.display()));
+                num_abcs += 1;
+
+                let text = disassemble(&abc);
+                let assembled = assemble(&text).unwrap_or_else(|e| {
+                    panic!("Failed to assemble ABC from {}: {e}", path.display())
+                });
+                assert_eq!(
+                    disassemble(&assembled),
+                    text,
+                    "Disassembly of {} changed after round trip",
+                    path.display()
+                );
+                assert!(
+                    write_abc(assembled) == write_abc(abc),
+                    "ABC from {} changed after round trip",
+                    path.display()
+                );
+            }
+        });
+        assert!(num_abcs > 0, "No ABC files found in the test suite");
+    }
+
+    #[test]
+    fn assemble_new_constants() {
+        let abc = assemble(
+            r#"
+            majorversion 46
+            minorversion 16
+            string "" ; #1
+            string "trace" ; #2
+            namespace PackageNamespace("") ; #1
+
+            method ; #0
+            end
+
+            body ; #0
+              method #0
+              maxstack 2
+              localcount 1
+              initscopedepth 0
+              maxscopedepth 1
+              code
+                findpropstrict QName(PackageNamespace(""), "trace")
+                pushstring "hello"
+                pushint -5
+              L0:
+                callpropvoid QName(PackageNamespace(""), "trace"), 1
+                jump L0
+              end
+            end
+            "#,
+        )
+        .unwrap();
+
+        let pool = &abc.constant_pool;
+        assert_eq!(
+            pool.strings,
+            [b"".to_vec(), b"trace".to_vec(), b"hello".to_vec()]
+        );
+        assert_eq!(pool.ints, [-5]);
+        assert_eq!(
+            pool.multinames,
+            [Multiname::QName {
+                namespace: Index::new(1),
+                name: Index::new(2),
+            }]
+        );
+        assert_eq!(
+            abc.method_bodies[0].code,
+            [
+                0x5d, 0x01, // findpropstrict
+                0x2c, 0x03, // pushstring
+                0x2d, 0x01, // pushint
+                0x4f, 0x01, 0x01, // callpropvoid
+                0x10, 0xf9, 0xff, 0xff, // jump -7
+            ]
+        );
+    }
+
+    #[test]
+    fn assemble_errors() {
+        let error = assemble("method\n  name \"x\"\n").unwrap_err();
+        assert_eq!(error.line, 2);
+
+        let error = assemble("body\n  code\n    jump L5\n  end\nend\n").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.message, "Unknown label `L5`");
+
+        let error = assemble("body\n  code\n    frobnicate\n  end\nend\n").unwrap_err();
+        assert_eq!(error.message, "Unknown instruction `frobnicate`");
+    }
+}
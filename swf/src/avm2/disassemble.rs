@@ -0,0 +1,930 @@
+//! Disassembly of ABC files into a human-readable text form.
+//!
+//! The text form lists the tables of an ABC file in order, and can be
+//! assembled back into an identical file with [`assemble`]:
+//!
+//! ```text
+//! majorversion 46
+//! minorversion 16
+//!
+//! string "Main" ; #1
+//! namespace PackageNamespace("") ; #1
+//! multiname QName(PackageNamespace(""), "Main") ; #1
+//!
+//! method ; #0
+//!   returns QName(PackageNamespace(""), "void")
+//! end
+//!
+//! class ; #0
+//!   instance QName(PackageNamespace(""), "Main")
+//!     extends QName(PackageNamespace("flash.display"), "Sprite")
+//!     flags SEALED
+//!     iinit #1
+//!     trait slot QName(PackageNamespace(""), "count") slotid 0 type QName(PackageNamespace(""), "int")
+//!   end
+//!   cinit #2
+//! end
+//!
+//! body ; #0
+//!   method #0
+//!   maxstack 1
+//!   localcount 1
+//!   initscopedepth 0
+//!   maxscopedepth 1
+//!   code
+//!   L0:
+//!     getlocal0
+//!     pushscope
+//!     iffalse L1
+//!     returnvoid
+//!   L1:
+//!     returnvoid
+//!   L2:
+//!     pop
+//!     returnvoid
+//!   end
+//!   try from L0 to L1 target L2 type QName(PackageNamespace(""), "Error") name null
+//! end
+//! ```
+//!
+//! References to the constant pool are written as the value they refer to,
+//! with `null` standing for index 0. A reference is written as a raw `#index`
+//! instead when it is out of range, or when its value appears more than once
+//! in the pool. Namespaces are the exception, as distinct namespaces with the
+//! same name are common: their later copies are written with an extra ordinal,
+//! as in `PrivateNamespace("Main", 1)`. Methods, metadata, classes and
+//! exceptions are always referred to by `#index`.
+//!
+//! Branch targets and exception offsets are written as labels. Instructions
+//! which wouldn't be encoded back into the same bytes, such as `getlocal`s
+//! using the long form for a short index, are written as `bytes` instead. So
+//! is the whole code of a method body which can't be decoded, or which
+//! branches into the middle of an instruction.
+//!
+//! [`assemble`]: crate::avm2::assemble::assemble
+
+use crate::assembly::{format_double, hex_bytes, string_literal};
+use crate::avm2::assemble::ConstantIndices;
+use crate::avm2::read::Reader;
+use crate::avm2::types::*;
+use crate::avm2::write::Writer;
+use crate::extensions::ReadSwfExt;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Disassembles an ABC file into its text form.
+pub fn disassemble(abc: &AbcFile) -> String {
+    let mut disassembler = Disassembler {
+        abc,
+        constants: ConstantIndices::new(&abc.constant_pool),
+        output: String::new(),
+    };
+    disassembler.write_abc();
+    disassembler.output
+}
+
+/// A decoded instruction of a method body.
+struct Instruction {
+    start: usize,
+    end: usize,
+
+    /// The decoded op, or `None` if it wouldn't be encoded back into the same
+    /// bytes.
+    op: Option<Op>,
+}
+
+/// Decodes the code of a method body, or returns `None` if it can't be
+/// decoded in full.
+fn decode_code(code: &[u8]) -> Option<Vec<Instruction>> {
+    let mut reader = Reader::new(code);
+    let mut instructions = vec![];
+    loop {
+        let start = code.len() - reader.as_slice().len();
+        if start == code.len() {
+            break;
+        }
+        let op = reader.read_op().ok()?;
+        let end = code.len() - reader.as_slice().len();
+        let mut encoded = vec![];
+        Writer::new(&mut encoded).write_op(&op).ok()?;
+        instructions.push(Instruction {
+            start,
+            end,
+            op: (encoded == code[start..end]).then_some(op),
+        });
+    }
+    Some(instructions)
+}
+
+/// Returns the offsets an instruction may branch to.
+fn branch_targets(instruction: &Instruction) -> Vec<isize> {
+    let start = instruction.start as isize;
+    let end = instruction.end as isize;
+    match &instruction.op {
+        Some(
+            Op::IfEq { offset }
+            | Op::IfFalse { offset }
+            | Op::IfGe { offset }
+            | Op::IfGt { offset }
+            | Op::IfLe { offset }
+            | Op::IfLt { offset }
+            | Op::IfNe { offset }
+            | Op::IfNge { offset }
+            | Op::IfNgt { offset }
+            | Op::IfNle { offset }
+            | Op::IfNlt { offset }
+            | Op::IfStrictEq { offset }
+            | Op::IfStrictNe { offset }
+            | Op::IfTrue { offset }
+            | Op::Jump { offset },
+        ) => vec![end + *offset as isize],
+        Some(Op::LookupSwitch(lookup_switch)) => std::iter::once(lookup_switch.default_offset)
+            .chain(lookup_switch.case_offsets.iter().copied())
+            .map(|offset| start + offset as isize)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+pub(super) fn namespace_kind(namespace: &Namespace) -> (&'static str, Index<String>) {
+    match *namespace {
+        Namespace::Namespace(name) => ("Namespace", name),
+        Namespace::Package(name) => ("PackageNamespace", name),
+        Namespace::PackageInternal(name) => ("PackageInternalNs", name),
+        Namespace::Protected(name) => ("ProtectedNamespace", name),
+        Namespace::Explicit(name) => ("ExplicitNamespace", name),
+        Namespace::StaticProtected(name) => ("StaticProtectedNs", name),
+        Namespace::Private(name) => ("PrivateNamespace", name),
+    }
+}
+
+struct Disassembler<'a> {
+    abc: &'a AbcFile,
+    constants: ConstantIndices,
+    output: String,
+}
+
+impl Disassembler<'_> {
+    fn line(&mut self, indent: usize, text: &str) {
+        for _ in 0..indent {
+            self.output.push_str("  ");
+        }
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    fn pool(&self) -> &ConstantPool {
+        &self.abc.constant_pool
+    }
+
+    fn write_abc(&mut self) {
+        let abc = self.abc;
+        self.line(0, &format!("majorversion {}", abc.major_version));
+        self.line(0, &format!("minorversion {}", abc.minor_version));
+
+        self.output.push('\n');
+        self.write_constant_pool();
+
+        for (i, method) in abc.methods.iter().enumerate() {
+            self.output.push('\n');
+            self.write_method(i, method);
+        }
+        for (i, metadata) in abc.metadata.iter().enumerate() {
+            self.output.push('\n');
+            self.write_metadata(i, metadata);
+        }
+        for (i, (instance, class)) in abc.instances.iter().zip(&abc.classes).enumerate() {
+            self.output.push('\n');
+            self.write_class(i, instance, class);
+        }
+        for (i, script) in abc.scripts.iter().enumerate() {
+            self.output.push('\n');
+            self.line(0, &format!("script ; #{i}"));
+            self.line(1, &format!("sinit #{}", script.init_method.0));
+            self.write_traits(1, &script.traits);
+            self.line(0, "end");
+        }
+        for (i, body) in abc.method_bodies.iter().enumerate() {
+            self.output.push('\n');
+            self.write_method_body(i, body);
+        }
+    }
+
+    fn write_constant_pool(&mut self) {
+        let pool = self.pool();
+        let mut lines = vec![];
+        for (i, value) in pool.ints.iter().enumerate() {
+            lines.push((format!("int {value}"), i + 1));
+        }
+        for (i, value) in pool.uints.iter().enumerate() {
+            lines.push((format!("uint {value}"), i + 1));
+        }
+        for (i, &value) in pool.doubles.iter().enumerate() {
+            lines.push((format!("double {}", format_double(value)), i + 1));
+        }
+        for (i, value) in pool.strings.iter().enumerate() {
+            lines.push((format!("string {}", string_literal(value)), i + 1));
+        }
+        for (i, namespace) in pool.namespaces.iter().enumerate() {
+            let (kind, name) = namespace_kind(namespace);
+            lines.push((format!("namespace {kind}({})", self.string(name.0)), i + 1));
+        }
+        for (i, namespace_set) in pool.namespace_sets.iter().enumerate() {
+            lines.push((
+                format!("nsset {}", self.namespace_set_value(namespace_set)),
+                i + 1,
+            ));
+        }
+        for (i, multiname) in pool.multinames.iter().enumerate() {
+            lines.push((
+                format!(
+                    "multiname {}",
+                    self.multiname_value(multiname, i as u32 + 1, 0)
+                ),
+                i + 1,
+            ));
+        }
+        for (line, index) in lines {
+            self.line(0, &format!("{line} ; #{index}"));
+        }
+    }
+
+    fn write_method(&mut self, index: usize, method: &Method) {
+        self.line(0, &format!("method ; #{index}"));
+        if method.name.0 != 0 {
+            self.line(1, &format!("name {}", self.string(method.name.0)));
+        }
+        for param in &method.params {
+            let mut line = format!("param {}", self.multiname(param.kind.0));
+            if let Some(name) = param.name {
+                let _ = write!(line, " name {}", self.string(name.0));
+            }
+            if let Some(value) = &param.default_value {
+                let _ = write!(line, " default {}", self.default_value(value));
+            }
+            self.line(1, &line);
+        }
+        if method.return_type.0 != 0 {
+            self.line(
+                1,
+                &format!("returns {}", self.multiname(method.return_type.0)),
+            );
+        }
+        if !method.flags.is_empty() {
+            let flags: Vec<_> = method.flags.iter_names().map(|(name, _)| name).collect();
+            self.line(1, &format!("flags {}", flags.join(" ")));
+        }
+        self.line(0, "end");
+    }
+
+    fn write_metadata(&mut self, index: usize, metadata: &Metadata) {
+        self.line(0, &format!("metadata ; #{index}"));
+        self.line(1, &format!("name {}", self.string(metadata.name.0)));
+        for item in &metadata.items {
+            self.line(
+                1,
+                &format!(
+                    "item {} {}",
+                    self.string(item.key.0),
+                    self.string(item.value.0)
+                ),
+            );
+        }
+        self.line(0, "end");
+    }
+
+    fn write_class(&mut self, index: usize, instance: &Instance, class: &Class) {
+        self.line(0, &format!("class ; #{index}"));
+        self.line(1, &format!("instance {}", self.multiname(instance.name.0)));
+        if instance.super_name.0 != 0 {
+            self.line(
+                2,
+                &format!("extends {}", self.multiname(instance.super_name.0)),
+            );
+        }
+        for interface in &instance.interfaces {
+            self.line(2, &format!("implements {}", self.multiname(interface.0)));
+        }
+        let flags: Vec<_> = [
+            (instance.is_sealed, "SEALED"),
+            (instance.is_final, "FINAL"),
+            (instance.is_interface, "INTERFACE"),
+        ]
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+        .collect();
+        if !flags.is_empty() {
+            self.line(2, &format!("flags {}", flags.join(" ")));
+        }
+        if let Some(namespace) = instance.protected_namespace {
+            self.line(2, &format!("protectedns {}", self.namespace(namespace.0)));
+        }
+        self.line(2, &format!("iinit #{}", instance.init_method.0));
+        self.write_traits(2, &instance.traits);
+        self.line(1, "end");
+        self.line(1, &format!("cinit #{}", class.init_method.0));
+        self.write_traits(1, &class.traits);
+        self.line(0, "end");
+    }
+
+    fn write_traits(&mut self, indent: usize, traits: &[Trait]) {
+        for t in traits {
+            let mut line = String::from("trait ");
+            let name = self.multiname(t.name.0);
+            match &t.kind {
+                TraitKind::Slot {
+                    slot_id,
+                    type_name,
+                    value,
+                }
+                | TraitKind::Const {
+                    slot_id,
+                    type_name,
+                    value,
+                } => {
+                    let kind = if matches!(t.kind, TraitKind::Slot { .. }) {
+                        "slot"
+                    } else {
+                        "const"
+                    };
+                    let _ = write!(line, "{kind} {name} slotid {slot_id}");
+                    if type_name.0 != 0 {
+                        let _ = write!(line, " type {}", self.multiname(type_name.0));
+                    }
+                    if let Some(value) = value {
+                        let _ = write!(line, " value {}", self.default_value(value));
+                    }
+                }
+                TraitKind::Method { disp_id, method } => {
+                    let _ = write!(line, "method {name} dispid {disp_id} method #{}", method.0);
+                }
+                TraitKind::Getter { disp_id, method } => {
+                    let _ = write!(line, "getter {name} dispid {disp_id} method #{}", method.0);
+                }
+                TraitKind::Setter { disp_id, method } => {
+                    let _ = write!(line, "setter {name} dispid {disp_id} method #{}", method.0);
+                }
+                TraitKind::Class { slot_id, class } => {
+                    let _ = write!(line, "class {name} slotid {slot_id} class #{}", class.0);
+                }
+                TraitKind::Function { slot_id, function } => {
+                    let _ = write!(
+                        line,
+                        "function {name} slotid {slot_id} method #{}",
+                        function.0
+                    );
+                }
+            }
+            if t.is_final {
+                line.push_str(" final");
+            }
+            if t.is_override {
+                line.push_str(" override");
+            }
+            for metadata in &t.metadata {
+                let _ = write!(line, " metadata #{}", metadata.0);
+            }
+            self.line(indent, &line);
+        }
+    }
+
+    fn write_method_body(&mut self, index: usize, body: &MethodBody) {
+        self.line(0, &format!("body ; #{index}"));
+        self.line(1, &format!("method #{}", body.method.0));
+        self.line(1, &format!("maxstack {}", body.max_stack));
+        self.line(1, &format!("localcount {}", body.num_locals));
+        self.line(1, &format!("initscopedepth {}", body.init_scope_depth));
+        self.line(1, &format!("maxscopedepth {}", body.max_scope_depth));
+
+        let labels = self.write_code(body);
+
+        for exception in &body.exceptions {
+            let offset = |offset: u32| match labels.get(&(offset as usize)) {
+                Some(label) => format!("L{label}"),
+                None => offset.to_string(),
+            };
+            self.line(
+                1,
+                &format!(
+                    "try from {} to {} target {} type {} name {}",
+                    offset(exception.from_offset),
+                    offset(exception.to_offset),
+                    offset(exception.target_offset),
+                    self.multiname(exception.type_name.0),
+                    self.multiname(exception.variable_name.0)
+                ),
+            );
+        }
+
+        self.write_traits(1, &body.traits);
+        self.line(0, "end");
+    }
+
+    /// Writes the code of a method body, returning the labels given to each
+    /// offset.
+    fn write_code(&mut self, body: &MethodBody) -> BTreeMap<usize, usize> {
+        let code = &body.code[..];
+        self.line(1, "code");
+        let code_start = self.output.len();
+
+        let labels = decode_code(code).and_then(|instructions| {
+            let boundaries: BTreeSet<usize> = instructions
+                .iter()
+                .map(|instruction| instruction.start)
+                .chain(std::iter::once(code.len()))
+                .collect();
+
+            let mut targets = BTreeSet::new();
+            for instruction in &instructions {
+                for target in branch_targets(instruction) {
+                    let target = usize::try_from(target).ok()?;
+                    if !boundaries.contains(&target) {
+                        return None;
+                    }
+                    targets.insert(target);
+                }
+            }
+            for exception in &body.exceptions {
+                for offset in [
+                    exception.from_offset,
+                    exception.to_offset,
+                    exception.target_offset,
+                ] {
+                    if boundaries.contains(&(offset as usize)) {
+                        targets.insert(offset as usize);
+                    }
+                }
+            }
+            let labels: BTreeMap<usize, usize> = targets
+                .into_iter()
+                .enumerate()
+                .map(|(label, offset)| (offset, label))
+                .collect();
+
+            for instruction in &instructions {
+                if let Some(label) = labels.get(&instruction.start) {
+                    self.line(1, &format!("L{label}:"));
+                }
+                let bytes = &code[instruction.start..instruction.end];
+                let line = match &instruction.op {
+                    Some(op) => self.op(op, instruction, &labels),
+                    None => {
+                        // Show what the bytes decode to, even though they
+                        // can't be written as such.
+                        let op = Reader::new(bytes).read_op().ok()?;
+                        format!(
+                            "{} ; {}",
+                            hex_bytes(bytes),
+                            self.op(&op, instruction, &labels)
+                        )
+                    }
+                };
+                self.line(2, &line);
+            }
+            if let Some(label) = labels.get(&code.len()) {
+                self.line(1, &format!("L{label}:"));
+            }
+            Some(labels)
+        });
+
+        let labels = match labels {
+            Some(labels) => labels,
+            None => {
+                // Throw away anything written before giving up.
+                self.output.truncate(code_start);
+                for chunk in code.chunks(16) {
+                    self.line(2, &hex_bytes(chunk));
+                }
+                BTreeMap::new()
+            }
+        };
+
+        self.line(1, "end");
+        labels
+    }
+
+    fn label(&self, labels: &BTreeMap<usize, usize>, target: isize) -> String {
+        format!("L{}", labels[&(target as usize)])
+    }
+
+    fn op(&self, op: &Op, instruction: &Instruction, labels: &BTreeMap<usize, usize>) -> String {
+        let end = instruction.end as isize;
+        let branch = |offset: i32| self.label(labels, end + offset as isize);
+        let (mnemonic, operands): (&str, Vec<String>) = match op {
+            Op::Add => ("add", vec![]),
+            Op::AddI => ("add_i", vec![]),
+            Op::ApplyType { num_types } => ("applytype", vec![num_types.to_string()]),
+            Op::AsType { type_name } => ("astype", vec![self.multiname(type_name.0)]),
+            Op::AsTypeLate => ("astypelate", vec![]),
+            Op::BitAnd => ("bitand", vec![]),
+            Op::BitNot => ("bitnot", vec![]),
+            Op::BitOr => ("bitor", vec![]),
+            Op::BitXor => ("bitxor", vec![]),
+            Op::Bkpt => ("bkpt", vec![]),
+            Op::BkptLine { line_num } => ("bkptline", vec![line_num.to_string()]),
+            Op::Call { num_args } => ("call", vec![num_args.to_string()]),
+            Op::CallMethod { index, num_args } => {
+                ("callmethod", vec![index.to_string(), num_args.to_string()])
+            }
+            Op::CallProperty { index, num_args } => (
+                "callproperty",
+                vec![self.multiname(index.0), num_args.to_string()],
+            ),
+            Op::CallPropLex { index, num_args } => (
+                "callproplex",
+                vec![self.multiname(index.0), num_args.to_string()],
+            ),
+            Op::CallPropVoid { index, num_args } => (
+                "callpropvoid",
+                vec![self.multiname(index.0), num_args.to_string()],
+            ),
+            Op::CallStatic { index, num_args } => (
+                "callstatic",
+                vec![format!("#{}", index.0), num_args.to_string()],
+            ),
+            Op::CallSuper { index, num_args } => (
+                "callsuper",
+                vec![self.multiname(index.0), num_args.to_string()],
+            ),
+            Op::CallSuperVoid { index, num_args } => (
+                "callsupervoid",
+                vec![self.multiname(index.0), num_args.to_string()],
+            ),
+            Op::CheckFilter => ("checkfilter", vec![]),
+            Op::Coerce { index } => ("coerce", vec![self.multiname(index.0)]),
+            Op::CoerceA => ("coerce_a", vec![]),
+            Op::CoerceB => ("coerce_b", vec![]),
+            Op::CoerceD => ("coerce_d", vec![]),
+            Op::CoerceI => ("coerce_i", vec![]),
+            Op::CoerceO => ("coerce_o", vec![]),
+            Op::CoerceS => ("coerce_s", vec![]),
+            Op::CoerceU => ("coerce_u", vec![]),
+            Op::Construct { num_args } => ("construct", vec![num_args.to_string()]),
+            Op::ConstructProp { index, num_args } => (
+                "constructprop",
+                vec![self.multiname(index.0), num_args.to_string()],
+            ),
+            Op::ConstructSuper { num_args } => ("constructsuper", vec![num_args.to_string()]),
+            Op::ConvertB => ("convert_b", vec![]),
+            Op::ConvertD => ("convert_d", vec![]),
+            Op::ConvertI => ("convert_i", vec![]),
+            Op::ConvertO => ("convert_o", vec![]),
+            Op::ConvertS => ("convert_s", vec![]),
+            Op::ConvertU => ("convert_u", vec![]),
+            Op::Debug {
+                is_local_register,
+                register_name,
+                register,
+                extra,
+            } => (
+                "debug",
+                vec![
+                    u8::from(*is_local_register).to_string(),
+                    self.string(register_name.0),
+                    register.to_string(),
+                    extra.to_string(),
+                ],
+            ),
+            Op::DebugFile { file_name } => ("debugfile", vec![self.string(file_name.0)]),
+            Op::DebugLine { line_num } => ("debugline", vec![line_num.to_string()]),
+            Op::DecLocal { index } => ("declocal", vec![index.to_string()]),
+            Op::DecLocalI { index } => ("declocal_i", vec![index.to_string()]),
+            Op::Decrement => ("decrement", vec![]),
+            Op::DecrementI => ("decrement_i", vec![]),
+            Op::DeleteProperty { index } => ("deleteproperty", vec![self.multiname(index.0)]),
+            Op::Divide => ("divide", vec![]),
+            Op::Dup => ("dup", vec![]),
+            Op::Dxns { index } => ("dxns", vec![self.string(index.0)]),
+            Op::DxnsLate => ("dxnslate", vec![]),
+            Op::Equals => ("equals", vec![]),
+            Op::EscXAttr => ("esc_xattr", vec![]),
+            Op::EscXElem => ("esc_xelem", vec![]),
+            Op::FindDef { index } => ("finddef", vec![self.multiname(index.0)]),
+            Op::FindProperty { index } => ("findproperty", vec![self.multiname(index.0)]),
+            Op::FindPropStrict { index } => ("findpropstrict", vec![self.multiname(index.0)]),
+            Op::GetDescendants { index } => ("getdescendants", vec![self.multiname(index.0)]),
+            Op::GetGlobalScope => ("getglobalscope", vec![]),
+            Op::GetGlobalSlot { index } => ("getglobalslot", vec![index.to_string()]),
+            Op::GetLex { index } => ("getlex", vec![self.multiname(index.0)]),
+            Op::GetLocal { index: 0 } => ("getlocal0", vec![]),
+            Op::GetLocal { index: 1 } => ("getlocal1", vec![]),
+            Op::GetLocal { index: 2 } => ("getlocal2", vec![]),
+            Op::GetLocal { index: 3 } => ("getlocal3", vec![]),
+            Op::GetLocal { index } => ("getlocal", vec![index.to_string()]),
+            Op::GetOuterScope { index } => ("getouterscope", vec![index.to_string()]),
+            Op::GetProperty { index } => ("getproperty", vec![self.multiname(index.0)]),
+            Op::GetScopeObject { index } => ("getscopeobject", vec![index.to_string()]),
+            Op::GetSlot { index } => ("getslot", vec![index.to_string()]),
+            Op::GetSuper { index } => ("getsuper", vec![self.multiname(index.0)]),
+            Op::GreaterEquals => ("greaterequals", vec![]),
+            Op::GreaterThan => ("greaterthan", vec![]),
+            Op::HasNext => ("hasnext", vec![]),
+            Op::HasNext2 {
+                object_register,
+                index_register,
+            } => (
+                "hasnext2",
+                vec![object_register.to_string(), index_register.to_string()],
+            ),
+            Op::IfEq { offset } => ("ifeq", vec![branch(*offset)]),
+            Op::IfFalse { offset } => ("iffalse", vec![branch(*offset)]),
+            Op::IfGe { offset } => ("ifge", vec![branch(*offset)]),
+            Op::IfGt { offset } => ("ifgt", vec![branch(*offset)]),
+            Op::IfLe { offset } => ("ifle", vec![branch(*offset)]),
+            Op::IfLt { offset } => ("iflt", vec![branch(*offset)]),
+            Op::IfNe { offset } => ("ifne", vec![branch(*offset)]),
+            Op::IfNge { offset } => ("ifnge", vec![branch(*offset)]),
+            Op::IfNgt { offset } => ("ifngt", vec![branch(*offset)]),
+            Op::IfNle { offset } => ("ifnle", vec![branch(*offset)]),
+            Op::IfNlt { offset } => ("ifnlt", vec![branch(*offset)]),
+            Op::IfStrictEq { offset } => ("ifstricteq", vec![branch(*offset)]),
+            Op::IfStrictNe { offset } => ("ifstrictne", vec![branch(*offset)]),
+            Op::IfTrue { offset } => ("iftrue", vec![branch(*offset)]),
+            Op::In => ("in", vec![]),
+            Op::IncLocal { index } => ("inclocal", vec![index.to_string()]),
+            Op::IncLocalI { index } => ("inclocal_i", vec![index.to_string()]),
+            Op::Increment => ("increment", vec![]),
+            Op::IncrementI => ("increment_i", vec![]),
+            Op::InitProperty { index } => ("initproperty", vec![self.multiname(index.0)]),
+            Op::InstanceOf => ("instanceof", vec![]),
+            Op::IsType { index } => ("istype", vec![self.multiname(index.0)]),
+            Op::IsTypeLate => ("istypelate", vec![]),
+            Op::Jump { offset } => ("jump", vec![branch(*offset)]),
+            Op::Kill { index } => ("kill", vec![index.to_string()]),
+            Op::Label => ("label", vec![]),
+            Op::LessEquals => ("lessequals", vec![]),
+            Op::LessThan => ("lessthan", vec![]),
+            Op::Lf32 => ("lf32", vec![]),
+            Op::Lf64 => ("lf64", vec![]),
+            Op::Li16 => ("li16", vec![]),
+            Op::Li32 => ("li32", vec![]),
+            Op::Li8 => ("li8", vec![]),
+            Op::LookupSwitch(lookup_switch) => {
+                let start = instruction.start as isize;
+                let cases: Vec<_> = lookup_switch
+                    .case_offsets
+                    .iter()
+                    .map(|&offset| self.label(labels, start + offset as isize))
+                    .collect();
+                (
+                    "lookupswitch",
+                    vec![
+                        self.label(labels, start + lookup_switch.default_offset as isize),
+                        format!("[{}]", cases.join(", ")),
+                    ],
+                )
+            }
+            Op::LShift => ("lshift", vec![]),
+            Op::Modulo => ("modulo", vec![]),
+            Op::Multiply => ("multiply", vec![]),
+            Op::MultiplyI => ("multiply_i", vec![]),
+            Op::Negate => ("negate", vec![]),
+            Op::NegateI => ("negate_i", vec![]),
+            Op::NewActivation => ("newactivation", vec![]),
+            Op::NewArray { num_args } => ("newarray", vec![num_args.to_string()]),
+            Op::NewCatch { index } => ("newcatch", vec![format!("#{}", index.0)]),
+            Op::NewClass { index } => ("newclass", vec![format!("#{}", index.0)]),
+            Op::NewFunction { index } => ("newfunction", vec![format!("#{}", index.0)]),
+            Op::NewObject { num_args } => ("newobject", vec![num_args.to_string()]),
+            Op::NextName => ("nextname", vec![]),
+            Op::NextValue => ("nextvalue", vec![]),
+            Op::Nop => ("nop", vec![]),
+            Op::Not => ("not", vec![]),
+            Op::Pop => ("pop", vec![]),
+            Op::PopScope => ("popscope", vec![]),
+            Op::PushByte { value } => ("pushbyte", vec![value.to_string()]),
+            Op::PushDouble { value } => ("pushdouble", vec![self.double(value.0)]),
+            Op::PushFalse => ("pushfalse", vec![]),
+            Op::PushInt { value } => ("pushint", vec![self.int(value.0)]),
+            Op::PushNamespace { value } => ("pushnamespace", vec![self.namespace(value.0)]),
+            Op::PushNaN => ("pushnan", vec![]),
+            Op::PushNull => ("pushnull", vec![]),
+            Op::PushScope => ("pushscope", vec![]),
+            Op::PushShort { value } => ("pushshort", vec![value.to_string()]),
+            Op::PushString { value } => ("pushstring", vec![self.string(value.0)]),
+            Op::PushTrue => ("pushtrue", vec![]),
+            Op::PushUint { value } => ("pushuint", vec![self.uint(value.0)]),
+            Op::PushUndefined => ("pushundefined", vec![]),
+            Op::PushWith => ("pushwith", vec![]),
+            Op::ReturnValue => ("returnvalue", vec![]),
+            Op::ReturnVoid => ("returnvoid", vec![]),
+            Op::RShift => ("rshift", vec![]),
+            Op::SetGlobalSlot { index } => ("setglobalslot", vec![index.to_string()]),
+            Op::SetLocal { index: 0 } => ("setlocal0", vec![]),
+            Op::SetLocal { index: 1 } => ("setlocal1", vec![]),
+            Op::SetLocal { index: 2 } => ("setlocal2", vec![]),
+            Op::SetLocal { index: 3 } => ("setlocal3", vec![]),
+            Op::SetLocal { index } => ("setlocal", vec![index.to_string()]),
+            Op::SetProperty { index } => ("setproperty", vec![self.multiname(index.0)]),
+            Op::SetSlot { index } => ("setslot", vec![index.to_string()]),
+            Op::SetSuper { index } => ("setsuper", vec![self.multiname(index.0)]),
+            Op::Sf32 => ("sf32", vec![]),
+            Op::Sf64 => ("sf64", vec![]),
+            Op::Si16 => ("si16", vec![]),
+            Op::Si32 => ("si32", vec![]),
+            Op::Si8 => ("si8", vec![]),
+            Op::StrictEquals => ("strictequals", vec![]),
+            Op::Subtract => ("subtract", vec![]),
+            Op::SubtractI => ("subtract_i", vec![]),
+            Op::Swap => ("swap", vec![]),
+            Op::Sxi1 => ("sxi1", vec![]),
+            Op::Sxi16 => ("sxi16", vec![]),
+            Op::Sxi8 => ("sxi8", vec![]),
+            Op::Throw => ("throw", vec![]),
+            Op::TypeOf => ("typeof", vec![]),
+            Op::Timestamp => ("timestamp", vec![]),
+            Op::URShift => ("urshift", vec![]),
+        };
+        if operands.is_empty() {
+            mnemonic.to_string()
+        } else {
+            format!("{mnemonic} {}", operands.join(", "))
+        }
+    }
+
+    fn int(&self, index: u32) -> String {
+        match self.pool().ints.get((index as usize).wrapping_sub(1)) {
+            Some(value) if self.constants.ints.first(value) == Some(index) => value.to_string(),
+            _ => format!("#{index}"),
+        }
+    }
+
+    fn uint(&self, index: u32) -> String {
+        match self.pool().uints.get((index as usize).wrapping_sub(1)) {
+            Some(value) if self.constants.uints.first(value) == Some(index) => value.to_string(),
+            _ => format!("#{index}"),
+        }
+    }
+
+    fn double(&self, index: u32) -> String {
+        match self.pool().doubles.get((index as usize).wrapping_sub(1)) {
+            Some(value) if self.constants.doubles.first(&value.to_bits()) == Some(index) => {
+                format_double(*value)
+            }
+            _ => format!("#{index}"),
+        }
+    }
+
+    fn string(&self, index: u32) -> String {
+        if index == 0 {
+            return "null".to_string();
+        }
+        match self.pool().strings.get(index as usize - 1) {
+            Some(value) if self.constants.strings.first(value) == Some(index) => {
+                string_literal(value)
+            }
+            _ => format!("#{index}"),
+        }
+    }
+
+    fn namespace(&self, index: u32) -> String {
+        if index == 0 {
+            return "null".to_string();
+        }
+        match self.pool().namespaces.get(index as usize - 1) {
+            Some(namespace) => {
+                let (kind, name) = namespace_kind(namespace);
+                let name = self.string(name.0);
+                match self.constants.namespaces.ordinal(namespace, index) {
+                    Some(0) | None => format!("{kind}({name})"),
+                    Some(ordinal) => format!("{kind}({name}, {ordinal})"),
+                }
+            }
+            None => format!("#{index}"),
+        }
+    }
+
+    fn namespace_set(&self, index: u32) -> String {
+        if index == 0 {
+            return "null".to_string();
+        }
+        match self.pool().namespace_sets.get(index as usize - 1) {
+            Some(namespace_set)
+                if self.constants.namespace_sets.first(namespace_set) == Some(index) =>
+            {
+                self.namespace_set_value(namespace_set)
+            }
+            _ => format!("#{index}"),
+        }
+    }
+
+    fn namespace_set_value(&self, namespace_set: &NamespaceSet) -> String {
+        let namespaces: Vec<_> = namespace_set
+            .iter()
+            .map(|namespace| self.namespace(namespace.0))
+            .collect();
+        format!("[{}]", namespaces.join(", "))
+    }
+
+    fn multiname(&self, index: u32) -> String {
+        self.multiname_before(index, u32::MAX, 0)
+    }
+
+    /// Writes a reference to a multiname, only writing out its value if it
+    /// comes before `limit` in the pool.
+    ///
+    /// Multiname declarations can only refer to earlier multinames by value.
+    /// Multinames referring to themselves are cut short at some `depth`.
+    fn multiname_before(&self, index: u32, limit: u32, depth: u32) -> String {
+        if index == 0 {
+            return "null".to_string();
+        }
+        match self.pool().multinames.get(index as usize - 1) {
+            Some(multiname)
+                if index < limit
+                    && depth < 16
+                    && self.constants.multinames.first(multiname) == Some(index) =>
+            {
+                self.multiname_value(multiname, limit, depth + 1)
+            }
+            _ => format!("#{index}"),
+        }
+    }
+
+    fn multiname_value(&self, multiname: &Multiname, limit: u32, depth: u32) -> String {
+        match multiname {
+            Multiname::QName { namespace, name } => format!(
+                "QName({}, {})",
+                self.namespace(namespace.0),
+                self.string(name.0)
+            ),
+            Multiname::QNameA { namespace, name } => format!(
+                "QNameA({}, {})",
+                self.namespace(namespace.0),
+                self.string(name.0)
+            ),
+            Multiname::RTQName { name } => format!("RTQName({})", self.string(name.0)),
+            Multiname::RTQNameA { name } => format!("RTQNameA({})", self.string(name.0)),
+            Multiname::RTQNameL => "RTQNameL".to_string(),
+            Multiname::RTQNameLA => "RTQNameLA".to_string(),
+            Multiname::Multiname {
+                namespace_set,
+                name,
+            } => format!(
+                "Multiname({}, {})",
+                self.string(name.0),
+                self.namespace_set(namespace_set.0)
+            ),
+            Multiname::MultinameA {
+                namespace_set,
+                name,
+            } => format!(
+                "MultinameA({}, {})",
+                self.string(name.0),
+                self.namespace_set(namespace_set.0)
+            ),
+            Multiname::MultinameL { namespace_set } => {
+                format!("MultinameL({})", self.namespace_set(namespace_set.0))
+            }
+            Multiname::MultinameLA { namespace_set } => {
+                format!("MultinameLA({})", self.namespace_set(namespace_set.0))
+            }
+            Multiname::TypeName {
+                base_type,
+                parameters,
+            } => {
+                let parameters: Vec<_> = parameters
+                    .iter()
+                    .map(|parameter| self.multiname_before(parameter.0, limit, depth))
+                    .collect();
+                format!(
+                    "TypeName({}<{}>)",
+                    self.multiname_before(base_type.0, limit, depth),
+                    parameters.join(", ")
+                )
+            }
+        }
+    }
+
+    fn default_value(&self, value: &DefaultValue) -> String {
+        let (kind, namespace) = match *value {
+            DefaultValue::Int(index) => return format!("Integer({})", self.int(index.0)),
+            DefaultValue::Uint(index) => return format!("UInteger({})", self.uint(index.0)),
+            DefaultValue::Double(index) => return format!("Double({})", self.double(index.0)),
+            DefaultValue::String(index) => return format!("Utf8({})", self.string(index.0)),
+            DefaultValue::True => return "True".to_string(),
+            DefaultValue::False => return "False".to_string(),
+            DefaultValue::Null => return "Null".to_string(),
+            DefaultValue::Undefined => return "Undefined".to_string(),
+            DefaultValue::Namespace(index) => ("Namespace", index),
+            DefaultValue::Package(index) => ("PackageNamespace", index),
+            DefaultValue::PackageInternal(index) => ("PackageInternalNs", index),
+            DefaultValue::Protected(index) => ("ProtectedNamespace", index),
+            DefaultValue::Explicit(index) => ("ExplicitNamespace", index),
+            DefaultValue::StaticProtected(index) => ("StaticProtectedNs", index),
+            DefaultValue::Private(index) => ("PrivateNamespace", index),
+        };
+        // Namespace values are usually written as the namespace itself, which
+        // only works when it is of the same kind as the value.
+        match self
+            .pool()
+            .namespaces
+            .get((namespace.0 as usize).wrapping_sub(1))
+        {
+            Some(pool_namespace) if namespace_kind(pool_namespace).0 == kind => {
+                self.namespace(namespace.0)
+            }
+            _ => format!("NamespaceValue({kind}, {})", self.namespace(namespace.0)),
+        }
+    }
+}
@@ -598,15 +598,12 @@ impl<'a> Reader<'a> {
             OpCode::ConvertO => Op::ConvertO,
             OpCode::ConvertS => Op::ConvertS,
             OpCode::ConvertU => Op::ConvertU,
-            OpCode::Debug => {
-                let op = Op::Debug {
-                    is_local_register: self.read_u8()? != 0,
-                    register_name: self.read_index()?,
-                    register: self.read_u8()?,
-                };
-                self.read_u30()?; // Unused
-                op
-            }
+            OpCode::Debug => Op::Debug {
+                is_local_register: self.read_u8()? != 0,
+                register_name: self.read_index()?,
+                register: self.read_u8()?,
+                extra: self.read_u30()?,
+            },
             OpCode::DebugFile => Op::DebugFile {
                 file_name: self.read_index()?,
             },
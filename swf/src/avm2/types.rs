@@ -1,4 +1,5 @@
 use bitflags::bitflags;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -31,6 +32,12 @@ pub struct Index<T>(pub u32, pub PhantomData<T>);
 // see: https://github.com/rust-lang/rust/issues/26925
 impl<T: Clone> Copy for Index<T> {}
 
+impl<T> Hash for Index<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
 impl<T> Index<T> {
     pub fn new(i: u32) -> Index<T> {
         Index(i, PhantomData)
@@ -41,7 +48,7 @@ impl<T> Index<T> {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Namespace {
     Namespace(Index<String>),
     Package(Index<String>),
@@ -54,7 +61,7 @@ pub enum Namespace {
 
 pub type NamespaceSet = Vec<Index<Namespace>>;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Multiname {
     QName {
         namespace: Index<Namespace>,
@@ -333,6 +340,8 @@ pub enum Op {
         is_local_register: bool,
         register_name: Index<String>,
         register: u8,
+        // Documented as unused, but usually holds a line number.
+        extra: u32,
     },
     DebugFile {
         file_name: Index<String>,
@@ -706,12 +706,13 @@ impl<W: Write> Writer<W> {
                 is_local_register,
                 ref register_name,
                 register,
+                extra,
             } => {
                 self.write_opcode(OpCode::Debug)?;
                 self.write_u8(is_local_register as u8)?;
                 self.write_index(register_name)?;
                 self.write_u8(register)?;
-                self.write_u30(0)?; // Unused
+                self.write_u30(extra)?;
             }
             Op::DebugFile { ref file_name } => {
                 self.write_opcode(OpCode::DebugFile)?;
@@ -1221,9 +1222,10 @@ pub mod tests {
             write(Op::Debug {
                 is_local_register: true,
                 register_name: Index::new(2),
-                register: 3
+                register: 3,
+                extra: 4,
             }),
-            b"\xEF\x01\x02\x03\x00"
+            b"\xEF\x01\x02\x03\x04"
         );
 
         assert_eq!(
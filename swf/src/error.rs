@@ -124,6 +124,36 @@ impl From<io::Error> for Error {
     }
 }
 
+/// A `Result` from assembling bytecode from its textual form.
+pub type AssembleResult<T> = std::result::Result<T, AssembleError>;
+
+/// An error in the source text given to an assembler.
+#[derive(Debug)]
+pub struct AssembleError {
+    /// The 1-based line of the source text the error occurred on.
+    pub line: usize,
+
+    pub message: String,
+}
+
+impl AssembleError {
+    #[inline]
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for AssembleError {}
+
 #[cfg(test)]
 #[test]
 fn test_error_send_sync() {
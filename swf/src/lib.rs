@@ -15,6 +15,7 @@ extern crate num_traits;
 
 pub mod avm1;
 pub mod avm2;
+mod assembly;
 pub mod error;
 // TODO: Make this private?
 pub mod extensions;
@@ -704,7 +704,8 @@ impl<'a> Reader<'a> {
 
     fn read_tag_list(&mut self) -> Result<Vec<Tag<'a>>> {
         let mut tags = Vec::new();
-        loop {
+        // Some files omit the `End` tag, so the end of the data also ends the list.
+        while !self.get_ref().is_empty() {
             let tag = self.read_tag()?;
             if tag == Tag::End {
                 break;
@@ -3030,6 +3031,13 @@ pub mod tests {
             let mut reader = Reader::new(&buf[..], 1);
             assert_eq!(reader.read_tag_list().unwrap(), [Tag::ShowFrame]);
         }
+
+        {
+            // Missing `End` tag.
+            let buf = [0b01_000000, 0b00000000];
+            let mut reader = Reader::new(&buf[..], 1);
+            assert_eq!(reader.read_tag_list().unwrap(), [Tag::ShowFrame]);
+        }
     }
 
     /// Ensure that we return an error on invalid data.
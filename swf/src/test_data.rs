@@ -11,6 +11,7 @@ use crate::types::*;
 use crate::write::write_swf;
 use std::borrow::Cow;
 use std::fs::File;
+use std::path::{Path, PathBuf};
 
 #[allow(dead_code)]
 pub fn echo_swf(filename: &str) {
@@ -21,6 +22,64 @@ pub fn echo_swf(filename: &str) {
     write_swf(swf.header.swf_header(), &swf.tags, out_file).unwrap();
 }
 
+/// The directory of the test suite of the `tests` crate.
+const TEST_SUITE_ROOT: &str = "../tests/tests/swfs";
+
+/// SWFs in the test suite of the `tests` crate that are intentionally malformed and can't be
+/// parsed.
+const MALFORMED_TEST_SUITE_SWFS: &[&str] = &[
+    "avm1/bad_swf_tag_past_eof/test.swf",
+    "avm1/movieclip_state_values/no correct file (image).swf",
+    "avm1/movieclip_state_values/no correct file (text).swf",
+    "from_shumway/as3-loader/bug1157243/empty/empty.swf",
+    "from_shumway/as3-loader/bug1157243/invalid/invalid.swf",
+];
+
+/// Returns the paths of every SWF in the test suite of the `tests` crate.
+pub fn test_suite_swfs() -> Vec<PathBuf> {
+    fn find_swfs(dir: &Path, swfs: &mut Vec<PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                find_swfs(&path, swfs);
+            } else if path.extension().is_some_and(|ext| ext == "swf") {
+                swfs.push(path);
+            }
+        }
+    }
+
+    let mut swfs = vec![];
+    find_swfs(Path::new(TEST_SUITE_ROOT), &mut swfs);
+    swfs.sort();
+    swfs
+}
+
+/// Calls `f` with every SWF in the test suite of the `tests` crate.
+///
+/// Panics if a SWF can't be decompressed or parsed, unless it's listed in
+/// `MALFORMED_TEST_SUITE_SWFS`.
+pub fn for_each_test_suite_swf(mut f: impl FnMut(&Path, &Swf)) {
+    let swfs = test_suite_swfs();
+    assert!(!swfs.is_empty(), "No SWFs found in the test suite");
+
+    for path in swfs {
+        let is_malformed = MALFORMED_TEST_SUITE_SWFS
+            .iter()
+            .any(|malformed| path.strip_prefix(TEST_SUITE_ROOT).unwrap() == Path::new(malformed));
+        let data = std::fs::read(&path).unwrap();
+        let swf = decompress_swf(&data[..]).and_then(|swf_buf| {
+            let swf = parse_swf(&swf_buf)?;
+            f(&path, &swf);
+            Ok(())
+        });
+        match swf {
+            Ok(()) if is_malformed => panic!("{} is no longer malformed", path.display()),
+            Err(e) if !is_malformed => panic!("Failed to read {}: {e}", path.display()),
+            _ => (),
+        }
+    }
+}
+
 pub type TestData<T> = (u8, T, Vec<u8>);
 pub type TagTestData = TestData<Tag<'static>>;
 pub type Avm1TestData = TestData<Action<'static>>;
@@ -2442,6 +2501,22 @@ pub fn avm1_tests() -> Vec<Avm1TestData> {
             }),
             vec![0x9F, 1, 0, 0],
         ),
+        (
+            4,
+            Action::GotoFrame2(GotoFrame2 {
+                set_playing: true,
+                scene_offset: 0,
+            }),
+            vec![0x9F, 1, 0, 0b01],
+        ),
+        (
+            4,
+            Action::GotoFrame2(GotoFrame2 {
+                set_playing: false,
+                scene_offset: 13,
+            }),
+            vec![0x9F, 3, 0, 0b10, 13, 0],
+        ),
         (
             4,
             Action::GotoFrame2(GotoFrame2 {
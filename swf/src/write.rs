@@ -2026,8 +2026,9 @@ impl<W: Write> Writer<W> {
             // as there's a variable size CodeTableOffset field in between.
             // We correct for it with a +4/+2 addition later.
             let mut offsets = Vec::with_capacity(num_glyphs);
-            let mut has_wide_offsets = false;
-            let has_wide_codes = !font.flags.contains(FontFlag::IS_ANSI);
+            let mut has_wide_offsets = font.flags.contains(FontFlag::HAS_WIDE_OFFSETS);
+            let has_wide_codes = font.flags.contains(FontFlag::HAS_WIDE_CODES)
+                || font.glyphs.iter().any(|glyph| glyph.code > 0xFF);
             let mut shape_buf = Vec::new();
             {
                 let mut shape_writer = Writer::new(&mut shape_buf, self.version);
@@ -2056,10 +2057,19 @@ impl<W: Write> Writer<W> {
                     bits.write_ubits(6, 0)?;
                 }
             }
+            // The code table offset must also fit.
+            if (num_glyphs + 1) * 2 + shape_buf.len() > 0xFFFF {
+                has_wide_offsets = true;
+            }
+
+            let mut flags = font.flags;
+            flags.set(FontFlag::HAS_WIDE_OFFSETS, has_wide_offsets);
+            flags.set(FontFlag::HAS_WIDE_CODES, has_wide_codes);
+            flags.set(FontFlag::HAS_LAYOUT, font.layout.is_some());
 
             let mut writer = Writer::new(&mut buf, self.version);
             writer.write_character_id(font.id)?;
-            writer.write_u8(font.flags.bits())?;
+            writer.write_u8(flags.bits())?;
             writer.write_language(font.language)?;
             writer.write_u8(font.name.len() as u8)?;
             writer.output.write_all(font.name.as_bytes())?;
@@ -2111,17 +2121,20 @@ impl<W: Write> Writer<W> {
                 for glyph in &font.glyphs {
                     writer.write_i16(glyph.advance)?;
                 }
-                for glyph in &font.glyphs {
-                    writer.write_rectangle(
-                        glyph
-                            .bounds
-                            .as_ref()
-                            .ok_or_else(|| Error::invalid_data("glyph.bounds cannot be None"))?,
-                    )?;
-                }
-                writer.write_u16(layout.kerning.len() as u16)?;
-                for kerning_record in &layout.kerning {
-                    writer.write_kerning_record(kerning_record, has_wide_codes)?;
+                // Some older SWFs end the tag here, as this data isn't used until v7.
+                let has_bounds = font.glyphs.iter().any(|glyph| glyph.bounds.is_some());
+                if has_bounds || font.glyphs.is_empty() || !layout.kerning.is_empty() {
+                    for glyph in &font.glyphs {
+                        writer.write_rectangle(
+                            glyph.bounds.as_ref().ok_or_else(|| {
+                                Error::invalid_data("glyph.bounds cannot be None")
+                            })?,
+                        )?;
+                    }
+                    writer.write_u16(layout.kerning.len() as u16)?;
+                    for kerning_record in &layout.kerning {
+                        writer.write_kerning_record(kerning_record, has_wide_codes)?;
+                    }
                 }
             }
         }
@@ -3049,4 +3062,37 @@ mod tests {
 
         assert_eq!(reread, Tag::DefineFontInfo(Box::new(font_info)));
     }
+
+    #[test]
+    fn round_trip_test_suite() {
+        test_data::for_each_test_suite_swf(|path, swf| {
+            let header = Header {
+                compression: Compression::None,
+                ..swf.header.swf_header().clone()
+            };
+            let mut written = vec![];
+            write_swf(&header, &swf.tags, &mut written)
+                .unwrap_or_else(|e| panic!("Failed to write {}: {e}", path.display()));
+            let reread_buf = crate::decompress_swf(&written[..]).unwrap();
+            let reread = crate::parse_swf(&reread_buf)
+                .unwrap_or_else(|e| panic!("Failed to read {} after writing: {e}", path.display()));
+            assert_eq!(reread.tags.len(), swf.tags.len());
+            for (index, (tag, reread_tag)) in swf.tags.iter().zip(&reread.tags).enumerate() {
+                assert!(
+                    tag == reread_tag,
+                    "Tag {index} of {} changed after round trip",
+                    path.display()
+                );
+            }
+
+            // Writing the tags again gives the same bytes.
+            let mut rewritten = vec![];
+            write_swf(&header, &reread.tags, &mut rewritten).unwrap();
+            assert!(
+                rewritten == written,
+                "Writing {} again gave different bytes",
+                path.display()
+            );
+        });
+    }
 }
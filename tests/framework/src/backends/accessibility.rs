@@ -0,0 +1,15 @@
+use ruffle_core::backend::accessibility::{AccessibilityBackend, AccessibilityNode};
+
+/// An accessibility backend that pretends a screen reader is always listening.
+///
+/// The tree itself is dumped by the test runner at the end of the test.
+#[derive(Default)]
+pub struct TestAccessibilityBackend;
+
+impl AccessibilityBackend for TestAccessibilityBackend {
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    fn update_tree(&mut self, _tree: AccessibilityNode) {}
+}
@@ -1,9 +1,11 @@
 use crate::test::Font;
 use chrono::{DateTime, Utc};
 use ruffle_core::backend::ui::{
-    DialogLoaderError, DialogResultFuture, FileDialogResult, FileFilter, FontDefinition,
-    FullscreenError, LanguageIdentifier, MouseCursor, UiBackend, US_ENGLISH,
+    ClipboardData, ClipboardFormat, DialogLoaderError, DialogResultFuture, FileDialogResult,
+    FileFilter, FontDefinition, FullscreenError, LanguageIdentifier, MouseCursor,
+    MultipleDialogResultFuture, UiBackend, US_ENGLISH,
 };
+use ruffle_core::swf::{Rectangle, Twips};
 use url::Url;
 
 /// A simulated file dialog response, for use in tests
@@ -73,21 +75,28 @@ impl FileDialogResult for TestFileDialogResult {
 /// Fundamentally, this is mostly the same as [`NullUiBackend`] with the following differences:
 /// * Attempting to display an open dialog with a filter with description "debug-select-success" will simulate successfully selecting a file,
 ///   otherwise a user cancellation will be simulated
+/// * The same goes for a dialog selecting several files, where a filter with description "debug-select-multiple"
+///   will simulate selecting two files
 /// * Attempting to display a file save dialog with a file name hint of "debug-success.txt" will simulate successfully selecting a destination
 ///   otherwise a user cancellation will be simulated
 /// * Simulated in-memory clipboard
 pub struct TestUiBackend {
     fonts: Vec<Font>,
-    clipboard: String,
+    clipboard: Vec<ClipboardData>,
 }
 
 impl TestUiBackend {
     pub fn new(fonts: Vec<Font>) -> Self {
         Self {
             fonts,
-            clipboard: "".to_string(),
+            clipboard: vec![],
         }
     }
+
+    /// The payloads the movie put on the simulated clipboard.
+    pub fn clipboard(&self) -> &[ClipboardData] {
+        &self.clipboard
+    }
 }
 
 impl UiBackend for TestUiBackend {
@@ -100,11 +109,29 @@ impl UiBackend for TestUiBackend {
     fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
 
     fn clipboard_content(&mut self) -> String {
-        self.clipboard.clone()
+        match self.clipboard_data(ClipboardFormat::Text) {
+            Some(ClipboardData::Text(text)) => text,
+            _ => "".to_string(),
+        }
     }
 
     fn set_clipboard_content(&mut self, content: String) {
-        self.clipboard = content;
+        self.clipboard = vec![ClipboardData::Text(content)];
+    }
+
+    fn clipboard_formats(&mut self) -> Vec<ClipboardFormat> {
+        self.clipboard.iter().map(ClipboardData::format).collect()
+    }
+
+    fn clipboard_data(&mut self, format: ClipboardFormat) -> Option<ClipboardData> {
+        self.clipboard
+            .iter()
+            .find(|data| data.format() == format)
+            .cloned()
+    }
+
+    fn set_clipboard_data(&mut self, data: Vec<ClipboardData>) {
+        self.clipboard = data;
     }
 
     fn set_fullscreen(&mut self, _is_full: bool) -> Result<(), FullscreenError> {
@@ -119,6 +146,10 @@ impl UiBackend for TestUiBackend {
 
     fn close_virtual_keyboard(&self) {}
 
+    fn set_ime_enabled(&mut self, _enabled: bool) {}
+
+    fn set_ime_cursor_area(&mut self, _area: Rectangle<Twips>) {}
+
     fn language(&self) -> LanguageIdentifier {
         US_ENGLISH.clone()
     }
@@ -167,6 +198,36 @@ impl UiBackend for TestUiBackend {
         }))
     }
 
+    fn display_file_open_dialog_multiple(
+        &mut self,
+        filters: Vec<FileFilter>,
+    ) -> Option<MultipleDialogResultFuture> {
+        Some(Box::pin(async move {
+            let file_names: &[&str] = if filters
+                .iter()
+                .any(|f| f.description == "debug-select-multiple")
+            {
+                &["test.txt", "test2.txt"]
+            } else if filters
+                .iter()
+                .any(|f| f.description == "debug-select-success")
+            {
+                &["test.txt"]
+            } else {
+                &[]
+            };
+
+            let result: Vec<Box<dyn FileDialogResult>> = file_names
+                .iter()
+                .map(|file_name| {
+                    Box::new(TestFileDialogResult::new_success(file_name.to_string()))
+                        as Box<dyn FileDialogResult>
+                })
+                .collect();
+            Ok(result)
+        }))
+    }
+
     fn display_file_save_dialog(
         &mut self,
         file_name: String,
@@ -1,4 +1,6 @@
 use crate::options::RenderOptions;
+use ruffle_render::backend::ViewportDimensions;
+use ruffle_render_software::SoftwareRenderBackend;
 
 pub use ruffle_render::backend::RenderBackend;
 
@@ -44,3 +46,42 @@ pub trait RenderInterface {
     /// The provided backend is guaranteed to be the same one paired with this interface.
     fn capture(&self, renderer: &mut Box<dyn RenderBackend>) -> image::RgbaImage;
 }
+
+/// A [RenderInterface] for the software renderer, which needs no graphics device.
+///
+/// This is the fallback when no GPU is available, so image comparisons run on any machine.
+pub struct SoftwareRenderInterface;
+
+impl SoftwareRenderInterface {
+    /// Checks if the software renderer can render the given test.
+    ///
+    /// It only knows the sample patterns of the standard stage qualities.
+    pub fn is_supported(requirements: &RenderOptions) -> bool {
+        matches!(requirements.sample_count, 1 | 2 | 4 | 8 | 16)
+    }
+
+    pub fn create_pair(
+        width: u32,
+        height: u32,
+    ) -> (Box<dyn RenderInterface>, Box<dyn RenderBackend>) {
+        let backend = SoftwareRenderBackend::new(ViewportDimensions {
+            width,
+            height,
+            scale_factor: 1.0,
+        });
+        (Box::new(Self), Box::new(backend))
+    }
+}
+
+impl RenderInterface for SoftwareRenderInterface {
+    fn name(&self) -> String {
+        "software".to_string()
+    }
+
+    fn capture(&self, renderer: &mut Box<dyn RenderBackend>) -> image::RgbaImage {
+        renderer
+            .downcast_ref::<SoftwareRenderBackend>()
+            .expect("Renderer must be the software renderer")
+            .capture_frame()
+    }
+}
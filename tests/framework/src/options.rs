@@ -31,6 +31,7 @@ pub struct TestOptions {
     pub log_fetch: bool,
     pub required_features: RequiredFeatures,
     pub fonts: HashMap<String, FontOptions>,
+    pub accessibility_path: Option<String>,
 }
 
 impl Default for TestOptions {
@@ -49,6 +50,7 @@ impl Default for TestOptions {
             log_fetch: false,
             required_features: RequiredFeatures::default(),
             fonts: Default::default(),
+            accessibility_path: None,
         }
     }
 }
@@ -223,10 +225,12 @@ impl PlayerOptions {
         environment: &impl Environment,
         dimensions: ViewportDimensions,
     ) -> Option<(Box<dyn RenderInterface>, Box<dyn RenderBackend>)> {
-        if self.with_renderer.is_some() {
-            environment.create_renderer(dimensions.width, dimensions.height)
-        } else {
-            None
+        match &self.with_renderer {
+            // An optional renderer still has to be supported, otherwise the test runs without one.
+            Some(render) if environment.is_render_supported(render) => {
+                environment.create_renderer(dimensions.width, dimensions.height)
+            }
+            _ => None,
         }
     }
 }
@@ -244,6 +248,14 @@ fn calc_difference(lhs: u8, rhs: u8) -> u8 {
 }
 
 impl ImageComparison {
+    pub fn new(tolerance: u8, max_outliers: usize) -> Self {
+        Self {
+            tolerance,
+            max_outliers,
+            trigger: ImageTrigger::default(),
+        }
+    }
+
     pub fn test(
         &self,
         name: &str,
@@ -1,4 +1,6 @@
-use crate::backends::{TestLogBackend, TestNavigatorBackend, TestUiBackend};
+use crate::backends::{
+    TestAccessibilityBackend, TestLogBackend, TestNavigatorBackend, TestUiBackend,
+};
 use crate::environment::RenderInterface;
 use crate::fs_commands::{FsCommand, TestFsCommandProvider};
 use crate::image_trigger::ImageTrigger;
@@ -9,7 +11,7 @@ use anyhow::{anyhow, Result};
 use image::ImageFormat;
 use pretty_assertions::Comparison;
 use ruffle_core::backend::navigator::NullExecutor;
-use ruffle_core::events::{KeyCode, TextControlCode as RuffleTextControlCode};
+use ruffle_core::events::{ImeEvent, KeyCode, TextControlCode as RuffleTextControlCode};
 use ruffle_core::events::{MouseButton as RuffleMouseButton, MouseWheelDelta};
 use ruffle_core::limits::ExecutionLimit;
 use ruffle_core::tag_utils::SwfMovie;
@@ -94,6 +96,10 @@ impl TestRunner {
                 viewport_dimensions.scale_factor,
             );
 
+        if test.options.accessibility_path.is_some() {
+            builder = builder.with_accessibility(TestAccessibilityBackend);
+        }
+
         let render_interface = if let Some((interface, backend)) = renderer {
             builder = builder.with_boxed_renderer(backend);
             Some(interface)
@@ -144,6 +150,14 @@ impl TestRunner {
         &self.options
     }
 
+    /// Renders the stage and captures it, if this test has a renderer.
+    pub fn capture(&self) -> Option<image::RgbaImage> {
+        let render_interface = self.render_interface.as_deref()?;
+        let mut player_lock = self.player.lock().unwrap();
+        player_lock.render();
+        Some(render_interface.capture(player_lock.renderer_mut()))
+    }
+
     pub fn next_tick_may_be_last(&self) -> bool {
         self.remaining_iterations == 1
     }
@@ -289,6 +303,12 @@ impl TestRunner {
                         InputTextControlCode::Delete => RuffleTextControlCode::Delete,
                     },
                 },
+                AutomatedEvent::ImePreedit { text, cursor } => {
+                    PlayerEvent::Ime(ImeEvent::Preedit(text.clone(), *cursor))
+                }
+                AutomatedEvent::ImeCommit { text } => {
+                    PlayerEvent::Ime(ImeEvent::Commit(text.clone()))
+                }
                 AutomatedEvent::FocusGained => PlayerEvent::FocusGained,
                 AutomatedEvent::FocusLost => PlayerEvent::FocusLost,
                 AutomatedEvent::Wait | AutomatedEvent::SetClipboardText { .. } => unreachable!(),
@@ -375,6 +395,16 @@ impl TestRunner {
             // bytes should explicitly test for them in ActionScript.
             let normalized_trace = trace.replace('\0', "");
             self.compare_output(&normalized_trace)?;
+
+            if let Some(accessibility_path) = &self.options.accessibility_path {
+                let expected_tree = self
+                    .root_path
+                    .join(accessibility_path)?
+                    .read_to_string()?
+                    .replace("\r\n", "\n");
+                let actual_tree = self.player.lock().unwrap().accessibility_tree().dump();
+                assert_text_matches(&actual_tree, &expected_tree)?;
+            }
         }
 
         Ok(match self.remaining_iterations {
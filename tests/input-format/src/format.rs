@@ -96,6 +96,18 @@ pub enum AutomatedEvent {
     /// Populate clipboard with the given text
     SetClipboardText { text: String },
 
+    /// Update the text being composed by the input method editor.
+    ///
+    /// The cursor is given as a range of UTF-8 byte offsets into the text.
+    /// An empty text ends the composition.
+    ImePreedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+
+    /// Finish the composition of the input method editor, inserting the given text.
+    ImeCommit { text: String },
+
     /// Inform the player that the focus has been gained (i.e. the window has been focused).
     FocusGained,
 
@@ -100,6 +100,8 @@ impl InputInjector {
                     | AutomatedEvent::TextInput { .. }
                     | AutomatedEvent::TextControl { .. }
                     | AutomatedEvent::SetClipboardText { .. }
+                    | AutomatedEvent::ImePreedit { .. }
+                    | AutomatedEvent::ImeCommit { .. }
                     | AutomatedEvent::MouseWheel { .. }
                     | AutomatedEvent::FocusGained
                     | AutomatedEvent::FocusLost => {}
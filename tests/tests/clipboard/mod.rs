@@ -0,0 +1,52 @@
+use ruffle_core::backend::ui::ClipboardData;
+use ruffle_test_framework::backends::TestUiBackend;
+use ruffle_test_framework::environment::Environment;
+use ruffle_test_framework::options::TestOptions;
+use ruffle_test_framework::runner::TestStatus;
+use ruffle_test_framework::test::Test;
+use ruffle_test_framework::vfs::{PhysicalFS, VfsPath};
+use std::thread::sleep;
+
+/// Checks that the standard formats set on `Clipboard.generalClipboard` reach the UI backend,
+/// while custom formats stay in the movie.
+pub fn clipboard_backend_avm2(environment: &impl Environment) -> Result<(), libtest_mimic::Failed> {
+    let root = VfsPath::new(PhysicalFS::new("tests/swfs/avm2/clipboard_backend/"));
+    let test = Test::from_options(
+        TestOptions::read(&root.join("test.toml")?)?,
+        root,
+        "clipboard_backend_avm2".to_string(),
+    )?;
+    let mut runner = test.create_test_runner(environment)?;
+
+    loop {
+        runner.tick();
+        match runner.test()? {
+            TestStatus::Continue => {}
+            TestStatus::Sleep(duration) => sleep(duration),
+            TestStatus::Finished => break,
+        }
+    }
+
+    let player = runner.player().lock().unwrap();
+    let ui = player
+        .ui()
+        .downcast_ref::<TestUiBackend>()
+        .expect("Test must use the test UI backend");
+    std::assert_eq!(
+        ui.clipboard(),
+        [
+            ClipboardData::Text("text".to_string()),
+            ClipboardData::Html("<b>html</b>".to_string()),
+            ClipboardData::RichText(b"{\\rtf1 rich}".to_vec()),
+            ClipboardData::Url("https://example.com/".to_string()),
+            ClipboardData::Bitmap {
+                width: 2,
+                height: 1,
+                rgba: vec![0, 0, 255, 255, 255, 0, 0, 128],
+            },
+            ClipboardData::FileList(vec!["/tmp/a.txt".to_string(), "/tmp/b.txt".to_string()]),
+        ]
+    );
+
+    Ok(())
+}
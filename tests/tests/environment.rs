@@ -1,26 +1,60 @@
-use ruffle_test_framework::environment::Environment;
+use ruffle_test_framework::environment::{
+    Environment, RenderBackend, RenderInterface, SoftwareRenderInterface,
+};
+use ruffle_test_framework::options::RenderOptions;
 
 pub struct NativeEnvironment;
 
 impl Environment for NativeEnvironment {
-    #[cfg(feature = "imgtests")]
-    fn is_render_supported(
+    fn is_render_supported(&self, requirements: &RenderOptions) -> bool {
+        #[cfg(feature = "imgtests")]
+        if renderer::is_supported() {
+            return true;
+        }
+
+        SoftwareRenderInterface::is_supported(requirements)
+    }
+
+    fn create_renderer(
         &self,
-        requirements: &ruffle_test_framework::options::RenderOptions,
-    ) -> bool {
-        renderer::is_supported(requirements)
+        width: u32,
+        height: u32,
+    ) -> Option<(Box<dyn RenderInterface>, Box<dyn RenderBackend>)> {
+        #[cfg(feature = "imgtests")]
+        if renderer::is_supported() {
+            return renderer::NativeRenderInterface::create_pair(width, height);
+        }
+
+        Some(SoftwareRenderInterface::create_pair(width, height))
+    }
+}
+
+/// An environment that always renders with the software renderer, even when a GPU is available.
+pub struct SoftwareEnvironment;
+
+impl Environment for SoftwareEnvironment {
+    fn is_render_supported(&self, requirements: &RenderOptions) -> bool {
+        SoftwareRenderInterface::is_supported(requirements)
     }
 
-    #[cfg(feature = "imgtests")]
     fn create_renderer(
         &self,
         width: u32,
         height: u32,
-    ) -> Option<(
-        Box<dyn ruffle_test_framework::environment::RenderInterface>,
-        Box<dyn ruffle_test_framework::environment::RenderBackend>,
-    )> {
-        renderer::NativeRenderInterface::create_pair(width, height)
+    ) -> Option<(Box<dyn RenderInterface>, Box<dyn RenderBackend>)> {
+        Some(SoftwareRenderInterface::create_pair(width, height))
+    }
+}
+
+/// Checks if [NativeEnvironment] renders on the GPU, rather than falling back to the software renderer.
+pub fn is_gpu_supported() -> bool {
+    #[cfg(feature = "imgtests")]
+    {
+        renderer::is_supported()
+    }
+    #[cfg(not(feature = "imgtests"))]
+    {
+        false
     }
 }
 
@@ -32,7 +66,6 @@ mod renderer {
     use ruffle_render_wgpu::target::TextureTarget;
     use ruffle_render_wgpu::wgpu;
     use ruffle_test_framework::environment::{RenderBackend, RenderInterface};
-    use ruffle_test_framework::options::RenderOptions;
     use {std::sync::Arc, std::sync::OnceLock};
 
     pub struct NativeRenderInterface;
@@ -76,7 +109,7 @@ mod renderer {
         }
     }
 
-    pub fn is_supported(_requirements: &RenderOptions) -> bool {
+    pub fn is_supported() -> bool {
         descriptors().is_some()
     }
 
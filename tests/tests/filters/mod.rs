@@ -0,0 +1,60 @@
+use crate::environment::SoftwareEnvironment;
+use anyhow::anyhow;
+use image::RgbaImage;
+use ruffle_test_framework::environment::Environment;
+use ruffle_test_framework::options::{ImageComparison, TestOptions};
+use ruffle_test_framework::runner::TestStatus;
+use ruffle_test_framework::test::Test;
+use ruffle_test_framework::vfs::{PhysicalFS, VfsPath};
+use std::thread::sleep;
+
+/// Checks that the GPU filters render the same as the CPU filters of the software renderer.
+///
+/// Both implement the same math, so only rounding may differ between them.
+pub fn filters_gpu_matches_software(
+    environment: &impl Environment,
+) -> Result<(), libtest_mimic::Failed> {
+    let root = VfsPath::new(PhysicalFS::new(
+        "tests/swfs/avm2/filters_gradient_convolution_render/",
+    ));
+    let options = TestOptions::read(&root.join("test.toml")?)?;
+
+    let gpu_image = render(environment, &options, &root)?;
+    let software_image = render(&SoftwareEnvironment, &options, &root)?;
+    ImageComparison::new(3, 20).test(
+        "output",
+        gpu_image,
+        software_image,
+        &root,
+        "gpu".to_string(),
+        false,
+    )?;
+
+    Ok(())
+}
+
+fn render(
+    environment: &impl Environment,
+    options: &TestOptions,
+    root: &VfsPath,
+) -> anyhow::Result<RgbaImage> {
+    let test = Test::from_options(
+        options.clone(),
+        root.clone(),
+        "filters_gpu_matches_software".to_string(),
+    )?;
+    let mut runner = test.create_test_runner(environment)?;
+
+    loop {
+        runner.tick();
+        match runner.test()? {
+            TestStatus::Continue => {}
+            TestStatus::Sleep(duration) => sleep(duration),
+            TestStatus::Finished => break,
+        }
+    }
+
+    runner
+        .capture()
+        .ok_or_else(|| anyhow!("The test must be run with a renderer"))
+}
@@ -2,8 +2,10 @@
 //!
 //! Trace output can be compared with correct output from the official Flash Player.
 
-use crate::environment::NativeEnvironment;
+use crate::clipboard::clipboard_backend_avm2;
+use crate::environment::{is_gpu_supported, NativeEnvironment};
 use crate::external_interface::tests::{external_interface_avm1, external_interface_avm2};
+use crate::filters::filters_gpu_matches_software;
 use crate::shared_object::{shared_object_avm1, shared_object_avm2, shared_object_self_ref_avm1};
 use anyhow::Context;
 use anyhow::Result;
@@ -17,8 +19,10 @@ use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
 use std::path::Path;
 use std::thread::sleep;
 
+mod clipboard;
 mod environment;
 mod external_interface;
+mod filters;
 mod shared_object;
 
 const TEST_TOML_NAME: &str = "test.toml";
@@ -119,12 +123,22 @@ fn main() {
     tests.push(Trial::test("shared_object_avm2", || {
         shared_object_avm2(&NativeEnvironment)
     }));
+    tests.push(Trial::test("clipboard_backend_avm2", || {
+        clipboard_backend_avm2(&NativeEnvironment)
+    }));
     tests.push(Trial::test("external_interface_avm1", || {
         external_interface_avm1(&NativeEnvironment)
     }));
     tests.push(Trial::test("external_interface_avm2", || {
         external_interface_avm2(&NativeEnvironment)
     }));
+    // Only meaningful with a GPU, since the software renderer would be compared with itself.
+    tests.push(
+        Trial::test("filters_gpu_matches_software", || {
+            filters_gpu_matches_software(&NativeEnvironment)
+        })
+        .with_ignored_flag(!args.list && !is_gpu_supported()),
+    );
 
     tests.sort_unstable_by(|a, b| a.name().cmp(b.name()));
 
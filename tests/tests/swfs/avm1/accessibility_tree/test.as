@@ -0,0 +1,47 @@
+// Compile with Flash 8 (AS2), SWF version 10.
+
+trace("// Accessibility.isActive()");
+trace(Accessibility.isActive());
+
+this.createTextField("label", 1, 10, 10, 100, 20);
+label.text = "Hello";
+
+this.createTextField("input", 2, 10, 40, 100, 20);
+input.text = "typed";
+input.type = "input";
+input.tabIndex = 2;
+
+this.createTextField("password", 3, 10, 70, 100, 20);
+password.text = "secret";
+password.type = "input";
+password.password = true;
+
+// Non-editable password fields must not expose their text either.
+this.createTextField("hiddenPassword", 4, 10, 100, 100, 20);
+hiddenPassword.text = "secret";
+hiddenPassword.password = true;
+
+this.createEmptyMovieClip("picture", 5);
+picture._x = 200;
+picture._y = 50;
+picture.beginFill(0x0000FF);
+picture.moveTo(0, 0);
+picture.lineTo(40, 0);
+picture.lineTo(40, 40);
+picture.lineTo(0, 40);
+picture.lineTo(0, 0);
+picture.endFill();
+var pictureProps = new Object();
+pictureProps.name = "Logo";
+pictureProps.description = "A blue square";
+picture._accProps = pictureProps;
+picture.createTextField("caption", 1, 0, 0, 40, 20);
+picture.caption.text = "Caption";
+
+this.createTextField("silent", 6, 200, 100, 100, 20);
+silent.text = "Silent";
+var silentProps = new Object();
+silentProps.silent = true;
+silent._accProps = silentProps;
+
+Accessibility.updateProperties();
@@ -0,0 +1,25 @@
+// Compile with Flash 8 (AS2), SWF version 10.
+
+var first = SharedObject.getLocal("first");
+first.data.value = "hello";
+first.flush();
+var second = SharedObject.getLocal("second");
+second.data.number = 42;
+second.flush();
+
+var usage = SharedObject.getDiskUsage(_url);
+trace("// SharedObject.getDiskUsage(_url) > 0");
+trace(usage > 0);
+
+// Other domains can't be accessed.
+trace("// SharedObject.getDiskUsage(\"http://example.com/\")");
+trace(SharedObject.getDiskUsage("http://example.com/"));
+trace("// SharedObject.deleteAll(\"http://example.com/\")");
+trace(SharedObject.deleteAll("http://example.com/"));
+trace("// SharedObject.getDiskUsage(_url) == usage");
+trace(SharedObject.getDiskUsage(_url) == usage);
+
+trace("// SharedObject.deleteAll(_url)");
+trace(SharedObject.deleteAll(_url));
+trace("// SharedObject.getDiskUsage(_url)");
+trace(SharedObject.getDiskUsage(_url));
@@ -0,0 +1,103 @@
+package {
+    import flash.accessibility.Accessibility;
+    import flash.accessibility.AccessibilityProperties;
+    import flash.display.MovieClip;
+    import flash.display.Sprite;
+    import flash.events.Event;
+    import flash.text.TextField;
+
+    // Checks when the `AccessibilityImplementation` of an object is asked about it:
+    // only when the object (or anything below it) changed since it was last asked.
+    public class Test extends MovieClip {
+        private var frame:int = 0;
+        private var custom:Sprite;
+        private var label:TextField;
+        private var other:Sprite;
+
+        public function Test() {
+            custom = new Sprite();
+            custom.graphics.beginFill(0xFF0000);
+            custom.graphics.drawRect(0, 0, 100, 20);
+            custom.x = 10;
+            custom.y = 10;
+            addChild(custom);
+
+            label = new TextField();
+            label.text = "Start";
+            custom.addChild(label);
+            custom.accessibilityImplementation = new LabelImplementation(label);
+
+            other = new Sprite();
+            other.graphics.beginFill(0x0000FF);
+            other.graphics.drawRect(0, 0, 20, 20);
+            other.x = 10;
+            other.y = 50;
+            var properties:AccessibilityProperties = new AccessibilityProperties();
+            properties.name = "Other";
+            other.accessibilityProperties = properties;
+            addChild(other);
+
+            trace("// frame 1");
+            addEventListener(Event.ENTER_FRAME, onEnterFrame);
+        }
+
+        private function onEnterFrame(event:Event):void {
+            frame++;
+            if (frame == 1) {
+                // The constructor ran in the first frame already.
+                return;
+            }
+            trace("// frame " + frame);
+            switch (frame) {
+                case 2:
+                    trace("(nothing changed)");
+                    break;
+                case 3:
+                    trace("other.x = 40");
+                    other.x = 40;
+                    break;
+                case 4:
+                    trace("Accessibility.sendEvent(custom, 0, EVENT_OBJECT_NAMECHANGE)");
+                    Accessibility.sendEvent(custom, 0, 0x800C);
+                    break;
+                case 5:
+                    trace("label.text = \"Changed\"");
+                    label.text = "Changed";
+                    break;
+            }
+        }
+    }
+}
+
+import flash.accessibility.AccessibilityImplementation;
+import flash.text.TextField;
+
+class LabelImplementation extends AccessibilityImplementation {
+    private var label:TextField;
+
+    public function LabelImplementation(label:TextField) {
+        this.label = label;
+    }
+
+    override public function get_accName(childID:uint):String {
+        trace("get_accName(" + childID + ")");
+        return label.text;
+    }
+
+    override public function get_accRole(childID:uint):uint {
+        trace("get_accRole(" + childID + ")");
+        // ROLE_SYSTEM_PUSHBUTTON
+        return 0x2B;
+    }
+
+    override public function get_accValue(childID:uint):String {
+        trace("get_accValue(" + childID + ")");
+        return null;
+    }
+
+    override public function get_accState(childID:uint):uint {
+        trace("get_accState(" + childID + ")");
+        // STATE_SYSTEM_PRESSED
+        return 0x8;
+    }
+}
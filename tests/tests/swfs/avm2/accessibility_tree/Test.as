@@ -0,0 +1,98 @@
+package {
+    import flash.accessibility.Accessibility;
+    import flash.accessibility.AccessibilityProperties;
+    import flash.display.MovieClip;
+    import flash.display.Shape;
+    import flash.display.SimpleButton;
+    import flash.display.Sprite;
+    import flash.text.TextField;
+    import flash.text.TextFieldType;
+
+    public class Test extends MovieClip {
+        public function Test() {
+            trace("// Accessibility.active");
+            trace(Accessibility.active);
+
+            var label:TextField = new TextField();
+            label.text = "Hello";
+            label.x = 10;
+            label.y = 10;
+            label.width = 100;
+            label.height = 20;
+            addChild(label);
+
+            var input:TextField = new TextField();
+            input.type = TextFieldType.INPUT;
+            input.text = "typed";
+            input.x = 10;
+            input.y = 40;
+            input.width = 100;
+            input.height = 20;
+            input.tabIndex = 2;
+            addChild(input);
+
+            var password:TextField = new TextField();
+            password.type = TextFieldType.INPUT;
+            password.displayAsPassword = true;
+            password.text = "secret";
+            password.x = 10;
+            password.y = 70;
+            password.width = 100;
+            password.height = 20;
+            addChild(password);
+
+            var hiddenPassword:TextField = new TextField();
+            hiddenPassword.displayAsPassword = true;
+            hiddenPassword.text = "secret";
+            hiddenPassword.x = 10;
+            hiddenPassword.y = 100;
+            hiddenPassword.width = 100;
+            hiddenPassword.height = 20;
+            addChild(hiddenPassword);
+
+            var button:SimpleButton = new SimpleButton();
+            var up:Shape = new Shape();
+            up.graphics.beginFill(0xFF0000);
+            up.graphics.drawRect(0, 0, 50, 20);
+            button.upState = up;
+            button.hitTestState = up;
+            button.x = 200;
+            button.y = 10;
+            var buttonProperties:AccessibilityProperties = new AccessibilityProperties();
+            buttonProperties.name = "Play";
+            buttonProperties.shortcut = "Ctrl+P";
+            button.accessibilityProperties = buttonProperties;
+            addChild(button);
+
+            var picture:Sprite = new Sprite();
+            picture.graphics.beginFill(0x0000FF);
+            picture.graphics.drawRect(0, 0, 40, 40);
+            picture.x = 200;
+            picture.y = 50;
+            var pictureProperties:AccessibilityProperties = new AccessibilityProperties();
+            pictureProperties.name = "Logo";
+            pictureProperties.description = "A blue square";
+            picture.accessibilityProperties = pictureProperties;
+            addChild(picture);
+
+            var caption:TextField = new TextField();
+            caption.text = "Caption";
+            caption.width = 40;
+            caption.height = 20;
+            picture.addChild(caption);
+
+            var silent:TextField = new TextField();
+            silent.text = "Silent";
+            silent.x = 200;
+            silent.y = 100;
+            silent.width = 100;
+            silent.height = 20;
+            var silentProperties:AccessibilityProperties = new AccessibilityProperties();
+            silentProperties.silent = true;
+            silent.accessibilityProperties = silentProperties;
+            addChild(silent);
+
+            Accessibility.updateProperties();
+        }
+    }
+}
@@ -0,0 +1,54 @@
+package {
+    import flash.desktop.Clipboard;
+    import flash.desktop.ClipboardFormats;
+    import flash.display.BitmapData;
+    import flash.display.MovieClip;
+    import flash.filesystem.File;
+    import flash.utils.ByteArray;
+
+    // The clipboard is left filled, so that the test can check what reached the UI backend.
+    public class Test extends MovieClip {
+        public function Test() {
+            var general:Clipboard = Clipboard.generalClipboard;
+            general.clear();
+
+            var richText:ByteArray = new ByteArray();
+            richText.writeUTFBytes("{\\rtf1 rich}");
+            var bitmap:BitmapData = new BitmapData(2, 1, true, 0xFF0000FF);
+            bitmap.setPixel32(1, 0, 0x80FF0000);
+
+            general.setData(ClipboardFormats.TEXT_FORMAT, "text");
+            general.setData(ClipboardFormats.HTML_FORMAT, "<b>html</b>");
+            general.setData(ClipboardFormats.RICH_TEXT_FORMAT, richText);
+            general.setData(ClipboardFormats.URL_FORMAT, "https://example.com/");
+            general.setData(ClipboardFormats.BITMAP_FORMAT, bitmap);
+            general.setData(ClipboardFormats.FILE_LIST_FORMAT, [new File("/tmp/a.txt"), "/tmp/b.txt"]);
+            general.setData("custom", "only in the movie");
+
+            var formats:Array = general.formats;
+            formats.sort();
+            trace("formats: " + formats);
+
+            trace("text: " + general.getData(ClipboardFormats.TEXT_FORMAT));
+            trace("html: " + general.getData(ClipboardFormats.HTML_FORMAT));
+
+            var readRichText:ByteArray = general.getData(ClipboardFormats.RICH_TEXT_FORMAT) as ByteArray;
+            trace("rich text: " + readRichText.readUTFBytes(readRichText.length));
+            trace("rich text is a copy: " + (readRichText !== richText));
+
+            trace("url: " + general.getData(ClipboardFormats.URL_FORMAT));
+
+            var readBitmap:BitmapData = general.getData(ClipboardFormats.BITMAP_FORMAT) as BitmapData;
+            trace("bitmap: " + readBitmap.width + "x" + readBitmap.height + ", transparent: " + readBitmap.transparent);
+            trace("pixels: " + readBitmap.getPixel32(0, 0).toString(16) + ", " + readBitmap.getPixel32(1, 0).toString(16));
+
+            var files:Array = general.getData(ClipboardFormats.FILE_LIST_FORMAT) as Array;
+            trace("files: " + files.length);
+            for each (var file:File in files) {
+                trace(file.nativePath);
+            }
+
+            trace("custom: " + general.getData("custom"));
+        }
+    }
+}
@@ -0,0 +1,81 @@
+package {
+    import flash.desktop.Clipboard;
+    import flash.desktop.ClipboardFormats;
+    import flash.display.MovieClip;
+
+    public class Test extends MovieClip {
+        public function Test() {
+            var general:Clipboard = Clipboard.generalClipboard;
+            general.clear();
+            trace("// clear()");
+            traceFormats(general);
+
+            general.setData(ClipboardFormats.TEXT_FORMAT, "hello");
+            trace("// setData(TEXT_FORMAT, \"hello\")");
+            traceFormats(general);
+            trace(general.getData(ClipboardFormats.TEXT_FORMAT));
+
+            var custom:Object = {value: 42};
+            general.setData("custom", custom);
+            general.setData(ClipboardFormats.HTML_FORMAT, "<b>bold</b>");
+            trace("// setData(\"custom\", custom), setData(HTML_FORMAT, \"<b>bold</b>\")");
+            traceFormats(general);
+            trace(general.getData("custom") === custom);
+            trace(general.getData(ClipboardFormats.HTML_FORMAT));
+
+            general.clearData(ClipboardFormats.TEXT_FORMAT);
+            trace("// clearData(TEXT_FORMAT)");
+            traceFormats(general);
+            trace(general.getData(ClipboardFormats.TEXT_FORMAT));
+            trace(general.getData("custom") === custom);
+
+            var calls:int = 0;
+            general.setDataHandler(ClipboardFormats.TEXT_FORMAT, function():String {
+                calls++;
+                trace("handler called");
+                return "deferred";
+            });
+            trace("// setDataHandler(TEXT_FORMAT, handler)");
+            traceFormats(general);
+            trace("calls: " + calls);
+            trace(general.getData(ClipboardFormats.TEXT_FORMAT));
+            trace(general.getData(ClipboardFormats.TEXT_FORMAT));
+            trace("calls: " + calls);
+
+            general.setDataHandler("custom", function():String {
+                trace("overridden handler called");
+                return "overridden";
+            });
+            general.setData("custom", "direct");
+            trace("// setDataHandler(\"custom\", handler), setData(\"custom\", \"direct\")");
+            trace(general.getData("custom"));
+
+            general.setDataHandler("custom", function():String {
+                trace("cleared handler called");
+                return "cleared";
+            });
+            general.clearData("custom");
+            trace("// setDataHandler(\"custom\", handler), clearData(\"custom\")");
+            traceFormats(general);
+            trace(general.getData("custom"));
+
+            var other:Clipboard = new Clipboard();
+            other.setData(ClipboardFormats.TEXT_FORMAT, "private");
+            trace("// new Clipboard().setData(TEXT_FORMAT, \"private\")");
+            traceFormats(other);
+            trace(other.getData(ClipboardFormats.TEXT_FORMAT));
+            trace(general.getData(ClipboardFormats.TEXT_FORMAT));
+
+            general.clear();
+            trace("// clear()");
+            traceFormats(general);
+            trace(general.getData(ClipboardFormats.HTML_FORMAT));
+        }
+
+        private function traceFormats(clipboard:Clipboard):void {
+            var formats:Array = clipboard.formats;
+            formats.sort();
+            trace("formats: " + formats + ", hasFormat(TEXT_FORMAT): " + clipboard.hasFormat(ClipboardFormats.TEXT_FORMAT));
+        }
+    }
+}
@@ -0,0 +1,11 @@
+package {
+    import flash.display.Sprite;
+
+    public class Square extends Sprite {
+        public function Square() {
+            graphics.beginFill(0xFF0000);
+            graphics.drawRect(0, 0, 20, 20);
+            graphics.endFill();
+        }
+    }
+}
@@ -0,0 +1,43 @@
+package {
+    import flash.display.DisplayObject;
+    import flash.display.MovieClip;
+    import flash.text.TextField;
+    import flash.text.TextLineMetrics;
+
+    public class Test extends MovieClip {
+        public function Test() {
+            var field:TextField = new TextField();
+            field.width = 200;
+            field.height = 200;
+            field.multiline = true;
+            field.wordWrap = true;
+            field.htmlText = '<img src="Square" id="left" align="left" width="40" height="40" hspace="2" vspace="0">' +
+                'Text flowing between both images, long enough to wrap a few times below them.' +
+                '<img src="Square" id="right" align="right" width="30" height="30" hspace="0" vspace="0">';
+            addChild(field);
+
+            trace("// numLines");
+            trace(field.numLines);
+            for (var i:int = 0; i < field.numLines; i++) {
+                var metrics:TextLineMetrics = field.getLineMetrics(i);
+                trace("// line " + i + ": x=" + metrics.x + ", height=" + metrics.height + ", leading=" + metrics.leading +
+                      ", text=" + field.getLineText(i));
+            }
+
+            traceImage(field, "left");
+            traceImage(field, "right");
+            traceImage(field, "missing");
+        }
+
+        private function traceImage(field:TextField, id:String):void {
+            var image:DisplayObject = field.getImageReference(id);
+            trace("// getImageReference(\"" + id + "\")");
+            if (image) {
+                trace(image + " x=" + image.x + " y=" + image.y +
+                      " width=" + image.width + " height=" + image.height);
+            } else {
+                trace(image);
+            }
+        }
+    }
+}
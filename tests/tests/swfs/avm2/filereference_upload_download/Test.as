@@ -0,0 +1,136 @@
+package {
+  import flash.display.Sprite;
+  public class Test extends Sprite { }
+}
+
+import flash.display.Sprite;
+import flash.net.FileReference;
+import flash.net.FileFilter;
+import flash.net.URLRequest;
+import flash.net.URLRequestMethod;
+import flash.net.URLVariables;
+import flash.events.Event;
+import flash.events.DataEvent;
+import flash.events.HTTPStatusEvent;
+import flash.events.IOErrorEvent;
+import flash.events.ProgressEvent;
+
+var file = new FileReference();
+var steps = [];
+var browsing = false;
+
+function listen(file) {
+    for each (var type in [Event.SELECT, Event.CANCEL, Event.OPEN, Event.COMPLETE]) {
+        file.addEventListener(type, function(e) {
+            trace("  " + e.type + " event");
+            // Downloads carry on after selecting a destination, browsing is done.
+            if (e.type == Event.SELECT ? browsing : e.type != Event.OPEN) {
+                browsing = false;
+                next();
+            }
+        });
+    }
+    file.addEventListener(ProgressEvent.PROGRESS, function(e) {
+        trace("  progress event: " + e.bytesLoaded + " / " + e.bytesTotal);
+    });
+    file.addEventListener(DataEvent.UPLOAD_COMPLETE_DATA, function(e) {
+        trace("  uploadCompleteData event: " + e.data);
+    });
+    file.addEventListener(HTTPStatusEvent.HTTP_STATUS, function(e) {
+        trace("  httpStatus event: " + e.status);
+    });
+    file.addEventListener(IOErrorEvent.IO_ERROR, function(e) {
+        trace("  ioError event: " + e.text);
+        next();
+    });
+}
+
+// Steps run on the next frame, so that every event of the previous one is traced first.
+var ticker = new Sprite();
+var advance = false;
+
+function next() {
+    advance = true;
+}
+
+ticker.addEventListener(Event.ENTER_FRAME, function(e) {
+    if (!advance) {
+        return;
+    }
+    advance = false;
+    if (steps.length > 0) {
+        steps.shift()();
+    } else if (ticker.hasEventListener(Event.ENTER_FRAME)) {
+        trace("done");
+        ticker.removeEventListener(Event.ENTER_FRAME, arguments.callee);
+    }
+});
+
+function attempt(name, fn) {
+    try {
+        fn();
+    } catch (e) {
+        trace(name + " threw: " + e);
+    }
+}
+
+listen(file);
+
+steps.push(function() {
+    trace("upload() before browse()");
+    attempt("upload()", function() { file.upload(new URLRequest("upload.php?debug-success")); });
+    trace("browse()");
+    browsing = true;
+    file.browse([new FileFilter("debug-select-success", "*.txt")]);
+});
+
+steps.push(function() {
+    trace("upload()");
+    file.upload(new URLRequest("upload.php?debug-success"));
+    trace("upload() again");
+    attempt("upload()", function() { file.upload(new URLRequest("upload.php?debug-success")); });
+});
+
+steps.push(function() {
+    trace("upload() with variables");
+    var request = new URLRequest("upload_response.txt");
+    request.method = URLRequestMethod.POST;
+    var variables = new URLVariables();
+    variables.user = "ruffle";
+    request.data = variables;
+    file.upload(request, "Upload1");
+});
+
+steps.push(function() {
+    trace("upload() failing");
+    file.upload(new URLRequest("upload.php?debug-error-statuscode"));
+});
+
+steps.push(function() {
+    trace("upload() then cancel()");
+    file.upload(new URLRequest("upload.php?debug-success"));
+    file.cancel();
+    trace("upload() after cancel()");
+    file.upload(new URLRequest("upload.php?debug-success"));
+});
+
+steps.push(function() {
+    trace("download() to a canceled destination");
+    file.download(new URLRequest("data.txt?debug-success"), "canceled.txt");
+});
+
+steps.push(function() {
+    trace("download()");
+    file.download(new URLRequest("data.txt?debug-success"), "debug-success.txt");
+});
+
+steps.push(function() {
+    trace("name: " + file.name + ", size: " + file.size);
+    trace("download() then cancel()");
+    file.download(new URLRequest("data.txt?debug-success"), "debug-success.txt");
+    file.cancel();
+    trace("download() failing");
+    file.download(new URLRequest("data.txt?debug-error-dns"), "debug-success.txt");
+});
+
+next();
@@ -0,0 +1,37 @@
+package {
+  import flash.display.Sprite;
+  public class Test extends Sprite { }
+}
+
+import flash.net.FileReferenceList;
+import flash.net.FileFilter;
+import flash.events.Event;
+
+var list = new FileReferenceList();
+
+function dump(list) {
+    trace("fileList.length: " + list.fileList.length);
+    for each (var file in list.fileList) {
+        trace("  " + file.name + ", " + file.size + " bytes, type " + file.type);
+    }
+}
+
+function onselect(e) {
+    trace("select event");
+    dump(e.target);
+
+    trace("browse() (canceled): " + list.browse([new FileFilter("Text", "*.txt")]));
+    dump(list);
+}
+
+function oncancel(e) {
+    trace("cancel event");
+    dump(e.target);
+}
+
+list.addEventListener(Event.SELECT, onselect);
+list.addEventListener(Event.CANCEL, oncancel);
+
+trace("fileList before browse(): " + list.fileList);
+trace("browse(): " + list.browse([new FileFilter("debug-select-multiple", "*.txt")]));
+dump(list);
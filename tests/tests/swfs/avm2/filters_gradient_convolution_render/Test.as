@@ -0,0 +1,93 @@
+package {
+
+import flash.display.MovieClip;
+import flash.display.Shape;
+import flash.filters.BitmapFilter;
+import flash.filters.BitmapFilterQuality;
+import flash.filters.BitmapFilterType;
+import flash.filters.ConvolutionFilter;
+import flash.filters.GradientBevelFilter;
+import flash.filters.GradientGlowFilter;
+import flash.utils.getQualifiedClassName;
+
+[SWF(width="300", height="140", backgroundColor="#FFFFFF")]
+public class Test extends MovieClip {
+    private var cell:int = 0;
+
+    public function Test() {
+        var glowColors:Array = [0xFFFFFF, 0xFF0000, 0xFFFF00];
+        var glowAlphas:Array = [0, 1, 1];
+        var glowRatios:Array = [0, 128, 255];
+
+        // Gradient glows
+        add([new GradientGlowFilter(0, 45, glowColors, glowAlphas, glowRatios, 8, 8, 2, BitmapFilterQuality.LOW, BitmapFilterType.OUTER)]);
+        add([new GradientGlowFilter(0, 45, glowColors, glowAlphas, glowRatios, 8, 8, 2, BitmapFilterQuality.LOW, BitmapFilterType.INNER)]);
+        add([new GradientGlowFilter(4, 45, glowColors, glowAlphas, glowRatios, 8, 8, 1, BitmapFilterQuality.MEDIUM, BitmapFilterType.FULL)]);
+        add([new GradientGlowFilter(0, 45, glowColors, glowAlphas, glowRatios, 8, 8, 2, BitmapFilterQuality.LOW, BitmapFilterType.OUTER, true)]);
+
+        // Gradient bevels
+        var bevelColors:Array = [0x0000FF, 0x000000, 0xFFFFFF];
+        var bevelAlphas:Array = [1, 0, 1];
+        var bevelRatios:Array = [0, 128, 255];
+        add([new GradientBevelFilter(4, 45, bevelColors, bevelAlphas, bevelRatios, 4, 4, 1, BitmapFilterQuality.LOW, BitmapFilterType.INNER)]);
+        add([new GradientBevelFilter(4, 45, bevelColors, bevelAlphas, bevelRatios, 4, 4, 1, BitmapFilterQuality.LOW, BitmapFilterType.OUTER)]);
+        add([new GradientBevelFilter(4, 135, bevelColors, bevelAlphas, bevelRatios, 6, 6, 2, BitmapFilterQuality.MEDIUM, BitmapFilterType.FULL)]);
+        add([new GradientBevelFilter(4, 45, bevelColors, bevelAlphas, bevelRatios, 4, 4, 1, BitmapFilterQuality.LOW, BitmapFilterType.INNER, true)]);
+
+        // Convolutions
+        var edges:Array = [0, -1, 0, -1, 4, -1, 0, -1, 0];
+        var blur:Array = [1, 2, 1, 2, 4, 2, 1, 2, 1];
+        add([new ConvolutionFilter(3, 3, edges, 1, 128)]);
+        add([new ConvolutionFilter(3, 3, blur, 16, 0, true, false)]);
+        add([new ConvolutionFilter(3, 3, blur, 16, 0, false, false, 0x00FF00, 1)]);
+        add([new ConvolutionFilter(3, 1, [-1, 0, 1], 1, 64, true, true)]);
+    }
+
+    private function add(filters:Array):void {
+        var shape:Shape = new Shape();
+        shape.graphics.beginFill(0x3366CC);
+        shape.graphics.drawRect(0, 0, 30, 30);
+        shape.graphics.endFill();
+        shape.graphics.beginFill(0xCC9933, 0.5);
+        shape.graphics.drawCircle(30, 30, 12);
+        shape.graphics.endFill();
+        shape.x = 15 + (cell % 4) * 75;
+        shape.y = 10 + int(cell / 4) * 45;
+        shape.filters = filters;
+        addChild(shape);
+        cell += 1;
+
+        // The filters are read back from the display object, so this shows what it's rendered with.
+        for each (var filter:BitmapFilter in shape.filters) {
+            trace("// " + getQualifiedClassName(filter));
+            if (filter is ConvolutionFilter) {
+                traceConvolution(ConvolutionFilter(filter));
+            } else if (filter is GradientGlowFilter) {
+                var glow:GradientGlowFilter = GradientGlowFilter(filter);
+                traceGradient(glow.colors, glow.alphas, glow.ratios, glow.quality, glow.type, glow.knockout);
+            } else if (filter is GradientBevelFilter) {
+                var bevel:GradientBevelFilter = GradientBevelFilter(filter);
+                traceGradient(bevel.colors, bevel.alphas, bevel.ratios, bevel.quality, bevel.type, bevel.knockout);
+            }
+        }
+    }
+
+    private function traceGradient(colors:Array, alphas:Array, ratios:Array, quality:int, type:String, knockout:Boolean):void {
+        var hexColors:Array = [];
+        for each (var color:uint in colors) {
+            hexColors.push("0x" + color.toString(16).toUpperCase());
+        }
+        trace("colors: " + hexColors);
+        trace("alphas: " + alphas);
+        trace("ratios: " + ratios);
+        trace("quality: " + quality + ", type: " + type + ", knockout: " + knockout);
+    }
+
+    private function traceConvolution(filter:ConvolutionFilter):void {
+        trace("matrix: " + filter.matrixX + "x" + filter.matrixY + " [" + filter.matrix + "]");
+        trace("divisor: " + filter.divisor + ", bias: " + filter.bias);
+        trace("preserveAlpha: " + filter.preserveAlpha + ", clamp: " + filter.clamp);
+        trace("color: 0x" + filter.color.toString(16).toUpperCase() + ", alpha: " + filter.alpha);
+    }
+}
+}
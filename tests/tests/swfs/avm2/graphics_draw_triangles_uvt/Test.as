@@ -0,0 +1,171 @@
+package {
+
+import flash.display.BitmapData;
+import flash.display.Graphics;
+import flash.display.MovieClip;
+import flash.display.Shape;
+import flash.display.TriangleCulling;
+
+[SWF(width="210", height="50", backgroundColor="#000000")]
+public class Test extends MovieClip {
+    private var shapeId:int = 0;
+    private var checker:BitmapData;
+
+    public function Test() {
+        checker = new BitmapData(8, 8, false, 0xFF0000);
+        for (var y:int = 0; y < 8; y++) {
+            for (var x:int = 0; x < 8; x++) {
+                if ((x + y) % 2 == 1) {
+                    checker.setPixel(x, y, 0x0000FF);
+                }
+            }
+        }
+
+        // The same trapezoid, as a quad seen in perspective: the top edge is twice as far away.
+        var trapezoid:Vector.<Number> = Vector.<Number>([
+            15, 5,
+            25, 5,
+            5, 35,
+            35, 35
+        ]);
+        var indices:Vector.<int> = Vector.<int>([0, 1, 2, 1, 3, 2]);
+
+        // Each shape is checked against the texture coordinates computed here, and traced.
+
+        // 1. Square with UVs
+        draw(Vector.<Number>([5, 5, 35, 5, 5, 35, 35, 35]), indices,
+            Vector.<Number>([0, 0, 1, 0, 0, 1, 1, 1]));
+
+        // 2. Trapezoid with UVs, mapped affinely
+        draw(trapezoid, indices, Vector.<Number>([0, 0, 1, 0, 0, 1, 1, 1]));
+
+        // 3. Trapezoid with UVTs, mapped with perspective correction
+        draw(trapezoid, indices, Vector.<Number>([0, 0, 0.5, 1, 0, 0.5, 0, 1, 1, 1, 1, 1]));
+
+        // Two triangles with UVTs, the left one wound clockwise and the right one counterclockwise.
+        var pair:Vector.<Number> = Vector.<Number>([
+            5, 5, 18, 5, 5, 35,
+            35, 5, 22, 35, 35, 35
+        ]);
+        var pairUvt:Vector.<Number> = Vector.<Number>([
+            0, 0, 1, 1, 0, 1, 0, 1, 0.5,
+            1, 0, 0.5, 0, 1, 1, 1, 1, 1
+        ]);
+
+        // 4. Positive culling
+        draw(pair, null, pairUvt, TriangleCulling.POSITIVE);
+
+        // 5. Negative culling
+        draw(pair, null, pairUvt, TriangleCulling.NEGATIVE);
+    }
+
+    private function draw(vertices:Vector.<Number>, indices:Vector.<int>, uvtData:Vector.<Number>, culling:String = "none"):void {
+        var shape:Shape = new Shape();
+        shape.x = shapeId * 40 + 5;
+        shape.y = 5;
+        shapeId += 1;
+        addChild(shape);
+
+        var g:Graphics = shape.graphics;
+        g.beginBitmapFill(checker, null, false, false);
+        g.drawTriangles(vertices, indices, uvtData, culling);
+        g.endFill();
+
+        check(shape, vertices, indices, uvtData, culling);
+    }
+
+    // Checks the rendered shape against the texture coordinates computed for the centers of its pixels.
+    // Pixels close to the edge of a triangle or of a texel are skipped, as they depend on rounding.
+    private function check(shape:Shape, vertices:Vector.<Number>, indices:Vector.<int>, uvtData:Vector.<Number>, culling:String):void {
+        var rendered:BitmapData = new BitmapData(40, 40, true, 0);
+        rendered.draw(shape);
+
+        if (indices == null) {
+            indices = new Vector.<int>();
+            for (var i:int = 0; i < vertices.length / 2; i++) {
+                indices.push(i);
+            }
+        }
+        var uvtSize:int = uvtData.length / (vertices.length / 2);
+
+        var checked:int = 0;
+        var matching:int = 0;
+        for (var y:int = 0; y < 40; y++) {
+            for (var x:int = 0; x < 40; x++) {
+                var expected:Object = expectedPixel(x + 0.5, y + 0.5, vertices, indices, uvtData, uvtSize, culling);
+                if (expected == null) {
+                    continue;
+                }
+                checked++;
+                var actual:uint = rendered.getPixel32(x, y);
+                if (actual == expected) {
+                    matching++;
+                } else {
+                    trace("  (" + x + ", " + y + "): " + actual.toString(16) + " instead of " + uint(expected).toString(16));
+                }
+            }
+        }
+        trace(shapeId + ". " + matching + " of " + checked + " pixels match");
+    }
+
+    // The color of the pixel centered at (x, y), or null if that's too close to call.
+    private function expectedPixel(x:Number, y:Number, vertices:Vector.<Number>, indices:Vector.<int>,
+            uvtData:Vector.<Number>, uvtSize:int, culling:String):Object {
+        const EDGE_DISTANCE:Number = 1;
+        const TEXEL_MARGIN:Number = 0.2;
+
+        for (var i:int = 0; i < indices.length; i += 3) {
+            var a:int = indices[i], b:int = indices[i + 1], c:int = indices[i + 2];
+            var ax:Number = vertices[a * 2], ay:Number = vertices[a * 2 + 1];
+            var bx:Number = vertices[b * 2], by:Number = vertices[b * 2 + 1];
+            var cx:Number = vertices[c * 2], cy:Number = vertices[c * 2 + 1];
+
+            // The same orientation as the culling in graphics_draw_triangles.
+            var orientation:Number = (bx - ax) * (cy - ay) - (by - ay) * (cx - ax);
+            if ((culling == "positive" && orientation >= 0) || (culling == "negative" && orientation <= 0)) {
+                continue;
+            }
+
+            // The distances of the point from each edge, positive on the inside.
+            var sign:Number = orientation > 0 ? 1 : -1;
+            var da:Number = sign * ((cx - bx) * (y - by) - (cy - by) * (x - bx));
+            var db:Number = sign * ((ax - cx) * (y - cy) - (ay - cy) * (x - cx));
+            var dc:Number = sign * ((bx - ax) * (y - ay) - (by - ay) * (x - ax));
+            var distance:Number = Math.min(
+                da / Math.sqrt((cx - bx) * (cx - bx) + (cy - by) * (cy - by)),
+                db / Math.sqrt((ax - cx) * (ax - cx) + (ay - cy) * (ay - cy)),
+                dc / Math.sqrt((bx - ax) * (bx - ax) + (by - ay) * (by - ay))
+            );
+            if (distance < -EDGE_DISTANCE) {
+                continue;
+            }
+            if (distance < EDGE_DISTANCE) {
+                return null;
+            }
+
+            // Interpolate u * t, v * t and t, which is the same as interpolating u and v without t.
+            var area:Number = da + db + dc;
+            var wa:Number = (da / area) * t(uvtData, a, uvtSize);
+            var wb:Number = (db / area) * t(uvtData, b, uvtSize);
+            var wc:Number = (dc / area) * t(uvtData, c, uvtSize);
+            var w:Number = wa + wb + wc;
+            var u:Number = (wa * uvtData[a * uvtSize] + wb * uvtData[b * uvtSize] + wc * uvtData[c * uvtSize]) / w;
+            var v:Number = (wa * uvtData[a * uvtSize + 1] + wb * uvtData[b * uvtSize + 1] + wc * uvtData[c * uvtSize + 1]) / w;
+
+            var texelX:Number = u * checker.width;
+            var texelY:Number = v * checker.height;
+            if (Math.abs(texelX - Math.round(texelX)) < TEXEL_MARGIN || Math.abs(texelY - Math.round(texelY)) < TEXEL_MARGIN) {
+                return null;
+            }
+            return checker.getPixel32(Math.floor(texelX), Math.floor(texelY));
+        }
+
+        // Nothing is drawn here.
+        return 0;
+    }
+
+    private function t(uvtData:Vector.<Number>, vertex:int, uvtSize:int):Number {
+        return uvtSize == 3 ? uvtData[vertex * 3 + 2] : 1;
+    }
+}
+}
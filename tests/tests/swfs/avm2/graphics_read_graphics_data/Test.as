@@ -0,0 +1,144 @@
+package {
+
+import flash.display.BitmapData;
+import flash.display.GradientType;
+import flash.display.Graphics;
+import flash.display.GraphicsBitmapFill;
+import flash.display.GraphicsEndFill;
+import flash.display.GraphicsGradientFill;
+import flash.display.GraphicsPath;
+import flash.display.GraphicsSolidFill;
+import flash.display.GraphicsStroke;
+import flash.display.IGraphicsData;
+import flash.display.MovieClip;
+import flash.display.Shape;
+import flash.display.Sprite;
+import flash.geom.Matrix;
+import flash.utils.getQualifiedClassName;
+
+[SWF(width="200", height="100", backgroundColor="#FFFFFF")]
+public class Test extends MovieClip {
+    public function Test() {
+        var original:Sprite = new Sprite();
+        draw(original);
+        addChild(original);
+
+        trace("// readGraphicsData(false)");
+        dumpAll(original.graphics.readGraphicsData(false));
+
+        trace("// readGraphicsData()");
+        var data:Vector.<IGraphicsData> = original.graphics.readGraphicsData();
+        dumpAll(data);
+
+        // Drawing the data again should look the same as the original.
+        var copy:Shape = new Shape();
+        copy.x = 100;
+        copy.graphics.drawGraphicsData(data);
+        addChild(copy);
+
+        trace("// readGraphicsData() of the copy");
+        dumpAll(copy.graphics.readGraphicsData());
+
+        trace("// Rendering of the copy");
+        compare(original, copy);
+    }
+
+    // Traces the pixels of the copy that differ noticeably from the same pixels of the original.
+    private function compare(original:Sprite, copy:Shape):void {
+        var expected:BitmapData = new BitmapData(100, 100, true, 0);
+        expected.draw(original);
+        var actual:BitmapData = new BitmapData(100, 100, true, 0);
+        actual.draw(copy);
+
+        var differences:int = 0;
+        for (var y:int = 0; y < 100; y++) {
+            for (var x:int = 0; x < 100; x++) {
+                var a:uint = expected.getPixel32(x, y);
+                var b:uint = actual.getPixel32(x, y);
+                for (var shift:int = 0; shift < 32; shift += 8) {
+                    if (Math.abs(int((a >>> shift) & 0xFF) - int((b >>> shift) & 0xFF)) > 2) {
+                        trace("  (" + x + ", " + y + "): " + b.toString(16) + " instead of " + a.toString(16));
+                        differences++;
+                        break;
+                    }
+                }
+            }
+        }
+        trace("differing pixels: " + differences);
+    }
+
+    private function draw(sprite:Sprite):void {
+        var g:Graphics = sprite.graphics;
+
+        g.beginFill(0xFF0000, 0.5);
+        g.drawRect(5, 5, 40, 20);
+        g.endFill();
+
+        var gradientMatrix:Matrix = new Matrix();
+        gradientMatrix.createGradientBox(40, 40, 0, 50, 5);
+        g.lineStyle(2, 0x000000, 1, true, "normal", "round", "miter", 4);
+        g.beginGradientFill(GradientType.RADIAL, [0x00FF00, 0x0000FF], [1, 1], [0, 255], gradientMatrix);
+        g.moveTo(50, 25);
+        g.curveTo(50, 5, 70, 5);
+        g.lineTo(90, 45);
+        g.lineTo(50, 25);
+        g.endFill();
+        g.lineStyle();
+
+        var bitmap:BitmapData = new BitmapData(4, 4, false, 0xFFFF00);
+        bitmap.setPixel(0, 0, 0x000000);
+        g.beginBitmapFill(bitmap, new Matrix(4, 0, 0, 4), true, false);
+        g.drawRect(5, 30, 40, 20);
+        g.endFill();
+
+        var child:Shape = new Shape();
+        child.x = 50;
+        child.y = 50;
+        child.graphics.beginFill(0x00FFFF);
+        child.graphics.drawCircle(20, 20, 15);
+        child.graphics.endFill();
+        sprite.addChild(child);
+    }
+
+    private function dumpAll(data:Vector.<IGraphicsData>):void {
+        trace("length: " + data.length);
+        for each (var item:IGraphicsData in data) {
+            dump(item, "  ");
+        }
+        trace("");
+    }
+
+    private function matrixString(m:Matrix):String {
+        return m == null ? "null" : "(" + [m.a, m.b, m.c, m.d, m.tx, m.ty].join(", ") + ")";
+    }
+
+    private function dump(item:Object, indent:String):void {
+        trace(indent + getQualifiedClassName(item));
+        if (item is GraphicsSolidFill) {
+            trace(indent + "  color: " + item.color.toString(16) + ", alpha: " + item.alpha);
+        } else if (item is GraphicsGradientFill) {
+            trace(indent + "  type: " + item.type + ", spreadMethod: " + item.spreadMethod
+                + ", interpolationMethod: " + item.interpolationMethod + ", focalPointRatio: " + item.focalPointRatio);
+            trace(indent + "  colors: " + item.colors + ", alphas: " + item.alphas + ", ratios: " + item.ratios);
+            trace(indent + "  matrix: " + matrixString(item.matrix));
+        } else if (item is GraphicsBitmapFill) {
+            trace(indent + "  bitmapData: " + item.bitmapData.width + "x" + item.bitmapData.height
+                + ", pixel (0, 0): " + item.bitmapData.getPixel(0, 0).toString(16)
+                + ", pixel (1, 1): " + item.bitmapData.getPixel(1, 1).toString(16));
+            trace(indent + "  matrix: " + matrixString(item.matrix) + ", repeat: " + item.repeat + ", smooth: " + item.smooth);
+        } else if (item is GraphicsStroke) {
+            trace(indent + "  thickness: " + item.thickness + ", pixelHinting: " + item.pixelHinting + ", scaleMode: " + item.scaleMode);
+            trace(indent + "  caps: " + item.caps + ", joints: " + item.joints + ", miterLimit: " + item.miterLimit);
+            if (item.fill != null) {
+                dump(item.fill, indent + "  ");
+            }
+        } else if (item is GraphicsPath) {
+            trace(indent + "  winding: " + item.winding);
+            trace(indent + "  commands: " + item.commands);
+            trace(indent + "  data: " + item.data);
+        } else if (!(item is GraphicsEndFill)) {
+            trace(indent + "  (unexpected)");
+        }
+    }
+}
+}
@@ -0,0 +1,97 @@
+package {
+	import flash.display.BitmapData;
+	import flash.display.GraphicsPath;
+	import flash.display.GraphicsShaderFill;
+	import flash.display.IGraphicsData;
+	import flash.display.MovieClip;
+	import flash.display.Shader;
+	import flash.display.Shape;
+	import flash.geom.Matrix;
+	import flash.utils.ByteArray;
+
+	public class Test extends MovieClip {
+
+		// Compiled from coords.pbk
+		private static const COORDS_PBJ:Array = [
+			0xa5, 0x01, 0x00, 0x00, 0x00, 0xa4, 0x06, 0x00, 0x43, 0x6f, 0x6f, 0x72, 0x64, 0x73, 0xa0, 0x0c,
+			0x6e, 0x61, 0x6d, 0x65, 0x73, 0x70, 0x61, 0x63, 0x65, 0x00, 0x52, 0x75, 0x66, 0x66, 0x6c, 0x65,
+			0x00, 0xa0, 0x0c, 0x76, 0x65, 0x6e, 0x64, 0x6f, 0x72, 0x00, 0x52, 0x75, 0x66, 0x66, 0x6c, 0x65,
+			0x00, 0xa0, 0x08, 0x76, 0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x00, 0x01, 0x00, 0xa0, 0x0c, 0x64,
+			0x65, 0x73, 0x63, 0x72, 0x69, 0x70, 0x74, 0x69, 0x6f, 0x6e, 0x00, 0x4f, 0x75, 0x74, 0x70, 0x75,
+			0x74, 0x73, 0x20, 0x74, 0x68, 0x65, 0x20, 0x63, 0x6f, 0x6f, 0x72, 0x64, 0x69, 0x6e, 0x61, 0x74,
+			0x65, 0x20, 0x69, 0x6e, 0x20, 0x72, 0x65, 0x64, 0x20, 0x61, 0x6e, 0x64, 0x20, 0x67, 0x72, 0x65,
+			0x65, 0x6e, 0x2c, 0x20, 0x63, 0x65, 0x6e, 0x74, 0x65, 0x72, 0x65, 0x64, 0x20, 0x6f, 0x6e, 0x20,
+			0x74, 0x68, 0x65, 0x20, 0x6f, 0x72, 0x69, 0x67, 0x69, 0x6e, 0x2e, 0x00, 0xa1, 0x01, 0x02, 0x00,
+			0x00, 0x0c, 0x5f, 0x4f, 0x75, 0x74, 0x43, 0x6f, 0x6f, 0x72, 0x64, 0x00, 0xa1, 0x02, 0x04, 0x01,
+			0x00, 0x0f, 0x64, 0x73, 0x74, 0x00, 0x32, 0x02, 0x00, 0x80, 0x3c, 0x00, 0x00, 0x00, 0x32, 0x02,
+			0x00, 0x40, 0x3f, 0x00, 0x00, 0x00, 0x1d, 0x01, 0x00, 0xc1, 0x00, 0x00, 0x10, 0x00, 0x03, 0x01,
+			0x00, 0xc1, 0x02, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0xc1, 0x02, 0x00, 0x50, 0x00, 0x32, 0x01,
+			0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x32, 0x01, 0x00, 0x10, 0x3f, 0x80, 0x00, 0x00
+		];
+
+		public function Test() {
+			var bytes:ByteArray = new ByteArray();
+			for each (var byte:uint in COORDS_PBJ) {
+				bytes.writeByte(byte);
+			}
+			var shader:Shader = new Shader(bytes);
+
+			// Centered on the origin, so that three quarters of it is at negative shader coordinates.
+			var centered:Shape = new Shape();
+			centered.graphics.beginShaderFill(shader);
+			centered.graphics.drawRect(-40, -40, 80, 80);
+			centered.x = 50;
+			centered.y = 50;
+			addChild(centered);
+
+			// Shader coordinates are (local - 10) / 2.
+			var transformed:Shape = new Shape();
+			var path:GraphicsPath = new GraphicsPath();
+			path.moveTo(-50, -30);
+			path.lineTo(30, -30);
+			path.lineTo(30, 50);
+			path.lineTo(-50, 50);
+			path.lineTo(-50, -30);
+			transformed.graphics.drawGraphicsData(Vector.<IGraphicsData>([
+				new GraphicsShaderFill(shader, new Matrix(2, 0, 0, 2, 10, 10)),
+				path
+			]));
+			transformed.x = 150;
+			transformed.y = 50;
+			addChild(transformed);
+
+			trace("// beginShaderFill");
+			check(centered, new Matrix(), [[-35, -35], [-1, -1], [0, 0], [-20, 30], [30, -20], [35, 35]]);
+
+			trace("// GraphicsShaderFill");
+			check(transformed, new Matrix(0.5, 0, 0, 0.5, -5, -5), [[-45, -25], [-10, 0], [9, 9], [11, 11], [25, 45]]);
+		}
+
+		// Checks the pixels at the given local coordinates against the shader, with `toShader` mapping them to shader coordinates.
+		private function check(shape:Shape, toShader:Matrix, points:Array):void {
+			var bounds = shape.getBounds(shape);
+			var bitmap:BitmapData = new BitmapData(bounds.width, bounds.height, true, 0);
+			bitmap.draw(shape, new Matrix(1, 0, 0, 1, -bounds.x, -bounds.y));
+
+			for each (var point:Array in points) {
+				var x:int = point[0];
+				var y:int = point[1];
+				var coordX:Number = toShader.a * (x + 0.5) + toShader.tx;
+				var coordY:Number = toShader.d * (y + 0.5) + toShader.ty;
+				var expected:uint = 0xFF000000 | channel(coordX) << 16 | channel(coordY) << 8;
+				var actual:uint = bitmap.getPixel32(x - bounds.x, y - bounds.y);
+				var matches:Boolean = close(actual >> 24, expected >> 24) && close(actual >> 16, expected >> 16) &&
+					close(actual >> 8, expected >> 8) && close(actual, expected);
+				trace("(" + x + ", " + y + "): " + (matches ? "matches" : "0x" + actual.toString(16) + " instead of 0x" + expected.toString(16)));
+			}
+		}
+
+		private function channel(coord:Number):uint {
+			return Math.round(Math.max(0, Math.min(1, 0.5 + coord / 128)) * 255);
+		}
+
+		private function close(a:uint, b:uint):Boolean {
+			return Math.abs(int(a & 0xFF) - int(b & 0xFF)) <= 2;
+		}
+	}
+}
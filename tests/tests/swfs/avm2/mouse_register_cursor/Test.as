@@ -0,0 +1,60 @@
+package {
+  import flash.display.Sprite;
+  public class Test extends Sprite { }
+}
+
+import flash.display.BitmapData;
+import flash.geom.Point;
+import flash.ui.Mouse;
+import flash.ui.MouseCursor;
+import flash.ui.MouseCursorData;
+
+function attempt(name, fn) {
+    try {
+        fn();
+        trace(name + ": ok, Mouse.cursor = " + Mouse.cursor);
+    } catch (e) {
+        trace(name + " threw: " + e);
+    }
+}
+
+function cursorData(bitmaps) {
+    var data = new MouseCursorData();
+    if (bitmaps != null) {
+        data.data = Vector.<BitmapData>(bitmaps);
+    }
+    return data;
+}
+
+trace("Mouse.cursor: " + Mouse.cursor);
+
+attempt("cursor = ibeam", function() { Mouse.cursor = MouseCursor.IBEAM; });
+attempt("cursor = unknown", function() { Mouse.cursor = "unknown"; });
+attempt("cursor = null", function() { Mouse.cursor = null; });
+
+var data = cursorData([]);
+trace("default frameRate: " + data.frameRate + ", hotSpot: " + data.hotSpot);
+
+attempt("registerCursor() without data", function() { Mouse.registerCursor("custom", cursorData(null)); });
+attempt("registerCursor() with no bitmaps", function() { Mouse.registerCursor("custom", cursorData([])); });
+
+var disposed = new BitmapData(4, 4, true, 0xFFFF0000);
+disposed.dispose();
+attempt("registerCursor() with a disposed bitmap", function() { Mouse.registerCursor("custom", cursorData([disposed])); });
+attempt("registerCursor() with a null name", function() { Mouse.registerCursor(null, cursorData([new BitmapData(4, 4)])); });
+attempt("registerCursor() with null data", function() { Mouse.registerCursor("custom", null); });
+attempt("cursor = custom before registering", function() { Mouse.cursor = "custom"; });
+
+var frames = cursorData([new BitmapData(16, 16, true, 0xFFFF0000), new BitmapData(16, 16, true, 0x8000FF00)]);
+frames.hotSpot = new Point(3, 5);
+frames.frameRate = 10;
+trace("frameRate: " + frames.frameRate + ", hotSpot: " + frames.hotSpot);
+attempt("registerCursor()", function() { Mouse.registerCursor("custom", frames); });
+attempt("cursor = custom", function() { Mouse.cursor = "custom"; });
+attempt("registerCursor() again while in use", function() { Mouse.registerCursor("custom", cursorData([new BitmapData(8, 8)])); });
+attempt("registerCursor() with a builtin name", function() { Mouse.registerCursor(MouseCursor.ARROW, cursorData([new BitmapData(8, 8)])); });
+attempt("cursor = arrow", function() { Mouse.cursor = MouseCursor.ARROW; });
+attempt("cursor = custom", function() { Mouse.cursor = "custom"; });
+attempt("unregisterCursor() unknown", function() { Mouse.unregisterCursor("unknown"); });
+attempt("unregisterCursor() in use", function() { Mouse.unregisterCursor("custom"); });
+attempt("cursor = custom after unregistering", function() { Mouse.cursor = "custom"; });
@@ -0,0 +1,68 @@
+package {
+    import flash.display.MovieClip;
+    import flash.text.StaticText;
+    import flash.text.TextSnapshot;
+
+    public class Test extends MovieClip {
+        public function Test() {
+            var text:StaticText = getChildAt(0) as StaticText;
+            trace("// text.text");
+            trace(text.text);
+
+            var snapshot:TextSnapshot = this.textSnapshot;
+            trace("// snapshot.charCount");
+            trace(snapshot.charCount);
+            trace("// snapshot.getText(0, snapshot.charCount)");
+            trace(snapshot.getText(0, snapshot.charCount));
+            trace("// snapshot.getText(2, 5)");
+            trace(snapshot.getText(2, 5));
+            trace("// snapshot.getText(5, 2)");
+            trace(snapshot.getText(5, 2));
+
+            trace("// snapshot.findText(0, \"e\", true)");
+            trace(snapshot.findText(0, "e", true));
+            trace("// snapshot.findText(2, \"e\", true)");
+            trace(snapshot.findText(2, "e", true));
+            trace("// snapshot.findText(0, \"E\", true)");
+            trace(snapshot.findText(0, "E", true));
+            trace("// snapshot.findText(0, \"E\", false)");
+            trace(snapshot.findText(0, "E", false));
+            trace("// snapshot.findText(0, \"not there\", false)");
+            trace(snapshot.findText(0, "not there", false));
+
+            trace("// snapshot.getSelected(0, snapshot.charCount)");
+            trace(snapshot.getSelected(0, snapshot.charCount));
+            snapshot.setSelected(1, 4, true);
+            trace("// snapshot.setSelected(1, 4, true)");
+            trace("// snapshot.getSelected(0, 1)");
+            trace(snapshot.getSelected(0, 1));
+            trace("// snapshot.getSelected(0, 2)");
+            trace(snapshot.getSelected(0, 2));
+            trace("// snapshot.getSelectedText()");
+            trace(snapshot.getSelectedText());
+            snapshot.setSelected(2, 3, false);
+            trace("// snapshot.setSelected(2, 3, false)");
+            trace("// snapshot.getSelectedText(true)");
+            trace(snapshot.getSelectedText(true));
+
+            trace("// snapshot.getTextRunInfo(0, 2)");
+            var runs:Array = snapshot.getTextRunInfo(0, 2);
+            for each (var run:Object in runs) {
+                trace("indexInRun=" + run.indexInRun + " selected=" + run.selected + " font=" + run.font +
+                      " color=" + run.color.toString(16) + " height=" + Math.round(run.height) +
+                      " corner0x=" + Math.round(run.corner0x) + " corner0y=" + Math.round(run.corner0y) +
+                      " corner2x=" + Math.round(run.corner2x) + " corner2y=" + Math.round(run.corner2y));
+            }
+
+            var first:Object = runs[0];
+            var x:Number = (first.corner0x + first.corner2x) / 2;
+            var y:Number = (first.corner0y + first.corner2y) / 2;
+            trace("// snapshot.hitTestTextNearPos(<center of first char>, 0)");
+            trace(snapshot.hitTestTextNearPos(x, y, 0));
+            trace("// snapshot.hitTestTextNearPos(-1000, -1000, 0)");
+            trace(snapshot.hitTestTextNearPos(-1000, -1000, 0));
+            trace("// snapshot.hitTestTextNearPos(-1000, -1000, 100000)");
+            trace(snapshot.hitTestTextNearPos(-1000, -1000, 100000));
+        }
+    }
+}
@@ -0,0 +1,43 @@
+package {
+    import flash.display.MovieClip;
+    import flash.events.Event;
+    import flash.events.IMEEvent;
+    import flash.events.TextEvent;
+    import flash.system.IME;
+    import flash.system.System;
+    import flash.text.TextField;
+    import flash.text.TextFieldType;
+
+    public class Test extends MovieClip {
+        private var field:TextField;
+        private var frame:int = 0;
+
+        public function Test() {
+            field = new TextField();
+            field.type = TextFieldType.INPUT;
+            field.width = 200;
+            field.height = 20;
+            field.text = "A";
+            field.setSelection(1, 1);
+            addChild(field);
+            stage.focus = field;
+
+            field.addEventListener(IMEEvent.IME_START_COMPOSITION, function(e:IMEEvent):void {
+                trace("imeStartComposition: target=" + (e.target == field) + ", text=" + field.text);
+            });
+            System.ime.addEventListener(IMEEvent.IME_COMPOSITION, function(e:IMEEvent):void {
+                trace("imeComposition: text=" + e.text + ", field.text=" + field.text);
+            });
+            field.addEventListener(TextEvent.TEXT_INPUT, function(e:TextEvent):void {
+                trace("textInput: " + e.text);
+            });
+            field.addEventListener(Event.CHANGE, function(e:Event):void {
+                trace("change: " + field.text);
+            });
+            addEventListener(Event.ENTER_FRAME, function(e:Event):void {
+                frame++;
+                trace("// frame " + frame + ": text=" + field.text + ", caret=" + field.caretIndex);
+            });
+        }
+    }
+}
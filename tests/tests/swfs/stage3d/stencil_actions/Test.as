@@ -0,0 +1,170 @@
+package {
+    import com.adobe.utils.AGALMiniAssembler;
+
+    import flash.display.MovieClip;
+    import flash.display.Stage3D;
+    import flash.display3D.Context3D;
+    import flash.display3D.Context3DCompareMode;
+    import flash.display3D.Context3DProgramType;
+    import flash.display3D.Context3DRenderMode;
+    import flash.display3D.Context3DStencilAction;
+    import flash.display3D.Context3DTriangleFace;
+    import flash.display3D.Context3DVertexBufferFormat;
+    import flash.display3D.IndexBuffer3D;
+    import flash.display3D.Program3D;
+    import flash.display3D.VertexBuffer3D;
+    import flash.events.Event;
+
+    // Builds up a stencil buffer with quads drawn without color, then draws
+    // quads that only show where the stencil test passes:
+    //
+    // - the left half is set to 1, then the top half is incremented, leaving
+    //   2 top left, 1 top right and bottom left, and 0 bottom right
+    // - red is drawn where the stencil is 1, green where it is 2
+    // - blue is drawn in the bottom right with a reference value of 1 and a
+    //   read mask of 0xFE, which matches the 0 there
+    //
+    // So the back buffer ends up green top left, red top right and bottom
+    // left, and blue bottom right.
+    //
+    // Every stencil call is traced along with its result, including calls
+    // with values that aren't accepted.
+    public class Test extends MovieClip {
+        private var renderContext:Context3D;
+        private var indexList:IndexBuffer3D;
+
+        private const VERTEX_SHADER:String =
+            "mov op, va0    \n" +
+            "mov v0, va1";
+
+        private const FRAGMENT_SHADER:String =
+            "mov oc, v0";
+
+        public function Test() {
+            var stage3D:Stage3D = this.stage.stage3Ds[0];
+            stage3D.addEventListener(Event.CONTEXT3D_CREATE, contextCreated);
+            stage3D.requestContext3D(Context3DRenderMode.AUTO, "standard");
+        }
+
+        private function contextCreated(event:Event):void {
+            renderContext = Stage3D(event.target).context3D;
+            renderContext.enableErrorChecking = true;
+            renderContext.configureBackBuffer(100, 100, 0, true);
+
+            var vertexData:Vector.<Number> = Vector.<Number>([
+                // x, y, z, r, g, b
+                // Left half
+                -1, 1, 0, 1, 1, 1,
+                0, 1, 0, 1, 1, 1,
+                -1, -1, 0, 1, 1, 1,
+                0, -1, 0, 1, 1, 1,
+                // Top half
+                -1, 1, 0, 1, 1, 1,
+                1, 1, 0, 1, 1, 1,
+                -1, 0, 0, 1, 1, 1,
+                1, 0, 0, 1, 1, 1,
+                // Everything, red
+                -1, 1, 0, 1, 0, 0,
+                1, 1, 0, 1, 0, 0,
+                -1, -1, 0, 1, 0, 0,
+                1, -1, 0, 1, 0, 0,
+                // Everything, green
+                -1, 1, 0, 0, 1, 0,
+                1, 1, 0, 0, 1, 0,
+                -1, -1, 0, 0, 1, 0,
+                1, -1, 0, 0, 1, 0,
+                // Bottom right, blue
+                0, 0, 0, 0, 0, 1,
+                1, 0, 0, 0, 0, 1,
+                0, -1, 0, 0, 0, 1,
+                1, -1, 0, 0, 0, 1
+            ]);
+            var vertexes:VertexBuffer3D = renderContext.createVertexBuffer(20, 6);
+            vertexes.uploadFromVector(vertexData, 0, 20);
+            renderContext.setVertexBufferAt(0, vertexes, 0, Context3DVertexBufferFormat.FLOAT_3);
+            renderContext.setVertexBufferAt(1, vertexes, 3, Context3DVertexBufferFormat.FLOAT_3);
+
+            var indices:Vector.<uint> = new Vector.<uint>();
+            for (var quad:uint = 0; quad < 5; quad++) {
+                var first:uint = quad * 4;
+                indices.push(first, first + 1, first + 2, first + 1, first + 3, first + 2);
+            }
+            indexList = renderContext.createIndexBuffer(indices.length);
+            indexList.uploadFromVector(indices, 0, indices.length);
+
+            var vertexAssembly:AGALMiniAssembler = new AGALMiniAssembler();
+            var fragmentAssembly:AGALMiniAssembler = new AGALMiniAssembler();
+            vertexAssembly.assemble(Context3DProgramType.VERTEX, VERTEX_SHADER);
+            fragmentAssembly.assemble(Context3DProgramType.FRAGMENT, FRAGMENT_SHADER);
+            var program:Program3D = renderContext.createProgram();
+            program.upload(vertexAssembly.agalcode, fragmentAssembly.agalcode);
+            renderContext.setProgram(program);
+            renderContext.setCulling(Context3DTriangleFace.NONE);
+
+            renderContext.clear(0, 0, 0, 1);
+
+            // Fill the stencil buffer without drawing anything.
+            renderContext.setColorMask(false, false, false, false);
+            setStencilReferenceValue(1);
+            setStencilActions(Context3DTriangleFace.FRONT_AND_BACK,
+                Context3DCompareMode.ALWAYS, Context3DStencilAction.SET);
+            drawQuad(0);
+            setStencilActions(Context3DTriangleFace.FRONT_AND_BACK,
+                Context3DCompareMode.ALWAYS, Context3DStencilAction.INCREMENT_SATURATE);
+            drawQuad(1);
+
+            // Values that aren't accepted don't change the stencil state.
+            setStencilActions("sideways", Context3DCompareMode.NEVER);
+            setStencilActions(Context3DTriangleFace.FRONT_AND_BACK, "sometimes");
+            setStencilActions(Context3DTriangleFace.FRONT_AND_BACK,
+                Context3DCompareMode.NEVER, "explode");
+            setStencilActions(Context3DTriangleFace.FRONT_AND_BACK,
+                Context3DCompareMode.NEVER, Context3DStencilAction.KEEP, "explode");
+            setStencilActions(Context3DTriangleFace.FRONT_AND_BACK,
+                Context3DCompareMode.NEVER, Context3DStencilAction.KEEP,
+                Context3DStencilAction.KEEP, "explode");
+
+            // Draw through the stencil buffer without changing it.
+            renderContext.setColorMask(true, true, true, true);
+            setStencilActions(Context3DTriangleFace.FRONT_AND_BACK,
+                Context3DCompareMode.EQUAL, Context3DStencilAction.KEEP);
+            setStencilReferenceValue(1);
+            drawQuad(2);
+            setStencilReferenceValue(2);
+            drawQuad(3);
+            setStencilReferenceValue(1, 0xFE);
+            drawQuad(4);
+
+            renderContext.present();
+        }
+
+        private function setStencilActions(triangleFace:String, compareMode:String,
+                actionOnBothPass:String = "keep", actionOnDepthFail:String = "keep",
+                actionOnDepthPassStencilFail:String = "keep"):void {
+            var call:String = "setStencilActions(" + [triangleFace, compareMode, actionOnBothPass,
+                actionOnDepthFail, actionOnDepthPassStencilFail].join(", ") + ")";
+            try {
+                renderContext.setStencilActions(triangleFace, compareMode, actionOnBothPass,
+                    actionOnDepthFail, actionOnDepthPassStencilFail);
+                trace(call + ": ok");
+            } catch (e:Error) {
+                trace(call + ": " + e);
+            }
+        }
+
+        private function setStencilReferenceValue(referenceValue:uint, readMask:uint = 255,
+                writeMask:uint = 255):void {
+            var call:String = "setStencilReferenceValue(" + [referenceValue, readMask, writeMask].join(", ") + ")";
+            try {
+                renderContext.setStencilReferenceValue(referenceValue, readMask, writeMask);
+                trace(call + ": ok");
+            } catch (e:Error) {
+                trace(call + ": " + e);
+            }
+        }
+
+        private function drawQuad(quad:int):void {
+            renderContext.drawTriangles(indexList, quad * 6, 2);
+        }
+    }
+}
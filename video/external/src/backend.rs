@@ -26,6 +26,9 @@ enum ProxyOrStream {
 
 /// A video backend that falls back to the software backend for most codecs,
 /// except for H.264, for which it uses an external decoder.
+///
+/// With the `h264` feature, H.264 also falls back to the software backend
+/// when OpenH264 isn't available.
 pub struct ExternalVideoBackend {
     streams: SlotMap<VideoStreamHandle, ProxyOrStream>,
     openh264_lib_filepath: Option<PathBuf>,
@@ -141,17 +144,19 @@ impl VideoBackend for ExternalVideoBackend {
         codec: VideoCodec,
         filter: VideoDeblocking,
     ) -> Result<VideoStreamHandle, Error> {
-        let proxy_or_stream = if codec == VideoCodec::H264 {
-            let openh264 = &self.openh264_lib_filepath;
-            if let Some(openh264) = openh264 {
-                tracing::info!("Using OpenH264 at {:?}", openh264);
-                let decoder = Box::new(crate::decoder::openh264::H264Decoder::new(openh264));
-                let stream = VideoStream::new(decoder);
-                ProxyOrStream::Owned(stream)
-            } else {
-                return Err(Error::DecoderError("No OpenH264".into()));
-            }
+        let openh264 = self
+            .openh264_lib_filepath
+            .as_ref()
+            .filter(|_| codec == VideoCodec::H264);
+        let proxy_or_stream = if let Some(openh264) = openh264 {
+            tracing::info!("Using OpenH264 at {:?}", openh264);
+            let decoder = Box::new(crate::decoder::openh264::H264Decoder::new(openh264));
+            let stream = VideoStream::new(decoder);
+            ProxyOrStream::Owned(stream)
+        } else if codec == VideoCodec::H264 && !cfg!(feature = "h264") {
+            return Err(Error::DecoderError("No OpenH264".into()));
         } else {
+            // Without OpenH264, H.264 falls back to the built-in software decoder.
             ProxyOrStream::Proxied(
                 self.software
                     .register_video_stream(num_frames, size, codec, filter)?,
@@ -251,3 +256,86 @@ impl VideoStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ExternalVideoBackend;
+    use ruffle_video::backend::VideoBackend;
+    use swf::{VideoCodec, VideoDeblocking};
+
+    #[test]
+    #[cfg(not(feature = "h264"))]
+    fn h264_needs_openh264() {
+        let mut backend = ExternalVideoBackend::new(None);
+        assert!(backend
+            .register_video_stream(
+                1,
+                (16, 16),
+                VideoCodec::H264,
+                VideoDeblocking::UseVideoPacketValue
+            )
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "h264")]
+    fn h264_falls_back_to_software_without_openh264() {
+        use flv_rs::{FlvReader, Header, Tag, TagData, VideoPacket};
+        use ruffle_render::backend::null::NullRenderer;
+        use ruffle_render::backend::ViewportDimensions;
+        use ruffle_video::frame::EncodedFrame;
+
+        let mut backend = ExternalVideoBackend::new(None);
+        let mut renderer = NullRenderer::new(ViewportDimensions {
+            width: 256,
+            height: 160,
+            scale_factor: 1.0,
+        });
+        let stream = backend
+            .register_video_stream(
+                9,
+                (256, 160),
+                VideoCodec::H264,
+                VideoDeblocking::UseVideoPacketValue,
+            )
+            .unwrap();
+
+        let mut reader = FlvReader::from_source(include_bytes!(
+            "../../software/src/decoder/h264/test-assets/hsv.flv"
+        ));
+        Header::parse(&mut reader).unwrap();
+        let mut frames = Vec::new();
+        while let Ok(tag) = Tag::parse(&mut reader) {
+            let TagData::Video(video) = tag.data else {
+                continue;
+            };
+            match video.data {
+                VideoPacket::AvcSequenceHeader(data) => backend
+                    .configure_video_stream_decoder(stream, data)
+                    .unwrap(),
+                VideoPacket::AvcNalu { data, .. } => {
+                    let frame = || EncodedFrame {
+                        codec: VideoCodec::H264,
+                        data,
+                        frame_id: frames.len() as u32,
+                    };
+                    let decoded = backend
+                        .preload_video_stream_frame(stream, frame())
+                        .and_then(|_| {
+                            backend.decode_video_stream_frame(stream, frame(), &mut renderer)
+                        });
+                    frames.push(decoded);
+                }
+                _ => {}
+            }
+        }
+
+        // The stream reorders by two frames, so the first two packets don't produce any picture.
+        assert_eq!(frames.len(), 9);
+        assert!(frames[..2].iter().all(Result::is_err));
+        for frame in &frames[2..] {
+            let frame = frame.as_ref().unwrap();
+            assert_eq!((frame.width, frame.height), (256, 160));
+        }
+    }
+}
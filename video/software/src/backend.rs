@@ -40,12 +40,20 @@ impl VideoBackend for SoftwareVideoBackend {
         let decoder: Box<dyn VideoDecoder> = match codec {
             #[cfg(feature = "h263")]
             VideoCodec::H263 => Box::new(crate::decoder::h263::H263Decoder::new(filter)),
+            #[cfg(feature = "h264")]
+            VideoCodec::H264 => Box::new(crate::decoder::h264::H264Decoder::new()),
             #[cfg(feature = "vp6")]
             VideoCodec::Vp6 => Box::new(crate::decoder::vp6::Vp6Decoder::new(false, size)),
             #[cfg(feature = "vp6")]
             VideoCodec::Vp6WithAlpha => Box::new(crate::decoder::vp6::Vp6Decoder::new(true, size)),
             #[cfg(feature = "screenvideo")]
-            VideoCodec::ScreenVideo => Box::new(crate::decoder::screen::ScreenVideoDecoder::new()),
+            VideoCodec::ScreenVideo => {
+                Box::new(crate::decoder::screen::ScreenVideoDecoder::new(false))
+            }
+            #[cfg(feature = "screenvideo")]
+            VideoCodec::ScreenVideoV2 => {
+                Box::new(crate::decoder::screen::ScreenVideoDecoder::new(true))
+            }
             other => return Err(Error::UnsupportedCodec(other)),
         };
         let stream = VideoStream::new(decoder);
@@ -68,11 +76,14 @@ impl VideoBackend for SoftwareVideoBackend {
 
     fn configure_video_stream_decoder(
         &mut self,
-        _stream: VideoStreamHandle,
-        _configuration_data: &[u8],
+        stream: VideoStreamHandle,
+        configuration_data: &[u8],
     ) -> Result<(), Error> {
-        // None of the software decoders require configuration.
-        Ok(())
+        let stream = self
+            .streams
+            .get_mut(stream)
+            .ok_or(Error::VideoStreamIsNotRegistered)?;
+        stream.decoder.configure_decoder(configuration_data)
     }
 
     fn decode_video_stream_frame(
@@ -4,6 +4,9 @@ use ruffle_video::frame::{DecodedFrame, EncodedFrame, FrameDependency};
 #[cfg(feature = "h263")]
 pub mod h263;
 
+#[cfg(feature = "h264")]
+pub mod h264;
+
 #[cfg(feature = "vp6")]
 pub mod vp6;
 
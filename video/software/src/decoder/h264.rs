@@ -0,0 +1,771 @@
+//! A decoder for the progressive 8-bit 4:2:0 subset of H.264 (ITU-T H.264 / ISO/IEC 14496-10),
+//! which covers the Constrained Baseline, Main and High profiles as Flash Player uses them.
+
+mod bitstream;
+mod cabac;
+mod cabac_tables;
+mod cavlc;
+mod deblock;
+mod dpb;
+mod inter;
+mod intra;
+mod macroblock;
+mod motion;
+mod params;
+mod picture;
+mod poc;
+mod slice;
+mod tables;
+mod transform;
+
+use crate::decoder::VideoDecoder;
+use ruffle_render::bitmap::BitmapFormat;
+use ruffle_video::error::Error;
+use ruffle_video::frame::{DecodedFrame, EncodedFrame, FrameDependency};
+use std::rc::Rc;
+
+use bitstream::BitReader;
+use cabac::CabacReader;
+use cavlc::CavlcReader;
+use dpb::{Dpb, PicNumContext, RefPic};
+use macroblock::SliceDecoder;
+use params::{Pps, Sps};
+use picture::Picture;
+use poc::{Poc, PocState};
+use slice::{Mmco, SliceHeader, SliceType};
+
+#[derive(thiserror::Error, Debug)]
+pub enum H264Error {
+    #[error("Unexpected end of file")]
+    UnexpectedEof,
+
+    #[error("Invalid data: {0}")]
+    InvalidData(&'static str),
+
+    #[error("Unsupported feature: {0}")]
+    Unsupported(&'static str),
+
+    #[error("Missing parameter set")]
+    MissingParameterSet,
+
+    #[error("Missing reference frame")]
+    MissingReferenceFrame,
+
+    #[error("Decoder not configured")]
+    NotConfigured,
+
+    #[error("No output frame produced by the decoder")]
+    NoOutputFrame,
+}
+
+impl From<H264Error> for Error {
+    fn from(error: H264Error) -> Self {
+        Error::DecoderError(Box::new(error))
+    }
+}
+
+const NAL_SLICE: u8 = 1;
+const NAL_SLICE_IDR: u8 = 5;
+const NAL_SPS: u8 = 7;
+const NAL_PPS: u8 = 8;
+
+/// The picture currently being decoded, with what is needed to finish it.
+struct CurrentPicture {
+    pic: Picture,
+    /// The header of the first slice of the picture.
+    header: SliceHeader,
+    sps: Rc<Sps>,
+    poc: Poc,
+}
+
+/// H.264 video decoder.
+pub struct H264Decoder {
+    /// The size of the length prefix of each NAL unit, or 0 if not configured yet.
+    length_size: usize,
+    sps: Vec<Option<Rc<Sps>>>,
+    pps: Vec<Option<Rc<Pps>>>,
+    dpb: Dpb,
+    poc_state: PocState,
+    current: Option<CurrentPicture>,
+    /// The `frame_num` of the last reference picture, for detecting gaps.
+    prev_ref_frame_num: u32,
+    next_picture_id: i32,
+    /// The picture returned most recently, which is shown again while
+    /// the decoder is still filling its reordering buffer.
+    last_output: Option<Rc<Picture>>,
+}
+
+impl H264Decoder {
+    pub fn new() -> Self {
+        Self {
+            length_size: 0,
+            sps: vec![None; 32],
+            pps: vec![None; 256],
+            dpb: Dpb::default(),
+            poc_state: PocState::default(),
+            current: None,
+            prev_ref_frame_num: 0,
+            next_picture_id: 0,
+            last_output: None,
+        }
+    }
+
+    /// Splits a packet into its length-prefixed NAL units.
+    fn nal_units<'a>(&self, mut data: &'a [u8]) -> Result<Vec<&'a [u8]>, H264Error> {
+        if self.length_size == 0 {
+            return Err(H264Error::NotConfigured);
+        }
+        let mut units = Vec::new();
+        while !data.is_empty() {
+            if data.len() < self.length_size {
+                return Err(H264Error::UnexpectedEof);
+            }
+            let length = data[..self.length_size]
+                .iter()
+                .fold(0, |length, &byte| (length << 8) | usize::from(byte));
+            data = &data[self.length_size..];
+            if data.len() < length {
+                return Err(H264Error::UnexpectedEof);
+            }
+            if length > 0 {
+                units.push(&data[..length]);
+            }
+            data = &data[length..];
+        }
+        Ok(units)
+    }
+
+    fn parse_sps(&mut self, nal: &[u8]) -> Result<(), H264Error> {
+        let rbsp = bitstream::unescape(&nal[1..]);
+        let sps = Sps::parse(&mut BitReader::new(&rbsp))?;
+        let id = sps.id as usize;
+        self.sps[id] = Some(Rc::new(sps));
+        Ok(())
+    }
+
+    fn parse_pps(&mut self, nal: &[u8]) -> Result<(), H264Error> {
+        let rbsp = bitstream::unescape(&nal[1..]);
+        let pps = Pps::parse(&mut BitReader::new(&rbsp), &self.sps)?;
+        let id = pps.id as usize;
+        self.pps[id] = Some(Rc::new(pps));
+        Ok(())
+    }
+
+    fn allocate_picture(&mut self, poc: i32, sps: &Sps) -> Picture {
+        self.next_picture_id = self.next_picture_id.wrapping_add(1) & i32::MAX;
+        let mut pic = Picture::new(self.next_picture_id, poc, sps.width_mbs, sps.height_mbs);
+        pic.crop = sps.crop;
+        pic
+    }
+
+    /// Decodes a slice NAL unit, finishing the previous picture first if the slice starts a new one.
+    fn decode_slice(&mut self, nal: &[u8], output: &mut Vec<Rc<Picture>>) -> Result<(), H264Error> {
+        let nal_ref_idc = (nal[0] >> 5) & 3;
+        let nal_unit_type = nal[0] & 0x1f;
+        let rbsp = bitstream::unescape(&nal[1..]);
+        let mut reader = BitReader::new(&rbsp);
+        let (header, sps, pps) = SliceHeader::parse(
+            &mut reader,
+            nal_unit_type,
+            nal_ref_idc,
+            &self.sps,
+            &self.pps,
+        )?;
+        if header.redundant_pic_cnt > 0 {
+            return Ok(());
+        }
+
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|current| !current.header.is_same_picture(&header))
+        {
+            output.extend(self.finish_picture());
+        }
+        if self.current.is_none() {
+            self.start_picture(&header, sps.clone());
+        }
+        let current = self.current.as_mut().expect("A picture was just started");
+        if current.sps.width_mbs != sps.width_mbs || current.sps.height_mbs != sps.height_mbs {
+            return Err(H264Error::InvalidData("slices of a picture differ in size"));
+        }
+
+        let ctx = PicNumContext {
+            frame_num: header.frame_num,
+            max_frame_num: sps.max_frame_num(),
+        };
+        let mut ref_lists: [Vec<RefPic>; 2] = match header.slice_type {
+            SliceType::I => [Vec::new(), Vec::new()],
+            SliceType::P => [self.dpb.initial_list_p(ctx), Vec::new()],
+            SliceType::B => self.dpb.initial_lists_b(current.pic.poc),
+        };
+        let num_lists = match header.slice_type {
+            SliceType::I => 0,
+            SliceType::P => 1,
+            SliceType::B => 2,
+        };
+        for (list, ref_list) in ref_lists.iter_mut().enumerate().take(num_lists) {
+            self.dpb.modify_list(
+                ref_list,
+                &header.ref_pic_list_modification[list],
+                header.num_ref_idx_active[list],
+                ctx,
+            )?;
+        }
+
+        current.pic.slices.push(header.deblock);
+        let slice_num = current.pic.slices.len() as u32;
+        let mut decoder =
+            SliceDecoder::new(&sps, &pps, &header, &mut current.pic, &ref_lists, slice_num);
+        if pps.entropy_coding_mode {
+            let mut cabac =
+                CabacReader::new(reader, header.slice_type, header.cabac_init_idc, header.qp)?;
+            decoder.decode(&mut cabac)
+        } else {
+            decoder.decode(&mut CavlcReader::new(reader))
+        }
+    }
+
+    fn start_picture(&mut self, header: &SliceHeader, sps: Rc<Sps>) {
+        if header.is_idr {
+            self.prev_ref_frame_num = 0;
+            self.dpb.start_new_epoch(header.no_output_of_prior_pics);
+        } else {
+            self.fill_frame_num_gap(header, &sps);
+        }
+        let poc = self.poc_state.compute(&sps, header);
+        let pic = self.allocate_picture(poc.value(), &sps);
+        self.current = Some(CurrentPicture {
+            pic,
+            header: header.clone(),
+            sps,
+            poc,
+        });
+    }
+
+    /// Inserts "non-existing" reference frames for frames missing from the stream,
+    /// as described in 8.2.5.2.
+    fn fill_frame_num_gap(&mut self, header: &SliceHeader, sps: &Sps) {
+        let max_frame_num = sps.max_frame_num();
+        let expected = (self.prev_ref_frame_num + 1) % max_frame_num;
+        if header.frame_num == self.prev_ref_frame_num || header.frame_num == expected {
+            return;
+        }
+        if !sps.gaps_in_frame_num_allowed {
+            log::warn!(
+                "H.264 stream skips from frame_num {} to {}",
+                self.prev_ref_frame_num,
+                header.frame_num
+            );
+        }
+        let mut frame_num = expected;
+        while frame_num != header.frame_num {
+            let pic = self.allocate_picture(0, sps);
+            let ctx = PicNumContext {
+                frame_num,
+                max_frame_num,
+            };
+            self.dpb
+                .add_gap_frame(Rc::new(pic), ctx, sps.max_num_ref_frames as usize);
+            self.poc_state.skip_frame(sps, frame_num);
+            self.prev_ref_frame_num = frame_num;
+            frame_num = (frame_num + 1) % max_frame_num;
+        }
+    }
+
+    /// Finishes decoding the current picture, returning the pictures that are due for output.
+    fn finish_picture(&mut self) -> Vec<Rc<Picture>> {
+        let Some(CurrentPicture {
+            mut pic,
+            header,
+            sps,
+            mut poc,
+        }) = self.current.take()
+        else {
+            return Vec::new();
+        };
+        deblock::deblock_picture(&mut pic);
+
+        let had_mmco5 = header
+            .mmcos
+            .as_ref()
+            .is_some_and(|mmcos| mmcos.iter().any(|mmco| matches!(mmco, Mmco::ForgetAll)));
+        self.poc_state.update(&header, &mut poc, had_mmco5);
+        if had_mmco5 {
+            pic.poc = poc.value();
+            self.dpb.start_new_epoch(false);
+        }
+
+        let pic = Rc::new(pic);
+        if header.nal_ref_idc != 0 {
+            if header.is_idr {
+                self.dpb.mark_idr(pic.clone(), header.long_term_reference);
+            } else {
+                let ctx = PicNumContext {
+                    frame_num: header.frame_num,
+                    max_frame_num: sps.max_frame_num(),
+                };
+                self.dpb.mark_reference(
+                    pic.clone(),
+                    header.mmcos.as_deref(),
+                    ctx,
+                    sps.max_num_ref_frames as usize,
+                );
+            }
+            self.prev_ref_frame_num = if had_mmco5 { 0 } else { header.frame_num };
+        }
+        self.dpb
+            .push_output(pic, sps.max_reorder_frames(), sps.dpb_size())
+    }
+}
+
+/// Converts a decoded picture to a cropped YUV 4:2:0 frame.
+fn to_decoded_frame(pic: &Picture) -> DecodedFrame {
+    let [left, right, top, bottom] = pic.crop;
+    let width = pic.luma.width - left - right;
+    let height = pic.luma.height - top - bottom;
+    let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+    let mut yuv = Vec::with_capacity(width * height + 2 * chroma_width * chroma_height);
+    for row in pic.luma.data.chunks(pic.luma.width).skip(top).take(height) {
+        yuv.extend_from_slice(&row[left..left + width]);
+    }
+    for plane in [&pic.cb, &pic.cr] {
+        for row in plane
+            .data
+            .chunks(plane.width)
+            .skip(top / 2)
+            .take(chroma_height)
+        {
+            yuv.extend_from_slice(&row[left / 2..left / 2 + chroma_width]);
+        }
+    }
+    DecodedFrame::new(width as u32, height as u32, BitmapFormat::Yuv420p, yuv)
+}
+
+impl Default for H264Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VideoDecoder for H264Decoder {
+    fn configure_decoder(&mut self, configuration_data: &[u8]) -> Result<(), Error> {
+        // This is an `AVCDecoderConfigurationRecord`, as described in ISO/IEC 14496-15.
+        let data = configuration_data;
+        if data.len() < 6 {
+            return Err(H264Error::UnexpectedEof.into());
+        }
+        if data[0] != 1 {
+            return Err(H264Error::InvalidData("configuration version").into());
+        }
+        self.length_size = usize::from(data[4] & 0b11) + 1;
+
+        let mut pos = 5;
+        let mut read_parameter_sets = |count_mask: u8| -> Result<Vec<&[u8]>, H264Error> {
+            let count = data.get(pos).ok_or(H264Error::UnexpectedEof)? & count_mask;
+            pos += 1;
+            let mut sets = Vec::new();
+            for _ in 0..count {
+                let length_bytes = data.get(pos..pos + 2).ok_or(H264Error::UnexpectedEof)?;
+                let length = usize::from(u16::from_be_bytes([length_bytes[0], length_bytes[1]]));
+                pos += 2;
+                sets.push(
+                    data.get(pos..pos + length)
+                        .ok_or(H264Error::UnexpectedEof)?,
+                );
+                pos += length;
+            }
+            Ok(sets)
+        };
+        let sps_units = read_parameter_sets(0x1f)?;
+        let pps_units = read_parameter_sets(0xff)?;
+        for sps in sps_units.into_iter().filter(|nal| !nal.is_empty()) {
+            self.parse_sps(sps)?;
+        }
+        for pps in pps_units.into_iter().filter(|nal| !nal.is_empty()) {
+            self.parse_pps(pps)?;
+        }
+        Ok(())
+    }
+
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        let is_idr = self
+            .nal_units(encoded_frame.data)?
+            .iter()
+            .map(|nal| nal[0] & 0x1f)
+            .find(|&nal_unit_type| nal_unit_type == NAL_SLICE || nal_unit_type == NAL_SLICE_IDR)
+            == Some(NAL_SLICE_IDR);
+
+        // 3.62 instantaneous decoding refresh (IDR) picture:
+        // After the decoding of an IDR picture all following coded pictures in decoding order can
+        // be decoded without inter prediction from any picture decoded prior to the IDR picture.
+        if is_idr {
+            Ok(FrameDependency::None)
+        } else {
+            Ok(FrameDependency::Past)
+        }
+    }
+
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        let mut output = Vec::new();
+        for nal in self.nal_units(encoded_frame.data)? {
+            if nal[0] & 0x80 != 0 {
+                return Err(H264Error::InvalidData("forbidden_zero_bit").into());
+            }
+            match nal[0] & 0x1f {
+                NAL_SPS => self.parse_sps(nal)?,
+                NAL_PPS => self.parse_pps(nal)?,
+                NAL_SLICE | NAL_SLICE_IDR => {
+                    if let Err(error) = self.decode_slice(nal, &mut output) {
+                        // Keep what was decoded of the picture, as a broken slice
+                        // shouldn't take the rest of the stream down with it.
+                        if self.current.is_none() {
+                            return Err(error.into());
+                        }
+                        log::warn!("Error while decoding H.264 slice: {error}");
+                    }
+                }
+                // Other NAL units, such as SEI and access unit delimiters, don't affect decoding.
+                _ => {}
+            }
+        }
+        // Each video packet holds exactly one picture.
+        output.extend(self.finish_picture());
+
+        if let Some(pic) = output.pop() {
+            self.last_output = Some(pic);
+        }
+        match &self.last_output {
+            Some(pic) => Ok(to_decoded_frame(pic)),
+            None => Err(H264Error::NoOutputFrame.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::H264Decoder;
+    use crate::decoder::VideoDecoder;
+    use flv_rs::{FlvReader, Header, Tag, TagData, VideoPacket};
+    use ruffle_render::bitmap::BitmapFormat;
+    use ruffle_video::error::Error;
+    use ruffle_video::frame::{DecodedFrame, EncodedFrame};
+    use swf::VideoCodec;
+
+    fn decode(
+        decoder: &mut H264Decoder,
+        data: &[u8],
+        frame_id: u32,
+    ) -> Result<DecodedFrame, Error> {
+        let frame = || EncodedFrame {
+            codec: VideoCodec::H264,
+            data,
+            frame_id,
+        };
+        decoder.preload_frame(frame())?;
+        decoder.decode_frame(frame())
+    }
+
+    fn psnr(a: &[u8], b: &[u8]) -> f64 {
+        assert_eq!(a.len(), b.len());
+        let mse = a
+            .iter()
+            .zip(b)
+            .map(|(&a, &b)| (f64::from(a) - f64::from(b)).powi(2))
+            .sum::<f64>()
+            / a.len() as f64;
+        10.0 * (255.0 * 255.0 / mse).log10()
+    }
+
+    /// A High profile CABAC stream with B-frames, encoded from the frames in `test-assets`.
+    #[test]
+    fn cabac_flv_matches_source_frames() {
+        let source_frames: Vec<Vec<u8>> = [
+            include_bytes!("h264/test-assets/source-0.png").as_slice(),
+            include_bytes!("h264/test-assets/source-1.png"),
+            include_bytes!("h264/test-assets/source-2.png"),
+            include_bytes!("h264/test-assets/source-3.png"),
+            include_bytes!("h264/test-assets/source-4.png"),
+            include_bytes!("h264/test-assets/source-5.png"),
+            include_bytes!("h264/test-assets/source-6.png"),
+        ]
+        .iter()
+        .map(|png| image::load_from_memory(png).unwrap().to_rgb8().into_raw())
+        .collect();
+
+        let mut reader = FlvReader::from_source(include_bytes!("h264/test-assets/hsv.flv"));
+        Header::parse(&mut reader).unwrap();
+        let mut decoder = H264Decoder::new();
+        let mut frames = Vec::new();
+        while let Ok(tag) = Tag::parse(&mut reader) {
+            let TagData::Video(video) = tag.data else {
+                continue;
+            };
+            match video.data {
+                VideoPacket::AvcSequenceHeader(data) => decoder.configure_decoder(data).unwrap(),
+                VideoPacket::AvcNalu { data, .. } => {
+                    frames.push(decode(&mut decoder, data, frames.len() as u32));
+                }
+                _ => {}
+            }
+        }
+        assert!(decoder.pps[0].as_ref().unwrap().entropy_coding_mode);
+
+        // The stream reorders by two frames, so the first two packets don't produce any picture.
+        assert_eq!(frames.len(), source_frames.len() + 2);
+        assert!(frames[..2].iter().all(Result::is_err));
+        for (i, (frame, source)) in frames[2..].iter().zip(&source_frames).enumerate() {
+            let frame = frame.as_ref().unwrap().clone().to_rgb();
+            assert_eq!((frame.width(), frame.height()), (256, 160));
+            let psnr = psnr(frame.data(), source);
+            assert!(psnr > 40.0, "frame {i} has a PSNR of {psnr:.1} dB");
+        }
+    }
+
+    /// Writes the syntax elements of a NAL unit.
+    #[derive(Default)]
+    struct NalWriter {
+        bits: Vec<bool>,
+    }
+
+    impl NalWriter {
+        fn u(&mut self, bits: u32, value: u32) -> &mut Self {
+            self.bits
+                .extend((0..bits).rev().map(|bit| (value >> bit) & 1 != 0));
+            self
+        }
+
+        fn ue(&mut self, value: u32) -> &mut Self {
+            let bits = 32 - (value + 1).leading_zeros();
+            self.u(bits - 1, 0).u(bits, value + 1)
+        }
+
+        fn se(&mut self, value: i32) -> &mut Self {
+            if value > 0 {
+                self.ue(value as u32 * 2 - 1)
+            } else {
+                self.ue(value.unsigned_abs() * 2)
+            }
+        }
+
+        fn align(&mut self) -> &mut Self {
+            self.bits.resize(self.bits.len().next_multiple_of(8), false);
+            self
+        }
+
+        /// Adds the RBSP trailing bits, and returns the NAL unit with emulation prevention bytes.
+        fn finish(&mut self, header: u8) -> Vec<u8> {
+            self.u(1, 1).align();
+            let mut nal = vec![header];
+            let mut zeros = 0;
+            for byte in self.bits.chunks(8) {
+                let byte = byte
+                    .iter()
+                    .fold(0, |byte, &bit| (byte << 1) | u8::from(bit));
+                if zeros == 2 && byte <= 3 {
+                    nal.push(3);
+                    zeros = 0;
+                }
+                zeros = if byte == 0 { zeros + 1 } else { 0 };
+                nal.push(byte);
+            }
+            nal
+        }
+    }
+
+    /// Prefixes every NAL unit with its 4 byte length.
+    fn packet(nals: &[Vec<u8>]) -> Vec<u8> {
+        nals.iter()
+            .flat_map(|nal| {
+                (nal.len() as u32)
+                    .to_be_bytes()
+                    .into_iter()
+                    .chain(nal.clone())
+            })
+            .collect()
+    }
+
+    const WIDTH: usize = 32;
+    const HEIGHT: usize = 32;
+
+    fn pcm_luma(x: usize, y: usize) -> u8 {
+        ((x * 13 + y * 7) % 254 + 1) as u8
+    }
+
+    fn pcm_chroma(plane: usize, x: usize, y: usize) -> u8 {
+        ((x * 29 + y * 5 + plane * 100) % 254 + 1) as u8
+    }
+
+    /// Returns the value of every sample of a YUV 4:2:0 frame.
+    fn yuv(frame: &DecodedFrame) -> (Vec<u8>, [Vec<u8>; 2]) {
+        assert_eq!(frame.format(), BitmapFormat::Yuv420p);
+        assert_eq!(
+            (frame.width(), frame.height()),
+            (WIDTH as u32, HEIGHT as u32)
+        );
+        let (luma, chroma) = frame.data().split_at(WIDTH * HEIGHT);
+        let (cb, cr) = chroma.split_at(WIDTH * HEIGHT / 4);
+        (luma.to_vec(), [cb.to_vec(), cr.to_vec()])
+    }
+
+    /// A synthetic Constrained Baseline CAVLC stream of 2x2 macroblocks, with deblocking disabled.
+    #[test]
+    fn cavlc_stream_decodes_exactly() {
+        let sps = NalWriter::default()
+            .u(8, 66) // profile_idc
+            .u(8, 0xc0) // constraint_set0_flag and constraint_set1_flag
+            .u(8, 10) // level_idc
+            .ue(0) // seq_parameter_set_id
+            .ue(0) // log2_max_frame_num_minus4
+            .ue(2) // pic_order_cnt_type
+            .ue(1) // max_num_ref_frames
+            .u(1, 0) // gaps_in_frame_num_value_allowed_flag
+            .ue(1) // pic_width_in_mbs_minus1
+            .ue(1) // pic_height_in_map_units_minus1
+            .u(1, 1) // frame_mbs_only_flag
+            .u(1, 1) // direct_8x8_inference_flag
+            .u(1, 0) // frame_cropping_flag
+            .u(1, 0) // vui_parameters_present_flag
+            .finish(0x67);
+        let pps = NalWriter::default()
+            .ue(0) // pic_parameter_set_id
+            .ue(0) // seq_parameter_set_id
+            .u(1, 0) // entropy_coding_mode_flag
+            .u(1, 0) // bottom_field_pic_order_in_frame_present_flag
+            .ue(0) // num_slice_groups_minus1
+            .ue(0) // num_ref_idx_l0_default_active_minus1
+            .ue(0) // num_ref_idx_l1_default_active_minus1
+            .u(1, 0) // weighted_pred_flag
+            .u(2, 0) // weighted_bipred_idc
+            .se(0) // pic_init_qp_minus26
+            .se(0) // pic_init_qs_minus26
+            .se(0) // chroma_qp_index_offset
+            .u(1, 1) // deblocking_filter_control_present_flag
+            .u(1, 0) // constrained_intra_pred_flag
+            .u(1, 0) // redundant_pic_cnt_present_flag
+            .finish(0x68);
+        let mut config = vec![1, 66, 0xc0, 10, 0xff, 0xe1];
+        config.extend((sps.len() as u16).to_be_bytes());
+        config.extend(&sps);
+        config.push(1);
+        config.extend((pps.len() as u16).to_be_bytes());
+        config.extend(&pps);
+
+        let mut decoder = H264Decoder::new();
+        decoder.configure_decoder(&config).unwrap();
+
+        // An IDR picture made of I_PCM macroblocks.
+        let mut idr = NalWriter::default();
+        idr.ue(0) // first_mb_in_slice
+            .ue(7) // slice_type (I)
+            .ue(0) // pic_parameter_set_id
+            .u(4, 0) // frame_num
+            .ue(0) // idr_pic_id
+            .u(1, 0) // no_output_of_prior_pics_flag
+            .u(1, 0) // long_term_reference_flag
+            .se(0) // slice_qp_delta
+            .ue(1); // disable_deblocking_filter_idc
+        for mb in 0..4 {
+            let (mb_x, mb_y) = (mb % 2, mb / 2);
+            idr.ue(25).align(); // mb_type (I_PCM)
+            for y in 0..16 {
+                for x in 0..16 {
+                    idr.u(8, pcm_luma(mb_x * 16 + x, mb_y * 16 + y).into());
+                }
+            }
+            for plane in 0..2 {
+                for y in 0..8 {
+                    for x in 0..8 {
+                        idr.u(8, pcm_chroma(plane, mb_x * 8 + x, mb_y * 8 + y).into());
+                    }
+                }
+            }
+        }
+        let frame = decode(&mut decoder, &packet(&[idr.finish(0x65)]), 0).unwrap();
+        let (luma, chroma) = yuv(&frame);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                assert_eq!(luma[y * WIDTH + x], pcm_luma(x, y), "luma {x}, {y}");
+            }
+        }
+        for (plane, samples) in chroma.iter().enumerate() {
+            for y in 0..HEIGHT / 2 {
+                for x in 0..WIDTH / 2 {
+                    assert_eq!(samples[y * WIDTH / 2 + x], pcm_chroma(plane, x, y));
+                }
+            }
+        }
+
+        // A P picture that moves the top left macroblock by one pixel and skips the others.
+        let p = NalWriter::default()
+            .ue(0) // first_mb_in_slice
+            .ue(5) // slice_type (P)
+            .ue(0) // pic_parameter_set_id
+            .u(4, 1) // frame_num
+            .u(1, 0) // num_ref_idx_active_override_flag
+            .u(1, 0) // ref_pic_list_modification_flag_l0
+            .u(1, 0) // adaptive_ref_pic_marking_mode_flag
+            .se(0) // slice_qp_delta
+            .ue(1) // disable_deblocking_filter_idc
+            .ue(0) // mb_skip_run
+            .ue(0) // mb_type (P_L0_16x16)
+            .se(4) // mvd_l0[0], in quarter samples
+            .se(0) // mvd_l0[1]
+            .ue(0) // coded_block_pattern (0)
+            .ue(3) // mb_skip_run
+            .finish(0x41);
+        let frame = decode(&mut decoder, &packet(&[p]), 1).unwrap();
+        let (luma, chroma) = yuv(&frame);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let source_x = if x < 16 && y < 16 { x + 1 } else { x };
+                assert_eq!(luma[y * WIDTH + x], pcm_luma(source_x, y), "luma {x}, {y}");
+            }
+        }
+        for (plane, samples) in chroma.iter().enumerate() {
+            for y in 0..HEIGHT / 2 {
+                for x in 0..WIDTH / 2 {
+                    let expected = if x < 8 && y < 8 {
+                        // Half a chroma sample to the right.
+                        let (a, b) = (pcm_chroma(plane, x, y), pcm_chroma(plane, x + 1, y));
+                        (u16::from(a) + u16::from(b)).div_ceil(2) as u8
+                    } else {
+                        pcm_chroma(plane, x, y)
+                    };
+                    assert_eq!(samples[y * WIDTH / 2 + x], expected, "chroma {x}, {y}");
+                }
+            }
+        }
+
+        // An I picture of Intra_16x16 DC predicted macroblocks, each adding a luma DC coefficient of 1.
+        let mut intra = NalWriter::default();
+        intra
+            .ue(0) // first_mb_in_slice
+            .ue(7) // slice_type (I)
+            .ue(0) // pic_parameter_set_id
+            .u(4, 2) // frame_num
+            .u(1, 0) // adaptive_ref_pic_marking_mode_flag
+            .se(0) // slice_qp_delta
+            .ue(1); // disable_deblocking_filter_idc
+        for _ in 0..4 {
+            intra
+                .ue(3) // mb_type (I_16x16_2_0_0)
+                .ue(0) // intra_chroma_pred_mode (DC)
+                .se(0) // mb_qp_delta
+                .u(2, 0b01) // coeff_token (TotalCoeff 1, TrailingOnes 1)
+                .u(1, 0) // trailing_ones_sign_flag
+                .u(1, 1); // total_zeros (0)
+        }
+        let frame = decode(&mut decoder, &packet(&[intra.finish(0x41)]), 2).unwrap();
+        let (luma, chroma) = yuv(&frame);
+        // At QP 26, the DC coefficient adds 1 to every sample of the DC prediction,
+        // which is 128 without neighbours and then builds up across the picture.
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let expected = 129 + u8::from(x >= 16) + u8::from(y >= 16);
+                assert_eq!(luma[y * WIDTH + x], expected, "luma {x}, {y}");
+            }
+        }
+        assert!(chroma.iter().flatten().all(|&sample| sample == 128));
+    }
+}
@@ -0,0 +1,133 @@
+use super::H264Error;
+
+/// Removes the emulation prevention bytes from the payload of a NAL unit,
+/// producing its raw byte sequence payload (RBSP).
+pub fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut rbsp = Vec::with_capacity(data.len());
+    let mut zeros = 0;
+    for &byte in data {
+        if zeros >= 2 && byte == 3 {
+            zeros = 0;
+            continue;
+        }
+        if byte == 0 {
+            zeros += 1;
+        } else {
+            zeros = 0;
+        }
+        rbsp.push(byte);
+    }
+    rbsp
+}
+
+/// Reads the big-endian bit fields and Exp-Golomb codes of an RBSP.
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn byte_align(&mut self) {
+        self.pos = self.pos.next_multiple_of(8);
+    }
+
+    /// Returns the next 32 bits without consuming them, padding with zeros past the end.
+    pub fn peek_u32(&self) -> u32 {
+        let byte = self.pos / 8;
+        let mut value = 0u64;
+        for i in 0..5 {
+            value = (value << 8) | u64::from(self.data.get(byte + i).copied().unwrap_or(0));
+        }
+        (value >> (8 - self.pos % 8)) as u32
+    }
+
+    pub fn skip_bits(&mut self, count: usize) -> Result<(), H264Error> {
+        if self.pos + count > self.data.len() * 8 {
+            return Err(H264Error::UnexpectedEof);
+        }
+        self.pos += count;
+        Ok(())
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, H264Error> {
+        Ok(self.read_bits(1)? != 0)
+    }
+
+    /// Reads up to 32 bits as an unsigned integer.
+    pub fn read_bits(&mut self, count: u32) -> Result<u32, H264Error> {
+        if count == 0 {
+            return Ok(0);
+        }
+        let value = self.peek_u32() >> (32 - count);
+        self.skip_bits(count as usize)?;
+        Ok(value)
+    }
+
+    /// Like `read_bits`, but reads zeros past the end of the data.
+    ///
+    /// The arithmetic decoder can legitimately read a few bits beyond the end of a slice.
+    pub fn read_bits_padded(&mut self, count: u32) -> u32 {
+        if count == 0 {
+            return 0;
+        }
+        let value = self.peek_u32() >> (32 - count);
+        self.pos += count as usize;
+        value
+    }
+
+    /// Reads an unsigned Exp-Golomb code, `ue(v)`.
+    pub fn read_ue(&mut self) -> Result<u32, H264Error> {
+        let mut leading_zeros = 0;
+        while !self.read_bit()? {
+            leading_zeros += 1;
+            if leading_zeros > 31 {
+                return Err(H264Error::InvalidData("Exp-Golomb code is too long"));
+            }
+        }
+        Ok(((1u64 << leading_zeros) - 1 + u64::from(self.read_bits(leading_zeros)?)) as u32)
+    }
+
+    /// Reads a signed Exp-Golomb code, `se(v)`.
+    pub fn read_se(&mut self) -> Result<i32, H264Error> {
+        let code = self.read_ue()?;
+        let magnitude = code.div_ceil(2) as i32;
+        Ok(if code % 2 == 0 { -magnitude } else { magnitude })
+    }
+
+    /// Reads an unsigned Exp-Golomb code and checks that it is at most `max`.
+    pub fn read_ue_max(&mut self, max: u32, what: &'static str) -> Result<u32, H264Error> {
+        let value = self.read_ue()?;
+        if value > max {
+            return Err(H264Error::InvalidData(what));
+        }
+        Ok(value)
+    }
+
+    /// Reads a signed Exp-Golomb code and checks that it is within `min..=max`.
+    pub fn read_se_range(
+        &mut self,
+        min: i32,
+        max: i32,
+        what: &'static str,
+    ) -> Result<i32, H264Error> {
+        let value = self.read_se()?;
+        if !(min..=max).contains(&value) {
+            return Err(H264Error::InvalidData(what));
+        }
+        Ok(value)
+    }
+
+    /// Whether there is more data before the RBSP trailing bits.
+    pub fn more_rbsp_data(&self) -> bool {
+        let Some(last) = self.data.iter().rposition(|&b| b != 0) else {
+            return false;
+        };
+        // The stop bit is the last set bit of the data.
+        let stop_bit = last * 8 + 7 - self.data[last].trailing_zeros() as usize;
+        self.pos < stop_bit
+    }
+}
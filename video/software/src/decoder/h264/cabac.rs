@@ -0,0 +1,643 @@
+use super::bitstream::BitReader;
+use super::cabac_tables::{
+    CABAC_INIT_8X8_I, CABAC_INIT_8X8_PB, CABAC_INIT_I, CABAC_INIT_PB, LAST_8X8_CTX, RANGE_TAB_LPS,
+    SIGNIFICANT_8X8_CTX, TRANS_IDX_LPS,
+};
+use super::macroblock::{EntropyReader, ResidualBlock, SliceDecoder};
+use super::picture::{MbInfo, MbKind};
+use super::slice::SliceType;
+use super::H264Error;
+
+const MB_SKIP_P: usize = 11;
+const MB_TYPE_P: usize = 14;
+const MB_TYPE_P_INTRA: usize = 17;
+const SUB_MB_TYPE_P: usize = 21;
+const MB_SKIP_B: usize = 24;
+const MB_TYPE_B: usize = 27;
+const MB_TYPE_B_INTRA: usize = 32;
+const SUB_MB_TYPE_B: usize = 36;
+const MB_TYPE_I: usize = 3;
+const MVD: [usize; 2] = [40, 47];
+const REF_IDX: usize = 54;
+const MB_QP_DELTA: usize = 60;
+const INTRA_CHROMA_PRED_MODE: usize = 64;
+const PREV_INTRA_PRED_MODE_FLAG: usize = 68;
+const REM_INTRA_PRED_MODE: usize = 69;
+const CODED_BLOCK_PATTERN_LUMA: usize = 73;
+const CODED_BLOCK_PATTERN_CHROMA: usize = 77;
+const CODED_BLOCK_FLAG: usize = 85;
+const SIGNIFICANT_COEFF_FLAG: usize = 105;
+const LAST_SIGNIFICANT_COEFF_FLAG: usize = 166;
+const COEFF_ABS_LEVEL_MINUS1: usize = 227;
+const TRANSFORM_SIZE_8X8_FLAG: usize = 399;
+const SIGNIFICANT_COEFF_FLAG_8X8: usize = 402;
+const LAST_SIGNIFICANT_COEFF_FLAG_8X8: usize = 417;
+const COEFF_ABS_LEVEL_MINUS1_8X8: usize = 426;
+const NUM_CONTEXTS: usize = 436;
+
+/// The context index offsets of each block category (`ctxBlockCatOffset`), for
+/// `coded_block_flag`, the significance maps and `coeff_abs_level_minus1`.
+const CODED_BLOCK_FLAG_CAT_OFFSET: [usize; 5] = [0, 4, 8, 12, 16];
+const SIGNIFICANCE_CAT_OFFSET: [usize; 5] = [0, 15, 29, 44, 47];
+const ABS_LEVEL_CAT_OFFSET: [usize; 5] = [0, 10, 20, 30, 39];
+
+/// The context-adaptive binary arithmetic decoder of 9.3.
+pub struct CabacReader<'a> {
+    reader: BitReader<'a>,
+    range: u32,
+    offset: u32,
+    /// The state of each context: `pStateIdx << 1 | valMPS`.
+    contexts: [u8; NUM_CONTEXTS],
+}
+
+impl<'a> CabacReader<'a> {
+    /// Starts decoding slice data, which must begin on a byte boundary.
+    pub fn new(
+        mut reader: BitReader<'a>,
+        slice_type: SliceType,
+        cabac_init_idc: usize,
+        qp: i32,
+    ) -> Result<Self, H264Error> {
+        reader.byte_align();
+        let (init, init_8x8) = if slice_type == SliceType::I {
+            (&CABAC_INIT_I, &CABAC_INIT_8X8_I)
+        } else {
+            (
+                &CABAC_INIT_PB[cabac_init_idc],
+                &CABAC_INIT_8X8_PB[cabac_init_idc],
+            )
+        };
+        let qp = qp.clamp(0, 51);
+        let init_state = |[m, n]: [i8; 2]| {
+            let state = (((i32::from(m) * qp) >> 4) + i32::from(n)).clamp(1, 126);
+            if state <= 63 {
+                ((63 - state) << 1) as u8
+            } else {
+                (((state - 64) << 1) | 1) as u8
+            }
+        };
+        let mut contexts = [0; NUM_CONTEXTS];
+        for (context, &values) in contexts.iter_mut().zip(init.iter()) {
+            *context = init_state(values);
+        }
+        for (context, &values) in contexts[TRANSFORM_SIZE_8X8_FLAG..]
+            .iter_mut()
+            .zip(init_8x8.iter())
+        {
+            *context = init_state(values);
+        }
+
+        let mut cabac = Self {
+            reader,
+            range: 0,
+            offset: 0,
+            contexts,
+        };
+        cabac.init_engine()?;
+        Ok(cabac)
+    }
+
+    fn init_engine(&mut self) -> Result<(), H264Error> {
+        self.range = 510;
+        self.offset = self.reader.read_bits(9)?;
+        if self.offset >= 510 {
+            return Err(H264Error::InvalidData("CABAC offset"));
+        }
+        Ok(())
+    }
+
+    fn decision(&mut self, context: usize) -> bool {
+        let state = self.contexts[context];
+        let p_state = usize::from(state >> 1);
+        let mps = state & 1;
+        let lps_range = u32::from(RANGE_TAB_LPS[p_state][((self.range >> 6) & 3) as usize]);
+        self.range -= lps_range;
+        let bin = if self.offset >= self.range {
+            self.offset -= self.range;
+            self.range = lps_range;
+            let mps = if p_state == 0 { 1 - mps } else { mps };
+            self.contexts[context] = (TRANS_IDX_LPS[p_state] << 1) | mps;
+            1 - (state & 1)
+        } else {
+            self.contexts[context] = ((p_state as u8 + 1).min(62) << 1) | mps;
+            mps
+        };
+        while self.range < 256 {
+            self.range <<= 1;
+            self.offset = (self.offset << 1) | self.reader.read_bits_padded(1);
+        }
+        bin != 0
+    }
+
+    fn bypass(&mut self) -> bool {
+        self.offset = (self.offset << 1) | self.reader.read_bits_padded(1);
+        if self.offset >= self.range {
+            self.offset -= self.range;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn terminate(&mut self) -> bool {
+        self.range -= 2;
+        if self.offset >= self.range {
+            true
+        } else {
+            while self.range < 256 {
+                self.range <<= 1;
+                self.offset = (self.offset << 1) | self.reader.read_bits_padded(1);
+            }
+            false
+        }
+    }
+
+    /// Decodes the bins of an Exp-Golomb suffix of order `k` in bypass mode.
+    fn exp_golomb_bypass(&mut self, mut k: u32) -> Result<u32, H264Error> {
+        let mut value = 0u32;
+        while self.bypass() {
+            value += 1 << k;
+            k += 1;
+            if k > 24 {
+                return Err(H264Error::InvalidData(
+                    "CABAC Exp-Golomb suffix is too long",
+                ));
+            }
+        }
+        while k > 0 {
+            k -= 1;
+            value += u32::from(self.bypass()) << k;
+        }
+        Ok(value)
+    }
+
+    /// Decodes the suffix of an intra `mb_type`, numbered as in I slices.
+    ///
+    /// `offset` is the context of the first bin, and `prefix` whether
+    /// this follows the prefix of a P or B `mb_type`.
+    fn intra_mb_type(&mut self, first: usize, offset: usize, prefix: bool) -> u32 {
+        if !self.decision(first) {
+            return 0;
+        }
+        if self.terminate() {
+            return 25;
+        }
+        // The contexts of the remaining bins, which differ between I slices and prefixed types.
+        let [luma, chroma, chroma2, pred0, pred1] = if prefix {
+            [offset + 1, offset + 2, offset + 2, offset + 3, offset + 3]
+        } else {
+            [offset + 3, offset + 4, offset + 5, offset + 6, offset + 7]
+        };
+        let luma = u32::from(self.decision(luma));
+        let chroma = if self.decision(chroma) {
+            1 + u32::from(self.decision(chroma2))
+        } else {
+            0
+        };
+        let pred = (u32::from(self.decision(pred0)) << 1) | u32::from(self.decision(pred1));
+        1 + pred + chroma * 4 + luma * 12
+    }
+}
+
+/// The macroblock to the left of or above the current one, if available.
+fn neighbour<'d>(d: &'d SliceDecoder, dx: i32, dy: i32) -> Option<&'d MbInfo> {
+    d.neighbour_mb(dx, dy).map(|addr| &d.pic.mbs[addr])
+}
+
+/// Sums a condition over the left (A) and top (B) neighbouring macroblocks.
+fn count_neighbours(d: &SliceDecoder, condition: impl Fn(&MbInfo) -> bool) -> usize {
+    [neighbour(d, -1, 0), neighbour(d, 0, -1)]
+        .into_iter()
+        .flatten()
+        .filter(|mb| condition(mb))
+        .count()
+}
+
+/// The index of the 8x8 partition containing a 4x4 block at a raster position.
+fn block_8x8(raster: usize) -> usize {
+    (raster / 8) * 2 + (raster % 4) / 2
+}
+
+impl SliceDecoder<'_> {
+    /// `coded_block_flag` of a neighbouring block for context selection, as described in 9.3.3.1.1.9.
+    ///
+    /// `block` selects the block of an available, non-PCM neighbour.
+    fn neighbour_coded_block_flag(
+        &self,
+        addr: Option<usize>,
+        block: impl Fn(&MbInfo) -> bool,
+    ) -> bool {
+        match addr {
+            None => self.cur_mb().kind.is_intra(),
+            Some(addr) => {
+                let mb = &self.pic.mbs[addr];
+                mb.kind == MbKind::IPcm || (!mb.kind.is_skip() && block(mb))
+            }
+        }
+    }
+
+    fn luma_coded_block_flag(&self, x: i32, y: i32) -> bool {
+        match self.luma_neighbour(x, y) {
+            None => self.neighbour_coded_block_flag(None, |_| false),
+            Some((addr, raster)) => self.neighbour_coded_block_flag(Some(addr), |mb| {
+                if mb.cbp & (1 << block_8x8(raster)) == 0 {
+                    false
+                } else if mb.transform_8x8 {
+                    true
+                } else {
+                    mb.nz[raster] > 0
+                }
+            }),
+        }
+    }
+}
+
+impl EntropyReader for CabacReader<'_> {
+    fn mb_skip(&mut self, d: &SliceDecoder) -> Result<bool, H264Error> {
+        let increment = count_neighbours(d, |mb| !mb.kind.is_skip());
+        let offset = if d.header.slice_type == SliceType::B {
+            MB_SKIP_B
+        } else {
+            MB_SKIP_P
+        };
+        Ok(self.decision(offset + increment))
+    }
+
+    fn end_of_slice(&mut self) -> Result<bool, H264Error> {
+        Ok(self.terminate())
+    }
+
+    fn mb_type(&mut self, d: &SliceDecoder) -> Result<u32, H264Error> {
+        Ok(match d.header.slice_type {
+            SliceType::I => {
+                let increment =
+                    count_neighbours(d, |mb| !matches!(mb.kind, MbKind::I4x4 | MbKind::I8x8));
+                self.intra_mb_type(MB_TYPE_I + increment, MB_TYPE_I, false)
+            }
+            SliceType::P => {
+                if self.decision(MB_TYPE_P) {
+                    5 + self.intra_mb_type(MB_TYPE_P_INTRA, MB_TYPE_P_INTRA, true)
+                } else if !self.decision(MB_TYPE_P + 1) {
+                    if self.decision(MB_TYPE_P + 2) {
+                        3
+                    } else {
+                        0
+                    }
+                } else if self.decision(MB_TYPE_P + 3) {
+                    1
+                } else {
+                    2
+                }
+            }
+            SliceType::B => {
+                let increment = count_neighbours(d, |mb| {
+                    !matches!(mb.kind, MbKind::BSkip | MbKind::BDirect16x16)
+                });
+                if !self.decision(MB_TYPE_B + increment) {
+                    0
+                } else if !self.decision(MB_TYPE_B + 3) {
+                    1 + u32::from(self.decision(MB_TYPE_B + 5))
+                } else {
+                    let mut bits = u32::from(self.decision(MB_TYPE_B + 4)) << 3;
+                    for shift in (0..3).rev() {
+                        bits |= u32::from(self.decision(MB_TYPE_B + 5)) << shift;
+                    }
+                    match bits {
+                        0..=7 => bits + 3,
+                        13 => 23 + self.intra_mb_type(MB_TYPE_B_INTRA, MB_TYPE_B_INTRA, true),
+                        14 => 11,
+                        15 => 22,
+                        _ => ((bits << 1) | u32::from(self.decision(MB_TYPE_B + 5))) - 4,
+                    }
+                }
+            }
+        })
+    }
+
+    fn sub_mb_type(&mut self, d: &SliceDecoder) -> Result<u32, H264Error> {
+        if d.header.slice_type == SliceType::P {
+            return Ok(if self.decision(SUB_MB_TYPE_P) {
+                0
+            } else if !self.decision(SUB_MB_TYPE_P + 1) {
+                1
+            } else if self.decision(SUB_MB_TYPE_P + 2) {
+                2
+            } else {
+                3
+            });
+        }
+        if !self.decision(SUB_MB_TYPE_B) {
+            return Ok(0);
+        }
+        if !self.decision(SUB_MB_TYPE_B + 1) {
+            return Ok(1 + u32::from(self.decision(SUB_MB_TYPE_B + 3)));
+        }
+        let mut sub_type = 3;
+        if self.decision(SUB_MB_TYPE_B + 2) {
+            if self.decision(SUB_MB_TYPE_B + 3) {
+                return Ok(11 + u32::from(self.decision(SUB_MB_TYPE_B + 3)));
+            }
+            sub_type += 4;
+        }
+        sub_type += u32::from(self.decision(SUB_MB_TYPE_B + 3)) << 1;
+        sub_type += u32::from(self.decision(SUB_MB_TYPE_B + 3));
+        Ok(sub_type)
+    }
+
+    fn transform_size_8x8_flag(&mut self, d: &SliceDecoder) -> Result<bool, H264Error> {
+        let increment = count_neighbours(d, |mb| mb.transform_8x8);
+        Ok(self.decision(TRANSFORM_SIZE_8X8_FLAG + increment))
+    }
+
+    fn intra_pred_mode(&mut self) -> Result<Option<u8>, H264Error> {
+        if self.decision(PREV_INTRA_PRED_MODE_FLAG) {
+            return Ok(None);
+        }
+        let mut mode = 0;
+        for bit in 0..3 {
+            mode |= u8::from(self.decision(REM_INTRA_PRED_MODE)) << bit;
+        }
+        Ok(Some(mode))
+    }
+
+    fn intra_chroma_pred_mode(&mut self, d: &SliceDecoder) -> Result<u8, H264Error> {
+        let increment = count_neighbours(d, |mb| {
+            mb.kind.is_intra() && mb.kind != MbKind::IPcm && mb.intra_chroma_mode != 0
+        });
+        let mut mode = 0;
+        while mode < 3 {
+            let context = if mode == 0 {
+                INTRA_CHROMA_PRED_MODE + increment
+            } else {
+                INTRA_CHROMA_PRED_MODE + 3
+            };
+            if !self.decision(context) {
+                break;
+            }
+            mode += 1;
+        }
+        Ok(mode)
+    }
+
+    fn ref_idx(
+        &mut self,
+        d: &SliceDecoder,
+        list: usize,
+        block: usize,
+        _num_active: usize,
+    ) -> Result<u32, H264Error> {
+        let (x, y) = ((block % 4) as i32 * 4, (block / 4) as i32 * 4);
+        let condition = |neighbour: Option<(usize, usize)>| {
+            neighbour.is_some_and(|(addr, raster)| {
+                let mb = &d.pic.mbs[addr];
+                let block8 = block_8x8(raster);
+                !mb.kind.is_skip()
+                    && !mb.kind.is_intra()
+                    && mb.direct & (1 << block8) == 0
+                    && mb.ref_idx[list][block8] > 0
+            })
+        };
+        let increment = usize::from(condition(d.luma_neighbour(x - 1, y)))
+            + 2 * usize::from(condition(d.luma_neighbour(x, y - 1)));
+        let mut ref_idx = 0;
+        let mut context = REF_IDX + increment;
+        while self.decision(context) {
+            ref_idx += 1;
+            context = if ref_idx == 1 {
+                REF_IDX + 4
+            } else {
+                REF_IDX + 5
+            };
+            if ref_idx > 32 {
+                return Err(H264Error::InvalidData("ref_idx"));
+            }
+        }
+        Ok(ref_idx)
+    }
+
+    fn mvd(
+        &mut self,
+        d: &SliceDecoder,
+        list: usize,
+        block: usize,
+        component: usize,
+    ) -> Result<i32, H264Error> {
+        let (x, y) = ((block % 4) as i32 * 4, (block / 4) as i32 * 4);
+        let abs_mvd = |neighbour: Option<(usize, usize)>| {
+            neighbour.map_or(0, |(addr, raster)| {
+                u32::from(d.pic.mbs[addr].mvd[list][raster][component])
+            })
+        };
+        let sum = abs_mvd(d.luma_neighbour(x - 1, y)) + abs_mvd(d.luma_neighbour(x, y - 1));
+        let offset = MVD[component];
+        let increment = match sum {
+            0..=2 => 0,
+            3..=32 => 1,
+            _ => 2,
+        };
+        if !self.decision(offset + increment) {
+            return Ok(0);
+        }
+        let mut value = 1;
+        let mut context = offset + 3;
+        while value < 9 && self.decision(context) {
+            if value < 4 {
+                context += 1;
+            }
+            value += 1;
+        }
+        if value >= 9 {
+            value += self.exp_golomb_bypass(3)?;
+        }
+        let value = value as i32;
+        Ok(if self.bypass() { -value } else { value })
+    }
+
+    fn coded_block_pattern(&mut self, d: &SliceDecoder) -> Result<u8, H264Error> {
+        // Unavailable neighbours count as having all of their luma blocks coded.
+        let luma_coded = |neighbour: Option<(usize, usize)>, current: u8| match neighbour {
+            None => true,
+            Some((addr, raster)) if addr == d.mb_addr => current & (1 << block_8x8(raster)) != 0,
+            Some((addr, raster)) => d.pic.mbs[addr].cbp & (1 << block_8x8(raster)) != 0,
+        };
+        let mut cbp = 0;
+        for block8 in 0..4 {
+            let (x, y) = ((block8 % 2) * 8, (block8 / 2) * 8);
+            let increment = usize::from(!luma_coded(d.luma_neighbour(x - 1, y), cbp))
+                + 2 * usize::from(!luma_coded(d.luma_neighbour(x, y - 1), cbp));
+            if self.decision(CODED_BLOCK_PATTERN_LUMA + increment) {
+                cbp |= 1 << block8;
+            }
+        }
+
+        let chroma = |mb: Option<&MbInfo>, min: u8| {
+            mb.is_some_and(|mb| mb.kind == MbKind::IPcm || (mb.cbp >> 4) >= min)
+        };
+        let (a, b) = (neighbour(d, -1, 0), neighbour(d, 0, -1));
+        let increment = usize::from(chroma(a, 1)) + 2 * usize::from(chroma(b, 1));
+        if self.decision(CODED_BLOCK_PATTERN_CHROMA + increment) {
+            let increment = usize::from(chroma(a, 2)) + 2 * usize::from(chroma(b, 2));
+            let chroma_cbp =
+                1 + u8::from(self.decision(CODED_BLOCK_PATTERN_CHROMA + 4 + increment));
+            cbp |= chroma_cbp << 4;
+        }
+        Ok(cbp)
+    }
+
+    fn mb_qp_delta(&mut self, d: &SliceDecoder) -> Result<i32, H264Error> {
+        let increment = usize::from(d.last_qp_delta != 0);
+        if !self.decision(MB_QP_DELTA + increment) {
+            return Ok(0);
+        }
+        let mut value = 1;
+        let mut context = MB_QP_DELTA + 2;
+        while self.decision(context) {
+            context = MB_QP_DELTA + 3;
+            value += 1;
+            if value > 52 {
+                return Err(H264Error::InvalidData("mb_qp_delta"));
+            }
+        }
+        Ok(if value % 2 == 0 {
+            -(value / 2)
+        } else {
+            (value + 1) / 2
+        })
+    }
+
+    fn residual_block(
+        &mut self,
+        d: &SliceDecoder,
+        block: ResidualBlock,
+        coeffs: &mut [i32],
+    ) -> Result<u8, H264Error> {
+        let max_coeffs = coeffs.len();
+        let category = match block {
+            ResidualBlock::LumaDc => 0,
+            ResidualBlock::LumaAc(_) => 1,
+            ResidualBlock::Luma4x4(_) => 2,
+            ResidualBlock::ChromaDc(_) => 3,
+            ResidualBlock::ChromaAc(..) => 4,
+            ResidualBlock::Luma8x8(_) => 5,
+        };
+
+        if category != 5 {
+            let coded = |dx: i32, dy: i32| -> bool {
+                match block {
+                    ResidualBlock::LumaDc => d
+                        .neighbour_coded_block_flag(d.neighbour_mb(dx, dy), |mb| {
+                            mb.kind == MbKind::I16x16 && mb.coded_dc & 1 != 0
+                        }),
+                    ResidualBlock::LumaAc(raster) | ResidualBlock::Luma4x4(raster) => {
+                        let (x, y) = ((raster % 4) as i32 * 4, (raster / 4) as i32 * 4);
+                        d.luma_coded_block_flag(x + dx, y + dy)
+                    }
+                    ResidualBlock::ChromaDc(c) => d
+                        .neighbour_coded_block_flag(d.neighbour_mb(dx, dy), |mb| {
+                            mb.cbp >> 4 != 0 && mb.coded_dc & (2 << c) != 0
+                        }),
+                    ResidualBlock::ChromaAc(c, raster) => {
+                        let (x, y) = ((raster % 2) as i32 * 4, (raster / 2) as i32 * 4);
+                        match d.chroma_neighbour(x + dx, y + dy) {
+                            None => d.neighbour_coded_block_flag(None, |_| false),
+                            Some((addr, raster)) => d
+                                .neighbour_coded_block_flag(Some(addr), |mb| {
+                                    mb.cbp >> 4 == 2 && mb.nz[16 + c * 4 + raster] > 0
+                                }),
+                        }
+                    }
+                    ResidualBlock::Luma8x8(_) => unreachable!(),
+                }
+            };
+            let increment = usize::from(coded(-1, 0)) + 2 * usize::from(coded(0, -1));
+            if !self.decision(CODED_BLOCK_FLAG + CODED_BLOCK_FLAG_CAT_OFFSET[category] + increment)
+            {
+                return Ok(0);
+            }
+        }
+
+        // The significance map.
+        let mut significant = [false; 64];
+        let mut count = 0;
+        let mut last = max_coeffs - 1;
+        for i in 0..max_coeffs - 1 {
+            let (sig_context, last_context) = match category {
+                5 => (
+                    SIGNIFICANT_COEFF_FLAG_8X8 + usize::from(SIGNIFICANT_8X8_CTX[i]),
+                    LAST_SIGNIFICANT_COEFF_FLAG_8X8 + usize::from(LAST_8X8_CTX[i]),
+                ),
+                3 => {
+                    let increment = SIGNIFICANCE_CAT_OFFSET[3] + i.min(2);
+                    (
+                        SIGNIFICANT_COEFF_FLAG + increment,
+                        LAST_SIGNIFICANT_COEFF_FLAG + increment,
+                    )
+                }
+                _ => {
+                    let increment = SIGNIFICANCE_CAT_OFFSET[category] + i;
+                    (
+                        SIGNIFICANT_COEFF_FLAG + increment,
+                        LAST_SIGNIFICANT_COEFF_FLAG + increment,
+                    )
+                }
+            };
+            if self.decision(sig_context) {
+                significant[i] = true;
+                count += 1;
+                if self.decision(last_context) {
+                    last = i;
+                    break;
+                }
+            }
+        }
+        if last == max_coeffs - 1 {
+            significant[last] = true;
+            count += 1;
+        }
+
+        // The levels, in reverse scanning order.
+        let abs_offset = if category == 5 {
+            COEFF_ABS_LEVEL_MINUS1_8X8
+        } else {
+            COEFF_ABS_LEVEL_MINUS1 + ABS_LEVEL_CAT_OFFSET[category]
+        };
+        let max_gt1_increment = if category == 3 { 3 } else { 4 };
+        let mut num_eq1 = 0;
+        let mut num_gt1 = 0;
+        for i in (0..=last).rev() {
+            if !significant[i] {
+                continue;
+            }
+            let first_context = if num_gt1 != 0 {
+                abs_offset
+            } else {
+                abs_offset + (1 + num_eq1).min(4)
+            };
+            let mut level = 1u32;
+            if self.decision(first_context) {
+                let context = abs_offset + 5 + num_gt1.min(max_gt1_increment);
+                level += 1;
+                while level < 15 && self.decision(context) {
+                    level += 1;
+                }
+                if level >= 15 {
+                    level += self.exp_golomb_bypass(0)?;
+                }
+                num_gt1 += 1;
+            } else {
+                num_eq1 += 1;
+            }
+            let level = level as i32;
+            coeffs[i] = if self.bypass() { -level } else { level };
+        }
+        Ok(count as u8)
+    }
+
+    fn pcm_samples(&mut self, samples: &mut [u8; 384]) -> Result<(), H264Error> {
+        self.reader.byte_align();
+        for sample in samples.iter_mut() {
+            *sample = self.reader.read_bits(8)? as u8;
+        }
+        self.init_engine()
+    }
+}
@@ -0,0 +1,298 @@
+use super::bitstream::BitReader;
+use super::macroblock::{EntropyReader, ResidualBlock, SliceDecoder};
+use super::picture::MbKind;
+use super::tables::{
+    CBP_INTER, CBP_INTRA, CHROMA_DC_COEFF_TOKEN_BITS, CHROMA_DC_COEFF_TOKEN_LEN,
+    CHROMA_DC_TOTAL_ZEROS_BITS, CHROMA_DC_TOTAL_ZEROS_LEN, COEFF_TOKEN_BITS, COEFF_TOKEN_LEN,
+    RUN_BEFORE_BITS, RUN_BEFORE_LEN, TOTAL_ZEROS_BITS, TOTAL_ZEROS_LEN,
+};
+use super::H264Error;
+
+/// Reads macroblocks coded with context-adaptive variable length coding.
+pub struct CavlcReader<'a> {
+    reader: BitReader<'a>,
+    /// The number of skipped macroblocks left in the current `mb_skip_run`,
+    /// or `None` if a new run is due.
+    skip_run: Option<u32>,
+}
+
+impl<'a> CavlcReader<'a> {
+    pub fn new(reader: BitReader<'a>) -> Self {
+        Self {
+            reader,
+            skip_run: None,
+        }
+    }
+
+    /// Reads a code from a table of code lengths and values, returning its index.
+    fn read_vlc(&mut self, lengths: &[u8], codes: &[u8]) -> Result<usize, H264Error> {
+        let bits = self.reader.peek_u32();
+        for (index, (&length, &code)) in lengths.iter().zip(codes).enumerate() {
+            if length != 0 && bits >> (32 - u32::from(length)) == u32::from(code) {
+                self.reader.skip_bits(usize::from(length))?;
+                return Ok(index);
+            }
+        }
+        Err(H264Error::InvalidData("invalid variable length code"))
+    }
+
+    fn read_level(
+        &mut self,
+        suffix_length: u32,
+        first_after_trailing_ones: bool,
+    ) -> Result<i32, H264Error> {
+        let mut level_prefix = 0;
+        while !self.reader.read_bit()? {
+            level_prefix += 1;
+            if level_prefix > 28 {
+                return Err(H264Error::InvalidData("level_prefix"));
+            }
+        }
+        let mut level_code = (level_prefix.min(15) << suffix_length) as i32;
+        let suffix_size = if level_prefix == 14 && suffix_length == 0 {
+            4
+        } else if level_prefix >= 15 {
+            level_prefix - 3
+        } else {
+            suffix_length
+        };
+        if suffix_size > 0 {
+            level_code += self.reader.read_bits(suffix_size)? as i32;
+        }
+        if level_prefix >= 15 && suffix_length == 0 {
+            level_code += 15;
+        }
+        if level_prefix >= 16 {
+            level_code += (1 << (level_prefix - 3)) - 4096;
+        }
+        if first_after_trailing_ones {
+            level_code += 2;
+        }
+        Ok(if level_code % 2 == 0 {
+            (level_code + 2) >> 1
+        } else {
+            (-level_code - 1) >> 1
+        })
+    }
+}
+
+impl SliceDecoder<'_> {
+    /// The number of non-zero coefficients of a neighbouring block, for `nC`.
+    fn neighbour_total_coeff(
+        &self,
+        neighbour: Option<(usize, usize)>,
+        index: impl Fn(usize) -> usize,
+    ) -> Option<u8> {
+        let (addr, raster) = neighbour?;
+        let mb = &self.pic.mbs[addr];
+        Some(match mb.kind {
+            MbKind::IPcm => 16,
+            _ => mb.nz[index(raster)],
+        })
+    }
+
+    /// Predicts the number of non-zero coefficients of a block, as described in 9.2.1.
+    fn predicted_total_coeff(&self, block: ResidualBlock) -> i32 {
+        let (a, b) = match block {
+            ResidualBlock::ChromaDc(_) => return -1,
+            ResidualBlock::LumaDc => (
+                self.neighbour_total_coeff(self.luma_neighbour(-1, 0), |r| r),
+                self.neighbour_total_coeff(self.luma_neighbour(0, -1), |r| r),
+            ),
+            ResidualBlock::LumaAc(raster)
+            | ResidualBlock::Luma4x4(raster)
+            | ResidualBlock::Luma8x8(raster) => {
+                let (x, y) = ((raster % 4) as i32 * 4, (raster / 4) as i32 * 4);
+                (
+                    self.neighbour_total_coeff(self.luma_neighbour(x - 1, y), |r| r),
+                    self.neighbour_total_coeff(self.luma_neighbour(x, y - 1), |r| r),
+                )
+            }
+            ResidualBlock::ChromaAc(c, raster) => {
+                let (x, y) = ((raster % 2) as i32 * 4, (raster / 2) as i32 * 4);
+                (
+                    self.neighbour_total_coeff(self.chroma_neighbour(x - 1, y), |r| 16 + c * 4 + r),
+                    self.neighbour_total_coeff(self.chroma_neighbour(x, y - 1), |r| 16 + c * 4 + r),
+                )
+            }
+        };
+        match (a, b) {
+            (Some(a), Some(b)) => (i32::from(a) + i32::from(b) + 1) >> 1,
+            (Some(n), None) | (None, Some(n)) => i32::from(n),
+            (None, None) => 0,
+        }
+    }
+}
+
+impl EntropyReader for CavlcReader<'_> {
+    fn mb_skip(&mut self, _d: &SliceDecoder) -> Result<bool, H264Error> {
+        let run = match self.skip_run {
+            Some(run) => run,
+            None => self.reader.read_ue()?,
+        };
+        if run > 0 {
+            self.skip_run = Some(run - 1);
+            Ok(true)
+        } else {
+            self.skip_run = None;
+            Ok(false)
+        }
+    }
+
+    fn end_of_slice(&mut self) -> Result<bool, H264Error> {
+        Ok(match self.skip_run {
+            // More skipped macroblocks follow.
+            Some(run) if run > 0 => false,
+            _ => !self.reader.more_rbsp_data(),
+        })
+    }
+
+    fn mb_type(&mut self, _d: &SliceDecoder) -> Result<u32, H264Error> {
+        self.reader.read_ue()
+    }
+
+    fn sub_mb_type(&mut self, _d: &SliceDecoder) -> Result<u32, H264Error> {
+        self.reader.read_ue()
+    }
+
+    fn transform_size_8x8_flag(&mut self, _d: &SliceDecoder) -> Result<bool, H264Error> {
+        self.reader.read_bit()
+    }
+
+    fn intra_pred_mode(&mut self) -> Result<Option<u8>, H264Error> {
+        if self.reader.read_bit()? {
+            Ok(None)
+        } else {
+            Ok(Some(self.reader.read_bits(3)? as u8))
+        }
+    }
+
+    fn intra_chroma_pred_mode(&mut self, _d: &SliceDecoder) -> Result<u8, H264Error> {
+        Ok(self.reader.read_ue_max(3, "intra_chroma_pred_mode")? as u8)
+    }
+
+    fn ref_idx(
+        &mut self,
+        _d: &SliceDecoder,
+        _list: usize,
+        _block: usize,
+        num_active: usize,
+    ) -> Result<u32, H264Error> {
+        if num_active == 2 {
+            Ok(u32::from(!self.reader.read_bit()?))
+        } else {
+            self.reader.read_ue()
+        }
+    }
+
+    fn mvd(
+        &mut self,
+        _d: &SliceDecoder,
+        _list: usize,
+        _block: usize,
+        _component: usize,
+    ) -> Result<i32, H264Error> {
+        self.reader.read_se()
+    }
+
+    fn coded_block_pattern(&mut self, d: &SliceDecoder) -> Result<u8, H264Error> {
+        let code = self.reader.read_ue_max(47, "coded_block_pattern")? as usize;
+        Ok(if d.cur_mb().kind.is_intra() {
+            CBP_INTRA[code]
+        } else {
+            CBP_INTER[code]
+        })
+    }
+
+    fn mb_qp_delta(&mut self, _d: &SliceDecoder) -> Result<i32, H264Error> {
+        self.reader.read_se()
+    }
+
+    fn residual_block(
+        &mut self,
+        d: &SliceDecoder,
+        block: ResidualBlock,
+        coeffs: &mut [i32],
+    ) -> Result<u8, H264Error> {
+        let max_coeffs = coeffs.len();
+        let nc = d.predicted_total_coeff(block);
+        let token = match nc {
+            -1 => self.read_vlc(&CHROMA_DC_COEFF_TOKEN_LEN, &CHROMA_DC_COEFF_TOKEN_BITS)?,
+            0..=1 => self.read_vlc(&COEFF_TOKEN_LEN[0], &COEFF_TOKEN_BITS[0])?,
+            2..=3 => self.read_vlc(&COEFF_TOKEN_LEN[1], &COEFF_TOKEN_BITS[1])?,
+            4..=7 => self.read_vlc(&COEFF_TOKEN_LEN[2], &COEFF_TOKEN_BITS[2])?,
+            _ => self.read_vlc(&COEFF_TOKEN_LEN[3], &COEFF_TOKEN_BITS[3])?,
+        };
+        let (total_coeff, trailing_ones) = (token / 4, token % 4);
+        if total_coeff == 0 {
+            return Ok(0);
+        }
+        if total_coeff > max_coeffs {
+            return Err(H264Error::InvalidData("coeff_token"));
+        }
+
+        let mut levels = [0; 16];
+        let mut suffix_length = if total_coeff > 10 && trailing_ones < 3 {
+            1
+        } else {
+            0
+        };
+        for (i, level) in levels[..total_coeff].iter_mut().enumerate() {
+            if i < trailing_ones {
+                *level = if self.reader.read_bit()? { -1 } else { 1 };
+                continue;
+            }
+            *level = self.read_level(suffix_length, i == trailing_ones && trailing_ones < 3)?;
+            if suffix_length == 0 {
+                suffix_length = 1;
+            }
+            if level.abs() > (3 << (suffix_length - 1)) && suffix_length < 6 {
+                suffix_length += 1;
+            }
+        }
+
+        let mut zeros_left = if total_coeff < max_coeffs {
+            if nc == -1 {
+                self.read_vlc(
+                    CHROMA_DC_TOTAL_ZEROS_LEN[total_coeff - 1],
+                    CHROMA_DC_TOTAL_ZEROS_BITS[total_coeff - 1],
+                )?
+            } else {
+                self.read_vlc(
+                    TOTAL_ZEROS_LEN[total_coeff - 1],
+                    TOTAL_ZEROS_BITS[total_coeff - 1],
+                )?
+            }
+        } else {
+            0
+        };
+        if total_coeff + zeros_left > max_coeffs {
+            return Err(H264Error::InvalidData("total_zeros"));
+        }
+
+        // Levels are coded from the highest frequency down, each preceded by a run of zeros.
+        let mut position = total_coeff + zeros_left;
+        for (i, &level) in levels[..total_coeff].iter().enumerate() {
+            position -= 1;
+            coeffs[position] = level;
+            if i + 1 < total_coeff && zeros_left > 0 {
+                let table = zeros_left.min(7) - 1;
+                let run = self.read_vlc(RUN_BEFORE_LEN[table], RUN_BEFORE_BITS[table])?;
+                if run > zeros_left {
+                    return Err(H264Error::InvalidData("run_before"));
+                }
+                zeros_left -= run;
+                position -= run;
+            }
+        }
+        Ok(total_coeff as u8)
+    }
+
+    fn pcm_samples(&mut self, samples: &mut [u8; 384]) -> Result<(), H264Error> {
+        self.reader.byte_align();
+        for sample in samples.iter_mut() {
+            *sample = self.reader.read_bits(8)? as u8;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+use super::picture::{MbInfo, Picture, Plane};
+use super::tables::{ALPHA, BETA, TC0};
+
+/// The index of the 8x8 partition containing a 4x4 block at a raster position.
+fn block_8x8(raster: usize) -> usize {
+    (raster / 8) * 2 + (raster % 4) / 2
+}
+
+fn has_coefficients(mb: &MbInfo, raster: usize) -> bool {
+    if mb.transform_8x8 {
+        let top_left = (raster / 8) * 8 + (raster % 4) / 2 * 2;
+        [0, 1, 4, 5].iter().any(|&i| mb.nz[top_left + i] > 0)
+    } else {
+        mb.nz[raster] > 0
+    }
+}
+
+fn mv_differs(a: [i16; 2], b: [i16; 2]) -> bool {
+    (i32::from(a[0]) - i32::from(b[0])).abs() >= 4 || (i32::from(a[1]) - i32::from(b[1])).abs() >= 4
+}
+
+/// Whether the motion of two blocks differs enough to filter the edge between them.
+fn motion_differs(p: &MbInfo, p_block: usize, q: &MbInfo, q_block: usize) -> bool {
+    let refs = |mb: &MbInfo, block: usize| {
+        let block8 = block_8x8(block);
+        [mb.ref_pic[0][block8], mb.ref_pic[1][block8]]
+    };
+    let (p_refs, q_refs) = (refs(p, p_block), refs(q, q_block));
+    let (p_mv, q_mv) = (
+        [p.mv[0][p_block], p.mv[1][p_block]],
+        [q.mv[0][q_block], q.mv[1][q_block]],
+    );
+    let used = |refs: [i32; 2]| refs.iter().filter(|&&r| r >= 0).count();
+    if used(p_refs) != used(q_refs) {
+        return true;
+    }
+    if used(p_refs) == 1 {
+        let p_list = if p_refs[0] >= 0 { 0 } else { 1 };
+        let q_list = if q_refs[0] >= 0 { 0 } else { 1 };
+        return p_refs[p_list] != q_refs[q_list] || mv_differs(p_mv[p_list], q_mv[q_list]);
+    }
+
+    let straight = p_refs[0] == q_refs[0] && p_refs[1] == q_refs[1];
+    let crossed = p_refs[0] == q_refs[1] && p_refs[1] == q_refs[0];
+    if !straight && !crossed {
+        return true;
+    }
+    let straight_differs = mv_differs(p_mv[0], q_mv[0]) || mv_differs(p_mv[1], q_mv[1]);
+    let crossed_differs = mv_differs(p_mv[0], q_mv[1]) || mv_differs(p_mv[1], q_mv[0]);
+    if p_refs[0] != p_refs[1] {
+        if straight {
+            straight_differs
+        } else {
+            crossed_differs
+        }
+    } else {
+        straight_differs && crossed_differs
+    }
+}
+
+/// Derives the boundary filtering strength between two 4x4 luma blocks, as described in 8.7.2.1.
+fn boundary_strength(p: &MbInfo, p_block: usize, q: &MbInfo, q_block: usize, mb_edge: bool) -> u8 {
+    if p.kind.is_intra() || q.kind.is_intra() {
+        if mb_edge {
+            4
+        } else {
+            3
+        }
+    } else if has_coefficients(p, p_block) || has_coefficients(q, q_block) {
+        2
+    } else if motion_differs(p, p_block, q, q_block) {
+        1
+    } else {
+        0
+    }
+}
+
+/// Filters the samples across one edge of a block, as described in 8.7.2.3 and 8.7.2.4.
+///
+/// `q0` is the index of the first sample after the edge, `step` the distance
+/// between samples across the edge and `along` the distance between lines of samples.
+#[allow(clippy::too_many_arguments)]
+fn filter_line(
+    data: &mut [u8],
+    q0: usize,
+    step: usize,
+    strength: u8,
+    alpha: i32,
+    beta: i32,
+    tc0: i32,
+    chroma: bool,
+) {
+    let sample = |data: &[u8], index: usize| i32::from(data[index]);
+    let (p0, p1) = (sample(data, q0 - step), sample(data, q0 - 2 * step));
+    let (q0v, q1) = (sample(data, q0), sample(data, q0 + step));
+    if (p0 - q0v).abs() >= alpha || (p1 - p0).abs() >= beta || (q1 - q0v).abs() >= beta {
+        return;
+    }
+    let clip1 = |value: i32| value.clamp(0, 255) as u8;
+
+    if chroma {
+        if strength < 4 {
+            let tc = tc0 + 1;
+            let delta = ((((q0v - p0) << 2) + (p1 - q1) + 4) >> 3).clamp(-tc, tc);
+            data[q0 - step] = clip1(p0 + delta);
+            data[q0] = clip1(q0v - delta);
+        } else {
+            data[q0 - step] = ((2 * p1 + p0 + q1 + 2) >> 2) as u8;
+            data[q0] = ((2 * q1 + q0v + p1 + 2) >> 2) as u8;
+        }
+        return;
+    }
+
+    let (p2, q2) = (sample(data, q0 - 3 * step), sample(data, q0 + 2 * step));
+    let ap = (p2 - p0).abs();
+    let aq = (q2 - q0v).abs();
+    if strength < 4 {
+        let tc = tc0 + i32::from(ap < beta) + i32::from(aq < beta);
+        let delta = ((((q0v - p0) << 2) + (p1 - q1) + 4) >> 3).clamp(-tc, tc);
+        data[q0 - step] = clip1(p0 + delta);
+        data[q0] = clip1(q0v - delta);
+        if ap < beta {
+            data[q0 - 2 * step] =
+                (p1 + ((p2 + ((p0 + q0v + 1) >> 1) - (p1 << 1)) >> 1).clamp(-tc0, tc0)) as u8;
+        }
+        if aq < beta {
+            data[q0 + step] =
+                (q1 + ((q2 + ((p0 + q0v + 1) >> 1) - (q1 << 1)) >> 1).clamp(-tc0, tc0)) as u8;
+        }
+        return;
+    }
+
+    let strong = (p0 - q0v).abs() < (alpha >> 2) + 2;
+    if ap < beta && strong {
+        let p3 = sample(data, q0 - 4 * step);
+        data[q0 - step] = ((p2 + 2 * p1 + 2 * p0 + 2 * q0v + q1 + 4) >> 3) as u8;
+        data[q0 - 2 * step] = ((p2 + p1 + p0 + q0v + 2) >> 2) as u8;
+        data[q0 - 3 * step] = ((2 * p3 + 3 * p2 + p1 + p0 + q0v + 4) >> 3) as u8;
+    } else {
+        data[q0 - step] = ((2 * p1 + p0 + q1 + 2) >> 2) as u8;
+    }
+    if aq < beta && strong {
+        let q3 = sample(data, q0 + 3 * step);
+        data[q0] = ((p1 + 2 * p0 + 2 * q0v + 2 * q1 + q2 + 4) >> 3) as u8;
+        data[q0 + step] = ((p0 + q0v + q1 + q2 + 2) >> 2) as u8;
+        data[q0 + 2 * step] = ((2 * q3 + 3 * q2 + q1 + q0v + p0 + 4) >> 3) as u8;
+    } else {
+        data[q0] = ((2 * q1 + q0v + p1 + 2) >> 2) as u8;
+    }
+}
+
+/// Filters one edge of a macroblock in a plane.
+///
+/// The edge starts at `(x, y)`, and `strengths` gives the strength of each
+/// quarter of it. `qp` is the average quantization parameter of both sides.
+#[allow(clippy::too_many_arguments)]
+fn filter_edge(
+    plane: &mut Plane,
+    x: usize,
+    y: usize,
+    vertical: bool,
+    length: usize,
+    strengths: [u8; 4],
+    qp: i32,
+    offsets: (i32, i32),
+    chroma: bool,
+) {
+    let index_a = (qp + offsets.0).clamp(0, 51) as usize;
+    let index_b = (qp + offsets.1).clamp(0, 51) as usize;
+    let alpha = i32::from(ALPHA[index_a]);
+    let beta = i32::from(BETA[index_b]);
+    if alpha == 0 || beta == 0 {
+        return;
+    }
+    let stride = plane.width;
+    let (step, along) = if vertical { (1, stride) } else { (stride, 1) };
+    let start = y * stride + x;
+    for i in 0..length {
+        let strength = strengths[i * 4 / length];
+        if strength == 0 {
+            continue;
+        }
+        let tc0 = if strength < 4 {
+            i32::from(TC0[index_a][usize::from(strength) - 1])
+        } else {
+            0
+        };
+        filter_line(
+            &mut plane.data,
+            start + i * along,
+            step,
+            strength,
+            alpha,
+            beta,
+            tc0,
+            chroma,
+        );
+    }
+}
+
+/// Applies the deblocking filter to a decoded picture, as described in 8.7.
+pub fn deblock_picture(pic: &mut Picture) {
+    let width_mbs = pic.width_mbs;
+    for addr in 0..pic.mbs.len() {
+        let q = pic.mbs[addr];
+        if q.slice == 0 {
+            continue;
+        }
+        let params = pic.slices[q.slice as usize - 1];
+        if params.disable_deblocking_filter_idc == 1 {
+            continue;
+        }
+        let offsets = (params.alpha_offset, params.beta_offset);
+        let (mb_x, mb_y) = (addr % width_mbs, addr / width_mbs);
+        let usable = |neighbour: &MbInfo| {
+            neighbour.slice != 0
+                && (params.disable_deblocking_filter_idc != 2 || neighbour.slice == q.slice)
+        };
+
+        for vertical in [true, false] {
+            let neighbour_addr = if vertical {
+                (mb_x > 0).then(|| addr - 1)
+            } else {
+                (mb_y > 0).then(|| addr - width_mbs)
+            };
+            let neighbour = neighbour_addr.map(|a| pic.mbs[a]).filter(|n| usable(n));
+
+            for edge in 0..4 {
+                // Blocks with an 8x8 transform have no luma edges at odd 4x4 positions.
+                let luma = !(q.transform_8x8 && edge % 2 == 1);
+                let chroma = edge % 2 == 0;
+                if !luma && !chroma {
+                    continue;
+                }
+                let p = if edge == 0 {
+                    match neighbour {
+                        Some(p) => p,
+                        None => continue,
+                    }
+                } else {
+                    q
+                };
+                let mut strengths = [0; 4];
+                for (k, strength) in strengths.iter_mut().enumerate() {
+                    let (q_block, p_block) = if vertical {
+                        (
+                            k * 4 + edge,
+                            if edge == 0 {
+                                k * 4 + 3
+                            } else {
+                                k * 4 + edge - 1
+                            },
+                        )
+                    } else {
+                        (
+                            edge * 4 + k,
+                            if edge == 0 {
+                                12 + k
+                            } else {
+                                (edge - 1) * 4 + k
+                            },
+                        )
+                    };
+                    *strength = boundary_strength(&p, p_block, &q, q_block, edge == 0);
+                }
+                if strengths == [0; 4] {
+                    continue;
+                }
+
+                let (x, y) = if vertical {
+                    (mb_x * 16 + edge * 4, mb_y * 16)
+                } else {
+                    (mb_x * 16, mb_y * 16 + edge * 4)
+                };
+                if luma {
+                    let qp = (i32::from(p.qp) + i32::from(q.qp) + 1) >> 1;
+                    filter_edge(
+                        &mut pic.luma,
+                        x,
+                        y,
+                        vertical,
+                        16,
+                        strengths,
+                        qp,
+                        offsets,
+                        false,
+                    );
+                }
+                if chroma {
+                    for (c, plane) in [&mut pic.cb, &mut pic.cr].into_iter().enumerate() {
+                        let qp = (i32::from(p.qpc[c]) + i32::from(q.qpc[c]) + 1) >> 1;
+                        filter_edge(
+                            plane,
+                            x / 2,
+                            y / 2,
+                            vertical,
+                            8,
+                            strengths,
+                            qp,
+                            offsets,
+                            true,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
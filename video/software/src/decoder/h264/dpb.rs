@@ -0,0 +1,366 @@
+use super::picture::Picture;
+use super::slice::Mmco;
+use super::H264Error;
+use std::rc::Rc;
+
+/// An entry of a reference picture list.
+#[derive(Clone)]
+pub struct RefPic {
+    pub pic: Rc<Picture>,
+    pub long_term: bool,
+}
+
+struct StoredRef {
+    pic: Rc<Picture>,
+    frame_num: u32,
+    /// `LongTermFrameIdx`, if this is a long-term reference.
+    long_term_frame_idx: Option<u32>,
+}
+
+struct PendingOutput {
+    /// Incremented by each IDR picture and each picture that resets the picture order count,
+    /// as those start a new output order.
+    epoch: u32,
+    poc: i32,
+    pic: Rc<Picture>,
+}
+
+/// The decoded picture buffer, which holds the pictures used for reference and
+/// those that are waiting to be output.
+#[derive(Default)]
+pub struct Dpb {
+    refs: Vec<StoredRef>,
+    /// `MaxLongTermFrameIdx`, or `None` for "no long-term frame indices".
+    max_long_term_frame_idx: Option<u32>,
+    pending: Vec<PendingOutput>,
+    epoch: u32,
+}
+
+/// The picture number context of the current picture.
+#[derive(Clone, Copy)]
+pub struct PicNumContext {
+    pub frame_num: u32,
+    pub max_frame_num: u32,
+}
+
+impl PicNumContext {
+    /// `FrameNumWrap`, which equals `PicNum` for frames.
+    fn pic_num(&self, frame_num: u32) -> i32 {
+        if frame_num > self.frame_num {
+            frame_num as i32 - self.max_frame_num as i32
+        } else {
+            frame_num as i32
+        }
+    }
+}
+
+impl Dpb {
+    pub fn clear_references(&mut self) {
+        self.refs.clear();
+        self.max_long_term_frame_idx = None;
+    }
+
+    /// Builds the initial reference picture list of a P slice, as described in 8.2.4.2.1.
+    pub fn initial_list_p(&self, ctx: PicNumContext) -> Vec<RefPic> {
+        let mut short_term: Vec<&StoredRef> = self.short_term().collect();
+        short_term.sort_by_key(|r| -ctx.pic_num(r.frame_num));
+        let mut list: Vec<RefPic> = short_term.into_iter().map(Self::entry).collect();
+        list.extend(self.long_term_sorted());
+        list
+    }
+
+    /// Builds the initial reference picture lists of a B slice, as described in 8.2.4.2.3.
+    pub fn initial_lists_b(&self, poc: i32) -> [Vec<RefPic>; 2] {
+        let mut before: Vec<&StoredRef> = self.short_term().filter(|r| r.pic.poc < poc).collect();
+        let mut after: Vec<&StoredRef> = self.short_term().filter(|r| r.pic.poc > poc).collect();
+        before.sort_by_key(|r| -r.pic.poc);
+        after.sort_by_key(|r| r.pic.poc);
+
+        let list0: Vec<RefPic> = before
+            .iter()
+            .chain(after.iter())
+            .map(|r| Self::entry(r))
+            .chain(self.long_term_sorted())
+            .collect();
+        let mut list1: Vec<RefPic> = after
+            .iter()
+            .chain(before.iter())
+            .map(|r| Self::entry(r))
+            .chain(self.long_term_sorted())
+            .collect();
+        if list1.len() > 1
+            && list0
+                .iter()
+                .zip(list1.iter())
+                .all(|(a, b)| Rc::ptr_eq(&a.pic, &b.pic))
+        {
+            list1.swap(0, 1);
+        }
+        [list0, list1]
+    }
+
+    /// Applies `ref_pic_list_modification` to an initial list, as described in 8.2.4.3.
+    pub fn modify_list(
+        &self,
+        list: &mut Vec<RefPic>,
+        modifications: &[(u32, u32)],
+        num_active: usize,
+        ctx: PicNumContext,
+    ) -> Result<(), H264Error> {
+        list.truncate(num_active);
+        let max_pic_num = ctx.max_frame_num as i32;
+        let curr_pic_num = ctx.frame_num as i32;
+        let mut pic_num_pred = curr_pic_num;
+        for (ref_idx, &(idc, value)) in modifications.iter().enumerate() {
+            let entry = if idc == 2 {
+                self.refs
+                    .iter()
+                    .find(|r| r.long_term_frame_idx == Some(value))
+                    .map(Self::entry)
+            } else {
+                let abs_diff_pic_num = value as i32 + 1;
+                let mut pic_num_no_wrap = if idc == 0 {
+                    pic_num_pred - abs_diff_pic_num
+                } else {
+                    pic_num_pred + abs_diff_pic_num
+                };
+                if pic_num_no_wrap < 0 {
+                    pic_num_no_wrap += max_pic_num;
+                } else if pic_num_no_wrap >= max_pic_num {
+                    pic_num_no_wrap -= max_pic_num;
+                }
+                pic_num_pred = pic_num_no_wrap;
+                let pic_num = if pic_num_no_wrap > curr_pic_num {
+                    pic_num_no_wrap - max_pic_num
+                } else {
+                    pic_num_no_wrap
+                };
+                self.short_term()
+                    .find(|r| ctx.pic_num(r.frame_num) == pic_num)
+                    .map(Self::entry)
+            };
+            let entry = entry.ok_or(H264Error::MissingReferenceFrame)?;
+
+            let ref_idx = ref_idx.min(list.len());
+            let mut index = 0;
+            list.retain(|other| {
+                index += 1;
+                index <= ref_idx || !Rc::ptr_eq(&other.pic, &entry.pic)
+            });
+            list.insert(ref_idx.min(list.len()), entry);
+        }
+        list.truncate(num_active);
+        Ok(())
+    }
+
+    /// Marks the references for an IDR picture, as described in 8.2.5.1.
+    pub fn mark_idr(&mut self, pic: Rc<Picture>, long_term: bool) {
+        self.clear_references();
+        if long_term {
+            self.max_long_term_frame_idx = Some(0);
+        }
+        self.refs.push(StoredRef {
+            pic,
+            frame_num: 0,
+            long_term_frame_idx: long_term.then_some(0),
+        });
+    }
+
+    /// Marks the references for a non-IDR reference picture, as described in 8.2.5.3 and 8.2.5.4.
+    pub fn mark_reference(
+        &mut self,
+        pic: Rc<Picture>,
+        mmcos: Option<&[Mmco]>,
+        ctx: PicNumContext,
+        max_num_ref_frames: usize,
+    ) {
+        let mut long_term_frame_idx = None;
+        match mmcos {
+            Some(mmcos) => {
+                for &mmco in mmcos {
+                    self.apply_mmco(mmco, ctx, &mut long_term_frame_idx);
+                }
+            }
+            None => self.sliding_window(ctx, max_num_ref_frames),
+        }
+        let frame_num =
+            if mmcos.is_some_and(|mmcos| mmcos.iter().any(|m| matches!(m, Mmco::ForgetAll))) {
+                0
+            } else {
+                ctx.frame_num
+            };
+        self.refs.push(StoredRef {
+            pic,
+            frame_num,
+            long_term_frame_idx,
+        });
+        // Streams that keep more references than they declared are broken,
+        // so conceal that by dropping the oldest ones.
+        while self.refs.len() > max_num_ref_frames.max(1) {
+            if !self.remove_oldest_short_term(ctx) {
+                break;
+            }
+        }
+    }
+
+    /// Adds a "non-existing" frame for a gap in `frame_num`, as described in 8.2.5.2.
+    pub fn add_gap_frame(
+        &mut self,
+        pic: Rc<Picture>,
+        ctx: PicNumContext,
+        max_num_ref_frames: usize,
+    ) {
+        self.sliding_window(ctx, max_num_ref_frames);
+        self.refs.push(StoredRef {
+            pic,
+            frame_num: ctx.frame_num,
+            long_term_frame_idx: None,
+        });
+    }
+
+    fn sliding_window(&mut self, ctx: PicNumContext, max_num_ref_frames: usize) {
+        while self.refs.len() >= max_num_ref_frames.max(1) {
+            if !self.remove_oldest_short_term(ctx) {
+                break;
+            }
+        }
+    }
+
+    fn remove_oldest_short_term(&mut self, ctx: PicNumContext) -> bool {
+        let oldest = self
+            .refs
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.long_term_frame_idx.is_none())
+            .min_by_key(|(_, r)| ctx.pic_num(r.frame_num))
+            .map(|(i, _)| i);
+        match oldest {
+            Some(i) => {
+                self.refs.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn apply_mmco(&mut self, mmco: Mmco, ctx: PicNumContext, current_long_term: &mut Option<u32>) {
+        let curr_pic_num = ctx.frame_num as i32;
+        match mmco {
+            Mmco::ForgetShortTerm {
+                difference_of_pic_nums,
+            } => {
+                let pic_num = curr_pic_num - difference_of_pic_nums as i32;
+                self.refs.retain(|r| {
+                    r.long_term_frame_idx.is_some() || ctx.pic_num(r.frame_num) != pic_num
+                });
+            }
+            Mmco::ForgetLongTerm { long_term_pic_num } => {
+                self.refs
+                    .retain(|r| r.long_term_frame_idx != Some(long_term_pic_num));
+            }
+            Mmco::ShortTermToLongTerm {
+                difference_of_pic_nums,
+                long_term_frame_idx,
+            } => {
+                let pic_num = curr_pic_num - difference_of_pic_nums as i32;
+                self.refs
+                    .retain(|r| r.long_term_frame_idx != Some(long_term_frame_idx));
+                if let Some(r) = self.refs.iter_mut().find(|r| {
+                    r.long_term_frame_idx.is_none() && ctx.pic_num(r.frame_num) == pic_num
+                }) {
+                    r.long_term_frame_idx = Some(long_term_frame_idx);
+                }
+            }
+            Mmco::SetMaxLongTermFrameIdx {
+                max_long_term_frame_idx_plus1,
+            } => {
+                self.max_long_term_frame_idx = max_long_term_frame_idx_plus1.checked_sub(1);
+                let max = self.max_long_term_frame_idx;
+                self.refs.retain(|r| match r.long_term_frame_idx {
+                    Some(idx) => max.is_some_and(|max| idx <= max),
+                    None => true,
+                });
+            }
+            Mmco::ForgetAll => {
+                self.clear_references();
+            }
+            Mmco::CurrentToLongTerm {
+                long_term_frame_idx,
+            } => {
+                self.refs
+                    .retain(|r| r.long_term_frame_idx != Some(long_term_frame_idx));
+                *current_long_term = Some(long_term_frame_idx);
+            }
+        }
+    }
+
+    fn short_term(&self) -> impl Iterator<Item = &StoredRef> {
+        self.refs.iter().filter(|r| r.long_term_frame_idx.is_none())
+    }
+
+    fn long_term_sorted(&self) -> impl Iterator<Item = RefPic> + '_ {
+        let mut long_term: Vec<&StoredRef> = self
+            .refs
+            .iter()
+            .filter(|r| r.long_term_frame_idx.is_some())
+            .collect();
+        long_term.sort_by_key(|r| r.long_term_frame_idx);
+        long_term.into_iter().map(Self::entry)
+    }
+
+    fn entry(stored: &StoredRef) -> RefPic {
+        RefPic {
+            pic: stored.pic.clone(),
+            long_term: stored.long_term_frame_idx.is_some(),
+        }
+    }
+
+    /// Starts a new output order, after an IDR picture or a picture order count reset.
+    pub fn start_new_epoch(&mut self, discard_pending: bool) {
+        if discard_pending {
+            self.pending.clear();
+        }
+        self.epoch = self.epoch.wrapping_add(1);
+    }
+
+    /// Queues a decoded picture for output, returning the pictures that are due in output order.
+    pub fn push_output(
+        &mut self,
+        pic: Rc<Picture>,
+        max_reorder: usize,
+        dpb_size: usize,
+    ) -> Vec<Rc<Picture>> {
+        self.pending.push(PendingOutput {
+            epoch: self.epoch,
+            poc: pic.poc,
+            pic,
+        });
+        let mut output = Vec::new();
+        while !self.pending.is_empty()
+            && (self.pending.len() > max_reorder || self.fullness() > dpb_size)
+        {
+            output.push(self.pop_next());
+        }
+        output
+    }
+
+    /// The number of frames held either for reference or for output.
+    fn fullness(&self) -> usize {
+        let waiting = self
+            .pending
+            .iter()
+            .filter(|p| !self.refs.iter().any(|r| Rc::ptr_eq(&r.pic, &p.pic)))
+            .count();
+        self.refs.len() + waiting
+    }
+
+    fn pop_next(&mut self) -> Rc<Picture> {
+        let (index, _) = self
+            .pending
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, p)| (p.epoch.wrapping_sub(self.epoch) as i32, p.poc))
+            .expect("Pending outputs must not be empty");
+        self.pending.remove(index).pic
+    }
+}
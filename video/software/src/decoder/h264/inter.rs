@@ -0,0 +1,125 @@
+use super::picture::Plane;
+use super::slice::Weight;
+
+/// Applies the 6-tap filter of half-sample luma interpolation.
+#[inline]
+fn tap(samples: [i32; 6]) -> i32 {
+    samples[0] - 5 * samples[1] + 20 * samples[2] + 20 * samples[3] - 5 * samples[4] + samples[5]
+}
+
+#[inline]
+fn clip(value: i32) -> i32 {
+    value.clamp(0, 255)
+}
+
+/// Predicts a block of luma samples at a quarter-sample motion vector, as described in 8.4.2.2.1.
+#[allow(clippy::too_many_arguments)]
+pub fn luma(
+    plane: &Plane,
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    mv: [i16; 2],
+    out: &mut [u8],
+) {
+    let x = x + i32::from(mv[0] >> 2);
+    let y = y + i32::from(mv[1] >> 2);
+    let (frac_x, frac_y) = (mv[0] & 3, mv[1] & 3);
+    let full = |dx: i32, dy: i32| i32::from(plane.clamped(x + dx, y + dy));
+    // The intermediate values of the half-sample positions to the right of and below a full sample.
+    let b1 = |dx: i32, dy: i32| tap(std::array::from_fn(|i| full(dx + i as i32 - 2, dy)));
+    let h1 = |dx: i32, dy: i32| tap(std::array::from_fn(|i| full(dx, dy + i as i32 - 2)));
+    let b = |dx: i32, dy: i32| clip((b1(dx, dy) + 16) >> 5);
+    let h = |dx: i32, dy: i32| clip((h1(dx, dy) + 16) >> 5);
+    let j = |dx: i32, dy: i32| {
+        clip((tap(std::array::from_fn(|i| b1(dx, dy + i as i32 - 2))) + 512) >> 10)
+    };
+
+    for row in 0..height {
+        for column in 0..width {
+            let (dx, dy) = (column as i32, row as i32);
+            let value = match (frac_x, frac_y) {
+                (0, 0) => full(dx, dy),
+                (0, 1) => (full(dx, dy) + h(dx, dy) + 1) >> 1,
+                (0, 2) => h(dx, dy),
+                (0, 3) => (full(dx, dy + 1) + h(dx, dy) + 1) >> 1,
+                (1, 0) => (full(dx, dy) + b(dx, dy) + 1) >> 1,
+                (2, 0) => b(dx, dy),
+                (3, 0) => (full(dx + 1, dy) + b(dx, dy) + 1) >> 1,
+                (2, 2) => j(dx, dy),
+                (2, 1) => (b(dx, dy) + j(dx, dy) + 1) >> 1,
+                (2, 3) => (b(dx, dy + 1) + j(dx, dy) + 1) >> 1,
+                (1, 2) => (h(dx, dy) + j(dx, dy) + 1) >> 1,
+                (3, 2) => (h(dx + 1, dy) + j(dx, dy) + 1) >> 1,
+                // The diagonal positions average the nearest horizontal and vertical half samples.
+                (1, 1) => (b(dx, dy) + h(dx, dy) + 1) >> 1,
+                (3, 1) => (b(dx, dy) + h(dx + 1, dy) + 1) >> 1,
+                (1, 3) => (b(dx, dy + 1) + h(dx, dy) + 1) >> 1,
+                _ => (b(dx, dy + 1) + h(dx + 1, dy) + 1) >> 1,
+            };
+            out[row * width + column] = value as u8;
+        }
+    }
+}
+
+/// Predicts a block of chroma samples at an eighth-sample motion vector, as described in 8.4.2.2.2.
+#[allow(clippy::too_many_arguments)]
+pub fn chroma(
+    plane: &Plane,
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    mv: [i16; 2],
+    out: &mut [u8],
+) {
+    let x = x + i32::from(mv[0] >> 3);
+    let y = y + i32::from(mv[1] >> 3);
+    let (frac_x, frac_y) = (i32::from(mv[0] & 7), i32::from(mv[1] & 7));
+    for row in 0..height {
+        for column in 0..width {
+            let (sx, sy) = (x + column as i32, y + row as i32);
+            let a = i32::from(plane.clamped(sx, sy));
+            let b = i32::from(plane.clamped(sx + 1, sy));
+            let c = i32::from(plane.clamped(sx, sy + 1));
+            let d = i32::from(plane.clamped(sx + 1, sy + 1));
+            let value = ((8 - frac_x) * (8 - frac_y) * a
+                + frac_x * (8 - frac_y) * b
+                + (8 - frac_x) * frac_y * c
+                + frac_x * frac_y * d
+                + 32)
+                >> 6;
+            out[row * width + column] = value as u8;
+        }
+    }
+}
+
+/// Averages two predictions into the first, for default bi-prediction.
+pub fn average(dst: &mut [u8], other: &[u8]) {
+    for (a, &b) in dst.iter_mut().zip(other) {
+        *a = ((u16::from(*a) + u16::from(b) + 1) >> 1) as u8;
+    }
+}
+
+/// Applies a weight and offset to a prediction from one list, as described in 8.4.2.3.2.
+pub fn weight(dst: &mut [u8], weight: Weight, log2_denom: u32) {
+    for sample in dst {
+        let value = i32::from(*sample) * weight.weight;
+        let value = if log2_denom >= 1 {
+            ((value + (1 << (log2_denom - 1))) >> log2_denom) + weight.offset
+        } else {
+            value + weight.offset
+        };
+        *sample = clip(value) as u8;
+    }
+}
+
+/// Combines two weighted predictions into the first, as described in 8.4.2.3.2.
+pub fn weight_bi(dst: &mut [u8], other: &[u8], weights: [Weight; 2], log2_denom: u32) {
+    let offset = (weights[0].offset + weights[1].offset + 1) >> 1;
+    for (a, &b) in dst.iter_mut().zip(other) {
+        let value = i32::from(*a) * weights[0].weight + i32::from(b) * weights[1].weight;
+        *a = clip(((value + (1 << log2_denom)) >> (log2_denom + 1)) + offset) as u8;
+    }
+}
@@ -0,0 +1,376 @@
+use super::picture::Plane;
+use super::H264Error;
+
+/// Which neighbouring samples of a block may be used for intra prediction.
+#[derive(Clone, Copy, Debug)]
+pub struct Availability {
+    pub left: bool,
+    pub top: bool,
+    pub top_right: bool,
+    pub top_left: bool,
+}
+
+/// The neighbouring samples of an NxN block.
+struct Neighbours {
+    size: usize,
+    corner: i32,
+    /// The 2N samples above the block and to its top right.
+    top: [i32; 16],
+    left: [i32; 8],
+}
+
+impl Neighbours {
+    fn load(plane: &Plane, x: usize, y: usize, size: usize, availability: Availability) -> Self {
+        let mut neighbours = Self {
+            size,
+            corner: 128,
+            top: [128; 16],
+            left: [128; 8],
+        };
+        let stride = plane.width;
+        if availability.top {
+            let start = (y - 1) * stride + x;
+            for (i, sample) in neighbours.top[..size].iter_mut().enumerate() {
+                *sample = i32::from(plane.data[start + i]);
+            }
+            for i in size..size * 2 {
+                neighbours.top[i] = if availability.top_right {
+                    i32::from(plane.data[start + i])
+                } else {
+                    neighbours.top[size - 1]
+                };
+            }
+        }
+        if availability.left {
+            for (i, sample) in neighbours.left[..size].iter_mut().enumerate() {
+                *sample = i32::from(plane.data[(y + i) * stride + x - 1]);
+            }
+        }
+        if availability.top_left {
+            neighbours.corner = i32::from(plane.data[(y - 1) * stride + x - 1]);
+        }
+        neighbours
+    }
+
+    /// Applies the reference sample filtering of Intra_8x8 prediction, as described in 8.3.2.2.1.
+    fn filter(&mut self, availability: Availability) {
+        let top = self.top;
+        let left = self.left;
+        let corner = self.corner;
+        if availability.top {
+            self.top[0] = if availability.top_left {
+                (corner + 2 * top[0] + top[1] + 2) >> 2
+            } else {
+                (3 * top[0] + top[1] + 2) >> 2
+            };
+            for i in 1..15 {
+                self.top[i] = (top[i - 1] + 2 * top[i] + top[i + 1] + 2) >> 2;
+            }
+            self.top[15] = (top[14] + 3 * top[15] + 2) >> 2;
+        }
+        if availability.top_left {
+            self.corner = match (availability.top, availability.left) {
+                (true, true) => (top[0] + 2 * corner + left[0] + 2) >> 2,
+                (true, false) => (3 * corner + top[0] + 2) >> 2,
+                (false, true) => (3 * corner + left[0] + 2) >> 2,
+                (false, false) => corner,
+            };
+        }
+        if availability.left {
+            self.left[0] = if availability.top_left {
+                (corner + 2 * left[0] + left[1] + 2) >> 2
+            } else {
+                (3 * left[0] + left[1] + 2) >> 2
+            };
+            for i in 1..7 {
+                self.left[i] = (left[i - 1] + 2 * left[i] + left[i + 1] + 2) >> 2;
+            }
+            self.left[7] = (left[6] + 3 * left[7] + 2) >> 2;
+        }
+    }
+
+    /// The neighbouring sample `p[x, y]`, where one of the coordinates is -1.
+    #[inline]
+    fn p(&self, x: i32, y: i32) -> i32 {
+        if y < 0 {
+            if x < 0 {
+                self.corner
+            } else {
+                self.top[x as usize]
+            }
+        } else {
+            self.left[y as usize]
+        }
+    }
+
+    /// Predicts an Intra_4x4 or Intra_8x8 block, as described in 8.3.1.2 and 8.3.2.2.
+    fn predict(&self, mode: u8, availability: Availability, out: &mut [u8]) {
+        let n = self.size as i32;
+        let p = |x: i32, y: i32| self.p(x, y);
+        let dc = {
+            let top: i32 = self.top[..self.size].iter().sum();
+            let left: i32 = self.left[..self.size].iter().sum();
+            let log2 = n.trailing_zeros();
+            match (availability.top, availability.left) {
+                (true, true) => (top + left + n) >> (log2 + 1),
+                (true, false) => (top + n / 2) >> log2,
+                (false, true) => (left + n / 2) >> log2,
+                (false, false) => 128,
+            }
+        };
+        for y in 0..n {
+            for x in 0..n {
+                let value = match mode {
+                    0 => p(x, -1),
+                    1 => p(-1, y),
+                    2 => dc,
+                    3 => {
+                        if x == n - 1 && y == n - 1 {
+                            (p(2 * n - 2, -1) + 3 * p(2 * n - 1, -1) + 2) >> 2
+                        } else {
+                            (p(x + y, -1) + 2 * p(x + y + 1, -1) + p(x + y + 2, -1) + 2) >> 2
+                        }
+                    }
+                    4 => {
+                        if x > y {
+                            (p(x - y - 2, -1) + 2 * p(x - y - 1, -1) + p(x - y, -1) + 2) >> 2
+                        } else if x < y {
+                            (p(-1, y - x - 2) + 2 * p(-1, y - x - 1) + p(-1, y - x) + 2) >> 2
+                        } else {
+                            (p(0, -1) + 2 * p(-1, -1) + p(-1, 0) + 2) >> 2
+                        }
+                    }
+                    5 => {
+                        let z = 2 * x - y;
+                        let i = x - (y >> 1);
+                        if z >= 0 && z % 2 == 0 {
+                            (p(i - 1, -1) + p(i, -1) + 1) >> 1
+                        } else if z >= 0 {
+                            (p(i - 2, -1) + 2 * p(i - 1, -1) + p(i, -1) + 2) >> 2
+                        } else if z == -1 {
+                            (p(-1, 0) + 2 * p(-1, -1) + p(0, -1) + 2) >> 2
+                        } else {
+                            let j = y - 2 * x;
+                            (p(-1, j - 1) + 2 * p(-1, j - 2) + p(-1, j - 3) + 2) >> 2
+                        }
+                    }
+                    6 => {
+                        let z = 2 * y - x;
+                        let j = y - (x >> 1);
+                        if z >= 0 && z % 2 == 0 {
+                            (p(-1, j - 1) + p(-1, j) + 1) >> 1
+                        } else if z >= 0 {
+                            (p(-1, j - 2) + 2 * p(-1, j - 1) + p(-1, j) + 2) >> 2
+                        } else if z == -1 {
+                            (p(-1, 0) + 2 * p(-1, -1) + p(0, -1) + 2) >> 2
+                        } else {
+                            let i = x - 2 * y;
+                            (p(i - 1, -1) + 2 * p(i - 2, -1) + p(i - 3, -1) + 2) >> 2
+                        }
+                    }
+                    7 => {
+                        let i = x + (y >> 1);
+                        if y % 2 == 0 {
+                            (p(i, -1) + p(i + 1, -1) + 1) >> 1
+                        } else {
+                            (p(i, -1) + 2 * p(i + 1, -1) + p(i + 2, -1) + 2) >> 2
+                        }
+                    }
+                    _ => {
+                        let z = x + 2 * y;
+                        let j = y + (x >> 1);
+                        if z > 2 * n - 3 {
+                            p(-1, n - 1)
+                        } else if z == 2 * n - 3 {
+                            (p(-1, n - 2) + 3 * p(-1, n - 1) + 2) >> 2
+                        } else if z % 2 == 0 {
+                            (p(-1, j) + p(-1, j + 1) + 1) >> 1
+                        } else {
+                            (p(-1, j) + 2 * p(-1, j + 1) + p(-1, j + 2) + 2) >> 2
+                        }
+                    }
+                };
+                out[(y * n + x) as usize] = value as u8;
+            }
+        }
+    }
+}
+
+fn store(plane: &mut Plane, x: usize, y: usize, size: usize, samples: &[u8]) {
+    for (row, samples) in samples.chunks_exact(size).enumerate() {
+        let start = (y + row) * plane.width + x;
+        plane.data[start..start + size].copy_from_slice(samples);
+    }
+}
+
+pub fn predict_4x4(plane: &mut Plane, x: usize, y: usize, mode: u8, availability: Availability) {
+    let neighbours = Neighbours::load(plane, x, y, 4, availability);
+    let mut out = [0; 16];
+    neighbours.predict(mode, availability, &mut out);
+    store(plane, x, y, 4, &out);
+}
+
+pub fn predict_8x8(plane: &mut Plane, x: usize, y: usize, mode: u8, availability: Availability) {
+    let mut neighbours = Neighbours::load(plane, x, y, 8, availability);
+    neighbours.filter(availability);
+    let mut out = [0; 64];
+    neighbours.predict(mode, availability, &mut out);
+    store(plane, x, y, 8, &out);
+}
+
+/// Plane prediction of a `size`x`size` block, as described in 8.3.3.4 and 8.3.4.4.
+fn predict_plane(plane: &mut Plane, x: usize, y: usize, size: usize) {
+    let stride = plane.width;
+    let top = |i: i32| i32::from(plane.data[(y - 1) * stride + (x as i32 + i) as usize]);
+    let left = |i: i32| i32::from(plane.data[(y as i32 + i) as usize * stride + x - 1]);
+    let half = size as i32 / 2;
+    let mut h = 0;
+    let mut v = 0;
+    for i in 0..half {
+        h += (i + 1) * (top(half + i) - top(half - 2 - i));
+        v += (i + 1) * (left(half + i) - left(half - 2 - i));
+    }
+    let (b, c) = if size == 16 {
+        ((5 * h + 32) >> 6, (5 * v + 32) >> 6)
+    } else {
+        ((34 * h + 32) >> 6, (34 * v + 32) >> 6)
+    };
+    let a = 16 * (left(size as i32 - 1) + top(size as i32 - 1));
+    for row in 0..size as i32 {
+        for column in 0..size as i32 {
+            let value = (a + b * (column - half + 1) + c * (row - half + 1) + 16) >> 5;
+            plane.data[(y + row as usize) * stride + x + column as usize] =
+                value.clamp(0, 255) as u8;
+        }
+    }
+}
+
+/// Fills a block with the samples above it, or to its left.
+fn predict_copy(plane: &mut Plane, x: usize, y: usize, size: usize, vertical: bool) {
+    let stride = plane.width;
+    for row in 0..size {
+        for column in 0..size {
+            plane.data[(y + row) * stride + x + column] = if vertical {
+                plane.data[(y - 1) * stride + x + column]
+            } else {
+                plane.data[(y + row) * stride + x - 1]
+            };
+        }
+    }
+}
+
+/// The DC prediction of a 4x4 block from the given neighbours, each of which is
+/// only used if available.
+fn dc_4x4(plane: &Plane, x: usize, y: usize, top: bool, left: bool) -> Option<u8> {
+    let stride = plane.width;
+    let sum_top = || -> u32 {
+        (0..4)
+            .map(|i| u32::from(plane.data[(y - 1) * stride + x + i]))
+            .sum()
+    };
+    let sum_left = || -> u32 {
+        (0..4)
+            .map(|i| u32::from(plane.data[(y + i) * stride + x - 1]))
+            .sum()
+    };
+    match (top, left) {
+        (true, true) => Some(((sum_top() + sum_left() + 4) >> 3) as u8),
+        (true, false) => Some(((sum_top() + 2) >> 2) as u8),
+        (false, true) => Some(((sum_left() + 2) >> 2) as u8),
+        (false, false) => None,
+    }
+}
+
+/// Checks that a vertical, horizontal or plane prediction mode only uses available samples.
+fn check_available(
+    vertical: bool,
+    horizontal: bool,
+    availability: Availability,
+) -> Result<(), H264Error> {
+    let plane = vertical && horizontal;
+    if (vertical && !availability.top)
+        || (horizontal && !availability.left)
+        || (plane && !availability.top_left)
+    {
+        return Err(H264Error::InvalidData(
+            "intra prediction mode uses unavailable samples",
+        ));
+    }
+    Ok(())
+}
+
+pub fn predict_16x16(
+    plane: &mut Plane,
+    x: usize,
+    y: usize,
+    mode: u8,
+    availability: Availability,
+) -> Result<(), H264Error> {
+    check_available(mode == 0 || mode == 3, mode == 1 || mode == 3, availability)?;
+    match mode {
+        0 => predict_copy(plane, x, y, 16, true),
+        1 => predict_copy(plane, x, y, 16, false),
+        2 => {
+            let stride = plane.width;
+            let sum_top = || -> u32 {
+                (0..16)
+                    .map(|i| u32::from(plane.data[(y - 1) * stride + x + i]))
+                    .sum()
+            };
+            let sum_left = || -> u32 {
+                (0..16)
+                    .map(|i| u32::from(plane.data[(y + i) * stride + x - 1]))
+                    .sum()
+            };
+            let dc = match (availability.top, availability.left) {
+                (true, true) => (sum_top() + sum_left() + 16) >> 5,
+                (true, false) => (sum_top() + 8) >> 4,
+                (false, true) => (sum_left() + 8) >> 4,
+                (false, false) => 128,
+            } as u8;
+            for row in 0..16 {
+                let start = (y + row) * stride + x;
+                plane.data[start..start + 16].fill(dc);
+            }
+        }
+        _ => predict_plane(plane, x, y, 16),
+    }
+    Ok(())
+}
+
+pub fn predict_chroma(
+    plane: &mut Plane,
+    x: usize,
+    y: usize,
+    mode: u8,
+    availability: Availability,
+) -> Result<(), H264Error> {
+    check_available(mode == 2 || mode == 3, mode == 1 || mode == 3, availability)?;
+    match mode {
+        0 => {
+            let stride = plane.width;
+            for block in 0..4 {
+                let (bx, by) = (x + (block % 2) * 4, y + (block / 2) * 4);
+                let (top, left) = (availability.top, availability.left);
+                // The blocks on the right edge prefer the top neighbours,
+                // those on the bottom edge prefer the left ones.
+                let dc = match block {
+                    1 => dc_4x4(plane, bx, by, top, false)
+                        .or_else(|| dc_4x4(plane, bx, by, false, left)),
+                    2 => dc_4x4(plane, bx, by, false, left)
+                        .or_else(|| dc_4x4(plane, bx, by, top, false)),
+                    _ => dc_4x4(plane, bx, by, top, left),
+                }
+                .unwrap_or(128);
+                for row in 0..4 {
+                    let start = (by + row) * stride + bx;
+                    plane.data[start..start + 4].fill(dc);
+                }
+            }
+        }
+        1 => predict_copy(plane, x, y, 8, false),
+        2 => predict_copy(plane, x, y, 8, true),
+        _ => predict_plane(plane, x, y, 8),
+    }
+    Ok(())
+}
@@ -0,0 +1,1008 @@
+use super::dpb::RefPic;
+use super::intra::{self, Availability};
+use super::params::{Pps, Sps};
+use super::picture::{MbInfo, MbKind, Picture};
+use super::slice::{SliceHeader, SliceType};
+use super::tables::{chroma_qp, BLOCK_RASTER};
+use super::transform::{self, Dequant};
+use super::H264Error;
+
+pub const PRED_L0: u8 = 1;
+pub const PRED_L1: u8 = 2;
+const PRED_BI: u8 = PRED_L0 | PRED_L1;
+
+/// A block of residual coefficients, identified well enough to select its coding contexts.
+#[derive(Clone, Copy, Debug)]
+pub enum ResidualBlock {
+    LumaDc,
+    /// The AC coefficients of an Intra_16x16 block, at a raster position.
+    LumaAc(usize),
+    /// A 4x4 luma block at a raster position.
+    Luma4x4(usize),
+    /// An 8x8 luma block, only coded as such with CABAC.
+    Luma8x8(usize),
+    /// The DC coefficients of a chroma component.
+    ChromaDc(usize),
+    /// The AC coefficients of a chroma component's 4x4 block at a raster position.
+    ChromaAc(usize, usize),
+}
+
+/// Reads the syntax elements of macroblocks, with either CAVLC or CABAC.
+///
+/// Elements whose coding depends on the neighbouring macroblocks are given the slice decoder.
+pub trait EntropyReader {
+    fn mb_skip(&mut self, d: &SliceDecoder) -> Result<bool, H264Error>;
+
+    /// Whether the slice ends after the current macroblock.
+    fn end_of_slice(&mut self) -> Result<bool, H264Error>;
+
+    /// Reads `mb_type`, numbered as in the tables for the slice type.
+    fn mb_type(&mut self, d: &SliceDecoder) -> Result<u32, H264Error>;
+
+    fn sub_mb_type(&mut self, d: &SliceDecoder) -> Result<u32, H264Error>;
+
+    fn transform_size_8x8_flag(&mut self, d: &SliceDecoder) -> Result<bool, H264Error>;
+
+    /// Reads `prev_intra_pred_mode_flag` and `rem_intra_pred_mode`,
+    /// returning the latter if the predicted mode is not used.
+    fn intra_pred_mode(&mut self) -> Result<Option<u8>, H264Error>;
+
+    fn intra_chroma_pred_mode(&mut self, d: &SliceDecoder) -> Result<u8, H264Error>;
+
+    /// Reads the reference index of the partition whose top left 4x4 block is at raster position `block`.
+    fn ref_idx(
+        &mut self,
+        d: &SliceDecoder,
+        list: usize,
+        block: usize,
+        num_active: usize,
+    ) -> Result<u32, H264Error>;
+
+    /// Reads a motion vector difference component of the partition whose top left
+    /// 4x4 block is at raster position `block`.
+    fn mvd(
+        &mut self,
+        d: &SliceDecoder,
+        list: usize,
+        block: usize,
+        component: usize,
+    ) -> Result<i32, H264Error>;
+
+    fn coded_block_pattern(&mut self, d: &SliceDecoder) -> Result<u8, H264Error>;
+
+    fn mb_qp_delta(&mut self, d: &SliceDecoder) -> Result<i32, H264Error>;
+
+    /// Reads the coefficients of a block in scanning order, returning how many are non-zero.
+    fn residual_block(
+        &mut self,
+        d: &SliceDecoder,
+        block: ResidualBlock,
+        coeffs: &mut [i32],
+    ) -> Result<u8, H264Error>;
+
+    /// Reads the samples of an I_PCM macroblock: 256 luma samples, then 64 of Cb and 64 of Cr.
+    fn pcm_samples(&mut self, samples: &mut [u8; 384]) -> Result<(), H264Error>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    S16x16,
+    S16x8,
+    S8x16,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum MbType {
+    INxN,
+    I16x16 {
+        pred_mode: u8,
+        cbp: u8,
+    },
+    IPcm,
+    /// A P or B macroblock of one or two partitions, with the lists that each partition predicts from.
+    Inter {
+        shape: Shape,
+        pred: [u8; 2],
+    },
+    P8x8 {
+        ref0: bool,
+    },
+    B8x8,
+    BDirect16x16,
+}
+
+/// The shapes and prediction lists of B macroblock types 1 to 21.
+const B_MB_TYPES: [(Shape, [u8; 2]); 21] = [
+    (Shape::S16x16, [PRED_L0, 0]),
+    (Shape::S16x16, [PRED_L1, 0]),
+    (Shape::S16x16, [PRED_BI, 0]),
+    (Shape::S16x8, [PRED_L0, PRED_L0]),
+    (Shape::S8x16, [PRED_L0, PRED_L0]),
+    (Shape::S16x8, [PRED_L1, PRED_L1]),
+    (Shape::S8x16, [PRED_L1, PRED_L1]),
+    (Shape::S16x8, [PRED_L0, PRED_L1]),
+    (Shape::S8x16, [PRED_L0, PRED_L1]),
+    (Shape::S16x8, [PRED_L1, PRED_L0]),
+    (Shape::S8x16, [PRED_L1, PRED_L0]),
+    (Shape::S16x8, [PRED_L0, PRED_BI]),
+    (Shape::S8x16, [PRED_L0, PRED_BI]),
+    (Shape::S16x8, [PRED_L1, PRED_BI]),
+    (Shape::S8x16, [PRED_L1, PRED_BI]),
+    (Shape::S16x8, [PRED_BI, PRED_L0]),
+    (Shape::S8x16, [PRED_BI, PRED_L0]),
+    (Shape::S16x8, [PRED_BI, PRED_L1]),
+    (Shape::S8x16, [PRED_BI, PRED_L1]),
+    (Shape::S16x8, [PRED_BI, PRED_BI]),
+    (Shape::S8x16, [PRED_BI, PRED_BI]),
+];
+
+/// The partition width and height (in 4x4 blocks) and prediction lists of P sub-macroblock types.
+const P_SUB_MB_TYPES: [(usize, usize, u8); 4] = [
+    (2, 2, PRED_L0),
+    (2, 1, PRED_L0),
+    (1, 2, PRED_L0),
+    (1, 1, PRED_L0),
+];
+
+/// Like `P_SUB_MB_TYPES`, for B sub-macroblock types. Type 0 is B_Direct_8x8.
+const B_SUB_MB_TYPES: [(usize, usize, u8); 13] = [
+    (2, 2, 0),
+    (2, 2, PRED_L0),
+    (2, 2, PRED_L1),
+    (2, 2, PRED_BI),
+    (2, 1, PRED_L0),
+    (1, 2, PRED_L0),
+    (2, 1, PRED_L1),
+    (1, 2, PRED_L1),
+    (2, 1, PRED_BI),
+    (1, 2, PRED_BI),
+    (1, 1, PRED_L0),
+    (1, 1, PRED_L1),
+    (1, 1, PRED_BI),
+];
+
+impl MbType {
+    fn new(slice_type: SliceType, raw: u32) -> Result<Self, H264Error> {
+        match slice_type {
+            SliceType::I => Self::intra(raw),
+            SliceType::P => match raw {
+                0 => Ok(Self::Inter {
+                    shape: Shape::S16x16,
+                    pred: [PRED_L0, 0],
+                }),
+                1 => Ok(Self::Inter {
+                    shape: Shape::S16x8,
+                    pred: [PRED_L0, PRED_L0],
+                }),
+                2 => Ok(Self::Inter {
+                    shape: Shape::S8x16,
+                    pred: [PRED_L0, PRED_L0],
+                }),
+                3 => Ok(Self::P8x8 { ref0: false }),
+                4 => Ok(Self::P8x8 { ref0: true }),
+                _ => Self::intra(raw - 5),
+            },
+            SliceType::B => match raw {
+                0 => Ok(Self::BDirect16x16),
+                1..=21 => {
+                    let (shape, pred) = B_MB_TYPES[raw as usize - 1];
+                    Ok(Self::Inter { shape, pred })
+                }
+                22 => Ok(Self::B8x8),
+                _ => Self::intra(raw - 23),
+            },
+        }
+    }
+
+    fn intra(raw: u32) -> Result<Self, H264Error> {
+        match raw {
+            0 => Ok(Self::INxN),
+            1..=24 => {
+                let index = raw - 1;
+                let luma = if index >= 12 { 15 } else { 0 };
+                let chroma = (index / 4) % 3;
+                Ok(Self::I16x16 {
+                    pred_mode: (index % 4) as u8,
+                    cbp: (luma | (chroma << 4)) as u8,
+                })
+            }
+            25 => Ok(Self::IPcm),
+            _ => Err(H264Error::InvalidData("mb_type")),
+        }
+    }
+}
+
+/// The residual coefficients of a macroblock, in scanning order.
+pub struct Residual {
+    luma_dc: [i32; 16],
+    /// 4x4 blocks by raster position. Intra_16x16 AC coefficients start at index 1.
+    luma: [[i32; 16]; 16],
+    luma_8x8: [[i32; 64]; 4],
+    chroma_dc: [[i32; 4]; 2],
+    /// Chroma blocks by component and raster position, starting at index 1.
+    chroma_ac: [[[i32; 16]; 4]; 2],
+}
+
+impl Default for Residual {
+    fn default() -> Self {
+        Self {
+            luma_dc: [0; 16],
+            luma: [[0; 16]; 16],
+            luma_8x8: [[0; 64]; 4],
+            chroma_dc: [[0; 4]; 2],
+            chroma_ac: [[[0; 16]; 4]; 2],
+        }
+    }
+}
+
+/// Decodes the macroblocks of one slice into a picture.
+pub struct SliceDecoder<'a> {
+    pub sps: &'a Sps,
+    pub pps: &'a Pps,
+    pub header: &'a SliceHeader,
+    pub pic: &'a mut Picture,
+    pub ref_lists: &'a [Vec<RefPic>; 2],
+    /// The number of this slice within the picture, starting at 1.
+    pub slice_num: u32,
+    pub mb_addr: usize,
+    pub mb_x: usize,
+    pub mb_y: usize,
+    /// `QPY` of the current macroblock.
+    pub qp: i32,
+    /// `mb_qp_delta` of the previous macroblock in decoding order, or zero if it had none.
+    pub last_qp_delta: i32,
+    dequant: Dequant,
+    residual: Box<Residual>,
+}
+
+impl<'a> SliceDecoder<'a> {
+    pub fn new(
+        sps: &'a Sps,
+        pps: &'a Pps,
+        header: &'a SliceHeader,
+        pic: &'a mut Picture,
+        ref_lists: &'a [Vec<RefPic>; 2],
+        slice_num: u32,
+    ) -> Self {
+        Self {
+            sps,
+            pps,
+            header,
+            pic,
+            ref_lists,
+            slice_num,
+            mb_addr: header.first_mb,
+            mb_x: 0,
+            mb_y: 0,
+            qp: header.qp,
+            last_qp_delta: 0,
+            dequant: Dequant::new(&pps.scaling_lists),
+            residual: Box::default(),
+        }
+    }
+
+    /// Decodes macroblocks until the end of the slice.
+    pub fn decode<R: EntropyReader>(&mut self, reader: &mut R) -> Result<(), H264Error> {
+        let mut addr = self.header.first_mb;
+        loop {
+            if addr >= self.pic.mbs.len() {
+                return Err(H264Error::InvalidData(
+                    "slice extends past the end of the picture",
+                ));
+            }
+            self.mb_addr = addr;
+            self.mb_x = addr % self.pic.width_mbs;
+            self.mb_y = addr / self.pic.width_mbs;
+            let skipped = self.header.slice_type != SliceType::I && reader.mb_skip(self)?;
+            self.decode_macroblock(reader, skipped)?;
+            if reader.end_of_slice()? {
+                return Ok(());
+            }
+            addr += 1;
+        }
+    }
+
+    pub fn cur_mb(&self) -> &MbInfo {
+        &self.pic.mbs[self.mb_addr]
+    }
+
+    pub fn cur_mb_mut(&mut self) -> &mut MbInfo {
+        &mut self.pic.mbs[self.mb_addr]
+    }
+
+    /// Returns the address of a neighbouring macroblock if it is available,
+    /// meaning that it has been decoded as part of the current slice.
+    pub fn neighbour_mb(&self, dx: i32, dy: i32) -> Option<usize> {
+        let x = self.mb_x as i32 + dx;
+        let y = self.mb_y as i32 + dy;
+        if x < 0 || y < 0 || x >= self.pic.width_mbs as i32 {
+            return None;
+        }
+        let addr = y as usize * self.pic.width_mbs + x as usize;
+        (addr < self.mb_addr && self.pic.mbs[addr].slice == self.slice_num).then_some(addr)
+    }
+
+    /// Locates the 4x4 luma block covering a luma position relative to the current macroblock,
+    /// returning the address of its macroblock and its raster position within it.
+    ///
+    /// This is the process of 6.4.12, for positions up to one sample outside of the macroblock.
+    pub fn luma_neighbour(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        self.neighbour_block(x, y, 16)
+    }
+
+    /// Like `luma_neighbour`, for the 4x4 blocks of an 8x8 chroma macroblock.
+    pub fn chroma_neighbour(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        self.neighbour_block(x, y, 8)
+    }
+
+    fn neighbour_block(&self, x: i32, y: i32, size: i32) -> Option<(usize, usize)> {
+        let dx = x.div_euclid(size);
+        let dy = y.div_euclid(size);
+        if dy > 0 || (dy == 0 && dx > 0) {
+            return None;
+        }
+        let addr = if dx == 0 && dy == 0 {
+            self.mb_addr
+        } else {
+            self.neighbour_mb(dx, dy)?
+        };
+        let blocks_per_row = size / 4;
+        let raster = (y.rem_euclid(size) / 4) * blocks_per_row + x.rem_euclid(size) / 4;
+        Some((addr, raster as usize))
+    }
+
+    /// Whether a neighbouring macroblock may be used for intra prediction.
+    fn intra_mb_available(&self, dx: i32, dy: i32) -> bool {
+        self.neighbour_mb(dx, dy).is_some_and(|addr| {
+            !self.pps.constrained_intra_pred || self.pic.mbs[addr].kind.is_intra()
+        })
+    }
+
+    fn decode_macroblock<R: EntropyReader>(
+        &mut self,
+        reader: &mut R,
+        skipped: bool,
+    ) -> Result<(), H264Error> {
+        *self.cur_mb_mut() = MbInfo {
+            slice: self.slice_num,
+            ..MbInfo::default()
+        };
+
+        if skipped {
+            if self.header.slice_type == SliceType::B {
+                let mb = self.cur_mb_mut();
+                mb.kind = MbKind::BSkip;
+                mb.direct = 0xf;
+                self.predict_direct(0xf)?;
+            } else {
+                self.cur_mb_mut().kind = MbKind::PSkip;
+                self.predict_p_skip()?;
+            }
+            self.last_qp_delta = 0;
+            self.store_qp();
+            self.inter_prediction()?;
+            return Ok(());
+        }
+
+        let mb_type = MbType::new(self.header.slice_type, reader.mb_type(self)?)?;
+        let mut no_sub_8x8_smaller_than_8x8 = true;
+        match mb_type {
+            MbType::IPcm => return self.decode_pcm(reader),
+            MbType::INxN => {
+                let transform_8x8 =
+                    self.pps.transform_8x8_mode && reader.transform_size_8x8_flag(self)?;
+                let mb = self.cur_mb_mut();
+                mb.transform_8x8 = transform_8x8;
+                mb.kind = if transform_8x8 {
+                    MbKind::I8x8
+                } else {
+                    MbKind::I4x4
+                };
+                self.parse_intra_modes(reader, transform_8x8)?;
+                self.cur_mb_mut().intra_chroma_mode = reader.intra_chroma_pred_mode(self)?;
+            }
+            MbType::I16x16 { pred_mode, .. } => {
+                let mb = self.cur_mb_mut();
+                mb.kind = MbKind::I16x16;
+                mb.intra_modes = [pred_mode; 16];
+                self.cur_mb_mut().intra_chroma_mode = reader.intra_chroma_pred_mode(self)?;
+            }
+            MbType::BDirect16x16 => {
+                let mb = self.cur_mb_mut();
+                mb.kind = MbKind::BDirect16x16;
+                mb.direct = 0xf;
+                self.predict_direct(0xf)?;
+                no_sub_8x8_smaller_than_8x8 = self.sps.direct_8x8_inference;
+            }
+            MbType::Inter { shape, pred } => {
+                self.cur_mb_mut().kind = MbKind::Inter;
+                self.parse_partitions(reader, shape, pred)?;
+            }
+            MbType::P8x8 { ref0 } => {
+                self.cur_mb_mut().kind = MbKind::Inter;
+                no_sub_8x8_smaller_than_8x8 = self.parse_sub_mbs(reader, ref0)?;
+            }
+            MbType::B8x8 => {
+                self.cur_mb_mut().kind = MbKind::Inter;
+                no_sub_8x8_smaller_than_8x8 = self.parse_sub_mbs(reader, false)?;
+            }
+        }
+
+        let is_i16x16 = matches!(mb_type, MbType::I16x16 { .. });
+        let cbp = match mb_type {
+            MbType::I16x16 { cbp, .. } => cbp,
+            _ => reader.coded_block_pattern(self)?,
+        };
+        self.cur_mb_mut().cbp = cbp;
+        let is_inter = !self.cur_mb().kind.is_intra();
+        if is_inter
+            && cbp & 15 != 0
+            && self.pps.transform_8x8_mode
+            && no_sub_8x8_smaller_than_8x8
+            && reader.transform_size_8x8_flag(self)?
+        {
+            self.cur_mb_mut().transform_8x8 = true;
+        }
+
+        let mut residual = std::mem::take(&mut self.residual);
+        if cbp != 0 || is_i16x16 {
+            let qp_delta = reader.mb_qp_delta(self)?;
+            if !(-26..=25).contains(&qp_delta) {
+                return Err(H264Error::InvalidData("mb_qp_delta"));
+            }
+            self.qp = (self.qp + qp_delta + 52) % 52;
+            self.last_qp_delta = qp_delta;
+            self.store_qp();
+            *residual = Residual::default();
+            self.parse_residual(reader, is_i16x16, &mut residual)?;
+        } else {
+            self.last_qp_delta = 0;
+            self.store_qp();
+        }
+
+        if is_inter {
+            self.inter_prediction()?;
+        }
+        self.reconstruct_luma(&residual)?;
+        self.reconstruct_chroma(&residual)?;
+        self.residual = residual;
+        Ok(())
+    }
+
+    fn store_qp(&mut self) {
+        let qp = self.qp;
+        let offsets = self.pps.chroma_qp_index_offset;
+        let mb = self.cur_mb_mut();
+        mb.qp = qp as u8;
+        mb.qpc = offsets.map(|offset| chroma_qp(qp, offset));
+    }
+
+    fn decode_pcm<R: EntropyReader>(&mut self, reader: &mut R) -> Result<(), H264Error> {
+        let mut samples = [0; 384];
+        reader.pcm_samples(&mut samples)?;
+        let offsets = self.pps.chroma_qp_index_offset;
+        let mb = self.cur_mb_mut();
+        mb.kind = MbKind::IPcm;
+        // Deblocking treats I_PCM macroblocks as having a QP of zero.
+        mb.qp = 0;
+        mb.qpc = offsets.map(|offset| chroma_qp(0, offset));
+        mb.nz = [16; 24];
+        mb.coded_dc = 0b111;
+        mb.cbp = 0x2f;
+        self.last_qp_delta = 0;
+
+        let (x, y) = (self.mb_x, self.mb_y);
+        let luma = &mut self.pic.luma;
+        for (row, chunk) in samples[..256].chunks_exact(16).enumerate() {
+            let start = (y * 16 + row) * luma.width + x * 16;
+            luma.data[start..start + 16].copy_from_slice(chunk);
+        }
+        for (plane, chunk) in [&mut self.pic.cb, &mut self.pic.cr]
+            .into_iter()
+            .zip(samples[256..].chunks_exact(64))
+        {
+            for (row, chunk) in chunk.chunks_exact(8).enumerate() {
+                let start = (y * 8 + row) * plane.width + x * 8;
+                plane.data[start..start + 8].copy_from_slice(chunk);
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_intra_modes<R: EntropyReader>(
+        &mut self,
+        reader: &mut R,
+        transform_8x8: bool,
+    ) -> Result<(), H264Error> {
+        if transform_8x8 {
+            for block in 0..4 {
+                let (x, y) = ((block % 2) * 8, (block / 2) * 8);
+                let mode = self.read_intra_mode(reader, x, y)?;
+                for raster in [0, 1, 4, 5] {
+                    self.cur_mb_mut().intra_modes[(y / 4) * 4 + x / 4 + raster] = mode;
+                }
+            }
+        } else {
+            for &raster in &BLOCK_RASTER {
+                let (x, y) = ((raster % 4) * 4, (raster / 4) * 4);
+                let mode = self.read_intra_mode(reader, x, y)?;
+                self.cur_mb_mut().intra_modes[raster] = mode;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the prediction mode of the Intra4x4 or Intra8x8 block at a luma position, as in 8.3.1.1.
+    fn read_intra_mode<R: EntropyReader>(
+        &mut self,
+        reader: &mut R,
+        x: usize,
+        y: usize,
+    ) -> Result<u8, H264Error> {
+        let mode_of = |neighbour: Option<(usize, usize)>| {
+            let (addr, raster) = neighbour?;
+            let mb = &self.pic.mbs[addr];
+            match mb.kind {
+                MbKind::I4x4 | MbKind::I8x8 => Some(mb.intra_modes[raster]),
+                kind if !kind.is_intra() && self.pps.constrained_intra_pred => None,
+                _ => Some(2),
+            }
+        };
+        let (x, y) = (x as i32, y as i32);
+        let predicted = match (
+            mode_of(self.luma_neighbour(x - 1, y)),
+            mode_of(self.luma_neighbour(x, y - 1)),
+        ) {
+            (Some(a), Some(b)) => a.min(b),
+            _ => 2,
+        };
+        Ok(match reader.intra_pred_mode()? {
+            None => predicted,
+            Some(rem) if rem < predicted => rem,
+            Some(rem) => rem + 1,
+        })
+    }
+
+    fn read_ref_idx<R: EntropyReader>(
+        &mut self,
+        reader: &mut R,
+        list: usize,
+        block: usize,
+    ) -> Result<i8, H264Error> {
+        let num_active = self.header.num_ref_idx_active[list];
+        let ref_idx = if num_active > 1 {
+            reader.ref_idx(self, list, block, num_active)?
+        } else {
+            0
+        };
+        if ref_idx as usize >= num_active || ref_idx as usize >= self.ref_lists[list].len() {
+            return Err(H264Error::MissingReferenceFrame);
+        }
+        Ok(ref_idx as i8)
+    }
+
+    /// Stores the reference index of the 8x8 partitions covered by a rectangle of 4x4 blocks.
+    fn set_ref(&mut self, list: usize, x4: usize, y4: usize, w4: usize, h4: usize, ref_idx: i8) {
+        let pic_id = if ref_idx >= 0 {
+            self.ref_lists[list][ref_idx as usize].pic.id
+        } else {
+            -1
+        };
+        let mb = &mut self.pic.mbs[self.mb_addr];
+        for y in (y4..y4 + h4).step_by(2) {
+            for x in (x4..x4 + w4).step_by(2) {
+                let block = (y / 2) * 2 + x / 2;
+                mb.ref_idx[list][block] = ref_idx;
+                mb.ref_pic[list][block] = pic_id;
+            }
+        }
+    }
+
+    /// Reads the motion vector difference of a partition, and stores its motion vector.
+    #[allow(clippy::too_many_arguments)]
+    fn read_motion_vector<R: EntropyReader>(
+        &mut self,
+        reader: &mut R,
+        list: usize,
+        x4: usize,
+        y4: usize,
+        w4: usize,
+        h4: usize,
+        decoded: &mut [bool; 16],
+    ) -> Result<(), H264Error> {
+        let block = y4 * 4 + x4;
+        let mvd = [
+            reader.mvd(self, list, block, 0)?,
+            reader.mvd(self, list, block, 1)?,
+        ];
+        let ref_idx = self.cur_mb().ref_idx[list][(y4 / 2) * 2 + x4 / 2];
+        let mvp = self.predict_mv(list, x4, y4, w4, h4, ref_idx, decoded);
+        let mv = [
+            (i32::from(mvp[0]) + mvd[0]) as i16,
+            (i32::from(mvp[1]) + mvd[1]) as i16,
+        ];
+        let abs_mvd = mvd.map(|d| d.unsigned_abs().min(255) as u8);
+        let mb = &mut self.pic.mbs[self.mb_addr];
+        for y in y4..y4 + h4 {
+            for x in x4..x4 + w4 {
+                mb.mv[list][y * 4 + x] = mv;
+                mb.mvd[list][y * 4 + x] = abs_mvd;
+                decoded[y * 4 + x] = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_partitions<R: EntropyReader>(
+        &mut self,
+        reader: &mut R,
+        shape: Shape,
+        pred: [u8; 2],
+    ) -> Result<(), H264Error> {
+        let partitions: &[(usize, usize, usize, usize)] = match shape {
+            Shape::S16x16 => &[(0, 0, 4, 4)],
+            Shape::S16x8 => &[(0, 0, 4, 2), (0, 2, 4, 2)],
+            Shape::S8x16 => &[(0, 0, 2, 4), (2, 0, 2, 4)],
+        };
+        for list in 0..2 {
+            for (&(x4, y4, w4, h4), &pred) in partitions.iter().zip(&pred) {
+                if pred & (1 << list) != 0 {
+                    let ref_idx = self.read_ref_idx(reader, list, y4 * 4 + x4)?;
+                    self.set_ref(list, x4, y4, w4, h4, ref_idx);
+                }
+            }
+        }
+        for list in 0..2 {
+            let mut decoded = [false; 16];
+            for (&(x4, y4, w4, h4), &pred) in partitions.iter().zip(&pred) {
+                if pred & (1 << list) != 0 {
+                    self.read_motion_vector(reader, list, x4, y4, w4, h4, &mut decoded)?;
+                } else {
+                    mark_decoded(&mut decoded, x4, y4, w4, h4);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses the sub-macroblocks of a P_8x8 or B_8x8 macroblock.
+    ///
+    /// Returns whether none of them is split into partitions smaller than 8x8.
+    fn parse_sub_mbs<R: EntropyReader>(
+        &mut self,
+        reader: &mut R,
+        ref0: bool,
+    ) -> Result<bool, H264Error> {
+        let is_b = self.header.slice_type == SliceType::B;
+        let mut sub_types = [(0, 0, 0); 4];
+        let mut direct = 0;
+        let mut no_smaller_than_8x8 = true;
+        for (i, sub_type) in sub_types.iter_mut().enumerate() {
+            let raw = reader.sub_mb_type(self)? as usize;
+            *sub_type = if is_b {
+                *B_SUB_MB_TYPES
+                    .get(raw)
+                    .ok_or(H264Error::InvalidData("sub_mb_type"))?
+            } else {
+                *P_SUB_MB_TYPES
+                    .get(raw)
+                    .ok_or(H264Error::InvalidData("sub_mb_type"))?
+            };
+            if is_b && raw == 0 {
+                direct |= 1 << i;
+                no_smaller_than_8x8 &= self.sps.direct_8x8_inference;
+            } else {
+                no_smaller_than_8x8 &= sub_type.0 == 2 && sub_type.1 == 2;
+            }
+        }
+        self.cur_mb_mut().direct = direct;
+        if direct != 0 {
+            self.predict_direct(direct)?;
+        }
+
+        for list in 0..2 {
+            for (i, &(_, _, pred)) in sub_types.iter().enumerate() {
+                if direct & (1 << i) != 0 || pred & (1 << list) == 0 {
+                    continue;
+                }
+                let (x4, y4) = ((i % 2) * 2, (i / 2) * 2);
+                let ref_idx = if ref0 {
+                    0
+                } else {
+                    self.read_ref_idx(reader, list, y4 * 4 + x4)?
+                };
+                self.set_ref(list, x4, y4, 2, 2, ref_idx);
+            }
+        }
+        for list in 0..2 {
+            let mut decoded = [false; 16];
+            for (i, &(w4, h4, pred)) in sub_types.iter().enumerate() {
+                let (x8, y8) = ((i % 2) * 2, (i / 2) * 2);
+                if direct & (1 << i) != 0 || pred & (1 << list) == 0 {
+                    mark_decoded(&mut decoded, x8, y8, 2, 2);
+                    continue;
+                }
+                for y4 in (y8..y8 + 2).step_by(h4) {
+                    for x4 in (x8..x8 + 2).step_by(w4) {
+                        self.read_motion_vector(reader, list, x4, y4, w4, h4, &mut decoded)?;
+                    }
+                }
+            }
+        }
+        Ok(no_smaller_than_8x8)
+    }
+
+    fn parse_residual<R: EntropyReader>(
+        &mut self,
+        reader: &mut R,
+        is_i16x16: bool,
+        residual: &mut Residual,
+    ) -> Result<(), H264Error> {
+        let cbp = self.cur_mb().cbp;
+        let transform_8x8 = self.cur_mb().transform_8x8;
+        if is_i16x16 {
+            let count =
+                reader.residual_block(self, ResidualBlock::LumaDc, &mut residual.luma_dc)?;
+            if count > 0 {
+                self.cur_mb_mut().coded_dc |= 1;
+            }
+        }
+        for block8 in 0..4 {
+            if cbp & (1 << block8) == 0 {
+                continue;
+            }
+            if transform_8x8 && self.pps.entropy_coding_mode {
+                let count = reader.residual_block(
+                    self,
+                    ResidualBlock::Luma8x8(block8),
+                    &mut residual.luma_8x8[block8],
+                )?;
+                for k in 0..4 {
+                    self.cur_mb_mut().nz[BLOCK_RASTER[block8 * 4 + k]] = count;
+                }
+            } else if transform_8x8 {
+                // CAVLC codes 8x8 blocks as four interleaved 4x4 blocks.
+                for k in 0..4 {
+                    let raster = BLOCK_RASTER[block8 * 4 + k];
+                    let mut coeffs = [0; 16];
+                    let count =
+                        reader.residual_block(self, ResidualBlock::Luma4x4(raster), &mut coeffs)?;
+                    self.cur_mb_mut().nz[raster] = count;
+                    for (i, &coeff) in coeffs.iter().enumerate() {
+                        residual.luma_8x8[block8][i * 4 + k] = coeff;
+                    }
+                }
+            } else {
+                for k in 0..4 {
+                    let raster = BLOCK_RASTER[block8 * 4 + k];
+                    let count = if is_i16x16 {
+                        reader.residual_block(
+                            self,
+                            ResidualBlock::LumaAc(raster),
+                            &mut residual.luma[raster][1..],
+                        )?
+                    } else {
+                        reader.residual_block(
+                            self,
+                            ResidualBlock::Luma4x4(raster),
+                            &mut residual.luma[raster],
+                        )?
+                    };
+                    self.cur_mb_mut().nz[raster] = count;
+                }
+            }
+        }
+
+        let chroma_cbp = cbp >> 4;
+        if chroma_cbp != 0 {
+            for c in 0..2 {
+                let count = reader.residual_block(
+                    self,
+                    ResidualBlock::ChromaDc(c),
+                    &mut residual.chroma_dc[c],
+                )?;
+                if count > 0 {
+                    self.cur_mb_mut().coded_dc |= 2 << c;
+                }
+            }
+        }
+        if chroma_cbp == 2 {
+            for c in 0..2 {
+                for block in 0..4 {
+                    let count = reader.residual_block(
+                        self,
+                        ResidualBlock::ChromaAc(c, block),
+                        &mut residual.chroma_ac[c][block][1..],
+                    )?;
+                    self.cur_mb_mut().nz[16 + c * 4 + block] = count;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The availability of the neighbours of an intra-predicted luma block of `size` samples.
+    fn luma_availability(&self, x: usize, y: usize, size: usize) -> Availability {
+        let left_mb = self.intra_mb_available(-1, 0);
+        let top_mb = self.intra_mb_available(0, -1);
+        let top_right = if y == 0 {
+            if x + size < 16 {
+                top_mb
+            } else {
+                self.intra_mb_available(1, -1)
+            }
+        } else if x + size >= 16 {
+            false
+        } else if size == 8 {
+            true
+        } else {
+            // Available if the block to the top right precedes this one in decoding order.
+            let current = BLOCK_RASTER[(y / 4) * 4 + x / 4];
+            let top_right = BLOCK_RASTER[(y / 4 - 1) * 4 + x / 4 + 1];
+            top_right < current
+        };
+        Availability {
+            left: x > 0 || left_mb,
+            top: y > 0 || top_mb,
+            top_right,
+            top_left: match (x > 0, y > 0) {
+                (true, true) => true,
+                (false, true) => left_mb,
+                (true, false) => top_mb,
+                (false, false) => self.intra_mb_available(-1, -1),
+            },
+        }
+    }
+
+    fn reconstruct_luma(&mut self, residual: &Residual) -> Result<(), H264Error> {
+        let mb = *self.cur_mb();
+        let (px, py) = (self.mb_x * 16, self.mb_y * 16);
+        let qp = self.qp;
+        let intra = mb.kind.is_intra();
+        let list = if intra { 0 } else { 3 };
+
+        match mb.kind {
+            MbKind::I4x4 => {
+                for &raster in &BLOCK_RASTER {
+                    let (x, y) = ((raster % 4) * 4, (raster / 4) * 4);
+                    let availability = self.luma_availability(x, y, 4);
+                    intra::predict_4x4(
+                        &mut self.pic.luma,
+                        px + x,
+                        py + y,
+                        mb.intra_modes[raster],
+                        availability,
+                    );
+                    if mb.nz[raster] > 0 {
+                        let coeffs = self.dequant.dequant_4x4(&residual.luma[raster], list, qp);
+                        transform::add_4x4(&mut self.pic.luma, px + x, py + y, coeffs);
+                    }
+                }
+            }
+            MbKind::I8x8 => {
+                for block in 0..4 {
+                    let (x, y) = ((block % 2) * 8, (block / 2) * 8);
+                    let availability = self.luma_availability(x, y, 8);
+                    intra::predict_8x8(
+                        &mut self.pic.luma,
+                        px + x,
+                        py + y,
+                        mb.intra_modes[(y / 4) * 4 + x / 4],
+                        availability,
+                    );
+                    self.add_residual_8x8(residual, block, 0, qp);
+                }
+            }
+            MbKind::I16x16 => {
+                let availability = Availability {
+                    left: self.intra_mb_available(-1, 0),
+                    top: self.intra_mb_available(0, -1),
+                    top_right: false,
+                    top_left: self.intra_mb_available(-1, -1),
+                };
+                intra::predict_16x16(&mut self.pic.luma, px, py, mb.intra_modes[0], availability)?;
+                let dc = if mb.coded_dc & 1 != 0 {
+                    self.dequant.luma_dc(&residual.luma_dc, qp)
+                } else {
+                    [0; 16]
+                };
+                for (raster, &dc) in dc.iter().enumerate() {
+                    if mb.nz[raster] == 0 && dc == 0 {
+                        continue;
+                    }
+                    let mut coeffs = self.dequant.dequant_4x4(&residual.luma[raster], 0, qp);
+                    coeffs[0] = dc;
+                    let (x, y) = ((raster % 4) * 4, (raster / 4) * 4);
+                    transform::add_4x4(&mut self.pic.luma, px + x, py + y, coeffs);
+                }
+            }
+            MbKind::IPcm => {}
+            _ => {
+                if mb.transform_8x8 {
+                    for block in 0..4 {
+                        self.add_residual_8x8(residual, block, 1, qp);
+                    }
+                } else {
+                    for raster in 0..16 {
+                        if mb.nz[raster] > 0 {
+                            let coeffs = self.dequant.dequant_4x4(&residual.luma[raster], list, qp);
+                            let (x, y) = ((raster % 4) * 4, (raster / 4) * 4);
+                            transform::add_4x4(&mut self.pic.luma, px + x, py + y, coeffs);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn add_residual_8x8(&mut self, residual: &Residual, block: usize, list: usize, qp: i32) {
+        let mb = self.cur_mb();
+        let (x, y) = ((block % 2) * 8, (block / 2) * 8);
+        let top_left = (y / 4) * 4 + x / 4;
+        if [0, 1, 4, 5].iter().all(|&i| mb.nz[top_left + i] == 0) {
+            return;
+        }
+        let coeffs = self
+            .dequant
+            .dequant_8x8(&residual.luma_8x8[block], list, qp);
+        let (px, py) = (self.mb_x * 16 + x, self.mb_y * 16 + y);
+        transform::add_8x8(&mut self.pic.luma, px, py, coeffs);
+    }
+
+    fn reconstruct_chroma(&mut self, residual: &Residual) -> Result<(), H264Error> {
+        let mb = *self.cur_mb();
+        if mb.kind == MbKind::IPcm {
+            return Ok(());
+        }
+        let (px, py) = (self.mb_x * 8, self.mb_y * 8);
+        let intra = mb.kind.is_intra();
+        let availability = intra.then(|| Availability {
+            left: self.intra_mb_available(-1, 0),
+            top: self.intra_mb_available(0, -1),
+            top_right: false,
+            top_left: self.intra_mb_available(-1, -1),
+        });
+        let chroma_cbp = mb.cbp >> 4;
+        for c in 0..2 {
+            let plane = if c == 0 {
+                &mut self.pic.cb
+            } else {
+                &mut self.pic.cr
+            };
+            if let Some(availability) = availability {
+                intra::predict_chroma(plane, px, py, mb.intra_chroma_mode, availability)?;
+            }
+            if chroma_cbp == 0 {
+                continue;
+            }
+            let list = if intra { 1 + c } else { 4 + c };
+            let qp = i32::from(mb.qpc[c]);
+            let dc = if mb.coded_dc & (2 << c) != 0 {
+                self.dequant.chroma_dc(&residual.chroma_dc[c], list, qp)
+            } else {
+                [0; 4]
+            };
+            for (block, &dc) in dc.iter().enumerate() {
+                if mb.nz[16 + c * 4 + block] == 0 && dc == 0 {
+                    continue;
+                }
+                let mut coeffs = self
+                    .dequant
+                    .dequant_4x4(&residual.chroma_ac[c][block], list, qp);
+                coeffs[0] = dc;
+                let (x, y) = ((block % 2) * 4, (block / 2) * 4);
+                transform::add_4x4(plane, px + x, py + y, coeffs);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn mark_decoded(decoded: &mut [bool; 16], x4: usize, y4: usize, w4: usize, h4: usize) {
+    for y in y4..y4 + h4 {
+        for x in x4..x4 + w4 {
+            decoded[y * 4 + x] = true;
+        }
+    }
+}
@@ -0,0 +1,447 @@
+use super::inter;
+use super::macroblock::SliceDecoder;
+use super::picture::{MbInfo, Picture};
+use super::slice::{SliceType, Weight};
+use super::H264Error;
+
+/// The motion of a neighbouring partition: its reference index (-1 if it does not
+/// use the list) and motion vector.
+type Motion = (i8, [i16; 2]);
+
+/// The motion of a co-located block, as described in 8.4.1.2.1.
+struct Colocated {
+    ref_idx: i8,
+    /// The picture that the co-located block refers to, or -1.
+    ref_pic: i32,
+    mv: [i16; 2],
+}
+
+/// Returns the smaller non-negative value, or -1 if both are negative.
+fn min_positive(a: i8, b: i8) -> i8 {
+    if a >= 0 && b >= 0 {
+        a.min(b)
+    } else {
+        a.max(b)
+    }
+}
+
+fn median(a: i16, b: i16, c: i16) -> i16 {
+    a.max(b).min(a.min(b).max(c))
+}
+
+/// The index of the 8x8 partition containing a 4x4 block at a raster position.
+fn block_8x8(raster: usize) -> usize {
+    (raster / 8) * 2 + (raster % 4) / 2
+}
+
+impl SliceDecoder<'_> {
+    /// Returns the motion of the partition covering a luma position relative to the current
+    /// macroblock, or `None` if it is not available.
+    ///
+    /// Positions within the current macroblock are only available once `decoded`.
+    fn neighbour_motion(
+        &self,
+        list: usize,
+        x: i32,
+        y: i32,
+        decoded: &[bool; 16],
+    ) -> Option<Motion> {
+        let (addr, raster) = self.luma_neighbour(x, y)?;
+        if addr == self.mb_addr && !decoded[raster] {
+            return None;
+        }
+        let mb = &self.pic.mbs[addr];
+        let ref_idx = mb.ref_idx[list][block_8x8(raster)];
+        if mb.kind.is_intra() || ref_idx < 0 {
+            Some((-1, [0, 0]))
+        } else {
+            Some((ref_idx, mb.mv[list][raster]))
+        }
+    }
+
+    /// Returns the motion of the neighbours A, B and C of a partition, as described in 8.4.1.3.2.
+    fn partition_neighbours(
+        &self,
+        list: usize,
+        x4: usize,
+        y4: usize,
+        w4: usize,
+        decoded: &[bool; 16],
+    ) -> [Option<Motion>; 3] {
+        let (x, y, w) = (x4 as i32 * 4, y4 as i32 * 4, w4 as i32 * 4);
+        [
+            self.neighbour_motion(list, x - 1, y, decoded),
+            self.neighbour_motion(list, x, y - 1, decoded),
+            self.neighbour_motion(list, x + w, y - 1, decoded)
+                .or_else(|| self.neighbour_motion(list, x - 1, y - 1, decoded)),
+        ]
+    }
+
+    /// Predicts the motion vector of a partition, as described in 8.4.1.3.
+    #[allow(clippy::too_many_arguments)]
+    pub fn predict_mv(
+        &self,
+        list: usize,
+        x4: usize,
+        y4: usize,
+        w4: usize,
+        h4: usize,
+        ref_idx: i8,
+        decoded: &[bool; 16],
+    ) -> [i16; 2] {
+        let [a, b, c] = self.partition_neighbours(list, x4, y4, w4, decoded);
+        let directional = match (w4, h4) {
+            (4, 2) if y4 == 0 => b,
+            (4, 2) => a,
+            (2, 4) if x4 == 0 => a,
+            (2, 4) => c,
+            _ => None,
+        };
+        if let Some((neighbour_ref, mv)) = directional {
+            if neighbour_ref == ref_idx {
+                return mv;
+            }
+        }
+
+        let unavailable = (-1, [0, 0]);
+        let (a, b, c) = match (a, b, c) {
+            (Some(a), None, None) => (a, a, a),
+            (a, b, c) => (
+                a.unwrap_or(unavailable),
+                b.unwrap_or(unavailable),
+                c.unwrap_or(unavailable),
+            ),
+        };
+        let matching = [a, b, c].into_iter().filter(|n| n.0 == ref_idx);
+        if matching.clone().count() == 1 {
+            return matching.map(|n| n.1).next().unwrap_or_default();
+        }
+        [
+            median(a.1[0], b.1[0], c.1[0]),
+            median(a.1[1], b.1[1], c.1[1]),
+        ]
+    }
+
+    fn set_motion(
+        &mut self,
+        list: usize,
+        block: usize,
+        ref_idx: i8,
+        mv: [i16; 2],
+    ) -> Result<(), H264Error> {
+        let ref_pic = if ref_idx >= 0 {
+            self.ref_lists[list]
+                .get(ref_idx as usize)
+                .ok_or(H264Error::MissingReferenceFrame)?
+                .pic
+                .id
+        } else {
+            -1
+        };
+        let mb = &mut self.pic.mbs[self.mb_addr];
+        mb.ref_idx[list][block_8x8(block)] = ref_idx;
+        mb.ref_pic[list][block_8x8(block)] = ref_pic;
+        mb.mv[list][block] = mv;
+        Ok(())
+    }
+
+    /// Derives the motion of a P_Skip macroblock, as described in 8.4.1.1.
+    pub fn predict_p_skip(&mut self) -> Result<(), H264Error> {
+        let decoded = [false; 16];
+        let a = self.neighbour_motion(0, -1, 0, &decoded);
+        let b = self.neighbour_motion(0, 0, -1, &decoded);
+        let mv = match (a, b) {
+            (None, _) | (_, None) | (Some((0, [0, 0])), _) | (_, Some((0, [0, 0]))) => [0, 0],
+            _ => self.predict_mv(0, 0, 0, 4, 4, 0, &decoded),
+        };
+        for block in 0..16 {
+            self.set_motion(0, block, 0, mv)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the 4x4 blocks whose motion is derived for each block in direct mode.
+    fn direct_source_block(&self, block: usize) -> usize {
+        if self.sps.direct_8x8_inference {
+            // The corner blocks of each 8x8 partition.
+            [0, 3, 12, 15][block_8x8(block)]
+        } else {
+            block
+        }
+    }
+
+    fn colocated(&self, col_pic: &Picture, block: usize) -> Colocated {
+        let mb: &MbInfo = &col_pic.mbs[self.mb_addr];
+        let block = self.direct_source_block(block);
+        let block8 = block_8x8(block);
+        if mb.kind.is_intra() {
+            return Colocated {
+                ref_idx: -1,
+                ref_pic: -1,
+                mv: [0, 0],
+            };
+        }
+        let list = if mb.ref_idx[0][block8] < 0 { 1 } else { 0 };
+        Colocated {
+            ref_idx: mb.ref_idx[list][block8],
+            ref_pic: mb.ref_pic[list][block8],
+            mv: mb.mv[list][block],
+        }
+    }
+
+    /// Derives the motion of the direct-predicted 8x8 partitions in `mask`, as described in 8.4.1.2.
+    pub fn predict_direct(&mut self, mask: u8) -> Result<(), H264Error> {
+        let col = self.ref_lists[1]
+            .first()
+            .ok_or(H264Error::MissingReferenceFrame)?
+            .clone();
+        if self.header.direct_spatial_mv_pred {
+            self.predict_spatial_direct(mask, &col.pic, col.long_term)
+        } else {
+            self.predict_temporal_direct(mask, &col.pic)
+        }
+    }
+
+    fn predict_spatial_direct(
+        &mut self,
+        mask: u8,
+        col_pic: &Picture,
+        col_long_term: bool,
+    ) -> Result<(), H264Error> {
+        let decoded = [false; 16];
+        let mut ref_idx = [-1; 2];
+        let mut mvp = [[0; 2]; 2];
+        for (list, ref_idx) in ref_idx.iter_mut().enumerate() {
+            let [a, b, c] = self.partition_neighbours(list, 0, 0, 4, &decoded);
+            let [a, b, c] = [a, b, c].map(|n| n.map_or(-1, |n| n.0));
+            *ref_idx = min_positive(a, min_positive(b, c));
+        }
+        let direct_zero = ref_idx[0] < 0 && ref_idx[1] < 0;
+        if direct_zero {
+            ref_idx = [0, 0];
+        } else {
+            for list in 0..2 {
+                if ref_idx[list] >= 0 {
+                    mvp[list] = self.predict_mv(list, 0, 0, 4, 4, ref_idx[list], &decoded);
+                }
+            }
+        }
+
+        for block in 0..16 {
+            if mask & (1 << block_8x8(block)) == 0 {
+                continue;
+            }
+            let colocated = self.colocated(col_pic, block);
+            let col_zero = !col_long_term
+                && colocated.ref_idx == 0
+                && colocated.mv.iter().all(|c| (-1..=1).contains(c));
+            for list in 0..2 {
+                let mv = if direct_zero || ref_idx[list] < 0 || (ref_idx[list] == 0 && col_zero) {
+                    [0, 0]
+                } else {
+                    mvp[list]
+                };
+                self.set_motion(list, block, ref_idx[list], mv)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn predict_temporal_direct(&mut self, mask: u8, col_pic: &Picture) -> Result<(), H264Error> {
+        let poc1 = col_pic.poc;
+        for block in 0..16 {
+            if mask & (1 << block_8x8(block)) == 0 {
+                continue;
+            }
+            let colocated = self.colocated(col_pic, block);
+            let ref_idx_l0 = if colocated.ref_idx < 0 {
+                0
+            } else {
+                self.ref_lists[0]
+                    .iter()
+                    .position(|r| r.pic.id == colocated.ref_pic)
+                    .ok_or(H264Error::MissingReferenceFrame)?
+            };
+            let ref0 = self.ref_lists[0]
+                .get(ref_idx_l0)
+                .ok_or(H264Error::MissingReferenceFrame)?;
+            let tb = (self.pic.poc - ref0.pic.poc).clamp(-128, 127);
+            let td = (poc1 - ref0.pic.poc).clamp(-128, 127);
+            let (mv_l0, mv_l1) = if ref0.long_term || td == 0 {
+                (colocated.mv, [0, 0])
+            } else {
+                let tx = (16384 + (td / 2).abs()) / td;
+                let scale = ((tb * tx + 32) >> 6).clamp(-1024, 1023);
+                let mv_l0 = colocated
+                    .mv
+                    .map(|c| ((scale * i32::from(c) + 128) >> 8) as i16);
+                (
+                    mv_l0,
+                    [
+                        mv_l0[0].wrapping_sub(colocated.mv[0]),
+                        mv_l0[1].wrapping_sub(colocated.mv[1]),
+                    ],
+                )
+            };
+            self.set_motion(0, block, ref_idx_l0 as i8, mv_l0)?;
+            self.set_motion(1, block, 0, mv_l1)?;
+        }
+        Ok(())
+    }
+
+    /// The implicit bi-prediction weights of a pair of references, as described in 8.4.2.3.1.
+    fn implicit_weights(&self, ref_idx: [i8; 2]) -> [i32; 2] {
+        let ref0 = &self.ref_lists[0][ref_idx[0] as usize];
+        let ref1 = &self.ref_lists[1][ref_idx[1] as usize];
+        let tb = (self.pic.poc - ref0.pic.poc).clamp(-128, 127);
+        let td = (ref1.pic.poc - ref0.pic.poc).clamp(-128, 127);
+        if td == 0 || ref0.long_term || ref1.long_term {
+            return [32, 32];
+        }
+        let tx = (16384 + (td / 2).abs()) / td;
+        let scale = ((tb * tx + 32) >> 6).clamp(-1024, 1023) >> 2;
+        if !(-64..=128).contains(&scale) {
+            return [32, 32];
+        }
+        [64 - scale, scale]
+    }
+
+    /// Predicts the samples of the current macroblock from its motion.
+    pub fn inter_prediction(&mut self) -> Result<(), H264Error> {
+        let mb = *self.cur_mb();
+        let same_motion = |a: usize, b: usize| {
+            (0..2).all(|list| {
+                mb.ref_idx[list][block_8x8(a)] == mb.ref_idx[list][block_8x8(b)]
+                    && (mb.ref_idx[list][block_8x8(a)] < 0 || mb.mv[list][a] == mb.mv[list][b])
+            })
+        };
+        if (1..16).all(|block| same_motion(0, block)) {
+            return self.predict_block(0, 0, 4, 4);
+        }
+        for block8 in 0..4 {
+            let (x4, y4) = ((block8 % 2) * 2, (block8 / 2) * 2);
+            let top_left = y4 * 4 + x4;
+            if [1, 4, 5]
+                .iter()
+                .all(|&i| same_motion(top_left, top_left + i))
+            {
+                self.predict_block(x4, y4, 2, 2)?;
+            } else {
+                for i in [0, 1, 4, 5] {
+                    let block = top_left + i;
+                    self.predict_block(block % 4, block / 4, 1, 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Predicts a rectangle of 4x4 blocks sharing the same motion.
+    fn predict_block(
+        &mut self,
+        x4: usize,
+        y4: usize,
+        w4: usize,
+        h4: usize,
+    ) -> Result<(), H264Error> {
+        let mb = *self.cur_mb();
+        let raster = y4 * 4 + x4;
+        let ref_idx = [
+            mb.ref_idx[0][block_8x8(raster)],
+            mb.ref_idx[1][block_8x8(raster)],
+        ];
+        let (w, h) = (w4 * 4, h4 * 4);
+        let x = (self.mb_x * 16 + x4 * 4) as i32;
+        let y = (self.mb_y * 16 + y4 * 4) as i32;
+
+        let mut predictions = [[[0u8; 256]; 3]; 2];
+        for list in 0..2 {
+            if ref_idx[list] < 0 {
+                continue;
+            }
+            let pic = &self.ref_lists[list]
+                .get(ref_idx[list] as usize)
+                .ok_or(H264Error::MissingReferenceFrame)?
+                .pic;
+            let mv = mb.mv[list][raster];
+            let [luma, cb, cr] = &mut predictions[list];
+            inter::luma(&pic.luma, x, y, w, h, mv, luma);
+            inter::chroma(&pic.cb, x / 2, y / 2, w / 2, h / 2, mv, cb);
+            inter::chroma(&pic.cr, x / 2, y / 2, w / 2, h / 2, mv, cr);
+        }
+
+        let explicit = match self.header.slice_type {
+            SliceType::P => self.pps.weighted_pred,
+            SliceType::B => self.pps.weighted_bipred_idc == 1,
+            SliceType::I => false,
+        };
+        let implicit = self.header.slice_type == SliceType::B && self.pps.weighted_bipred_idc == 2;
+        let [first, second] = &mut predictions;
+        let sizes = [w * h, w * h / 4, w * h / 4];
+        let result = if ref_idx[0] >= 0 && ref_idx[1] >= 0 {
+            for (component, &size) in sizes.iter().enumerate() {
+                let (dst, other) = (&mut first[component][..size], &second[component][..size]);
+                if let (true, Some(table)) = (explicit, &self.header.pred_weight_table) {
+                    let weights = [
+                        table.weights[0][ref_idx[0] as usize][component],
+                        table.weights[1][ref_idx[1] as usize][component],
+                    ];
+                    let log2_denom = if component == 0 {
+                        table.luma_log2_denom
+                    } else {
+                        table.chroma_log2_denom
+                    };
+                    inter::weight_bi(dst, other, weights, log2_denom);
+                } else if implicit {
+                    let [w0, w1] = self.implicit_weights(ref_idx);
+                    let weights = [
+                        Weight {
+                            weight: w0,
+                            offset: 0,
+                        },
+                        Weight {
+                            weight: w1,
+                            offset: 0,
+                        },
+                    ];
+                    inter::weight_bi(dst, other, weights, 5);
+                } else {
+                    inter::average(dst, other);
+                }
+            }
+            first
+        } else {
+            let list = if ref_idx[0] >= 0 { 0 } else { 1 };
+            let prediction = if list == 0 { first } else { second };
+            if let (true, Some(table)) = (explicit, &self.header.pred_weight_table) {
+                for (component, &size) in sizes.iter().enumerate() {
+                    let log2_denom = if component == 0 {
+                        table.luma_log2_denom
+                    } else {
+                        table.chroma_log2_denom
+                    };
+                    inter::weight(
+                        &mut prediction[component][..size],
+                        table.weights[list][ref_idx[list] as usize][component],
+                        log2_denom,
+                    );
+                }
+            }
+            prediction
+        };
+
+        let planes = [&mut self.pic.luma, &mut self.pic.cb, &mut self.pic.cr];
+        for (component, plane) in planes.into_iter().enumerate() {
+            let (x, y, w, h) = if component == 0 {
+                (x as usize, y as usize, w, h)
+            } else {
+                (x as usize / 2, y as usize / 2, w / 2, h / 2)
+            };
+            for (row, samples) in result[component][..w * h].chunks_exact(w).enumerate() {
+                let start = (y + row) * plane.width + x;
+                plane.data[start..start + w].copy_from_slice(samples);
+            }
+        }
+        Ok(())
+    }
+}
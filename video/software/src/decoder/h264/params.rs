@@ -0,0 +1,455 @@
+use super::bitstream::BitReader;
+use super::tables::{
+    DEFAULT_SCALING_4X4_INTER, DEFAULT_SCALING_4X4_INTRA, DEFAULT_SCALING_8X8_INTER,
+    DEFAULT_SCALING_8X8_INTRA, ZIGZAG_4X4, ZIGZAG_8X8,
+};
+use super::H264Error;
+use std::rc::Rc;
+
+const FLAT_4X4: [u8; 16] = [16; 16];
+const FLAT_8X8: [u8; 64] = [16; 64];
+
+/// Scaling matrices, in raster order.
+///
+/// The 4x4 lists are, in order, intra Y, Cb and Cr, then inter Y, Cb and Cr.
+/// The 8x8 lists are intra Y and inter Y.
+#[derive(Clone, Debug)]
+pub struct ScalingLists {
+    pub list_4x4: [[u8; 16]; 6],
+    pub list_8x8: [[u8; 64]; 2],
+}
+
+impl Default for ScalingLists {
+    fn default() -> Self {
+        Self {
+            list_4x4: [FLAT_4X4; 6],
+            list_8x8: [FLAT_8X8; 2],
+        }
+    }
+}
+
+impl ScalingLists {
+    /// Parses the scaling lists of an SPS or PPS.
+    ///
+    /// `fallback` provides the lists used by fall-back rule B;
+    /// for an SPS (rule A) these are the default lists.
+    fn parse(
+        reader: &mut BitReader,
+        num_8x8: usize,
+        fallback: Option<&ScalingLists>,
+    ) -> Result<Self, H264Error> {
+        let mut lists = ScalingLists::default();
+        for i in 0..6 {
+            let present = reader.read_bit()?;
+            let default = if i < 3 {
+                &DEFAULT_SCALING_4X4_INTRA
+            } else {
+                &DEFAULT_SCALING_4X4_INTER
+            };
+            let inferred = match (i, fallback) {
+                (0, None) | (3, None) => to_raster(default, &ZIGZAG_4X4),
+                (0, Some(fallback)) | (3, Some(fallback)) => fallback.list_4x4[i],
+                _ => lists.list_4x4[i - 1],
+            };
+            lists.list_4x4[i] = if present {
+                match read_scaling_list::<16>(reader)? {
+                    ScalingList::Explicit(list) => to_raster(&list, &ZIGZAG_4X4),
+                    ScalingList::UseDefault => to_raster(default, &ZIGZAG_4X4),
+                }
+            } else {
+                inferred
+            };
+        }
+        for i in 0..num_8x8.min(2) {
+            let present = reader.read_bit()?;
+            let default = if i == 0 {
+                &DEFAULT_SCALING_8X8_INTRA
+            } else {
+                &DEFAULT_SCALING_8X8_INTER
+            };
+            lists.list_8x8[i] = if present {
+                match read_scaling_list::<64>(reader)? {
+                    ScalingList::Explicit(list) => to_raster(&list, &ZIGZAG_8X8),
+                    ScalingList::UseDefault => to_raster(default, &ZIGZAG_8X8),
+                }
+            } else if let Some(fallback) = fallback {
+                fallback.list_8x8[i]
+            } else {
+                to_raster(default, &ZIGZAG_8X8)
+            };
+        }
+        // Only 4:4:4 video has chroma 8x8 lists, which is not supported.
+        for _ in 2..num_8x8 {
+            if reader.read_bit()? {
+                read_scaling_list::<64>(reader)?;
+            }
+        }
+        Ok(lists)
+    }
+}
+
+enum ScalingList<const N: usize> {
+    Explicit([u8; N]),
+    UseDefault,
+}
+
+fn read_scaling_list<const N: usize>(reader: &mut BitReader) -> Result<ScalingList<N>, H264Error> {
+    let mut list = [0u8; N];
+    let mut last_scale = 8;
+    let mut next_scale = 8;
+    for (j, entry) in list.iter_mut().enumerate() {
+        if next_scale != 0 {
+            let delta_scale = reader.read_se_range(-128, 127, "delta_scale")?;
+            next_scale = (last_scale + delta_scale + 256) % 256;
+            if j == 0 && next_scale == 0 {
+                return Ok(ScalingList::UseDefault);
+            }
+        }
+        if next_scale != 0 {
+            last_scale = next_scale;
+        }
+        *entry = last_scale as u8;
+    }
+    Ok(ScalingList::Explicit(list))
+}
+
+fn to_raster<const N: usize>(list: &[u8; N], scan: &[usize; N]) -> [u8; N] {
+    let mut raster = [0; N];
+    for (i, &value) in list.iter().enumerate() {
+        raster[scan[i]] = value;
+    }
+    raster
+}
+
+/// A sequence parameter set.
+#[derive(Clone, Debug)]
+pub struct Sps {
+    pub profile_idc: u8,
+    pub level_idc: u8,
+    pub id: u32,
+    pub scaling_lists: Option<ScalingLists>,
+    pub log2_max_frame_num: u32,
+    pub pic_order_cnt_type: u32,
+    pub log2_max_pic_order_cnt_lsb: u32,
+    pub delta_pic_order_always_zero: bool,
+    pub offset_for_non_ref_pic: i32,
+    pub offset_for_top_to_bottom_field: i32,
+    pub offset_for_ref_frame: Vec<i32>,
+    pub max_num_ref_frames: u32,
+    pub gaps_in_frame_num_allowed: bool,
+    pub width_mbs: usize,
+    pub height_mbs: usize,
+    pub direct_8x8_inference: bool,
+    /// Left, right, top and bottom cropping, in luma samples.
+    pub crop: [usize; 4],
+    pub max_num_reorder_frames: Option<u32>,
+    pub max_dec_frame_buffering: Option<u32>,
+}
+
+impl Sps {
+    pub fn parse(reader: &mut BitReader) -> Result<Self, H264Error> {
+        let profile_idc = reader.read_bits(8)? as u8;
+        // Constraint set flags and reserved bits.
+        reader.skip_bits(8)?;
+        let level_idc = reader.read_bits(8)? as u8;
+        let id = reader.read_ue_max(31, "seq_parameter_set_id")?;
+
+        let mut scaling_lists = None;
+        if matches!(
+            profile_idc,
+            100 | 110 | 122 | 244 | 44 | 83 | 86 | 118 | 128 | 138 | 139 | 134 | 135
+        ) {
+            let chroma_format_idc = reader.read_ue_max(3, "chroma_format_idc")?;
+            if chroma_format_idc == 3 {
+                // separate_colour_plane_flag
+                reader.read_bit()?;
+            }
+            if chroma_format_idc != 1 {
+                return Err(H264Error::Unsupported("chroma formats other than 4:2:0"));
+            }
+            let bit_depth_luma = reader.read_ue()? + 8;
+            let bit_depth_chroma = reader.read_ue()? + 8;
+            if bit_depth_luma != 8 || bit_depth_chroma != 8 {
+                return Err(H264Error::Unsupported("bit depths other than 8"));
+            }
+            if reader.read_bit()? {
+                return Err(H264Error::Unsupported("lossless transform bypass"));
+            }
+            if reader.read_bit()? {
+                scaling_lists = Some(ScalingLists::parse(reader, 2, None)?);
+            }
+        }
+
+        let log2_max_frame_num = reader.read_ue_max(12, "log2_max_frame_num_minus4")? + 4;
+        let pic_order_cnt_type = reader.read_ue_max(2, "pic_order_cnt_type")?;
+        let mut log2_max_pic_order_cnt_lsb = 0;
+        let mut delta_pic_order_always_zero = false;
+        let mut offset_for_non_ref_pic = 0;
+        let mut offset_for_top_to_bottom_field = 0;
+        let mut offset_for_ref_frame = Vec::new();
+        match pic_order_cnt_type {
+            0 => {
+                log2_max_pic_order_cnt_lsb =
+                    reader.read_ue_max(12, "log2_max_pic_order_cnt_lsb_minus4")? + 4;
+            }
+            1 => {
+                delta_pic_order_always_zero = reader.read_bit()?;
+                offset_for_non_ref_pic = reader.read_se()?;
+                offset_for_top_to_bottom_field = reader.read_se()?;
+                let count = reader.read_ue_max(255, "num_ref_frames_in_pic_order_cnt_cycle")?;
+                for _ in 0..count {
+                    offset_for_ref_frame.push(reader.read_se()?);
+                }
+            }
+            _ => {}
+        }
+
+        let max_num_ref_frames = reader.read_ue_max(16, "max_num_ref_frames")?;
+        let gaps_in_frame_num_allowed = reader.read_bit()?;
+        let width_mbs = reader.read_ue_max(1023, "pic_width_in_mbs_minus1")? as usize + 1;
+        let height_mbs = reader.read_ue_max(1023, "pic_height_in_map_units_minus1")? as usize + 1;
+        if !reader.read_bit()? {
+            return Err(H264Error::Unsupported("interlaced video"));
+        }
+        let direct_8x8_inference = reader.read_bit()?;
+
+        let mut crop = [0; 4];
+        if reader.read_bit()? {
+            for value in &mut crop {
+                // Cropping is in units of two samples for 4:2:0 frames.
+                *value = reader.read_ue()? as usize * 2;
+            }
+            if crop[0] + crop[1] >= width_mbs * 16 || crop[2] + crop[3] >= height_mbs * 16 {
+                return Err(H264Error::InvalidData("frame cropping"));
+            }
+        }
+
+        let mut sps = Self {
+            profile_idc,
+            level_idc,
+            id,
+            scaling_lists,
+            log2_max_frame_num,
+            pic_order_cnt_type,
+            log2_max_pic_order_cnt_lsb,
+            delta_pic_order_always_zero,
+            offset_for_non_ref_pic,
+            offset_for_top_to_bottom_field,
+            offset_for_ref_frame,
+            max_num_ref_frames,
+            gaps_in_frame_num_allowed,
+            width_mbs,
+            height_mbs,
+            direct_8x8_inference,
+            crop,
+            max_num_reorder_frames: None,
+            max_dec_frame_buffering: None,
+        };
+        if reader.read_bit()? {
+            sps.parse_vui(reader)?;
+        }
+        Ok(sps)
+    }
+
+    fn parse_vui(&mut self, reader: &mut BitReader) -> Result<(), H264Error> {
+        // aspect_ratio_info_present_flag
+        if reader.read_bit()? {
+            let aspect_ratio_idc = reader.read_bits(8)?;
+            if aspect_ratio_idc == 255 {
+                // sar_width and sar_height
+                reader.skip_bits(32)?;
+            }
+        }
+        // overscan_info_present_flag
+        if reader.read_bit()? {
+            reader.skip_bits(1)?;
+        }
+        // video_signal_type_present_flag
+        if reader.read_bit()? {
+            reader.skip_bits(4)?;
+            // colour_description_present_flag
+            if reader.read_bit()? {
+                reader.skip_bits(24)?;
+            }
+        }
+        // chroma_loc_info_present_flag
+        if reader.read_bit()? {
+            reader.read_ue()?;
+            reader.read_ue()?;
+        }
+        // timing_info_present_flag
+        if reader.read_bit()? {
+            reader.skip_bits(65)?;
+        }
+        let nal_hrd = reader.read_bit()?;
+        if nal_hrd {
+            skip_hrd_parameters(reader)?;
+        }
+        let vcl_hrd = reader.read_bit()?;
+        if vcl_hrd {
+            skip_hrd_parameters(reader)?;
+        }
+        if nal_hrd || vcl_hrd {
+            // low_delay_hrd_flag
+            reader.skip_bits(1)?;
+        }
+        // pic_struct_present_flag
+        reader.skip_bits(1)?;
+        // bitstream_restriction_flag
+        if reader.read_bit()? {
+            // motion_vectors_over_pic_boundaries_flag
+            reader.skip_bits(1)?;
+            // max_bytes_per_pic_denom, max_bits_per_mb_denom,
+            // log2_max_mv_length_horizontal and log2_max_mv_length_vertical
+            for _ in 0..4 {
+                reader.read_ue()?;
+            }
+            self.max_num_reorder_frames = Some(reader.read_ue_max(16, "max_num_reorder_frames")?);
+            self.max_dec_frame_buffering = Some(reader.read_ue_max(16, "max_dec_frame_buffering")?);
+        }
+        Ok(())
+    }
+
+    pub fn max_frame_num(&self) -> u32 {
+        1 << self.log2_max_frame_num
+    }
+
+    /// The number of frames the decoded picture buffer holds.
+    pub fn dpb_size(&self) -> usize {
+        if let Some(max_dec_frame_buffering) = self.max_dec_frame_buffering {
+            return (max_dec_frame_buffering.max(self.max_num_ref_frames)).max(1) as usize;
+        }
+        // MaxDpbMbs of Table A-1.
+        let max_dpb_mbs = match self.level_idc {
+            9 | 10 => 396,
+            11 => 900,
+            12 | 13 | 20 => 2376,
+            21 => 4752,
+            22 | 30 => 8100,
+            31 => 18000,
+            32 => 20480,
+            40 | 41 => 32768,
+            42 => 34816,
+            50 => 110400,
+            51 | 52 => 184320,
+            _ => 696320,
+        };
+        (max_dpb_mbs / (self.width_mbs * self.height_mbs))
+            .clamp(self.max_num_ref_frames.max(1) as usize, 16)
+    }
+
+    /// The number of frames that may precede another in decoding order while following it in output order.
+    pub fn max_reorder_frames(&self) -> usize {
+        if let Some(max_num_reorder_frames) = self.max_num_reorder_frames {
+            return max_num_reorder_frames as usize;
+        }
+        if self.pic_order_cnt_type == 2 || self.profile_idc == 66 {
+            // Output order always equals decoding order for these,
+            // as they cannot contain B slices.
+            return 0;
+        }
+        self.dpb_size()
+    }
+}
+
+fn skip_hrd_parameters(reader: &mut BitReader) -> Result<(), H264Error> {
+    let cpb_cnt = reader.read_ue_max(31, "cpb_cnt_minus1")? + 1;
+    // bit_rate_scale and cpb_size_scale
+    reader.skip_bits(8)?;
+    for _ in 0..cpb_cnt {
+        reader.read_ue()?;
+        reader.read_ue()?;
+        // cbr_flag
+        reader.skip_bits(1)?;
+    }
+    // Initial CPB removal delay, CPB removal delay, DPB output delay and time offset lengths.
+    reader.skip_bits(20)?;
+    Ok(())
+}
+
+/// A picture parameter set.
+#[derive(Clone, Debug)]
+pub struct Pps {
+    pub id: u32,
+    pub sps_id: u32,
+    pub entropy_coding_mode: bool,
+    pub bottom_field_pic_order_in_frame_present: bool,
+    pub num_ref_idx_default_active: [u32; 2],
+    pub weighted_pred: bool,
+    pub weighted_bipred_idc: u32,
+    pub pic_init_qp: i32,
+    /// The offsets of the Cb and Cr quantization parameters.
+    pub chroma_qp_index_offset: [i32; 2],
+    pub deblocking_filter_control_present: bool,
+    pub constrained_intra_pred: bool,
+    pub redundant_pic_cnt_present: bool,
+    pub transform_8x8_mode: bool,
+    pub scaling_lists: ScalingLists,
+}
+
+impl Pps {
+    pub fn parse(reader: &mut BitReader, sps_list: &[Option<Rc<Sps>>]) -> Result<Self, H264Error> {
+        let id = reader.read_ue_max(255, "pic_parameter_set_id")?;
+        let sps_id = reader.read_ue_max(31, "seq_parameter_set_id")?;
+        let sps = sps_list[sps_id as usize]
+            .as_ref()
+            .ok_or(H264Error::MissingParameterSet)?;
+        let entropy_coding_mode = reader.read_bit()?;
+        let bottom_field_pic_order_in_frame_present = reader.read_bit()?;
+        if reader.read_ue()? != 0 {
+            return Err(H264Error::Unsupported("slice groups"));
+        }
+        let num_ref_idx_default_active = [
+            reader.read_ue_max(31, "num_ref_idx_l0_default_active_minus1")? + 1,
+            reader.read_ue_max(31, "num_ref_idx_l1_default_active_minus1")? + 1,
+        ];
+        let weighted_pred = reader.read_bit()?;
+        let weighted_bipred_idc = reader.read_bits(2)?;
+        if weighted_bipred_idc == 3 {
+            return Err(H264Error::InvalidData("weighted_bipred_idc"));
+        }
+        let pic_init_qp = 26 + reader.read_se_range(-26, 25, "pic_init_qp_minus26")?;
+        // pic_init_qs_minus26, only used by SP and SI slices.
+        reader.read_se()?;
+        let chroma_qp_index_offset = reader.read_se_range(-12, 12, "chroma_qp_index_offset")?;
+        let deblocking_filter_control_present = reader.read_bit()?;
+        let constrained_intra_pred = reader.read_bit()?;
+        let redundant_pic_cnt_present = reader.read_bit()?;
+
+        let mut transform_8x8_mode = false;
+        let mut second_chroma_qp_index_offset = chroma_qp_index_offset;
+        let sps_lists = sps.scaling_lists.clone().unwrap_or_default();
+        let mut scaling_lists = sps_lists.clone();
+        if reader.more_rbsp_data() {
+            transform_8x8_mode = reader.read_bit()?;
+            if reader.read_bit()? {
+                // Fall-back rule A applies when the SPS has no lists, rule B otherwise.
+                let fallback = sps.scaling_lists.as_ref();
+                let num_8x8 = if transform_8x8_mode { 2 } else { 0 };
+                scaling_lists = ScalingLists::parse(reader, num_8x8, fallback)?;
+                if !transform_8x8_mode {
+                    scaling_lists.list_8x8 = sps_lists.list_8x8;
+                }
+            }
+            second_chroma_qp_index_offset =
+                reader.read_se_range(-12, 12, "second_chroma_qp_index_offset")?;
+        }
+
+        Ok(Self {
+            id,
+            sps_id,
+            entropy_coding_mode,
+            bottom_field_pic_order_in_frame_present,
+            num_ref_idx_default_active,
+            weighted_pred,
+            weighted_bipred_idc,
+            pic_init_qp,
+            chroma_qp_index_offset: [chroma_qp_index_offset, second_chroma_qp_index_offset],
+            deblocking_filter_control_present,
+            constrained_intra_pred,
+            redundant_pic_cnt_present,
+            transform_8x8_mode,
+            scaling_lists,
+        })
+    }
+}
@@ -0,0 +1,145 @@
+/// A plane of 8-bit samples.
+#[derive(Clone)]
+pub struct Plane {
+    pub data: Vec<u8>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Plane {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            data: vec![128; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Returns the sample at the given position, clamping it to the plane as
+    /// motion compensation requires.
+    #[inline]
+    pub fn clamped(&self, x: i32, y: i32) -> u8 {
+        let x = x.clamp(0, self.width as i32 - 1) as usize;
+        let y = y.clamp(0, self.height as i32 - 1) as usize;
+        self.data[y * self.width + x]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MbKind {
+    #[default]
+    I4x4,
+    I8x8,
+    I16x16,
+    IPcm,
+    PSkip,
+    BSkip,
+    BDirect16x16,
+    /// Any other P or B macroblock.
+    Inter,
+}
+
+impl MbKind {
+    pub fn is_intra(self) -> bool {
+        matches!(self, Self::I4x4 | Self::I8x8 | Self::I16x16 | Self::IPcm)
+    }
+
+    pub fn is_skip(self) -> bool {
+        matches!(self, Self::PSkip | Self::BSkip)
+    }
+}
+
+/// What is remembered about a decoded macroblock, for the prediction of its
+/// neighbours, the deblocking filter and the direct prediction of later pictures.
+///
+/// Per-4x4 block values are in raster order within the macroblock.
+#[derive(Clone, Copy)]
+pub struct MbInfo {
+    /// The number of the slice this macroblock belongs to, starting at 1.
+    /// Zero means that the macroblock has not been decoded.
+    pub slice: u32,
+    pub kind: MbKind,
+    pub transform_8x8: bool,
+    /// `coded_block_pattern`, with the chroma part in bits 4 and 5.
+    pub cbp: u8,
+    /// `QPY`, or zero for I_PCM macroblocks.
+    pub qp: u8,
+    /// `QPc` of the Cb and Cr components.
+    pub qpc: [u8; 2],
+    pub intra_chroma_mode: u8,
+    pub intra_modes: [u8; 16],
+    /// The number of non-zero coefficients of each luma block, then each Cb and Cr block.
+    pub nz: [u8; 24],
+    /// The `coded_block_flag` of the luma, Cb and Cr DC blocks, in bits 0 to 2.
+    pub coded_dc: u8,
+    /// Which 8x8 partitions were predicted in direct mode.
+    pub direct: u8,
+    /// The reference index of each 8x8 partition, for each list; -1 if unused.
+    pub ref_idx: [[i8; 4]; 2],
+    /// The picture that each 8x8 partition refers to, for each list; -1 if unused.
+    pub ref_pic: [[i32; 4]; 2],
+    pub mv: [[[i16; 2]; 16]; 2],
+    /// The absolute motion vector differences, as CABAC context selection needs.
+    pub mvd: [[[u8; 2]; 16]; 2],
+}
+
+impl Default for MbInfo {
+    fn default() -> Self {
+        Self {
+            slice: 0,
+            kind: MbKind::default(),
+            transform_8x8: false,
+            cbp: 0,
+            qp: 0,
+            qpc: [0; 2],
+            intra_chroma_mode: 0,
+            intra_modes: [2; 16],
+            nz: [0; 24],
+            coded_dc: 0,
+            direct: 0,
+            ref_idx: [[-1; 4]; 2],
+            ref_pic: [[-1; 4]; 2],
+            mv: [[[0; 2]; 16]; 2],
+            mvd: [[[0; 2]; 16]; 2],
+        }
+    }
+}
+
+/// The deblocking parameters of a slice.
+#[derive(Clone, Copy, Debug)]
+pub struct DeblockParams {
+    pub disable_deblocking_filter_idc: u32,
+    pub alpha_offset: i32,
+    pub beta_offset: i32,
+}
+
+pub struct Picture {
+    /// Identifies this picture when it is referenced by others.
+    pub id: i32,
+    pub poc: i32,
+    pub luma: Plane,
+    pub cb: Plane,
+    pub cr: Plane,
+    pub width_mbs: usize,
+    pub mbs: Vec<MbInfo>,
+    /// The deblocking parameters of each slice, by slice number minus 1.
+    pub slices: Vec<DeblockParams>,
+    /// Left, right, top and bottom cropping of the output, in luma samples.
+    pub crop: [usize; 4],
+}
+
+impl Picture {
+    pub fn new(id: i32, poc: i32, width_mbs: usize, height_mbs: usize) -> Self {
+        Self {
+            id,
+            poc,
+            luma: Plane::new(width_mbs * 16, height_mbs * 16),
+            cb: Plane::new(width_mbs * 8, height_mbs * 8),
+            cr: Plane::new(width_mbs * 8, height_mbs * 8),
+            width_mbs,
+            mbs: vec![MbInfo::default(); width_mbs * height_mbs],
+            slices: Vec::new(),
+            crop: [0; 4],
+        }
+    }
+}
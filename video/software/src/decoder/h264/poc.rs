@@ -0,0 +1,144 @@
+use super::params::Sps;
+use super::slice::SliceHeader;
+
+/// The state carried between pictures to derive their picture order counts, as described in 8.2.1.
+#[derive(Default)]
+pub struct PocState {
+    prev_pic_order_cnt_msb: i32,
+    prev_pic_order_cnt_lsb: i32,
+    prev_frame_num_offset: i32,
+    prev_frame_num: u32,
+}
+
+/// The picture order count of a frame, and the values needed to update the state after decoding it.
+#[derive(Clone, Copy, Debug)]
+pub struct Poc {
+    pub top: i32,
+    pub bottom: i32,
+    pic_order_cnt_msb: i32,
+    frame_num_offset: i32,
+}
+
+impl Poc {
+    /// `PicOrderCnt` of the frame.
+    pub fn value(&self) -> i32 {
+        self.top.min(self.bottom)
+    }
+}
+
+impl PocState {
+    pub fn compute(&self, sps: &Sps, header: &SliceHeader) -> Poc {
+        let frame_num_offset = if header.is_idr {
+            0
+        } else if self.prev_frame_num > header.frame_num {
+            self.prev_frame_num_offset + sps.max_frame_num() as i32
+        } else {
+            self.prev_frame_num_offset
+        };
+        let is_reference = header.nal_ref_idc != 0;
+
+        match sps.pic_order_cnt_type {
+            0 => {
+                let (prev_msb, prev_lsb) = if header.is_idr {
+                    (0, 0)
+                } else {
+                    (self.prev_pic_order_cnt_msb, self.prev_pic_order_cnt_lsb)
+                };
+                let max_lsb = 1 << sps.log2_max_pic_order_cnt_lsb;
+                let lsb = header.pic_order_cnt_lsb as i32;
+                let msb = if lsb < prev_lsb && prev_lsb - lsb >= max_lsb / 2 {
+                    prev_msb + max_lsb
+                } else if lsb > prev_lsb && lsb - prev_lsb > max_lsb / 2 {
+                    prev_msb - max_lsb
+                } else {
+                    prev_msb
+                };
+                let top = msb + lsb;
+                Poc {
+                    top,
+                    bottom: top + header.delta_pic_order_cnt_bottom,
+                    pic_order_cnt_msb: msb,
+                    frame_num_offset,
+                }
+            }
+            1 => {
+                let cycle = &sps.offset_for_ref_frame;
+                let mut abs_frame_num = if cycle.is_empty() {
+                    0
+                } else {
+                    frame_num_offset + header.frame_num as i32
+                };
+                if !is_reference && abs_frame_num > 0 {
+                    abs_frame_num -= 1;
+                }
+                let mut expected = 0;
+                if abs_frame_num > 0 {
+                    let cycle_count = (abs_frame_num - 1) / cycle.len() as i32;
+                    let frame_in_cycle = (abs_frame_num - 1) as usize % cycle.len();
+                    let expected_delta_per_cycle: i32 = cycle.iter().sum();
+                    expected = cycle_count * expected_delta_per_cycle
+                        + cycle[..=frame_in_cycle].iter().sum::<i32>();
+                }
+                if !is_reference {
+                    expected += sps.offset_for_non_ref_pic;
+                }
+                let top = expected + header.delta_pic_order_cnt[0];
+                Poc {
+                    top,
+                    bottom: top
+                        + sps.offset_for_top_to_bottom_field
+                        + header.delta_pic_order_cnt[1],
+                    pic_order_cnt_msb: 0,
+                    frame_num_offset,
+                }
+            }
+            _ => {
+                let poc = if header.is_idr {
+                    0
+                } else if !is_reference {
+                    2 * (frame_num_offset + header.frame_num as i32) - 1
+                } else {
+                    2 * (frame_num_offset + header.frame_num as i32)
+                };
+                Poc {
+                    top: poc,
+                    bottom: poc,
+                    pic_order_cnt_msb: 0,
+                    frame_num_offset,
+                }
+            }
+        }
+    }
+
+    /// Remembers what the next picture needs from a decoded one.
+    ///
+    /// A memory management control operation 5 resets the picture order count,
+    /// which changes `poc` to be relative to the reset.
+    pub fn update(&mut self, header: &SliceHeader, poc: &mut Poc, had_mmco5: bool) {
+        if had_mmco5 {
+            let temp = poc.value();
+            poc.top -= temp;
+            poc.bottom -= temp;
+            poc.pic_order_cnt_msb = 0;
+            poc.frame_num_offset = 0;
+        }
+        if header.nal_ref_idc != 0 {
+            self.prev_pic_order_cnt_msb = poc.pic_order_cnt_msb;
+            self.prev_pic_order_cnt_lsb = if had_mmco5 {
+                poc.top
+            } else {
+                header.pic_order_cnt_lsb as i32
+            };
+        }
+        self.prev_frame_num_offset = poc.frame_num_offset;
+        self.prev_frame_num = if had_mmco5 { 0 } else { header.frame_num };
+    }
+
+    /// Accounts for a "non-existing" frame inferred from a gap in `frame_num`.
+    pub fn skip_frame(&mut self, sps: &Sps, frame_num: u32) {
+        if self.prev_frame_num > frame_num {
+            self.prev_frame_num_offset += sps.max_frame_num() as i32;
+        }
+        self.prev_frame_num = frame_num;
+    }
+}
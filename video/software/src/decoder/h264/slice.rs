@@ -0,0 +1,335 @@
+use super::bitstream::BitReader;
+use super::params::{Pps, Sps};
+use super::picture::DeblockParams;
+use super::H264Error;
+use std::rc::Rc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SliceType {
+    P,
+    B,
+    I,
+}
+
+/// A memory management control operation of `dec_ref_pic_marking`.
+#[derive(Clone, Copy, Debug)]
+pub enum Mmco {
+    ForgetShortTerm {
+        difference_of_pic_nums: u32,
+    },
+    ForgetLongTerm {
+        long_term_pic_num: u32,
+    },
+    ShortTermToLongTerm {
+        difference_of_pic_nums: u32,
+        long_term_frame_idx: u32,
+    },
+    SetMaxLongTermFrameIdx {
+        max_long_term_frame_idx_plus1: u32,
+    },
+    ForgetAll,
+    CurrentToLongTerm {
+        long_term_frame_idx: u32,
+    },
+}
+
+/// An explicit weight and offset for weighted prediction.
+#[derive(Clone, Copy, Debug)]
+pub struct Weight {
+    pub weight: i32,
+    pub offset: i32,
+}
+
+/// The weights of one reference picture: luma, Cb and Cr.
+pub type RefWeights = [Weight; 3];
+
+#[derive(Clone, Debug)]
+pub struct PredWeightTable {
+    pub luma_log2_denom: u32,
+    pub chroma_log2_denom: u32,
+    /// The weights of each reference index, for each list.
+    pub weights: [Vec<RefWeights>; 2],
+}
+
+#[derive(Clone, Debug)]
+pub struct SliceHeader {
+    pub nal_ref_idc: u8,
+    pub is_idr: bool,
+    pub first_mb: usize,
+    pub slice_type: SliceType,
+    pub pps_id: u32,
+    pub frame_num: u32,
+    pub idr_pic_id: u32,
+    pub pic_order_cnt_lsb: u32,
+    pub delta_pic_order_cnt_bottom: i32,
+    pub delta_pic_order_cnt: [i32; 2],
+    pub redundant_pic_cnt: u32,
+    pub direct_spatial_mv_pred: bool,
+    pub num_ref_idx_active: [usize; 2],
+    /// `(modification_of_pic_nums_idc, value)` pairs for each list.
+    pub ref_pic_list_modification: [Vec<(u32, u32)>; 2],
+    pub pred_weight_table: Option<PredWeightTable>,
+    pub no_output_of_prior_pics: bool,
+    pub long_term_reference: bool,
+    /// The operations of adaptive reference picture marking, if used.
+    pub mmcos: Option<Vec<Mmco>>,
+    pub cabac_init_idc: usize,
+    /// `SliceQPY`.
+    pub qp: i32,
+    pub deblock: DeblockParams,
+}
+
+impl SliceHeader {
+    pub fn parse(
+        reader: &mut BitReader,
+        nal_unit_type: u8,
+        nal_ref_idc: u8,
+        sps_list: &[Option<Rc<Sps>>],
+        pps_list: &[Option<Rc<Pps>>],
+    ) -> Result<(Self, Rc<Sps>, Rc<Pps>), H264Error> {
+        let is_idr = nal_unit_type == 5;
+        let first_mb = reader.read_ue()? as usize;
+        let slice_type = match reader.read_ue_max(9, "slice_type")? % 5 {
+            0 => SliceType::P,
+            1 => SliceType::B,
+            2 => SliceType::I,
+            _ => return Err(H264Error::Unsupported("SP and SI slices")),
+        };
+        if is_idr && slice_type != SliceType::I {
+            return Err(H264Error::InvalidData(
+                "IDR pictures must only contain I slices",
+            ));
+        }
+        let pps_id = reader.read_ue_max(255, "pic_parameter_set_id")?;
+        let pps = pps_list[pps_id as usize]
+            .clone()
+            .ok_or(H264Error::MissingParameterSet)?;
+        let sps = sps_list[pps.sps_id as usize]
+            .clone()
+            .ok_or(H264Error::MissingParameterSet)?;
+        if first_mb >= sps.width_mbs * sps.height_mbs {
+            return Err(H264Error::InvalidData("first_mb_in_slice"));
+        }
+
+        let frame_num = reader.read_bits(sps.log2_max_frame_num)?;
+        let idr_pic_id = if is_idr {
+            reader.read_ue_max(65535, "idr_pic_id")?
+        } else {
+            0
+        };
+        let mut pic_order_cnt_lsb = 0;
+        let mut delta_pic_order_cnt_bottom = 0;
+        let mut delta_pic_order_cnt = [0; 2];
+        if sps.pic_order_cnt_type == 0 {
+            pic_order_cnt_lsb = reader.read_bits(sps.log2_max_pic_order_cnt_lsb)?;
+            if pps.bottom_field_pic_order_in_frame_present {
+                delta_pic_order_cnt_bottom = reader.read_se()?;
+            }
+        } else if sps.pic_order_cnt_type == 1 && !sps.delta_pic_order_always_zero {
+            delta_pic_order_cnt[0] = reader.read_se()?;
+            if pps.bottom_field_pic_order_in_frame_present {
+                delta_pic_order_cnt[1] = reader.read_se()?;
+            }
+        }
+        let redundant_pic_cnt = if pps.redundant_pic_cnt_present {
+            reader.read_ue_max(127, "redundant_pic_cnt")?
+        } else {
+            0
+        };
+
+        let direct_spatial_mv_pred = slice_type == SliceType::B && reader.read_bit()?;
+        let mut num_ref_idx_active = pps.num_ref_idx_default_active.map(|n| n as usize);
+        if slice_type != SliceType::I && reader.read_bit()? {
+            num_ref_idx_active[0] =
+                reader.read_ue_max(31, "num_ref_idx_l0_active_minus1")? as usize + 1;
+            if slice_type == SliceType::B {
+                num_ref_idx_active[1] =
+                    reader.read_ue_max(31, "num_ref_idx_l1_active_minus1")? as usize + 1;
+            }
+        }
+        match slice_type {
+            SliceType::I => num_ref_idx_active = [0, 0],
+            SliceType::P => num_ref_idx_active[1] = 0,
+            SliceType::B => {}
+        }
+
+        let mut ref_pic_list_modification = [Vec::new(), Vec::new()];
+        for (list, modifications) in ref_pic_list_modification.iter_mut().enumerate() {
+            if num_ref_idx_active[list] == 0 || !reader.read_bit()? {
+                continue;
+            }
+            loop {
+                let idc = reader.read_ue_max(3, "modification_of_pic_nums_idc")?;
+                if idc == 3 {
+                    break;
+                }
+                modifications.push((idc, reader.read_ue()?));
+                if modifications.len() > 100 {
+                    return Err(H264Error::InvalidData("too many list modifications"));
+                }
+            }
+        }
+
+        let pred_weight_table = if (pps.weighted_pred && slice_type == SliceType::P)
+            || (pps.weighted_bipred_idc == 1 && slice_type == SliceType::B)
+        {
+            Some(PredWeightTable::parse(reader, num_ref_idx_active)?)
+        } else {
+            None
+        };
+
+        let mut no_output_of_prior_pics = false;
+        let mut long_term_reference = false;
+        let mut mmcos = None;
+        if nal_ref_idc != 0 {
+            if is_idr {
+                no_output_of_prior_pics = reader.read_bit()?;
+                long_term_reference = reader.read_bit()?;
+            } else if reader.read_bit()? {
+                mmcos = Some(parse_mmcos(reader)?);
+            }
+        }
+
+        let cabac_init_idc = if pps.entropy_coding_mode && slice_type != SliceType::I {
+            reader.read_ue_max(2, "cabac_init_idc")? as usize
+        } else {
+            0
+        };
+        let qp = pps.pic_init_qp + reader.read_se()?;
+        if !(0..=51).contains(&qp) {
+            return Err(H264Error::InvalidData("slice_qp_delta"));
+        }
+
+        let mut deblock = DeblockParams {
+            disable_deblocking_filter_idc: 0,
+            alpha_offset: 0,
+            beta_offset: 0,
+        };
+        if pps.deblocking_filter_control_present {
+            deblock.disable_deblocking_filter_idc =
+                reader.read_ue_max(2, "disable_deblocking_filter_idc")?;
+            if deblock.disable_deblocking_filter_idc != 1 {
+                deblock.alpha_offset =
+                    reader.read_se_range(-6, 6, "slice_alpha_c0_offset_div2")? * 2;
+                deblock.beta_offset = reader.read_se_range(-6, 6, "slice_beta_offset_div2")? * 2;
+            }
+        }
+
+        let header = Self {
+            nal_ref_idc,
+            is_idr,
+            first_mb,
+            slice_type,
+            pps_id,
+            frame_num,
+            idr_pic_id,
+            pic_order_cnt_lsb,
+            delta_pic_order_cnt_bottom,
+            delta_pic_order_cnt,
+            redundant_pic_cnt,
+            direct_spatial_mv_pred,
+            num_ref_idx_active,
+            ref_pic_list_modification,
+            pred_weight_table,
+            no_output_of_prior_pics,
+            long_term_reference,
+            mmcos,
+            cabac_init_idc,
+            qp,
+            deblock,
+        };
+        Ok((header, sps, pps))
+    }
+
+    /// Whether this slice belongs to the same picture as a previous one, as described in 7.4.1.2.4.
+    pub fn is_same_picture(&self, other: &SliceHeader) -> bool {
+        self.frame_num == other.frame_num
+            && self.pps_id == other.pps_id
+            && (self.nal_ref_idc == 0) == (other.nal_ref_idc == 0)
+            && self.pic_order_cnt_lsb == other.pic_order_cnt_lsb
+            && self.delta_pic_order_cnt_bottom == other.delta_pic_order_cnt_bottom
+            && self.delta_pic_order_cnt == other.delta_pic_order_cnt
+            && self.is_idr == other.is_idr
+            && self.idr_pic_id == other.idr_pic_id
+    }
+}
+
+impl PredWeightTable {
+    fn parse(reader: &mut BitReader, num_ref_idx_active: [usize; 2]) -> Result<Self, H264Error> {
+        let luma_log2_denom = reader.read_ue_max(7, "luma_log2_weight_denom")?;
+        let chroma_log2_denom = reader.read_ue_max(7, "chroma_log2_weight_denom")?;
+        let mut weights = [Vec::new(), Vec::new()];
+        for (list, weights) in weights.iter_mut().enumerate() {
+            for _ in 0..num_ref_idx_active[list] {
+                let mut entry = [
+                    Weight {
+                        weight: 1 << luma_log2_denom,
+                        offset: 0,
+                    },
+                    Weight {
+                        weight: 1 << chroma_log2_denom,
+                        offset: 0,
+                    },
+                    Weight {
+                        weight: 1 << chroma_log2_denom,
+                        offset: 0,
+                    },
+                ];
+                if reader.read_bit()? {
+                    entry[0].weight = reader.read_se_range(-128, 127, "luma_weight")?;
+                    entry[0].offset = reader.read_se_range(-128, 127, "luma_offset")?;
+                }
+                if reader.read_bit()? {
+                    for weight in &mut entry[1..] {
+                        weight.weight = reader.read_se_range(-128, 127, "chroma_weight")?;
+                        weight.offset = reader.read_se_range(-128, 127, "chroma_offset")?;
+                    }
+                }
+                weights.push(entry);
+            }
+        }
+        Ok(Self {
+            luma_log2_denom,
+            chroma_log2_denom,
+            weights,
+        })
+    }
+}
+
+fn parse_mmcos(reader: &mut BitReader) -> Result<Vec<Mmco>, H264Error> {
+    let mut mmcos = Vec::new();
+    loop {
+        let mmco = match reader.read_ue()? {
+            0 => break,
+            1 => Mmco::ForgetShortTerm {
+                difference_of_pic_nums: reader.read_ue()? + 1,
+            },
+            2 => Mmco::ForgetLongTerm {
+                long_term_pic_num: reader.read_ue()?,
+            },
+            3 => Mmco::ShortTermToLongTerm {
+                difference_of_pic_nums: reader.read_ue()? + 1,
+                long_term_frame_idx: reader.read_ue_max(15, "long_term_frame_idx")?,
+            },
+            4 => Mmco::SetMaxLongTermFrameIdx {
+                max_long_term_frame_idx_plus1: reader.read_ue_max(16, "max_long_term_frame_idx")?,
+            },
+            5 => Mmco::ForgetAll,
+            6 => Mmco::CurrentToLongTerm {
+                long_term_frame_idx: reader.read_ue_max(15, "long_term_frame_idx")?,
+            },
+            _ => {
+                return Err(H264Error::InvalidData(
+                    "memory_management_control_operation",
+                ))
+            }
+        };
+        mmcos.push(mmco);
+        if mmcos.len() > 66 {
+            return Err(H264Error::InvalidData(
+                "too many memory management operations",
+            ));
+        }
+    }
+    Ok(mmcos)
+}
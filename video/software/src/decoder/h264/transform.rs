@@ -0,0 +1,221 @@
+use super::params::ScalingLists;
+use super::picture::Plane;
+use super::tables::{NORM_ADJUST_4X4, NORM_ADJUST_8X8, ZIGZAG_4X4, ZIGZAG_8X8};
+
+/// The `LevelScale` factors of each scaling list, as described in 8.5.9.
+pub struct Dequant {
+    /// By list, `qP % 6` and raster position.
+    level_scale_4x4: [[[i32; 16]; 6]; 6],
+    level_scale_8x8: [[[i32; 64]; 6]; 2],
+}
+
+impl Dequant {
+    pub fn new(lists: &ScalingLists) -> Self {
+        let mut level_scale_4x4 = [[[0; 16]; 6]; 6];
+        for (scales, weights) in level_scale_4x4.iter_mut().zip(&lists.list_4x4) {
+            for (m, scales) in scales.iter_mut().enumerate() {
+                for (pos, scale) in scales.iter_mut().enumerate() {
+                    let (i, j) = (pos / 4, pos % 4);
+                    let class = match (i % 2, j % 2) {
+                        (0, 0) => 0,
+                        (1, 1) => 1,
+                        _ => 2,
+                    };
+                    *scale = i32::from(weights[pos]) * NORM_ADJUST_4X4[m][class];
+                }
+            }
+        }
+
+        let mut level_scale_8x8 = [[[0; 64]; 6]; 2];
+        for (scales, weights) in level_scale_8x8.iter_mut().zip(&lists.list_8x8) {
+            for (m, scales) in scales.iter_mut().enumerate() {
+                for (pos, scale) in scales.iter_mut().enumerate() {
+                    let (i, j) = (pos / 8, pos % 8);
+                    let class = if i % 4 == 0 && j % 4 == 0 {
+                        0
+                    } else if i % 2 == 1 && j % 2 == 1 {
+                        1
+                    } else if i % 4 == 2 && j % 4 == 2 {
+                        2
+                    } else if (i % 4 == 0 && j % 2 == 1) || (i % 2 == 1 && j % 4 == 0) {
+                        3
+                    } else if (i % 4 == 0 && j % 4 == 2) || (i % 4 == 2 && j % 4 == 0) {
+                        4
+                    } else {
+                        5
+                    };
+                    *scale = i32::from(weights[pos]) * NORM_ADJUST_8X8[m][class];
+                }
+            }
+        }
+
+        Self {
+            level_scale_4x4,
+            level_scale_8x8,
+        }
+    }
+
+    /// Scales the coefficients of a 4x4 block, given in zigzag order, into raster order.
+    pub fn dequant_4x4(&self, coeffs: &[i32; 16], list: usize, qp: i32) -> [i32; 16] {
+        let scales = &self.level_scale_4x4[list][(qp % 6) as usize];
+        let shift = qp / 6;
+        let mut out = [0; 16];
+        for (&coeff, &pos) in coeffs.iter().zip(&ZIGZAG_4X4) {
+            if coeff == 0 {
+                continue;
+            }
+            let value = coeff * scales[pos];
+            out[pos] = if shift >= 4 {
+                value << (shift - 4)
+            } else {
+                (value + (1 << (3 - shift))) >> (4 - shift)
+            };
+        }
+        out
+    }
+
+    /// Scales the coefficients of an 8x8 block, given in zigzag order, into raster order.
+    pub fn dequant_8x8(&self, coeffs: &[i32; 64], list: usize, qp: i32) -> [i32; 64] {
+        let scales = &self.level_scale_8x8[list][(qp % 6) as usize];
+        let shift = qp / 6;
+        let mut out = [0; 64];
+        for (&coeff, &pos) in coeffs.iter().zip(&ZIGZAG_8X8) {
+            if coeff == 0 {
+                continue;
+            }
+            let value = coeff * scales[pos];
+            out[pos] = if shift >= 6 {
+                value << (shift - 6)
+            } else {
+                (value + (1 << (5 - shift))) >> (6 - shift)
+            };
+        }
+        out
+    }
+
+    /// Transforms and scales the luma DC coefficients of an Intra_16x16 macroblock,
+    /// returning the DC of each 4x4 block in raster order.
+    pub fn luma_dc(&self, coeffs: &[i32; 16], qp: i32) -> [i32; 16] {
+        let mut c = [0; 16];
+        for (&coeff, &pos) in coeffs.iter().zip(&ZIGZAG_4X4) {
+            c[pos] = coeff;
+        }
+        for row in c.chunks_exact_mut(4) {
+            let [a, b, cc, d] = [row[0], row[1], row[2], row[3]];
+            row[0] = a + b + cc + d;
+            row[1] = a + b - cc - d;
+            row[2] = a - b - cc + d;
+            row[3] = a - b + cc - d;
+        }
+        for x in 0..4 {
+            let [a, b, cc, d] = [c[x], c[4 + x], c[8 + x], c[12 + x]];
+            c[x] = a + b + cc + d;
+            c[4 + x] = a + b - cc - d;
+            c[8 + x] = a - b - cc + d;
+            c[12 + x] = a - b + cc - d;
+        }
+
+        let scale = self.level_scale_4x4[0][(qp % 6) as usize][0];
+        let shift = qp / 6;
+        c.map(|f| {
+            if shift >= 6 {
+                (f * scale) << (shift - 6)
+            } else {
+                (f * scale + (1 << (5 - shift))) >> (6 - shift)
+            }
+        })
+    }
+
+    /// Transforms and scales the DC coefficients of a chroma component,
+    /// returning the DC of each 4x4 block in raster order.
+    pub fn chroma_dc(&self, coeffs: &[i32; 4], list: usize, qp: i32) -> [i32; 4] {
+        let [c0, c1, c2, c3] = *coeffs;
+        let f = [
+            c0 + c1 + c2 + c3,
+            c0 - c1 + c2 - c3,
+            c0 + c1 - c2 - c3,
+            c0 - c1 - c2 + c3,
+        ];
+        let scale = self.level_scale_4x4[list][(qp % 6) as usize][0];
+        f.map(|f| ((f * scale) << (qp / 6)) >> 5)
+    }
+}
+
+/// Inverse transforms a 4x4 block of scaled coefficients and adds it to the prediction in a plane.
+pub fn add_4x4(plane: &mut Plane, x: usize, y: usize, mut d: [i32; 16]) {
+    for row in d.chunks_exact_mut(4) {
+        let e = row[0] + row[2];
+        let f = row[0] - row[2];
+        let g = (row[1] >> 1) - row[3];
+        let h = row[1] + (row[3] >> 1);
+        row[0] = e + h;
+        row[1] = f + g;
+        row[2] = f - g;
+        row[3] = e - h;
+    }
+    for i in 0..4 {
+        let e = d[i] + d[8 + i];
+        let f = d[i] - d[8 + i];
+        let g = (d[4 + i] >> 1) - d[12 + i];
+        let h = d[4 + i] + (d[12 + i] >> 1);
+        d[i] = e + h;
+        d[4 + i] = f + g;
+        d[8 + i] = f - g;
+        d[12 + i] = e - h;
+    }
+    add_residual(plane, x, y, 4, &d);
+}
+
+/// The one-dimensional 8x8 inverse transform of 8.5.13.2, on every `stride`th element.
+fn idct_8(d: &mut [i32], stride: usize) {
+    let v: [i32; 8] = std::array::from_fn(|i| d[i * stride]);
+    let a0 = v[0] + v[4];
+    let a4 = v[0] - v[4];
+    let a2 = (v[2] >> 1) - v[6];
+    let a6 = v[2] + (v[6] >> 1);
+    let b0 = a0 + a6;
+    let b2 = a4 + a2;
+    let b4 = a4 - a2;
+    let b6 = a0 - a6;
+    let a1 = -v[3] + v[5] - v[7] - (v[7] >> 1);
+    let a3 = v[1] + v[7] - v[3] - (v[3] >> 1);
+    let a5 = -v[1] + v[7] + v[5] + (v[5] >> 1);
+    let a7 = v[3] + v[5] + v[1] + (v[1] >> 1);
+    let b1 = a1 + (a7 >> 2);
+    let b7 = a7 - (a1 >> 2);
+    let b3 = a3 + (a5 >> 2);
+    let b5 = (a3 >> 2) - a5;
+    let out = [
+        b0 + b7,
+        b2 + b5,
+        b4 + b3,
+        b6 + b1,
+        b6 - b1,
+        b4 - b3,
+        b2 - b5,
+        b0 - b7,
+    ];
+    for (i, value) in out.into_iter().enumerate() {
+        d[i * stride] = value;
+    }
+}
+
+/// Inverse transforms an 8x8 block of scaled coefficients and adds it to the prediction in a plane.
+pub fn add_8x8(plane: &mut Plane, x: usize, y: usize, mut d: [i32; 64]) {
+    for row in d.chunks_exact_mut(8) {
+        idct_8(row, 1);
+    }
+    for column in 0..8 {
+        idct_8(&mut d[column..], 8);
+    }
+    add_residual(plane, x, y, 8, &d);
+}
+
+fn add_residual(plane: &mut Plane, x: usize, y: usize, size: usize, residual: &[i32]) {
+    for (row, residual) in residual.chunks_exact(size).enumerate() {
+        let start = (y + row) * plane.width + x;
+        for (sample, &r) in plane.data[start..start + size].iter_mut().zip(residual) {
+            *sample = (i32::from(*sample) + ((r + 32) >> 6)).clamp(0, 255) as u8;
+        }
+    }
+}
@@ -5,7 +5,7 @@ use crate::decoder::VideoDecoder;
 use ruffle_render::bitmap::BitmapFormat;
 use ruffle_video::error::Error;
 
-use flate2::Decompress;
+use flate2::{Decompress, FlushDecompress};
 use ruffle_video::frame::{DecodedFrame, EncodedFrame, FrameDependency};
 
 #[derive(thiserror::Error, Debug)]
@@ -24,6 +24,18 @@ pub enum ScreenError {
 
     #[error("Not all blocks were updated by a supposed keyframe")]
     KeyframeInvalid,
+
+    #[error("Unsupported color depth: {0}")]
+    UnsupportedColorDepth(u8),
+
+    #[error("Diff block rows {0}..{1} are outside of the block")]
+    InvalidDiffBlock(usize, usize),
+
+    #[error("Missing block data for zlib priming")]
+    MissingPrimingBlock,
+
+    #[error("Image block data is too short")]
+    ImageBlockTooShort,
 }
 
 impl From<ScreenError> for Error {
@@ -32,8 +44,38 @@ impl From<ScreenError> for Error {
     }
 }
 
-/// Screen Video (V1 only) decoder.
+/// The palette used by the hybrid color mode of Screen Video V2 until a
+/// stream provides its own, as `0xRRGGBB` values.
+#[rustfmt::skip]
+const DEFAULT_PALETTE: [u32; 128] = [
+    0x000000, 0x333333, 0x666666, 0x999999, 0xCCCCCC, 0xFFFFFF,
+    0x330000, 0x660000, 0x990000, 0xCC0000, 0xFF0000, 0x003300,
+    0x006600, 0x009900, 0x00CC00, 0x00FF00, 0x000033, 0x000066,
+    0x000099, 0x0000CC, 0x0000FF, 0x333300, 0x666600, 0x999900,
+    0xCCCC00, 0xFFFF00, 0x003333, 0x006666, 0x009999, 0x00CCCC,
+    0x00FFFF, 0x330033, 0x660066, 0x990099, 0xCC00CC, 0xFF00FF,
+    0xFFFF33, 0xFFFF66, 0xFFFF99, 0xFFFFCC, 0xFF33FF, 0xFF66FF,
+    0xFF99FF, 0xFFCCFF, 0x33FFFF, 0x66FFFF, 0x99FFFF, 0xCCFFFF,
+    0xCCCC33, 0xCCCC66, 0xCCCC99, 0xCCCCFF, 0xCC33CC, 0xCC66CC,
+    0xCC99CC, 0xCCFFCC, 0x33CCCC, 0x66CCCC, 0x99CCCC, 0xFFCCCC,
+    0x999933, 0x999966, 0x9999CC, 0x9999FF, 0x993399, 0x996699,
+    0x99CC99, 0x99FF99, 0x339999, 0x669999, 0xCC9999, 0xFF9999,
+    0x666633, 0x666699, 0x6666CC, 0x6666FF, 0x663366, 0x669966,
+    0x66CC66, 0x66FF66, 0x336666, 0x996666, 0xCC6666, 0xFF6666,
+    0x333366, 0x333399, 0x3333CC, 0x3333FF, 0x336633, 0x339933,
+    0x33CC33, 0x33FF33, 0x663333, 0x993333, 0xCC3333, 0xFF3333,
+    0x003366, 0x336600, 0x660033, 0x006633, 0x330066, 0x663300,
+    0x336699, 0x669933, 0x993366, 0x339966, 0x663399, 0x996633,
+    0x6699CC, 0x99CC66, 0xCC6699, 0x66CC99, 0x9966CC, 0xCC9966,
+    0x99CCFF, 0xCCFF99, 0xFF99CC, 0x99FFCC, 0xCC99FF, 0xFFCC99,
+    0x111111, 0x222222, 0x444444, 0x555555, 0xAAAAAA, 0xBBBBBB,
+    0xDDDDDD, 0xEEEEEE,
+];
+
+/// Screen Video (V1 and V2) decoder.
 pub struct ScreenVideoDecoder {
+    is_v2: bool,
+
     w: usize,
     h: usize,
     block_w: usize,
@@ -42,6 +84,16 @@ pub struct ScreenVideoDecoder {
     tile: Vec<u8>, // acts as a scratch buffer
 
     last_frame: Option<Vec<u8>>,
+
+    /// The image of the last keyframe, which V2 diff blocks are relative to.
+    keyframe: Option<Vec<u8>>,
+
+    /// The decompressed data of each block of the last keyframe, which V2
+    /// blocks can prime their zlib stream with.
+    keyframe_blocks: Vec<Vec<u8>>,
+
+    /// The palette of the V2 hybrid color mode, as `0xRRGGBB` values.
+    palette: [u32; 128],
 }
 
 struct ByteReader<'a> {
@@ -69,7 +121,7 @@ impl<'a> ByteReader<'a> {
         Ok((byte1 as u16) << 8 | (byte2 as u16))
     }
 
-    fn read_buf_ref(&mut self, length: usize) -> Result<&[u8], ScreenError> {
+    fn read_buf_ref(&mut self, length: usize) -> Result<&'a [u8], ScreenError> {
         if self.pos + length > self.data.len() {
             return Err(ScreenError::UnexpectedEOF);
         }
@@ -77,17 +129,88 @@ impl<'a> ByteReader<'a> {
         self.pos += length;
         Ok(result)
     }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+}
+
+/// The header of a V2 `IMAGEBLOCKV2`, which follows its size.
+struct ImageBlockHeader {
+    color_depth: u8,
+    /// The rows of the block that are coded, counted from its bottom,
+    /// if only those differ from the keyframe.
+    diff_rows: Option<(usize, usize)>,
+    /// The position of a block in the current frame to prime the zlib stream with.
+    prime_current: Option<(usize, usize)>,
+    /// Whether to prime the zlib stream with the same block of the last keyframe.
+    prime_previous: bool,
+}
+
+impl ImageBlockHeader {
+    fn read(src: &mut ByteReader) -> Result<Self, ScreenError> {
+        let flags = src.read_byte()?;
+        let color_depth = (flags >> 3) & 0b11;
+        let diff_rows = if flags & 0b100 != 0 {
+            let start = src.read_byte()? as usize;
+            let height = src.read_byte()? as usize;
+            Some((start, height))
+        } else {
+            None
+        };
+        let prime_current = if flags & 0b10 != 0 {
+            let column = src.read_byte()? as usize;
+            let row = src.read_byte()? as usize;
+            Some((column, row))
+        } else {
+            None
+        };
+        Ok(Self {
+            color_depth,
+            diff_rows,
+            prime_current,
+            prime_previous: flags & 0b1 != 0,
+        })
+    }
+}
+
+/// Inflates a zlib stream, optionally continuing one primed with earlier data.
+///
+/// Primed blocks of V2 don't start a new zlib stream; they continue one that
+/// has already produced `prime`, so back-references can reach into it.
+fn inflate(data: &[u8], prime: Option<&[u8]>, out: &mut Vec<u8>) -> Result<(), ScreenError> {
+    let mut decompress = Decompress::new(prime.is_none());
+    if let Some(prime) = prime {
+        // Feed the priming data as stored deflate blocks, without a zlib header.
+        let mut stored = Vec::with_capacity(prime.len() + prime.len() / 0xFFFF * 5 + 5);
+        for chunk in prime.chunks(0xFFFF) {
+            let length = chunk.len() as u16;
+            stored.push(0);
+            stored.extend_from_slice(&length.to_le_bytes());
+            stored.extend_from_slice(&(!length).to_le_bytes());
+            stored.extend_from_slice(chunk);
+        }
+        let mut primed = Vec::with_capacity(prime.len());
+        decompress.decompress_vec(&stored, &mut primed, FlushDecompress::Sync)?;
+    }
+    out.clear();
+    decompress.decompress_vec(data, out, FlushDecompress::Finish)?;
+    Ok(())
 }
 
 impl ScreenVideoDecoder {
-    pub fn new() -> Self {
+    pub fn new(is_v2: bool) -> Self {
         Self {
+            is_v2,
             w: 0,
             h: 0,
             block_w: 0,
             block_h: 0,
             tile: vec![],
             last_frame: None,
+            keyframe: None,
+            keyframe_blocks: vec![],
+            palette: DEFAULT_PALETTE,
         }
     }
 
@@ -127,8 +250,195 @@ impl ScreenVideoDecoder {
         Ok(is_intra)
     }
 
+    /// Decodes the `SCREENV2VIDEOPACKET` that follows the image and block sizes.
+    fn decode_v2(
+        &mut self,
+        src: &mut ByteReader,
+        data: &mut [u8],
+        stride: usize,
+        is_keyframe: bool,
+    ) -> Result<bool, Error> {
+        let flags = src.read_byte()?;
+        let has_iframe_image = flags & 0b10 != 0;
+        let has_palette_info = flags & 0b1 != 0;
+
+        if has_palette_info {
+            self.read_palette(src)?;
+        }
+
+        let mut blocks = vec![];
+        let is_intra = self.decode_blocks_v2(src, data, stride, &mut blocks)?;
+
+        if is_keyframe {
+            self.keyframe = Some(data.to_vec());
+            self.keyframe_blocks = blocks;
+        }
+
+        if has_iframe_image {
+            // The I-frame image replaces the keyframe that later frames refer to,
+            // without being displayed itself.
+            let mut keyframe = self
+                .keyframe
+                .take()
+                .unwrap_or_else(|| vec![0; self.w * self.h * 3]);
+            let mut blocks = vec![];
+            self.decode_blocks_v2(src, &mut keyframe, stride, &mut blocks)?;
+            self.keyframe = Some(keyframe);
+            self.keyframe_blocks = blocks;
+        }
+
+        Ok(is_intra)
+    }
+
+    /// Reads the `PaletteInfo` of a V2 packet, which is a compressed list of
+    /// BGR colors in the shape of an image block.
+    fn read_palette(&mut self, src: &mut ByteReader) -> Result<(), ScreenError> {
+        let size = src.read_u16be()? as usize;
+        if size == 0 {
+            return Ok(());
+        }
+        let mut block = ByteReader::new(src.read_buf_ref(size)?);
+        // The image format flags don't apply to a palette.
+        block.read_byte()?;
+        // Inflating only fills the capacity of the buffer.
+        let mut colors = Vec::with_capacity(self.palette.len() * 3);
+        inflate(block.remaining(), None, &mut colors)?;
+        for (entry, bgr) in self.palette.iter_mut().zip(colors.chunks_exact(3)) {
+            *entry = (bgr[2] as u32) << 16 | (bgr[1] as u32) << 8 | bgr[0] as u32;
+        }
+        Ok(())
+    }
+
+    /// Decodes one `IMAGEBLOCKV2` for each block of the image into `data`.
+    ///
+    /// The decompressed data of each block is collected into `blocks`, for
+    /// later blocks to prime their zlib streams with.
+    fn decode_blocks_v2(
+        &mut self,
+        src: &mut ByteReader,
+        data: &mut [u8],
+        stride: usize,
+        blocks: &mut Vec<Vec<u8>>,
+    ) -> Result<bool, Error> {
+        let columns = self.w.div_ceil(self.block_w);
+        let mut is_intra = true;
+        for (yy, row) in data.chunks_mut(stride * self.block_h).enumerate() {
+            let cur_h = (self.h - yy * self.block_h).min(self.block_h);
+            for (xx, x) in (0..self.w).step_by(self.block_w).enumerate() {
+                let cur_w = (self.w - x).min(self.block_w);
+
+                let data_size = src.read_u16be()? as usize;
+                if data_size == 0 {
+                    is_intra = false;
+                    blocks.push(vec![]);
+                    continue;
+                }
+                let mut block = ByteReader::new(src.read_buf_ref(data_size)?);
+                let header = ImageBlockHeader::read(&mut block)?;
+
+                let (start, height) = match header.diff_rows {
+                    Some((start, height)) => {
+                        if start + height > cur_h {
+                            return Err(ScreenError::InvalidDiffBlock(start, start + height).into());
+                        }
+                        // The rows that aren't coded are those of the keyframe.
+                        let keyframe = self
+                            .keyframe
+                            .as_ref()
+                            .ok_or(ScreenError::MissingReferenceFrame)?;
+                        let offset = yy * self.block_h * stride;
+                        for line in 0..cur_h {
+                            let line_start = line * stride + x * 3;
+                            row[line_start..line_start + cur_w * 3].copy_from_slice(
+                                &keyframe[offset + line_start..offset + line_start + cur_w * 3],
+                            );
+                        }
+                        is_intra = false;
+                        (start, height)
+                    }
+                    None => (0, cur_h),
+                };
+
+                let prime = match (header.prime_current, header.prime_previous) {
+                    (Some((column, row)), _) => Some(
+                        blocks
+                            .get(row * columns + column)
+                            .filter(|block| !block.is_empty())
+                            .ok_or(ScreenError::MissingPrimingBlock)?,
+                    ),
+                    (None, true) => Some(
+                        self.keyframe_blocks
+                            .get(blocks.len())
+                            .filter(|block| !block.is_empty())
+                            .ok_or(ScreenError::MissingPrimingBlock)?,
+                    ),
+                    (None, false) => None,
+                };
+                let mut decompressed = Vec::with_capacity(cur_w * cur_h * 3);
+                inflate(
+                    block.remaining(),
+                    prime.map(Vec::as_slice),
+                    &mut decompressed,
+                )?;
+
+                let lines = row[(start * stride)..]
+                    .chunks_mut(stride)
+                    .take(height)
+                    .map(|line| &mut line[x * 3..(x + cur_w) * 3]);
+                match header.color_depth {
+                    0 => {
+                        if decompressed.len() < cur_w * height * 3 {
+                            return Err(ScreenError::ImageBlockTooShort.into());
+                        }
+                        for (dst, src) in lines.zip(decompressed.chunks(cur_w * 3)) {
+                            dst.copy_from_slice(src);
+                        }
+                    }
+                    2 => self.decode_hybrid(&decompressed, lines)?,
+                    depth => return Err(ScreenError::UnsupportedColorDepth(depth).into()),
+                }
+
+                debug_assert_eq!(blocks.len(), yy * columns + xx);
+                blocks.push(decompressed);
+            }
+        }
+        Ok(is_intra)
+    }
+
+    /// Decodes the hybrid color mode of V2, where each pixel is either a
+    /// 7-bit palette index or a 15-bit RGB color with the top bit set.
+    fn decode_hybrid<'a>(
+        &self,
+        mut src: &[u8],
+        lines: impl Iterator<Item = &'a mut [u8]>,
+    ) -> Result<(), ScreenError> {
+        for line in lines {
+            for pixel in line.chunks_exact_mut(3) {
+                let (&first, rest) = src.split_first().ok_or(ScreenError::ImageBlockTooShort)?;
+                if first & 0x80 != 0 {
+                    let (&second, rest) =
+                        rest.split_first().ok_or(ScreenError::ImageBlockTooShort)?;
+                    let color = u16::from_be_bytes([first & 0x7F, second]);
+                    // Expand each 5-bit channel to 8 bits by repeating its top bits.
+                    let expand = |c: u16| ((c << 3) | (c >> 2)) as u8;
+                    pixel[0] = expand(color & 0x1F);
+                    pixel[1] = expand((color >> 5) & 0x1F);
+                    pixel[2] = expand(color >> 10);
+                    src = rest;
+                } else {
+                    let color = self.palette[first as usize];
+                    pixel.copy_from_slice(&[color as u8, (color >> 8) as u8, (color >> 16) as u8]);
+                    src = rest;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn flush(&mut self) {
         self.last_frame = None;
+        self.keyframe = None;
+        self.keyframe_blocks.clear();
     }
 }
 
@@ -139,7 +449,7 @@ impl VideoDecoder for ScreenVideoDecoder {
         // in FLV. This is super helpful, because it encodes whether the frame is a keyframe or not.
 
         // Just a quick sanity check for codec IDs...
-        debug_assert!(encoded_frame.data[0] & 0xF == 3);
+        debug_assert!(encoded_frame.data[0] & 0xF == if self.is_v2 { 6 } else { 3 });
 
         match encoded_frame.data[0] >> 4 {
             1 => Ok(FrameDependency::None),
@@ -184,10 +494,19 @@ impl VideoDecoder for ScreenVideoDecoder {
 
         let stride = w * 3;
 
-        let is_intra = self.decode_v1(&mut br, data.as_mut_slice(), stride)?;
+        if self.is_v2 {
+            let is_intra = self.decode_v2(&mut br, data.as_mut_slice(), stride, is_keyframe)?;
+
+            // Unlike V1, interframes of V2 may update every block.
+            if is_keyframe && !is_intra {
+                return Err(ScreenError::KeyframeInvalid.into());
+            }
+        } else {
+            let is_intra = self.decode_v1(&mut br, data.as_mut_slice(), stride)?;
 
-        if is_intra != is_keyframe {
-            return Err(ScreenError::KeyframeInvalid.into());
+            if is_intra != is_keyframe {
+                return Err(ScreenError::KeyframeInvalid.into());
+            }
         }
 
         let mut rgb = vec![0u8; w * h * 3];
@@ -215,6 +534,280 @@ impl VideoDecoder for ScreenVideoDecoder {
 
 impl Default for ScreenVideoDecoder {
     fn default() -> Self {
-        Self::new()
+        Self::new(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScreenError, ScreenVideoDecoder};
+    use crate::decoder::VideoDecoder;
+    use flate2::{Compress, Compression, FlushCompress};
+    use ruffle_video::error::Error;
+    use ruffle_video::frame::{DecodedFrame, EncodedFrame};
+    use swf::VideoCodec;
+
+    /// The image is 20x20 with 16x16 blocks, so the blocks are (in the bottom-up order of the
+    /// bitstream) 16x16, 4x16, 16x4 and 4x4 pixels big.
+    const SIZE: usize = 20;
+    const BLOCK_SIZES: [(usize, usize); 4] = [(16, 16), (4, 16), (16, 4), (4, 4)];
+
+    const KEYFRAME: u8 = 0x16;
+    const INTERFRAME: u8 = 0x26;
+
+    const RAW: u8 = 0;
+    const HYBRID: u8 = 2 << 3;
+    const HAS_DIFF_BLOCKS: u8 = 0b100;
+    const PRIME_CURRENT: u8 = 0b10;
+    const PRIME_PREVIOUS: u8 = 0b1;
+
+    /// Compresses `data` as a zlib stream, or as the raw deflate stream that continues after `prime`.
+    fn compress(data: &[u8], prime: Option<&[u8]>) -> Vec<u8> {
+        let prime_len = prime.map_or(0, <[u8]>::len);
+        let mut compress = Compress::new(Compression::best(), prime.is_none());
+        let mut out = Vec::with_capacity(2 * (data.len() + prime_len) + 64);
+        if let Some(prime) = prime {
+            compress
+                .compress_vec(prime, &mut out, FlushCompress::Sync)
+                .unwrap();
+            out.clear();
+        }
+        compress
+            .compress_vec(data, &mut out, FlushCompress::Finish)
+            .unwrap();
+        out
+    }
+
+    /// An `IMAGEBLOCKV2` (or `PaletteInfo`), preceded by its size.
+    fn block(flags: u8, extra: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut block = ((1 + extra.len() + data.len()) as u16)
+            .to_be_bytes()
+            .to_vec();
+        block.push(flags);
+        block.extend_from_slice(extra);
+        block.extend_from_slice(data);
+        block
+    }
+
+    fn raw_block(index: usize, bgr: [u8; 3]) -> Vec<u8> {
+        let (width, height) = BLOCK_SIZES[index];
+        block(RAW, &[], &compress(&bgr.repeat(width * height), None))
+    }
+
+    const SKIPPED: [u8; 2] = [0, 0];
+
+    fn packet(frame_type: u8, flags: u8, contents: &[&[u8]]) -> Vec<u8> {
+        let mut packet = vec![frame_type];
+        packet.extend_from_slice(&(SIZE as u16).to_be_bytes());
+        packet.extend_from_slice(&(SIZE as u16).to_be_bytes());
+        packet.push(flags);
+        packet.extend(contents.concat());
+        packet
+    }
+
+    fn decode(decoder: &mut ScreenVideoDecoder, data: &[u8]) -> Result<DecodedFrame, Error> {
+        let frame = || EncodedFrame {
+            codec: VideoCodec::ScreenVideoV2,
+            data,
+            frame_id: 0,
+        };
+        decoder.preload_frame(frame())?;
+        decoder.decode_frame(frame())
+    }
+
+    /// Returns the RGB color of a pixel, counted from the top left.
+    fn pixel(frame: &DecodedFrame, x: usize, y: usize) -> [u8; 3] {
+        let start = (y * SIZE + x) * 3;
+        frame.data()[start..start + 3].try_into().unwrap()
+    }
+
+    #[test]
+    fn keyframe_is_flipped_and_converted_to_rgb() {
+        let mut decoder = ScreenVideoDecoder::new(true);
+        // The rows of the bottom left block get brighter towards the top of the image.
+        let rows: Vec<u8> = (0..16u8)
+            .flat_map(|row| [row * 10, 0, 255].repeat(16))
+            .collect();
+        let frame = decode(
+            &mut decoder,
+            &packet(
+                KEYFRAME,
+                0,
+                &[
+                    &block(RAW, &[], &compress(&rows, None)),
+                    &raw_block(1, [0, 255, 0]),
+                    &raw_block(2, [255, 0, 0]),
+                    &raw_block(3, [0, 128, 128]),
+                ],
+            ),
+        )
+        .unwrap();
+
+        assert_eq!((frame.width(), frame.height()), (20, 20));
+        assert_eq!(pixel(&frame, 0, 19), [255, 0, 0]);
+        assert_eq!(pixel(&frame, 15, 4), [255, 0, 150]);
+        assert_eq!(pixel(&frame, 16, 19), [0, 255, 0]);
+        assert_eq!(pixel(&frame, 0, 0), [0, 0, 255]);
+        assert_eq!(pixel(&frame, 19, 3), [128, 128, 0]);
+    }
+
+    #[test]
+    fn interframes_update_blocks_and_rows_of_the_keyframe() {
+        let mut decoder = ScreenVideoDecoder::new(true);
+        let keyframe = packet(
+            KEYFRAME,
+            0,
+            &[
+                &raw_block(0, [0, 0, 255]),
+                &raw_block(1, [0, 255, 0]),
+                &raw_block(2, [255, 0, 0]),
+                &raw_block(3, [0, 128, 128]),
+            ],
+        );
+        decode(&mut decoder, &keyframe).unwrap();
+
+        // Replace the bottom right block, and the second and third row of the top left one.
+        let rows = block(
+            RAW | HAS_DIFF_BLOCKS,
+            &[1, 2],
+            &compress(&[255; 16 * 2 * 3], None),
+        );
+        let frame = decode(
+            &mut decoder,
+            &packet(
+                INTERFRAME,
+                0,
+                &[&SKIPPED, &raw_block(1, [0, 0, 0]), &rows, &SKIPPED],
+            ),
+        )
+        .unwrap();
+        assert_eq!(pixel(&frame, 0, 19), [255, 0, 0]);
+        assert_eq!(pixel(&frame, 16, 19), [0, 0, 0]);
+        assert_eq!(pixel(&frame, 0, 3), [0, 0, 255]);
+        assert_eq!(pixel(&frame, 0, 2), [255, 255, 255]);
+        assert_eq!(pixel(&frame, 0, 1), [255, 255, 255]);
+        assert_eq!(pixel(&frame, 0, 0), [0, 0, 255]);
+        assert_eq!(pixel(&frame, 19, 0), [128, 128, 0]);
+
+        // Diff blocks are relative to the keyframe, not to the previous frame.
+        let rows = block(
+            RAW | HAS_DIFF_BLOCKS,
+            &[0, 1],
+            &compress(&[0; 16 * 3], None),
+        );
+        let frame = decode(
+            &mut decoder,
+            &packet(INTERFRAME, 0, &[&SKIPPED, &SKIPPED, &rows, &SKIPPED]),
+        )
+        .unwrap();
+        assert_eq!(pixel(&frame, 16, 19), [0, 0, 0]);
+        assert_eq!(pixel(&frame, 0, 3), [0, 0, 0]);
+        assert_eq!(pixel(&frame, 0, 2), [0, 0, 255]);
+        assert_eq!(pixel(&frame, 0, 1), [0, 0, 255]);
+    }
+
+    #[test]
+    fn hybrid_blocks_use_the_palette() {
+        let mut decoder = ScreenVideoDecoder::new(true);
+        // Replace the first two palette entries.
+        let palette = block(
+            0,
+            &[],
+            &compress(&[0x10, 0x20, 0x30, 0x40, 0x50, 0x60], None),
+        );
+        // Palette entries 0, 1 and 2, then a 15-bit color, then white for the rest of the block.
+        let mut colors = vec![0, 1, 2, 0xFC, 0x00];
+        colors.resize(colors.len() + 16 * 16 - 4, 5);
+        let white = |index: usize| {
+            let (width, height) = BLOCK_SIZES[index];
+            block(HYBRID, &[], &compress(&vec![5; width * height], None))
+        };
+        let frame = decode(
+            &mut decoder,
+            &packet(
+                KEYFRAME,
+                0b1,
+                &[
+                    &palette,
+                    &block(HYBRID, &[], &compress(&colors, None)),
+                    &white(1),
+                    &white(2),
+                    &white(3),
+                ],
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(pixel(&frame, 0, 19), [0x30, 0x20, 0x10]);
+        assert_eq!(pixel(&frame, 1, 19), [0x60, 0x50, 0x40]);
+        // Entries that the palette doesn't replace keep their default color.
+        assert_eq!(pixel(&frame, 2, 19), [0x66, 0x66, 0x66]);
+        assert_eq!(pixel(&frame, 3, 19), [255, 0, 0]);
+        assert_eq!(pixel(&frame, 4, 19), [255, 255, 255]);
+        assert_eq!(pixel(&frame, 19, 0), [255, 255, 255]);
+    }
+
+    #[test]
+    fn primed_blocks_continue_earlier_zlib_streams() {
+        let mut decoder = ScreenVideoDecoder::new(true);
+        let first: Vec<u8> = (0..16 * 16 * 3).map(|i| (i * 7 % 251) as u8).collect();
+        let second = &first[..4 * 16 * 3];
+        let keyframe = packet(
+            KEYFRAME,
+            0,
+            &[
+                &block(RAW, &[], &compress(&first, None)),
+                // Primed with the block in column 0 and row 0 of this frame.
+                &block(
+                    RAW | PRIME_CURRENT,
+                    &[0, 0],
+                    &compress(second, Some(&first)),
+                ),
+                &raw_block(2, [0, 0, 0]),
+                &raw_block(3, [0, 0, 0]),
+            ],
+        );
+        let frame = decode(&mut decoder, &keyframe).unwrap();
+        assert_eq!(pixel(&frame, 0, 19), [first[2], first[1], first[0]]);
+        assert_eq!(pixel(&frame, 17, 19), [second[5], second[4], second[3]]);
+
+        // Primed with the same block of the keyframe.
+        let third: Vec<u8> = first.iter().rev().copied().collect();
+        let frame = decode(
+            &mut decoder,
+            &packet(
+                INTERFRAME,
+                0,
+                &[
+                    &block(RAW | PRIME_PREVIOUS, &[], &compress(&third, Some(&first))),
+                    &SKIPPED,
+                    &SKIPPED,
+                    &SKIPPED,
+                ],
+            ),
+        )
+        .unwrap();
+        assert_eq!(pixel(&frame, 0, 19), [third[2], third[1], third[0]]);
+        assert_eq!(pixel(&frame, 17, 19), [second[5], second[4], second[3]]);
+    }
+
+    #[test]
+    fn invalid_frames_are_rejected() {
+        let mut decoder = ScreenVideoDecoder::new(true);
+        let interframe = packet(INTERFRAME, 0, &[&SKIPPED, &SKIPPED, &SKIPPED, &SKIPPED]);
+        assert_eq!(
+            decode(&mut decoder, &interframe).unwrap_err().to_string(),
+            ScreenError::MissingReferenceFrame.to_string()
+        );
+
+        let keyframe = packet(
+            KEYFRAME,
+            0,
+            &[&raw_block(0, [0; 3]), &SKIPPED, &SKIPPED, &SKIPPED],
+        );
+        assert_eq!(
+            decode(&mut decoder, &keyframe).unwrap_err().to_string(),
+            ScreenError::KeyframeInvalid.to_string()
+        );
     }
 }
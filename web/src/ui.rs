@@ -1,17 +1,24 @@
 use super::JavascriptPlayer;
 use rfd::{AsyncFileDialog, FileHandle};
 use ruffle_core::backend::ui::{
-    DialogLoaderError, DialogResultFuture, FileDialogResult, FileFilter,
+    ClipboardData, ClipboardFormat, CursorImage, CustomMouseCursor, DialogLoaderError,
+    DialogResultFuture, FileDialogResult, FileFilter, MultipleDialogResultFuture,
 };
 use ruffle_core::backend::ui::{
     FontDefinition, FullscreenError, LanguageIdentifier, MouseCursor, UiBackend, US_ENGLISH,
 };
+use ruffle_core::swf::{Rectangle, Twips};
 use ruffle_web_common::JsResult;
 use std::borrow::Cow;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::Arc;
 use url::Url;
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::prelude::Closure;
+use wasm_bindgen::{Clamped, JsCast, JsValue};
 use web_sys::{
-    Blob, HtmlCanvasElement, HtmlDocument, HtmlElement, HtmlTextAreaElement, Url as JsUrl,
+    Blob, CanvasRenderingContext2d, HtmlCanvasElement, HtmlDocument, HtmlElement,
+    HtmlTextAreaElement, ImageData, Url as JsUrl,
 };
 
 use chrono::{DateTime, Utc};
@@ -161,8 +168,102 @@ pub struct WebUiBackend {
     language: LanguageIdentifier,
     clipboard_content: String,
 
+    /// Non-text clipboard payloads, which can't leave the page.
+    clipboard_data: Vec<ClipboardData>,
+
     /// Is a dialog currently open
     dialog_open: bool,
+
+    /// The CSS cursor made from the custom cursor currently in use
+    custom_cursor: Option<CustomCursorStyle>,
+}
+
+/// A custom cursor shown through CSS `url()` images, one for each of its frames.
+struct CustomCursorStyle {
+    cursor: Arc<CustomMouseCursor>,
+
+    /// The interval switching between the frames, if the cursor is animated.
+    animation: Option<(i32, Closure<dyn FnMut()>)>,
+}
+
+impl CustomCursorStyle {
+    fn new(canvas: &HtmlCanvasElement, cursor: Arc<CustomMouseCursor>) -> Self {
+        let (hotspot_x, hotspot_y) = cursor.hotspot;
+        let frames: Vec<String> = cursor
+            .frames
+            .iter()
+            .map(|frame| match image_data_url(frame) {
+                Ok(url) => format!("url({url}) {hotspot_x} {hotspot_y}, auto"),
+                Err(e) => {
+                    tracing::warn!("Couldn't create custom cursor image: {e:?}");
+                    "auto".to_string()
+                }
+            })
+            .collect();
+
+        let first_frame = frames.first().map_or("auto", String::as_str);
+        canvas
+            .style()
+            .set_property("cursor", first_frame)
+            .warn_on_error();
+
+        // CSS cursors can't be animated, so the frames are switched by hand.
+        let animation = cursor.frame_duration().and_then(|frame_duration| {
+            let canvas = canvas.clone();
+            let current_frame = Rc::new(Cell::new(0));
+            let closure = Closure::<dyn FnMut()>::new(move || {
+                let frame = (current_frame.get() + 1) % frames.len();
+                current_frame.set(frame);
+                canvas
+                    .style()
+                    .set_property("cursor", &frames[frame])
+                    .warn_on_error();
+            });
+            let interval = web_sys::window()
+                .expect("window()")
+                .set_interval_with_callback_and_timeout_and_arguments_0(
+                    closure.as_ref().unchecked_ref(),
+                    frame_duration.as_millis() as i32,
+                )
+                .ok()?;
+            Some((interval, closure))
+        });
+
+        Self { cursor, animation }
+    }
+}
+
+impl Drop for CustomCursorStyle {
+    fn drop(&mut self) {
+        if let Some((interval, _)) = &self.animation {
+            web_sys::window()
+                .expect("window()")
+                .clear_interval_with_handle(*interval);
+        }
+    }
+}
+
+/// Encodes a cursor image as a PNG `data:` URL, by drawing it onto a canvas.
+fn image_data_url(image: &CursorImage) -> Result<String, JsValue> {
+    let canvas: HtmlCanvasElement = web_sys::window()
+        .expect("window()")
+        .document()
+        .expect("document()")
+        .create_element("canvas")?
+        .dyn_into()?;
+    canvas.set_width(image.width);
+    canvas.set_height(image.height);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or("Couldn't create a 2D canvas context")?
+        .dyn_into()?;
+    let image_data = ImageData::new_with_u8_clamped_array_and_sh(
+        Clamped(&image.rgba),
+        image.width,
+        image.height,
+    )?;
+    context.put_image_data(&image_data, 0.0, 0.0)?;
+    canvas.to_data_url()
 }
 
 impl WebUiBackend {
@@ -179,21 +280,32 @@ impl WebUiBackend {
             cursor: MouseCursor::Arrow,
             language,
             clipboard_content: "".into(),
+            clipboard_data: vec![],
             dialog_open: false,
+            custom_cursor: None,
         }
     }
 
-    fn update_mouse_cursor(&self) {
+    fn update_mouse_cursor(&mut self) {
         let cursor = if self.cursor_visible {
-            match self.cursor {
+            match &self.cursor {
                 MouseCursor::Arrow => "auto",
                 MouseCursor::Hand => "pointer",
                 MouseCursor::IBeam => "text",
                 MouseCursor::Grab => "grab",
+                MouseCursor::Custom(cursor) => {
+                    if !matches!(&self.custom_cursor, Some(style) if Arc::ptr_eq(&style.cursor, cursor))
+                    {
+                        self.custom_cursor =
+                            Some(CustomCursorStyle::new(&self.canvas, cursor.clone()));
+                    }
+                    return;
+                }
             }
         } else {
             "none"
         };
+        self.custom_cursor = None;
         self.canvas
             .style()
             .set_property("cursor", cursor)
@@ -201,6 +313,32 @@ impl WebUiBackend {
     }
 }
 
+/// Creates a dialog for opening files of the types matching the given filters.
+fn open_file_dialog(filters: Vec<FileFilter>) -> AsyncFileDialog {
+    let mut dialog = AsyncFileDialog::new();
+
+    for filter in filters {
+        let window = web_sys::window().expect("window()");
+        let navigator = window.navigator();
+        let platform = navigator.platform().expect("navigator.platform");
+
+        if platform.contains("Mac") && filter.mac_type.is_some() {
+            let mac_type = filter.mac_type.expect("Cant fail");
+            let extensions: Vec<&str> = mac_type.split(';').collect();
+            dialog = dialog.add_filter(&filter.description, &extensions);
+        } else {
+            let extensions: Vec<&str> = filter
+                .extensions
+                .split(';')
+                .map(|x| x.trim_start_matches("*."))
+                .collect();
+            dialog = dialog.add_filter(&filter.description, &extensions);
+        }
+    }
+
+    dialog
+}
+
 impl UiBackend for WebUiBackend {
     fn mouse_visible(&self) -> bool {
         self.cursor_visible
@@ -230,6 +368,7 @@ impl UiBackend for WebUiBackend {
 
     fn set_clipboard_content(&mut self, content: String) {
         self.clipboard_content = content.to_owned();
+        self.clipboard_data.clear();
         // We use `document.execCommand("copy")` as `navigator.clipboard.writeText("string")`
         // is available only in secure contexts (HTTPS).
         if let Some(element) = self.canvas.parent_element() {
@@ -274,6 +413,38 @@ impl UiBackend for WebUiBackend {
         }
     }
 
+    fn clipboard_formats(&mut self) -> Vec<ClipboardFormat> {
+        let mut formats = Vec::with_capacity(self.clipboard_data.len() + 1);
+        if !self.clipboard_content.is_empty() {
+            formats.push(ClipboardFormat::Text);
+        }
+        formats.extend(self.clipboard_data.iter().map(ClipboardData::format));
+        formats
+    }
+
+    fn clipboard_data(&mut self, format: ClipboardFormat) -> Option<ClipboardData> {
+        if format == ClipboardFormat::Text {
+            return Some(ClipboardData::Text(self.clipboard_content.to_owned()));
+        }
+        self.clipboard_data
+            .iter()
+            .find(|data| data.format() == format)
+            .cloned()
+    }
+
+    fn set_clipboard_data(&mut self, data: Vec<ClipboardData>) {
+        let (text, other): (Vec<_>, Vec<_>) = data
+            .into_iter()
+            .partition(|data| data.format() == ClipboardFormat::Text);
+        let text = match text.into_iter().next() {
+            Some(ClipboardData::Text(text)) => text,
+            _ => "".to_string(),
+        };
+        // Only text can be copied to the system clipboard.
+        self.set_clipboard_content(text);
+        self.clipboard_data = other;
+    }
+
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
         match self.js_player.set_fullscreen(is_full) {
             Ok(_) => Ok(()),
@@ -301,6 +472,12 @@ impl UiBackend for WebUiBackend {
         self.js_player.close_virtual_keyboard()
     }
 
+    fn set_ime_enabled(&mut self, _enabled: bool) {
+        // Composition is handled by the browser.
+    }
+
+    fn set_ime_cursor_area(&mut self, _area: Rectangle<Twips>) {}
+
     fn language(&self) -> LanguageIdentifier {
         self.language.clone()
     }
@@ -329,26 +506,7 @@ impl UiBackend for WebUiBackend {
 
         // Create the dialog future
         Some(Box::pin(async move {
-            let mut dialog = AsyncFileDialog::new();
-
-            for filter in filters {
-                let window = web_sys::window().expect("window()");
-                let navigator = window.navigator();
-                let platform = navigator.platform().expect("navigator.platform");
-
-                if platform.contains("Mac") && filter.mac_type.is_some() {
-                    let mac_type = filter.mac_type.expect("Cant fail");
-                    let extensions: Vec<&str> = mac_type.split(';').collect();
-                    dialog = dialog.add_filter(&filter.description, &extensions);
-                } else {
-                    let extensions: Vec<&str> = filter
-                        .extensions
-                        .split(';')
-                        .map(|x| x.trim_start_matches("*."))
-                        .collect();
-                    dialog = dialog.add_filter(&filter.description, &extensions);
-                }
-            }
+            let dialog = open_file_dialog(filters);
 
             let result: Result<Box<dyn FileDialogResult>, DialogLoaderError> = Ok(Box::new(
                 WebFileDialogResult::new_pick(dialog.pick_file().await).await,
@@ -357,6 +515,28 @@ impl UiBackend for WebUiBackend {
         }))
     }
 
+    fn display_file_open_dialog_multiple(
+        &mut self,
+        filters: Vec<FileFilter>,
+    ) -> Option<MultipleDialogResultFuture> {
+        // Prevent opening multiple dialogs at the same time
+        if self.dialog_open {
+            return None;
+        }
+        self.dialog_open = true;
+
+        // Create the dialog future
+        Some(Box::pin(async move {
+            let dialog = open_file_dialog(filters);
+
+            let mut results: Vec<Box<dyn FileDialogResult>> = vec![];
+            for handle in dialog.pick_files().await.unwrap_or_default() {
+                results.push(Box::new(WebFileDialogResult::new_pick(Some(handle)).await));
+            }
+            Ok(results)
+        }))
+    }
+
     fn close_file_dialog(&mut self) {
         self.dialog_open = false;
     }